@@ -6,16 +6,34 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use openclaw_ai::{AIProvider, ChatRequest};
+use openclaw_core::telemetry::{AGENT_TASK_DURATION_SECONDS, AGENT_TASKS_IN_FLIGHT, AGENT_TASKS_TOTAL};
 use openclaw_core::{Content, Message, Result, session::SessionScope};
-use openclaw_memory::MemoryManager;
+use openclaw_memory::{MemoryManager, MemoryNamespace};
 use openclaw_security::{PipelineResult, SecurityPipeline};
 use openclaw_tools::ToolResult as OpenClawToolResult;
 
 use crate::device_tool_registry::DeviceToolRegistry;
+use crate::memory::ConversationMemory;
 use crate::ports::{AIPort, DevicePort, MemoryPort, SecurityPort, ToolPort};
 use crate::task::{TaskInput, TaskOutput, TaskRequest, TaskResult, TaskStatus};
+use crate::tool_loop::{
+    AgentStepSender, AgentToolSet, PortToolSource, ToolLoop, ToolLoopConfig, ToolLoopStop,
+};
 use crate::types::{AgentConfig, AgentInfo, AgentStatus, AgentType, Capability};
 
+/// 提取任务输入的文本，用于安全检查和记忆召回
+fn task_input_text(input: &TaskInput) -> String {
+    match input {
+        TaskInput::Message { message } => extract_text_from_content(&message.content),
+        TaskInput::Text { content } => content.clone(),
+        TaskInput::Code { code, .. } => code.clone(),
+        TaskInput::Data { data } => serde_json::to_string(data).unwrap_or_default(),
+        TaskInput::File { content, .. } => content.clone(),
+        TaskInput::SearchQuery { query } => query.clone(),
+        TaskInput::ToolCall { name, arguments } => format!("{}: {}", name, arguments),
+    }
+}
+
 fn extract_text_from_content(content: &[Content]) -> String {
     content
         .iter()
@@ -63,6 +81,16 @@ pub trait Agent: Send + Sync {
     /// 处理任务
     async fn process(&self, task: TaskRequest) -> Result<TaskResult>;
 
+    /// 处理任务，并推送工具调用等中间步骤
+    async fn process_with_steps(
+        &self,
+        task: TaskRequest,
+        steps: AgentStepSender,
+    ) -> Result<TaskResult> {
+        let _ = steps;
+        self.process(task).await
+    }
+
     /// 是否可用
     fn is_available(&self) -> bool;
 
@@ -79,6 +107,9 @@ pub trait Agent: Send + Sync {
         device_port: Option<Arc<dyn DevicePort>>,
     );
 
+    /// 设置对话记忆，召回和写入按 `AgentConfig::memory` 执行
+    async fn set_conversation_memory(&self, memory: Arc<ConversationMemory>);
+
     /// 获取 Port（异步）
     async fn get_ai_port(&self) -> Option<Arc<dyn AIPort>>;
     async fn get_memory_port(&self) -> Option<Arc<dyn MemoryPort>>;
@@ -134,11 +165,15 @@ pub struct BaseAgent {
     status: AgentStatus,
     current_tasks: usize,
     ai_provider: Arc<tokio::sync::RwLock<Option<Arc<dyn AIProvider>>>>,
-    memory: Arc<tokio::sync::Mutex<Option<Arc<MemoryManager>>>>,
+    memory: Arc<tokio::sync::RwLock<Option<Arc<tokio::sync::Mutex<MemoryManager>>>>>,
+    conversation_memory: Arc<tokio::sync::RwLock<Option<Arc<ConversationMemory>>>>,
     security_pipeline: Arc<tokio::sync::RwLock<Option<Arc<SecurityPipeline>>>>,
     tool_executor: Arc<tokio::sync::RwLock<Option<Arc<openclaw_tools::SkillRegistry>>>>,
     tool_registry: Arc<tokio::sync::RwLock<Option<Arc<openclaw_tools::ToolRegistry>>>>,
     device_tool_registry: Arc<tokio::sync::RwLock<Option<Arc<DeviceToolRegistry>>>>,
+    skill_tools: Arc<tokio::sync::RwLock<Option<Arc<openclaw_tools::SkillToolBridge>>>>,
+    mcp_client: Arc<tokio::sync::RwLock<Option<Arc<openclaw_tools::McpClient>>>>,
+    tool_loop: ToolLoopConfig,
     ai_port: Arc<tokio::sync::RwLock<Option<Arc<dyn AIPort>>>>,
     memory_port: Arc<tokio::sync::RwLock<Option<Arc<dyn MemoryPort>>>>,
     security_port: Arc<tokio::sync::RwLock<Option<Arc<dyn SecurityPort>>>>,
@@ -157,11 +192,15 @@ impl BaseAgent {
             current_tasks: 0,
             config,
             ai_provider: Arc::new(RwLock::new(None)),
-            memory: Arc::new(tokio::sync::Mutex::new(None)),
+            memory: Arc::new(RwLock::new(None)),
+            conversation_memory: Arc::new(RwLock::new(None)),
             security_pipeline: Arc::new(RwLock::new(None)),
             tool_executor: Arc::new(RwLock::new(None)),
             tool_registry: Arc::new(RwLock::new(None)),
             device_tool_registry: Arc::new(RwLock::new(None)),
+            skill_tools: Arc::new(RwLock::new(None)),
+            mcp_client: Arc::new(RwLock::new(None)),
+            tool_loop: ToolLoopConfig::default(),
             ai_port: Arc::new(RwLock::new(None)),
             memory_port: Arc::new(RwLock::new(None)),
             security_port: Arc::new(RwLock::new(None)),
@@ -230,6 +269,70 @@ impl BaseAgent {
         self
     }
 
+    /// 设置工具循环的轮数、超时和并行度
+    pub fn with_tool_loop(mut self, config: ToolLoopConfig) -> Self {
+        self.tool_loop = config;
+        self
+    }
+
+    pub async fn set_ai_provider(&self, provider: Arc<dyn AIProvider>) {
+        *self.ai_provider.write().await = Some(provider);
+    }
+
+    /// 与调用方共享同一个 MemoryManager，写入对双方都可见
+    pub async fn set_memory(&self, memory: Arc<tokio::sync::Mutex<MemoryManager>>) {
+        *self.memory.write().await = Some(memory);
+    }
+
+    pub async fn set_security_pipeline(&self, pipeline: Arc<SecurityPipeline>) {
+        *self.security_pipeline.write().await = Some(pipeline);
+    }
+
+    /// 仅用于 `TaskInput::ToolCall`，不会公布给模型；模型可调用的技能工具见 [`Self::set_skill_tools`]
+    pub async fn set_tool_executor(&self, executor: Arc<openclaw_tools::SkillRegistry>) {
+        *self.tool_executor.write().await = Some(executor);
+    }
+
+    pub async fn set_tool_registry(&self, registry: Arc<openclaw_tools::ToolRegistry>) {
+        *self.tool_registry.write().await = Some(registry);
+    }
+
+    pub async fn set_device_tool_registry(&self, registry: Arc<DeviceToolRegistry>) {
+        *self.device_tool_registry.write().await = Some(registry);
+    }
+
+    /// 技能工具：只有已启用技能绑定的工具会交给模型
+    pub async fn set_skill_tools(&self, bridge: Arc<openclaw_tools::SkillToolBridge>) {
+        *self.skill_tools.write().await = Some(bridge);
+    }
+
+    pub async fn set_mcp_client(&self, client: Arc<openclaw_tools::McpClient>) {
+        *self.mcp_client.write().await = Some(client);
+    }
+
+    /// 汇总模型可调用的工具，同名时 ToolPort 优先
+    async fn tool_set(&self) -> AgentToolSet {
+        let mut tools = AgentToolSet::new();
+        if let Some(port) = self.tool_port.read().await.clone() {
+            tools
+                .add_source(Arc::new(PortToolSource::new(port, self.tool_loop.sandbox)))
+                .await;
+        }
+        if let Some(registry) = self.tool_registry.read().await.clone() {
+            tools.add_source(registry).await;
+        }
+        if let Some(bridge) = self.skill_tools.read().await.clone() {
+            tools.add_source(bridge).await;
+        }
+        if let Some(client) = self.mcp_client.read().await.clone() {
+            tools.add_source(client).await;
+        }
+        if let Some(registry) = self.device_tool_registry.read().await.clone() {
+            tools.add_source(registry).await;
+        }
+        tools
+    }
+
     pub fn with_session_storage(mut self, storage: Arc<dyn crate::sessions::SessionStorage>) -> Self {
         self.session_storage = Arc::new(RwLock::new(Some(storage)));
         self
@@ -429,8 +532,8 @@ impl BaseAgent {
         self
     }
 
-    pub fn get_memory_blocking(&self) -> Arc<tokio::sync::Mutex<Option<Arc<MemoryManager>>>> {
-        self.memory.clone()
+    pub async fn get_memory(&self) -> Option<Arc<tokio::sync::Mutex<MemoryManager>>> {
+        self.memory.read().await.clone()
     }
 
     async fn build_messages(&self, task: &TaskRequest) -> Vec<Message> {
@@ -445,11 +548,9 @@ impl BaseAgent {
         messages.extend(task.context.clone());
 
         // 从记忆获取上下文
-        let memory_lock = self.get_memory_blocking();
-        let ctx = if let Some(mem) = memory_lock.lock().await.as_ref() {
-            mem.get_context()
-        } else {
-            vec![]
+        let ctx = match self.get_memory().await {
+            Some(mem) => mem.lock().await.get_context(),
+            None => vec![],
         };
         if !ctx.is_empty() {
             messages.extend(ctx);
@@ -495,6 +596,39 @@ impl BaseAgent {
         messages
     }
 
+    /// 工作记忆同步写入，长期记忆和事实提取在后台进行，不阻塞回复
+    async fn remember_exchange(
+        &self,
+        user: Message,
+        reply: Message,
+        conversation_memory: Option<Arc<ConversationMemory>>,
+        namespace: Option<MemoryNamespace>,
+    ) {
+        if self.config.memory.store_exchanges
+            && let Some(memory) = self.get_memory().await
+        {
+            let mut memory = memory.lock().await;
+            for message in [user.clone(), reply.clone()] {
+                if let Err(e) = memory.add(message).await {
+                    tracing::warn!("Failed to add message to memory: {}", e);
+                }
+            }
+        }
+
+        if let Some(memory) = conversation_memory {
+            let config = self.config.memory.clone();
+            let agent_id = self.id().to_string();
+            tokio::spawn(async move {
+                let result = memory
+                    .remember(&user, &reply, namespace.as_ref(), &config)
+                    .await;
+                if let Err(e) = result {
+                    tracing::warn!("Failed to store memory for agent {}: {}", agent_id, e);
+                }
+            });
+        }
+    }
+
     /// 获取要使用的模型
     fn get_model(&self) -> String {
         self.config
@@ -502,31 +636,45 @@ impl BaseAgent {
             .clone()
             .unwrap_or_else(|| "gpt-4o".to_string())
     }
-}
 
-#[async_trait]
-impl Agent for BaseAgent {
-    fn id(&self) -> &str {
-        &self.config.id
-    }
-
-    fn name(&self) -> &str {
-        &self.config.name
-    }
-
-    fn agent_type(&self) -> AgentType {
-        self.config.agent_type.clone()
-    }
+    /// 处理任务并记录指标，任务被取消时在途计数也会回落
+    #[tracing::instrument(
+        name = "agent.process",
+        skip_all,
+        fields(agent_id = %self.id(), task_id = %task.id, session_id = task.session_id.as_deref())
+    )]
+    async fn process_observed(
+        &self,
+        task: TaskRequest,
+        steps: Option<AgentStepSender>,
+    ) -> Result<TaskResult> {
+        let agent_id = self.id().to_string();
+        let started = std::time::Instant::now();
+        let in_flight =
+            InFlight::enter(metrics::gauge!(AGENT_TASKS_IN_FLIGHT, "agent" => agent_id.clone()));
+
+        let result = self.run_task(task, steps).await;
+
+        drop(in_flight);
+        let status = match &result {
+            Ok(r) if r.status == TaskStatus::Completed => "completed",
+            Ok(_) => "failed",
+            Err(_) => "error",
+        };
+        metrics::counter!(AGENT_TASKS_TOTAL, "agent" => agent_id.clone(), "status" => status)
+            .increment(1);
+        metrics::histogram!(AGENT_TASK_DURATION_SECONDS, "agent" => agent_id)
+            .record(started.elapsed().as_secs_f64());
 
-    fn capabilities(&self) -> Vec<Capability> {
-        self.config.capabilities.clone()
+        result
     }
 
-    fn info(&self) -> AgentInfo {
-        AgentInfo::new(self.config.clone())
-    }
-
-    async fn process(&self, task: TaskRequest) -> Result<TaskResult> {
+    /// 执行单个任务：安全检查、多轮调用模型和工具、校验输出
+    async fn run_task(
+        &self,
+        task: TaskRequest,
+        steps: Option<AgentStepSender>,
+    ) -> Result<TaskResult> {
         let started_at = Utc::now();
         let session_id = format!("agent-{}", self.id());
         let task_id_str = task.id.to_string();
@@ -540,21 +688,16 @@ impl Agent for BaseAgent {
 
         let security_pipeline = self.security_pipeline.read().await.clone();
         let ai_provider = self.ai_provider.read().await.clone();
-        let _memory = self.memory.clone();
+        let conversation_memory = self.conversation_memory.read().await.clone();
+        let input_text = task_input_text(&task.input);
+        // 对话记忆按发起任务的用户隔离，persona 为当前 Agent
+        let namespace = task
+            .user_id
+            .as_ref()
+            .map(|user| MemoryNamespace::new(user, self.id()));
 
         // 安全检查：输入过滤和分类
         if let Some(pipeline) = &security_pipeline {
-            // 提取输入文本进行安全检查
-            let input_text = match &task.input {
-                TaskInput::Message { message } => extract_text_from_content(&message.content),
-                TaskInput::Text { content } => content.clone(),
-                TaskInput::Code { code, .. } => code.clone(),
-                TaskInput::Data { data } => serde_json::to_string(data).unwrap_or_default(),
-                TaskInput::File { content, .. } => content.clone(),
-                TaskInput::SearchQuery { query } => query.clone(),
-                TaskInput::ToolCall { name, arguments } => format!("{}: {}", name, arguments),
-            };
-
             // 输入安全检查
             let (security_result, _classification) =
                 pipeline.check_input(&session_id, &input_text).await;
@@ -609,11 +752,31 @@ impl Agent for BaseAgent {
         }
 
         // 构建消息
-        let messages = self.build_messages(&task).await;
+        let mut messages = self.build_messages(&task).await;
+
+        // 召回相关记忆，放在系统提示词之后
+        if let Some(memory) = &conversation_memory
+            && self.config.memory.recall
+        {
+            let recalled = memory
+                .recall(&input_text, namespace.as_ref(), &self.config.memory)
+                .await;
+            match recalled {
+                Ok(recalled) => {
+                    let budget = self.config.memory.recall_token_budget;
+                    if let Some(context) = ConversationMemory::context_message(&recalled, budget) {
+                        let at = usize::from(self.config.system_prompt.is_some());
+                        messages.insert(at, context);
+                    }
+                }
+                Err(e) => tracing::warn!("Memory recall failed for task {}: {}", task.id, e),
+            }
+        }
 
         // 创建 ChatRequest
         let model = self.get_model();
-        let chat_request = ChatRequest::new(&model, messages);
+        let mut chat_request = ChatRequest::new(&model, messages);
+        chat_request.tools = task.tools.clone();
 
         // 记录操作开始（用于自我修复）
         let operation_id = if let Some(pipeline) = &security_pipeline {
@@ -637,11 +800,39 @@ impl Agent for BaseAgent {
             }
         }
 
-        // 调用 AI
-        let ai_result = ai_provider.chat(chat_request).await;
+        // 调用 AI，模型请求的工具在循环内执行后继续对话
+        let tools = self.tool_set().await;
+        let mut tool_loop = ToolLoop::new(ai_provider, &tools, self.tool_loop.clone());
+        if let Some(pipeline) = &security_pipeline {
+            tool_loop = tool_loop.with_security(pipeline.clone(), session_id.clone());
+        }
+        if let Some(steps) = steps {
+            tool_loop = tool_loop.with_steps(steps);
+        }
+        let ai_result = tool_loop.run(chat_request).await;
 
         // 处理 AI 响应
         match ai_result {
+            Ok(outcome)
+                if matches!(outcome.stop, ToolLoopStop::Limit(_) | ToolLoopStop::Timeout) =>
+            {
+                let reason = match outcome.stop {
+                    ToolLoopStop::Limit(reason) => reason.to_string(),
+                    _ => "timeout".to_string(),
+                };
+                if let (Some(pipeline), Some(op_id)) = (&security_pipeline, &operation_id) {
+                    let status = format!("stopped: {}", reason);
+                    pipeline
+                        .complete_operation(&session_id, op_id, &status, 0)
+                        .await;
+                }
+
+                Ok(TaskResult::failure(
+                    task.id,
+                    self.id().to_string(),
+                    format!("Tool loop stopped after {} steps: {}", outcome.steps, reason),
+                ))
+            }
             Ok(response) => {
                 // 记录进度
                 if let (Some(pipeline), Some(op_id)) = (&security_pipeline, &operation_id) {
@@ -676,6 +867,17 @@ impl Agent for BaseAgent {
 
                 let tokens_used = response.usage.total_tokens;
 
+                // 模型请求的工具调用原样返回给调用方
+                let mut output_message = openclaw_core::Message::assistant(final_output.clone());
+                output_message.content.extend(
+                    response
+                        .message
+                        .content
+                        .iter()
+                        .filter(|c| matches!(c, Content::ToolCall { .. }))
+                        .cloned(),
+                );
+
                 // 完成任务
                 if let (Some(pipeline), Some(op_id)) = (&security_pipeline, &operation_id) {
                     let duration = Utc::now().signed_duration_since(started_at);
@@ -689,8 +891,18 @@ impl Agent for BaseAgent {
                         .await;
                 }
 
-                // 写入对话到记忆 - 记忆服务独立处理，Agent 不直接写入
-                // MemoryService 会自动处理记忆的持久化
+                // 写入本轮对话到记忆
+                let user_message = match &task.input {
+                    TaskInput::Message { message } => message.clone(),
+                    _ => Message::user(input_text),
+                };
+                self.remember_exchange(
+                    user_message,
+                    Message::assistant(final_output),
+                    conversation_memory,
+                    namespace,
+                )
+                .await;
 
                 // 构建任务结果
                 Ok(TaskResult {
@@ -698,7 +910,7 @@ impl Agent for BaseAgent {
                     agent_id: self.id().to_string(),
                     status: TaskStatus::Completed,
                     output: Some(TaskOutput::Message {
-                        message: openclaw_core::Message::assistant(final_output.clone()),
+                        message: output_message,
                     }),
                     error: None,
                     started_at,
@@ -727,6 +939,41 @@ impl Agent for BaseAgent {
             }
         }
     }
+}
+
+#[async_trait]
+impl Agent for BaseAgent {
+    fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn agent_type(&self) -> AgentType {
+        self.config.agent_type.clone()
+    }
+
+    fn capabilities(&self) -> Vec<Capability> {
+        self.config.capabilities.clone()
+    }
+
+    fn info(&self) -> AgentInfo {
+        AgentInfo::new(self.config.clone())
+    }
+
+    async fn process(&self, task: TaskRequest) -> Result<TaskResult> {
+        self.process_observed(task, None).await
+    }
+
+    async fn process_with_steps(
+        &self,
+        task: TaskRequest,
+        steps: AgentStepSender,
+    ) -> Result<TaskResult> {
+        self.process_observed(task, Some(steps)).await
+    }
 
     fn is_available(&self) -> bool {
         self.config.enabled
@@ -782,6 +1029,10 @@ impl Agent for BaseAgent {
         *self.device_port.write().await = device_port;
     }
 
+    async fn set_conversation_memory(&self, memory: Arc<ConversationMemory>) {
+        *self.conversation_memory.write().await = Some(memory);
+    }
+
     async fn init_safety(&self, config: crate::safety::AgentSafetyConfig) {
         let wrapper = Arc::new(crate::safety::AgentSafetyWrapper::new(config));
         *self.safety_wrapper.write().await = Some(wrapper);
@@ -1102,6 +1353,22 @@ const CONVERSATIONALIST_PROMPT: &str = r#"You are a **Conversational Agent** - s
 - Provide complete but concise responses
 - End with inviting follow-up"#;
 
+/// 在途计数，drop 时减一
+struct InFlight(metrics::Gauge);
+
+impl InFlight {
+    fn enter(gauge: metrics::Gauge) -> Self {
+        gauge.increment(1.0);
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.decrement(1.0);
+    }
+}
+
 #[cfg(feature = "testing")]
 #[cfg(test)]
 mod tests {
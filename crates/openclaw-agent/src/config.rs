@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::memory::ConversationMemoryConfig;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AgentsConfig {
     pub list: Vec<AgentInstanceConfig>,
@@ -17,6 +19,12 @@ pub struct AgentInstanceConfig {
     pub aieos_path: Option<PathBuf>,
     #[serde(default)]
     pub persona_id: Option<String>,
+    /// 系统提示词，未配置 AIEOS 时使用，修改后网关会热加载
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// 对话记忆的召回与写入配置
+    #[serde(default)]
+    pub memory: ConversationMemoryConfig,
 }
 
 impl AgentInstanceConfig {
@@ -27,6 +35,8 @@ impl AgentInstanceConfig {
             default: false,
             aieos_path: None,
             persona_id: None,
+            system_prompt: None,
+            memory: ConversationMemoryConfig::default(),
         }
     }
 }
@@ -66,6 +76,8 @@ mod tests {
             default: true,
             aieos_path: Some(PathBuf::from("/path/to/aieos")),
             persona_id: None,
+            system_prompt: None,
+            memory: ConversationMemoryConfig::default(),
         };
         assert_eq!(config.id, "test_agent");
         assert!(config.default);
@@ -88,6 +100,8 @@ mod tests {
                 default: true,
                 aieos_path: None,
                 persona_id: None,
+                system_prompt: None,
+                memory: ConversationMemoryConfig::default(),
             }],
             defaults: AgentDefaults {
                 model: "gpt-4o".to_string(),
@@ -127,6 +141,8 @@ mod tests {
                     default: true,
                     aieos_path: None,
                     persona_id: None,
+                    system_prompt: None,
+                    memory: ConversationMemoryConfig::default(),
                 },
                 AgentInstanceConfig {
                     id: "agent2".to_string(),
@@ -134,6 +150,8 @@ mod tests {
                     default: false,
                     aieos_path: Some(PathBuf::from("/aieos")),
                     persona_id: None,
+                    system_prompt: None,
+                    memory: ConversationMemoryConfig::default(),
                 },
             ],
             defaults: AgentDefaults::default(),
@@ -74,6 +74,8 @@ impl HandOutputManager {
                     md5: None,
                     articles: None,
                     media_id: None,
+                    thread_id: None,
+                    reply_to: None,
                 };
                 
                 if let Err(e) = manager.send_to_channel(&channel_type, send_msg).await {
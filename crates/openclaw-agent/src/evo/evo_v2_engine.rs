@@ -7,6 +7,7 @@ use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
 use openclaw_ai::AIProvider;
+use openclaw_memory::graph_store::SqliteGraphStore;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
@@ -524,10 +525,11 @@ pub async fn skill_{}(context: &super::EvoContext) -> Result<String, String> {{
     }
 
     pub async fn save_all(&self, data_dir: &str) -> std::io::Result<()> {
-        let kg_path = format!("{}/knowledge_graph.json", data_dir);
+        let kg_path = format!("{}/knowledge_graph.db", data_dir);
         {
+            let store = SqliteGraphStore::open(&kg_path).map_err(std::io::Error::other)?;
             let graph = self.knowledge_graph.read().await;
-            graph.save_to_file(&kg_path)?;
+            graph.save_to_store(&store).await.map_err(std::io::Error::other)?;
         }
 
         let vm_path = format!("{}/skill_versions.json", data_dir);
@@ -540,8 +542,17 @@ pub async fn skill_{}(context: &super::EvoContext) -> Result<String, String> {{
     }
 
     pub async fn load_all(&self, data_dir: &str) -> std::io::Result<()> {
-        let kg_path = format!("{}/knowledge_graph.json", data_dir);
-        if let Ok(graph) = KnowledgeGraph::load_from_file(&kg_path) {
+        // 优先读取 SQLite 图谱，旧版本保存的 JSON 文件作为后备
+        let kg_path = format!("{}/knowledge_graph.db", data_dir);
+        let graph = if std::path::Path::new(&kg_path).exists() {
+            match SqliteGraphStore::open(&kg_path) {
+                Ok(store) => KnowledgeGraph::load_from_store(&store).await.ok(),
+                Err(_) => None,
+            }
+        } else {
+            KnowledgeGraph::load_from_file(&format!("{}/knowledge_graph.json", data_dir)).ok()
+        };
+        if let Some(graph) = graph {
             let mut g = self.knowledge_graph.write().await;
             *g = graph;
         }
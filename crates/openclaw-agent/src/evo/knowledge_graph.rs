@@ -3,6 +3,8 @@
 //! 存储和管理学习的知识，建立技能之间的关联
 
 use chrono::{DateTime, Utc};
+use openclaw_memory::graph_store::GraphStore;
+use openclaw_memory::knowledge_graph::{Entity, EntityType, Relation, RelationType};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
@@ -39,6 +41,9 @@ pub enum EdgeType {
     VariantOf,
 }
 
+const SKILL_PROPERTY: &str = "evo_skill";
+const EDGE_TYPE_PROPERTY: &str = "edge_type";
+
 #[derive(Serialize, Deserialize)]
 pub struct KnowledgeGraph {
     nodes: HashMap<String, SkillNode>,
@@ -318,6 +323,74 @@ impl KnowledgeGraph {
         let graph: KnowledgeGraph = serde_json::from_str(&json).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
         Ok(graph)
     }
+
+    /// 写入图谱存储：技能存为 Skill 实体，边存为 RelatedTo 关系，存储中原有数据会被清空
+    pub async fn save_to_store(&self, store: &dyn GraphStore) -> openclaw_core::Result<()> {
+        store.clear().await?;
+        for node in self.nodes.values() {
+            let entity = Entity {
+                id: node.skill_id.clone(),
+                name: node.name.clone(),
+                entity_type: EntityType::Skill,
+                properties: HashMap::from([
+                    ("category".to_string(), node.category.clone()),
+                    (SKILL_PROPERTY.to_string(), serde_json::to_string(node)?),
+                ]),
+                created_at: node.created_at,
+                updated_at: node.last_used.unwrap_or(node.created_at),
+                confidence: node.success_rate as f32,
+            };
+            store.upsert_entity(&entity).await?;
+        }
+        for edge in self.edges.values().flatten() {
+            let edge_type = serde_json::to_string(&edge.edge_type)?;
+            let relation = Relation {
+                id: format!(
+                    "{}->{}:{}",
+                    edge.from_skill_id,
+                    edge.to_skill_id,
+                    edge_type.trim_matches('"')
+                ),
+                source_id: edge.from_skill_id.clone(),
+                target_id: edge.to_skill_id.clone(),
+                relation_type: RelationType::RelatedTo,
+                properties: HashMap::from([(EDGE_TYPE_PROPERTY.to_string(), edge_type)]),
+                weight: edge.weight as f32,
+                created_at: Utc::now(),
+            };
+            store.upsert_relation(&relation).await?;
+        }
+        Ok(())
+    }
+
+    /// 从图谱存储读取，忽略不是由 `save_to_store` 写入的实体和关系
+    pub async fn load_from_store(store: &dyn GraphStore) -> openclaw_core::Result<Self> {
+        let mut graph = Self::new();
+        for entity in store.find_by_type(&EntityType::Skill).await? {
+            let node = entity
+                .properties
+                .get(SKILL_PROPERTY)
+                .and_then(|json| serde_json::from_str::<SkillNode>(json).ok());
+            if let Some(node) = node {
+                graph.add_skill(node);
+            }
+        }
+        for relation in store.all_relations().await? {
+            let edge_type = relation
+                .properties
+                .get(EDGE_TYPE_PROPERTY)
+                .and_then(|json| serde_json::from_str::<EdgeType>(json).ok());
+            if let Some(edge_type) = edge_type {
+                graph.add_edge(SkillEdge {
+                    from_skill_id: relation.source_id,
+                    to_skill_id: relation.target_id,
+                    edge_type,
+                    weight: relation.weight as f64,
+                });
+            }
+        }
+        Ok(graph)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -618,4 +691,29 @@ mod tests {
 
         std::fs::remove_file(path).ok();
     }
+
+    #[tokio::test]
+    async fn test_save_load_store() {
+        use openclaw_memory::graph_store::SqliteGraphStore;
+
+        let mut graph = KnowledgeGraph::new();
+        graph.add_skill(create_test_node("skill-1", "search"));
+        graph.add_skill(create_test_node("skill-2", "search"));
+        graph.add_edge(SkillEdge {
+            from_skill_id: "skill-1".to_string(),
+            to_skill_id: "skill-2".to_string(),
+            edge_type: EdgeType::DependsOn,
+            weight: 0.5,
+        });
+
+        let store = SqliteGraphStore::open_in_memory().unwrap();
+        graph.save_to_store(&store).await.unwrap();
+        graph.save_to_store(&store).await.unwrap();
+
+        let loaded = KnowledgeGraph::load_from_store(&store).await.unwrap();
+        assert_eq!(loaded.get_skills_count(), 2);
+        let stats = loaded.get_statistics();
+        assert_eq!(stats.total_edges, 1);
+        assert_eq!(loaded.edges["skill-1"][0].edge_type, EdgeType::DependsOn);
+    }
 }
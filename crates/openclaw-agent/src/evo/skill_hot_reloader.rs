@@ -1,11 +1,13 @@
 //! Skill 热加载器
 //!
 //! 监控 Skills 目录变化，支持动态添加、删除、修改 Skills
+//! 配置了签名校验器时，Skill 目录内任一文件变化都会重新校验，未通过的 Skill 会被移除
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use openclaw_memory::file_watcher::{FileChange, FileChangeType, FileWatcher, FileWatcherConfig};
+use openclaw_tools::bundle_signing::BundleVerifier;
 use tokio::sync::RwLock;
 
 use super::registry::DynamicSkill;
@@ -25,6 +27,7 @@ pub struct SkillHotReloader {
     watcher: Arc<RwLock<Option<FileWatcher>>>,
     skills: Arc<RwLock<Vec<DynamicSkill>>>,
     callback: Option<SkillChangeCallback>,
+    verifier: Option<Arc<BundleVerifier>>,
 }
 
 impl SkillHotReloader {
@@ -34,6 +37,7 @@ impl SkillHotReloader {
             watcher: Arc::new(RwLock::new(None)),
             skills: Arc::new(RwLock::new(Vec::new())),
             callback: None,
+            verifier: None,
         }
     }
 
@@ -42,6 +46,12 @@ impl SkillHotReloader {
         self
     }
 
+    /// 加载前校验 Skill 目录中 bundle.json 的摘要与签名
+    pub fn with_verifier(mut self, verifier: BundleVerifier) -> Self {
+        self.verifier = Some(Arc::new(verifier));
+        self
+    }
+
     pub async fn start(&self, skill_paths: Vec<PathBuf>) -> Result<(), String> {
         // 初始加载所有 Skills
         self.reload_skills(skill_paths.clone()).await?;
@@ -60,15 +70,17 @@ impl SkillHotReloader {
         let skill_loader = self.skill_loader.clone();
         let skills = self.skills.clone();
         let callback = self.callback.clone();
+        let verifier = self.verifier.clone();
 
         let watcher = FileWatcher::new(config)
             .with_callback(Arc::new(move |change| {
                 let skill_loader = skill_loader.clone();
                 let skills = skills.clone();
                 let callback = callback.clone();
+                let verifier = verifier.clone();
                 
                 tokio::spawn(async move {
-                    if let Err(e) = Self::handle_file_change(change, &skill_loader, &skills, &callback).await {
+                    if let Err(e) = Self::handle_file_change(change, &skill_loader, &skills, &callback, &verifier).await {
                         tracing::error!("Error handling file change: {}", e);
                     }
                 });
@@ -97,6 +109,20 @@ impl SkillHotReloader {
             }
         }
 
+        // 丢弃未通过签名校验的 Skill
+        all_skills.retain(|skill| {
+            let Some(dir) = skill.metadata.get("skill_dir") else {
+                return true;
+            };
+            match Self::verify_skill_dir(&self.verifier, Path::new(dir)) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("Skipping skill {}: {}", skill.id, e);
+                    false
+                }
+            }
+        });
+
         *self.skills.write().await = all_skills.clone();
         
         // 触发回调
@@ -114,19 +140,47 @@ impl SkillHotReloader {
         skill_loader: &Arc<SkillLoader>,
         skills: &Arc<RwLock<Vec<DynamicSkill>>>,
         callback: &Option<SkillChangeCallback>,
+        verifier: &Option<Arc<BundleVerifier>>,
     ) -> Result<(), String> {
-        let path = &change.path;
+        let is_skill_md = change.path.file_name().map(|n| n == "SKILL.md").unwrap_or(false);
 
-        // 检查是否是 SKILL.md 文件
-        if !path.file_name().map(|n| n == "SKILL.md").unwrap_or(false) {
+        // 检查是否是 SKILL.md 文件；启用签名校验时脚本等其他文件的变化也需要处理
+        let (skill_dir, path) = if is_skill_md {
+            let skill_dir = change.path.parent().ok_or("Invalid skill path")?;
+            (skill_dir, change.path.clone())
+        } else if verifier.is_some() {
+            match change.path.ancestors().skip(1).find(|dir| dir.join("SKILL.md").exists()) {
+                Some(skill_dir) => (skill_dir, skill_dir.join("SKILL.md")),
+                None => return Ok(()),
+            }
+        } else {
             return Ok(());
-        }
+        };
+        let path = &path;
 
-        // 获取技能目录
-        let skill_dir = path.parent().ok_or("Invalid skill path")?;
+        let change_type = match change.change_type {
+            FileChangeType::Removed if !is_skill_md => FileChangeType::Modified,
+            other => other,
+        };
 
-        match change.change_type {
+        match change_type {
             FileChangeType::Created | FileChangeType::Modified => {
+                // 校验未通过时移除已加载的版本，避免运行被篡改的脚本
+                if let Err(e) = Self::verify_skill_dir(verifier, skill_dir) {
+                    tracing::warn!("Rejected skill change in {}: {}", skill_dir.display(), e);
+                    let dir = skill_dir.to_string_lossy();
+                    let mut skills_guard = skills.write().await;
+                    if let Some(pos) = skills_guard.iter().position(|s| {
+                        s.metadata.get("skill_dir").map(|d| d.as_str() == dir).unwrap_or(false)
+                    }) {
+                        let removed = skills_guard.remove(pos);
+                        if let Some(cb) = callback {
+                            cb(SkillChangeEvent::Removed(removed.id));
+                        }
+                    }
+                    return Ok(());
+                }
+
                 // 重新加载该 Skill
                 match skill_loader.load_from_file(path).await {
                     Ok(mut skill) => {
@@ -195,6 +249,13 @@ impl SkillHotReloader {
         Ok(())
     }
 
+    fn verify_skill_dir(verifier: &Option<Arc<BundleVerifier>>, skill_dir: &Path) -> Result<(), String> {
+        match verifier {
+            Some(verifier) => verifier.verify_dir(skill_dir).map(|_| ()).map_err(|e| e.to_string()),
+            None => Ok(()),
+        }
+    }
+
     pub async fn get_skills(&self) -> Vec<DynamicSkill> {
         self.skills.read().await.clone()
     }
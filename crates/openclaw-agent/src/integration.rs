@@ -276,6 +276,8 @@ mod tests {
             default: true,
             aieos_path: None,
             persona_id: Some("doctor_zhangsan".to_string()),
+            system_prompt: None,
+            memory: Default::default(),
         };
 
         assert_eq!(agent_config.persona_id, Some("doctor_zhangsan".to_string()));
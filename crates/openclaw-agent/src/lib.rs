@@ -26,6 +26,7 @@ pub mod evo;
 pub mod sub_agent;
 pub mod task;
 pub mod team;
+pub mod tool_loop;
 pub mod types;
 
 pub use agent::*;
@@ -48,7 +49,7 @@ pub use safety::{TurnLimiter, TurnLimitConfig, LimitReason, TimeoutController, T
 pub use session_tree::*;
 pub use sessions::*;
 pub use squad::{SquadRegistry, Squad, SquadType, SquadMember, SquadRole, SquadCollaboration, AgentMemoryService, SquadMemoryService};
-pub use memory::{CompressionConfig, ContextCompactor, ContextMessage, CleanupPolicy, MemoryCleanupPolicy};
+pub use memory::{CompressionConfig, ContextCompactor, ContextMessage, CleanupPolicy, MemoryCleanupPolicy, ConversationMemory, ConversationMemoryConfig, RecalledMemory, RememberOutcome};
 pub use extension::{Extension, ExtensionError, ExtensionMeta, ExtensionRegistry, ExtensionResult, ExtensionSource, Priority};
 pub use evo::{CompiledSkill, DynamicCompiler, EvolutionEngine, EvolutionResult, EvolutionStatus, ProgrammingLanguage, SkillGenerator, SkillSandbox, ToolNeed, Sandbox};
 pub use evo::registry::{SharedSkillRegistry, DynamicSkill, SkillSource, SkillType, SkillGating, SkillFormat};
@@ -75,6 +76,10 @@ pub use evo::autonomous::{
 pub use sub_agent::*;
 pub use task::*;
 pub use team::*;
+pub use tool_loop::{
+    AgentStep, AgentStepSender, AgentToolSet, PortToolSource, ToolLoop, ToolLoopConfig,
+    ToolLoopOutcome, ToolLoopStop, ToolSource,
+};
 pub use types::*;
 
 pub use openclaw_core::{OpenClawError, Result};
@@ -0,0 +1,797 @@
+//! 对话记忆 - 每轮对话的自动召回与写入
+//!
+//! - 调用模型前：通过 `HybridSearchManager` 召回相关记忆，按 token 预算注入提示词
+//! - 调用模型后：用 `ImportanceScorer` 为本轮对话打分，达到阈值的写入长期记忆
+//! - 用 `FactExtractor` 提取原子事实，经 `ConflictResolver` 与已有事实合并，
+//!   `ResolutionMethod::LLMDecision` 时由模型裁决，裁决理由随事实写入 `resolution` 字段
+//! - 用 `GraphExtractor` 提取实体和关系写入知识图谱，来源为本轮对话的记忆 ID
+//!
+//! 对话和事实都写入向量存储并带上命名空间，召回和事实合并只看当前用户的记忆。
+//! 写入前经 `PiiRedactor` 脱敏，事实提取、实体提取和裁决记录都只看到脱敏后的文本。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::Utc;
+use openclaw_core::{Message, Result};
+use openclaw_memory::ImportanceScorer;
+use openclaw_memory::conflict_resolver::{
+    Conflict, ConflictAction, ConflictResolver, ResolutionMethod,
+};
+use openclaw_memory::embedding::EmbeddingProvider;
+use openclaw_memory::entity_extractor::GraphExtractor;
+use openclaw_memory::fact_extractor::{AtomicFact, FactExtractor};
+use openclaw_memory::hybrid_search::{HybridSearchConfig, HybridSearchManager};
+use openclaw_memory::privacy::PiiRedactor;
+use openclaw_memory::types::MemoryNamespace;
+use openclaw_vector::{Filter, VectorItem};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+/// 记忆 payload 中区分对话和事实的字段
+const KIND_FIELD: &str = "kind";
+const KIND_EXCHANGE: &str = "exchange";
+const KIND_FACT: &str = "fact";
+
+/// 对话记忆配置，每个 Agent 可单独设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConversationMemoryConfig {
+    /// 调用模型前召回相关记忆
+    pub recall: bool,
+    /// 最多召回条数
+    pub recall_limit: usize,
+    /// 注入提示词的记忆 token 预算
+    pub recall_token_budget: usize,
+    /// 召回结果的最低分数
+    pub min_recall_score: f32,
+    /// 调用模型后写入本轮对话
+    pub store_exchanges: bool,
+    /// 重要性低于该值的对话不写入长期记忆
+    pub min_importance: f32,
+    /// 提取原子事实并合并到长期记忆
+    pub extract_facts: bool,
+    /// 事实冲突的处理方式，未设置时按类别权重和置信度决定
+    pub resolution: Option<ResolutionMethod>,
+    /// 提取实体和关系写入知识图谱
+    pub extract_entities: bool,
+}
+
+impl Default for ConversationMemoryConfig {
+    fn default() -> Self {
+        Self {
+            recall: true,
+            recall_limit: 5,
+            recall_token_budget: 800,
+            min_recall_score: 0.0,
+            store_exchanges: true,
+            min_importance: 0.3,
+            extract_facts: true,
+            resolution: None,
+            extract_entities: true,
+        }
+    }
+}
+
+/// 召回的一条记忆
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecalledMemory {
+    pub id: String,
+    pub content: String,
+    pub score: f32,
+}
+
+/// 一轮对话写入记忆的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RememberOutcome {
+    /// 对话本身是否写入
+    pub stored: bool,
+    /// 新增的事实数
+    pub facts_added: usize,
+    /// 因冲突被替换的旧事实数
+    pub facts_replaced: usize,
+    /// 新增或合并到知识图谱的实体数
+    pub entities_linked: usize,
+}
+
+/// 对话记忆，多个 Agent 可共享同一实例
+pub struct ConversationMemory {
+    search: Arc<HybridSearchManager>,
+    embedder: Arc<dyn EmbeddingProvider>,
+    scorer: ImportanceScorer,
+    extractor: Option<Arc<dyn FactExtractor>>,
+    resolver: ConflictResolver,
+    graph_extractor: Option<Arc<GraphExtractor>>,
+    namespace: Option<MemoryNamespace>,
+    redactor: Option<Arc<PiiRedactor>>,
+    /// 串行化事实合并，避免并发的读取-裁决-写入互相覆盖
+    merge_lock: Mutex<()>,
+}
+
+impl ConversationMemory {
+    pub fn new(search: Arc<HybridSearchManager>, embedder: Arc<dyn EmbeddingProvider>) -> Self {
+        Self {
+            search,
+            embedder,
+            scorer: ImportanceScorer::new(),
+            extractor: None,
+            resolver: ConflictResolver::new(),
+            graph_extractor: None,
+            namespace: None,
+            redactor: Some(Arc::new(PiiRedactor::new())),
+            merge_lock: Mutex::new(()),
+        }
+    }
+
+    /// 调用方未指定用户时使用的命名空间
+    pub fn with_namespace(mut self, namespace: MemoryNamespace) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
+    /// 设置写入前使用的脱敏器，默认使用内置规则
+    pub fn with_redactor(mut self, redactor: Arc<PiiRedactor>) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// 关闭写入前脱敏，对应 `privacy.redact_pii = false`
+    pub fn without_redaction(mut self) -> Self {
+        self.redactor = None;
+        self
+    }
+
+    pub fn with_fact_extractor(mut self, extractor: Arc<dyn FactExtractor>) -> Self {
+        self.extractor = Some(extractor);
+        self
+    }
+
+    pub fn with_resolver(mut self, resolver: ConflictResolver) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    pub fn with_graph_extractor(mut self, extractor: Arc<GraphExtractor>) -> Self {
+        self.graph_extractor = Some(extractor);
+        self
+    }
+
+    fn redact(&self, text: &str) -> String {
+        match &self.redactor {
+            Some(redactor) => redactor.redact_text(text),
+            None => text.to_string(),
+        }
+    }
+
+    fn scope<'a>(&'a self, namespace: Option<&'a MemoryNamespace>) -> Option<&'a MemoryNamespace> {
+        namespace.or(self.namespace.as_ref())
+    }
+
+    /// 命名空间内保留的长期事实，按创建时间排序
+    pub async fn facts(&self, namespace: Option<&MemoryNamespace>) -> Result<Vec<AtomicFact>> {
+        let mut filter = Filter::eq(KIND_FIELD, serde_json::json!(KIND_FACT));
+        if let Some(namespace) = self.scope(namespace) {
+            filter = filter.and(namespace.filter());
+        }
+        let mut facts: Vec<AtomicFact> = self
+            .search
+            .list_memories(&filter)
+            .await?
+            .iter()
+            .filter_map(fact_from_item)
+            .collect();
+        facts.sort_by_key(|f| f.created_at);
+        Ok(facts)
+    }
+
+    /// 按查询召回命名空间内的相关记忆
+    pub async fn recall(
+        &self,
+        query: &str,
+        namespace: Option<&MemoryNamespace>,
+        config: &ConversationMemoryConfig,
+    ) -> Result<Vec<RecalledMemory>> {
+        if query.trim().is_empty() || config.recall_limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let vector = self.embedder.embed(query).await?;
+        let search_config = HybridSearchConfig {
+            limit: config.recall_limit,
+            min_score: Some(config.min_recall_score),
+            namespace: self.scope(namespace).cloned(),
+            ..Default::default()
+        };
+        let results = self
+            .search
+            .unified_search(query, Some(vector), &search_config)
+            .await?;
+
+        Ok(results
+            .into_iter()
+            .filter(|r| !r.content.trim().is_empty() && r.score >= config.min_recall_score)
+            .take(config.recall_limit)
+            .map(|r| RecalledMemory {
+                id: r.id,
+                content: r.content,
+                score: r.score,
+            })
+            .collect())
+    }
+
+    /// 将召回结果整理为一条系统消息，超出 token 预算的记忆被丢弃
+    pub fn context_message(memories: &[RecalledMemory], token_budget: usize) -> Option<Message> {
+        let mut text = String::from("Relevant memories from earlier conversations:");
+        let mut used = text.len() / 4;
+        let mut included = 0;
+
+        for memory in memories {
+            let line = format!("\n- {}", memory.content.trim());
+            let tokens = line.len() / 4;
+            if used + tokens > token_budget {
+                break;
+            }
+            used += tokens;
+            included += 1;
+            text.push_str(&line);
+        }
+
+        (included > 0).then(|| Message::system(text))
+    }
+
+    /// 写入一轮对话：按重要性保存对话，并提取事实合并到命名空间内的长期记忆
+    pub async fn remember(
+        &self,
+        user: &Message,
+        reply: &Message,
+        namespace: Option<&MemoryNamespace>,
+        config: &ConversationMemoryConfig,
+    ) -> Result<RememberOutcome> {
+        let namespace = self.scope(namespace);
+        let mut outcome = RememberOutcome::default();
+        let user_text = self.redact(user.text_content().unwrap_or_default().trim());
+        let reply_text = self.redact(reply.text_content().unwrap_or_default().trim());
+        if user_text.is_empty() && reply_text.is_empty() {
+            return Ok(outcome);
+        }
+        let exchange = format!("User: {}\nAssistant: {}", user_text, reply_text);
+
+        let importance = self.scorer.score(user).max(self.scorer.score(reply));
+        let memory_id = uuid::Uuid::new_v4().to_string();
+        if config.store_exchanges && importance >= config.min_importance {
+            let vector = self.embedder.embed(&exchange).await?;
+            let mut metadata = serde_json::json!({
+                KIND_FIELD: KIND_EXCHANGE,
+                "importance": importance,
+                "created_at": Utc::now().to_rfc3339(),
+            });
+            if let Some(namespace) = namespace {
+                namespace.stamp(&mut metadata);
+            }
+            self.search
+                .add_memory(memory_id.clone(), exchange.clone(), vector, metadata)
+                .await?;
+            outcome.stored = true;
+        }
+
+        if config.extract_facts
+            && let Some(extractor) = &self.extractor
+        {
+            let facts = extractor.extract_facts(&exchange).await?;
+            let (added, replaced) = self.merge_facts(facts, namespace, config).await?;
+            outcome.facts_added = added;
+            outcome.facts_replaced = replaced;
+        }
+
+        if config.extract_entities
+            && let Some(extractor) = &self.graph_extractor
+        {
+            // 对话未写入时没有可关联的记忆
+            let source_id = outcome.stored.then_some(memory_id.as_str());
+            let redacted = [Message::user(user_text), Message::assistant(reply_text)];
+            let report = extractor.extract_conversation(&redacted, source_id).await?;
+            outcome.entities_linked = report.entities_added + report.entities_merged;
+        }
+
+        Ok(outcome)
+    }
+
+    /// 合并新事实：重复内容跳过，冲突交给 `ConflictResolver` 处理
+    async fn merge_facts(
+        &self,
+        new_facts: Vec<AtomicFact>,
+        namespace: Option<&MemoryNamespace>,
+        config: &ConversationMemoryConfig,
+    ) -> Result<(usize, usize)> {
+        let _guard = self.merge_lock.lock().await;
+        let known = self.facts(namespace).await?;
+        let mut seen: HashSet<String> = known.iter().map(|f| normalize(&f.content)).collect();
+        let fresh: Vec<AtomicFact> = new_facts
+            .into_iter()
+            .map(|mut f| {
+                f.content = self.redact(&f.content);
+                f
+            })
+            .filter(|f| !f.content.trim().is_empty() && seen.insert(normalize(&f.content)))
+            .collect();
+        if fresh.is_empty() {
+            return Ok((0, 0));
+        }
+
+        let mut combined = known.clone();
+        combined.extend(fresh);
+        let (resolved, conflicts) = match &config.resolution {
+            Some(ResolutionMethod::LLMDecision) => {
+                let resolved = self.resolver.resolve_facts_with_llm(&combined).await;
+                (resolved.facts, resolved.conflicts)
+            }
+            Some(method) => (
+                self.resolver.resolve_facts(&combined, method.clone()),
+                Vec::new(),
+            ),
+            None => (self.resolver.weighted_resolve(&combined), Vec::new()),
+        };
+        let audit = resolution_audit(&conflicts, |text| self.redact(text));
+        let (mut written, removed) = diff_facts(&known, &combined, resolved);
+        let added = written.len();
+
+        // 胜出的旧事实内容不变，也要重写一次以记录裁决理由
+        let rewritten: Vec<AtomicFact> = known
+            .iter()
+            .filter(|f| audit.contains_key(&f.id))
+            .filter(|f| !removed.iter().any(|r| r.id == f.id))
+            .filter(|f| !written.iter().any(|w| w.id == f.id))
+            .cloned()
+            .collect();
+        written.extend(rewritten);
+
+        for fact in &removed {
+            self.search.remove_memory(&fact.id).await?;
+        }
+        for fact in &written {
+            let vector = self.embedder.embed(&fact.content).await?;
+            let mut metadata = serde_json::json!({
+                KIND_FIELD: KIND_FACT,
+                "category": fact.category,
+                "confidence": fact.confidence,
+                "is_negative": fact.is_negative,
+                "source_message_id": fact.source_message_id,
+                "created_at": fact.created_at.to_rfc3339(),
+            });
+            if let Some(resolution) = audit.get(&fact.id) {
+                metadata["resolution"] = resolution.clone();
+            }
+            if let Some(namespace) = namespace {
+                namespace.stamp(&mut metadata);
+            }
+            self.search
+                .add_memory(fact.id.clone(), fact.content.clone(), vector, metadata)
+                .await?;
+        }
+
+        Ok((added, removed.len()))
+    }
+}
+
+/// 从存储的 payload 还原事实，缺少字段的条目跳过
+fn fact_from_item(item: &VectorItem) -> Option<AtomicFact> {
+    let payload = &item.payload;
+    Some(AtomicFact {
+        id: item.id.clone(),
+        content: payload.get("content")?.as_str()?.to_string(),
+        category: serde_json::from_value(payload.get("category")?.clone()).ok()?,
+        source_message_id: payload
+            .get("source_message_id")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        created_at: payload
+            .get("created_at")?
+            .as_str()?
+            .parse::<chrono::DateTime<Utc>>()
+            .ok()?,
+        confidence: payload.get("confidence")?.as_f64()? as f32,
+        is_negative: payload
+            .get("is_negative")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+    })
+}
+
+/// 按裁决结果产出的事实 ID 索引裁决记录，写入事实 payload 供审计；记录中的文本经 `redact` 脱敏
+fn resolution_audit(
+    conflicts: &[Conflict],
+    redact: impl Fn(&str) -> String,
+) -> HashMap<String, serde_json::Value> {
+    let mut audit = HashMap::new();
+    for conflict in conflicts {
+        let Some(resolution) = &conflict.resolution else {
+            continue;
+        };
+        let record = serde_json::json!({
+            "method": resolution.method,
+            "action": resolution.action,
+            "reason": redact(&resolution.reason),
+            "facts": [redact(&conflict.fact_a.content), redact(&conflict.fact_b.content)],
+            "resolved_at": resolution.resolved_at.to_rfc3339(),
+        });
+        match resolution.action {
+            ConflictAction::KeepOne => {
+                audit.insert(resolution.winner.clone(), record);
+            }
+            ConflictAction::Merge | ConflictAction::KeepBoth => {
+                for fact in &resolution.replacements {
+                    audit.insert(fact.id.clone(), record.clone());
+                }
+            }
+        }
+    }
+    audit
+}
+
+/// 对比裁决前后的事实，返回需要写入的事实（按原顺序）和被淘汰的旧事实
+fn diff_facts(
+    known: &[AtomicFact],
+    combined: &[AtomicFact],
+    mut resolved: Vec<AtomicFact>,
+) -> (Vec<AtomicFact>, Vec<AtomicFact>) {
+    let position: HashMap<&str, usize> = combined
+        .iter()
+        .enumerate()
+        .map(|(i, f)| (f.id.as_str(), i))
+        .collect();
+    resolved.sort_by_key(|f| position.get(f.id.as_str()).copied().unwrap_or(usize::MAX));
+
+    let unchanged: HashSet<(&str, &str)> = known
+        .iter()
+        .map(|f| (f.id.as_str(), f.content.as_str()))
+        .collect();
+    let written = resolved
+        .iter()
+        .filter(|f| !unchanged.contains(&(f.id.as_str(), f.content.as_str())))
+        .cloned()
+        .collect();
+
+    let kept_ids: HashSet<&str> = resolved.iter().map(|f| f.id.as_str()).collect();
+    let removed = known
+        .iter()
+        .filter(|f| !kept_ids.contains(f.id.as_str()))
+        .cloned()
+        .collect();
+    (written, removed)
+}
+
+fn normalize(content: &str) -> String {
+    content.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use openclaw_memory::fact_extractor::FactCategory;
+    use openclaw_vector::{MemoryStore, VectorStore};
+
+    struct HashEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for HashEmbedding {
+        fn name(&self) -> &str {
+            "hash"
+        }
+
+        fn model(&self) -> &str {
+            "hash"
+        }
+
+        fn dimensions(&self) -> usize {
+            8
+        }
+
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            let mut vector = vec![0.0; 8];
+            for (i, b) in text.bytes().enumerate() {
+                vector[i % 8] += b as f32;
+            }
+            Ok(vector)
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut out = Vec::new();
+            for text in texts {
+                out.push(self.embed(text).await?);
+            }
+            Ok(out)
+        }
+    }
+
+    struct FixedFacts(Vec<&'static str>);
+
+    #[async_trait]
+    impl FactExtractor for FixedFacts {
+        async fn extract_facts(
+            &self,
+            _conversation: &str,
+        ) -> std::result::Result<Vec<AtomicFact>, openclaw_core::OpenClawError> {
+            Ok(self
+                .0
+                .iter()
+                .map(|c| AtomicFact::new(c.to_string(), FactCategory::UserPreference))
+                .collect())
+        }
+    }
+
+    fn memory() -> ConversationMemory {
+        memory_on(Arc::new(MemoryStore::new()))
+    }
+
+    fn memory_on(store: Arc<MemoryStore>) -> ConversationMemory {
+        let search = HybridSearchManager::new(store, HybridSearchConfig::default());
+        ConversationMemory::new(Arc::new(search), Arc::new(HashEmbedding))
+    }
+
+    fn recalled(content: &str) -> RecalledMemory {
+        RecalledMemory {
+            id: content.to_string(),
+            content: content.to_string(),
+            score: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_context_message_respects_budget() {
+        let memories = vec![recalled(&"a".repeat(40)), recalled(&"b".repeat(400))];
+
+        let message = ConversationMemory::context_message(&memories, 30).unwrap();
+        let text = message.text_content().unwrap();
+        assert!(text.contains(&"a".repeat(40)));
+        assert!(!text.contains('b'));
+
+        assert!(ConversationMemory::context_message(&memories, 5).is_none());
+        assert!(ConversationMemory::context_message(&[], 1000).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_remember_and_recall() {
+        let memory = memory();
+        let config = ConversationMemoryConfig::default();
+
+        let outcome = memory
+            .remember(
+                &Message::user("What is the deploy password for the staging account?"),
+                &Message::assistant("It is stored in the team vault under staging."),
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+        assert!(outcome.stored);
+
+        let recalled = memory
+            .recall("staging password", None, &config)
+            .await
+            .unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert!(recalled[0].content.contains("team vault"));
+    }
+
+    #[tokio::test]
+    async fn test_entities_link_to_stored_exchange() {
+        use openclaw_memory::knowledge_graph::KnowledgeGraph;
+
+        let graph = Arc::new(KnowledgeGraph::new());
+        let memory = memory().with_graph_extractor(Arc::new(GraphExtractor::new(graph.clone())));
+        let config = ConversationMemoryConfig {
+            min_importance: 0.0,
+            ..Default::default()
+        };
+
+        let outcome = memory
+            .remember(
+                &Message::user("I work at Acme Corp and need the VPN setup steps"),
+                &Message::assistant("Install the Acme VPN client first."),
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+        assert!(outcome.stored);
+        assert_eq!(outcome.entities_linked, 2);
+
+        let recalled = memory.recall("Acme VPN", None, &config).await.unwrap();
+        let acme = &graph.find_entities_by_name("Acme Corp").await[0];
+        assert_eq!(acme.source_ids(), vec![recalled[0].id.clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_low_importance_exchange_is_skipped() {
+        let memory = memory();
+        let config = ConversationMemoryConfig {
+            min_importance: 0.9,
+            ..Default::default()
+        };
+
+        let outcome = memory
+            .remember(
+                &Message::user("hi"),
+                &Message::assistant("hello"),
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+        assert!(!outcome.stored);
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_fact_replaces_old_one() {
+        let config = ConversationMemoryConfig {
+            store_exchanges: false,
+            resolution: Some(ResolutionMethod::Latest),
+            ..Default::default()
+        };
+
+        let memory = memory().with_fact_extractor(Arc::new(FixedFacts(vec!["用户喜欢咖啡"])));
+        let outcome = memory
+            .remember(
+                &Message::user("我喜欢咖啡"),
+                &Message::assistant("好的"),
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.facts_added, 1);
+
+        // 重复的事实不会再次写入
+        let outcome = memory
+            .remember(
+                &Message::user("我喜欢咖啡"),
+                &Message::assistant("好的"),
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.facts_added, 0);
+
+        let memory = memory.with_fact_extractor(Arc::new(FixedFacts(vec!["用户不喜欢咖啡"])));
+        let outcome = memory
+            .remember(
+                &Message::user("我不喜欢咖啡了"),
+                &Message::assistant("好的"),
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.facts_added, 1);
+        assert_eq!(outcome.facts_replaced, 1);
+
+        let facts = memory.facts(None).await.unwrap();
+        assert_eq!(facts.len(), 1);
+        assert_eq!(facts[0].content, "用户不喜欢咖啡");
+        assert_eq!(facts[0].category, FactCategory::UserPreference);
+    }
+
+    #[tokio::test]
+    async fn test_llm_resolution_is_recorded_on_fact() {
+        let config = ConversationMemoryConfig {
+            store_exchanges: false,
+            resolution: Some(ResolutionMethod::LLMDecision),
+            ..Default::default()
+        };
+        let store = Arc::new(MemoryStore::new());
+
+        let memory = memory_on(store.clone())
+            .with_fact_extractor(Arc::new(FixedFacts(vec!["用户喜欢咖啡"])));
+        memory
+            .remember(
+                &Message::user("我喜欢咖啡"),
+                &Message::assistant("好的"),
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+
+        let memory = memory.with_fact_extractor(Arc::new(FixedFacts(vec!["用户不喜欢咖啡"])));
+        let outcome = memory
+            .remember(
+                &Message::user("我不喜欢咖啡了"),
+                &Message::assistant("好的"),
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.facts_added, 1);
+        assert_eq!(outcome.facts_replaced, 1);
+
+        let facts = memory.facts(None).await.unwrap();
+        assert_eq!(facts.len(), 1);
+        let item = store.get(&facts[0].id).await.unwrap().unwrap();
+        let resolution = &item.payload["resolution"];
+        assert_eq!(resolution["action"], "keep_one");
+        assert!(
+            resolution["reason"]
+                .as_str()
+                .unwrap()
+                .contains("未配置 LLM")
+        );
+        assert_eq!(resolution["facts"][0], "用户喜欢咖啡");
+    }
+
+    #[tokio::test]
+    async fn test_memories_are_scoped_to_namespace() {
+        let config = ConversationMemoryConfig {
+            min_importance: 0.0,
+            ..Default::default()
+        };
+        let alice = MemoryNamespace::new("alice", "assistant");
+        let bob = MemoryNamespace::new("bob", "assistant");
+
+        let store = Arc::new(MemoryStore::new());
+        let memory = memory_on(store.clone())
+            .with_fact_extractor(Arc::new(FixedFacts(vec!["用户住在上海"])));
+        memory
+            .remember(
+                &Message::user("I moved to Shanghai, my new address is on Nanjing Road"),
+                &Message::assistant("Noted your new address."),
+                Some(&alice),
+                &config,
+            )
+            .await
+            .unwrap();
+
+        let recalled = memory
+            .recall("Shanghai address", Some(&alice), &config)
+            .await
+            .unwrap();
+        assert!(!recalled.is_empty());
+        assert!(
+            memory
+                .recall("Shanghai address", Some(&bob), &config)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // 事实保存在存储中，新实例也能读到
+        let reopened = memory_on(store);
+        assert_eq!(reopened.facts(Some(&alice)).await.unwrap().len(), 1);
+        assert!(reopened.facts(Some(&bob)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pii_is_redacted_before_storage() {
+        let config = ConversationMemoryConfig {
+            min_importance: 0.0,
+            ..Default::default()
+        };
+        let memory = memory()
+            .with_fact_extractor(Arc::new(FixedFacts(vec!["用户邮箱是 alice@example.com"])));
+
+        let outcome = memory
+            .remember(
+                &Message::user("Please send the invoice to alice@example.com"),
+                &Message::assistant("I will send it to alice@example.com today."),
+                None,
+                &config,
+            )
+            .await
+            .unwrap();
+        assert!(outcome.stored);
+
+        let recalled = memory.recall("invoice", None, &config).await.unwrap();
+        assert!(!recalled.is_empty());
+        assert!(
+            recalled
+                .iter()
+                .all(|m| !m.content.contains("alice@example.com"))
+        );
+
+        let facts = memory.facts(None).await.unwrap();
+        assert_eq!(facts.len(), 1);
+        assert!(!facts[0].content.contains("alice@example.com"));
+    }
+}
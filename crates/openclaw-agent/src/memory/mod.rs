@@ -1,7 +1,9 @@
 //! Memory Module - 内存管理模块
 //!
-//! 提供上下文压缩和内存清理功能
+//! 提供上下文压缩、内存清理和对话记忆的召回与写入
 
 pub mod compression;
+pub mod conversation;
 
 pub use compression::{CleanupPolicy, CompressionConfig, ContextCompactor, ContextMessage, MemoryCleanupPolicy};
+pub use conversation::{ConversationMemory, ConversationMemoryConfig, RecalledMemory, RememberOutcome};
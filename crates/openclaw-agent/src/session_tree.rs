@@ -5,6 +5,7 @@
 //! - 会话历史回溯
 //! - 分支合并
 //! - 上下文路径构建
+//! - 在任意消息处分叉、比较分支对话
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -25,6 +26,12 @@ pub struct SessionNode {
     pub id: Uuid,
     /// 父节点 ID（根节点为 None）
     pub parent_id: Option<Uuid>,
+    /// 所属会话 ID（根节点即会话本身）
+    #[serde(default)]
+    pub session_id: Uuid,
+    /// 分叉点：只继承父节点到此消息（含）为止的历史，None 表示继承全部
+    #[serde(default)]
+    pub fork_message_id: Option<Uuid>,
     /// 分支标识（同一父节点的分支用 branch_id 区分）
     pub branch_id: Uuid,
     /// 会话名称
@@ -65,11 +72,13 @@ impl SessionNode {
         scope: SessionScope,
     ) -> Self {
         let now = Utc::now();
-        let branch_id = Uuid::new_v4();
+        let id = Uuid::new_v4();
         Self {
-            id: Uuid::new_v4(),
+            id,
             parent_id: None,
-            branch_id,
+            session_id: id,
+            fork_message_id: None,
+            branch_id: Uuid::new_v4(),
             name: name.into(),
             agent_id,
             scope,
@@ -93,6 +102,8 @@ impl SessionNode {
         Self {
             id: session.id,
             parent_id: None,
+            session_id: session.id,
+            fork_message_id: None,
             branch_id: Uuid::new_v4(),
             name: session.name.clone(),
             agent_id: session.agent_id.clone(),
@@ -117,6 +128,8 @@ impl SessionNode {
         Self {
             id: Uuid::new_v4(),
             parent_id: Some(self.id),
+            session_id: self.session_id,
+            fork_message_id: None,
             branch_id: Uuid::new_v4(),
             name: name.into(),
             agent_id: self.agent_id.clone(),
@@ -135,6 +148,23 @@ impl SessionNode {
         }
     }
 
+    /// 在指定消息处创建分支，消息不属于本节点时返回 None
+    pub fn fork_at(&self, message_id: Uuid, name: impl Into<String>) -> Option<Self> {
+        self.message_history.iter().find(|m| m.id == message_id)?;
+        let mut child = self.branch(name);
+        child.fork_message_id = Some(message_id);
+        Some(child)
+    }
+
+    /// 子节点从本节点继承的消息
+    fn inherited_by(&self, child: &SessionNode) -> &[SessionMessage] {
+        let end = child
+            .fork_message_id
+            .and_then(|id| self.message_history.iter().position(|m| m.id == id))
+            .map_or(self.message_history.len(), |i| i + 1);
+        &self.message_history[..end]
+    }
+
     /// 检查是否为根节点
     #[inline]
     pub fn is_root(&self) -> bool {
@@ -207,6 +237,23 @@ pub struct SessionMessage {
     pub timestamp: DateTime<Utc>,
 }
 
+impl From<&openclaw_core::Message> for SessionMessage {
+    fn from(message: &openclaw_core::Message) -> Self {
+        let role = match message.role {
+            openclaw_core::Role::System => "system",
+            openclaw_core::Role::User => "user",
+            openclaw_core::Role::Assistant => "assistant",
+            openclaw_core::Role::Tool => "tool",
+        };
+        Self {
+            id: message.id,
+            role: role.to_string(),
+            content: message.text_content().unwrap_or_default().to_string(),
+            timestamp: message.created_at,
+        }
+    }
+}
+
 /// 会话树状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -214,6 +261,8 @@ pub enum SessionTreeState {
     Active,
     Paused,
     Closed,
+    /// 摘要已合并回父节点
+    Merged,
 }
 
 /// 会话树管理器
@@ -234,6 +283,27 @@ impl SessionTree {
         }
     }
 
+    /// 从持久化的节点重建会话树，活跃路径恢复到最近活跃的未合并节点
+    pub fn from_nodes(nodes: Vec<SessionNode>) -> Self {
+        let root_id = nodes.iter().find(|n| n.is_root()).map(|n| n.id);
+        let current = nodes
+            .iter()
+            .filter(|n| n.state != SessionTreeState::Merged)
+            .max_by_key(|n| n.last_active_at)
+            .map(|n| n.id);
+        let nodes: HashMap<Uuid, SessionNode> = nodes.into_iter().map(|n| (n.id, n)).collect();
+        let active_path = current
+            .and_then(|id| path_in(&nodes, id))
+            .map(|path| path.iter().map(|n| n.id).collect())
+            .unwrap_or_default();
+
+        Self {
+            nodes: Arc::new(RwLock::new(nodes)),
+            active_path: Arc::new(RwLock::new(active_path)),
+            root_id: Arc::new(RwLock::new(root_id)),
+        }
+    }
+
     /// 初始化根节点（用于测试）
     pub async fn init_root(&self, name: impl Into<String>) {
         let node = SessionNode::root(name, "system".to_string(), SessionScope::Main);
@@ -564,11 +634,166 @@ impl Default for SessionTree {
 impl SessionTree {
     /// 获取上下文构建所需的消息（从根到当前）
     pub async fn get_context_messages(&self) -> Vec<SessionMessage> {
-        let path = self.path().await;
-        path.into_iter()
-            .flat_map(|node| node.message_history.clone())
-            .collect()
+        let current = {
+            let active_path = self.active_path.read().await;
+            active_path.last().copied()
+        };
+        match current {
+            Some(id) => self.transcript(id).await.unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+
+    /// 获取指定节点
+    pub async fn get(&self, node_id: Uuid) -> Option<SessionNode> {
+        let nodes = self.nodes.read().await;
+        nodes.get(&node_id).cloned()
+    }
+
+    /// 从根到指定节点的完整对话，按各分支的分叉点截断父节点历史
+    pub async fn transcript(&self, node_id: Uuid) -> Option<Vec<SessionMessage>> {
+        let nodes = self.nodes.read().await;
+        let path = path_in(&nodes, node_id)?;
+        let mut messages = Vec::new();
+        for pair in path.windows(2) {
+            messages.extend_from_slice(pair[0].inherited_by(pair[1]));
+        }
+        messages.extend_from_slice(&path.last()?.message_history);
+        Some(messages)
+    }
+
+    /// 在某条消息处分叉并切换到新分支
+    ///
+    /// 消息可以位于 `node_id` 或其任一祖先节点，新分支挂在持有该消息的节点下，
+    /// 只继承到该消息为止的历史，用于“从这里编辑重试”。
+    pub async fn fork_at(
+        &self,
+        node_id: Uuid,
+        message_id: Uuid,
+        name: impl Into<String>,
+    ) -> Option<SessionNode> {
+        let child = {
+            let mut nodes = self.nodes.write().await;
+            let path = path_in(&nodes, node_id)?;
+            let owner = path
+                .iter()
+                .rev()
+                .find(|n| n.message_history.iter().any(|m| m.id == message_id))?;
+            let child = owner.fork_at(message_id, name)?;
+            nodes.insert(child.id, child.clone());
+            child
+        };
+
+        self.switch_to(child.id).await
+    }
+
+    /// 比较两个分支的对话
+    pub async fn diff(&self, left: Uuid, right: Uuid) -> Option<BranchDiff> {
+        let left_messages = self.transcript(left).await?;
+        let right_messages = self.transcript(right).await?;
+
+        let common_ancestor = {
+            let nodes = self.nodes.read().await;
+            let left_path = path_in(&nodes, left)?;
+            let right_path = path_in(&nodes, right)?;
+            left_path
+                .iter()
+                .zip(right_path.iter())
+                .take_while(|(l, r)| l.id == r.id)
+                .last()
+                .map(|(node, _)| node.id)
+        };
+
+        let shared = left_messages
+            .iter()
+            .zip(right_messages.iter())
+            .take_while(|(l, r)| l.id == r.id)
+            .count();
+
+        Some(BranchDiff {
+            left,
+            right,
+            common_ancestor,
+            shared,
+            left_only: left_messages[shared..].to_vec(),
+            right_only: right_messages[shared..].to_vec(),
+        })
+    }
+
+    /// 将分支摘要合并回父节点，返回更新后的父节点
+    ///
+    /// 未提供摘要时使用分支自身消息的节选。分支标记为已合并，
+    /// 当前位于该分支时切回父节点。
+    pub async fn merge(&self, branch_id: Uuid, summary: Option<String>) -> Option<SessionNode> {
+        let parent = {
+            let mut nodes = self.nodes.write().await;
+            let branch = nodes.get_mut(&branch_id)?;
+            let parent_id = branch.parent_id?;
+            if branch.state == SessionTreeState::Merged {
+                return None;
+            }
+
+            let summary = summary.unwrap_or_else(|| summarize_messages(&branch.message_history));
+            branch.state = SessionTreeState::Merged;
+            branch.updated_at = Utc::now();
+            branch
+                .metadata
+                .insert("merge_summary".to_string(), serde_json::json!(summary));
+            let branch_name = branch.name.clone();
+
+            let parent = nodes.get_mut(&parent_id)?;
+            parent.add_message("system", format!("[分支「{}」摘要] {}", branch_name, summary), 0);
+            parent.clone()
+        };
+
+        let on_branch = self.active_path.read().await.contains(&branch_id);
+        if on_branch {
+            self.switch_to(parent.id).await;
+        }
+        Some(parent)
+    }
+}
+
+/// 从根到指定节点的路径
+fn path_in(nodes: &HashMap<Uuid, SessionNode>, node_id: Uuid) -> Option<Vec<&SessionNode>> {
+    let mut path = Vec::new();
+    let mut current = Some(node_id);
+    while let Some(id) = current {
+        let node = nodes.get(&id)?;
+        path.push(node);
+        current = node.parent_id;
     }
+    path.reverse();
+    Some(path)
+}
+
+/// 合并时未提供摘要的兜底：每条消息截取开头
+fn summarize_messages(messages: &[SessionMessage]) -> String {
+    const MAX_CHARS: usize = 80;
+    messages
+        .iter()
+        .map(|m| {
+            let mut content: String = m.content.chars().take(MAX_CHARS).collect();
+            if m.content.chars().count() > MAX_CHARS {
+                content.push('…');
+            }
+            format!("{}: {}", m.role, content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 两个分支的对话差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchDiff {
+    pub left: Uuid,
+    pub right: Uuid,
+    /// 最近公共祖先节点
+    pub common_ancestor: Option<Uuid>,
+    /// 开头相同的消息数
+    pub shared: usize,
+    pub left_only: Vec<SessionMessage>,
+    pub right_only: Vec<SessionMessage>,
 }
 
 /// 分支信息（用于显示）
@@ -577,6 +802,8 @@ pub struct BranchInfo {
     pub id: Uuid,
     pub name: String,
     pub parent_id: Option<Uuid>,
+    pub fork_message_id: Option<Uuid>,
+    pub state: SessionTreeState,
     pub message_count: usize,
     pub created_at: DateTime<Utc>,
     pub is_active: bool,
@@ -588,6 +815,8 @@ impl From<&SessionNode> for BranchInfo {
             id: node.id,
             name: node.name.clone(),
             parent_id: node.parent_id,
+            fork_message_id: node.fork_message_id,
+            state: node.state,
             message_count: node.message_count,
             created_at: node.created_at,
             is_active: node.is_active(),
@@ -864,4 +1093,63 @@ mod tests {
         let current = tree.current().await.unwrap();
         assert_eq!(current.name, "branch-1");
     }
+
+    #[tokio::test]
+    async fn test_fork_at_message_and_diff() {
+        let tree = create_test_tree();
+        let root = tree
+            .create_session("main", "agent-1".to_string(), SessionScope::Main)
+            .await;
+        tree.add_message("user", "写一首诗", 5).await;
+        tree.add_message("assistant", "床前明月光", 5).await;
+        tree.add_message("user", "换成五言绝句", 5).await;
+        tree.add_message("assistant", "白日依山尽", 5).await;
+
+        let messages = tree.get(root.id).await.unwrap().message_history;
+        let retry = tree.fork_at(root.id, messages[1].id, "retry").await.unwrap();
+        assert_eq!(retry.parent_id, Some(root.id));
+        assert_eq!(retry.session_id, root.id);
+        assert_eq!(tree.current().await.unwrap().id, retry.id);
+
+        tree.add_message("user", "换成七言绝句", 5).await;
+        let context = tree.get_context_messages().await;
+        let contents: Vec<&str> = context.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["写一首诗", "床前明月光", "换成七言绝句"]);
+
+        let diff = tree.diff(root.id, retry.id).await.unwrap();
+        assert_eq!(diff.common_ancestor, Some(root.id));
+        assert_eq!(diff.shared, 2);
+        assert_eq!(diff.left_only.len(), 2);
+        assert_eq!(diff.right_only[0].content, "换成七言绝句");
+
+        assert!(tree.fork_at(root.id, Uuid::new_v4(), "missing").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merge_and_restore() {
+        let tree = create_test_tree();
+        let root = tree
+            .create_session("main", "agent-1".to_string(), SessionScope::Main)
+            .await;
+        tree.add_message("user", "hello", 1).await;
+        let branch = tree.branch("experiment").await.unwrap();
+        tree.add_message("assistant", "tried another approach", 1).await;
+
+        let parent = tree.merge(branch.id, None).await.unwrap();
+        assert_eq!(parent.id, root.id);
+        let last = parent.message_history.last().unwrap();
+        assert_eq!(last.role, "system");
+        assert!(last.content.contains("tried another approach"));
+        assert_eq!(tree.current().await.unwrap().id, root.id);
+        assert!(tree.merge(branch.id, None).await.is_none());
+        assert!(tree.merge(root.id, None).await.is_none());
+
+        let restored = SessionTree::from_nodes(tree.all_nodes().await);
+        assert_eq!(restored.root().await.unwrap().id, root.id);
+        assert_eq!(restored.current().await.unwrap().id, root.id);
+        assert_eq!(
+            restored.get(branch.id).await.unwrap().state,
+            SessionTreeState::Merged
+        );
+    }
 }
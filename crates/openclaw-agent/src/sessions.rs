@@ -5,8 +5,9 @@
 //! - 会话历史
 //! - 会话元数据
 //! - 会话状态持久化
+//! - 会话分支树持久化，分叉、比较与合并
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -18,8 +19,12 @@ use uuid::Uuid;
 
 use openclaw_core::session::SessionScope;
 
+use crate::session_tree::{BranchDiff, BranchInfo, SessionMessage, SessionNode, SessionTree};
 use crate::types::{AgentId, PersonaId};
 
+/// 会话元数据中记录所有者（创建会话的 API 调用方）的键
+pub const SESSION_OWNER_KEY: &str = "owner";
+
 /// Session 会话
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
@@ -120,6 +125,15 @@ impl Session {
         self
     }
 
+    pub fn with_owner(self, owner: impl Into<String>) -> Self {
+        self.with_metadata(SESSION_OWNER_KEY, serde_json::Value::String(owner.into()))
+    }
+
+    /// 会话所有者，通道等内部创建的会话没有所有者
+    pub fn owner(&self) -> Option<&str> {
+        self.metadata.get(SESSION_OWNER_KEY).and_then(|v| v.as_str())
+    }
+
     pub fn update_activity(&mut self) {
         self.last_active_at = Utc::now();
         self.updated_at = Utc::now();
@@ -207,12 +221,24 @@ pub trait SessionStorage: Send + Sync {
     async fn save_messages(&self, session_id: &Uuid, messages: &[openclaw_core::Message]) -> crate::Result<()>;
     async fn load_messages(&self, session_id: &Uuid) -> crate::Result<Vec<openclaw_core::Message>>;
     async fn delete_messages(&self, session_id: &Uuid) -> crate::Result<()>;
+    /// 保存分支树节点（按 ID 覆盖）
+    async fn save_nodes(&self, nodes: &[SessionNode]) -> crate::Result<()>;
+    /// 加载会话的全部分支树节点
+    async fn load_nodes(&self, session_id: &Uuid) -> crate::Result<Vec<SessionNode>>;
+    /// 一次加载符合条件的所有会话的分支树节点，按会话 ID 分组
+    async fn list_nodes(
+        &self,
+        agent_id: Option<&AgentId>,
+        state: Option<SessionState>,
+    ) -> crate::Result<HashMap<Uuid, Vec<SessionNode>>>;
+    async fn delete_nodes(&self, session_id: &Uuid) -> crate::Result<()>;
 }
 
 /// 内存会话存储
 pub struct MemorySessionStorage {
     sessions: Arc<RwLock<HashMap<Uuid, Session>>>,
     key_index: Arc<RwLock<HashMap<String, Uuid>>>,
+    nodes: Arc<RwLock<HashMap<Uuid, SessionNode>>>,
 }
 
 impl MemorySessionStorage {
@@ -220,6 +246,7 @@ impl MemorySessionStorage {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             key_index: Arc::new(RwLock::new(HashMap::new())),
+            nodes: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -241,9 +268,12 @@ impl SqliteSessionStorage {
         }
     }
 
+    /// 打开连接并确保表结构存在，新建的数据库也可以直接查询
     fn get_connection(&self) -> crate::Result<rusqlite::Connection> {
-        rusqlite::Connection::open(&self.db_path)
-            .map_err(|e| crate::OpenClawError::Session(format!("SQLite error: {}", e)))
+        let conn = rusqlite::Connection::open(&self.db_path)
+            .map_err(|e| crate::OpenClawError::Session(format!("SQLite error: {}", e)))?;
+        Self::init_tables(&conn)?;
+        Ok(conn)
     }
 
     fn init_tables(conn: &rusqlite::Connection) -> crate::Result<()> {
@@ -294,6 +324,22 @@ impl SqliteSessionStorage {
             [],
         ).map_err(|e| crate::OpenClawError::Session(format!("Failed to create messages index: {}", e)))?;
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_nodes (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                parent_id TEXT,
+                data TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        ).map_err(|e| crate::OpenClawError::Session(format!("Failed to create nodes table: {}", e)))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_session_nodes_session_id ON session_nodes(session_id)",
+            [],
+        ).map_err(|e| crate::OpenClawError::Session(format!("Failed to create nodes index: {}", e)))?;
+
         Ok(())
     }
 }
@@ -302,7 +348,6 @@ impl SqliteSessionStorage {
 impl SessionStorage for SqliteSessionStorage {
     async fn save(&self, session: &Session) -> crate::Result<()> {
         let conn = self.get_connection()?;
-        Self::init_tables(&conn)?;
 
         let id = session.id.to_string();
         let scope = session_scope_to_string(&session.scope);
@@ -320,11 +365,20 @@ impl SessionStorage for SqliteSessionStorage {
         let history_summary = session.history_summary.clone();
 
         conn.execute(
-            "INSERT OR REPLACE INTO sessions 
+            // 用 upsert 而不是 INSERT OR REPLACE：替换会先删除旧行，级联删除会话消息
+            "INSERT INTO sessions 
              (id, name, scope, agent_id, persona_id, channel_type, account_id, peer_id, state,
               created_at, updated_at, last_active_at, message_count, token_count, metadata,
               system_prompt, history_summary)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+             ON CONFLICT(id) DO UPDATE SET
+              name = excluded.name, scope = excluded.scope, agent_id = excluded.agent_id,
+              persona_id = excluded.persona_id, channel_type = excluded.channel_type,
+              account_id = excluded.account_id, peer_id = excluded.peer_id, state = excluded.state,
+              created_at = excluded.created_at, updated_at = excluded.updated_at,
+              last_active_at = excluded.last_active_at, message_count = excluded.message_count,
+              token_count = excluded.token_count, metadata = excluded.metadata,
+              system_prompt = excluded.system_prompt, history_summary = excluded.history_summary",
             rusqlite::params![
                 id, session.name, scope, agent_id, persona_id, channel_type, account_id, peer_id,
                 state, created_at, updated_at, last_active_at, session.message_count as i64,
@@ -382,7 +436,7 @@ impl SessionStorage for SqliteSessionStorage {
         let conn = self.get_connection()?;
         conn.execute("DELETE FROM sessions WHERE id = ?1", [id.to_string()])
             .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
-        Ok(())
+        self.delete_nodes(id).await
     }
 
     async fn list(
@@ -483,7 +537,6 @@ impl SessionStorage for SqliteSessionStorage {
 
     async fn save_messages(&self, session_id: &Uuid, messages: &[openclaw_core::Message]) -> crate::Result<()> {
         let conn = self.get_connection()?;
-        Self::init_tables(&conn)?;
 
         for msg in messages {
             let msg_id = msg.id.to_string();
@@ -558,6 +611,104 @@ impl SessionStorage for SqliteSessionStorage {
         ).map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
         Ok(())
     }
+
+    async fn save_nodes(&self, nodes: &[SessionNode]) -> crate::Result<()> {
+        let mut conn = self.get_connection()?;
+
+        let tx = conn
+            .transaction()
+            .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+        for node in nodes {
+            let data = serde_json::to_string(node)
+                .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+            tx.execute(
+                "INSERT OR REPLACE INTO session_nodes (id, session_id, parent_id, data, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![
+                    node.id.to_string(),
+                    node.session_id.to_string(),
+                    node.parent_id.map(|id| id.to_string()),
+                    data,
+                    node.updated_at.to_rfc3339()
+                ],
+            )
+            .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+        }
+        tx.commit()
+            .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load_nodes(&self, session_id: &Uuid) -> crate::Result<Vec<SessionNode>> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn
+            .prepare("SELECT id, data FROM session_nodes WHERE session_id = ?1")
+            .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+        let rows = stmt
+            .query_map([session_id.to_string()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+
+        let mut nodes = Vec::new();
+        for row in rows {
+            let (id, data) = row.map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+            nodes.push(decode_node(&id, &data)?);
+        }
+        Ok(nodes)
+    }
+
+    async fn list_nodes(
+        &self,
+        agent_id: Option<&AgentId>,
+        state: Option<SessionState>,
+    ) -> crate::Result<HashMap<Uuid, Vec<SessionNode>>> {
+        let conn = self.get_connection()?;
+        let mut sql = String::from(
+            "SELECT n.id, n.data FROM session_nodes n
+             JOIN sessions s ON s.id = n.session_id WHERE 1=1",
+        );
+        let mut params: Vec<String> = Vec::new();
+        if let Some(aid) = agent_id {
+            sql.push_str(" AND s.agent_id = ?");
+            params.push(aid.to_string());
+        }
+        if let Some(ref st) = state {
+            sql.push_str(" AND s.state = ?");
+            params.push(session_state_to_string(st).to_string());
+        }
+
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+
+        let mut nodes: HashMap<Uuid, Vec<SessionNode>> = HashMap::new();
+        for row in rows {
+            let (id, data) = row.map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+            match decode_node(&id, &data) {
+                Ok(node) => nodes.entry(node.session_id).or_default().push(node),
+                // 列表接口中单个损坏的节点不影响其他会话
+                Err(e) => tracing::warn!("{}", e),
+            }
+        }
+        Ok(nodes)
+    }
+
+    async fn delete_nodes(&self, session_id: &Uuid) -> crate::Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM session_nodes WHERE session_id = ?1",
+            [session_id.to_string()],
+        )
+        .map_err(|e| crate::OpenClawError::Session(e.to_string()))?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -587,7 +738,7 @@ impl SessionStorage for MemorySessionStorage {
             key_index.remove(&format!("{}:{}", session.agent_id, key));
         }
 
-        Ok(())
+        self.delete_nodes(id).await
     }
 
     async fn list(
@@ -634,6 +785,57 @@ impl SessionStorage for MemorySessionStorage {
     async fn delete_messages(&self, _session_id: &Uuid) -> crate::Result<()> {
         Ok(())
     }
+
+    async fn save_nodes(&self, nodes: &[SessionNode]) -> crate::Result<()> {
+        let mut stored = self.nodes.write().await;
+        for node in nodes {
+            stored.insert(node.id, node.clone());
+        }
+        Ok(())
+    }
+
+    async fn load_nodes(&self, session_id: &Uuid) -> crate::Result<Vec<SessionNode>> {
+        let stored = self.nodes.read().await;
+        Ok(stored
+            .values()
+            .filter(|n| &n.session_id == session_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_nodes(
+        &self,
+        agent_id: Option<&AgentId>,
+        state: Option<SessionState>,
+    ) -> crate::Result<HashMap<Uuid, Vec<SessionNode>>> {
+        let sessions = self.sessions.read().await;
+        let stored = self.nodes.read().await;
+        let mut nodes: HashMap<Uuid, Vec<SessionNode>> = HashMap::new();
+        for node in stored.values() {
+            let Some(session) = sessions.get(&node.session_id) else {
+                continue;
+            };
+            if agent_id.is_some_and(|aid| &session.agent_id != aid)
+                || state.is_some_and(|st| session.state != st)
+            {
+                continue;
+            }
+            nodes.entry(node.session_id).or_default().push(node.clone());
+        }
+        Ok(nodes)
+    }
+
+    async fn delete_nodes(&self, session_id: &Uuid) -> crate::Result<()> {
+        let mut stored = self.nodes.write().await;
+        stored.retain(|_, n| &n.session_id != session_id);
+        Ok(())
+    }
+}
+
+fn decode_node(id: &str, data: &str) -> crate::Result<SessionNode> {
+    serde_json::from_str(data).map_err(|e| {
+        crate::OpenClawError::Session(format!("Failed to decode session node {}: {}", id, e))
+    })
 }
 
 /// 会话管理器
@@ -722,6 +924,28 @@ impl SessionManager {
         Ok(session)
     }
 
+    /// 为调用方创建会话，不复用同键的已有会话，避免不同调用方共享会话
+    pub async fn create_owned_session(
+        &self,
+        owner: impl Into<String>,
+        name: impl Into<String>,
+        agent_id: AgentId,
+        scope: SessionScope,
+        channel_type: Option<String>,
+    ) -> crate::Result<Session> {
+        let mut session = Session::new(name, agent_id, scope).with_owner(owner);
+        session.channel_type = channel_type;
+
+        if self.config.auto_save {
+            self.storage.save(&session).await?;
+        }
+
+        let mut active = self.active_sessions.write().await;
+        active.insert(session.id, session.clone());
+
+        Ok(session)
+    }
+
     /// 获取会话
     pub async fn get_session(&self, id: &Uuid) -> crate::Result<Option<Session>> {
         {
@@ -827,6 +1051,188 @@ impl SessionManager {
         Ok(cleaned)
     }
 
+    /// 加载会话分支树；尚未分叉过的会话以已保存的消息作为根节点
+    ///
+    /// 根节点的对话始终以会话消息表为准，分叉后才保存的消息也会补进根节点。
+    pub async fn session_tree(&self, session_id: &Uuid) -> crate::Result<SessionTree> {
+        let messages = self.storage.load_messages(session_id).await?;
+        let mut nodes = self.storage.load_nodes(session_id).await?;
+        if nodes.is_empty() {
+            let session = self.get_session(session_id).await?.ok_or_else(|| {
+                crate::OpenClawError::Session(format!("Session {} not found", session_id))
+            })?;
+            nodes.push(SessionNode::from_session(&session));
+        }
+
+        if let Some(root) = nodes.iter_mut().find(|n| n.id == *session_id) {
+            let known: HashSet<Uuid> = root.message_history.iter().map(|m| m.id).collect();
+            root.message_history.extend(
+                messages
+                    .iter()
+                    .filter(|m| !known.contains(&m.id))
+                    .map(SessionMessage::from),
+            );
+        }
+        Ok(SessionTree::from_nodes(nodes))
+    }
+
+    /// 向会话的某个分支追加消息
+    ///
+    /// 未指定分支或指定根节点时写入会话消息表，否则写入该分支节点。
+    pub async fn append_messages(
+        &self,
+        session_id: &Uuid,
+        branch_id: Option<Uuid>,
+        messages: &[openclaw_core::Message],
+    ) -> crate::Result<()> {
+        let mut session = self.get_session(session_id).await?.ok_or_else(|| {
+            crate::OpenClawError::Session(format!("Session {} not found", session_id))
+        })?;
+
+        match branch_id.filter(|id| id != session_id) {
+            None => self.storage.save_messages(session_id, messages).await?,
+            Some(branch_id) => {
+                let mut node = self
+                    .storage
+                    .load_nodes(session_id)
+                    .await?
+                    .into_iter()
+                    .find(|n| n.id == branch_id)
+                    .ok_or_else(|| branch_not_found(&branch_id))?;
+                node.message_history
+                    .extend(messages.iter().map(SessionMessage::from));
+                node.message_count += messages.len();
+                node.updated_at = Utc::now();
+                node.last_active_at = node.updated_at;
+                self.storage.save_nodes(&[node]).await?;
+            }
+        }
+
+        for message in messages {
+            session.add_message(message.metadata.token_count.unwrap_or(0) as u64);
+        }
+        self.update_session(&session).await
+    }
+
+    /// 列出会话的所有分支（含根节点），按创建时间排序
+    pub async fn list_branches(&self, session_id: &Uuid) -> crate::Result<Vec<BranchInfo>> {
+        let mut nodes = self.session_tree(session_id).await?.all_nodes().await;
+        nodes.sort_by_key(|n| n.created_at);
+        Ok(nodes.iter().map(BranchInfo::from).collect())
+    }
+
+    /// 列出会话及其分支，分支树一次查询加载
+    ///
+    /// 尚未分叉过的会话只有根节点，直接由会话本身生成。
+    pub async fn list_sessions_with_branches(
+        &self,
+        agent_id: Option<AgentId>,
+        state: Option<SessionState>,
+    ) -> crate::Result<Vec<(Session, Vec<BranchInfo>)>> {
+        let sessions = self.storage.list(agent_id.as_ref(), state).await?;
+        let mut nodes = self.storage.list_nodes(agent_id.as_ref(), state).await?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|session| {
+                let branches = match nodes.remove(&session.id) {
+                    Some(mut nodes) => {
+                        nodes.sort_by_key(|n| n.created_at);
+                        nodes.iter().map(BranchInfo::from).collect()
+                    }
+                    None => vec![BranchInfo::from(&SessionNode::from_session(&session))],
+                };
+                (session, branches)
+            })
+            .collect())
+    }
+
+    /// 获取某个分支从根开始的完整对话
+    pub async fn branch_transcript(
+        &self,
+        session_id: &Uuid,
+        branch_id: &Uuid,
+    ) -> crate::Result<Vec<SessionMessage>> {
+        self.session_tree(session_id)
+            .await?
+            .transcript(*branch_id)
+            .await
+            .ok_or_else(|| branch_not_found(branch_id))
+    }
+
+    /// 在某条消息处分叉出新分支并持久化
+    ///
+    /// 未指定 `branch_id` 时在整棵树中查找持有该消息的节点。
+    pub async fn fork_session(
+        &self,
+        session_id: &Uuid,
+        branch_id: Option<Uuid>,
+        message_id: Uuid,
+        name: impl Into<String>,
+    ) -> crate::Result<SessionNode> {
+        let tree = self.session_tree(session_id).await?;
+        let branch_id = match branch_id {
+            Some(id) => id,
+            None => tree
+                .all_nodes()
+                .await
+                .into_iter()
+                .find(|n| n.message_history.iter().any(|m| m.id == message_id))
+                .map(|n| n.id)
+                .ok_or_else(|| {
+                    crate::OpenClawError::Session(format!("Message {} not found", message_id))
+                })?,
+        };
+
+        let node = tree
+            .fork_at(branch_id, message_id, name)
+            .await
+            .ok_or_else(|| {
+                crate::OpenClawError::Session(format!(
+                    "Message {} not found on branch {}",
+                    message_id, branch_id
+                ))
+            })?;
+        self.storage.save_nodes(&tree.all_nodes().await).await?;
+        Ok(node)
+    }
+
+    /// 比较两个分支的对话
+    pub async fn diff_branches(
+        &self,
+        session_id: &Uuid,
+        left: &Uuid,
+        right: &Uuid,
+    ) -> crate::Result<BranchDiff> {
+        let tree = self.session_tree(session_id).await?;
+        for id in [left, right] {
+            if !tree.contains(*id).await {
+                return Err(branch_not_found(id));
+            }
+        }
+        tree.diff(*left, *right)
+            .await
+            .ok_or_else(|| branch_not_found(left))
+    }
+
+    /// 将分支摘要合并回父节点并持久化，返回更新后的父节点
+    pub async fn merge_branch(
+        &self,
+        session_id: &Uuid,
+        branch_id: &Uuid,
+        summary: Option<String>,
+    ) -> crate::Result<SessionNode> {
+        let tree = self.session_tree(session_id).await?;
+        let parent = tree.merge(*branch_id, summary).await.ok_or_else(|| {
+            crate::OpenClawError::Session(format!(
+                "Branch {} cannot be merged (missing, root or already merged)",
+                branch_id
+            ))
+        })?;
+        self.storage.save_nodes(&tree.all_nodes().await).await?;
+        Ok(parent)
+    }
+
     /// 统计会话
     pub async fn get_stats(&self) -> SessionStats {
         let sessions = self.storage.list(None, None).await.unwrap_or_default();
@@ -870,6 +1276,10 @@ pub struct SessionStats {
     pub total_tokens: u64,
 }
 
+fn branch_not_found(id: &Uuid) -> crate::OpenClawError {
+    crate::OpenClawError::Session(format!("Branch {} not found", id))
+}
+
 /// Session Scope 辅助函数
 pub fn session_scope_to_string(scope: &SessionScope) -> &'static str {
     match scope {
@@ -982,4 +1392,131 @@ mod tests {
         let loaded = manager.get_session(&session.id).await.unwrap().unwrap();
         assert!(loaded.is_closed());
     }
+
+    #[tokio::test]
+    async fn test_branches_persist_in_sqlite() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(SqliteSessionStorage::new(dir.path().join("sessions.db")));
+        let manager = SessionManager::new(storage.clone());
+
+        let session = manager
+            .create_session("test", "agent-1".to_string(), SessionScope::Main, None, None)
+            .await
+            .unwrap();
+        let messages = vec![
+            openclaw_core::Message::user("question"),
+            openclaw_core::Message::assistant("first answer"),
+        ];
+        storage.save_messages(&session.id, &messages).await.unwrap();
+
+        let branch = manager
+            .fork_session(&session.id, None, messages[0].id, "retry")
+            .await
+            .unwrap();
+        assert_eq!(branch.parent_id, Some(session.id));
+
+        // 新的管理器从数据库恢复分支树
+        let manager = SessionManager::new(storage);
+        let branches = manager.list_branches(&session.id).await.unwrap();
+        assert_eq!(branches.len(), 2);
+
+        let diff = manager
+            .diff_branches(&session.id, &session.id, &branch.id)
+            .await
+            .unwrap();
+        assert_eq!(diff.shared, 1);
+        assert_eq!(diff.left_only[0].content, "first answer");
+        assert!(diff.right_only.is_empty());
+
+        let parent = manager
+            .merge_branch(&session.id, &branch.id, Some("tried again".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(parent.id, session.id);
+        let transcript = manager
+            .branch_transcript(&session.id, &session.id)
+            .await
+            .unwrap();
+        assert!(transcript.last().unwrap().content.contains("tried again"));
+        assert!(
+            manager
+                .merge_branch(&session.id, &branch.id, None)
+                .await
+                .is_err()
+        );
+
+        let other = manager
+            .create_owned_session("key:abc", "mine", "agent-1".to_string(), SessionScope::Main, None)
+            .await
+            .unwrap();
+        assert_ne!(other.id, session.id);
+        let listed = manager.list_sessions_with_branches(None, None).await.unwrap();
+        let count = |id: Uuid| listed.iter().find(|(s, _)| s.id == id).unwrap().1.len();
+        assert_eq!((count(session.id), count(other.id)), (2, 1));
+        let owned = listed.iter().find(|(s, _)| s.id == other.id).unwrap();
+        assert_eq!(owned.0.owner(), Some("key:abc"));
+
+        // 损坏的节点不再被静默忽略
+        let conn = rusqlite::Connection::open(dir.path().join("sessions.db")).unwrap();
+        conn.execute(
+            "UPDATE session_nodes SET data = 'not json' WHERE id = ?1",
+            [branch.id.to_string()],
+        )
+        .unwrap();
+        assert!(manager.list_branches(&session.id).await.is_err());
+        let listed = manager.list_sessions_with_branches(None, None).await.unwrap();
+        assert_eq!(listed.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_messages_after_fork_reach_their_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(SqliteSessionStorage::new(dir.path().join("sessions.db")));
+        let manager = SessionManager::new(storage);
+
+        let session = manager
+            .create_session("test", "agent-1".to_string(), SessionScope::Main, None, None)
+            .await
+            .unwrap();
+        let question = openclaw_core::Message::user("question");
+        manager
+            .append_messages(&session.id, None, &[question.clone()])
+            .await
+            .unwrap();
+        let branch = manager
+            .fork_session(&session.id, None, question.id, "retry")
+            .await
+            .unwrap();
+
+        // 分叉后根节点继续收到消息
+        manager
+            .append_messages(&session.id, None, &[openclaw_core::Message::assistant("later")])
+            .await
+            .unwrap();
+        manager
+            .append_messages(
+                &session.id,
+                Some(branch.id),
+                &[openclaw_core::Message::user("edited")],
+            )
+            .await
+            .unwrap();
+
+        let contents = |messages: Vec<SessionMessage>| {
+            messages.into_iter().map(|m| m.content).collect::<Vec<_>>()
+        };
+        let root = manager.branch_transcript(&session.id, &session.id).await.unwrap();
+        assert_eq!(contents(root), ["question", "later"]);
+        let forked = manager.branch_transcript(&session.id, &branch.id).await.unwrap();
+        assert_eq!(contents(forked), ["question", "edited"]);
+
+        let loaded = manager.get_session(&session.id).await.unwrap().unwrap();
+        assert_eq!(loaded.message_count, 3);
+        assert!(
+            manager
+                .append_messages(&session.id, Some(Uuid::new_v4()), &[question])
+                .await
+                .is_err()
+        );
+    }
 }
@@ -503,7 +503,9 @@ impl SubAgentOrchestrator {
                     context: task.context.clone(),
                     timeout_seconds: task.timeout_seconds,
                     session_id: task.session_id.clone(),
+                    user_id: task.user_id.clone(),
                     created_at: Utc::now(),
+                    tools: task.tools.clone(),
                 };
                 (agent_id.clone(), sub_task)
             })
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::types::Capability;
+use openclaw_ai::types::ToolDefinition;
 use openclaw_core::Message;
 
 /// 任务类型
@@ -106,7 +107,13 @@ pub struct TaskRequest {
     pub preferred_agent: Option<String>,
     pub timeout_seconds: Option<u64>,
     pub session_id: Option<String>,
+    /// 发起任务的用户，用于限定对话记忆的命名空间
+    #[serde(default)]
+    pub user_id: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// 调用方提供的工具定义，随请求一起交给模型
+    #[serde(default)]
+    pub tools: Vec<ToolDefinition>,
 }
 
 impl TaskRequest {
@@ -121,7 +128,9 @@ impl TaskRequest {
             preferred_agent: None,
             timeout_seconds: None,
             session_id: None,
+            user_id: None,
             created_at: Utc::now(),
+            tools: Vec::new(),
         }
     }
 
@@ -140,6 +149,11 @@ impl TaskRequest {
         self
     }
 
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
     pub fn with_preferred_agent(mut self, agent_id: impl Into<String>) -> Self {
         self.preferred_agent = Some(agent_id.into());
         self
@@ -150,6 +164,11 @@ impl TaskRequest {
         self
     }
 
+    pub fn with_tools(mut self, tools: Vec<ToolDefinition>) -> Self {
+        self.tools = tools;
+        self
+    }
+
     /// 从用户消息创建对话任务
     pub fn from_message(message: Message) -> Self {
         let input = TaskInput::Message {
@@ -0,0 +1,874 @@
+//! Agent 工具循环
+//!
+//! 让模型在多轮对话中自主调用工具：
+//! - 汇总 Agent 可用的工具（ToolPort、ToolRegistry、已启用技能的工具、MCP 工具、设备工具）
+//! - 模型请求的工具调用经安全检查后并行执行，结果以 `ToolResult` 追加回对话
+//! - 调用方声明的工具不能与服务端工具重名，模型请求这类工具时交回调用方执行；
+//!   同一轮的服务端工具调用暂不执行，调用方回传结果后由模型重新发起
+//! - 由 `TurnLimiter` / `TimeoutController` 限制轮数、token 和总时长，模型调用也计入总时长
+//! - 中间步骤通过 [`AgentStep`] 实时推送给调用方
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use openclaw_ai::types::ToolDefinition;
+use openclaw_ai::{AIProvider, ChatRequest, TokenUsage};
+use openclaw_core::{Content, Message, OpenClawError, Result, Role};
+use openclaw_security::{PipelineResult, SecurityPipeline};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::device_tool_registry::DeviceToolRegistry;
+use crate::ports::ToolPort;
+use crate::safety::{LimitReason, TimeoutConfig, TimeoutController, TurnLimitConfig, TurnLimiter};
+
+/// 可被模型调用的工具来源
+#[async_trait]
+pub trait ToolSource: Send + Sync {
+    /// 向模型公布的工具定义
+    async fn definitions(&self) -> Vec<ToolDefinition>;
+
+    /// 执行工具，返回结果 JSON
+    async fn call(&self, name: &str, arguments: serde_json::Value) -> Result<serde_json::Value>;
+}
+
+fn object_schema() -> serde_json::Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+#[async_trait]
+impl ToolSource for openclaw_tools::ToolRegistry {
+    async fn definitions(&self) -> Vec<ToolDefinition> {
+        self.list_tools()
+            .into_iter()
+            .filter_map(|name| {
+                self.get(&name).map(|tool| ToolDefinition {
+                    description: tool.description().to_string(),
+                    name,
+                    parameters: object_schema(),
+                })
+            })
+            .collect()
+    }
+
+    async fn call(&self, name: &str, arguments: serde_json::Value) -> Result<serde_json::Value> {
+        self.execute(name, arguments).await
+    }
+}
+
+#[async_trait]
+impl ToolSource for DeviceToolRegistry {
+    async fn definitions(&self) -> Vec<ToolDefinition> {
+        self.list_tools()
+            .into_iter()
+            .map(|info| ToolDefinition {
+                name: info.name,
+                description: info.description,
+                parameters: object_schema(),
+            })
+            .collect()
+    }
+
+    async fn call(&self, name: &str, arguments: serde_json::Value) -> Result<serde_json::Value> {
+        let result = self.execute(name, arguments).await;
+        if result.success {
+            Ok(result.data.unwrap_or(serde_json::Value::Null))
+        } else {
+            Err(OpenClawError::Tool(result.error.unwrap_or_else(|| {
+                format!("Device tool '{}' failed", name)
+            })))
+        }
+    }
+}
+
+/// 只公布已启用技能对应的工具；`SkillRegistry` 本身只有元数据，需经 `SkillToolBridge` 绑定工具
+#[async_trait]
+impl ToolSource for openclaw_tools::SkillToolBridge {
+    async fn definitions(&self) -> Vec<ToolDefinition> {
+        let enabled = self.get_enabled_tools().await;
+        let registry = self.get_tool_registry().await;
+        let registry = registry.read().await;
+        enabled
+            .into_iter()
+            .filter_map(|name| {
+                registry.get(&name).map(|tool| ToolDefinition {
+                    description: tool.description().to_string(),
+                    name,
+                    parameters: object_schema(),
+                })
+            })
+            .collect()
+    }
+
+    async fn call(&self, name: &str, arguments: serde_json::Value) -> Result<serde_json::Value> {
+        if !self.get_enabled_tools().await.iter().any(|t| t == name) {
+            return Err(OpenClawError::Tool(format!(
+                "Skill tool '{}' is not enabled",
+                name
+            )));
+        }
+        let registry = self.get_tool_registry().await;
+        let registry = registry.read().await;
+        registry.execute(name, arguments).await
+    }
+}
+
+/// 已连接 MCP 服务器上的工具；多个服务器同名时按服务器名排序取第一个
+#[async_trait]
+impl ToolSource for openclaw_tools::McpClient {
+    async fn definitions(&self) -> Vec<ToolDefinition> {
+        mcp_tools(self)
+            .await
+            .into_iter()
+            .map(|(_, tool)| ToolDefinition {
+                name: tool.name,
+                description: tool.description,
+                parameters: tool.input_schema,
+            })
+            .collect()
+    }
+
+    async fn call(&self, name: &str, arguments: serde_json::Value) -> Result<serde_json::Value> {
+        let server = mcp_tools(self)
+            .await
+            .into_iter()
+            .find(|(_, tool)| tool.name == name)
+            .map(|(server, _)| server)
+            .ok_or_else(|| OpenClawError::Tool(format!("MCP tool '{}' not found", name)))?;
+        self.call_tool(&server, name, arguments)
+            .await
+            .map_err(|e| OpenClawError::Tool(e.to_string()))
+    }
+}
+
+/// 按服务器名排序并去重后的 `(服务器, 工具)` 列表
+async fn mcp_tools(client: &openclaw_tools::McpClient) -> Vec<(String, openclaw_tools::McpTool)> {
+    let mut servers: Vec<_> = client.get_all_tools().await.into_iter().collect();
+    servers.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut seen = std::collections::HashSet::new();
+    servers
+        .into_iter()
+        .flat_map(|(server, tools)| tools.into_iter().map(move |tool| (server.clone(), tool)))
+        .filter(|(_, tool)| seen.insert(tool.name.clone()))
+        .collect()
+}
+
+/// 通过 [`ToolPort`] 执行的工具，可选择在沙箱中运行
+pub struct PortToolSource {
+    port: Arc<dyn ToolPort>,
+    sandbox: bool,
+}
+
+impl PortToolSource {
+    pub fn new(port: Arc<dyn ToolPort>, sandbox: bool) -> Self {
+        Self { port, sandbox }
+    }
+}
+
+#[async_trait]
+impl ToolSource for PortToolSource {
+    async fn definitions(&self) -> Vec<ToolDefinition> {
+        match self.port.list_tools().await {
+            Ok(tools) => tools
+                .into_iter()
+                .map(|info| ToolDefinition {
+                    name: info.name,
+                    description: info.description,
+                    parameters: info.parameters,
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to list tools from tool port: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn call(&self, name: &str, arguments: serde_json::Value) -> Result<serde_json::Value> {
+        self.port
+            .execute_with_sandbox(name, arguments, self.sandbox)
+            .await
+    }
+}
+
+/// Agent 可用的工具集合，按名称分派到各来源
+#[derive(Default)]
+pub struct AgentToolSet {
+    definitions: Vec<ToolDefinition>,
+    routes: HashMap<String, Arc<dyn ToolSource>>,
+}
+
+impl AgentToolSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加入一个工具来源，同名工具以先加入的来源为准
+    pub async fn add_source(&mut self, source: Arc<dyn ToolSource>) {
+        for definition in source.definitions().await {
+            if self.routes.contains_key(&definition.name) {
+                tracing::debug!("Duplicate tool '{}' ignored", definition.name);
+                continue;
+            }
+            self.routes.insert(definition.name.clone(), source.clone());
+            self.definitions.push(definition);
+        }
+    }
+
+    pub fn definitions(&self) -> &[ToolDefinition] {
+        &self.definitions
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.routes.contains_key(name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    pub async fn call(
+        &self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        match self.routes.get(name) {
+            Some(source) => source.call(name, arguments).await,
+            None => Err(OpenClawError::Tool(format!("Tool not found: {}", name))),
+        }
+    }
+}
+
+/// 工具循环的中间步骤
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentStep {
+    /// 模型在调用工具前给出的文字
+    Thought { text: String },
+    /// 模型请求调用工具
+    ToolCall {
+        id: String,
+        name: String,
+        arguments: serde_json::Value,
+    },
+    /// 工具执行结果
+    ToolResult {
+        id: String,
+        name: String,
+        output: String,
+        is_error: bool,
+    },
+}
+
+pub type AgentStepSender = mpsc::UnboundedSender<AgentStep>;
+
+/// 工具循环配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolLoopConfig {
+    /// 最多调用模型的轮数
+    pub max_steps: u64,
+    /// 整个循环的 token 上限
+    pub max_total_tokens: u64,
+    /// 整个循环的总时长上限 (毫秒)
+    pub total_timeout_ms: u64,
+    /// 单次工具调用超时 (毫秒)
+    pub tool_timeout_ms: u64,
+    /// 同一轮最多并行执行的工具调用数
+    pub max_parallel_calls: usize,
+    /// ToolPort 工具是否在沙箱中执行
+    pub sandbox: bool,
+}
+
+impl Default for ToolLoopConfig {
+    fn default() -> Self {
+        Self {
+            max_steps: 10,
+            max_total_tokens: 200_000,
+            total_timeout_ms: 300_000,
+            tool_timeout_ms: 60_000,
+            max_parallel_calls: 8,
+            sandbox: true,
+        }
+    }
+}
+
+/// 工具循环结束的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolLoopStop {
+    /// 模型给出了最终回答
+    FinalAnswer,
+    /// 模型请求了调用方声明的工具，只把调用方的工具交回，同一轮的服务端工具不执行
+    ExternalToolCalls,
+    /// 达到轮数或 token 上限
+    Limit(LimitReason),
+    /// 超过总时长
+    Timeout,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolLoopOutcome {
+    /// 最后一条模型消息
+    pub message: Message,
+    pub stop: ToolLoopStop,
+    /// 调用模型的轮数
+    pub steps: u64,
+    /// 各轮累计的 token 用量
+    pub usage: TokenUsage,
+}
+
+/// 多轮工具调用循环
+pub struct ToolLoop<'a> {
+    provider: Arc<dyn AIProvider>,
+    tools: &'a AgentToolSet,
+    config: ToolLoopConfig,
+    security: Option<(Arc<SecurityPipeline>, String)>,
+    steps: Option<AgentStepSender>,
+}
+
+impl<'a> ToolLoop<'a> {
+    pub fn new(
+        provider: Arc<dyn AIProvider>,
+        tools: &'a AgentToolSet,
+        config: ToolLoopConfig,
+    ) -> Self {
+        Self {
+            provider,
+            tools,
+            config,
+            security: None,
+            steps: None,
+        }
+    }
+
+    /// 工具参数做输入检查，工具输出做脱敏
+    pub fn with_security(
+        mut self,
+        pipeline: Arc<SecurityPipeline>,
+        session_id: impl Into<String>,
+    ) -> Self {
+        self.security = Some((pipeline, session_id.into()));
+        self
+    }
+
+    /// 推送中间步骤
+    pub fn with_steps(mut self, steps: AgentStepSender) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    pub async fn run(&self, mut request: ChatRequest) -> Result<ToolLoopOutcome> {
+        // 重名的调用方工具会被路由到服务端执行，直接拒绝
+        if let Some(tool) = request.tools.iter().find(|t| self.tools.contains(&t.name)) {
+            return Err(OpenClawError::Tool(format!(
+                "Tool '{}' conflicts with a built-in tool",
+                tool.name
+            )));
+        }
+        request
+            .tools
+            .extend(self.tools.definitions().iter().cloned());
+
+        let limiter = TurnLimiter::new(TurnLimitConfig {
+            max_turns: self.config.max_steps,
+            max_tokens_per_turn: self.config.max_total_tokens,
+            max_total_tokens: self.config.max_total_tokens,
+            ..Default::default()
+        });
+        let timeout = TimeoutController::new(TimeoutConfig {
+            operation_timeout_ms: self.config.tool_timeout_ms,
+            total_timeout_ms: self.config.total_timeout_ms,
+            idle_timeout_ms: self.config.total_timeout_ms,
+            warn_threshold_ms: self.config.total_timeout_ms,
+        });
+        timeout.start().await;
+
+        let mut usage = TokenUsage::new(0, 0);
+        let mut last = Message::assistant("");
+
+        let stop = loop {
+            if let Err(reason) = limiter.check() {
+                break ToolLoopStop::Limit(reason);
+            }
+            if timeout.check_timeout().await {
+                break ToolLoopStop::Timeout;
+            }
+
+            let remaining = Duration::from_millis(timeout.remaining_ms().await);
+            let response =
+                match tokio::time::timeout(remaining, self.provider.chat(request.clone())).await {
+                    Ok(response) => response?,
+                    Err(_) => break ToolLoopStop::Timeout,
+                };
+            timeout.record_activity().await;
+            usage.prompt_tokens += response.usage.prompt_tokens;
+            usage.completion_tokens += response.usage.completion_tokens;
+            usage.total_tokens += response.usage.total_tokens;
+
+            let calls: Vec<(String, String, serde_json::Value)> = response
+                .message
+                .content
+                .iter()
+                .filter_map(|c| match c {
+                    Content::ToolCall {
+                        id,
+                        name,
+                        arguments,
+                    } => Some((id.clone(), name.clone(), arguments.clone())),
+                    _ => None,
+                })
+                .collect();
+            last = response.message;
+
+            // 上下文逐轮增长，token 突增不作为终止条件
+            match limiter.increment_turn(response.usage.total_tokens as u64) {
+                Ok(()) | Err(LimitReason::TokenBurstDetected) => {}
+                Err(reason) => break ToolLoopStop::Limit(reason),
+            }
+
+            if calls.is_empty() {
+                break ToolLoopStop::FinalAnswer;
+            }
+
+            if let Some(text) = last.text_content()
+                && !text.trim().is_empty()
+            {
+                self.emit(AgentStep::Thought {
+                    text: text.to_string(),
+                });
+            }
+
+            // 调用方只回传自己工具的结果，这一轮的服务端工具执行了结果也会丢失，
+            // 因此不执行也不暴露给调用方，调用方回传结果后由模型重新发起
+            if calls.iter().any(|(_, name, _)| !self.tools.contains(name)) {
+                last.content.retain(|c| match c {
+                    Content::ToolCall { name, .. } => !self.tools.contains(name),
+                    _ => true,
+                });
+                break ToolLoopStop::ExternalToolCalls;
+            }
+            let results = self.execute_calls(&timeout, calls).await;
+
+            request.messages.push(last.clone());
+            request.messages.push(Message::new(Role::Tool, results));
+        };
+
+        timeout.stop().await;
+
+        Ok(ToolLoopOutcome {
+            message: last,
+            stop,
+            steps: limiter.current_turn(),
+            usage,
+        })
+    }
+
+    async fn execute_calls(
+        &self,
+        timeout: &TimeoutController,
+        calls: Vec<(String, String, serde_json::Value)>,
+    ) -> Vec<Content> {
+        let pending: Vec<_> = calls
+            .into_iter()
+            .map(|(id, name, arguments)| self.execute_call(timeout, id, name, arguments))
+            .collect();
+        futures::stream::iter(pending)
+            .buffered(self.config.max_parallel_calls.max(1))
+            .collect()
+            .await
+    }
+
+    async fn execute_call(
+        &self,
+        timeout: &TimeoutController,
+        id: String,
+        name: String,
+        arguments: serde_json::Value,
+    ) -> Content {
+        self.emit(AgentStep::ToolCall {
+            id: id.clone(),
+            name: name.clone(),
+            arguments: arguments.clone(),
+        });
+
+        let result = match self.check_arguments(&name, &arguments).await {
+            Ok(()) => {
+                match tokio::time::timeout(
+                    timeout.operation_timeout(),
+                    self.tools.call(&name, arguments),
+                )
+                .await
+                {
+                    Ok(result) => result,
+                    Err(_) => Err(OpenClawError::Tool(format!("Tool '{}' timed out", name))),
+                }
+            }
+            Err(e) => Err(e),
+        };
+        timeout.record_activity().await;
+
+        // 失败也作为结果交给模型，由模型决定如何继续
+        let (output, is_error) = match result {
+            Ok(serde_json::Value::String(text)) => (text, false),
+            Ok(value) => (value.to_string(), false),
+            Err(e) => (format!("Error: {}", e), true),
+        };
+        let output = self.redact(output).await;
+
+        self.emit(AgentStep::ToolResult {
+            id: id.clone(),
+            name,
+            output: output.clone(),
+            is_error,
+        });
+
+        Content::ToolResult {
+            id,
+            content: output,
+        }
+    }
+
+    async fn check_arguments(&self, name: &str, arguments: &serde_json::Value) -> Result<()> {
+        let Some((pipeline, session_id)) = &self.security else {
+            return Ok(());
+        };
+
+        let input = format!("{}: {}", name, arguments);
+        match pipeline.check_input(session_id, &input).await.0 {
+            PipelineResult::Block(reason) => Err(OpenClawError::Tool(format!(
+                "Tool call blocked by security: {}",
+                reason
+            ))),
+            PipelineResult::Warn(warning) => {
+                tracing::warn!("Security warning for tool '{}': {}", name, warning);
+                Ok(())
+            }
+            PipelineResult::Allow => Ok(()),
+        }
+    }
+
+    async fn redact(&self, output: String) -> String {
+        match &self.security {
+            Some((pipeline, session_id)) => pipeline.validate_output(session_id, &output).await.0,
+            None => output,
+        }
+    }
+
+    fn emit(&self, step: AgentStep) {
+        if let Some(steps) = &self.steps {
+            let _ = steps.send(step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::Stream;
+    use openclaw_ai::{
+        ChatResponse, EmbeddingRequest, EmbeddingResponse, FinishReason, StreamChunk,
+    };
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::sync::Mutex;
+
+    /// 按顺序返回预设消息，用尽后重复最后一条
+    struct ScriptedProvider {
+        replies: Mutex<VecDeque<Message>>,
+        requests: Mutex<Vec<ChatRequest>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(replies: Vec<Message>) -> Self {
+            Self {
+                replies: Mutex::new(replies.into()),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AIProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+            self.requests.lock().unwrap().push(request);
+            let mut replies = self.replies.lock().unwrap();
+            let message = if replies.len() > 1 {
+                replies.pop_front().unwrap()
+            } else {
+                replies.front().cloned().unwrap()
+            };
+            Ok(ChatResponse {
+                id: "scripted".to_string(),
+                model: "scripted".to_string(),
+                message,
+                usage: TokenUsage::new(10, 5),
+                finish_reason: FinishReason::Stop,
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: ChatRequest,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+            Err(OpenClawError::AIProvider("not supported".to_string()))
+        }
+
+        async fn embed(&self, _request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+            Err(OpenClawError::AIProvider("not supported".to_string()))
+        }
+
+        async fn models(&self) -> Result<Vec<String>> {
+            Ok(vec!["scripted".to_string()])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl openclaw_tools::Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the arguments"
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> Result<serde_json::Value> {
+            Ok(args)
+        }
+    }
+
+    fn tool_call(id: &str, name: &str) -> Message {
+        Message::new(
+            Role::Assistant,
+            vec![Content::ToolCall {
+                id: id.to_string(),
+                name: name.to_string(),
+                arguments: serde_json::json!({ "value": id }),
+            }],
+        )
+    }
+
+    async fn echo_tools() -> AgentToolSet {
+        let mut registry = openclaw_tools::ToolRegistry::new();
+        registry.register("echo".to_string(), Arc::new(EchoTool));
+        let mut tools = AgentToolSet::new();
+        tools.add_source(Arc::new(registry)).await;
+        tools
+    }
+
+    #[tokio::test]
+    async fn test_runs_tools_until_final_answer() {
+        let mut parallel = tool_call("a", "echo");
+        parallel.content.extend(tool_call("b", "echo").content);
+        let provider = Arc::new(ScriptedProvider::new(vec![
+            parallel,
+            Message::assistant("done"),
+        ]));
+        let tools = echo_tools().await;
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let outcome = ToolLoop::new(provider.clone(), &tools, ToolLoopConfig::default())
+            .with_steps(tx)
+            .run(ChatRequest::new("test", vec![Message::user("hi")]))
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.stop, ToolLoopStop::FinalAnswer);
+        assert_eq!(outcome.message.text_content(), Some("done"));
+        assert_eq!(outcome.steps, 2);
+        assert_eq!(outcome.usage.total_tokens, 30);
+
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests[0].tools.len(), 1);
+        let tool_message = requests[1].messages.last().unwrap();
+        assert_eq!(tool_message.role, Role::Tool);
+        assert_eq!(tool_message.content.len(), 2);
+
+        let mut results = 0;
+        while let Ok(step) = rx.try_recv() {
+            if let AgentStep::ToolResult { is_error, .. } = step {
+                assert!(!is_error);
+                results += 1;
+            }
+        }
+        assert_eq!(results, 2);
+    }
+
+    #[tokio::test]
+    async fn test_skill_tools_follow_skill_enabled_state() {
+        let bridge = openclaw_tools::SkillToolBridge::new();
+        bridge
+            .register_skill_tool("builtin.file_ops", Arc::new(EchoTool))
+            .await
+            .unwrap();
+
+        let definitions = bridge.definitions().await;
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].name, "echo");
+        let value = bridge
+            .call("echo", serde_json::json!({ "v": 1 }))
+            .await
+            .unwrap();
+        assert_eq!(value["v"], 1);
+
+        bridge.disable_skill("builtin.file_ops").await.unwrap();
+        assert!(bridge.definitions().await.is_empty());
+        assert!(bridge.call("echo", serde_json::json!({})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tool_is_returned_to_caller() {
+        let provider = Arc::new(ScriptedProvider::new(vec![tool_call("a", "client_tool")]));
+        let tools = echo_tools().await;
+
+        let outcome = ToolLoop::new(provider, &tools, ToolLoopConfig::default())
+            .run(ChatRequest::new("test", vec![Message::user("hi")]))
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.stop, ToolLoopStop::ExternalToolCalls);
+        assert!(matches!(
+            outcome.message.content[0],
+            Content::ToolCall { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mixed_turn_defers_internal_calls_and_returns_external_ones() {
+        let mut mixed = tool_call("a", "echo");
+        mixed.content.extend(tool_call("b", "client_tool").content);
+        let provider = Arc::new(ScriptedProvider::new(vec![mixed]));
+        let tools = echo_tools().await;
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let outcome = ToolLoop::new(provider, &tools, ToolLoopConfig::default())
+            .with_steps(tx)
+            .run(ChatRequest::new("test", vec![Message::user("hi")]))
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.stop, ToolLoopStop::ExternalToolCalls);
+        let names: Vec<&str> = outcome
+            .message
+            .content
+            .iter()
+            .filter_map(|c| match c {
+                Content::ToolCall { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, vec!["client_tool"]);
+
+        // 服务端工具没有执行，也没有结果被丢弃
+        while let Ok(step) = rx.try_recv() {
+            assert!(!matches!(
+                step,
+                AgentStep::ToolCall { .. } | AgentStep::ToolResult { .. }
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_caller_tool_colliding_with_server_tool_is_rejected() {
+        let provider = Arc::new(ScriptedProvider::new(vec![Message::assistant("done")]));
+        let tools = echo_tools().await;
+        let mut request = ChatRequest::new("test", vec![Message::user("hi")]);
+        request.tools.push(ToolDefinition {
+            name: "echo".to_string(),
+            description: "client echo".to_string(),
+            parameters: object_schema(),
+        });
+
+        let result = ToolLoop::new(provider.clone(), &tools, ToolLoopConfig::default())
+            .run(request)
+            .await;
+
+        assert!(result.is_err());
+        assert!(provider.requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_provider_call_is_bounded_by_total_timeout() {
+        struct SlowProvider(ScriptedProvider);
+
+        #[async_trait]
+        impl AIProvider for SlowProvider {
+            fn name(&self) -> &str {
+                "slow"
+            }
+
+            async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                self.0.chat(request).await
+            }
+
+            async fn chat_stream(
+                &self,
+                request: ChatRequest,
+            ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+                self.0.chat_stream(request).await
+            }
+
+            async fn embed(&self, request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+                self.0.embed(request).await
+            }
+
+            async fn models(&self) -> Result<Vec<String>> {
+                self.0.models().await
+            }
+
+            async fn health_check(&self) -> Result<bool> {
+                Ok(true)
+            }
+        }
+
+        let provider = Arc::new(SlowProvider(ScriptedProvider::new(vec![
+            Message::assistant("late"),
+        ])));
+        let tools = echo_tools().await;
+        let config = ToolLoopConfig {
+            total_timeout_ms: 50,
+            ..Default::default()
+        };
+
+        let outcome = ToolLoop::new(provider, &tools, config)
+            .run(ChatRequest::new("test", vec![Message::user("hi")]))
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.stop, ToolLoopStop::Timeout);
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_max_steps() {
+        let provider = Arc::new(ScriptedProvider::new(vec![tool_call("a", "echo")]));
+        let tools = echo_tools().await;
+        let config = ToolLoopConfig {
+            max_steps: 3,
+            ..Default::default()
+        };
+
+        let outcome = ToolLoop::new(provider, &tools, config)
+            .run(ChatRequest::new("test", vec![Message::user("hi")]))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outcome.stop,
+            ToolLoopStop::Limit(LimitReason::MaxTurnsReached)
+        );
+        assert_eq!(outcome.steps, 3);
+    }
+}
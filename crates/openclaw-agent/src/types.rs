@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::aieos::AIEOS;
 use crate::context::ContextEngineConfig;
+use crate::memory::ConversationMemoryConfig;
 
 /// Agent ID 类型
 pub type AgentId = String;
@@ -168,6 +169,9 @@ pub struct AgentConfig {
     /// Session 存储路径 (SQLite)
     #[serde(default)]
     pub session_storage_path: Option<String>,
+    /// 对话记忆的召回与写入配置
+    #[serde(default)]
+    pub memory: ConversationMemoryConfig,
 }
 
 impl AgentConfig {
@@ -186,6 +190,7 @@ impl AgentConfig {
             enabled: true,
             context_engine_config: None,
             session_storage_path: None,
+            memory: ConversationMemoryConfig::default(),
         }
     }
 
@@ -224,6 +229,11 @@ impl AgentConfig {
         self
     }
 
+    pub fn with_memory(mut self, memory: ConversationMemoryConfig) -> Self {
+        self.memory = memory;
+        self
+    }
+
     pub fn load_context_engine_from_yaml(&mut self, yaml_content: &str) -> crate::Result<()> {
         let config: ContextEngineConfig = serde_yaml::from_str(yaml_content)
             .map_err(|e| crate::OpenClawError::Config(e.to_string()))?;
@@ -148,16 +148,16 @@ impl ProviderFactory {
     ) -> Result<Arc<dyn AIProvider>, String> {
         use super::*;
 
-        match provider_type {
-            ProviderType::OpenAI => Ok(Arc::new(OpenAIProvider::new(config))),
-            ProviderType::Anthropic => Ok(Arc::new(AnthropicProvider::new(config))),
-            ProviderType::Gemini => Ok(Arc::new(GeminiProvider::new(config))),
-            ProviderType::DeepSeek => Ok(Arc::new(DeepSeekProvider::new(config))),
-            ProviderType::Qwen => Ok(Arc::new(QwenProvider::new(config))),
-            ProviderType::Doubao => Ok(Arc::new(DoubaoProvider::new(config))),
-            ProviderType::Glm => Ok(Arc::new(GlmProvider::new(config))),
-            ProviderType::Minimax => Ok(Arc::new(MinimaxProvider::new(config))),
-            ProviderType::Kimi => Ok(Arc::new(KimiProvider::new(config))),
+        let provider: Arc<dyn AIProvider> = match provider_type {
+            ProviderType::OpenAI => Arc::new(OpenAIProvider::new(config)),
+            ProviderType::Anthropic => Arc::new(AnthropicProvider::new(config)),
+            ProviderType::Gemini => Arc::new(GeminiProvider::new(config)),
+            ProviderType::DeepSeek => Arc::new(DeepSeekProvider::new(config)),
+            ProviderType::Qwen => Arc::new(QwenProvider::new(config)),
+            ProviderType::Doubao => Arc::new(DoubaoProvider::new(config)),
+            ProviderType::Glm => Arc::new(GlmProvider::new(config)),
+            ProviderType::Minimax => Arc::new(MinimaxProvider::new(config)),
+            ProviderType::Kimi => Arc::new(KimiProvider::new(config)),
             ProviderType::OpenRouter => {
                 let info = openai_compatible::ProviderInfo {
                     name: "openrouter",
@@ -170,9 +170,9 @@ impl ProviderFactory {
                         "mistralai/mistral-7b-instruct",
                     ],
                 };
-                Ok(Arc::new(OpenAICompatibleProvider::new(config, info)))
+                Arc::new(OpenAICompatibleProvider::new(config, info))
             }
-            ProviderType::Ollama => Ok(Arc::new(OllamaProvider::new(config))),
+            ProviderType::Ollama => Arc::new(OllamaProvider::new(config)),
             ProviderType::Custom => {
                 let base_url = config
                     .base_url
@@ -182,9 +182,11 @@ impl ProviderFactory {
                     .api_key
                     .clone()
                     .unwrap_or_else(|| "dummy".to_string());
-                Ok(Arc::new(CustomProvider::new("custom", base_url, api_key)))
+                Arc::new(CustomProvider::new("custom", base_url, api_key))
             }
-        }
+        };
+
+        Ok(InstrumentedProvider::wrap(provider))
     }
 
     /// 从提供商名称字符串创建提供商 (返回 Arc)
@@ -0,0 +1,169 @@
+//! 带指标与追踪的提供商包装
+//!
+//! 为任意 [`AIProvider`] 记录请求数、耗时、错误和 token 用量，
+//! 并为每次调用创建 `llm.*` span，使其挂在当前 Agent 任务的追踪链路下。
+
+use async_trait::async_trait;
+use futures::Stream;
+use openclaw_core::Result;
+use openclaw_core::telemetry::{
+    LLM_REQUEST_DURATION_SECONDS, LLM_REQUESTS_TOTAL, LLM_TOKENS_TOTAL,
+};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::Instrument;
+
+use super::AIProvider;
+use crate::types::{
+    ChatRequest, ChatResponse, EmbeddingRequest, EmbeddingResponse, StreamChunk, TokenUsage,
+};
+
+/// 指标包装器，由 [`ProviderFactory`](super::ProviderFactory) 自动套在创建的提供商外层
+pub struct InstrumentedProvider {
+    inner: Arc<dyn AIProvider>,
+}
+
+impl InstrumentedProvider {
+    pub fn new(inner: Arc<dyn AIProvider>) -> Self {
+        Self { inner }
+    }
+
+    /// 包装为 `Arc<dyn AIProvider>`
+    pub fn wrap(inner: Arc<dyn AIProvider>) -> Arc<dyn AIProvider> {
+        Arc::new(Self::new(inner))
+    }
+
+    fn record<T>(
+        &self,
+        operation: &'static str,
+        model: &str,
+        started: Instant,
+        result: &Result<T>,
+    ) {
+        let provider = self.inner.name().to_string();
+        let status = if result.is_ok() { "ok" } else { "error" };
+
+        metrics::counter!(
+            LLM_REQUESTS_TOTAL,
+            "provider" => provider.clone(),
+            "model" => model.to_string(),
+            "operation" => operation,
+            "status" => status
+        )
+        .increment(1);
+        metrics::histogram!(
+            LLM_REQUEST_DURATION_SECONDS,
+            "provider" => provider,
+            "model" => model.to_string(),
+            "operation" => operation
+        )
+        .record(started.elapsed().as_secs_f64());
+
+        if let Err(e) = result {
+            tracing::warn!(operation, model, error = %e, "LLM request failed");
+        }
+    }
+
+    fn record_usage(&self, model: &str, usage: &TokenUsage) {
+        let span = tracing::Span::current();
+        span.record("prompt_tokens", usage.prompt_tokens);
+        span.record("completion_tokens", usage.completion_tokens);
+
+        for (kind, tokens) in [
+            ("prompt", usage.prompt_tokens),
+            ("completion", usage.completion_tokens),
+        ] {
+            metrics::counter!(
+                LLM_TOKENS_TOTAL,
+                "provider" => self.inner.name().to_string(),
+                "model" => model.to_string(),
+                "kind" => kind
+            )
+            .increment(tokens as u64);
+        }
+    }
+}
+
+#[async_trait]
+impl AIProvider for InstrumentedProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        let model = request.model.clone();
+        let span = tracing::info_span!(
+            "llm.chat",
+            provider = self.inner.name(),
+            model = %model,
+            messages = request.messages.len(),
+            tools = request.tools.len(),
+            prompt_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty,
+        );
+
+        async {
+            let started = Instant::now();
+            let result = self.inner.chat(request).await;
+            self.record("chat", &model, started, &result);
+            if let Ok(response) = &result {
+                self.record_usage(&model, &response.usage);
+            }
+            result
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+        let model = request.model.clone();
+        let span =
+            tracing::info_span!("llm.chat_stream", provider = self.inner.name(), model = %model);
+
+        // 流式请求只统计建立连接的耗时
+        async {
+            let started = Instant::now();
+            let result = self.inner.chat_stream(request).await;
+            self.record("chat_stream", &model, started, &result);
+            result
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn embed(&self, request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+        let model = request.model.clone();
+        let span = tracing::info_span!(
+            "llm.embed",
+            provider = self.inner.name(),
+            model = %model,
+            inputs = request.input.len(),
+            prompt_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty,
+        );
+
+        async {
+            let started = Instant::now();
+            let result = self.inner.embed(request).await;
+            self.record("embed", &model, started, &result);
+            if let Ok(response) = &result {
+                self.record_usage(&model, &response.usage);
+            }
+            result
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn models(&self) -> Result<Vec<String>> {
+        self.inner.models().await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.inner.health_check().await
+    }
+}
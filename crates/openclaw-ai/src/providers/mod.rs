@@ -13,12 +13,14 @@ mod doubao;
 mod factory;
 mod gemini;
 mod glm;
+mod instrumented;
 mod kimi;
 mod minimax;
 mod ollama;
 mod openai;
 mod openai_compatible;
 mod qwen;
+mod reloadable;
 
 pub use anthropic::*;
 pub use base::*;
@@ -28,12 +30,14 @@ pub use doubao::*;
 pub use factory::*;
 pub use gemini::*;
 pub use glm::*;
+pub use instrumented::*;
 pub use kimi::*;
 pub use minimax::*;
 pub use ollama::*;
 pub use openai::*;
 pub use openai_compatible::*;
 pub use qwen::*;
+pub use reloadable::*;
 
 use async_trait::async_trait;
 use futures::Stream;
@@ -2,10 +2,11 @@
 
 use async_trait::async_trait;
 use futures::{Stream, StreamExt};
-use openclaw_core::{Message, OpenClawError, Result, Role};
+use openclaw_core::{Message, OpenClawError, Result};
 use reqwest::{Response, header};
 use std::pin::Pin;
 
+use super::openai_compatible::{parse_openai_choice, to_openai_messages, to_openai_tools};
 use crate::providers::{AIProvider, ProviderConfig};
 use crate::types::{
     ChatRequest, ChatResponse, EmbeddingRequest, EmbeddingResponse, FinishReason, FunctionDelta,
@@ -32,24 +33,7 @@ impl OpenAIProvider {
     }
 
     fn convert_messages(&self, messages: Vec<Message>) -> Vec<serde_json::Value> {
-        messages
-            .into_iter()
-            .map(|m| {
-                let role = match m.role {
-                    Role::System => "system",
-                    Role::User => "user",
-                    Role::Assistant => "assistant",
-                    Role::Tool => "tool",
-                };
-
-                let content = m.text_content().unwrap_or("").to_string();
-
-                serde_json::json!({
-                    "role": role,
-                    "content": content
-                })
-            })
-            .collect()
+        to_openai_messages(messages)
     }
 }
 
@@ -62,13 +46,16 @@ impl AIProvider for OpenAIProvider {
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
         let url = format!("{}/chat/completions", self.get_base_url());
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "model": request.model,
             "messages": self.convert_messages(request.messages),
             "temperature": request.temperature,
             "max_tokens": request.max_tokens,
             "stream": false
         });
+        if let Some(tools) = to_openai_tools(&request.tools) {
+            body["tools"] = tools;
+        }
 
         let response = self
             .client
@@ -96,25 +83,19 @@ impl AIProvider for OpenAIProvider {
             .map_err(|e| OpenClawError::AIProvider(format!("解析响应失败: {}", e)))?;
 
         // 解析响应
-        let choice = &json["choices"][0];
-        let message_content = choice["message"]["content"]
-            .as_str()
-            .unwrap_or("")
-            .to_string();
+        let (message, finish_reason) = parse_openai_choice(&json["choices"][0]);
 
         let usage = TokenUsage::new(
             json["usage"]["prompt_tokens"].as_u64().unwrap_or(0) as usize,
             json["usage"]["completion_tokens"].as_u64().unwrap_or(0) as usize,
         );
 
-        let message = Message::assistant(&message_content);
-
         Ok(ChatResponse {
             id: json["id"].as_str().unwrap_or("").to_string(),
             model: json["model"].as_str().unwrap_or("").to_string(),
             message,
             usage,
-            finish_reason: FinishReason::Stop,
+            finish_reason,
         })
     }
 
@@ -124,13 +105,16 @@ impl AIProvider for OpenAIProvider {
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
         let url = format!("{}/chat/completions", self.get_base_url());
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "model": request.model,
             "messages": self.convert_messages(request.messages),
             "temperature": request.temperature,
             "max_tokens": request.max_tokens,
             "stream": true
         });
+        if let Some(tools) = to_openai_tools(&request.tools) {
+            body["tools"] = tools;
+        }
 
         let response = self
             .client
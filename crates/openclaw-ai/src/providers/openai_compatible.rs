@@ -10,16 +10,135 @@
 
 use async_trait::async_trait;
 use futures::{Stream, StreamExt};
-use openclaw_core::{Message, OpenClawError, Result, Role};
+use openclaw_core::{Content, Message, OpenClawError, Result, Role};
 use reqwest::{Response, header};
 use std::pin::Pin;
 
 use crate::providers::{AIProvider, ProviderConfig};
 use crate::types::{
     ChatRequest, ChatResponse, EmbeddingRequest, EmbeddingResponse, FinishReason, FunctionDelta,
-    StreamChunk, StreamDelta, TokenUsage, ToolCallDelta,
+    StreamChunk, StreamDelta, TokenUsage, ToolCallDelta, ToolDefinition,
 };
 
+/// 转换为 OpenAI 消息格式（含工具调用与工具结果）
+pub(crate) fn to_openai_messages(messages: Vec<Message>) -> Vec<serde_json::Value> {
+    let mut converted = Vec::with_capacity(messages.len());
+
+    for m in messages {
+        let content = m.text_content().unwrap_or("").to_string();
+
+        match m.role {
+            Role::Tool => {
+                // 每个工具结果单独成为一条 tool 消息
+                for c in &m.content {
+                    if let Content::ToolResult { id, content } = c {
+                        converted.push(serde_json::json!({
+                            "role": "tool",
+                            "tool_call_id": id,
+                            "content": content
+                        }));
+                    }
+                }
+            }
+            Role::Assistant => {
+                let tool_calls: Vec<serde_json::Value> = m
+                    .content
+                    .iter()
+                    .filter_map(|c| match c {
+                        Content::ToolCall {
+                            id,
+                            name,
+                            arguments,
+                        } => Some(serde_json::json!({
+                            "id": id,
+                            "type": "function",
+                            "function": {
+                                "name": name,
+                                "arguments": arguments.to_string()
+                            }
+                        })),
+                        _ => None,
+                    })
+                    .collect();
+
+                if tool_calls.is_empty() {
+                    converted.push(serde_json::json!({
+                        "role": "assistant",
+                        "content": content
+                    }));
+                } else {
+                    converted.push(serde_json::json!({
+                        "role": "assistant",
+                        "content": if content.is_empty() { serde_json::Value::Null } else { content.into() },
+                        "tool_calls": tool_calls
+                    }));
+                }
+            }
+            Role::System | Role::User => {
+                let role = match m.role {
+                    Role::System => "system",
+                    _ => "user",
+                };
+                converted.push(serde_json::json!({
+                    "role": role,
+                    "content": content
+                }));
+            }
+        }
+    }
+
+    converted
+}
+
+/// 转换为 OpenAI tools 参数，没有工具时返回 None
+pub(crate) fn to_openai_tools(tools: &[ToolDefinition]) -> Option<serde_json::Value> {
+    if tools.is_empty() {
+        return None;
+    }
+    Some(
+        tools
+            .iter()
+            .map(|t| {
+                serde_json::json!({
+                    "type": "function",
+                    "function": {
+                        "name": t.name,
+                        "description": t.description,
+                        "parameters": t.parameters
+                    }
+                })
+            })
+            .collect(),
+    )
+}
+
+/// 解析响应中的 choice，返回助手消息与完成原因
+pub(crate) fn parse_openai_choice(choice: &serde_json::Value) -> (Message, FinishReason) {
+    let text = choice["message"]["content"].as_str().unwrap_or("");
+    let mut message = Message::assistant(text);
+
+    if let Some(calls) = choice["message"]["tool_calls"].as_array() {
+        for call in calls {
+            let raw = call["function"]["arguments"].as_str().unwrap_or("{}");
+            message.content.push(Content::ToolCall {
+                id: call["id"].as_str().unwrap_or("").to_string(),
+                name: call["function"]["name"].as_str().unwrap_or("").to_string(),
+                arguments: serde_json::from_str(raw)
+                    .unwrap_or_else(|_| serde_json::Value::String(raw.to_string())),
+            });
+        }
+    }
+
+    let finish_reason = match choice["finish_reason"].as_str() {
+        Some("length") => FinishReason::Length,
+        Some("tool_calls") | Some("function_call") => FinishReason::ToolCalls,
+        Some("content_filter") => FinishReason::ContentFilter,
+        _ => FinishReason::Stop,
+    };
+
+    (message, finish_reason)
+}
+
 /// OpenAI 兼容提供商配置
 pub struct ProviderInfo {
     pub name: &'static str,
@@ -60,24 +179,7 @@ impl OpenAICompatibleProvider {
 
     /// 转换消息格式
     fn convert_messages(&self, messages: Vec<Message>) -> Vec<serde_json::Value> {
-        messages
-            .into_iter()
-            .map(|m| {
-                let role = match m.role {
-                    Role::System => "system",
-                    Role::User => "user",
-                    Role::Assistant => "assistant",
-                    Role::Tool => "tool",
-                };
-
-                let content = m.text_content().unwrap_or("").to_string();
-
-                serde_json::json!({
-                    "role": role,
-                    "content": content
-                })
-            })
-            .collect()
+        to_openai_messages(messages)
     }
 
     /// 解析 SSE 流
@@ -211,12 +313,15 @@ impl AIProvider for OpenAICompatibleProvider {
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
         let url = format!("{}/chat/completions", self.get_base_url());
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "model": request.model,
             "messages": self.convert_messages(request.messages),
             "temperature": request.temperature,
             "max_tokens": request.max_tokens,
         });
+        if let Some(tools) = to_openai_tools(&request.tools) {
+            body["tools"] = tools;
+        }
 
         let response = self
             .client
@@ -247,25 +352,19 @@ impl AIProvider for OpenAICompatibleProvider {
             .map_err(|e| OpenClawError::AIProvider(format!("解析响应失败: {}", e)))?;
 
         // 解析响应
-        let choice = &json["choices"][0];
-        let message_content = choice["message"]["content"]
-            .as_str()
-            .unwrap_or("")
-            .to_string();
+        let (message, finish_reason) = parse_openai_choice(&json["choices"][0]);
 
         let usage = TokenUsage::new(
             json["usage"]["prompt_tokens"].as_u64().unwrap_or(0) as usize,
             json["usage"]["completion_tokens"].as_u64().unwrap_or(0) as usize,
         );
 
-        let message = Message::assistant(&message_content);
-
         Ok(ChatResponse {
             id: json["id"].as_str().unwrap_or("").to_string(),
             model: json["model"].as_str().unwrap_or("").to_string(),
             message,
             usage,
-            finish_reason: FinishReason::Stop,
+            finish_reason,
         })
     }
 
@@ -275,13 +374,16 @@ impl AIProvider for OpenAICompatibleProvider {
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
         let url = format!("{}/chat/completions", self.get_base_url());
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "model": request.model,
             "messages": self.convert_messages(request.messages),
             "temperature": request.temperature,
             "max_tokens": request.max_tokens,
             "stream": true
         });
+        if let Some(tools) = to_openai_tools(&request.tools) {
+            body["tools"] = tools;
+        }
 
         let response = self
             .client
@@ -393,3 +495,48 @@ impl AIProvider for OpenAICompatibleProvider {
         Ok(self.config.api_key.is_some())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_call_roundtrip() {
+        let mut assistant = Message::assistant("");
+        assistant.content.push(Content::ToolCall {
+            id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            arguments: serde_json::json!({ "city": "Paris" }),
+        });
+        let tool = Message::new(
+            Role::Tool,
+            vec![Content::ToolResult {
+                id: "call_1".to_string(),
+                content: "sunny".to_string(),
+            }],
+        );
+
+        let converted = to_openai_messages(vec![Message::user("weather?"), assistant, tool]);
+        assert_eq!(
+            converted[1]["tool_calls"][0]["function"]["name"],
+            "get_weather"
+        );
+        assert_eq!(
+            converted[1]["tool_calls"][0]["function"]["arguments"],
+            r#"{"city":"Paris"}"#
+        );
+        assert_eq!(converted[2]["role"], "tool");
+        assert_eq!(converted[2]["tool_call_id"], "call_1");
+
+        let choice = serde_json::json!({
+            "message": { "content": null, "tool_calls": converted[1]["tool_calls"] },
+            "finish_reason": "tool_calls"
+        });
+        let (message, finish_reason) = parse_openai_choice(&choice);
+        assert_eq!(finish_reason, FinishReason::ToolCalls);
+        assert!(matches!(
+            &message.content[1],
+            Content::ToolCall { arguments, .. } if arguments["city"] == "Paris"
+        ));
+    }
+}
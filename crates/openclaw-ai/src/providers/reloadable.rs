@@ -0,0 +1,69 @@
+//! 可热替换的提供商
+//!
+//! 配置热加载修改密钥或地址时，网关用新的实例替换内部提供商，
+//! 持有 `Arc<dyn AIProvider>` 的各个服务无需重建。进行中的请求继续使用旧实例。
+
+use async_trait::async_trait;
+use futures::Stream;
+use openclaw_core::Result;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use super::AIProvider;
+use crate::types::{ChatRequest, ChatResponse, EmbeddingRequest, EmbeddingResponse, StreamChunk};
+
+pub struct ReloadableProvider {
+    /// 创建时的名称，替换后不变（`name()` 需要返回借用）
+    name: String,
+    inner: RwLock<Arc<dyn AIProvider>>,
+}
+
+impl ReloadableProvider {
+    pub fn new(inner: Arc<dyn AIProvider>) -> Self {
+        Self {
+            name: inner.name().to_string(),
+            inner: RwLock::new(inner),
+        }
+    }
+
+    /// 替换内部提供商，返回旧实例
+    pub fn swap(&self, provider: Arc<dyn AIProvider>) -> Arc<dyn AIProvider> {
+        let mut inner = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        std::mem::replace(&mut *inner, provider)
+    }
+
+    /// 当前生效的提供商
+    pub fn current(&self) -> Arc<dyn AIProvider> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[async_trait]
+impl AIProvider for ReloadableProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        self.current().chat(request).await
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+        self.current().chat_stream(request).await
+    }
+
+    async fn embed(&self, request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+        self.current().embed(request).await
+    }
+
+    async fn models(&self) -> Result<Vec<String>> {
+        self.current().models().await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.current().health_check().await
+    }
+}
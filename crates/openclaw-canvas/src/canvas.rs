@@ -1,12 +1,17 @@
 //! 画布核心模块
 
+use crate::export::{CanvasExporter, ExportFormat, ExportOptions, ExportOutput};
+use crate::storage::{
+    CanvasSnapshot, CanvasStorage, CanvasStorageConfig, CanvasVersion, MemoryCanvasStorage,
+    PersistMode, create_canvas_storage,
+};
 use crate::types::*;
 use chrono::Utc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use thiserror::Error;
-use tokio::sync::RwLock;
-use tracing::{debug, info};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 /// 画布错误
@@ -27,6 +32,15 @@ pub enum CanvasError {
     #[error("无效操作: {0}")]
     InvalidOperation(String),
 
+    #[error("画布版本不存在: {0} v{1}")]
+    VersionNotFound(CanvasId, u64),
+
+    #[error("图表解析失败: {0}")]
+    InvalidDiagram(String),
+
+    #[error("存储错误: {0}")]
+    Storage(String),
+
     #[error("内部错误: {0}")]
     Internal(#[from] anyhow::Error),
 }
@@ -72,6 +86,12 @@ pub enum AgentAction {
     },
     /// 添加形状
     AddShape { shape: Shape, position: Point },
+    /// 根据 Mermaid / DOT 源码生成图表
+    AddDiagram {
+        source: String,
+        format: Option<crate::diagram::DiagramFormat>,
+        position: Point,
+    },
     /// 撤销操作
     Undo,
     /// 重做操作
@@ -83,18 +103,45 @@ pub enum AgentAction {
 }
 
 /// 画布管理器
+///
+/// 已加载的画布缓存在内存中，首次访问时从存储后端懒加载。
+/// 修改后需调用 [`CanvasManager::commit`] 以按 [`PersistMode`] 持久化。
 pub struct CanvasManager {
     canvases: Arc<RwLock<HashMap<CanvasId, Arc<RwLock<CanvasState>>>>>,
+    storage: Arc<dyn CanvasStorage>,
+    persist_mode: PersistMode,
+    dirty: Arc<RwLock<HashSet<CanvasId>>>,
+    /// 每个画布一把锁，串行化快照版本号的分配
+    snapshot_locks: Arc<Mutex<HashMap<CanvasId, Arc<Mutex<()>>>>>,
 }
 
 impl CanvasManager {
-    /// 创建新的画布管理器
+    /// 创建新的画布管理器（内存存储）
     pub fn new() -> Self {
+        Self::with_storage(Arc::new(MemoryCanvasStorage::new()), PersistMode::Immediate)
+    }
+
+    /// 使用指定存储后端创建画布管理器
+    pub fn with_storage(storage: Arc<dyn CanvasStorage>, persist_mode: PersistMode) -> Self {
         Self {
             canvases: Arc::new(RwLock::new(HashMap::new())),
+            storage,
+            persist_mode,
+            dirty: Arc::new(RwLock::new(HashSet::new())),
+            snapshot_locks: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
+    /// 根据配置创建画布管理器
+    pub fn from_config(config: &CanvasStorageConfig) -> Result<Self, CanvasError> {
+        let storage = create_canvas_storage(config)?;
+        Ok(Self::with_storage(storage, config.persist_mode))
+    }
+
+    pub fn persist_mode(&self) -> PersistMode {
+        self.persist_mode
+    }
+
     /// 创建新画布
     pub async fn create_canvas(&self, name: String, width: f64, height: f64) -> CanvasId {
         let id = Uuid::new_v4().to_string();
@@ -112,6 +159,10 @@ impl CanvasManager {
             updated_at: now,
         };
 
+        if let Err(e) = self.storage.save_canvas(&canvas).await {
+            warn!("保存画布失败 {}: {}", id, e);
+        }
+
         let mut canvases = self.canvases.write().await;
         canvases.insert(id.clone(), Arc::new(RwLock::new(canvas)));
 
@@ -119,10 +170,31 @@ impl CanvasManager {
         id
     }
 
-    /// 获取画布
+    /// 获取画布，未加载时从存储后端懒加载
     pub async fn get_canvas(&self, id: &CanvasId) -> Option<Arc<RwLock<CanvasState>>> {
-        let canvases = self.canvases.read().await;
-        canvases.get(id).cloned()
+        {
+            let canvases = self.canvases.read().await;
+            if let Some(canvas) = canvases.get(id) {
+                return Some(canvas.clone());
+            }
+        }
+
+        let state = match self.storage.load_canvas(id).await {
+            Ok(state) => state?,
+            Err(e) => {
+                warn!("加载画布失败 {}: {}", id, e);
+                return None;
+            }
+        };
+
+        debug!("从存储加载画布: {}", id);
+        let mut canvases = self.canvases.write().await;
+        Some(
+            canvases
+                .entry(id.clone())
+                .or_insert_with(|| Arc::new(RwLock::new(state)))
+                .clone(),
+        )
     }
 
     /// 获取画布状态
@@ -135,10 +207,15 @@ impl CanvasManager {
         Ok(state.clone())
     }
 
-    /// 删除画布
+    /// 删除画布（包括所有快照）
     pub async fn delete_canvas(&self, id: &CanvasId) -> Result<(), CanvasError> {
-        let mut canvases = self.canvases.write().await;
-        if canvases.remove(id).is_some() {
+        let loaded = self.canvases.write().await.remove(id).is_some();
+        let stored = self.storage.load_canvas(id).await?.is_some();
+        self.dirty.write().await.remove(id);
+        self.snapshot_locks.lock().await.remove(id);
+
+        if loaded || stored {
+            self.storage.delete_canvas(id).await?;
             info!("删除画布: {}", id);
             Ok(())
         } else {
@@ -146,23 +223,207 @@ impl CanvasManager {
         }
     }
 
-    /// 列出所有画布
+    /// 列出所有画布（包括尚未加载的已持久化画布）
     pub async fn list_canvases(&self) -> Vec<CanvasInfo> {
-        let canvases = self.canvases.read().await;
-        canvases
-            .values()
-            .map(|c| {
-                let rt = tokio::runtime::Handle::current();
-                let state = rt.block_on(async { c.read().await });
+        let mut infos: HashMap<CanvasId, CanvasInfo> = match self.storage.list_canvases().await {
+            Ok(list) => list
+                .into_iter()
+                .map(|info| (info.id.clone(), info))
+                .collect(),
+            Err(e) => {
+                warn!("列出已存储画布失败: {}", e);
+                HashMap::new()
+            }
+        };
+
+        let loaded: Vec<_> = self.canvases.read().await.values().cloned().collect();
+        for canvas in loaded {
+            let state = canvas.read().await;
+            infos.insert(
+                state.id.clone(),
                 CanvasInfo {
                     id: state.id.clone(),
                     name: state.name.clone(),
                     element_count: state.elements.len(),
                     created_at: state.created_at,
                     updated_at: state.updated_at,
+                },
+            );
+        }
+
+        let mut result: Vec<_> = infos.into_values().collect();
+        result.sort_by_key(|info| info.created_at);
+        result
+    }
+
+    /// 提交画布修改
+    ///
+    /// `Immediate` 模式立即写入存储；`Batched` 模式仅标记为脏，由 [`CanvasManager::flush`]
+    /// 或 [`CanvasManager::start_autosave`] 批量写入。
+    pub async fn commit(&self, id: &CanvasId) -> Result<(), CanvasError> {
+        match self.persist_mode {
+            PersistMode::Immediate => self.persist(id).await,
+            PersistMode::Batched { .. } => {
+                self.dirty.write().await.insert(id.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// 写入所有待保存的画布，返回写入数量
+    pub async fn flush(&self) -> Result<usize, CanvasError> {
+        Self::flush_dirty(&self.canvases, &self.storage, &self.dirty).await
+    }
+
+    /// 启动后台批量保存任务（仅 `Batched` 模式）
+    pub fn start_autosave(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let interval = self.persist_mode.flush_interval()?;
+        let canvases = self.canvases.clone();
+        let storage = self.storage.clone();
+        let dirty = self.dirty.clone();
+
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = Self::flush_dirty(&canvases, &storage, &dirty).await {
+                    warn!("画布批量保存失败: {}", e);
                 }
-            })
-            .collect()
+            }
+        }))
+    }
+
+    /// 创建命名快照，返回新版本信息
+    pub async fn create_snapshot(
+        &self,
+        id: &CanvasId,
+        name: Option<String>,
+    ) -> Result<CanvasVersion, CanvasError> {
+        // 整个快照过程持有画布锁，并发创建不会得到相同版本号
+        let lock = self
+            .snapshot_locks
+            .lock()
+            .await
+            .entry(id.clone())
+            .or_default()
+            .clone();
+        let _guard = lock.lock().await;
+
+        let state = self.get_canvas_state(id).await?;
+        // 快照前先落盘当前状态，保证快照与最新状态一致
+        self.persist(id).await?;
+
+        let version = self
+            .storage
+            .list_snapshots(id)
+            .await?
+            .last()
+            .map(|v| v.version + 1)
+            .unwrap_or(1);
+        let name = name.unwrap_or_else(|| format!("v{}", version));
+        let snapshot = CanvasSnapshot::new(version, name, state);
+        self.storage.save_snapshot(&snapshot).await?;
+
+        info!("创建画布快照: {} v{}", id, version);
+        Ok(snapshot.info)
+    }
+
+    /// 列出画布的所有版本
+    pub async fn list_versions(&self, id: &CanvasId) -> Result<Vec<CanvasVersion>, CanvasError> {
+        if self.get_canvas(id).await.is_none() {
+            return Err(CanvasError::NotFound(id.clone()));
+        }
+        self.storage.list_snapshots(id).await
+    }
+
+    /// 回滚到指定版本
+    ///
+    /// 原地替换画布状态，已有的画布句柄（如协作会话）会看到回滚后的内容。
+    pub async fn restore_snapshot(
+        &self,
+        id: &CanvasId,
+        version: u64,
+    ) -> Result<CanvasState, CanvasError> {
+        let canvas = self
+            .get_canvas(id)
+            .await
+            .ok_or_else(|| CanvasError::NotFound(id.clone()))?;
+        let snapshot = self
+            .storage
+            .load_snapshot(id, version)
+            .await?
+            .ok_or_else(|| CanvasError::VersionNotFound(id.clone(), version))?;
+
+        let restored = {
+            let mut state = canvas.write().await;
+            *state = snapshot.state;
+            state.updated_at = Utc::now();
+            state.clone()
+        };
+        self.storage.save_canvas(&restored).await?;
+        self.dirty.write().await.remove(id);
+
+        info!("画布 {} 回滚到 v{}", id, version);
+        Ok(restored)
+    }
+
+    /// 导出画布为 SVG / PNG / PDF
+    pub async fn export(
+        &self,
+        id: &CanvasId,
+        format: ExportFormat,
+        options: &ExportOptions,
+    ) -> Result<ExportOutput, CanvasError> {
+        let state = self.get_canvas_state(id).await?;
+        let options = options.clone();
+        // 光栅化与 PDF 生成是 CPU 密集操作，避免阻塞异步运行时
+        tokio::task::spawn_blocking(move || CanvasExporter::export(&state, format, &options))
+            .await
+            .map_err(|e| CanvasError::Internal(e.into()))?
+    }
+
+    async fn persist(&self, id: &CanvasId) -> Result<(), CanvasError> {
+        let canvas = self
+            .get_canvas(id)
+            .await
+            .ok_or_else(|| CanvasError::NotFound(id.clone()))?;
+        let state = canvas.read().await.clone();
+        self.storage.save_canvas(&state).await?;
+        self.dirty.write().await.remove(id);
+        Ok(())
+    }
+
+    async fn flush_dirty(
+        canvases: &RwLock<HashMap<CanvasId, Arc<RwLock<CanvasState>>>>,
+        storage: &Arc<dyn CanvasStorage>,
+        dirty: &RwLock<HashSet<CanvasId>>,
+    ) -> Result<usize, CanvasError> {
+        let ids: Vec<CanvasId> = dirty.write().await.drain().collect();
+        let mut saved = 0;
+        let mut first_error = None;
+
+        for id in ids {
+            let canvas = canvases.read().await.get(&id).cloned();
+            let Some(canvas) = canvas else {
+                continue;
+            };
+            let state = canvas.read().await.clone();
+            if let Err(e) = storage.save_canvas(&state).await {
+                // 写入失败时保留脏标记，下次重试
+                dirty.write().await.insert(id);
+                first_error.get_or_insert(e);
+                continue;
+            }
+            saved += 1;
+        }
+
+        if saved > 0 {
+            debug!("批量保存画布: {}", saved);
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(saved),
+        }
     }
 }
 
@@ -329,3 +590,117 @@ impl CanvasOps {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::FileCanvasStorage;
+
+    fn line() -> Element {
+        Element::new(
+            Shape::Line {
+                start: Point::new(0.0, 0.0),
+                end: Point::new(5.0, 5.0),
+                stroke: StrokeStyle::default(),
+            },
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_lazy_load_from_storage() {
+        let storage: Arc<dyn CanvasStorage> = Arc::new(MemoryCanvasStorage::new());
+        let manager = CanvasManager::with_storage(storage.clone(), PersistMode::Immediate);
+        let id = manager
+            .create_canvas("test".to_string(), 100.0, 100.0)
+            .await;
+        let canvas = manager.get_canvas(&id).await.unwrap();
+        CanvasOps::add_element(&canvas, line()).await.unwrap();
+        manager.commit(&id).await.unwrap();
+
+        // 模拟网关重启：新的管理器共享同一存储
+        let restarted = CanvasManager::with_storage(storage, PersistMode::Immediate);
+        assert_eq!(restarted.list_canvases().await.len(), 1);
+        let state = restarted.get_canvas_state(&id).await.unwrap();
+        assert_eq!(state.elements.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_batched_commit_requires_flush() {
+        let storage: Arc<dyn CanvasStorage> = Arc::new(MemoryCanvasStorage::new());
+        let manager = CanvasManager::with_storage(
+            storage.clone(),
+            PersistMode::Batched { interval_ms: 1000 },
+        );
+        let id = manager
+            .create_canvas("test".to_string(), 100.0, 100.0)
+            .await;
+        let canvas = manager.get_canvas(&id).await.unwrap();
+        CanvasOps::add_element(&canvas, line()).await.unwrap();
+        manager.commit(&id).await.unwrap();
+
+        let stored = storage.load_canvas(&id).await.unwrap().unwrap();
+        assert!(stored.elements.is_empty());
+
+        assert_eq!(manager.flush().await.unwrap(), 1);
+        let stored = storage.load_canvas(&id).await.unwrap().unwrap();
+        assert_eq!(stored.elements.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_snapshots_get_distinct_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(FileCanvasStorage::new(dir.path()).unwrap());
+        let manager = Arc::new(CanvasManager::with_storage(storage, PersistMode::Immediate));
+        let id = manager
+            .create_canvas("test".to_string(), 100.0, 100.0)
+            .await;
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let manager = manager.clone();
+                let id = id.clone();
+                tokio::spawn(async move { manager.create_snapshot(&id, None).await.unwrap() })
+            })
+            .collect();
+        let mut versions = Vec::new();
+        for task in tasks {
+            versions.push(task.await.unwrap().version);
+        }
+        versions.sort();
+
+        assert_eq!(versions, (1..=8).collect::<Vec<_>>());
+        assert_eq!(manager.list_versions(&id).await.unwrap().len(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_and_restore() {
+        let manager = CanvasManager::new();
+        let id = manager
+            .create_canvas("test".to_string(), 100.0, 100.0)
+            .await;
+        let canvas = manager.get_canvas(&id).await.unwrap();
+
+        let v1 = manager.create_snapshot(&id, None).await.unwrap();
+        assert_eq!(v1.version, 1);
+        assert_eq!(v1.name, "v1");
+
+        CanvasOps::add_element(&canvas, line()).await.unwrap();
+        manager.commit(&id).await.unwrap();
+        let v2 = manager
+            .create_snapshot(&id, Some("with line".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(v2.version, 2);
+        assert_eq!(manager.list_versions(&id).await.unwrap().len(), 2);
+
+        let restored = manager.restore_snapshot(&id, 1).await.unwrap();
+        assert!(restored.elements.is_empty());
+        assert!(canvas.read().await.elements.is_empty());
+
+        assert!(matches!(
+            manager.restore_snapshot(&id, 9).await,
+            Err(CanvasError::VersionNotFound(_, 9))
+        ));
+    }
+}
@@ -1,10 +1,12 @@
 //! 实时协作模块
 
+use crate::canvas::CanvasError;
+use crate::crdt::{CrdtDocument, CrdtOp, OpKind, OpOrigin, VectorClock};
 use crate::types::*;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{RwLock, broadcast};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// 协作事件
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -44,6 +46,11 @@ pub enum CollabEvent {
         user_id: UserId,
         viewport: Viewport,
     },
+    /// CRDT 操作已合并（客户端按状态向量去重，可直接应用）
+    OpsApplied {
+        canvas_id: CanvasId,
+        ops: Vec<CrdtOp>,
+    },
     /// 图层操作
     LayerAdded { canvas_id: CanvasId, layer: Layer },
     LayerDeleted {
@@ -62,10 +69,13 @@ pub struct UserInfo {
 }
 
 /// 协作会话
+///
+/// 会话持有画布的服务端 CRDT 副本，所有客户端和服务端（REST、Agent）的编辑都经由它合并。
 pub struct CollabSession {
     pub canvas_id: CanvasId,
     pub users: Arc<RwLock<HashMap<UserId, UserInfo>>>,
     pub cursors: Arc<RwLock<HashMap<UserId, UserCursor>>>,
+    doc: Arc<RwLock<CrdtDocument>>,
     event_sender: broadcast::Sender<CollabEvent>,
 }
 
@@ -73,14 +83,118 @@ impl CollabSession {
     /// 创建新的协作会话
     pub fn new(canvas_id: CanvasId) -> Self {
         let (event_sender, _) = broadcast::channel(1024);
+        // 每次创建会话使用新的副本 ID，避免与旧会话产生的操作序号冲突
+        let doc = CrdtDocument::new(format!("server-{}", uuid::Uuid::new_v4()));
         Self {
             canvas_id,
             users: Arc::new(RwLock::new(HashMap::new())),
             cursors: Arc::new(RwLock::new(HashMap::new())),
+            doc: Arc::new(RwLock::new(doc)),
             event_sender,
         }
     }
 
+    /// 以画布当前状态初始化 CRDT 文档（仅在文档为空时生效）
+    pub async fn seed(&self, state: &CanvasState) {
+        let mut doc = self.doc.write().await;
+        if doc.state_vector().is_empty() {
+            let client_id = doc.client_id().to_string();
+            *doc = CrdtDocument::from_state(client_id, state);
+            debug!(
+                "初始化协作文档: {} ({} 个元素)",
+                self.canvas_id,
+                state.elements.len()
+            );
+        }
+    }
+
+    /// 服务端状态向量
+    pub async fn state_vector(&self) -> VectorClock {
+        self.doc.read().await.state_vector().clone()
+    }
+
+    /// 对方缺少的操作，需要全量同步时返回 None
+    pub async fn diff(&self, remote: &VectorClock) -> Option<Vec<CrdtOp>> {
+        self.doc.read().await.diff(remote)
+    }
+
+    /// 合并客户端操作并广播实际应用的部分
+    ///
+    /// 只接受来自该连接副本 `client` 的操作，操作归属的用户一律改写为 `user`。
+    pub async fn apply_ops(&self, client: &str, user: &str, ops: Vec<CrdtOp>) -> Vec<CrdtOp> {
+        let ops: Vec<_> = ops
+            .into_iter()
+            .filter_map(|mut op| {
+                if op.client != client {
+                    warn!("拒绝副本 {} 代替副本 {} 提交的操作", client, op.client);
+                    return None;
+                }
+                op.user = Some(user.to_string());
+                match &mut op.origin {
+                    OpOrigin::Edit => {}
+                    OpOrigin::Undo { user: origin } | OpOrigin::Redo { user: origin } => {
+                        *origin = user.to_string();
+                    }
+                }
+                Some(op)
+            })
+            .collect();
+        let applied = self.doc.write().await.apply_remote(ops);
+        self.broadcast_ops(applied.clone());
+        applied
+    }
+
+    /// 服务端编辑，`user` 为撤销记录归属的用户
+    pub async fn edit(&self, op: OpKind, user: Option<&str>) -> Result<CrdtOp, CanvasError> {
+        let op = self.doc.write().await.edit(op, user)?;
+        self.broadcast_ops(vec![op.clone()]);
+        Ok(op)
+    }
+
+    /// 删除所有元素
+    pub async fn clear(&self, user: Option<&str>) -> Vec<CrdtOp> {
+        let ops = self.doc.write().await.clear(user);
+        self.broadcast_ops(ops.clone());
+        ops
+    }
+
+    /// 使文档与给定画布状态一致（如恢复快照后）
+    pub async fn reset_to(&self, state: &CanvasState, user: Option<&str>) -> Vec<CrdtOp> {
+        let ops = self.doc.write().await.reset_to(&state.elements, user);
+        self.broadcast_ops(ops.clone());
+        ops
+    }
+
+    /// 撤销用户最近一次操作
+    pub async fn undo(&self, user_id: &str) -> Vec<CrdtOp> {
+        let ops = self.doc.write().await.undo(user_id);
+        self.broadcast_ops(ops.clone());
+        ops
+    }
+
+    /// 重做用户最近一次撤销
+    pub async fn redo(&self, user_id: &str) -> Vec<CrdtOp> {
+        let ops = self.doc.write().await.redo(user_id);
+        self.broadcast_ops(ops.clone());
+        ops
+    }
+
+    /// 将合并后的元素写入画布状态
+    pub async fn apply_to(&self, state: &mut CanvasState) {
+        self.doc.read().await.apply_to(state);
+    }
+
+    fn broadcast_ops(&self, ops: Vec<CrdtOp>) {
+        if ops.is_empty() {
+            return;
+        }
+        let event = CollabEvent::OpsApplied {
+            canvas_id: self.canvas_id.clone(),
+            ops,
+        };
+        let _ = self.event_sender.send(event);
+    }
+
     /// 用户加入
     pub async fn join(&self, user: UserInfo) -> broadcast::Receiver<CollabEvent> {
         let user_id = user.id.clone();
@@ -275,8 +389,22 @@ pub enum WsMessage {
     LeaveCanvas { canvas_id: CanvasId },
     /// 光标移动
     CursorMove { position: Point, tool: Tool },
-    /// 绘图操作
+    /// 绘图操作（旧协议，服务端转换为 CRDT 操作）
     DrawAction { action: super::draw::DrawAction },
+    /// 同步第一步：客户端发送自己的状态向量
+    SyncStep1 { state_vector: VectorClock },
+    /// 同步第二步：服务端返回客户端缺少的操作及服务端状态向量，
+    /// 客户端据此将离线期间的操作以 `Update` 发回
+    SyncStep2 {
+        ops: Vec<CrdtOp>,
+        state_vector: VectorClock,
+    },
+    /// CRDT 操作
+    Update { ops: Vec<CrdtOp> },
+    /// 撤销当前用户的上一次操作
+    Undo,
+    /// 重做当前用户的上一次撤销
+    Redo,
     /// 视口变化
     ViewportChange { viewport: Viewport },
     /// 同步请求
@@ -345,6 +473,65 @@ mod tests {
         assert_eq!(session.canvas_id, "canvas1".to_string());
     }
 
+    #[tokio::test]
+    async fn test_collab_session_merges_ops() {
+        let session = CollabSession::new("canvas1".to_string());
+        let mut client = CrdtDocument::new("client1");
+        let element = Element::new(
+            Shape::Text {
+                position: Point::new(0.0, 0.0),
+                content: "hi".to_string(),
+                font_size: 12.0,
+                font_family: "sans-serif".to_string(),
+                color: Color::black(),
+            },
+            None,
+        );
+        let id = element.id.clone();
+        let op = client.edit(OpKind::Insert { element }, None).unwrap();
+
+        // 冒充其他副本的操作被拒绝
+        let mut forged = op.clone();
+        forged.client = "client2".to_string();
+        assert!(
+            session
+                .apply_ops("client1", "u1", vec![forged])
+                .await
+                .is_empty()
+        );
+
+        let applied = session.apply_ops("client1", "u1", vec![op]).await;
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].actor(), "u1");
+        assert!(
+            session
+                .diff(client.state_vector())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        session
+            .edit(
+                OpKind::Delete {
+                    element_id: id.clone(),
+                },
+                Some("agent"),
+            )
+            .await
+            .unwrap();
+        let missing = session.diff(client.state_vector()).await.unwrap();
+        assert_eq!(missing.len(), 1);
+        client.apply_remote(missing);
+        assert!(client.elements().is_empty());
+
+        // agent 的删除只能由 agent 撤销
+        assert!(session.undo("u1").await.is_empty());
+        assert_eq!(session.undo("agent").await.len(), 1);
+        client.apply_remote(session.diff(client.state_vector()).await.unwrap());
+        assert!(client.elements().contains_key(&id));
+    }
+
     #[test]
     fn test_user_color_generator() {
         let generator = UserColorGenerator::new();
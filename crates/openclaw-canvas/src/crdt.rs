@@ -0,0 +1,992 @@
+//! CRDT 协作文档模块
+//!
+//! 画布元素的每个字段都是一个 LWW（最后写入者胜）寄存器，写入以
+//! `(lamport, client)` 全序比较，因此任意顺序收到同一组操作的副本都会收敛到相同状态。
+//! 每个副本用向量时钟记录各客户端已连续应用的操作序号，重连时只需交换差异；
+//! 离线期间的本地操作保存在操作日志中，重连后照常同步。操作日志超出上限后丢弃最早的一段，
+//! 落后于丢弃位置的副本无法增量同步，需要重新拉取全量状态。
+//!
+//! 撤销按用户分别记录：撤销只回滚该用户自己写入、且之后未被他人覆盖的字段。
+
+use crate::canvas::CanvasError;
+use crate::types::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// 副本（客户端）ID
+pub type ClientId = String;
+
+/// 默认每个用户保留的撤销步数
+const DEFAULT_MAX_HISTORY: usize = 100;
+
+/// 默认保留的操作日志条数
+const DEFAULT_MAX_LOG: usize = 10_000;
+
+/// 默认最多暂存的乱序远端操作数
+const DEFAULT_MAX_PENDING: usize = 1_000;
+
+/// 向量时钟（状态向量）
+///
+/// 记录每个客户端已连续应用的最大操作序号。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorClock(BTreeMap<ClientId, u64>);
+
+impl VectorClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取客户端已应用的操作序号
+    pub fn get(&self, client: &str) -> u64 {
+        self.0.get(client).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, client: ClientId, seq: u64) {
+        self.0.insert(client, seq);
+    }
+
+    /// 逐项取最大值合并
+    pub fn merge(&mut self, other: &VectorClock) {
+        for (client, seq) in &other.0 {
+            let entry = self.0.entry(client.clone()).or_insert(0);
+            *entry = (*entry).max(*seq);
+        }
+    }
+
+    /// 是否已包含另一时钟的全部操作
+    pub fn dominates(&self, other: &VectorClock) -> bool {
+        other.0.iter().all(|(client, seq)| self.get(client) >= *seq)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// 操作时间戳，lamport 相同时按客户端 ID 决胜
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Stamp {
+    pub lamport: u64,
+    pub client: ClientId,
+}
+
+/// 操作内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OpKind {
+    /// 插入元素（对已存在的元素等同于覆盖全部字段）
+    Insert { element: Element },
+    /// 更新元素字段
+    Update {
+        element_id: String,
+        updates: ElementUpdate,
+    },
+    /// 删除元素（保留墓碑）
+    Delete { element_id: String },
+    /// 恢复已删除的元素
+    Restore { element_id: String },
+}
+
+impl OpKind {
+    pub fn element_id(&self) -> &str {
+        match self {
+            OpKind::Insert { element } => &element.id,
+            OpKind::Update { element_id, .. }
+            | OpKind::Delete { element_id }
+            | OpKind::Restore { element_id } => element_id,
+        }
+    }
+}
+
+/// 操作来源，各副本据此一致地维护按用户的撤销栈
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpOrigin {
+    #[default]
+    Edit,
+    Undo {
+        user: UserId,
+    },
+    Redo {
+        user: UserId,
+    },
+}
+
+/// CRDT 操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrdtOp {
+    /// 产生操作的副本
+    pub client: ClientId,
+    /// 该副本内连续递增的序号，从 1 开始
+    pub seq: u64,
+    pub lamport: u64,
+    /// 代为操作的用户（如服务端代 REST 调用方写入），缺省为 `client`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<UserId>,
+    #[serde(default)]
+    pub origin: OpOrigin,
+    pub op: OpKind,
+}
+
+impl CrdtOp {
+    pub fn stamp(&self) -> Stamp {
+        Stamp {
+            lamport: self.lamport,
+            client: self.client.clone(),
+        }
+    }
+
+    /// 操作归属的用户
+    pub fn actor(&self) -> &str {
+        self.user.as_deref().unwrap_or(&self.client)
+    }
+}
+
+/// LWW 寄存器
+#[derive(Debug, Clone)]
+struct Lww<T> {
+    value: T,
+    stamp: Stamp,
+}
+
+impl<T> Lww<T> {
+    fn new(value: T, stamp: Stamp) -> Self {
+        Self { value, stamp }
+    }
+
+    /// 时间戳更新时写入，返回被覆盖的旧值
+    fn set(&mut self, value: T, stamp: &Stamp) -> Option<T> {
+        if *stamp > self.stamp {
+            self.stamp = stamp.clone();
+            Some(std::mem::replace(&mut self.value, value))
+        } else {
+            None
+        }
+    }
+}
+
+/// 文档中的元素
+#[derive(Debug, Clone)]
+struct CrdtElement {
+    id: String,
+    created_at: DateTime<Utc>,
+    created_by: Option<UserId>,
+    updated_at: DateTime<Utc>,
+    shape: Lww<Shape>,
+    layer: Lww<usize>,
+    locked: Lww<bool>,
+    visible: Lww<bool>,
+    opacity: Lww<f64>,
+    transform: Lww<Transform>,
+    deleted: Lww<bool>,
+}
+
+impl CrdtElement {
+    /// 占位元素：尚未插入，视为已删除，所有寄存器都会被首个插入覆盖
+    fn placeholder(element: &Element) -> Self {
+        let zero = Stamp {
+            lamport: 0,
+            client: ClientId::new(),
+        };
+        Self {
+            id: element.id.clone(),
+            created_at: element.created_at,
+            created_by: element.created_by.clone(),
+            updated_at: element.updated_at,
+            shape: Lww::new(element.shape.clone(), zero.clone()),
+            layer: Lww::new(element.layer, zero.clone()),
+            locked: Lww::new(element.locked, zero.clone()),
+            visible: Lww::new(element.visible, zero.clone()),
+            opacity: Lww::new(element.opacity, zero.clone()),
+            transform: Lww::new(element.transform.clone(), zero.clone()),
+            deleted: Lww::new(true, zero),
+        }
+    }
+
+    fn to_element(&self) -> Element {
+        Element {
+            id: self.id.clone(),
+            shape: self.shape.value.clone(),
+            layer: self.layer.value,
+            locked: self.locked.value,
+            visible: self.visible.value,
+            opacity: self.opacity.value,
+            transform: self.transform.value.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            created_by: self.created_by.clone(),
+        }
+    }
+
+    /// 按字段合并更新，返回被覆盖字段的旧值
+    fn apply_updates(&mut self, updates: ElementUpdate, stamp: &Stamp) -> ElementUpdate {
+        ElementUpdate {
+            shape: updates.shape.and_then(|v| self.shape.set(v, stamp)),
+            layer: updates.layer.and_then(|v| self.layer.set(v, stamp)),
+            locked: updates.locked.and_then(|v| self.locked.set(v, stamp)),
+            visible: updates.visible.and_then(|v| self.visible.set(v, stamp)),
+            opacity: updates.opacity.and_then(|v| self.opacity.set(v, stamp)),
+            transform: updates.transform.and_then(|v| self.transform.set(v, stamp)),
+        }
+    }
+
+    /// 仍由指定时间戳持有的字段
+    fn retain_owned(&self, update: ElementUpdate, stamp: &Stamp) -> ElementUpdate {
+        ElementUpdate {
+            shape: update.shape.filter(|_| self.shape.stamp == *stamp),
+            layer: update.layer.filter(|_| self.layer.stamp == *stamp),
+            locked: update.locked.filter(|_| self.locked.stamp == *stamp),
+            visible: update.visible.filter(|_| self.visible.stamp == *stamp),
+            opacity: update.opacity.filter(|_| self.opacity.stamp == *stamp),
+            transform: update.transform.filter(|_| self.transform.stamp == *stamp),
+        }
+    }
+
+    /// 与目标元素不同的字段
+    fn diff(&self, target: &Element) -> ElementUpdate {
+        ElementUpdate {
+            shape: (self.shape.value != target.shape).then(|| target.shape.clone()),
+            layer: (self.layer.value != target.layer).then_some(target.layer),
+            locked: (self.locked.value != target.locked).then_some(target.locked),
+            visible: (self.visible.value != target.visible).then_some(target.visible),
+            opacity: (self.opacity.value != target.opacity).then_some(target.opacity),
+            transform: (self.transform.value != target.transform).then(|| target.transform.clone()),
+        }
+    }
+}
+
+fn is_empty_update(update: &ElementUpdate) -> bool {
+    update.shape.is_none()
+        && update.layer.is_none()
+        && update.locked.is_none()
+        && update.visible.is_none()
+        && update.opacity.is_none()
+        && update.transform.is_none()
+}
+
+/// 撤销记录：某个操作覆盖掉的旧值
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    element_id: String,
+    stamp: Stamp,
+    previous: ElementUpdate,
+    previous_deleted: Option<bool>,
+}
+
+/// 单个用户的撤销/重做栈
+#[derive(Debug, Clone, Default)]
+struct UserHistory {
+    undo_stack: VecDeque<HistoryEntry>,
+    redo_stack: VecDeque<HistoryEntry>,
+}
+
+impl UserHistory {
+    fn push(stack: &mut VecDeque<HistoryEntry>, entry: HistoryEntry, max_history: usize) {
+        if stack.len() >= max_history {
+            stack.pop_front();
+        }
+        stack.push_back(entry);
+    }
+}
+
+/// CRDT 画布文档（一个副本）
+#[derive(Debug, Clone)]
+pub struct CrdtDocument {
+    client_id: ClientId,
+    lamport: u64,
+    state_vector: VectorClock,
+    elements: HashMap<String, CrdtElement>,
+    log: Vec<CrdtOp>,
+    /// 已从日志中丢弃的各客户端最大序号
+    compacted: VectorClock,
+    pending: Vec<CrdtOp>,
+    histories: HashMap<UserId, UserHistory>,
+    max_history: usize,
+    max_log: usize,
+    max_pending: usize,
+}
+
+impl CrdtDocument {
+    /// 创建空文档
+    pub fn new(client_id: impl Into<ClientId>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            lamport: 0,
+            state_vector: VectorClock::new(),
+            elements: HashMap::new(),
+            log: Vec::new(),
+            compacted: VectorClock::new(),
+            pending: Vec::new(),
+            histories: HashMap::new(),
+            max_history: DEFAULT_MAX_HISTORY,
+            max_log: DEFAULT_MAX_LOG,
+            max_pending: DEFAULT_MAX_PENDING,
+        }
+    }
+
+    /// 以现有画布状态为初始内容创建文档（初始内容不可撤销）
+    pub fn from_state(client_id: impl Into<ClientId>, state: &CanvasState) -> Self {
+        let mut doc = Self::new(client_id);
+        let mut elements: Vec<_> = state.elements.values().cloned().collect();
+        elements.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        for element in elements {
+            doc.push_local(OpKind::Insert { element }, None, OpOrigin::Edit);
+        }
+        doc.histories.clear();
+        doc
+    }
+
+    pub fn with_max_history(mut self, max_history: usize) -> Self {
+        self.max_history = max_history.max(1);
+        self
+    }
+
+    /// 操作日志上限，超出后丢弃最早的一半
+    pub fn with_max_log(mut self, max_log: usize) -> Self {
+        self.max_log = max_log.max(2);
+        self
+    }
+
+    /// 暂存乱序远端操作的上限，超出的操作直接丢弃
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending.max(1);
+        self
+    }
+
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// 当前状态向量
+    pub fn state_vector(&self) -> &VectorClock {
+        &self.state_vector
+    }
+
+    /// 等待前序操作的远端操作数量
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// 对方缺少的操作（按应用顺序）
+    ///
+    /// 对方缺少的操作已被压缩掉时返回 None，需要改为全量同步。
+    pub fn diff(&self, remote: &VectorClock) -> Option<Vec<CrdtOp>> {
+        if !remote.dominates(&self.compacted) {
+            return None;
+        }
+        Some(
+            self.log
+                .iter()
+                .filter(|op| op.seq > remote.get(&op.client))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// 本地编辑
+    ///
+    /// `user` 为代为操作的用户，撤销记录归属于该用户；缺省归属于本副本。
+    pub fn edit(&mut self, op: OpKind, user: Option<&str>) -> Result<CrdtOp, CanvasError> {
+        let element_id = op.element_id().to_string();
+        match (&op, self.elements.get(&element_id)) {
+            (OpKind::Insert { .. }, _) => {}
+            (_, None) => return Err(CanvasError::ElementNotFound(element_id)),
+            (OpKind::Restore { .. }, Some(_)) => {}
+            (_, Some(element)) if element.deleted.value => {
+                return Err(CanvasError::ElementNotFound(element_id));
+            }
+            (OpKind::Update { updates, .. }, Some(element))
+                if element.locked.value && updates.locked != Some(false) =>
+            {
+                return Err(CanvasError::Forbidden("元素已锁定".to_string()));
+            }
+            _ => {}
+        }
+        Ok(self.push_local(op, user.map(str::to_string), OpOrigin::Edit))
+    }
+
+    /// 删除所有可见元素
+    pub fn clear(&mut self, user: Option<&str>) -> Vec<CrdtOp> {
+        let mut ids: Vec<_> = self
+            .elements
+            .values()
+            .filter(|e| !e.deleted.value)
+            .map(|e| e.id.clone())
+            .collect();
+        ids.sort();
+        ids.into_iter()
+            .map(|element_id| {
+                self.push_local(
+                    OpKind::Delete { element_id },
+                    user.map(str::to_string),
+                    OpOrigin::Edit,
+                )
+            })
+            .collect()
+    }
+
+    /// 生成使文档内容与给定元素集合一致的操作（如恢复快照后）
+    pub fn reset_to(
+        &mut self,
+        elements: &HashMap<String, Element>,
+        user: Option<&str>,
+    ) -> Vec<CrdtOp> {
+        let user = user.map(str::to_string);
+        let mut kinds = Vec::new();
+
+        let mut removed: Vec<_> = self
+            .elements
+            .values()
+            .filter(|e| !e.deleted.value && !elements.contains_key(&e.id))
+            .map(|e| e.id.clone())
+            .collect();
+        removed.sort();
+        kinds.extend(
+            removed
+                .into_iter()
+                .map(|element_id| OpKind::Delete { element_id }),
+        );
+
+        let mut targets: Vec<_> = elements.values().collect();
+        targets.sort_by(|a, b| a.id.cmp(&b.id));
+        for target in targets {
+            match self.elements.get(&target.id) {
+                None => kinds.push(OpKind::Insert {
+                    element: target.clone(),
+                }),
+                Some(current) => {
+                    if current.deleted.value {
+                        kinds.push(OpKind::Restore {
+                            element_id: target.id.clone(),
+                        });
+                    }
+                    let updates = current.diff(target);
+                    if !is_empty_update(&updates) {
+                        kinds.push(OpKind::Update {
+                            element_id: target.id.clone(),
+                            updates,
+                        });
+                    }
+                }
+            }
+        }
+
+        kinds
+            .into_iter()
+            .map(|op| self.push_local(op, user.clone(), OpOrigin::Edit))
+            .collect()
+    }
+
+    /// 应用远端操作
+    ///
+    /// 重复操作被忽略；序号不连续或目标元素尚未插入的操作暂存，待前序操作到达后再应用。
+    /// 暂存区超出上限时丢弃序号最靠后的操作，发送方可通过状态向量重新同步。
+    /// 返回本次实际应用的操作（包括被解除阻塞的暂存操作）。
+    pub fn apply_remote(&mut self, ops: impl IntoIterator<Item = CrdtOp>) -> Vec<CrdtOp> {
+        for op in ops {
+            if op.seq > self.state_vector.get(&op.client)
+                && !self
+                    .pending
+                    .iter()
+                    .any(|p| p.client == op.client && p.seq == op.seq)
+            {
+                self.pending.push(op);
+            }
+        }
+
+        let mut applied = Vec::new();
+        loop {
+            let ready = self.pending.iter().position(|op| {
+                op.seq == self.state_vector.get(&op.client) + 1
+                    && (matches!(op.op, OpKind::Insert { .. })
+                        || self.elements.contains_key(op.op.element_id()))
+            });
+            let Some(index) = ready else { break };
+            let op = self.pending.swap_remove(index);
+            self.integrate(op.clone());
+            applied.push(op);
+        }
+
+        if self.pending.len() > self.max_pending {
+            let dropped = self.pending.len() - self.max_pending;
+            self.pending.sort_by_key(|op| op.seq);
+            self.pending.truncate(self.max_pending);
+            tracing::warn!("CRDT 暂存区已满，丢弃 {} 个远端操作", dropped);
+        }
+        applied
+    }
+
+    /// 撤销用户最近一次仍然有效的操作
+    pub fn undo(&mut self, user: &str) -> Vec<CrdtOp> {
+        self.replay_history(user, true)
+    }
+
+    /// 重做用户最近一次撤销
+    pub fn redo(&mut self, user: &str) -> Vec<CrdtOp> {
+        self.replay_history(user, false)
+    }
+
+    pub fn can_undo(&self, user: &str) -> bool {
+        self.histories
+            .get(user)
+            .is_some_and(|h| !h.undo_stack.is_empty())
+    }
+
+    pub fn can_redo(&self, user: &str) -> bool {
+        self.histories
+            .get(user)
+            .is_some_and(|h| !h.redo_stack.is_empty())
+    }
+
+    /// 当前可见元素
+    pub fn elements(&self) -> HashMap<String, Element> {
+        self.elements
+            .values()
+            .filter(|e| !e.deleted.value)
+            .map(|e| (e.id.clone(), e.to_element()))
+            .collect()
+    }
+
+    /// 将文档内容写入画布状态
+    pub fn apply_to(&self, state: &mut CanvasState) {
+        state.elements = self.elements();
+        state.updated_at = Utc::now();
+    }
+
+    fn push_local(&mut self, op: OpKind, user: Option<UserId>, origin: OpOrigin) -> CrdtOp {
+        let op = CrdtOp {
+            client: self.client_id.clone(),
+            seq: self.state_vector.get(&self.client_id) + 1,
+            lamport: self.lamport + 1,
+            user,
+            origin,
+            op,
+        };
+        self.integrate(op.clone());
+        op
+    }
+
+    fn integrate(&mut self, op: CrdtOp) {
+        self.lamport = self.lamport.max(op.lamport);
+        self.state_vector.set(op.client.clone(), op.seq);
+
+        if let Some(entry) = self.apply_op(&op) {
+            let max_history = self.max_history;
+            match &op.origin {
+                OpOrigin::Edit => {
+                    let history = self.histories.entry(op.actor().to_string()).or_default();
+                    UserHistory::push(&mut history.undo_stack, entry, max_history);
+                    history.redo_stack.clear();
+                }
+                OpOrigin::Undo { user } => {
+                    let history = self.histories.entry(user.clone()).or_default();
+                    UserHistory::push(&mut history.redo_stack, entry, max_history);
+                }
+                OpOrigin::Redo { user } => {
+                    let history = self.histories.entry(user.clone()).or_default();
+                    UserHistory::push(&mut history.undo_stack, entry, max_history);
+                }
+            }
+        }
+
+        self.log.push(op);
+        self.compact_log();
+    }
+
+    /// 日志超出上限时丢弃最早的一半，并记录丢弃位置
+    fn compact_log(&mut self) {
+        if self.log.len() <= self.max_log {
+            return;
+        }
+        let keep = self.max_log / 2;
+        let removed = self.log.len() - keep;
+        for op in self.log.drain(..removed) {
+            if op.seq > self.compacted.get(&op.client) {
+                self.compacted.set(op.client, op.seq);
+            }
+        }
+    }
+
+    /// 合并单个操作，返回其覆盖掉的旧值（未覆盖任何字段时为 None）
+    fn apply_op(&mut self, op: &CrdtOp) -> Option<HistoryEntry> {
+        let stamp = op.stamp();
+        let (element_id, previous, previous_deleted) = match &op.op {
+            OpKind::Insert { element } => {
+                let is_new = !self.elements.contains_key(&element.id);
+                let current = self
+                    .elements
+                    .entry(element.id.clone())
+                    .or_insert_with(|| CrdtElement::placeholder(element));
+                let previous = current.apply_updates(
+                    ElementUpdate {
+                        shape: Some(element.shape.clone()),
+                        layer: Some(element.layer),
+                        locked: Some(element.locked),
+                        visible: Some(element.visible),
+                        opacity: Some(element.opacity),
+                        transform: Some(element.transform.clone()),
+                    },
+                    &stamp,
+                );
+                let previous_deleted = current.deleted.set(false, &stamp);
+                current.updated_at = element.updated_at.max(current.updated_at);
+                // 新元素的撤销只需删除，占位字段无需回滚
+                let previous = if is_new {
+                    ElementUpdate::default()
+                } else {
+                    previous
+                };
+                (element.id.clone(), previous, previous_deleted)
+            }
+            OpKind::Update {
+                element_id,
+                updates,
+            } => {
+                let current = self.elements.get_mut(element_id)?;
+                let previous = current.apply_updates(updates.clone(), &stamp);
+                current.updated_at = Utc::now();
+                (element_id.clone(), previous, None)
+            }
+            OpKind::Delete { element_id } | OpKind::Restore { element_id } => {
+                let deleted = matches!(op.op, OpKind::Delete { .. });
+                let current = self.elements.get_mut(element_id)?;
+                let previous_deleted = current.deleted.set(deleted, &stamp);
+                (
+                    element_id.clone(),
+                    ElementUpdate::default(),
+                    previous_deleted,
+                )
+            }
+        };
+
+        if is_empty_update(&previous) && previous_deleted.is_none() {
+            return None;
+        }
+        Some(HistoryEntry {
+            element_id,
+            stamp,
+            previous,
+            previous_deleted,
+        })
+    }
+
+    fn replay_history(&mut self, user: &str, undo: bool) -> Vec<CrdtOp> {
+        loop {
+            let entry = {
+                let Some(history) = self.histories.get_mut(user) else {
+                    return Vec::new();
+                };
+                let stack = if undo {
+                    &mut history.undo_stack
+                } else {
+                    &mut history.redo_stack
+                };
+                let Some(entry) = stack.pop_back() else {
+                    return Vec::new();
+                };
+                entry
+            };
+
+            // 只回滚仍由该操作持有的字段，他人之后的修改保持不变
+            let Some(element) = self.elements.get(&entry.element_id) else {
+                continue;
+            };
+            let updates = element.retain_owned(entry.previous, &entry.stamp);
+            let deleted = entry
+                .previous_deleted
+                .filter(|_| element.deleted.stamp == entry.stamp);
+
+            let mut kinds = Vec::new();
+            if !is_empty_update(&updates) {
+                kinds.push(OpKind::Update {
+                    element_id: entry.element_id.clone(),
+                    updates,
+                });
+            }
+            match deleted {
+                Some(true) => kinds.push(OpKind::Delete {
+                    element_id: entry.element_id.clone(),
+                }),
+                Some(false) => kinds.push(OpKind::Restore {
+                    element_id: entry.element_id.clone(),
+                }),
+                None => {}
+            }
+            if kinds.is_empty() {
+                continue;
+            }
+
+            let origin = if undo {
+                OpOrigin::Undo {
+                    user: user.to_string(),
+                }
+            } else {
+                OpOrigin::Redo {
+                    user: user.to_string(),
+                }
+            };
+            return kinds
+                .into_iter()
+                .map(|op| self.push_local(op, Some(user.to_string()), origin.clone()))
+                .collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64) -> Element {
+        Element::new(
+            Shape::Rectangle {
+                rect: Rect::new(x, 0.0, 10.0, 10.0),
+                stroke: None,
+                fill: None,
+            },
+            None,
+        )
+    }
+
+    fn opacity_update(id: &str, opacity: f64) -> OpKind {
+        OpKind::Update {
+            element_id: id.to_string(),
+            updates: ElementUpdate {
+                opacity: Some(opacity),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn sync(a: &mut CrdtDocument, b: &mut CrdtDocument) {
+        let to_b = a.diff(b.state_vector()).unwrap();
+        let to_a = b.diff(a.state_vector()).unwrap();
+        b.apply_remote(to_b);
+        a.apply_remote(to_a);
+    }
+
+    #[test]
+    fn test_concurrent_updates_converge() {
+        let mut a = CrdtDocument::new("a");
+        let mut b = CrdtDocument::new("b");
+        let element = rect(0.0);
+        let id = element.id.clone();
+        a.edit(OpKind::Insert { element }, None).unwrap();
+        sync(&mut a, &mut b);
+
+        a.edit(opacity_update(&id, 0.2), None).unwrap();
+        b.edit(opacity_update(&id, 0.8), None).unwrap();
+        b.edit(
+            OpKind::Update {
+                element_id: id.clone(),
+                updates: ElementUpdate {
+                    layer: Some(2),
+                    ..Default::default()
+                },
+            },
+            None,
+        )
+        .unwrap();
+        sync(&mut a, &mut b);
+
+        let ea = &a.elements()[&id];
+        let eb = &b.elements()[&id];
+        assert_eq!(ea.opacity, eb.opacity);
+        assert_eq!(ea.opacity, 0.8);
+        assert_eq!(ea.layer, 2);
+        assert_eq!(a.state_vector(), b.state_vector());
+    }
+
+    #[test]
+    fn test_out_of_order_ops_are_buffered() {
+        let mut a = CrdtDocument::new("a");
+        let mut b = CrdtDocument::new("b");
+        let element = rect(0.0);
+        let id = element.id.clone();
+        let insert = a.edit(OpKind::Insert { element }, None).unwrap();
+        let update = a.edit(opacity_update(&id, 0.5), None).unwrap();
+
+        assert!(b.apply_remote(vec![update.clone()]).is_empty());
+        assert_eq!(b.pending_len(), 1);
+
+        let applied = b.apply_remote(vec![insert.clone(), insert, update]);
+        assert_eq!(applied.len(), 2);
+        assert_eq!(b.pending_len(), 0);
+        assert_eq!(b.elements()[&id].opacity, 0.5);
+    }
+
+    #[test]
+    fn test_pending_is_bounded() {
+        let mut a = CrdtDocument::new("a");
+        let mut b = CrdtDocument::new("b").with_max_pending(2);
+        let ops: Vec<_> = (0..5)
+            .map(|i| {
+                a.edit(
+                    OpKind::Insert {
+                        element: rect(i as f64),
+                    },
+                    None,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        // 缺少第一个操作，其余暂存，超出上限的被丢弃
+        b.apply_remote(ops[1..].to_vec());
+        assert_eq!(b.pending_len(), 2);
+
+        b.apply_remote(vec![ops[0].clone()]);
+        assert_eq!(b.elements().len(), 3);
+        sync(&mut a, &mut b);
+        assert_eq!(b.elements().len(), 5);
+    }
+
+    #[test]
+    fn test_log_compaction_requires_full_sync() {
+        let mut a = CrdtDocument::new("a").with_max_log(4);
+        let mut b = CrdtDocument::new("b");
+        a.edit(OpKind::Insert { element: rect(0.0) }, None).unwrap();
+        sync(&mut a, &mut b);
+
+        for i in 1..6 {
+            a.edit(
+                OpKind::Insert {
+                    element: rect(i as f64),
+                },
+                None,
+            )
+            .unwrap();
+        }
+        assert!(a.log.len() <= 4);
+        // b 缺少的操作已被压缩
+        assert!(a.diff(b.state_vector()).is_none());
+        // 最新的副本仍可增量同步
+        let mut c = a.clone();
+        c.edit(OpKind::Insert { element: rect(9.0) }, None).unwrap();
+        assert_eq!(a.diff(c.state_vector()).unwrap().len(), 0);
+        assert_eq!(c.diff(a.state_vector()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_offline_edits_sync_on_reconnect() {
+        let mut server = CrdtDocument::new("server");
+        let mut client = CrdtDocument::new("client");
+        let element = rect(0.0);
+        let id = element.id.clone();
+        server.edit(OpKind::Insert { element }, None).unwrap();
+        sync(&mut server, &mut client);
+
+        // 断线期间双方各自编辑
+        client
+            .edit(
+                OpKind::Delete {
+                    element_id: id.clone(),
+                },
+                None,
+            )
+            .unwrap();
+        client
+            .edit(OpKind::Insert { element: rect(5.0) }, None)
+            .unwrap();
+        server.edit(opacity_update(&id, 0.3), None).unwrap();
+
+        let missing = server.diff(client.state_vector()).unwrap();
+        assert_eq!(missing.len(), 1);
+        sync(&mut server, &mut client);
+
+        assert_eq!(server.elements().len(), 1);
+        assert!(!server.elements().contains_key(&id));
+        assert_eq!(
+            server.elements().keys().collect::<Vec<_>>(),
+            client.elements().keys().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_undo_is_per_user() {
+        let mut a = CrdtDocument::new("a");
+        let mut b = CrdtDocument::new("b");
+        let element = rect(0.0);
+        let id = element.id.clone();
+        a.edit(OpKind::Insert { element }, None).unwrap();
+        sync(&mut a, &mut b);
+
+        a.edit(opacity_update(&id, 0.2), None).unwrap();
+        sync(&mut a, &mut b);
+        b.edit(
+            OpKind::Update {
+                element_id: id.clone(),
+                updates: ElementUpdate {
+                    layer: Some(3),
+                    ..Default::default()
+                },
+            },
+            None,
+        )
+        .unwrap();
+        sync(&mut a, &mut b);
+
+        // a 撤销自己的透明度修改，不影响 b 的图层修改
+        let ops = a.undo("a");
+        assert_eq!(ops.len(), 1);
+        sync(&mut a, &mut b);
+        assert_eq!(b.elements()[&id].opacity, 1.0);
+        assert_eq!(b.elements()[&id].layer, 3);
+
+        // 撤销记录同步到其他副本
+        assert!(b.can_redo("a"));
+        a.redo("a");
+        sync(&mut a, &mut b);
+        assert_eq!(b.elements()[&id].opacity, 0.2);
+    }
+
+    #[test]
+    fn test_undo_skips_overwritten_fields() {
+        let mut a = CrdtDocument::new("a");
+        let mut b = CrdtDocument::new("b");
+        let element = rect(0.0);
+        let id = element.id.clone();
+        a.edit(OpKind::Insert { element }, None).unwrap();
+        a.edit(opacity_update(&id, 0.2), None).unwrap();
+        sync(&mut a, &mut b);
+        b.edit(opacity_update(&id, 0.9), None).unwrap();
+        sync(&mut a, &mut b);
+
+        // 透明度已被 b 覆盖，a 的下一步撤销回到插入本身
+        let ops = a.undo("a");
+        assert!(matches!(ops[0].op, OpKind::Delete { .. }));
+        assert!(a.elements().is_empty());
+    }
+
+    #[test]
+    fn test_reset_to_state() {
+        let mut doc = CrdtDocument::new("server");
+        let keep = rect(0.0);
+        let drop = rect(1.0);
+        doc.edit(
+            OpKind::Insert {
+                element: keep.clone(),
+            },
+            None,
+        )
+        .unwrap();
+        doc.edit(
+            OpKind::Insert {
+                element: drop.clone(),
+            },
+            None,
+        )
+        .unwrap();
+
+        let mut changed = keep.clone();
+        changed.opacity = 0.4;
+        let target = HashMap::from([(changed.id.clone(), changed)]);
+        let ops = doc.reset_to(&target, None);
+
+        assert_eq!(ops.len(), 2);
+        let elements = doc.elements();
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[&keep.id].opacity, 0.4);
+    }
+}
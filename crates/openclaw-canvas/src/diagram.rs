@@ -0,0 +1,1866 @@
+//! 图表生成模块
+//!
+//! 将 Mermaid（flowchart / sequenceDiagram）与 Graphviz DOT 源码解析为图结构并自动布局，
+//! 生成独立的画布元素：节点为图形加文本，连线为线段加箭头，生成后仍可逐个编辑。
+
+use crate::canvas::CanvasError;
+use crate::types::*;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 图表源码格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagramFormat {
+    Mermaid,
+    Dot,
+}
+
+impl DiagramFormat {
+    /// 根据源码首行推断格式
+    pub fn detect(source: &str) -> Option<Self> {
+        let first = source
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with("%%") && !l.starts_with("//"))?;
+        let lower = first.to_lowercase();
+        let keyword = lower.split_whitespace().next().unwrap_or_default();
+
+        match keyword {
+            "digraph" | "strict" => Some(Self::Dot),
+            "flowchart" | "sequencediagram" => Some(Self::Mermaid),
+            _ if lower.starts_with("graph") => {
+                // `graph TD` 为 Mermaid，`graph name {` 为 DOT
+                let head = source.split('{').next().unwrap_or_default();
+                let tokens = head.split_whitespace().count();
+                if source.contains('{') && tokens <= 2 && !head.contains(';') {
+                    Some(Self::Dot)
+                } else {
+                    Some(Self::Mermaid)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for DiagramFormat {
+    type Err = CanvasError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mermaid" | "mmd" => Ok(Self::Mermaid),
+            "dot" | "graphviz" | "gv" => Ok(Self::Dot),
+            other => Err(CanvasError::InvalidOperation(format!(
+                "不支持的图表格式: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// 节点形状
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeShape {
+    Rectangle,
+    Ellipse,
+    Circle,
+    Diamond,
+}
+
+/// 图节点（时序图中为参与者）
+#[derive(Debug, Clone)]
+pub struct DiagramNode {
+    pub id: String,
+    pub label: String,
+    pub shape: NodeShape,
+}
+
+/// 连线样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Thick,
+}
+
+/// 连线（时序图中为消息）
+#[derive(Debug, Clone)]
+pub struct DiagramEdge {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+    pub arrow: bool,
+    pub style: EdgeStyle,
+}
+
+/// 布局方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    TopBottom,
+    BottomTop,
+    LeftRight,
+    RightLeft,
+}
+
+impl Direction {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_uppercase().as_str() {
+            "TD" | "TB" => Some(Self::TopBottom),
+            "BT" => Some(Self::BottomTop),
+            "LR" => Some(Self::LeftRight),
+            "RL" => Some(Self::RightLeft),
+            _ => None,
+        }
+    }
+
+    fn is_horizontal(self) -> bool {
+        matches!(self, Self::LeftRight | Self::RightLeft)
+    }
+}
+
+/// 解析后的图表
+#[derive(Debug, Clone)]
+pub enum Diagram {
+    /// 流程图 / 有向图
+    Graph {
+        direction: Direction,
+        nodes: Vec<DiagramNode>,
+        edges: Vec<DiagramEdge>,
+    },
+    /// 时序图
+    Sequence {
+        participants: Vec<DiagramNode>,
+        messages: Vec<DiagramEdge>,
+    },
+}
+
+impl Diagram {
+    /// 解析图表源码，未指定格式时自动推断
+    pub fn parse(source: &str, format: Option<DiagramFormat>) -> Result<Self, CanvasError> {
+        let format = format
+            .or_else(|| DiagramFormat::detect(source))
+            .ok_or_else(|| CanvasError::InvalidDiagram("无法识别图表格式".to_string()))?;
+
+        let diagram = match format {
+            DiagramFormat::Mermaid => parse_mermaid(source)?,
+            DiagramFormat::Dot => parse_dot(source)?,
+        };
+
+        let empty = match &diagram {
+            Diagram::Graph { nodes, .. } => nodes.is_empty(),
+            Diagram::Sequence { participants, .. } => participants.is_empty(),
+        };
+        if empty {
+            return Err(CanvasError::InvalidDiagram("图表为空".to_string()));
+        }
+        Ok(diagram)
+    }
+}
+
+/// 图表生成选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiagramOptions {
+    /// 图表左上角在画布上的位置
+    pub origin: Point,
+    pub font_size: f64,
+    /// 同层节点间距
+    pub node_spacing: f64,
+    /// 层间距
+    pub rank_spacing: f64,
+    pub layer: usize,
+    pub stroke_color: Color,
+    pub fill_color: Color,
+    pub text_color: Color,
+}
+
+impl Default for DiagramOptions {
+    fn default() -> Self {
+        Self {
+            origin: Point::new(40.0, 40.0),
+            font_size: 14.0,
+            node_spacing: 40.0,
+            rank_spacing: 60.0,
+            layer: 0,
+            stroke_color: Color::new(51, 51, 51, 255),
+            fill_color: Color::white(),
+            text_color: Color::new(34, 34, 34, 255),
+        }
+    }
+}
+
+/// 图表生成结果
+#[derive(Debug, Clone)]
+pub struct DiagramLayout {
+    /// 按绘制顺序排列的元素
+    pub elements: Vec<Element>,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// 图表生成器
+pub struct DiagramGenerator;
+
+impl DiagramGenerator {
+    /// 解析并布局图表，生成画布元素
+    pub fn generate(
+        source: &str,
+        format: Option<DiagramFormat>,
+        options: &DiagramOptions,
+        created_by: Option<UserId>,
+    ) -> Result<DiagramLayout, CanvasError> {
+        let diagram = Diagram::parse(source, format)?;
+        Ok(Self::layout(&diagram, options, created_by))
+    }
+
+    /// 布局已解析的图表
+    pub fn layout(
+        diagram: &Diagram,
+        options: &DiagramOptions,
+        created_by: Option<UserId>,
+    ) -> DiagramLayout {
+        let mut builder = ElementBuilder {
+            options,
+            created_by,
+            elements: Vec::new(),
+        };
+        let (width, height) = match diagram {
+            Diagram::Graph {
+                direction,
+                nodes,
+                edges,
+            } => layout_graph(&mut builder, *direction, nodes, edges),
+            Diagram::Sequence {
+                participants,
+                messages,
+            } => layout_sequence(&mut builder, participants, messages),
+        };
+
+        // 导出按创建时间排序绘制，保证文本位于图形之上
+        let mut elements = builder.elements;
+        let now = Utc::now();
+        for (i, element) in elements.iter_mut().enumerate() {
+            element.created_at = now + Duration::microseconds(i as i64);
+            element.updated_at = element.created_at;
+        }
+
+        DiagramLayout {
+            elements,
+            width,
+            height,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 布局
+// ---------------------------------------------------------------------------
+
+const ARROW_SIZE: f64 = 10.0;
+const LINE_HEIGHT: f64 = 1.2;
+
+/// 已放置的节点
+#[derive(Debug, Clone, Copy)]
+struct Placed {
+    cx: f64,
+    cy: f64,
+    w: f64,
+    h: f64,
+    shape: NodeShape,
+}
+
+impl Placed {
+    /// 从中心沿方向 (dx, dy) 与节点边界的交点
+    fn boundary(&self, dx: f64, dy: f64) -> Point {
+        let (hw, hh) = (self.w / 2.0, self.h / 2.0);
+        let t = match self.shape {
+            NodeShape::Rectangle => {
+                let tx = if dx != 0.0 {
+                    hw / dx.abs()
+                } else {
+                    f64::INFINITY
+                };
+                let ty = if dy != 0.0 {
+                    hh / dy.abs()
+                } else {
+                    f64::INFINITY
+                };
+                tx.min(ty)
+            }
+            NodeShape::Ellipse | NodeShape::Circle => {
+                1.0 / ((dx / hw).powi(2) + (dy / hh).powi(2)).sqrt()
+            }
+            NodeShape::Diamond => 1.0 / (dx.abs() / hw + dy.abs() / hh),
+        };
+        if !t.is_finite() {
+            return Point::new(self.cx, self.cy);
+        }
+        Point::new(self.cx + dx * t, self.cy + dy * t)
+    }
+}
+
+fn text_width(text: &str, font_size: f64) -> f64 {
+    text.chars()
+        .map(|c| if c.is_ascii() { 0.6 } else { 1.0 })
+        .sum::<f64>()
+        * font_size
+}
+
+fn label_lines(label: &str) -> Vec<&str> {
+    label.split('\n').collect()
+}
+
+fn label_size(label: &str, font_size: f64) -> (f64, f64) {
+    let lines = label_lines(label);
+    let w = lines
+        .iter()
+        .map(|l| text_width(l, font_size))
+        .fold(0.0, f64::max);
+    (w, lines.len() as f64 * font_size * LINE_HEIGHT)
+}
+
+fn node_size(node: &DiagramNode, font_size: f64) -> (f64, f64) {
+    let (tw, th) = label_size(&node.label, font_size);
+    let pad = font_size;
+    let (w, h) = match node.shape {
+        NodeShape::Rectangle => (tw + 2.0 * pad, th + pad),
+        NodeShape::Ellipse => ((tw + 2.0 * pad) * 1.25, (th + pad) * 1.4),
+        NodeShape::Circle => {
+            let d = tw.max(th) + 2.0 * pad;
+            (d, d)
+        }
+        NodeShape::Diamond => ((tw + pad) * 1.8, (th + pad) * 1.8),
+    };
+    (w.max(font_size * 3.0), h)
+}
+
+/// 分层布局：去环、最长路径分层、长边插入虚拟节点、重心法排序
+fn layout_graph(
+    builder: &mut ElementBuilder,
+    direction: Direction,
+    nodes: &[DiagramNode],
+    edges: &[DiagramEdge],
+) -> (f64, f64) {
+    let options = builder.options;
+    let n = nodes.len();
+    let index: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.id.as_str(), i))
+        .collect();
+    let links: Vec<Option<(usize, usize)>> = edges
+        .iter()
+        .map(|e| Some((*index.get(e.from.as_str())?, *index.get(e.to.as_str())?)))
+        .collect();
+
+    let reversed = break_cycles(n, &links);
+    let oriented: Vec<(usize, usize)> = links
+        .iter()
+        .zip(&reversed)
+        .filter_map(|(link, &rev)| {
+            let (u, v) = (*link)?;
+            (u != v).then_some(if rev { (v, u) } else { (u, v) })
+        })
+        .collect();
+    let mut ranks = assign_ranks(n, &oriented);
+
+    // 跨越多层的边在中间各层插入虚拟节点，使连线绕开其他节点
+    let mut routes: Vec<Vec<usize>> = Vec::with_capacity(edges.len());
+    let mut dag = Vec::new();
+    for (link, &rev) in links.iter().zip(&reversed) {
+        let Some((u, v)) = *link else {
+            routes.push(Vec::new());
+            continue;
+        };
+        if u == v {
+            routes.push(vec![u, v]);
+            continue;
+        }
+        let (a, b) = if rev { (v, u) } else { (u, v) };
+        let mut chain = vec![a];
+        for r in ranks[a] + 1..ranks[b] {
+            chain.push(ranks.len());
+            ranks.push(r);
+        }
+        chain.push(b);
+        dag.extend(chain.windows(2).map(|w| (w[0], w[1])));
+        if rev {
+            chain.reverse();
+        }
+        routes.push(chain);
+    }
+    let layers = order_layers(&ranks, &dag);
+
+    // 主轴为层方向，交叉轴为同层排列方向；虚拟节点不占空间
+    let mut sizes: Vec<(f64, f64)> = nodes
+        .iter()
+        .map(|n| node_size(n, options.font_size))
+        .collect();
+    sizes.resize(ranks.len(), (0.0, 0.0));
+    let horizontal = direction.is_horizontal();
+    let main_size = |i: usize| if horizontal { sizes[i].0 } else { sizes[i].1 };
+    let cross_size = |i: usize| if horizontal { sizes[i].1 } else { sizes[i].0 };
+
+    let extents: Vec<f64> = layers
+        .iter()
+        .map(|layer| layer.iter().map(|&i| main_size(i)).fold(0.0, f64::max))
+        .collect();
+    let totals: Vec<f64> = layers
+        .iter()
+        .map(|layer| {
+            layer.iter().map(|&i| cross_size(i)).sum::<f64>()
+                + options.node_spacing * layer.len().saturating_sub(1) as f64
+        })
+        .collect();
+    let cross_total = totals.iter().copied().fold(0.0, f64::max);
+    let main_total =
+        extents.iter().sum::<f64>() + options.rank_spacing * extents.len().saturating_sub(1) as f64;
+
+    let mut placed = vec![
+        Placed {
+            cx: 0.0,
+            cy: 0.0,
+            w: 0.0,
+            h: 0.0,
+            shape: NodeShape::Rectangle,
+        };
+        ranks.len()
+    ];
+    let mut main_pos = 0.0;
+    for (r, layer) in layers.iter().enumerate() {
+        let main = main_pos + extents[r] / 2.0;
+        let mut cross_pos = (cross_total - totals[r]) / 2.0;
+        for &i in layer {
+            let cross = cross_pos + cross_size(i) / 2.0;
+            cross_pos += cross_size(i) + options.node_spacing;
+            let (x, y) = match direction {
+                Direction::TopBottom => (cross, main),
+                Direction::BottomTop => (cross, main_total - main),
+                Direction::LeftRight => (main, cross),
+                Direction::RightLeft => (main_total - main, cross),
+            };
+            placed[i] = Placed {
+                cx: options.origin.x + x,
+                cy: options.origin.y + y,
+                w: sizes[i].0,
+                h: sizes[i].1,
+                shape: nodes.get(i).map_or(NodeShape::Rectangle, |n| n.shape),
+            };
+        }
+        main_pos += extents[r] + options.rank_spacing;
+    }
+
+    for (node, p) in nodes.iter().zip(&placed) {
+        builder.node(p, &node.label);
+    }
+
+    // 同一对节点之间的多条直连边错开绘制
+    let mut parallel: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    for route in routes.iter().filter(|r| r.len() == 2 && r[0] != r[1]) {
+        let key = (route[0].min(route[1]), route[0].max(route[1]));
+        parallel.entry(key).or_default().1 += 1;
+    }
+
+    for (edge, route) in edges.iter().zip(&routes) {
+        match route.as_slice() {
+            [] => {}
+            [from, to] if from == to => builder.self_loop(&placed[*from], edge),
+            [from, to] => {
+                let key = ((*from).min(*to), (*from).max(*to));
+                let (seen, total) = parallel.get_mut(&key).expect("counted above");
+                let mut offset = (*seen as f64 - (*total as f64 - 1.0) / 2.0) * 10.0;
+                *seen += 1;
+                // 偏移方向以节点对为准，避免反向边偏到同一侧
+                if from > to {
+                    offset = -offset;
+                }
+                builder.edge(&placed[*from], &placed[*to], edge, offset);
+            }
+            _ => {
+                let points: Vec<&Placed> = route.iter().map(|&i| &placed[i]).collect();
+                builder.routed_edge(&points, edge);
+            }
+        }
+    }
+
+    if horizontal {
+        (main_total, cross_total)
+    } else {
+        (cross_total, main_total)
+    }
+}
+
+/// 深度优先搜索找出回边，返回每条边是否需要反转以得到无环图（自环不反转）
+fn break_cycles(n: usize, links: &[Option<(usize, usize)>]) -> Vec<bool> {
+    let mut adjacency = vec![Vec::new(); n];
+    for (idx, link) in links.iter().enumerate() {
+        if let Some((u, v)) = *link
+            && u != v
+        {
+            adjacency[u].push((v, idx));
+        }
+    }
+
+    // 0 未访问，1 在栈中，2 已完成
+    let mut state = vec![0u8; n];
+    let mut reversed = vec![false; links.len()];
+    for start in 0..n {
+        if state[start] != 0 {
+            continue;
+        }
+        let mut stack = vec![(start, 0usize)];
+        state[start] = 1;
+        while let Some(&mut (u, ref mut next)) = stack.last_mut() {
+            if let Some(&(v, idx)) = adjacency[u].get(*next) {
+                *next += 1;
+                match state[v] {
+                    0 => {
+                        state[v] = 1;
+                        stack.push((v, 0));
+                    }
+                    1 => reversed[idx] = true,
+                    _ => {}
+                }
+            } else {
+                state[u] = 2;
+                stack.pop();
+            }
+        }
+    }
+    reversed
+}
+
+/// 最长路径分层
+fn assign_ranks(n: usize, dag: &[(usize, usize)]) -> Vec<usize> {
+    let mut indegree = vec![0usize; n];
+    let mut successors = vec![Vec::new(); n];
+    for &(u, v) in dag {
+        indegree[v] += 1;
+        successors[u].push(v);
+    }
+
+    let mut ranks = vec![0usize; n];
+    let mut queue: std::collections::VecDeque<usize> =
+        (0..n).filter(|&i| indegree[i] == 0).collect();
+    while let Some(u) = queue.pop_front() {
+        for &v in &successors[u] {
+            ranks[v] = ranks[v].max(ranks[u] + 1);
+            indegree[v] -= 1;
+            if indegree[v] == 0 {
+                queue.push_back(v);
+            }
+        }
+    }
+    ranks
+}
+
+/// 按层分组并用重心法减少交叉
+fn order_layers(ranks: &[usize], dag: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let depth = ranks.iter().copied().max().map_or(0, |r| r + 1);
+    let mut layers = vec![Vec::new(); depth];
+    for (i, &r) in ranks.iter().enumerate() {
+        layers[r].push(i);
+    }
+
+    let mut position = vec![0usize; ranks.len()];
+    let update = |layers: &Vec<Vec<usize>>, position: &mut Vec<usize>| {
+        for layer in layers {
+            for (p, &i) in layer.iter().enumerate() {
+                position[i] = p;
+            }
+        }
+    };
+    update(&layers, &mut position);
+
+    for sweep in 0..4 {
+        let downward = sweep % 2 == 0;
+        let order: Vec<usize> = if downward {
+            (1..depth).collect()
+        } else {
+            (0..depth.saturating_sub(1)).rev().collect()
+        };
+        for r in order {
+            let mut keyed: Vec<(f64, usize)> = layers[r]
+                .iter()
+                .map(|&v| {
+                    let neighbors: Vec<usize> = dag
+                        .iter()
+                        .filter_map(|&(a, b)| match downward {
+                            true if b == v => Some(a),
+                            false if a == v => Some(b),
+                            _ => None,
+                        })
+                        .collect();
+                    let key = if neighbors.is_empty() {
+                        position[v] as f64
+                    } else {
+                        neighbors.iter().map(|&u| position[u] as f64).sum::<f64>()
+                            / neighbors.len() as f64
+                    };
+                    (key, v)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            layers[r] = keyed.into_iter().map(|(_, v)| v).collect();
+            update(&layers, &mut position);
+        }
+    }
+    layers
+}
+
+/// 时序图布局：参与者横向排列，消息自上而下
+fn layout_sequence(
+    builder: &mut ElementBuilder,
+    participants: &[DiagramNode],
+    messages: &[DiagramEdge],
+) -> (f64, f64) {
+    let options = builder.options;
+    let font_size = options.font_size;
+    let index: HashMap<&str, usize> = participants
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.id.as_str(), i))
+        .collect();
+    let sizes: Vec<(f64, f64)> = participants
+        .iter()
+        .map(|p| node_size(p, font_size))
+        .collect();
+    let header_height = sizes.iter().map(|s| s.1).fold(0.0, f64::max);
+
+    // 相邻参与者之间的间距需容纳两者之间的消息文本
+    let mut gaps = vec![options.node_spacing; participants.len().saturating_sub(1)];
+    for message in messages {
+        let (Some(&a), Some(&b)) = (
+            index.get(message.from.as_str()),
+            index.get(message.to.as_str()),
+        ) else {
+            continue;
+        };
+        let (lo, hi) = (a.min(b), a.max(b));
+        if hi == lo + 1
+            && let Some(label) = &message.label
+        {
+            let needed = label_size(label, font_size).0 + font_size * 2.0
+                - (sizes[lo].0 + sizes[hi].0) / 2.0;
+            gaps[lo] = gaps[lo].max(needed);
+        }
+    }
+
+    let mut centers = Vec::with_capacity(participants.len());
+    let mut x = 0.0;
+    for (i, size) in sizes.iter().enumerate() {
+        centers.push(x + size.0 / 2.0);
+        x += size.0 + gaps.get(i).copied().unwrap_or(0.0);
+    }
+    let width = x;
+
+    let step = font_size * 3.0;
+    let height = header_height + step * (messages.len() + 1) as f64;
+    let (ox, oy) = (options.origin.x, options.origin.y);
+
+    for (i, participant) in participants.iter().enumerate() {
+        let placed = Placed {
+            cx: ox + centers[i],
+            cy: oy + header_height / 2.0,
+            w: sizes[i].0,
+            h: sizes[i].1,
+            shape: NodeShape::Rectangle,
+        };
+        builder.node(&placed, &participant.label);
+        builder.lifeline(
+            Point::new(ox + centers[i], oy + header_height),
+            Point::new(ox + centers[i], oy + height),
+        );
+    }
+
+    for (k, message) in messages.iter().enumerate() {
+        let (Some(&from), Some(&to)) = (
+            index.get(message.from.as_str()),
+            index.get(message.to.as_str()),
+        ) else {
+            continue;
+        };
+        let y = oy + header_height + step * (k + 1) as f64;
+        builder.message(ox + centers[from], ox + centers[to], y, message);
+    }
+
+    (width, height)
+}
+
+// ---------------------------------------------------------------------------
+// 元素生成
+// ---------------------------------------------------------------------------
+
+struct ElementBuilder<'a> {
+    options: &'a DiagramOptions,
+    created_by: Option<UserId>,
+    elements: Vec<Element>,
+}
+
+impl ElementBuilder<'_> {
+    fn push(&mut self, shape: Shape) {
+        self.elements
+            .push(Element::new(shape, self.created_by.clone()).with_layer(self.options.layer));
+    }
+
+    fn stroke(&self, style: EdgeStyle) -> StrokeStyle {
+        StrokeStyle {
+            color: self.options.stroke_color,
+            width: if style == EdgeStyle::Thick { 3.0 } else { 1.5 },
+            dash: (style == EdgeStyle::Dashed).then(|| vec![6.0, 4.0]),
+            ..Default::default()
+        }
+    }
+
+    fn node(&mut self, p: &Placed, label: &str) {
+        let stroke = Some(self.stroke(EdgeStyle::Solid));
+        let fill = Some(FillStyle {
+            color: self.options.fill_color,
+        });
+        let shape = match p.shape {
+            NodeShape::Rectangle => Shape::Rectangle {
+                rect: Rect::new(p.cx - p.w / 2.0, p.cy - p.h / 2.0, p.w, p.h),
+                stroke,
+                fill,
+            },
+            NodeShape::Ellipse | NodeShape::Circle => Shape::Ellipse {
+                center: Point::new(p.cx, p.cy),
+                radius_x: p.w / 2.0,
+                radius_y: p.h / 2.0,
+                stroke,
+                fill,
+            },
+            NodeShape::Diamond => Shape::Path {
+                points: vec![
+                    Point::new(p.cx, p.cy - p.h / 2.0),
+                    Point::new(p.cx + p.w / 2.0, p.cy),
+                    Point::new(p.cx, p.cy + p.h / 2.0),
+                    Point::new(p.cx - p.w / 2.0, p.cy),
+                    Point::new(p.cx, p.cy - p.h / 2.0),
+                ],
+                stroke: self.stroke(EdgeStyle::Solid),
+            },
+        };
+        self.push(shape);
+        self.label(Point::new(p.cx, p.cy), label);
+    }
+
+    /// 以 center 为中心绘制（可能多行的）文本
+    fn label(&mut self, center: Point, label: &str) {
+        let font_size = self.options.font_size;
+        let lines = label_lines(label);
+        let line_height = font_size * LINE_HEIGHT;
+        let top =
+            center.y - line_height * lines.len() as f64 / 2.0 + (line_height - font_size) / 2.0;
+        for (i, line) in lines.iter().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            self.push(Shape::Text {
+                position: Point::new(
+                    center.x - text_width(line, font_size) / 2.0,
+                    top + line_height * i as f64,
+                ),
+                content: line.to_string(),
+                font_size,
+                font_family: "sans-serif".to_string(),
+                color: self.options.text_color,
+            });
+        }
+    }
+
+    fn arrowhead(&mut self, tip: Point, dx: f64, dy: f64) {
+        let len = (dx * dx + dy * dy).sqrt();
+        if len == 0.0 {
+            return;
+        }
+        let (ux, uy) = (dx / len, dy / len);
+        let wing = |angle: f64| {
+            let (sin, cos) = angle.sin_cos();
+            let (rx, ry) = (ux * cos - uy * sin, ux * sin + uy * cos);
+            Point::new(tip.x - rx * ARROW_SIZE, tip.y - ry * ARROW_SIZE)
+        };
+        self.push(Shape::Path {
+            points: vec![wing(0.45), tip, wing(-0.45)],
+            stroke: self.stroke(EdgeStyle::Solid),
+        });
+    }
+
+    /// 自环画在节点右侧
+    fn self_loop(&mut self, node: &Placed, edge: &DiagramEdge) {
+        let stroke = self.stroke(edge.style);
+        let x = node.cx + node.w / 2.0;
+        let (y1, y2) = (node.cy - node.h / 4.0, node.cy + node.h / 4.0);
+        let loop_x = x + self.options.node_spacing / 2.0;
+        self.push(Shape::Path {
+            points: vec![
+                Point::new(x, y1),
+                Point::new(loop_x, y1),
+                Point::new(loop_x, y2),
+                Point::new(x, y2),
+            ],
+            stroke,
+        });
+        if edge.arrow {
+            self.arrowhead(Point::new(x, y2), -1.0, 0.0);
+        }
+        if let Some(label) = &edge.label {
+            let w = text_width(label, self.options.font_size);
+            self.label(Point::new(loop_x + w / 2.0 + 4.0, node.cy), label);
+        }
+    }
+
+    /// 直连边，`offset` 为垂直于连线方向的平移量
+    fn edge(&mut self, from: &Placed, to: &Placed, edge: &DiagramEdge, offset: f64) {
+        let stroke = self.stroke(edge.style);
+        let (dx, dy) = (to.cx - from.cx, to.cy - from.cy);
+        let len = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+        let (ox, oy) = (-dy / len * offset, dx / len * offset);
+        let shift = |p: Point| Point::new(p.x + ox, p.y + oy);
+
+        let start = shift(from.boundary(dx, dy));
+        let end = shift(to.boundary(-dx, -dy));
+        self.push(Shape::Line { start, end, stroke });
+        if edge.arrow {
+            self.arrowhead(end, dx, dy);
+        }
+        if let Some(label) = &edge.label {
+            let mid = Point::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0);
+            self.label(mid, label);
+        }
+    }
+
+    /// 经过虚拟节点的折线边
+    fn routed_edge(&mut self, route: &[&Placed], edge: &DiagramEdge) {
+        let (first, second) = (route[0], route[1]);
+        let (last, before_last) = (route[route.len() - 1], route[route.len() - 2]);
+
+        let mut points = vec![first.boundary(second.cx - first.cx, second.cy - first.cy)];
+        points.extend(
+            route[1..route.len() - 1]
+                .iter()
+                .map(|p| Point::new(p.cx, p.cy)),
+        );
+        let (dx, dy) = (last.cx - before_last.cx, last.cy - before_last.cy);
+        let end = last.boundary(-dx, -dy);
+        points.push(end);
+
+        let label_at = points[points.len() / 2];
+        self.push(Shape::Path {
+            points,
+            stroke: self.stroke(edge.style),
+        });
+        if edge.arrow {
+            self.arrowhead(end, dx, dy);
+        }
+        if let Some(label) = &edge.label {
+            self.label(label_at, label);
+        }
+    }
+
+    fn lifeline(&mut self, start: Point, end: Point) {
+        let mut stroke = self.stroke(EdgeStyle::Dashed);
+        stroke.width = 1.0;
+        self.push(Shape::Line { start, end, stroke });
+    }
+
+    fn message(&mut self, x1: f64, x2: f64, y: f64, message: &DiagramEdge) {
+        let font_size = self.options.font_size;
+        let stroke = self.stroke(message.style);
+
+        if x1 == x2 {
+            let loop_x = x1 + font_size * 2.0;
+            let y2 = y + font_size;
+            self.push(Shape::Path {
+                points: vec![
+                    Point::new(x1, y),
+                    Point::new(loop_x, y),
+                    Point::new(loop_x, y2),
+                    Point::new(x1, y2),
+                ],
+                stroke,
+            });
+            if message.arrow {
+                self.arrowhead(Point::new(x1, y2), -1.0, 0.0);
+            }
+            if let Some(label) = &message.label {
+                let w = text_width(label, font_size);
+                self.label(Point::new(loop_x + w / 2.0 + 4.0, y), label);
+            }
+            return;
+        }
+
+        let (start, end) = (Point::new(x1, y), Point::new(x2, y));
+        self.push(Shape::Line { start, end, stroke });
+        if message.arrow {
+            self.arrowhead(end, x2 - x1, 0.0);
+        }
+        if let Some(label) = &message.label {
+            self.label(Point::new((x1 + x2) / 2.0, y - font_size * 0.9), label);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Mermaid 解析
+// ---------------------------------------------------------------------------
+
+/// 节点表，保持首次出现顺序
+#[derive(Default)]
+struct NodeTable {
+    nodes: Vec<DiagramNode>,
+    index: HashMap<String, usize>,
+}
+
+impl NodeTable {
+    /// 登记节点；显式给出的标签和形状覆盖已有定义
+    fn declare(
+        &mut self,
+        id: &str,
+        label: Option<String>,
+        shape: Option<NodeShape>,
+        default: NodeShape,
+    ) {
+        match self.index.get(id) {
+            Some(&i) => {
+                if let Some(label) = label {
+                    self.nodes[i].label = label;
+                }
+                if let Some(shape) = shape {
+                    self.nodes[i].shape = shape;
+                }
+            }
+            None => {
+                self.index.insert(id.to_string(), self.nodes.len());
+                self.nodes.push(DiagramNode {
+                    id: id.to_string(),
+                    label: label.unwrap_or_else(|| id.to_string()),
+                    shape: shape.unwrap_or(default),
+                });
+            }
+        }
+    }
+}
+
+fn clean_label(raw: &str) -> String {
+    let trimmed = raw.trim();
+    let unquoted = trimmed
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(trimmed);
+    unquoted
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("<br>", "\n")
+}
+
+fn parse_mermaid(source: &str) -> Result<Diagram, CanvasError> {
+    let mut lines = source
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("%%"));
+    let header = lines
+        .next()
+        .ok_or_else(|| CanvasError::InvalidDiagram("图表为空".to_string()))?;
+
+    // 首行可能用分号接续语句，如 `graph TD; A-->B`
+    let mut segments = header.split(';');
+    let head = segments.next().unwrap_or_default().trim();
+    let inline: Vec<&str> = segments.collect();
+    let mut tokens = head.split_whitespace();
+    let kind = tokens.next().unwrap_or_default().to_lowercase();
+
+    match kind.as_str() {
+        "flowchart" | "graph" => {
+            let direction = tokens.next().and_then(Direction::parse).unwrap_or_default();
+            let statements = inline
+                .into_iter()
+                .chain(lines.flat_map(|l| l.split(';')))
+                .map(str::trim)
+                .filter(|s| !s.is_empty());
+            parse_flowchart(direction, statements)
+        }
+        "sequencediagram" => Ok(parse_sequence(inline.into_iter().chain(lines))),
+        other => Err(CanvasError::InvalidDiagram(format!(
+            "不支持的 Mermaid 图表类型: {}",
+            other
+        ))),
+    }
+}
+
+fn parse_flowchart<'a>(
+    direction: Direction,
+    statements: impl Iterator<Item = &'a str>,
+) -> Result<Diagram, CanvasError> {
+    const IGNORED: &[&str] = &[
+        "subgraph",
+        "end",
+        "classdef",
+        "class",
+        "style",
+        "linkstyle",
+        "click",
+        "direction",
+    ];
+
+    let mut table = NodeTable::default();
+    let mut edges = Vec::new();
+
+    for statement in statements {
+        let keyword = statement
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        if IGNORED.contains(&keyword.as_str()) {
+            continue;
+        }
+
+        let mut parser = FlowParser::new(statement);
+        let Some(mut previous) = parser.node_group(&mut table) else {
+            continue;
+        };
+        while let Some(link) = parser.link() {
+            let Some(next) = parser.node_group(&mut table) else {
+                break;
+            };
+            for from in &previous {
+                for to in &next {
+                    edges.push(DiagramEdge {
+                        from: from.clone(),
+                        to: to.clone(),
+                        label: link.label.clone(),
+                        arrow: link.arrow,
+                        style: link.style,
+                    });
+                }
+            }
+            previous = next;
+        }
+    }
+
+    Ok(Diagram::Graph {
+        direction,
+        nodes: table.nodes,
+        edges,
+    })
+}
+
+struct Link {
+    arrow: bool,
+    label: Option<String>,
+    style: EdgeStyle,
+}
+
+/// Mermaid 流程图语句解析器
+struct FlowParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl FlowParser {
+    /// 节点形状定界符，较长的在前
+    const SHAPES: &'static [(&'static str, &'static [&'static str], NodeShape)] = &[
+        ("(((", &[")))"], NodeShape::Circle),
+        ("((", &["))"], NodeShape::Circle),
+        ("([", &["])"], NodeShape::Ellipse),
+        ("[(", &[")]"], NodeShape::Rectangle),
+        ("[[", &["]]"], NodeShape::Rectangle),
+        ("{{", &["}}"], NodeShape::Diamond),
+        ("[/", &["/]", "\\]"], NodeShape::Rectangle),
+        ("[\\", &["\\]", "/]"], NodeShape::Rectangle),
+        ("[", &["]"], NodeShape::Rectangle),
+        ("(", &[")"], NodeShape::Rectangle),
+        ("{", &["}"], NodeShape::Diamond),
+        (">", &["]"], NodeShape::Rectangle),
+    ];
+
+    fn new(s: &str) -> Self {
+        Self {
+            chars: s.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_at(i) == Some(c))
+    }
+
+    /// 从当前位置查找 pattern，返回相对偏移
+    fn find(&self, pattern: &str) -> Option<usize> {
+        let pattern: Vec<char> = pattern.chars().collect();
+        self.chars[self.pos..]
+            .windows(pattern.len())
+            .position(|w| w == pattern.as_slice())
+    }
+
+    fn take(&mut self, len: usize) -> String {
+        let end = (self.pos + len).min(self.chars.len());
+        let s: String = self.chars[self.pos..end].iter().collect();
+        self.pos = end;
+        s
+    }
+
+    fn node_group(&mut self, table: &mut NodeTable) -> Option<Vec<String>> {
+        let mut ids = vec![self.node(table)?];
+        loop {
+            self.skip_ws();
+            if self.peek() != Some('&') {
+                break;
+            }
+            self.pos += 1;
+            ids.push(self.node(table)?);
+        }
+        Some(ids)
+    }
+
+    fn node(&mut self, table: &mut NodeTable) -> Option<String> {
+        self.skip_ws();
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            let id_char = c.is_alphanumeric()
+                || c == '_'
+                || (c == '-' && self.peek_at(1).is_some_and(char::is_alphanumeric));
+            if !id_char {
+                break;
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return None;
+        }
+        let id: String = self.chars[start..self.pos].iter().collect();
+
+        let mut label = None;
+        let mut shape = None;
+        if let Some(&(open, closers, node_shape)) = Self::SHAPES
+            .iter()
+            .find(|(open, _, _)| self.starts_with(open))
+        {
+            self.pos += open.chars().count();
+            let close = closers
+                .iter()
+                .filter_map(|c| self.find(c).map(|at| (at, c.chars().count())))
+                .min();
+            let (at, close_len) = close.unwrap_or((self.chars.len() - self.pos, 0));
+            label = Some(clean_label(&self.take(at)));
+            self.pos += close_len;
+            shape = Some(node_shape);
+        }
+
+        // 跳过 `:::className`
+        if self.starts_with(":::") {
+            self.pos += 3;
+            while self
+                .peek()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-')
+            {
+                self.pos += 1;
+            }
+        }
+
+        table.declare(&id, label, shape, NodeShape::Rectangle);
+        Some(id)
+    }
+
+    fn operator(&mut self) -> (String, bool) {
+        let mut op = String::new();
+        if self.peek() == Some('<') {
+            self.pos += 1;
+        }
+        while let Some(c) = self.peek().filter(|c| matches!(c, '-' | '=' | '.')) {
+            op.push(c);
+            self.pos += 1;
+        }
+        let arrow = match self.peek() {
+            Some('>') => {
+                self.pos += 1;
+                true
+            }
+            Some('o' | 'x')
+                if op.len() >= 2
+                    && self
+                        .peek_at(1)
+                        .is_none_or(|c| c.is_whitespace() || c == '|') =>
+            {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        };
+        (op, arrow)
+    }
+
+    fn link(&mut self) -> Option<Link> {
+        self.skip_ws();
+        let start = self.pos;
+        let (mut op, mut arrow) = self.operator();
+        if op.len() < 2 {
+            self.pos = start;
+            return None;
+        }
+
+        let mut label = None;
+        // `A -- text --> B` 形式的行内标签
+        if !arrow
+            && matches!(op.as_str(), "--" | "==" | "-.")
+            && self.peek().is_some_and(char::is_whitespace)
+        {
+            let close = ["-->", "---", "==>", "===", ".->", ".-", "--o", "--x"]
+                .iter()
+                .filter_map(|p| self.find(p))
+                .min();
+            if let Some(at) = close {
+                label = Some(clean_label(&self.take(at)));
+                let (close_op, close_arrow) = self.operator();
+                op.push_str(&close_op);
+                arrow = close_arrow;
+            }
+        }
+
+        self.skip_ws();
+        if self.peek() == Some('|') {
+            self.pos += 1;
+            let at = self.find("|").unwrap_or(self.chars.len() - self.pos);
+            label = Some(clean_label(&self.take(at)));
+            self.pos += 1;
+        }
+
+        let style = if op.contains('.') {
+            EdgeStyle::Dashed
+        } else if op.contains('=') {
+            EdgeStyle::Thick
+        } else {
+            EdgeStyle::Solid
+        };
+        Some(Link {
+            arrow,
+            label: label.filter(|l| !l.is_empty()),
+            style,
+        })
+    }
+}
+
+fn parse_sequence<'a>(lines: impl Iterator<Item = &'a str>) -> Diagram {
+    const IGNORED: &[&str] = &[
+        "note",
+        "loop",
+        "alt",
+        "else",
+        "opt",
+        "par",
+        "and",
+        "end",
+        "rect",
+        "critical",
+        "break",
+        "activate",
+        "deactivate",
+        "autonumber",
+        "title",
+        "box",
+        "create",
+        "destroy",
+        "link",
+        "links",
+    ];
+    // 较长的箭头在前
+    const ARROWS: &[(&str, bool, EdgeStyle)] = &[
+        ("-->>", true, EdgeStyle::Dashed),
+        ("->>", true, EdgeStyle::Solid),
+        ("--x", true, EdgeStyle::Dashed),
+        ("-x", true, EdgeStyle::Solid),
+        ("--)", true, EdgeStyle::Dashed),
+        ("-)", true, EdgeStyle::Solid),
+        ("-->", false, EdgeStyle::Dashed),
+        ("->", false, EdgeStyle::Solid),
+    ];
+
+    let mut table = NodeTable::default();
+    let mut messages = Vec::new();
+
+    for line in lines.map(str::trim).filter(|l| !l.is_empty()) {
+        let keyword = line
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        if keyword == "participant" || keyword == "actor" {
+            let rest = line[keyword.len()..].trim();
+            let (id, label) = match rest.split_once(" as ") {
+                Some((id, label)) => (id.trim(), Some(clean_label(label))),
+                None => (rest, None),
+            };
+            table.declare(id, label, None, NodeShape::Rectangle);
+            continue;
+        }
+        if IGNORED.contains(&keyword.as_str()) {
+            continue;
+        }
+
+        let found = line.char_indices().find_map(|(i, _)| {
+            ARROWS
+                .iter()
+                .find(|(arrow, _, _)| line[i..].starts_with(arrow))
+                .map(|a| (i, a))
+        });
+        let Some((at, &(arrow, has_head, style))) = found else {
+            continue;
+        };
+        let from = line[..at].trim();
+        let rest = line[at + arrow.len()..].trim_start_matches(['+', '-']);
+        let (to, text) = match rest.split_once(':') {
+            Some((to, text)) => (to.trim(), Some(clean_label(text))),
+            None => (rest.trim(), None),
+        };
+        if from.is_empty() || to.is_empty() {
+            continue;
+        }
+
+        table.declare(from, None, None, NodeShape::Rectangle);
+        table.declare(to, None, None, NodeShape::Rectangle);
+        messages.push(DiagramEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label: text.filter(|t| !t.is_empty()),
+            arrow: has_head,
+            style,
+        });
+    }
+
+    Diagram::Sequence {
+        participants: table.nodes,
+        messages,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Graphviz DOT 解析
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Id(String),
+    Edge,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Eq,
+    Sep,
+    Colon,
+}
+
+fn tokenize_dot(source: &str) -> Result<Vec<Token>, CanvasError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '-' if matches!(next, Some('>') | Some('-')) => {
+                tokens.push(Token::Edge);
+                i += 2;
+            }
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' | ':' => {
+                tokens.push(match c {
+                    '{' => Token::LBrace,
+                    '}' => Token::RBrace,
+                    '[' => Token::LBracket,
+                    ']' => Token::RBracket,
+                    '=' => Token::Eq,
+                    ':' => Token::Colon,
+                    _ => Token::Sep,
+                });
+                i += 1;
+            }
+            '"' => {
+                let mut value = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && chars.get(i + 1) == Some(&'"') {
+                        value.push('"');
+                        i += 2;
+                        continue;
+                    }
+                    value.push(chars[i]);
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(CanvasError::InvalidDiagram("字符串未闭合".to_string()));
+                }
+                i += 1;
+                tokens.push(Token::Id(value));
+            }
+            '<' => {
+                // HTML 标签：去掉标记，仅保留文本
+                let mut depth = 0;
+                let mut value = String::new();
+                let mut in_tag = false;
+                while i < chars.len() {
+                    match chars[i] {
+                        '<' => {
+                            depth += 1;
+                            if depth > 1 {
+                                in_tag = true;
+                            }
+                        }
+                        '>' => {
+                            depth -= 1;
+                            in_tag = false;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        ch if !in_tag && depth == 1 => value.push(ch),
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                i += 1;
+                tokens.push(Token::Id(value.trim().to_string()));
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let start = i;
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Id(chars[start..i].iter().collect()));
+            }
+            _ => {
+                return Err(CanvasError::InvalidDiagram(format!(
+                    "无法识别的字符: {}",
+                    c
+                )));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// DOT 属性列表
+type Attrs = Vec<(String, String)>;
+
+fn attr<'a>(attrs: &'a Attrs, key: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .rev()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v.as_str())
+}
+
+fn dot_label(raw: &str) -> String {
+    raw.replace("\\n", "\n")
+        .replace("\\l", "\n")
+        .replace("\\r", "\n")
+        .trim_end_matches('\n')
+        .to_string()
+}
+
+fn dot_shape(shape: &str) -> NodeShape {
+    match shape.to_lowercase().as_str() {
+        "box" | "rect" | "rectangle" | "square" | "record" | "mrecord" | "note" | "tab"
+        | "folder" | "component" | "cylinder" | "plaintext" | "plain" | "none" => {
+            NodeShape::Rectangle
+        }
+        "circle" | "doublecircle" | "point" => NodeShape::Circle,
+        "diamond" | "mdiamond" => NodeShape::Diamond,
+        _ => NodeShape::Ellipse,
+    }
+}
+
+struct DotParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl DotParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn id(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.pos += 1;
+                Some(id)
+            }
+            _ => None,
+        }
+    }
+
+    /// 解析零个或多个 `[k=v, ...]`
+    fn attrs(&mut self) -> Attrs {
+        let mut attrs = Vec::new();
+        while self.peek() == Some(&Token::LBracket) {
+            self.pos += 1;
+            loop {
+                match self.next() {
+                    Some(Token::RBracket) | None => break,
+                    Some(Token::Id(key)) if self.peek() == Some(&Token::Eq) => {
+                        self.pos += 1;
+                        if let Some(value) = self.id() {
+                            attrs.push((key, value));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        attrs
+    }
+
+    /// 节点 ID，忽略端口 `a:port:compass`
+    fn node_id(&mut self) -> Option<String> {
+        let id = self.id()?;
+        while self.peek() == Some(&Token::Colon) {
+            self.pos += 1;
+            self.id();
+        }
+        Some(id)
+    }
+}
+
+fn parse_dot(source: &str) -> Result<Diagram, CanvasError> {
+    let mut parser = DotParser {
+        tokens: tokenize_dot(source)?,
+        pos: 0,
+    };
+
+    let mut keyword = parser.id().unwrap_or_default().to_lowercase();
+    if keyword == "strict" {
+        keyword = parser.id().unwrap_or_default().to_lowercase();
+    }
+    let directed = match keyword.as_str() {
+        "digraph" => true,
+        "graph" => false,
+        _ => {
+            return Err(CanvasError::InvalidDiagram(
+                "DOT 源码需以 graph 或 digraph 开头".to_string(),
+            ));
+        }
+    };
+    parser.id();
+    if parser.next() != Some(Token::LBrace) {
+        return Err(CanvasError::InvalidDiagram("缺少 '{'".to_string()));
+    }
+
+    let mut table = NodeTable::default();
+    let mut edges = Vec::new();
+    let mut direction = Direction::TopBottom;
+    let mut node_defaults: Attrs = Vec::new();
+    let mut edge_defaults: Attrs = Vec::new();
+    let mut depth = 1;
+
+    while depth > 0 {
+        let Some(token) = parser.peek().cloned() else {
+            return Err(CanvasError::InvalidDiagram("缺少 '}'".to_string()));
+        };
+        let Token::Id(id) = token else {
+            match token {
+                Token::LBrace => depth += 1,
+                Token::RBrace => depth -= 1,
+                _ => {}
+            }
+            parser.pos += 1;
+            continue;
+        };
+
+        match id.to_lowercase().as_str() {
+            "subgraph" => {
+                parser.pos += 1;
+                if parser.peek() != Some(&Token::LBrace) {
+                    parser.id();
+                }
+                continue;
+            }
+            "node" | "edge" | "graph"
+                if parser.tokens.get(parser.pos + 1) == Some(&Token::LBracket) =>
+            {
+                parser.pos += 1;
+                let attrs = parser.attrs();
+                match id.to_lowercase().as_str() {
+                    "node" => node_defaults.extend(attrs),
+                    "edge" => edge_defaults.extend(attrs),
+                    _ => {
+                        if let Some(d) = attr(&attrs, "rankdir").and_then(Direction::parse) {
+                            direction = d;
+                        }
+                    }
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        // 图属性 `rankdir=LR`
+        if parser.tokens.get(parser.pos + 1) == Some(&Token::Eq) {
+            parser.pos += 2;
+            let value = parser.id().unwrap_or_default();
+            if id.eq_ignore_ascii_case("rankdir")
+                && let Some(d) = Direction::parse(&value)
+            {
+                direction = d;
+            }
+            continue;
+        }
+
+        let Some(first) = parser.node_id() else {
+            parser.pos += 1;
+            continue;
+        };
+        let mut chain = vec![first];
+        while parser.peek() == Some(&Token::Edge) {
+            parser.pos += 1;
+            match parser.node_id() {
+                Some(next) => chain.push(next),
+                None => break,
+            }
+        }
+        let attrs = parser.attrs();
+
+        let default_shape = attr(&node_defaults, "shape")
+            .map(dot_shape)
+            .unwrap_or(NodeShape::Ellipse);
+        if chain.len() == 1 {
+            let label = attr(&attrs, "label")
+                .or_else(|| attr(&node_defaults, "label"))
+                .map(dot_label);
+            let shape = attr(&attrs, "shape").map(dot_shape);
+            table.declare(&chain[0], label, shape, default_shape);
+            continue;
+        }
+
+        for id in &chain {
+            table.declare(id, None, None, default_shape);
+        }
+        let mut merged = edge_defaults.clone();
+        merged.extend(attrs);
+        let style = match attr(&merged, "style").map(str::to_lowercase).as_deref() {
+            Some("dashed" | "dotted") => EdgeStyle::Dashed,
+            Some("bold") => EdgeStyle::Thick,
+            _ => EdgeStyle::Solid,
+        };
+        let arrow = directed
+            && !matches!(attr(&merged, "dir"), Some("none"))
+            && !matches!(attr(&merged, "arrowhead"), Some("none"));
+        let back = matches!(attr(&merged, "dir"), Some("back"));
+        for pair in chain.windows(2) {
+            let (from, to) = if back {
+                (&pair[1], &pair[0])
+            } else {
+                (&pair[0], &pair[1])
+            };
+            edges.push(DiagramEdge {
+                from: from.clone(),
+                to: to.clone(),
+                label: attr(&merged, "label").map(dot_label),
+                arrow,
+                style,
+            });
+        }
+    }
+
+    Ok(Diagram::Graph {
+        direction,
+        nodes: table.nodes,
+        edges,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(diagram: &Diagram) -> (&[DiagramNode], &[DiagramEdge]) {
+        match diagram {
+            Diagram::Graph { nodes, edges, .. } => (nodes, edges),
+            _ => panic!("expected graph"),
+        }
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            DiagramFormat::detect("graph TD\nA-->B"),
+            Some(DiagramFormat::Mermaid)
+        );
+        assert_eq!(
+            DiagramFormat::detect("graph TD\nA{x}"),
+            Some(DiagramFormat::Mermaid)
+        );
+        assert_eq!(
+            DiagramFormat::detect("graph G { a -- b }"),
+            Some(DiagramFormat::Dot)
+        );
+        assert_eq!(
+            DiagramFormat::detect("digraph { a -> b }"),
+            Some(DiagramFormat::Dot)
+        );
+        assert_eq!(
+            DiagramFormat::detect("sequenceDiagram\nA->>B: hi"),
+            Some(DiagramFormat::Mermaid)
+        );
+        assert_eq!(DiagramFormat::detect("hello"), None);
+    }
+
+    #[test]
+    fn test_parse_mermaid_flowchart() {
+        let source = "flowchart LR\n  A[Start] --> B{Is it?}\n  B -->|Yes| C((Done))\n  B -- No --> D[Retry] -.-> A\n  C & D --- E";
+        let diagram = Diagram::parse(source, None).unwrap();
+        let Diagram::Graph { direction, .. } = &diagram else {
+            panic!("expected graph");
+        };
+        assert_eq!(*direction, Direction::LeftRight);
+
+        let (nodes, edges) = graph(&diagram);
+        let ids: Vec<_> = nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, ["A", "B", "C", "D", "E"]);
+        assert_eq!(nodes[1].shape, NodeShape::Diamond);
+        assert_eq!(nodes[1].label, "Is it?");
+        assert_eq!(nodes[2].shape, NodeShape::Circle);
+
+        assert_eq!(edges.len(), 6);
+        assert_eq!(edges[1].label.as_deref(), Some("Yes"));
+        assert_eq!(edges[2].label.as_deref(), Some("No"));
+        assert!(edges[2].arrow);
+        assert_eq!(edges[3].style, EdgeStyle::Dashed);
+        assert!(!edges[4].arrow);
+    }
+
+    #[test]
+    fn test_parse_mermaid_sequence() {
+        let source = "sequenceDiagram\n  participant U as User\n  U->>+S: request\n  S-->>-U: response\n  Note over U,S: ignored\n  S->S: self";
+        let Diagram::Sequence {
+            participants,
+            messages,
+        } = Diagram::parse(source, None).unwrap()
+        else {
+            panic!("expected sequence");
+        };
+        assert_eq!(participants.len(), 2);
+        assert_eq!(participants[0].label, "User");
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].to, "S");
+        assert_eq!(messages[1].style, EdgeStyle::Dashed);
+        assert!(!messages[2].arrow);
+    }
+
+    #[test]
+    fn test_parse_dot() {
+        let source = r#"
+            digraph G {
+                rankdir=LR;
+                node [shape=box];
+                start [label="Start\nhere", shape=ellipse];
+                start -> parse -> done [label="ok"];
+                parse -> start [style=dashed];
+                subgraph cluster_0 { x; y }
+            }
+        "#;
+        let diagram = Diagram::parse(source, None).unwrap();
+        let (nodes, edges) = graph(&diagram);
+        assert_eq!(nodes.len(), 5);
+        assert_eq!(nodes[0].label, "Start\nhere");
+        assert_eq!(nodes[0].shape, NodeShape::Ellipse);
+        assert_eq!(nodes[1].shape, NodeShape::Rectangle);
+        assert_eq!(edges.len(), 3);
+        assert_eq!(edges[1].label.as_deref(), Some("ok"));
+        assert_eq!(edges[2].style, EdgeStyle::Dashed);
+    }
+
+    #[test]
+    fn test_layout_ranks_and_elements() {
+        let source = "graph TD\nA-->B\nA-->C\nB-->D\nC-->D\nD-->A";
+        let layout =
+            DiagramGenerator::generate(source, None, &DiagramOptions::default(), None).unwrap();
+
+        // 4 个节点各一个图形一个文本，5 条边各一条线一个箭头
+        assert_eq!(layout.elements.len(), 4 * 2 + 5 * 2);
+        assert!(layout.width > 0.0 && layout.height > 0.0);
+
+        let text_y = |content: &str| {
+            layout
+                .elements
+                .iter()
+                .find_map(|e| match &e.shape {
+                    Shape::Text {
+                        content: c,
+                        position,
+                        ..
+                    } if c == content => Some(position.y),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        // 回边 D-->A 不影响分层
+        assert!(text_y("A") < text_y("B"));
+        assert_eq!(text_y("B"), text_y("C"));
+        assert!(text_y("C") < text_y("D"));
+
+        // 绘制顺序严格递增
+        assert!(
+            layout
+                .elements
+                .windows(2)
+                .all(|w| w[0].created_at < w[1].created_at)
+        );
+    }
+
+    #[test]
+    fn test_invalid_diagram() {
+        assert!(Diagram::parse("pie title x", Some(DiagramFormat::Mermaid)).is_err());
+        assert!(Diagram::parse("digraph { a -> b", None).is_err());
+        assert!(Diagram::parse("graph TD", None).is_err());
+    }
+}
@@ -5,6 +5,8 @@ use chrono::Utc;
 use std::collections::VecDeque;
 
 /// 绘图历史记录
+///
+/// 单用户的本地撤销栈；协作画布按用户撤销请使用 [`crate::crdt::CrdtDocument::undo`]。
 #[derive(Debug, Clone)]
 pub struct DrawHistory {
     undo_stack: VecDeque<DrawAction>,
@@ -0,0 +1,775 @@
+//! 画布导出模块
+//!
+//! 将画布渲染为 SVG、PNG 或 PDF：
+//! - SVG 直接由画布元素生成，是其它格式的中间表示
+//! - PNG 通过 resvg 按指定缩放比例光栅化
+//! - PDF 通过 svg2pdf 输出矢量文档，文本转换为路径以保证字体一致
+//!
+//! 内嵌 SVG 在输出前清洗，解析时只允许 `data:` 图片，导出不会读取服务器上的文件。
+
+use crate::canvas::CanvasError;
+use crate::types::*;
+use resvg::usvg::{self, PostProcessingSteps, TreeParsing, TreePostProc, fontdb};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::sync::OnceLock;
+use tracing::warn;
+
+/// 单边最大像素数，防止超大画布或缩放比例耗尽内存
+const MAX_RASTER_DIMENSION: f64 = 16384.0;
+
+/// 内嵌 SVG 中不允许出现的元素（脚本和可嵌入 HTML 的容器）
+const FORBIDDEN_SVG_ELEMENTS: &[&str] = &[
+    "script",
+    "foreignobject",
+    "iframe",
+    "object",
+    "embed",
+    "handler",
+    "listener",
+];
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Svg,
+    Png,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Svg => "image/svg+xml",
+            Self::Png => "image/png",
+            Self::Pdf => "application/pdf",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+            Self::Pdf => "pdf",
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = CanvasError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "svg" => Ok(Self::Svg),
+            "png" => Ok(Self::Png),
+            "pdf" => Ok(Self::Pdf),
+            other => Err(CanvasError::InvalidOperation(format!(
+                "不支持的导出格式: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// 导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// 光栅化缩放比例（仅 PNG）
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    /// 是否绘制画布背景色
+    #[serde(default = "default_background")]
+    pub background: bool,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+fn default_background() -> bool {
+    true
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            scale: default_scale(),
+            background: default_background(),
+        }
+    }
+}
+
+/// 导出结果
+#[derive(Debug, Clone)]
+pub struct ExportOutput {
+    pub format: ExportFormat,
+    pub data: Vec<u8>,
+    /// 输出尺寸（PNG 为像素，其它格式为画布单位）
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ExportOutput {
+    pub fn mime_type(&self) -> &'static str {
+        self.format.mime_type()
+    }
+}
+
+/// 画布导出器
+pub struct CanvasExporter;
+
+impl CanvasExporter {
+    /// 按格式导出画布
+    pub fn export(
+        state: &CanvasState,
+        format: ExportFormat,
+        options: &ExportOptions,
+    ) -> Result<ExportOutput, CanvasError> {
+        let svg = Self::render_svg(state, options);
+        let (width, height) = (state.width.ceil() as u32, state.height.ceil() as u32);
+
+        match format {
+            ExportFormat::Svg => Ok(ExportOutput {
+                format,
+                data: svg.into_bytes(),
+                width,
+                height,
+            }),
+            ExportFormat::Png => {
+                let (data, width, height) = Self::rasterize(&svg, options.scale)?;
+                Ok(ExportOutput {
+                    format,
+                    data,
+                    width,
+                    height,
+                })
+            }
+            ExportFormat::Pdf => {
+                let tree = Self::parse_svg(&svg)?;
+                Ok(ExportOutput {
+                    format,
+                    data: svg2pdf::convert_tree(&tree, svg2pdf::Options::default()),
+                    width,
+                    height,
+                })
+            }
+        }
+    }
+
+    /// 渲染为 SVG 文档
+    ///
+    /// 图层按 `order` 升序绘制，同一图层内的元素按创建时间排序；
+    /// 隐藏的图层和元素不输出，图层透明度作用于整组元素。
+    pub fn render_svg(state: &CanvasState, options: &ExportOptions) -> String {
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = state.width,
+            h = state.height,
+        );
+
+        if options.background && state.background_color.a > 0 {
+            let _ = write!(
+                svg,
+                r#"<rect x="0" y="0" width="{}" height="{}" {}/>"#,
+                state.width,
+                state.height,
+                paint_attrs("fill", &state.background_color),
+            );
+        }
+
+        let mut layer_indices: Vec<usize> = (0..state.layers.len()).collect();
+        layer_indices.sort_by_key(|&idx| state.layers[idx].order);
+
+        for idx in layer_indices {
+            let layer = &state.layers[idx];
+            if !layer.visible {
+                continue;
+            }
+            let _ = write!(
+                svg,
+                r#"<g id="layer-{}" opacity="{}">"#,
+                escape_xml(&layer.id),
+                layer.opacity
+            );
+            for element in Self::layer_elements(state, |l| l == idx) {
+                Self::write_element(&mut svg, element);
+            }
+            svg.push_str("</g>");
+        }
+
+        // 图层已被删除的孤立元素绘制在最上层，避免静默丢失
+        let layer_count = state.layers.len();
+        for element in Self::layer_elements(state, |l| l >= layer_count) {
+            Self::write_element(&mut svg, element);
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+
+    fn layer_elements(state: &CanvasState, filter: impl Fn(usize) -> bool) -> Vec<&Element> {
+        let mut elements: Vec<&Element> = state
+            .elements
+            .values()
+            .filter(|e| e.visible && filter(e.layer))
+            .collect();
+        elements.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        elements
+    }
+
+    fn write_element(svg: &mut String, element: &Element) {
+        let t = &element.transform;
+        let _ = write!(
+            svg,
+            r#"<g opacity="{}" transform="translate({} {}) rotate({}) scale({} {})">"#,
+            element.opacity,
+            t.translate_x,
+            t.translate_y,
+            t.rotate.to_degrees(),
+            t.scale_x,
+            t.scale_y,
+        );
+        Self::write_shape(svg, &element.shape);
+        svg.push_str("</g>");
+    }
+
+    fn write_shape(svg: &mut String, shape: &Shape) {
+        match shape {
+            Shape::Path { points, stroke } => {
+                let Some((first, rest)) = points.split_first() else {
+                    return;
+                };
+                let mut d = format!("M {} {}", first.x, first.y);
+                for p in rest {
+                    let _ = write!(d, " L {} {}", p.x, p.y);
+                }
+                let _ = write!(
+                    svg,
+                    r#"<path d="{}" fill="none" {}/>"#,
+                    d,
+                    stroke_attrs(stroke)
+                );
+            }
+            Shape::Line { start, end, stroke } => {
+                let _ = write!(
+                    svg,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}" {}/>"#,
+                    start.x,
+                    start.y,
+                    end.x,
+                    end.y,
+                    stroke_attrs(stroke)
+                );
+            }
+            Shape::Rectangle { rect, stroke, fill } => {
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                    rect.x,
+                    rect.y,
+                    rect.width,
+                    rect.height,
+                    fill_stroke_attrs(stroke.as_ref(), fill.as_ref())
+                );
+            }
+            Shape::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                stroke,
+                fill,
+            } => {
+                let _ = write!(
+                    svg,
+                    r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}" {}/>"#,
+                    center.x,
+                    center.y,
+                    radius_x,
+                    radius_y,
+                    fill_stroke_attrs(stroke.as_ref(), fill.as_ref())
+                );
+            }
+            Shape::Text {
+                position,
+                content,
+                font_size,
+                font_family,
+                color,
+            } => {
+                // position 为文本框左上角（与命中检测一致），换算为基线坐标
+                for (i, line) in content.lines().enumerate() {
+                    let baseline = position.y + font_size * (0.8 + 1.2 * i as f64);
+                    let _ = write!(
+                        svg,
+                        r#"<text x="{}" y="{}" font-size="{}" font-family="{}" {}>{}</text>"#,
+                        position.x,
+                        baseline,
+                        font_size,
+                        escape_xml(font_family),
+                        paint_attrs("fill", color),
+                        escape_xml(line)
+                    );
+                }
+            }
+            Shape::Image {
+                rect,
+                data,
+                mime_type,
+            } => {
+                let href = if data.starts_with("data:") {
+                    data.clone()
+                } else {
+                    format!("data:{};base64,{}", mime_type, data)
+                };
+                let _ = write!(
+                    svg,
+                    r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none" xlink:href="{}"/>"#,
+                    rect.x,
+                    rect.y,
+                    rect.width,
+                    rect.height,
+                    escape_xml(&href)
+                );
+            }
+            Shape::Svg {
+                position,
+                svg_content,
+                scale,
+            } => match sanitize_svg(svg_content) {
+                Ok(content) => {
+                    let _ = write!(
+                        svg,
+                        r#"<g transform="translate({} {}) scale({})">{}</g>"#,
+                        position.x, position.y, scale, content
+                    );
+                }
+                Err(e) => warn!("跳过无法解析的内嵌 SVG: {}", e),
+            },
+        }
+    }
+
+    fn parse_svg(svg: &str) -> Result<usvg::Tree, CanvasError> {
+        let options = usvg::Options {
+            // 只解析 data: 图片，拒绝文件路径和其它外部引用
+            image_href_resolver: usvg::ImageHrefResolver {
+                resolve_data: usvg::ImageHrefResolver::default_data_resolver(),
+                resolve_string: Box::new(|_, _| None),
+            },
+            ..Default::default()
+        };
+        let mut tree = usvg::Tree::from_str(svg, &options)
+            .map_err(|e| CanvasError::InvalidOperation(format!("SVG 解析失败: {}", e)))?;
+        tree.postprocess(PostProcessingSteps::default(), font_database());
+        Ok(tree)
+    }
+
+    fn rasterize(svg: &str, scale: f64) -> Result<(Vec<u8>, u32, u32), CanvasError> {
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(CanvasError::InvalidOperation(format!(
+                "无效的缩放比例: {}",
+                scale
+            )));
+        }
+
+        let tree = Self::parse_svg(svg)?;
+        let size = tree.size;
+        let width = (size.width() as f64 * scale).ceil();
+        let height = (size.height() as f64 * scale).ceil();
+        if width > MAX_RASTER_DIMENSION || height > MAX_RASTER_DIMENSION {
+            return Err(CanvasError::InvalidOperation(format!(
+                "导出尺寸过大: {}x{}",
+                width, height
+            )));
+        }
+
+        let (width, height) = (width as u32, height as u32);
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height).ok_or_else(|| {
+            CanvasError::InvalidOperation(format!("无法创建 {}x{} 画布", width, height))
+        })?;
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::from_scale(scale as f32, scale as f32),
+            &mut pixmap.as_mut(),
+        );
+
+        let data = pixmap
+            .encode_png()
+            .map_err(|e| CanvasError::Internal(anyhow::anyhow!("PNG 编码失败: {}", e)))?;
+        Ok((data, width, height))
+    }
+}
+
+/// 系统字体库只加载一次
+fn font_database() -> &'static fontdb::Database {
+    static FONTS: OnceLock<fontdb::Database> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let mut db = fontdb::Database::new();
+        db.load_system_fonts();
+        db
+    })
+}
+
+fn paint_attrs(kind: &str, color: &Color) -> String {
+    format!(
+        r#"{kind}="rgb({},{},{})" {kind}-opacity="{}""#,
+        color.r,
+        color.g,
+        color.b,
+        color.a as f64 / 255.0
+    )
+}
+
+fn stroke_attrs(stroke: &StrokeStyle) -> String {
+    let cap = match stroke.line_cap {
+        LineCap::Butt => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square",
+    };
+    let join = match stroke.line_join {
+        LineJoin::Miter => "miter",
+        LineJoin::Round => "round",
+        LineJoin::Bevel => "bevel",
+    };
+    let mut attrs = format!(
+        r#"{} stroke-width="{}" stroke-linecap="{}" stroke-linejoin="{}""#,
+        paint_attrs("stroke", &stroke.color),
+        stroke.width,
+        cap,
+        join
+    );
+    if let Some(dash) = stroke.dash.as_ref().filter(|d| !d.is_empty()) {
+        let dash: Vec<String> = dash.iter().map(|d| d.to_string()).collect();
+        let _ = write!(attrs, r#" stroke-dasharray="{}""#, dash.join(" "));
+    }
+    attrs
+}
+
+fn fill_stroke_attrs(stroke: Option<&StrokeStyle>, fill: Option<&FillStyle>) -> String {
+    let fill = fill
+        .map(|f| paint_attrs("fill", &f.color))
+        .unwrap_or_else(|| r#"fill="none""#.to_string());
+    let stroke = stroke
+        .map(stroke_attrs)
+        .unwrap_or_else(|| r#"stroke="none""#.to_string());
+    format!("{} {}", fill, stroke)
+}
+
+/// 重新序列化内嵌 SVG，只保留 SVG 命名空间内的安全元素
+///
+/// 删除脚本、`foreignObject` 等元素，`on*` 事件属性，以及指向文档外部（非 `#id`、非 `data:`）的链接。
+fn sanitize_svg(content: &str) -> Result<String, CanvasError> {
+    // 包一层声明了命名空间的分组，片段和省略 xmlns 的内容都按 SVG 解析
+    let wrapped = format!(
+        r#"<g xmlns="{}" xmlns:xlink="{}">{}</g>"#,
+        SVG_NAMESPACE,
+        XLINK_NAMESPACE,
+        strip_xml_declaration(content)
+    );
+    let doc = usvg::roxmltree::Document::parse(&wrapped)
+        .map_err(|e| CanvasError::InvalidOperation(format!("内嵌 SVG 解析失败: {}", e)))?;
+    let mut out = String::with_capacity(content.len());
+    for child in doc.root_element().children() {
+        write_sanitized(&mut out, child);
+    }
+    Ok(out)
+}
+
+fn write_sanitized(out: &mut String, node: usvg::roxmltree::Node) {
+    if node.is_text() {
+        out.push_str(&escape_xml(node.text().unwrap_or_default()));
+        return;
+    }
+    if !node.is_element() {
+        return;
+    }
+
+    let tag = node.tag_name();
+    if tag.namespace() != Some(SVG_NAMESPACE)
+        || FORBIDDEN_SVG_ELEMENTS.contains(&tag.name().to_ascii_lowercase().as_str())
+    {
+        return;
+    }
+
+    let _ = write!(out, "<{}", tag.name());
+    for attr in node.attributes() {
+        let prefix = match attr.namespace() {
+            None => "",
+            Some(XLINK_NAMESPACE) => "xlink:",
+            Some(XML_NAMESPACE) => "xml:",
+            Some(_) => continue,
+        };
+        if !is_safe_attribute(attr.name(), attr.value()) {
+            continue;
+        }
+        let _ = write!(
+            out,
+            r#" {}{}="{}""#,
+            prefix,
+            attr.name(),
+            escape_xml(attr.value())
+        );
+    }
+    out.push('>');
+    for child in node.children() {
+        write_sanitized(out, child);
+    }
+    let _ = write!(out, "</{}>", tag.name());
+}
+
+fn strip_xml_declaration(content: &str) -> &str {
+    let trimmed = content.trim_start();
+    if trimmed.starts_with("<?xml")
+        && let Some(end) = trimmed.find("?>")
+    {
+        return &trimmed[end + 2..];
+    }
+    trimmed
+}
+
+fn is_safe_attribute(name: &str, value: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    if name.starts_with("on") {
+        return false;
+    }
+    let value = value.trim().to_ascii_lowercase();
+    if name == "href" {
+        return value.starts_with('#') || value.starts_with("data:");
+    }
+    !value.contains("javascript:")
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use std::collections::HashMap;
+
+    fn sample_state() -> CanvasState {
+        let now = Utc::now();
+        let mut state = CanvasState {
+            id: "canvas".to_string(),
+            name: "test".to_string(),
+            width: 200.0,
+            height: 100.0,
+            background_color: Color::white(),
+            elements: HashMap::new(),
+            layers: vec![
+                Layer::new("bottom".to_string(), 0),
+                Layer::new("top".to_string(), 1),
+            ],
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut rect = Element::new(
+            Shape::Rectangle {
+                rect: Rect::new(10.0, 10.0, 50.0, 30.0),
+                stroke: Some(StrokeStyle::default()),
+                fill: Some(FillStyle {
+                    color: Color::red(),
+                }),
+            },
+            None,
+        )
+        .with_layer(1);
+        rect.created_at = now;
+        let mut text = Element::new(
+            Shape::Text {
+                position: Point::new(5.0, 5.0),
+                content: "a < b".to_string(),
+                font_size: 12.0,
+                font_family: "sans-serif".to_string(),
+                color: Color::black(),
+            },
+            None,
+        );
+        text.created_at = now + Duration::seconds(1);
+        let mut hidden = Element::new(
+            Shape::Ellipse {
+                center: Point::new(50.0, 50.0),
+                radius_x: 10.0,
+                radius_y: 10.0,
+                stroke: None,
+                fill: Some(FillStyle::default()),
+            },
+            None,
+        );
+        hidden.visible = false;
+
+        for e in [rect, text, hidden] {
+            state.elements.insert(e.id.clone(), e);
+        }
+        state
+    }
+
+    #[test]
+    fn test_svg_respects_layer_order_and_visibility() {
+        let svg = CanvasExporter::render_svg(&sample_state(), &ExportOptions::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("a &lt; b"));
+        assert!(!svg.contains("<ellipse"));
+        // 文本在底层，矩形在顶层，顶层应后绘制
+        assert!(svg.find("<text").unwrap() < svg.find("<rect x=\"10\"").unwrap());
+    }
+
+    #[test]
+    fn test_png_export_scale() {
+        let output = CanvasExporter::export(
+            &sample_state(),
+            ExportFormat::Png,
+            &ExportOptions {
+                scale: 2.0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!((output.width, output.height), (400, 200));
+        assert!(output.data.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn test_pdf_export() {
+        let output = CanvasExporter::export(
+            &sample_state(),
+            ExportFormat::Pdf,
+            &ExportOptions::default(),
+        )
+        .unwrap();
+        assert!(output.data.starts_with(b"%PDF"));
+        assert_eq!(output.mime_type(), "application/pdf");
+    }
+
+    #[test]
+    fn test_invalid_scale_rejected() {
+        let result = CanvasExporter::export(
+            &sample_state(),
+            ExportFormat::Png,
+            &ExportOptions {
+                scale: 0.0,
+                ..Default::default()
+            },
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_file_href_not_resolved() {
+        let dir = tempfile::tempdir().unwrap();
+        let png = CanvasExporter::export(
+            &sample_state(),
+            ExportFormat::Png,
+            &ExportOptions::default(),
+        )
+        .unwrap();
+        let path = dir.path().join("secret.png");
+        std::fs::write(&path, png.data).unwrap();
+
+        for href in [
+            path.display().to_string(),
+            format!("file://{}", path.display()),
+        ] {
+            let svg = format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"><image width="10" height="10" href="{}"/></svg>"#,
+                href
+            );
+            let tree = CanvasExporter::parse_svg(&svg).unwrap();
+            assert!(!tree.root.has_children(), "{} should not be loaded", href);
+        }
+
+        // 内嵌 SVG 中的外部链接在输出前即被删除
+        let mut state = sample_state();
+        let element = Element::new(
+            Shape::Svg {
+                position: Point::new(0.0, 0.0),
+                svg_content: format!(
+                    r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink"><image xlink:href="file://{}"/><use href="#a"/></svg>"##,
+                    path.display()
+                ),
+                scale: 1.0,
+            },
+            None,
+        );
+        state.elements.insert(element.id.clone(), element);
+        let svg = CanvasExporter::render_svg(&state, &ExportOptions::default());
+        assert!(!svg.contains("secret.png"));
+        assert!(svg.contains(r##"<use href="#a">"##));
+    }
+
+    #[test]
+    fn test_embedded_svg_sanitized() {
+        let mut state = sample_state();
+        let element = Element::new(
+            Shape::Svg {
+                position: Point::new(0.0, 0.0),
+                svg_content: r#"<?xml version="1.0"?>
+<svg xmlns="http://www.w3.org/2000/svg" onload="alert(1)">
+  <script>alert(document.cookie)</script>
+  <foreignObject><div xmlns="http://www.w3.org/1999/xhtml">x</div></foreignObject>
+  <a href="javascript:alert(1)"><circle r="4" onclick="alert(2)" fill="red"/></a>
+</svg>"#
+                    .to_string(),
+                scale: 1.0,
+            },
+            None,
+        );
+        state.elements.insert(element.id.clone(), element);
+
+        let svg = CanvasExporter::render_svg(&state, &ExportOptions::default());
+        let lower = svg.to_lowercase();
+        for forbidden in [
+            "<script",
+            "alert",
+            "foreignobject",
+            "onload",
+            "onclick",
+            "<div",
+        ] {
+            assert!(!lower.contains(forbidden), "{} leaked: {}", forbidden, svg);
+        }
+        assert!(svg.contains(r#"<circle r="4" fill="red">"#));
+        assert!(CanvasExporter::parse_svg(&svg).is_ok());
+
+        // 无法解析的内嵌内容整体丢弃
+        let mut state = sample_state();
+        let element = Element::new(
+            Shape::Svg {
+                position: Point::new(0.0, 0.0),
+                svg_content: "<svg><script>alert(1)</svg>".to_string(),
+                scale: 1.0,
+            },
+            None,
+        );
+        state.elements.insert(element.id.clone(), element);
+        let svg = CanvasExporter::render_svg(&state, &ExportOptions::default());
+        assert!(!svg.contains("alert"));
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("PNG".parse::<ExportFormat>().unwrap(), ExportFormat::Png);
+        assert!("gif".parse::<ExportFormat>().is_err());
+    }
+}
@@ -4,12 +4,24 @@
 
 pub mod canvas;
 pub mod collaboration;
+pub mod crdt;
+pub mod diagram;
 pub mod draw;
+pub mod export;
+pub mod storage;
 pub mod types;
 pub mod websocket;
 
-pub use canvas::{CanvasInfo, CanvasManager, CanvasOps};
+pub use canvas::{CanvasError, CanvasInfo, CanvasManager, CanvasOps};
 pub use collaboration::{CollabEvent, CollabManager, CollabSession, UserInfo, UserColorGenerator, WsMessage};
+pub use crdt::{ClientId, CrdtDocument, CrdtOp, OpKind, OpOrigin, VectorClock};
+pub use diagram::{Diagram, DiagramFormat, DiagramGenerator, DiagramLayout, DiagramOptions};
 pub use draw::DrawAction;
+pub use export::{CanvasExporter, ExportFormat, ExportOptions, ExportOutput};
+pub use storage::{
+    CanvasSnapshot, CanvasStorage, CanvasStorageBackend, CanvasStorageConfig, CanvasVersion,
+    FileCanvasStorage, MemoryCanvasStorage, PersistMode, SqliteCanvasStorage,
+    create_canvas_storage,
+};
 pub use types::{CanvasState, Color, Element, ElementUpdate, UserCursor, CanvasId, UserId};
 pub use websocket::protocol::CollabMessage;
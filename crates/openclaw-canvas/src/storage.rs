@@ -0,0 +1,636 @@
+//! 画布持久化模块
+//!
+//! 提供画布状态与版本快照的存储后端：
+//! - 内存存储（默认，进程重启后丢失）
+//! - 文件系统存储（每个画布一个目录，JSON 格式）
+//! - SQLite 存储
+
+use crate::canvas::{CanvasError, CanvasInfo};
+use crate::types::*;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// 画布版本信息
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CanvasVersion {
+    pub canvas_id: CanvasId,
+    /// 版本号，从 1 开始递增
+    pub version: u64,
+    /// 快照名称
+    pub name: String,
+    pub element_count: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 画布快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanvasSnapshot {
+    #[serde(flatten)]
+    pub info: CanvasVersion,
+    pub state: CanvasState,
+}
+
+impl CanvasSnapshot {
+    pub fn new(version: u64, name: String, state: CanvasState) -> Self {
+        Self {
+            info: CanvasVersion {
+                canvas_id: state.id.clone(),
+                version,
+                name,
+                element_count: state.elements.len(),
+                created_at: Utc::now(),
+            },
+            state,
+        }
+    }
+}
+
+/// 画布存储后端
+#[async_trait]
+pub trait CanvasStorage: Send + Sync {
+    async fn save_canvas(&self, state: &CanvasState) -> Result<(), CanvasError>;
+    async fn load_canvas(&self, id: &CanvasId) -> Result<Option<CanvasState>, CanvasError>;
+    async fn delete_canvas(&self, id: &CanvasId) -> Result<(), CanvasError>;
+    async fn list_canvases(&self) -> Result<Vec<CanvasInfo>, CanvasError>;
+    async fn save_snapshot(&self, snapshot: &CanvasSnapshot) -> Result<(), CanvasError>;
+    async fn load_snapshot(
+        &self,
+        id: &CanvasId,
+        version: u64,
+    ) -> Result<Option<CanvasSnapshot>, CanvasError>;
+    /// 按版本号升序列出快照
+    async fn list_snapshots(&self, id: &CanvasId) -> Result<Vec<CanvasVersion>, CanvasError>;
+}
+
+/// 持久化时机
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum PersistMode {
+    /// 每次修改后立即写入
+    #[default]
+    Immediate,
+    /// 标记为脏，按固定间隔批量写入
+    Batched { interval_ms: u64 },
+}
+
+impl PersistMode {
+    pub fn flush_interval(&self) -> Option<Duration> {
+        match self {
+            Self::Immediate => None,
+            Self::Batched { interval_ms } => Some(Duration::from_millis(*interval_ms)),
+        }
+    }
+}
+
+/// 存储后端类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CanvasStorageBackend {
+    #[default]
+    Memory,
+    File,
+    Sqlite,
+}
+
+/// 画布存储配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CanvasStorageConfig {
+    #[serde(default)]
+    pub backend: CanvasStorageBackend,
+    /// 文件存储为目录，SQLite 为数据库文件
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    #[serde(default)]
+    pub persist_mode: PersistMode,
+}
+
+/// 根据配置创建存储后端
+pub fn create_canvas_storage(
+    config: &CanvasStorageConfig,
+) -> Result<Arc<dyn CanvasStorage>, CanvasError> {
+    let default_path = || {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".openclaw-rust")
+            .join(match config.backend {
+                CanvasStorageBackend::Sqlite => "canvas.db",
+                _ => "canvas",
+            })
+    };
+
+    match config.backend {
+        CanvasStorageBackend::Memory => Ok(Arc::new(MemoryCanvasStorage::new())),
+        CanvasStorageBackend::File => {
+            let path = config.path.clone().unwrap_or_else(default_path);
+            Ok(Arc::new(FileCanvasStorage::new(path)?))
+        }
+        CanvasStorageBackend::Sqlite => {
+            let path = config.path.clone().unwrap_or_else(default_path);
+            Ok(Arc::new(SqliteCanvasStorage::new(path)?))
+        }
+    }
+}
+
+fn canvas_info(state: &CanvasState) -> CanvasInfo {
+    CanvasInfo {
+        id: state.id.clone(),
+        name: state.name.clone(),
+        element_count: state.elements.len(),
+        created_at: state.created_at,
+        updated_at: state.updated_at,
+    }
+}
+
+fn storage_err(e: impl std::fmt::Display) -> CanvasError {
+    CanvasError::Storage(e.to_string())
+}
+
+/// 内存画布存储
+#[derive(Default)]
+pub struct MemoryCanvasStorage {
+    canvases: RwLock<HashMap<CanvasId, CanvasState>>,
+    snapshots: RwLock<HashMap<CanvasId, Vec<CanvasSnapshot>>>,
+}
+
+impl MemoryCanvasStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CanvasStorage for MemoryCanvasStorage {
+    async fn save_canvas(&self, state: &CanvasState) -> Result<(), CanvasError> {
+        let mut canvases = self.canvases.write().await;
+        canvases.insert(state.id.clone(), state.clone());
+        Ok(())
+    }
+
+    async fn load_canvas(&self, id: &CanvasId) -> Result<Option<CanvasState>, CanvasError> {
+        let canvases = self.canvases.read().await;
+        Ok(canvases.get(id).cloned())
+    }
+
+    async fn delete_canvas(&self, id: &CanvasId) -> Result<(), CanvasError> {
+        self.canvases.write().await.remove(id);
+        self.snapshots.write().await.remove(id);
+        Ok(())
+    }
+
+    async fn list_canvases(&self) -> Result<Vec<CanvasInfo>, CanvasError> {
+        let canvases = self.canvases.read().await;
+        Ok(canvases.values().map(canvas_info).collect())
+    }
+
+    async fn save_snapshot(&self, snapshot: &CanvasSnapshot) -> Result<(), CanvasError> {
+        let mut snapshots = self.snapshots.write().await;
+        let list = snapshots
+            .entry(snapshot.info.canvas_id.clone())
+            .or_default();
+        list.retain(|s| s.info.version != snapshot.info.version);
+        list.push(snapshot.clone());
+        list.sort_by_key(|s| s.info.version);
+        Ok(())
+    }
+
+    async fn load_snapshot(
+        &self,
+        id: &CanvasId,
+        version: u64,
+    ) -> Result<Option<CanvasSnapshot>, CanvasError> {
+        let snapshots = self.snapshots.read().await;
+        Ok(snapshots
+            .get(id)
+            .and_then(|list| list.iter().find(|s| s.info.version == version))
+            .cloned())
+    }
+
+    async fn list_snapshots(&self, id: &CanvasId) -> Result<Vec<CanvasVersion>, CanvasError> {
+        let snapshots = self.snapshots.read().await;
+        Ok(snapshots
+            .get(id)
+            .map(|list| list.iter().map(|s| s.info.clone()).collect())
+            .unwrap_or_default())
+    }
+}
+
+/// 文件系统画布存储
+///
+/// 目录结构：`<root>/<canvas_id>/state.json` 与 `<root>/<canvas_id>/versions/<version>.json`
+pub struct FileCanvasStorage {
+    root: PathBuf,
+}
+
+impl FileCanvasStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self, CanvasError> {
+        let root = root.into();
+        std::fs::create_dir_all(&root).map_err(storage_err)?;
+        Ok(Self { root })
+    }
+
+    fn canvas_dir(&self, id: &CanvasId) -> Result<PathBuf, CanvasError> {
+        // 画布 ID 作为目录名，拒绝路径穿越
+        if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+            return Err(CanvasError::InvalidOperation(format!(
+                "非法画布 ID: {}",
+                id
+            )));
+        }
+        Ok(self.root.join(id))
+    }
+
+    fn versions_dir(&self, id: &CanvasId) -> Result<PathBuf, CanvasError> {
+        Ok(self.canvas_dir(id)?.join("versions"))
+    }
+
+    async fn write_json<T: Serialize>(path: PathBuf, value: &T) -> Result<(), CanvasError> {
+        let json = serde_json::to_vec_pretty(value).map_err(storage_err)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(storage_err)?;
+        }
+        // 先写临时文件再重命名，避免写入中途崩溃留下半个文件
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, json).await.map_err(storage_err)?;
+        tokio::fs::rename(&tmp, &path).await.map_err(storage_err)
+    }
+
+    async fn read_json<T: for<'de> Deserialize<'de>>(
+        path: PathBuf,
+    ) -> Result<Option<T>, CanvasError> {
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(storage_err),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(storage_err(e)),
+        }
+    }
+}
+
+#[async_trait]
+impl CanvasStorage for FileCanvasStorage {
+    async fn save_canvas(&self, state: &CanvasState) -> Result<(), CanvasError> {
+        let path = self.canvas_dir(&state.id)?.join("state.json");
+        Self::write_json(path, state).await
+    }
+
+    async fn load_canvas(&self, id: &CanvasId) -> Result<Option<CanvasState>, CanvasError> {
+        Self::read_json(self.canvas_dir(id)?.join("state.json")).await
+    }
+
+    async fn delete_canvas(&self, id: &CanvasId) -> Result<(), CanvasError> {
+        match tokio::fs::remove_dir_all(self.canvas_dir(id)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(storage_err(e)),
+        }
+    }
+
+    async fn list_canvases(&self) -> Result<Vec<CanvasInfo>, CanvasError> {
+        let mut result = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.root).await.map_err(storage_err)?;
+        while let Some(entry) = entries.next_entry().await.map_err(storage_err)? {
+            let path = entry.path().join("state.json");
+            if let Some(state) = Self::read_json::<CanvasState>(path).await? {
+                result.push(canvas_info(&state));
+            }
+        }
+        Ok(result)
+    }
+
+    async fn save_snapshot(&self, snapshot: &CanvasSnapshot) -> Result<(), CanvasError> {
+        let path = self
+            .versions_dir(&snapshot.info.canvas_id)?
+            .join(format!("{}.json", snapshot.info.version));
+        Self::write_json(path, snapshot).await
+    }
+
+    async fn load_snapshot(
+        &self,
+        id: &CanvasId,
+        version: u64,
+    ) -> Result<Option<CanvasSnapshot>, CanvasError> {
+        Self::read_json(self.versions_dir(id)?.join(format!("{}.json", version))).await
+    }
+
+    async fn list_snapshots(&self, id: &CanvasId) -> Result<Vec<CanvasVersion>, CanvasError> {
+        let dir = self.versions_dir(id)?;
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(storage_err(e)),
+        };
+
+        let mut versions = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(storage_err)? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json")
+                && let Some(snapshot) = Self::read_json::<CanvasSnapshot>(path).await?
+            {
+                versions.push(snapshot.info);
+            }
+        }
+        versions.sort_by_key(|v| v.version);
+        Ok(versions)
+    }
+}
+
+/// SQLite 画布存储
+pub struct SqliteCanvasStorage {
+    db_path: PathBuf,
+}
+
+impl SqliteCanvasStorage {
+    pub fn new(db_path: impl Into<PathBuf>) -> Result<Self, CanvasError> {
+        let storage = Self {
+            db_path: db_path.into(),
+        };
+        if let Some(parent) = storage.db_path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent).map_err(storage_err)?;
+        }
+        let conn = storage.get_connection()?;
+        Self::init_tables(&conn)?;
+        Ok(storage)
+    }
+
+    fn get_connection(&self) -> Result<rusqlite::Connection, CanvasError> {
+        rusqlite::Connection::open(&self.db_path).map_err(storage_err)
+    }
+
+    fn init_tables(conn: &rusqlite::Connection) -> Result<(), CanvasError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS canvases (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                element_count INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                state TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS canvas_versions (
+                canvas_id TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                element_count INTEGER NOT NULL,
+                created_at TEXT NOT NULL,
+                state TEXT NOT NULL,
+                PRIMARY KEY (canvas_id, version)
+            );",
+        )
+        .map_err(storage_err)
+    }
+
+    fn parse_time(value: String) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&value)
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now())
+    }
+}
+
+#[async_trait]
+impl CanvasStorage for SqliteCanvasStorage {
+    async fn save_canvas(&self, state: &CanvasState) -> Result<(), CanvasError> {
+        let conn = self.get_connection()?;
+        let json = serde_json::to_string(state).map_err(storage_err)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO canvases (id, name, element_count, created_at, updated_at, state)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                state.id,
+                state.name,
+                state.elements.len() as i64,
+                state.created_at.to_rfc3339(),
+                state.updated_at.to_rfc3339(),
+                json
+            ],
+        )
+        .map_err(storage_err)?;
+        Ok(())
+    }
+
+    async fn load_canvas(&self, id: &CanvasId) -> Result<Option<CanvasState>, CanvasError> {
+        let conn = self.get_connection()?;
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT state FROM canvases WHERE id = ?1",
+                rusqlite::params![id],
+                |row| row.get(0),
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(storage_err(e)),
+            })?;
+
+        json.map(|j| serde_json::from_str(&j).map_err(storage_err))
+            .transpose()
+    }
+
+    async fn delete_canvas(&self, id: &CanvasId) -> Result<(), CanvasError> {
+        let conn = self.get_connection()?;
+        conn.execute("DELETE FROM canvases WHERE id = ?1", rusqlite::params![id])
+            .map_err(storage_err)?;
+        conn.execute(
+            "DELETE FROM canvas_versions WHERE canvas_id = ?1",
+            rusqlite::params![id],
+        )
+        .map_err(storage_err)?;
+        Ok(())
+    }
+
+    async fn list_canvases(&self) -> Result<Vec<CanvasInfo>, CanvasError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn
+            .prepare("SELECT id, name, element_count, created_at, updated_at FROM canvases")
+            .map_err(storage_err)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(CanvasInfo {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    element_count: row.get::<_, i64>(2)? as usize,
+                    created_at: Self::parse_time(row.get(3)?),
+                    updated_at: Self::parse_time(row.get(4)?),
+                })
+            })
+            .map_err(storage_err)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(storage_err)
+    }
+
+    async fn save_snapshot(&self, snapshot: &CanvasSnapshot) -> Result<(), CanvasError> {
+        let conn = self.get_connection()?;
+        let json = serde_json::to_string(&snapshot.state).map_err(storage_err)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO canvas_versions
+             (canvas_id, version, name, element_count, created_at, state)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                snapshot.info.canvas_id,
+                snapshot.info.version as i64,
+                snapshot.info.name,
+                snapshot.info.element_count as i64,
+                snapshot.info.created_at.to_rfc3339(),
+                json
+            ],
+        )
+        .map_err(storage_err)?;
+        Ok(())
+    }
+
+    async fn load_snapshot(
+        &self,
+        id: &CanvasId,
+        version: u64,
+    ) -> Result<Option<CanvasSnapshot>, CanvasError> {
+        let conn = self.get_connection()?;
+        let row = conn
+            .query_row(
+                "SELECT name, element_count, created_at, state FROM canvas_versions
+                 WHERE canvas_id = ?1 AND version = ?2",
+                rusqlite::params![id, version as i64],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                    ))
+                },
+            )
+            .map(Some)
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(storage_err(e)),
+            })?;
+
+        let Some((name, element_count, created_at, state)) = row else {
+            return Ok(None);
+        };
+
+        Ok(Some(CanvasSnapshot {
+            info: CanvasVersion {
+                canvas_id: id.clone(),
+                version,
+                name,
+                element_count: element_count as usize,
+                created_at: Self::parse_time(created_at),
+            },
+            state: serde_json::from_str(&state).map_err(storage_err)?,
+        }))
+    }
+
+    async fn list_snapshots(&self, id: &CanvasId) -> Result<Vec<CanvasVersion>, CanvasError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT version, name, element_count, created_at FROM canvas_versions
+                 WHERE canvas_id = ?1 ORDER BY version ASC",
+            )
+            .map_err(storage_err)?;
+        let rows = stmt
+            .query_map(rusqlite::params![id], |row| {
+                Ok(CanvasVersion {
+                    canvas_id: id.clone(),
+                    version: row.get::<_, i64>(0)? as u64,
+                    name: row.get(1)?,
+                    element_count: row.get::<_, i64>(2)? as usize,
+                    created_at: Self::parse_time(row.get(3)?),
+                })
+            })
+            .map_err(storage_err)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(storage_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_state(id: &str) -> CanvasState {
+        let now = Utc::now();
+        let mut elements = HashMap::new();
+        let element = Element::new(
+            Shape::Line {
+                start: Point::new(0.0, 0.0),
+                end: Point::new(10.0, 10.0),
+                stroke: StrokeStyle::default(),
+            },
+            None,
+        );
+        elements.insert(element.id.clone(), element);
+        CanvasState {
+            id: id.to_string(),
+            name: "test".to_string(),
+            width: 800.0,
+            height: 600.0,
+            background_color: Color::white(),
+            elements,
+            layers: vec![Layer::new("Layer 1".to_string(), 0)],
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    async fn roundtrip(storage: &dyn CanvasStorage) {
+        let state = sample_state("canvas-1");
+        storage.save_canvas(&state).await.unwrap();
+
+        let loaded = storage.load_canvas(&state.id).await.unwrap().unwrap();
+        assert_eq!(loaded.elements.len(), 1);
+        assert_eq!(storage.list_canvases().await.unwrap().len(), 1);
+
+        storage
+            .save_snapshot(&CanvasSnapshot::new(2, "v2".to_string(), state.clone()))
+            .await
+            .unwrap();
+        storage
+            .save_snapshot(&CanvasSnapshot::new(1, "v1".to_string(), state.clone()))
+            .await
+            .unwrap();
+        let versions = storage.list_snapshots(&state.id).await.unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        let snapshot = storage.load_snapshot(&state.id, 1).await.unwrap().unwrap();
+        assert_eq!(snapshot.info.name, "v1");
+
+        storage.delete_canvas(&state.id).await.unwrap();
+        assert!(storage.load_canvas(&state.id).await.unwrap().is_none());
+        assert!(storage.list_snapshots(&state.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_memory_storage_roundtrip() {
+        roundtrip(&MemoryCanvasStorage::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        roundtrip(&FileCanvasStorage::new(dir.path()).unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_storage_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        roundtrip(&SqliteCanvasStorage::new(dir.path().join("canvas.db")).unwrap()).await;
+    }
+
+    #[test]
+    fn test_file_storage_rejects_path_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileCanvasStorage::new(dir.path()).unwrap();
+        assert!(storage.canvas_dir(&"../etc".to_string()).is_err());
+    }
+}
@@ -110,6 +110,9 @@ pub struct StrokeStyle {
     pub width: f64,
     pub line_cap: LineCap,
     pub line_join: LineJoin,
+    /// 虚线模式（线段与间隔长度交替），None 为实线
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dash: Option<Vec<f64>>,
 }
 
 impl Default for StrokeStyle {
@@ -119,6 +122,7 @@ impl Default for StrokeStyle {
             width: 1.0,
             line_cap: LineCap::Round,
             line_join: LineJoin::Round,
+            dash: None,
         }
     }
 }
@@ -2,10 +2,11 @@
 
 use async_trait::async_trait;
 use futures::Stream;
-use openclaw_core::Result;
+use openclaw_core::{OpenClawError, Result};
 use std::pin::Pin;
 
-use crate::types::{ChannelMessage, ChannelType, SendMessage};
+use crate::streaming::TextStream;
+use crate::types::{ChannelCapabilities, ChannelMessage, ChannelType, SendMessage};
 
 /// 消息通道 Trait
 #[async_trait]
@@ -30,6 +31,35 @@ pub trait Channel: Send + Sync {
         None
     }
 
+    /// 通道能力，默认只支持发送普通消息
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities::default()
+    }
+
+    /// 用新内容编辑已发送的消息，`message.chat_id` 为消息所在会话
+    async fn edit_message(&self, message_id: &str, message: SendMessage) -> Result<ChannelMessage> {
+        let _ = (message_id, message);
+        Err(unsupported(self.name(), "编辑消息"))
+    }
+
+    /// 删除已发送的消息
+    async fn delete_message(&self, chat_id: &str, message_id: &str) -> Result<()> {
+        let _ = (chat_id, message_id);
+        Err(unsupported(self.name(), "删除消息"))
+    }
+
+    /// 对消息添加表情回应
+    async fn add_reaction(&self, chat_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        let _ = (chat_id, message_id, emoji);
+        Err(unsupported(self.name(), "表情回应"))
+    }
+
+    /// 显示“正在输入”提示，不支持的通道直接忽略
+    async fn send_typing(&self, chat_id: &str) -> Result<()> {
+        let _ = chat_id;
+        Ok(())
+    }
+
     /// 健康检查
     async fn health_check(&self) -> Result<bool>;
 }
@@ -41,6 +71,17 @@ pub enum ChannelEvent {
     Message(ChannelMessage),
     /// 消息已发送
     MessageSent { message_id: String },
+    /// 消息被编辑
+    MessageEdited(ChannelMessage),
+    /// 消息被删除
+    MessageDeleted { chat_id: String, message_id: String },
+    /// 收到表情回应
+    ReactionAdded {
+        chat_id: String,
+        message_id: String,
+        user_id: String,
+        emoji: String,
+    },
     /// 用户加入
     UserJoined { chat_id: String, user_id: String },
     /// 用户离开
@@ -55,7 +96,18 @@ pub trait ChannelHandler: Send + Sync {
     /// 处理消息
     async fn handle(&self, message: ChannelMessage) -> Result<Option<SendMessage>>;
 
+    /// 以流式方式处理消息，返回回复文本的增量流
+    ///
+    /// 返回 None 时改用 `handle`。
+    async fn handle_stream(&self, message: ChannelMessage) -> Result<Option<TextStream>> {
+        let _ = message;
+        Ok(None)
+    }
+
     /// 处理通道事件
+    ///
+    /// `ChannelManager` 会直接用 `handle` 处理新消息并把回复发回来源会话，
+    /// 其余事件才经过这里。
     async fn handle_event(&self, event: ChannelEvent) -> Result<()> {
         if let ChannelEvent::Message(msg) = event {
             self.handle(msg).await?;
@@ -63,3 +115,7 @@ pub trait ChannelHandler: Send + Sync {
         Ok(())
     }
 }
+
+fn unsupported(channel: &str, action: &str) -> OpenClawError {
+    OpenClawError::Platform(format!("{} {}", channel, action))
+}
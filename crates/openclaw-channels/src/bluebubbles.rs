@@ -334,6 +334,8 @@ impl Channel for BlueBubblesClient {
             content: message.content,
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
@@ -248,6 +248,8 @@ impl Channel for DingTalkChannel {
             content: message.content.clone(),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
@@ -17,7 +17,7 @@ use tokio::sync::Mutex;
 #[cfg(feature = "discord")]
 use crate::discord_gateway::{DiscordGatewayClient, DiscordGatewayEvent};
 use crate::base::{Channel, ChannelEvent};
-use crate::types::{ChannelMessage, ChannelType, SendMessage};
+use crate::types::{ChannelCapabilities, ChannelMessage, ChannelType, SendMessage};
 
 /// Discord @提及
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -746,11 +746,6 @@ impl DiscordChannel {
         message_id: &str,
         content: &str,
     ) -> Result<String> {
-        let url = self.get_api_url(&format!(
-            "channels/{}/messages",
-            channel_id
-        ));
-
         let body = json!({
             "content": content,
             "message_reference": {
@@ -758,12 +753,22 @@ impl DiscordChannel {
             }
         });
 
+        self.create_message(channel_id, &body).await
+    }
+
+    /// 创建消息，返回消息 ID
+    async fn create_message(&self, channel_id: &str, body: &serde_json::Value) -> Result<String> {
+        let url = self.get_api_url(&format!(
+            "channels/{}/messages",
+            channel_id
+        ));
+
         let response = self
             .client
             .post(&url)
             .header("Authorization", format!("Bot {}", self.config.bot_token))
             .header("Content-Type", "application/json")
-            .json(&body)
+            .json(body)
             .send()
             .await
             .map_err(|e| OpenClawError::Http(format!("Discord API 请求失败: {}", e)))?;
@@ -977,22 +982,36 @@ impl Channel for DiscordChannel {
     }
 
     async fn send(&self, message: SendMessage) -> Result<ChannelMessage> {
-        let message_id = uuid::Uuid::new_v4().to_string();
+        let mut message_id = uuid::Uuid::new_v4().to_string();
+        // Discord 的线程本身就是频道
+        let channel_id = message
+            .thread_id
+            .clone()
+            .unwrap_or_else(|| message.chat_id.clone());
 
         // 优先使用 Webhook
         if self.config.webhook_url.is_some() {
             self.send_webhook(&message.content, None).await?;
-        } else if !message.chat_id.is_empty() {
+        } else if !channel_id.is_empty() {
             // 使用 Bot API
             match message.message_type.as_str() {
                 "embed" => {
                     let title = message.title.as_deref().unwrap_or("消息");
-                    self.send_embed(&message.chat_id, title, &message.content, None)
+                    self.send_embed(&channel_id, title, &message.content, None)
                         .await?;
                 }
                 _ => {
-                    self.send_to_channel(&message.chat_id, &message.content)
-                        .await?;
+                    let mut body = json!({
+                        "content": message.content
+                    });
+                    if let Some(reply_to) = &message.reply_to {
+                        // 被回复的消息不存在时仍然发送
+                        body["message_reference"] = json!({
+                            "message_id": reply_to,
+                            "fail_if_not_exists": false
+                        });
+                    }
+                    message_id = self.create_message(&channel_id, &body).await?;
                 }
             }
         } else {
@@ -1004,14 +1023,60 @@ impl Channel for DiscordChannel {
         Ok(ChannelMessage {
             id: message_id,
             channel_type: ChannelType::Discord,
-            chat_id: message.chat_id,
+            chat_id: channel_id,
             user_id: "bot".to_string(),
             content: message.content.clone(),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: message.thread_id,
+            reply_to: message.reply_to,
+        })
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        // Webhook 发送拿不到消息 ID，只有 Bot API 支持后续操作
+        if self.config.webhook_url.is_some() {
+            return ChannelCapabilities::default();
+        }
+        ChannelCapabilities {
+            threads: true,
+            replies: true,
+            edit: true,
+            delete: true,
+            reactions: true,
+            typing: true,
+        }
+    }
+
+    async fn edit_message(&self, message_id: &str, message: SendMessage) -> Result<ChannelMessage> {
+        let id =
+            DiscordChannel::edit_message(self, &message.chat_id, message_id, &message.content)
+                .await?;
+        Ok(ChannelMessage {
+            id,
+            channel_type: ChannelType::Discord,
+            chat_id: message.chat_id,
+            user_id: "bot".to_string(),
+            content: message.content,
+            timestamp: chrono::Utc::now(),
+            metadata: None,
+            thread_id: message.thread_id,
+            reply_to: message.reply_to,
         })
     }
 
+    async fn delete_message(&self, chat_id: &str, message_id: &str) -> Result<()> {
+        DiscordChannel::delete_message(self, chat_id, message_id).await
+    }
+
+    async fn add_reaction(&self, chat_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        DiscordChannel::add_reaction(self, chat_id, message_id, emoji).await
+    }
+
+    async fn send_typing(&self, chat_id: &str) -> Result<()> {
+        self.trigger_typing(chat_id).await
+    }
+
     #[cfg(feature = "discord")]
     fn messages(&self) -> Option<Pin<Box<dyn Stream<Item = ChannelMessage> + Send>>> {
         self.msg_receiver.as_ref().map(|rx| {
@@ -60,6 +60,11 @@ impl EventHandler for GatewayEventHandler {
             }).collect(),
             mention_roles: msg.mention_roles.iter().map(|r| r.to_string()).collect(),
             is_bot: msg.author.bot,
+            referenced_message_id: msg
+                .message_reference
+                .as_ref()
+                .and_then(|r| r.message_id)
+                .map(|id| id.to_string()),
         };
 
         let _ = self.event_sender.send(DiscordGatewayEvent::MessageCreate(gateway_msg));
@@ -18,6 +18,9 @@ pub struct DiscordGatewayMessage {
     pub mentions: Vec<DiscordGatewayUser>,
     pub mention_roles: Vec<String>,
     pub is_bot: bool,
+    /// 所回复的消息 ID
+    #[serde(default)]
+    pub referenced_message_id: Option<String>,
 }
 
 #[cfg(feature = "discord")]
@@ -41,6 +44,8 @@ impl From<DiscordGatewayMessage> for ChannelMessage {
             content: msg.content,
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: msg.referenced_message_id,
         }
     }
 }
@@ -90,6 +95,7 @@ mod tests {
             mentions: vec![],
             mention_roles: vec![],
             is_bot: false,
+            referenced_message_id: None,
         };
 
         let channel_msg: ChannelMessage = msg.into();
@@ -119,6 +125,7 @@ mod tests {
             mentions: vec![],
             mention_roles: vec![],
             is_bot: false,
+            referenced_message_id: None,
         });
 
         let channel_msg = event.into_channel_message().unwrap();
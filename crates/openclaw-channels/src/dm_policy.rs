@@ -339,6 +339,16 @@ impl DmPolicyManager {
         }
     }
 
+    /// 移除配对
+    pub async fn unpair(&self, account_id: &str, channel: &str, user_id: &str) {
+        let key = format!("{}:{}", channel, account_id);
+
+        let mut policies = self.account_policies.write().await;
+        if let Some(account_policy) = policies.get_mut(&key) {
+            account_policy.unpair(user_id);
+        }
+    }
+
     /// 检查限流
     pub async fn check_rate_limit(&self, account_id: &str, channel: &str, user_id: &str) -> bool {
         let key = format!("{}:{}", channel, account_id);
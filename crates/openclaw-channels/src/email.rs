@@ -66,6 +66,8 @@ impl EmailClient {
             content: format!("Subject: {}\n\n{}", email.subject, email.body),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 }
@@ -49,6 +49,12 @@ impl ChannelFactoryRegistry {
         creator(config)
     }
 
+    /// 移除通道类型的创建器，返回是否存在
+    pub async fn unregister(&self, channel_type: &str) -> bool {
+        let mut creators = self.creators.write().await;
+        creators.remove(channel_type).is_some()
+    }
+
     pub async fn list_types(&self) -> Vec<String> {
         let creators = self.creators.read().await;
         creators.keys().cloned().collect()
@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::base::Channel;
-use crate::types::{ChannelMessage, ChannelType, SendMessage};
+use crate::types::{ChannelCapabilities, ChannelMessage, ChannelType, SendMessage};
 
 /// 飞书 @消息提及
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +40,12 @@ pub struct FeishuMessageParseResult {
     pub user_id: Option<String>,
     /// 发送者名称
     pub user_name: Option<String>,
+    /// 话题 ID
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// 所回复的消息 ID
+    #[serde(default)]
+    pub parent_id: Option<String>,
 }
 
 /// 飞书群信息
@@ -82,6 +88,7 @@ struct FeishuSendMessageResponse {
 
 #[derive(Debug, Deserialize)]
 struct FeishuMessageData {
+    #[serde(default)]
     message_id: String,
 }
 
@@ -153,7 +160,7 @@ pub struct FeishuConfig {
 pub struct FeishuChannel {
     config: FeishuConfig,
     client: Client,
-    access_token: Option<String>,
+    access_token: std::sync::RwLock<Option<String>>,
 }
 
 impl FeishuChannel {
@@ -163,13 +170,18 @@ impl FeishuChannel {
         Self {
             config,
             client,
-            access_token: None,
+            access_token: std::sync::RwLock::new(None),
         }
     }
 
     /// 获取 tenant_access_token
     pub async fn get_access_token(&mut self) -> Result<String> {
-        if let Some(token) = &self.access_token {
+        self.tenant_token().await
+    }
+
+    /// 获取并缓存 tenant_access_token
+    async fn tenant_token(&self) -> Result<String> {
+        if let Some(token) = self.access_token.read().unwrap().as_ref() {
             return Ok(token.clone());
         }
 
@@ -201,10 +213,95 @@ impl FeishuChannel {
             )));
         }
 
-        self.access_token = Some(result.tenant_access_token.clone());
+        *self.access_token.write().unwrap() = Some(result.tenant_access_token.clone());
         Ok(result.tenant_access_token)
     }
 
+    /// 调用消息相关的 Bot API
+    async fn bot_request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<Option<FeishuMessageData>> {
+        let token = self.tenant_token().await?;
+        let url = format!("https://open.feishu.cn/open-apis/im/v1/{}", path);
+
+        let mut request = self
+            .client
+            .request(method, &url)
+            .header("Authorization", format!("Bearer {}", token));
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| OpenClawError::Http(format!("飞书 API 请求失败: {}", e)))?;
+
+        let result: FeishuSendMessageResponse = response
+            .json()
+            .await
+            .map_err(|e| OpenClawError::Http(format!("解析响应失败: {}", e)))?;
+
+        if result.code != 0 {
+            return Err(OpenClawError::AIProvider(format!(
+                "飞书 API 返回错误: {} - {}",
+                result.code, result.msg
+            )));
+        }
+
+        Ok(result.data)
+    }
+
+    /// 通过 Bot API 发送文本消息；指定 `reply_to` 时回复该消息，`in_thread` 时以话题形式回复
+    pub async fn send_text_message(
+        &self,
+        chat_id: &str,
+        content: &str,
+        reply_to: Option<&str>,
+        in_thread: bool,
+    ) -> Result<String> {
+        // content 字段是 JSON 字符串
+        let content = json!({ "text": content }).to_string();
+        let data = match reply_to {
+            Some(message_id) => {
+                let body = json!({
+                    "msg_type": "text",
+                    "content": content,
+                    "reply_in_thread": in_thread
+                });
+                self.bot_request(
+                    reqwest::Method::POST,
+                    &format!("messages/{}/reply", message_id),
+                    Some(body),
+                )
+                .await?
+            }
+            None => {
+                let body = json!({
+                    "receive_id": chat_id,
+                    "msg_type": "text",
+                    "content": content
+                });
+                self.bot_request(
+                    reqwest::Method::POST,
+                    "messages?receive_id_type=chat_id",
+                    Some(body),
+                )
+                .await?
+            }
+        };
+
+        Ok(data.map(|d| d.message_id).unwrap_or_default())
+    }
+
+    /// 是否通过 Bot API 发送（Webhook 不返回消息 ID）
+    fn uses_bot_api(&self) -> bool {
+        self.config.webhook.is_none() && !self.config.app_id.is_empty()
+    }
+
     /// 解析飞书 @消息
     ///
     /// 飞书 @消息格式:
@@ -245,6 +342,8 @@ impl FeishuChannel {
             message_id: None,
             user_id: None,
             user_name: None,
+            thread_id: None,
+            parent_id: None,
         }
     }
 
@@ -260,6 +359,8 @@ impl FeishuChannel {
             message_id: None,
             user_id: None,
             user_name: None,
+            thread_id: None,
+            parent_id: None,
         };
 
         if let Some(message) = event_json.get("message") {
@@ -288,6 +389,12 @@ impl FeishuChannel {
             if let Some(user_name) = message.get("sender").and_then(|v| v.get("name")).and_then(|v| v.as_str()) {
                 result.user_name = Some(user_name.to_string());
             }
+            if let Some(thread_id) = message.get("thread_id").and_then(|v| v.as_str()) {
+                result.thread_id = Some(thread_id.to_string());
+            }
+            if let Some(parent_id) = message.get("parent_id").and_then(|v| v.as_str()) {
+                result.parent_id = Some(parent_id.to_string());
+            }
         }
 
         result
@@ -764,6 +871,30 @@ impl Channel for FeishuChannel {
     }
 
     async fn send(&self, message: SendMessage) -> Result<ChannelMessage> {
+        if self.uses_bot_api() && !message.chat_id.is_empty() {
+            // 没有指定回复对象时，话题内的消息回复到话题中
+            let reply_to = message.reply_to.as_deref().or(message.thread_id.as_deref());
+            let message_id = self
+                .send_text_message(
+                    &message.chat_id,
+                    &message.content,
+                    reply_to,
+                    message.thread_id.is_some(),
+                )
+                .await?;
+            return Ok(ChannelMessage {
+                id: message_id,
+                channel_type: ChannelType::Feishu,
+                chat_id: message.chat_id,
+                user_id: "bot".to_string(),
+                content: message.content,
+                timestamp: chrono::Utc::now(),
+                metadata: None,
+                thread_id: message.thread_id,
+                reply_to: message.reply_to,
+            });
+        }
+
         let message_id = uuid::Uuid::new_v4().to_string();
 
         // 优先使用 Webhook
@@ -811,9 +942,74 @@ impl Channel for FeishuChannel {
             content: message.content.clone(),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
+        })
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        if !self.uses_bot_api() {
+            return ChannelCapabilities::default();
+        }
+        ChannelCapabilities {
+            threads: true,
+            replies: true,
+            edit: true,
+            delete: true,
+            reactions: true,
+            typing: false,
+        }
+    }
+
+    async fn edit_message(&self, message_id: &str, message: SendMessage) -> Result<ChannelMessage> {
+        let body = json!({
+            "msg_type": "text",
+            "content": json!({ "text": message.content }).to_string()
+        });
+        self.bot_request(
+            reqwest::Method::PUT,
+            &format!("messages/{}", message_id),
+            Some(body),
+        )
+        .await?;
+
+        Ok(ChannelMessage {
+            id: message_id.to_string(),
+            channel_type: ChannelType::Feishu,
+            chat_id: message.chat_id,
+            user_id: "bot".to_string(),
+            content: message.content,
+            timestamp: chrono::Utc::now(),
+            metadata: None,
+            thread_id: message.thread_id,
+            reply_to: message.reply_to,
         })
     }
 
+    async fn delete_message(&self, _chat_id: &str, message_id: &str) -> Result<()> {
+        self.bot_request(
+            reqwest::Method::DELETE,
+            &format!("messages/{}", message_id),
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// `emoji` 为飞书表情类型，如 `THUMBSUP`、`OK`
+    async fn add_reaction(&self, _chat_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        let body = json!({
+            "reaction_type": { "emoji_type": emoji }
+        });
+        self.bot_request(
+            reqwest::Method::POST,
+            &format!("messages/{}/reactions", message_id),
+            Some(body),
+        )
+        .await?;
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<bool> {
         Ok(self.config.enabled)
     }
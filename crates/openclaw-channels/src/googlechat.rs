@@ -185,6 +185,8 @@ impl GoogleChatClient {
             content: msg.message.unwrap_or_default(),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
@@ -231,6 +233,8 @@ impl Channel for GoogleChatClient {
             content: message.content,
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
@@ -0,0 +1,737 @@
+//! 跨通道用户身份
+//!
+//! 同一个人在不同通道上的账号可以关联为一个统一用户：在已有账号上发送 `/link` 获取配对码，
+//! 再到另一个通道发送 `/link <发起账号> <配对码>` 完成验证（复用 `DmPolicyManager` 的配对码流程）。
+//! 关联后的账号解析为同一个用户 ID，用作会话键、记忆命名空间和权限判断的主体。
+//!
+//! 配对码绑定发起账号，同一配对码连续输错 `MAX_CODE_ATTEMPTS` 次即作废，
+//! 同一账号短时间内失败过多会被暂时禁止关联，避免穷举 6 位配对码。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::dm_policy::{DmAccessResult, DmPolicyManager};
+use crate::types::ChannelMessage;
+use openclaw_core::{OpenClawError, Result};
+
+/// 身份关联配对码在 `DmPolicyManager` 中使用的通道名
+const LINK_CHANNEL: &str = "identity-link";
+
+/// 未使用的配对码最多保留的时长（小时），过期判断仍以 `DmPolicyManager` 为准
+const PENDING_LINK_HOURS: i64 = 24;
+
+/// 单个配对码允许的错误次数，达到后配对码作废
+const MAX_CODE_ATTEMPTS: u32 = 5;
+
+/// 单个账号在 `LINK_FAILURE_WINDOW_MINUTES` 内允许的关联失败次数
+const MAX_LINK_FAILURES: u32 = 10;
+
+const LINK_FAILURE_WINDOW_MINUTES: i64 = 15;
+
+/// 通道未提供机器人账号时，DM 策略使用的账号 ID
+pub const DEFAULT_DM_ACCOUNT: &str = "default";
+
+/// 某个通道上的账号
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ChannelIdentity {
+    /// 通道名（小写，如 telegram、slack）
+    pub channel: String,
+    /// 通道内的用户 ID
+    pub user_id: String,
+}
+
+impl ChannelIdentity {
+    pub fn new(channel: impl Into<String>, user_id: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into().to_lowercase(),
+            user_id: user_id.into(),
+        }
+    }
+
+    /// 消息发送者的账号
+    pub fn from_message(message: &ChannelMessage) -> Self {
+        Self::new(message.channel_type.as_str(), message.user_id.clone())
+    }
+
+    /// 从账号键 `通道:用户ID` 解析
+    pub fn from_key(key: &str) -> Option<Self> {
+        let (channel, user_id) = key.split_once(':')?;
+        if channel.is_empty() || user_id.is_empty() {
+            return None;
+        }
+        Some(Self::new(channel, user_id))
+    }
+
+    /// 账号键，也是未关联账号的用户 ID
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.channel, self.user_id)
+    }
+}
+
+/// 统一用户
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnifiedUser {
+    pub id: String,
+    pub display_name: Option<String>,
+    /// 已关联的通道账号
+    pub identities: Vec<ChannelIdentity>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UnifiedUser {
+    fn new(identity: ChannelIdentity) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            display_name: None,
+            identities: vec![identity],
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// 身份解析结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedIdentity {
+    /// 统一用户 ID；未关联的账号为账号键 `通道:用户ID`
+    pub user_id: String,
+    /// 是否已关联到统一用户
+    pub linked: bool,
+}
+
+/// 身份关联命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdentityCommand {
+    /// `/link`：生成配对码
+    StartLink,
+    /// `/link <发起账号> <配对码>`：把当前账号关联到发起账号所属的用户
+    CompleteLink {
+        initiator: ChannelIdentity,
+        code: String,
+    },
+    /// `/unlink`：解除当前账号的关联
+    Unlink,
+    /// 参数不完整的 `/link`
+    Usage,
+}
+
+impl IdentityCommand {
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut parts = content.split_whitespace();
+        match parts.next()? {
+            "/link" => Some(match (parts.next(), parts.next()) {
+                (None, _) => Self::StartLink,
+                (Some(initiator), Some(code)) => match ChannelIdentity::from_key(initiator) {
+                    Some(initiator) => Self::CompleteLink {
+                        initiator,
+                        code: code.to_string(),
+                    },
+                    None => Self::Usage,
+                },
+                (Some(_), None) => Self::Usage,
+            }),
+            "/unlink" => Some(Self::Unlink),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingLink {
+    code: String,
+    user_id: String,
+    created_at: DateTime<Utc>,
+    /// 输错次数
+    attempts: u32,
+}
+
+/// 账号最近的关联失败记录
+#[derive(Debug, Clone)]
+struct LinkFailures {
+    count: u32,
+    since: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IdentityState {
+    users: HashMap<String, UnifiedUser>,
+    /// 账号键 -> 统一用户 ID
+    #[serde(skip)]
+    index: HashMap<String, String>,
+    /// 发起账号键 -> 待完成的配对码
+    #[serde(skip)]
+    pending: HashMap<String, PendingLink>,
+    /// 完成关联的账号键 -> 失败记录
+    #[serde(skip)]
+    failures: HashMap<String, LinkFailures>,
+}
+
+impl IdentityState {
+    fn reindex(&mut self) {
+        self.index = self
+            .users
+            .values()
+            .flat_map(|user| {
+                user.identities
+                    .iter()
+                    .map(|identity| (identity.key(), user.id.clone()))
+            })
+            .collect();
+    }
+
+    /// 账号近期失败次数过多时拒绝继续尝试
+    fn check_failures(&mut self, identity: &ChannelIdentity) -> Result<()> {
+        let cutoff = Utc::now() - chrono::Duration::minutes(LINK_FAILURE_WINDOW_MINUTES);
+        self.failures.retain(|_, f| f.since > cutoff);
+        match self.failures.get(&identity.key()) {
+            Some(f) if f.count >= MAX_LINK_FAILURES => Err(OpenClawError::Channel(
+                "关联失败次数过多，请稍后再试".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn record_failure(&mut self, identity: &ChannelIdentity) {
+        let failures = self
+            .failures
+            .entry(identity.key())
+            .or_insert_with(|| LinkFailures {
+                count: 0,
+                since: Utc::now(),
+            });
+        failures.count += 1;
+        if failures.count >= MAX_LINK_FAILURES {
+            warn!("账号 {} 关联失败次数过多，暂时禁止关联", identity.key());
+        }
+    }
+
+    fn user_mut(&mut self, user_id: &str) -> Result<&mut UnifiedUser> {
+        self.users
+            .get_mut(user_id)
+            .ok_or_else(|| OpenClawError::Channel(format!("用户不存在: {}", user_id)))
+    }
+
+    /// 把 source 的账号并入 target 并删除 source
+    fn merge(&mut self, target: &str, source: &str) -> Result<()> {
+        if target == source {
+            return Ok(());
+        }
+        self.user_mut(target)?;
+        let source = self
+            .users
+            .remove(source)
+            .ok_or_else(|| OpenClawError::Channel(format!("用户不存在: {}", source)))?;
+
+        let user = self.user_mut(target)?;
+        for identity in source.identities {
+            if !user.identities.contains(&identity) {
+                user.identities.push(identity);
+            }
+        }
+        if user.display_name.is_none() {
+            user.display_name = source.display_name;
+        }
+        user.updated_at = Utc::now();
+        info!("用户 {} 已并入 {}", source.id, target);
+        Ok(())
+    }
+}
+
+/// 身份关联管理器
+pub struct IdentityManager {
+    dm_policy: Arc<DmPolicyManager>,
+    state: RwLock<IdentityState>,
+    /// 持久化文件，None 时只保存在内存中
+    path: Option<PathBuf>,
+}
+
+impl IdentityManager {
+    pub fn new(dm_policy: Arc<DmPolicyManager>) -> Self {
+        Self {
+            dm_policy,
+            state: RwLock::new(IdentityState::default()),
+            path: None,
+        }
+    }
+
+    /// 从文件加载已关联的身份，之后的变更写回该文件（文件不存在时从空开始）
+    pub fn with_storage(dm_policy: Arc<DmPolicyManager>, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut state: IdentityState = if path.exists() {
+            serde_json::from_str(&std::fs::read_to_string(&path)?)?
+        } else {
+            IdentityState::default()
+        };
+        state.reindex();
+
+        Ok(Self {
+            dm_policy,
+            state: RwLock::new(state),
+            path: Some(path),
+        })
+    }
+
+    /// 解析账号对应的用户 ID
+    pub async fn resolve(&self, identity: &ChannelIdentity) -> ResolvedIdentity {
+        let state = self.state.read().await;
+        match state.index.get(&identity.key()) {
+            Some(user_id) => ResolvedIdentity {
+                user_id: user_id.clone(),
+                linked: true,
+            },
+            None => ResolvedIdentity {
+                user_id: identity.key(),
+                linked: false,
+            },
+        }
+    }
+
+    pub async fn user(&self, user_id: &str) -> Option<UnifiedUser> {
+        self.state.read().await.users.get(user_id).cloned()
+    }
+
+    /// 账号所属的统一用户
+    pub async fn user_of(&self, identity: &ChannelIdentity) -> Option<UnifiedUser> {
+        let state = self.state.read().await;
+        let user_id = state.index.get(&identity.key())?;
+        state.users.get(user_id).cloned()
+    }
+
+    pub async fn list_users(&self) -> Vec<UnifiedUser> {
+        let mut users: Vec<_> = self.state.read().await.users.values().cloned().collect();
+        users.sort_by_key(|user| user.created_at);
+        users
+    }
+
+    /// 为账号生成关联配对码，账号尚未关联时先为它创建统一用户
+    ///
+    /// 配对码绑定该账号，重新生成时旧配对码失效。
+    pub async fn start_link(&self, identity: &ChannelIdentity) -> Result<String> {
+        let user_id = {
+            let mut state = self.state.write().await;
+            match state.index.get(&identity.key()) {
+                Some(user_id) => user_id.clone(),
+                None => {
+                    let user = UnifiedUser::new(identity.clone());
+                    let user_id = user.id.clone();
+                    state.users.insert(user_id.clone(), user);
+                    state.reindex();
+                    self.save(&state)?;
+                    user_id
+                }
+            }
+        };
+
+        self.dm_policy
+            .get_or_create_policy(&user_id, LINK_CHANNEL)
+            .await;
+        let code = self
+            .dm_policy
+            .generate_pairing_code(&user_id, LINK_CHANNEL)
+            .await
+            .map_err(OpenClawError::Channel)?;
+
+        let mut state = self.state.write().await;
+        let cutoff = Utc::now() - chrono::Duration::hours(PENDING_LINK_HOURS);
+        state.pending.retain(|_, link| link.created_at > cutoff);
+        state.pending.insert(
+            identity.key(),
+            PendingLink {
+                code: code.clone(),
+                user_id,
+                created_at: Utc::now(),
+                attempts: 0,
+            },
+        );
+        Ok(code)
+    }
+
+    /// 用发起账号的配对码把账号关联到发起方；账号原先属于其他用户时两者合并
+    pub async fn complete_link(
+        &self,
+        identity: &ChannelIdentity,
+        initiator: &ChannelIdentity,
+        code: &str,
+    ) -> Result<UnifiedUser> {
+        let invalid = || OpenClawError::Channel("配对码无效或已过期".to_string());
+        let user_id = {
+            let mut state = self.state.write().await;
+            state.check_failures(identity)?;
+
+            let key = initiator.key();
+            let Some(link) = state.pending.get_mut(&key) else {
+                state.record_failure(identity);
+                return Err(invalid());
+            };
+            if link.code != code {
+                link.attempts += 1;
+                if link.attempts >= MAX_CODE_ATTEMPTS {
+                    state.pending.remove(&key);
+                    warn!("账号 {} 的配对码输错次数过多，已作废", key);
+                }
+                state.record_failure(identity);
+                return Err(invalid());
+            }
+
+            let link = state.pending.remove(&key).ok_or_else(invalid)?;
+            state.failures.remove(&identity.key());
+            link.user_id
+        };
+
+        if !self
+            .dm_policy
+            .verify_pairing_code(&user_id, LINK_CHANNEL, code, &identity.key())
+            .await
+        {
+            return Err(invalid());
+        }
+
+        let mut state = self.state.write().await;
+        match state.index.get(&identity.key()).cloned() {
+            Some(owner) => state.merge(&user_id, &owner)?,
+            None => {
+                let user = state.user_mut(&user_id)?;
+                user.identities.push(identity.clone());
+                user.updated_at = Utc::now();
+            }
+        }
+        state.reindex();
+        self.save(&state)?;
+
+        info!("账号 {} 已关联到用户 {}", identity.key(), user_id);
+        Ok(state.users[&user_id].clone())
+    }
+
+    /// 管理员直接关联账号（不需要配对码），账号不能已属于其他用户
+    pub async fn link(&self, user_id: &str, identity: &ChannelIdentity) -> Result<UnifiedUser> {
+        let mut state = self.state.write().await;
+        match state.index.get(&identity.key()) {
+            Some(owner) if owner == user_id => {}
+            Some(owner) => {
+                return Err(OpenClawError::Channel(format!(
+                    "账号 {} 已关联到用户 {}，请先解除关联或合并用户",
+                    identity.key(),
+                    owner
+                )));
+            }
+            None => {
+                let user = state.user_mut(user_id)?;
+                user.identities.push(identity.clone());
+                user.updated_at = Utc::now();
+                state.reindex();
+                self.save(&state)?;
+            }
+        }
+        Ok(state.users[user_id].clone())
+    }
+
+    /// 合并两个用户，source 的账号并入 target，source 被删除
+    pub async fn merge(&self, target: &str, source: &str) -> Result<UnifiedUser> {
+        let mut state = self.state.write().await;
+        state.merge(target, source)?;
+        state.reindex();
+        self.save(&state)?;
+        Ok(state.users[target].clone())
+    }
+
+    /// 解除账号的关联，用户没有剩余账号时一并删除
+    pub async fn unlink(&self, identity: &ChannelIdentity) -> Result<()> {
+        let user_id = {
+            let mut state = self.state.write().await;
+            let user_id =
+                state.index.get(&identity.key()).cloned().ok_or_else(|| {
+                    OpenClawError::Channel(format!("账号未关联: {}", identity.key()))
+                })?;
+
+            let user = state.user_mut(&user_id)?;
+            user.identities.retain(|i| i != identity);
+            user.updated_at = Utc::now();
+            if user.identities.is_empty() {
+                state.users.remove(&user_id);
+            }
+            state.reindex();
+            self.save(&state)?;
+            user_id
+        };
+
+        self.dm_policy
+            .unpair(&user_id, LINK_CHANNEL, &identity.key())
+            .await;
+        info!("账号 {} 已解除与用户 {} 的关联", identity.key(), user_id);
+        Ok(())
+    }
+
+    /// 按统一用户检查 DM 权限
+    ///
+    /// 平台账号被拒绝时直接拒绝；已关联时统一用户 ID 上的黑白名单、配对同样生效。
+    /// `account_id` 为接收消息的机器人账号。
+    pub async fn check_access(
+        &self,
+        account_id: &str,
+        identity: &ChannelIdentity,
+        is_group: bool,
+    ) -> DmAccessResult {
+        let direct = self
+            .dm_policy
+            .can_send_message(account_id, &identity.channel, &identity.user_id, is_group)
+            .await;
+        let resolved = self.resolve(identity).await;
+        if direct.is_blocked() || !resolved.linked {
+            return direct;
+        }
+
+        let unified = self
+            .dm_policy
+            .can_send_message(account_id, &identity.channel, &resolved.user_id, is_group)
+            .await;
+        if unified.needs_pairing() {
+            direct
+        } else {
+            unified
+        }
+    }
+
+    /// 检查通道消息的发送者是否允许与助手对话
+    ///
+    /// 元数据中 `is_group` 为 true 时按群组消息处理。
+    pub async fn check_message_access(&self, message: &ChannelMessage) -> DmAccessResult {
+        let is_group = message
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("is_group"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        self.check_access(
+            DEFAULT_DM_ACCOUNT,
+            &ChannelIdentity::from_message(message),
+            is_group,
+        )
+        .await
+    }
+
+    /// 执行关联命令，返回给用户的回复
+    pub async fn handle_command(
+        &self,
+        identity: &ChannelIdentity,
+        command: IdentityCommand,
+    ) -> String {
+        match command {
+            IdentityCommand::StartLink => match self.start_link(identity).await {
+                Ok(code) => format!(
+                    "配对码：{}\n请在另一个通道上发送 /link {} {} 完成关联。",
+                    code,
+                    identity.key(),
+                    code
+                ),
+                Err(e) => format!("生成配对码失败：{}", e),
+            },
+            IdentityCommand::CompleteLink { initiator, code } => {
+                match self.complete_link(identity, &initiator, &code).await {
+                    Ok(user) => format!("关联成功，当前共关联 {} 个账号。", user.identities.len()),
+                    Err(e) => format!("关联失败：{}", e),
+                }
+            }
+            IdentityCommand::Usage => "用法：/link <发起账号> <配对码>".to_string(),
+            IdentityCommand::Unlink => match self.unlink(identity).await {
+                Ok(()) => "已解除关联。".to_string(),
+                Err(e) => format!("解除关联失败：{}", e),
+            },
+        }
+    }
+
+    fn save(&self, state: &IdentityState) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> IdentityManager {
+        IdentityManager::new(Arc::new(DmPolicyManager::default()))
+    }
+
+    #[tokio::test]
+    async fn test_link_resolves_to_same_user() {
+        let manager = manager();
+        let telegram = ChannelIdentity::new("Telegram", "42");
+        let slack = ChannelIdentity::new("slack", "U1");
+
+        assert_eq!(manager.resolve(&slack).await.user_id, "slack:U1");
+
+        let code = manager.start_link(&telegram).await.unwrap();
+        let user = manager
+            .complete_link(&slack, &telegram, &code)
+            .await
+            .unwrap();
+        assert_eq!(user.identities.len(), 2);
+
+        let a = manager.resolve(&telegram).await;
+        let b = manager.resolve(&slack).await;
+        assert!(a.linked && b.linked);
+        assert_eq!(a.user_id, b.user_id);
+
+        // 配对码只能用一次
+        let email = ChannelIdentity::new("email", "a@b.c");
+        assert!(
+            manager
+                .complete_link(&email, &telegram, &code)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_link_attempts_limited() {
+        let manager = manager();
+        let victim = ChannelIdentity::new("telegram", "42");
+        let attacker = ChannelIdentity::new("slack", "U666");
+
+        // 配对码绑定发起账号，换一个账号使用无效
+        let code = manager.start_link(&victim).await.unwrap();
+        let other = ChannelIdentity::new("email", "x@y.z");
+        assert!(
+            manager
+                .complete_link(&attacker, &other, &code)
+                .await
+                .is_err()
+        );
+
+        let wrong = if code == "000000" { "000001" } else { "000000" };
+        for _ in 0..MAX_CODE_ATTEMPTS {
+            assert!(
+                manager
+                    .complete_link(&attacker, &victim, wrong)
+                    .await
+                    .is_err()
+            );
+        }
+        // 输错次数用尽后正确的配对码也已作废
+        assert!(
+            manager
+                .complete_link(&attacker, &victim, &code)
+                .await
+                .is_err()
+        );
+        assert!(!manager.resolve(&attacker).await.linked);
+
+        // 失败过多的账号暂时不能再尝试，即使配对码正确
+        for _ in 0..MAX_LINK_FAILURES {
+            let _ = manager.complete_link(&attacker, &victim, wrong).await;
+        }
+        let code = manager.start_link(&victim).await.unwrap();
+        let err = manager
+            .complete_link(&attacker, &victim, &code)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("过多"));
+
+        let slack = ChannelIdentity::new("slack", "U1");
+        manager.complete_link(&slack, &victim, &code).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_message_access() {
+        use crate::dm_policy::{DmAccessPolicy, DmPolicyConfig};
+        use crate::types::ChannelType;
+
+        let dm_policy = Arc::new(DmPolicyManager::new(DmPolicyConfig {
+            default_policy: DmAccessPolicy::AllowAll,
+            ..Default::default()
+        }));
+        let manager = IdentityManager::new(dm_policy.clone());
+        let message = ChannelMessage {
+            id: "1".to_string(),
+            channel_type: ChannelType::WhatsApp,
+            chat_id: "chat".to_string(),
+            user_id: "15550001".to_string(),
+            content: "hi".to_string(),
+            timestamp: Utc::now(),
+            metadata: None,
+            thread_id: None,
+            reply_to: None,
+        };
+        assert_eq!(
+            ChannelIdentity::from_message(&message).key(),
+            "whatsapp:15550001"
+        );
+        assert!(manager.check_message_access(&message).await.is_allowed());
+
+        // 统一用户被拉黑后，其关联的所有账号都被拒绝
+        let telegram = ChannelIdentity::new("telegram", "42");
+        let code = manager.start_link(&telegram).await.unwrap();
+        let whatsapp = ChannelIdentity::from_message(&message);
+        let user = manager
+            .complete_link(&whatsapp, &telegram, &code)
+            .await
+            .unwrap();
+        dm_policy
+            .add_to_blocked(DEFAULT_DM_ACCOUNT, "whatsapp", &user.id)
+            .await;
+        assert!(manager.check_message_access(&message).await.is_blocked());
+    }
+
+    #[tokio::test]
+    async fn test_link_merges_existing_users_and_unlink() {
+        let manager = manager();
+        let telegram = ChannelIdentity::new("telegram", "42");
+        let slack = ChannelIdentity::new("slack", "U1");
+        let email = ChannelIdentity::new("email", "a@b.c");
+
+        let code = manager.start_link(&telegram).await.unwrap();
+        let target = manager
+            .complete_link(&slack, &telegram, &code)
+            .await
+            .unwrap();
+        let code = manager.start_link(&email).await.unwrap();
+        manager.complete_link(&slack, &email, &code).await.unwrap();
+
+        // slack 原属的用户并入 email 发起方
+        assert_eq!(manager.list_users().await.len(), 1);
+        assert_ne!(manager.resolve(&telegram).await.user_id, target.id);
+        assert!(manager.user(&target.id).await.is_none());
+
+        manager.unlink(&slack).await.unwrap();
+        assert!(!manager.resolve(&slack).await.linked);
+        assert_eq!(manager.user_of(&email).await.unwrap().identities.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_identity_command() {
+        assert_eq!(
+            IdentityCommand::parse("/link"),
+            Some(IdentityCommand::StartLink)
+        );
+        assert_eq!(
+            IdentityCommand::parse("/link Telegram:42 123456"),
+            Some(IdentityCommand::CompleteLink {
+                initiator: ChannelIdentity::new("telegram", "42"),
+                code: "123456".to_string(),
+            })
+        );
+        assert_eq!(
+            IdentityCommand::parse("/link 123456"),
+            Some(IdentityCommand::Usage)
+        );
+        assert_eq!(
+            IdentityCommand::parse("/unlink"),
+            Some(IdentityCommand::Unlink)
+        );
+        assert_eq!(IdentityCommand::parse("hello /link"), None);
+    }
+}
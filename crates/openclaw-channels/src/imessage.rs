@@ -287,6 +287,8 @@ end tell
                 "method": "imessage",
                 "result": result,
             })),
+            thread_id: None,
+            reply_to: None,
         })
     }
 
@@ -361,6 +363,8 @@ end tell
                     "has_attachment": true,
                     "attachment_path": attachment_path,
                 })),
+                thread_id: None,
+                reply_to: None,
             })
         } else {
             let error = String::from_utf8_lossy(&output.stderr);
@@ -39,13 +39,16 @@ pub mod email;
 pub mod factory;
 pub mod feishu;
 pub mod googlechat;
+pub mod identity;
 pub mod imessage;
 pub mod manager;
 pub mod matrix;
 pub mod registry;
+pub mod reply;
 pub mod signal;
 pub mod slack;
 pub mod sms;
+pub mod streaming;
 pub mod teams;
 pub mod telegram;
 pub mod types;
@@ -59,6 +62,9 @@ pub use account::{AccountConfig, AccountId, AccountManager, Platform};
 pub use base::{Channel, ChannelEvent, ChannelHandler};
 pub use config::ChannelConfigs;
 pub use factory::ChannelFactoryRegistry;
+pub use identity::{ChannelIdentity, IdentityCommand, IdentityManager, UnifiedUser};
 pub use manager::ChannelManager;
 pub use registry::{register_default_channels, register_channels_from_config};
-pub use types::{ChannelMessage, ChannelType, SendMessage};
+pub use reply::ReplySession;
+pub use streaming::{StreamingOptions, StreamingReply, TextStream};
+pub use types::{ChannelCapabilities, ChannelMessage, ChannelType, SendMessage};
@@ -4,8 +4,12 @@ use tokio::sync::RwLock;
 
 use crate::base::{Channel, ChannelEvent, ChannelHandler};
 use crate::factory::ChannelFactoryRegistry;
-use crate::types::{ChannelMessage, SendMessage};
-use openclaw_core::Result;
+use crate::reply::ReplySession;
+use crate::streaming::{StreamingOptions, StreamingReply};
+use crate::types::{ChannelCapabilities, ChannelMessage, SendMessage};
+use openclaw_core::telemetry::{CHANNEL_MESSAGES_IN_FLIGHT, CHANNEL_MESSAGES_TOTAL};
+use openclaw_core::{OpenClawError, Result};
+use tracing::Instrument;
 
 pub struct ChannelManager {
     channels: Arc<RwLock<HashMap<String, Arc<RwLock<dyn Channel>>>>>,
@@ -83,23 +87,177 @@ impl ChannelManager {
         channel_name: &str,
         message: SendMessage,
     ) -> Result<ChannelMessage> {
-        let channel = {
-            let channels = self.channels.read().await;
-            channels.get(channel_name).cloned().ok_or_else(|| {
-                openclaw_core::OpenClawError::Config(format!("Channel not found: {}", channel_name))
-            })?
-        };
+        let channel = self.require_channel(channel_name).await?;
+
+        let result = channel
+            .write()
+            .await
+            .send(message)
+            .instrument(tracing::info_span!("channel.send", channel = channel_name))
+            .await;
+        record_outbound(channel_name, result.is_ok());
+        result
+    }
+
+    /// 通道能力，通道不存在时返回 None
+    pub async fn capabilities(&self, channel_name: &str) -> Option<ChannelCapabilities> {
+        let channel = self.get_channel(channel_name).await?;
+        let capabilities = channel.read().await.capabilities();
+        Some(capabilities)
+    }
+
+    pub async fn edit_in_channel(
+        &self,
+        channel_name: &str,
+        message_id: &str,
+        message: SendMessage,
+    ) -> Result<ChannelMessage> {
+        let channel = self.require_channel(channel_name).await?;
+        let channel = channel.read().await;
+        channel
+            .edit_message(message_id, message)
+            .instrument(tracing::info_span!("channel.edit", channel = channel_name))
+            .await
+    }
 
-        channel.write().await.send(message).await
+    pub async fn delete_in_channel(
+        &self,
+        channel_name: &str,
+        chat_id: &str,
+        message_id: &str,
+    ) -> Result<()> {
+        let channel = self.require_channel(channel_name).await?;
+        let channel = channel.read().await;
+        channel.delete_message(chat_id, message_id).await
+    }
+
+    pub async fn react_in_channel(
+        &self,
+        channel_name: &str,
+        chat_id: &str,
+        message_id: &str,
+        emoji: &str,
+    ) -> Result<()> {
+        let channel = self.require_channel(channel_name).await?;
+        let channel = channel.read().await;
+        channel.add_reaction(chat_id, message_id, emoji).await
+    }
+
+    pub async fn typing_in_channel(&self, channel_name: &str, chat_id: &str) -> Result<()> {
+        let channel = self.require_channel(channel_name).await?;
+        let channel = channel.read().await;
+        channel.send_typing(chat_id).await
+    }
+
+    /// 为入站消息创建回复会话：回复挂在来源线程下，多次回复尽量合并编辑为一条
+    pub async fn reply_session(&self, source: &ChannelMessage) -> Option<ReplySession> {
+        let channel = self.source_channel(source).await?;
+        Some(ReplySession::new(channel, source.clone()).await)
     }
 
     pub async fn process_event(&self, event: ChannelEvent) {
+        // 入站消息是一条追踪链路的起点，后续 Agent、LLM、工具调用的 span 都挂在其下
+        let span = match &event {
+            ChannelEvent::Message(msg) => tracing::info_span!(
+                "channel.ingress",
+                channel = %channel_label(msg),
+                chat_id = %msg.chat_id,
+                message_id = %msg.id,
+            ),
+            _ => tracing::Span::none(),
+        };
+        let channel = match &event {
+            ChannelEvent::Message(msg) => Some(channel_label(msg)),
+            _ => None,
+        };
+
+        async {
+            // 处理被取消时 drop 也会回落在途计数
+            let in_flight = channel.as_ref().map(|channel| {
+                InFlight::enter(metrics::gauge!(
+                    CHANNEL_MESSAGES_IN_FLIGHT,
+                    "channel" => channel.clone()
+                ))
+            });
+
+            let status = match &event {
+                ChannelEvent::Message(msg) => self.dispatch_message(msg).await,
+                _ => {
+                    let mut status = "ok";
+                    let handlers = self.handlers.read().await;
+                    for handler in handlers.iter() {
+                        if let Err(e) = handler.handle_event(event.clone()).await {
+                            tracing::error!("Handler error: {}", e);
+                            status = "error";
+                        }
+                    }
+                    status
+                }
+            };
+
+            drop(in_flight);
+            if let Some(channel) = channel {
+                metrics::counter!(
+                    CHANNEL_MESSAGES_TOTAL,
+                    "channel" => channel,
+                    "direction" => "inbound",
+                    "status" => status
+                )
+                .increment(1);
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// 交给各处理器处理入站消息，回复经同一个回复会话发回来源线程
+    async fn dispatch_message(&self, msg: &ChannelMessage) -> &'static str {
+        let mut session = self.reply_session(msg).await;
+        if let Some(session) = &session {
+            session.typing().await;
+        }
+
+        let mut status = "ok";
         let handlers = self.handlers.read().await;
         for handler in handlers.iter() {
-            if let Err(e) = handler.handle_event(event.clone()).await {
+            if let Err(e) = self.run_handler(handler, msg, session.as_mut()).await {
                 tracing::error!("Handler error: {}", e);
+                status = "error";
             }
         }
+        status
+    }
+
+    /// 运行单个处理器：优先流式回复，处理器不支持时一次性回复
+    async fn run_handler(
+        &self,
+        handler: &Arc<dyn ChannelHandler>,
+        msg: &ChannelMessage,
+        session: Option<&mut ReplySession>,
+    ) -> Result<()> {
+        let label = channel_label(msg);
+
+        if let Some(stream) = handler.handle_stream(msg.clone()).await? {
+            let Some(session) = session else {
+                tracing::warn!("No channel registered for {}, reply dropped", label);
+                return Ok(());
+            };
+            let options = StreamingOptions::for_channel(&msg.channel_type);
+            let result = StreamingReply::new(session, options).run(stream).await;
+            record_outbound(&label, result.is_ok());
+            return result.map(|_| ());
+        }
+
+        let Some(reply) = handler.handle(msg.clone()).await? else {
+            return Ok(());
+        };
+        let Some(session) = session else {
+            tracing::warn!("No channel registered for {}, reply dropped", label);
+            return Ok(());
+        };
+        let result = session.push(reply).await;
+        record_outbound(&label, result.is_ok());
+        result.map(|_| ())
     }
 
     pub async fn broadcast(&self, message: SendMessage) -> Result<Vec<ChannelMessage>> {
@@ -107,7 +265,9 @@ impl ChannelManager {
         let mut results = Vec::new();
 
         for (name, channel) in channels.iter() {
-            match channel.write().await.send(message.clone()).await {
+            let result = channel.write().await.send(message.clone()).await;
+            record_outbound(name, result.is_ok());
+            match result {
                 Ok(msg) => results.push(msg),
                 Err(e) => tracing::warn!("Failed to send to channel {}: {}", name, e),
             }
@@ -146,7 +306,7 @@ impl ChannelManager {
             channels.insert(channel_type.to_string(), channel.clone());
             Ok(channel)
         } else {
-            Err(openclaw_core::OpenClawError::Config(format!(
+            Err(OpenClawError::Config(format!(
                 "Channel type '{}' not found and no factory available to create it",
                 channel_type
             )))
@@ -154,8 +314,60 @@ impl ChannelManager {
     }
 }
 
+impl ChannelManager {
+    async fn require_channel(&self, channel_name: &str) -> Result<Arc<RwLock<dyn Channel>>> {
+        self.get_channel(channel_name)
+            .await
+            .ok_or_else(|| OpenClawError::Config(format!("Channel not found: {}", channel_name)))
+    }
+
+    /// 找到入站消息所属的通道：先按类型名查找，再按通道类型匹配
+    async fn source_channel(&self, message: &ChannelMessage) -> Option<Arc<RwLock<dyn Channel>>> {
+        if let Some(channel) = self.get_channel(&channel_label(message)).await {
+            return Some(channel);
+        }
+        let channels = self.channels.read().await;
+        for channel in channels.values() {
+            if channel.read().await.channel_type() == message.channel_type {
+                return Some(channel.clone());
+            }
+        }
+        None
+    }
+}
+
 impl Default for ChannelManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+fn channel_label(message: &ChannelMessage) -> String {
+    message.channel_type.as_str().to_string()
+}
+
+fn record_outbound(channel: &str, ok: bool) {
+    metrics::counter!(
+        CHANNEL_MESSAGES_TOTAL,
+        "channel" => channel.to_string(),
+        "direction" => "outbound",
+        "status" => if ok { "ok" } else { "error" }
+    )
+    .increment(1);
+}
+
+/// 在途计数，drop 时减一
+struct InFlight(metrics::Gauge);
+
+impl InFlight {
+    fn enter(gauge: metrics::Gauge) -> Self {
+        gauge.increment(1.0);
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.decrement(1.0);
+    }
+}
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use openclaw_core::{OpenClawError, Result};
 
 use crate::base::Channel;
-use crate::types::{ChannelMessage, ChannelType, SendMessage};
+use crate::types::{ChannelCapabilities, ChannelMessage, ChannelType, SendMessage};
 
 /// Matrix 配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -168,9 +168,20 @@ impl MatrixClient {
         message_type: &str,
         body: &str,
     ) -> Result<MatrixSendResponse> {
-        let txn_id = format!("m{}", uuid::Uuid::new_v4());
+        self.send_reply(room_id, message_type, body, None, None)
+            .await
+    }
 
-        let content = match message_type {
+    /// 发送房间消息，可回复指定事件或发到线程中
+    pub async fn send_reply(
+        &self,
+        room_id: &str,
+        message_type: &str,
+        body: &str,
+        thread_id: Option<&str>,
+        reply_to: Option<&str>,
+    ) -> Result<MatrixSendResponse> {
+        let mut content = match message_type {
             "text" | "markdown" => {
                 serde_json::json!({
                     "msgtype": "m.text",
@@ -192,9 +203,24 @@ impl MatrixClient {
             }
         };
 
+        if let Some(relation) = relation(thread_id, reply_to) {
+            content["m.relates_to"] = relation;
+        }
+
+        self.send_event(room_id, "m.room.message", content).await
+    }
+
+    /// 发送任意房间事件
+    async fn send_event(
+        &self,
+        room_id: &str,
+        event_type: &str,
+        content: serde_json::Value,
+    ) -> Result<MatrixSendResponse> {
+        let txn_id = format!("m{}", uuid::Uuid::new_v4());
         let endpoint = format!(
-            "/_matrix/client/r0/rooms/{}/send/m.room.message/{}",
-            room_id, txn_id
+            "/_matrix/client/r0/rooms/{}/send/{}/{}",
+            room_id, event_type, txn_id
         );
 
         self.request(reqwest::Method::PUT, &endpoint, Some(content))
@@ -368,7 +394,13 @@ impl Channel for MatrixClient {
 
     async fn send(&self, message: SendMessage) -> Result<ChannelMessage> {
         let response = self
-            .send_message(&message.chat_id, &message.message_type, &message.content)
+            .send_reply(
+                &message.chat_id,
+                &message.message_type,
+                &message.content,
+                message.thread_id.as_deref(),
+                message.reply_to.as_deref(),
+            )
             .await?;
 
         Ok(ChannelMessage {
@@ -379,9 +411,92 @@ impl Channel for MatrixClient {
             content: message.content,
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: message.thread_id,
+            reply_to: message.reply_to,
         })
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            threads: true,
+            replies: true,
+            edit: true,
+            delete: true,
+            reactions: true,
+            typing: self.config.user_id.is_some(),
+        }
+    }
+
+    async fn edit_message(&self, message_id: &str, message: SendMessage) -> Result<ChannelMessage> {
+        // 不支持编辑的客户端显示带 * 前缀的回退内容
+        let body = serde_json::json!({
+            "msgtype": "m.text",
+            "body": format!("* {}", message.content),
+            "m.new_content": {
+                "msgtype": "m.text",
+                "body": message.content
+            },
+            "m.relates_to": {
+                "rel_type": "m.replace",
+                "event_id": message_id
+            }
+        });
+        self.send_event(&message.chat_id, "m.room.message", body)
+            .await?;
+
+        // 编辑后消息仍以原事件 ID 标识
+        Ok(ChannelMessage {
+            id: message_id.to_string(),
+            channel_type: ChannelType::Matrix,
+            chat_id: message.chat_id,
+            user_id: self.config.user_id.clone().unwrap_or_default(),
+            content: message.content,
+            timestamp: chrono::Utc::now(),
+            metadata: None,
+            thread_id: message.thread_id,
+            reply_to: message.reply_to,
+        })
+    }
+
+    async fn delete_message(&self, chat_id: &str, message_id: &str) -> Result<()> {
+        let txn_id = format!("m{}", uuid::Uuid::new_v4());
+        let endpoint = format!(
+            "/_matrix/client/r0/rooms/{}/redact/{}/{}",
+            chat_id, message_id, txn_id
+        );
+        let _: MatrixSendResponse = self
+            .request(reqwest::Method::PUT, &endpoint, Some(serde_json::json!({})))
+            .await?;
+        Ok(())
+    }
+
+    async fn add_reaction(&self, chat_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        let body = serde_json::json!({
+            "m.relates_to": {
+                "rel_type": "m.annotation",
+                "event_id": message_id,
+                "key": emoji
+            }
+        });
+        self.send_event(chat_id, "m.reaction", body).await?;
+        Ok(())
+    }
+
+    async fn send_typing(&self, chat_id: &str) -> Result<()> {
+        let Some(user_id) = &self.config.user_id else {
+            return Ok(());
+        };
+        let endpoint = format!("/_matrix/client/r0/rooms/{}/typing/{}", chat_id, user_id);
+        let _: serde_json::Value = self
+            .request(
+                reqwest::Method::PUT,
+                &endpoint,
+                Some(serde_json::json!({ "typing": true, "timeout": 30000 })),
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<bool> {
         if self.config.access_token.is_none() {
             return Ok(false);
@@ -408,6 +523,14 @@ pub fn parse_matrix_message(event: &serde_json::Value) -> Option<ChannelMessage>
     }
 
     let body = content.get("body")?.as_str()?;
+    let relates_to = content.get("m.relates_to");
+    let thread_id = relates_to
+        .filter(|r| r.get("rel_type").and_then(|t| t.as_str()) == Some("m.thread"))
+        .and_then(|r| r.get("event_id")?.as_str())
+        .map(str::to_string);
+    let reply_to = relates_to
+        .and_then(|r| r.get("m.in_reply_to")?.get("event_id")?.as_str())
+        .map(str::to_string);
 
     Some(ChannelMessage {
         id: event.get("event_id")?.as_str()?.to_string(),
@@ -417,5 +540,23 @@ pub fn parse_matrix_message(event: &serde_json::Value) -> Option<ChannelMessage>
         content: body.to_string(),
         timestamp: chrono::Utc::now(),
         metadata: Some(event.clone()),
+        thread_id,
+        reply_to,
     })
 }
+
+/// 构建 `m.relates_to`：线程消息同时带上回复回退，兼容不支持线程的客户端
+fn relation(thread_id: Option<&str>, reply_to: Option<&str>) -> Option<serde_json::Value> {
+    match (thread_id, reply_to) {
+        (Some(thread), reply) => Some(serde_json::json!({
+            "rel_type": "m.thread",
+            "event_id": thread,
+            "is_falling_back": reply.is_none(),
+            "m.in_reply_to": { "event_id": reply.unwrap_or(thread) }
+        })),
+        (None, Some(reply)) => Some(serde_json::json!({
+            "m.in_reply_to": { "event_id": reply }
+        })),
+        (None, None) => None,
+    }
+}
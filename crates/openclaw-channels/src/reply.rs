@@ -0,0 +1,260 @@
+//! 回复会话
+//!
+//! 对一条入站消息的回复发到同一线程；支持编辑的通道把后续更新写回同一条消息，
+//! 不支持的通道退化为在线程内追加新消息。
+
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::base::Channel;
+use crate::types::{ChannelCapabilities, ChannelMessage, SendMessage};
+use openclaw_core::Result;
+
+pub struct ReplySession {
+    channel: Arc<RwLock<dyn Channel>>,
+    capabilities: ChannelCapabilities,
+    source: ChannelMessage,
+    /// 已发出的回复消息，后续编辑都针对它
+    sent: Option<ChannelMessage>,
+    /// 已发出回复的完整内容
+    content: String,
+}
+
+impl ReplySession {
+    pub async fn new(channel: Arc<RwLock<dyn Channel>>, source: ChannelMessage) -> Self {
+        let capabilities = channel.read().await.capabilities();
+        Self {
+            channel,
+            capabilities,
+            source,
+            sent: None,
+            content: String::new(),
+        }
+    }
+
+    pub fn capabilities(&self) -> ChannelCapabilities {
+        self.capabilities
+    }
+
+    /// 被回复的入站消息
+    pub fn source(&self) -> &ChannelMessage {
+        &self.source
+    }
+
+    /// 当前回复消息（尚未发送时为 None）
+    pub fn message(&self) -> Option<&ChannelMessage> {
+        self.sent.as_ref()
+    }
+
+    /// 提示对方正在输入，失败只记录日志
+    pub async fn typing(&self) {
+        if !self.capabilities.typing {
+            return;
+        }
+        let channel = self.channel.read().await;
+        if let Err(e) = channel.send_typing(&self.source.chat_id).await {
+            tracing::debug!("Typing indicator failed on {}: {}", channel.name(), e);
+        }
+    }
+
+    /// 发送一条回复；已有文本回复且通道可编辑时追加到同一条消息
+    pub async fn push(&mut self, reply: SendMessage) -> Result<ChannelMessage> {
+        if self.sent.is_some() && self.capabilities.edit && is_text(&reply) {
+            let content = format!("{}\n\n{}", self.content, reply.content);
+            return self.replace(&content).await;
+        }
+        self.post(reply).await
+    }
+
+    /// 用新内容替换当前回复；不能编辑时在线程内另发一条
+    pub async fn replace(&mut self, content: &str) -> Result<ChannelMessage> {
+        let reply = SendMessage::text(self.source.chat_id.clone(), content);
+        self.replace_with(reply).await
+    }
+
+    /// 同 `replace`，可指定消息格式
+    pub async fn replace_with(&mut self, reply: SendMessage) -> Result<ChannelMessage> {
+        let Some(sent) = &self.sent else {
+            return self.post(reply).await;
+        };
+        if !self.capabilities.edit {
+            return self.post(reply).await;
+        }
+
+        let message_id = sent.id.clone();
+        let mut reply = self.threaded(reply);
+        reply.chat_id = sent.chat_id.clone();
+        let content = reply.content.clone();
+        let edited = self
+            .channel
+            .read()
+            .await
+            .edit_message(&message_id, reply)
+            .await?;
+        self.content = content;
+        self.sent = Some(edited.clone());
+        Ok(edited)
+    }
+
+    /// 结束当前回复消息，之后的内容发成新消息（仍在同一线程内）
+    pub fn next_message(&mut self) {
+        self.sent = None;
+        self.content.clear();
+    }
+
+    /// 撤回当前回复消息
+    pub async fn delete(&mut self) -> Result<()> {
+        let Some(sent) = self.sent.take() else {
+            return Ok(());
+        };
+        self.content.clear();
+        self.channel
+            .read()
+            .await
+            .delete_message(&sent.chat_id, &sent.id)
+            .await
+    }
+
+    async fn post(&mut self, reply: SendMessage) -> Result<ChannelMessage> {
+        let reply = self.threaded(reply);
+        let content = reply.content.clone();
+        let text = is_text(&reply);
+        let sent = self.channel.read().await.send(reply).await?;
+        // 只有文本回复才作为后续编辑的目标
+        if text {
+            self.content = content;
+            self.sent = Some(sent.clone());
+        }
+        Ok(sent)
+    }
+
+    /// 回复默认挂到来源消息下，处理器显式指定的线程优先
+    fn threaded(&self, mut reply: SendMessage) -> SendMessage {
+        if reply.chat_id.is_empty() {
+            reply.chat_id = self.source.chat_id.clone();
+        }
+        if reply.reply_to.is_none() && reply.thread_id.is_none() {
+            reply = reply.in_reply_to(&self.source);
+        }
+        reply
+    }
+}
+
+fn is_text(reply: &SendMessage) -> bool {
+    matches!(
+        reply.message_type.as_str(),
+        "text" | "markdown" | "markdownv2" | "markdown_v2" | "html"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ChannelType;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingChannel {
+        capabilities: ChannelCapabilities,
+        sent: Mutex<Vec<SendMessage>>,
+    }
+
+    fn message(id: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: id.to_string(),
+            channel_type: ChannelType::Slack,
+            chat_id: "C1".to_string(),
+            user_id: "U1".to_string(),
+            content: content.to_string(),
+            timestamp: chrono::Utc::now(),
+            metadata: None,
+            thread_id: None,
+            reply_to: None,
+        }
+    }
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn channel_type(&self) -> ChannelType {
+            ChannelType::Slack
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn start(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, message: SendMessage) -> Result<ChannelMessage> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(message.clone());
+            Ok(message_with(format!("m{}", sent.len()), message))
+        }
+
+        fn capabilities(&self) -> ChannelCapabilities {
+            self.capabilities
+        }
+
+        async fn edit_message(
+            &self,
+            message_id: &str,
+            reply: SendMessage,
+        ) -> Result<ChannelMessage> {
+            Ok(message(message_id, &reply.content))
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    fn message_with(id: String, sent: SendMessage) -> ChannelMessage {
+        let mut msg = message(&id, &sent.content);
+        msg.thread_id = sent.thread_id;
+        msg.reply_to = sent.reply_to;
+        msg
+    }
+
+    async fn session(capabilities: ChannelCapabilities) -> ReplySession {
+        let channel: Arc<RwLock<dyn Channel>> = Arc::new(RwLock::new(RecordingChannel {
+            capabilities,
+            ..Default::default()
+        }));
+        ReplySession::new(channel, message("in1", "hi")).await
+    }
+
+    #[tokio::test]
+    async fn test_reply_is_threaded_and_edited_in_place() {
+        let mut session = session(ChannelCapabilities {
+            edit: true,
+            threads: true,
+            ..Default::default()
+        })
+        .await;
+
+        let first = session.push(SendMessage::text("", "part 1")).await.unwrap();
+        assert_eq!(first.reply_to.as_deref(), Some("in1"));
+        assert_eq!(first.chat_id, "C1");
+
+        let second = session.push(SendMessage::text("", "part 2")).await.unwrap();
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.content, "part 1\n\npart 2");
+    }
+
+    #[tokio::test]
+    async fn test_reply_without_edit_sends_new_messages() {
+        let mut session = session(ChannelCapabilities::default()).await;
+
+        let first = session.push(SendMessage::text("C1", "a")).await.unwrap();
+        let second = session.replace("b").await.unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(second.reply_to.as_deref(), Some("in1"));
+    }
+}
@@ -133,6 +133,8 @@ impl SignalChannel {
                 "attachments": msg.attachments,
                 "group_id": msg.group_id,
             })),
+            thread_id: None,
+            reply_to: None,
         }
     }
 }
@@ -220,6 +222,8 @@ impl Channel for SignalChannel {
                 "message_id": response.id,
                 "timestamp": response.timestamp,
             })),
+            thread_id: None,
+            reply_to: None,
         })
     }
 
@@ -373,6 +377,8 @@ impl SignalChannel {
                 "message_id": resp.id,
                 "is_group": true,
             })),
+            thread_id: None,
+            reply_to: None,
         })
     }
 }
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::base::Channel;
-use crate::types::{ChannelMessage, ChannelType, SendMessage};
+use crate::types::{ChannelCapabilities, ChannelMessage, ChannelType, SendMessage};
 
 /// Slack 配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,14 +85,18 @@ impl SlackChannel {
         text: &str,
         blocks: Option<Vec<SlackBlock>>,
     ) -> Result<()> {
-        let bot_token = self
-            .config
-            .bot_token
-            .as_ref()
-            .ok_or_else(|| OpenClawError::Config("未配置 Bot Token".to_string()))?;
-
-        let url = self.get_api_url("chat.postMessage");
+        self.post_message(channel, text, blocks, None).await?;
+        Ok(())
+    }
 
+    /// 发送消息，可指定线程；返回消息的 ts
+    pub async fn post_message(
+        &self,
+        channel: &str,
+        text: &str,
+        blocks: Option<Vec<SlackBlock>>,
+        thread_ts: Option<&str>,
+    ) -> Result<String> {
         let mut body = json!({
             "channel": channel,
             "text": text
@@ -101,13 +105,30 @@ impl SlackChannel {
         if let Some(b) = blocks {
             body["blocks"] = json!(b);
         }
+        if let Some(ts) = thread_ts {
+            body["thread_ts"] = json!(ts);
+        }
+
+        let result = self.call("chat.postMessage", &body).await?;
+        result
+            .ts
+            .ok_or_else(|| OpenClawError::AIProvider("Slack API 未返回消息 ts".to_string()))
+    }
+
+    /// 调用 Bot API 方法
+    async fn call(&self, method: &str, body: &serde_json::Value) -> Result<SlackResponse> {
+        let bot_token = self
+            .config
+            .bot_token
+            .as_ref()
+            .ok_or_else(|| OpenClawError::Config("未配置 Bot Token".to_string()))?;
 
         let response = self
             .client
-            .post(&url)
+            .post(self.get_api_url(method))
             .header("Authorization", format!("Bearer {}", bot_token))
             .header("Content-Type", "application/json")
-            .json(&body)
+            .json(body)
             .send()
             .await
             .map_err(|e| OpenClawError::Http(format!("Slack API 请求失败: {}", e)))?;
@@ -132,7 +153,12 @@ impl SlackChannel {
             )));
         }
 
-        Ok(())
+        Ok(result)
+    }
+
+    /// 是否通过 Bot API 发送（Webhook 拿不到消息 ts，无法编辑或回复）
+    fn uses_bot_api(&self) -> bool {
+        self.config.webhook_url.is_none() && self.config.bot_token.is_some()
     }
 
     /// 发送临时消息（只有用户可见）
@@ -229,32 +255,38 @@ impl Channel for SlackChannel {
     }
 
     async fn send(&self, message: SendMessage) -> Result<ChannelMessage> {
-        let message_id = uuid::Uuid::new_v4().to_string();
+        let mut message_id = uuid::Uuid::new_v4().to_string();
+        // Slack 线程以根消息的 ts 标识，回复没有线程时以被回复消息为根
+        let thread_ts = message
+            .thread_id
+            .clone()
+            .or_else(|| message.reply_to.clone());
 
         // 优先使用 Webhook
         if self.config.webhook_url.is_some() {
             self.send_webhook(&message.content, None).await?;
         } else if self.config.bot_token.is_some() && !message.chat_id.is_empty() {
             // 使用 Bot API
-            match message.message_type.as_str() {
-                "blocks" => {
-                    let blocks = vec![SlackBlock {
-                        r#type: "section".to_string(),
-                        text: Some(SlackText {
-                            r#type: "mrkdwn".to_string(),
-                            text: message.content.clone(),
-                            emoji: None,
-                        }),
-                        ..Default::default()
-                    }];
-                    self.send_to_channel(&message.chat_id, &message.content, Some(blocks))
-                        .await?;
-                }
-                _ => {
-                    self.send_to_channel(&message.chat_id, &message.content, None)
-                        .await?;
-                }
-            }
+            let blocks = match message.message_type.as_str() {
+                "blocks" => Some(vec![SlackBlock {
+                    r#type: "section".to_string(),
+                    text: Some(SlackText {
+                        r#type: "mrkdwn".to_string(),
+                        text: message.content.clone(),
+                        emoji: None,
+                    }),
+                    ..Default::default()
+                }]),
+                _ => None,
+            };
+            message_id = self
+                .post_message(
+                    &message.chat_id,
+                    &message.content,
+                    blocks,
+                    thread_ts.as_deref(),
+                )
+                .await?;
         } else {
             return Err(OpenClawError::Config(
                 "Slack 通道需要配置 webhook_url 或 bot_token + chat_id".to_string(),
@@ -269,9 +301,67 @@ impl Channel for SlackChannel {
             content: message.content.clone(),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: thread_ts,
+            reply_to: message.reply_to,
+        })
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        if !self.uses_bot_api() {
+            return ChannelCapabilities::default();
+        }
+        // Bot 无法发送“正在输入”提示（仅 RTM 支持）
+        ChannelCapabilities {
+            threads: true,
+            replies: true,
+            edit: true,
+            delete: true,
+            reactions: true,
+            typing: false,
+        }
+    }
+
+    async fn edit_message(&self, message_id: &str, message: SendMessage) -> Result<ChannelMessage> {
+        let body = json!({
+            "channel": message.chat_id,
+            "ts": message_id,
+            "text": message.content
+        });
+        self.call("chat.update", &body).await?;
+
+        Ok(ChannelMessage {
+            id: message_id.to_string(),
+            channel_type: ChannelType::Slack,
+            chat_id: message.chat_id,
+            user_id: "bot".to_string(),
+            content: message.content,
+            timestamp: chrono::Utc::now(),
+            metadata: None,
+            thread_id: message.thread_id,
+            reply_to: message.reply_to,
         })
     }
 
+    async fn delete_message(&self, chat_id: &str, message_id: &str) -> Result<()> {
+        let body = json!({
+            "channel": chat_id,
+            "ts": message_id
+        });
+        self.call("chat.delete", &body).await?;
+        Ok(())
+    }
+
+    async fn add_reaction(&self, chat_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        // Slack 使用表情名称，不带冒号
+        let body = json!({
+            "channel": chat_id,
+            "timestamp": message_id,
+            "name": emoji.trim_matches(':')
+        });
+        self.call("reactions.add", &body).await?;
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<bool> {
         Ok(self.config.enabled)
     }
@@ -282,6 +372,8 @@ impl Channel for SlackChannel {
 struct SlackResponse {
     ok: bool,
     error: Option<String>,
+    /// 消息时间戳，同时是消息 ID
+    ts: Option<String>,
 }
 
 /// Slack Block
@@ -319,5 +411,20 @@ mod tests {
         };
         let channel = SlackChannel::new(config);
         assert_eq!(channel.name(), "slack");
+        // 配置了 Webhook 时走 Webhook，拿不到 ts，不能编辑
+        assert!(!channel.capabilities().edit);
+    }
+
+    #[test]
+    fn test_slack_bot_capabilities() {
+        let config = SlackConfig {
+            bot_token: Some("xoxb-xxx".to_string()),
+            webhook_url: None,
+            app_token: None,
+            enabled: true,
+        };
+        let capabilities = SlackChannel::new(config).capabilities();
+        assert!(capabilities.threads && capabilities.edit && capabilities.reactions);
+        assert!(!capabilities.typing);
     }
 }
@@ -262,6 +262,8 @@ impl Channel for SmsClient {
             content: message.content,
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
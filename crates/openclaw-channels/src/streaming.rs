@@ -0,0 +1,550 @@
+//! 流式回复
+//!
+//! 模型边生成边更新聊天消息：先发占位消息，按平台的编辑频率限制节流更新，
+//! 超过单条消息长度时另起一条，结束时按 `ParseMode` 输出最终格式。
+//! 不能编辑消息的通道退化为按段落分块发送。
+
+use futures::{Stream, StreamExt};
+use regex::Regex;
+use std::pin::Pin;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use crate::reply::ReplySession;
+use crate::types::{ChannelMessage, ChannelType, ParseMode, SendMessage};
+use openclaw_core::Result;
+
+/// 回复文本的增量流
+pub type TextStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// 流式回复参数
+#[derive(Debug, Clone)]
+pub struct StreamingOptions {
+    /// 两次更新之间的最小间隔
+    pub update_interval: Duration,
+    /// 单条消息的最大字符数
+    pub max_message_len: usize,
+    /// 单条消息的最多编辑次数（不含最终格式化），None 表示不限
+    pub max_edits: Option<usize>,
+    /// 占位消息内容
+    pub placeholder: String,
+    /// 最终格式
+    pub parse_mode: ParseMode,
+}
+
+impl Default for StreamingOptions {
+    fn default() -> Self {
+        Self {
+            update_interval: Duration::from_secs(1),
+            max_message_len: 4000,
+            max_edits: None,
+            placeholder: "…".to_string(),
+            parse_mode: ParseMode::Plain,
+        }
+    }
+}
+
+impl StreamingOptions {
+    /// 按平台限制取默认参数
+    pub fn for_channel(channel_type: &ChannelType) -> Self {
+        let default = Self::default();
+        match channel_type {
+            // 同一会话约每秒 1 条；上限 4096 字符，给 HTML 标签留出余量
+            ChannelType::Telegram => Self {
+                update_interval: Duration::from_millis(1500),
+                max_message_len: 3500,
+                parse_mode: ParseMode::Html,
+                ..default
+            },
+            // 每个频道 5 次/5 秒；上限 2000 字符
+            ChannelType::Discord => Self {
+                update_interval: Duration::from_millis(1200),
+                max_message_len: 1900,
+                parse_mode: ParseMode::Markdown,
+                ..default
+            },
+            // chat.update 属于 Tier 3（约 50 次/分钟）；超过 4000 字符的文本会被截断
+            ChannelType::Slack => Self {
+                update_interval: Duration::from_millis(1500),
+                max_message_len: 3900,
+                parse_mode: ParseMode::Markdown,
+                ..default
+            },
+            // Synapse 默认每个用户 0.2 条/秒
+            ChannelType::Matrix => Self {
+                update_interval: Duration::from_secs(5),
+                max_message_len: 16000,
+                parse_mode: ParseMode::Markdown,
+                ..default
+            },
+            // 单条消息最多编辑 20 次
+            ChannelType::Feishu => Self {
+                update_interval: Duration::from_secs(2),
+                max_message_len: 10000,
+                max_edits: Some(18),
+                ..default
+            },
+            _ => default,
+        }
+    }
+}
+
+/// 把增量文本逐步写入回复会话
+pub struct StreamingReply<'a> {
+    session: &'a mut ReplySession,
+    options: StreamingOptions,
+    /// 当前消息的完整内容
+    buffer: String,
+    /// 当前消息已显示的内容
+    shown: String,
+    /// 当前消息已编辑次数
+    edits: usize,
+    last_update: Option<Instant>,
+    /// 已定稿的消息
+    messages: Vec<ChannelMessage>,
+}
+
+impl<'a> StreamingReply<'a> {
+    pub fn new(session: &'a mut ReplySession, options: StreamingOptions) -> Self {
+        Self {
+            session,
+            options,
+            buffer: String::new(),
+            shown: String::new(),
+            edits: 0,
+            last_update: None,
+            messages: Vec::new(),
+        }
+    }
+
+    /// 消费整个增量流，返回最终发出的消息
+    ///
+    /// 流中途出错时保留已输出的内容并返回错误。
+    pub async fn run(mut self, mut stream: TextStream) -> Result<Vec<ChannelMessage>> {
+        self.start().await?;
+
+        loop {
+            let next = if self.pending() {
+                match tokio::time::timeout(self.until_due(), stream.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        self.update().await;
+                        continue;
+                    }
+                }
+            } else {
+                stream.next().await
+            };
+
+            match next {
+                Some(Ok(delta)) => self.push(&delta).await?,
+                Some(Err(e)) => {
+                    if let Err(finish_err) = self.finish().await {
+                        tracing::warn!("Failed to finalize partial reply: {}", finish_err);
+                    }
+                    return Err(e);
+                }
+                None => break,
+            }
+        }
+
+        self.finish().await
+    }
+
+    /// 发送占位消息；不能编辑的通道只提示“正在输入”
+    pub async fn start(&mut self) -> Result<()> {
+        // 不覆盖同一会话中之前的回复
+        self.session.next_message();
+        self.session.typing().await;
+        if self.can_edit() {
+            self.session.replace(&self.options.placeholder).await?;
+            self.last_update = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// 追加一段增量，到达节流间隔时更新消息
+    pub async fn push(&mut self, delta: &str) -> Result<()> {
+        self.buffer.push_str(delta);
+        self.split_overflow().await?;
+        if self.until_due().is_zero() {
+            self.update().await;
+        }
+        Ok(())
+    }
+
+    /// 输出最终格式，返回所有发出的消息
+    pub async fn finish(mut self) -> Result<Vec<ChannelMessage>> {
+        let content = std::mem::take(&mut self.buffer);
+        if !content.trim().is_empty() {
+            self.finalize(&content).await?;
+        } else if self.shown.is_empty()
+            && self.session.message().is_some()
+            && self.session.capabilities().delete
+        {
+            // 没有任何输出，撤掉占位消息
+            if let Err(e) = self.session.delete().await {
+                tracing::warn!("Failed to delete placeholder message: {}", e);
+            }
+        }
+        Ok(self.messages)
+    }
+
+    fn can_edit(&self) -> bool {
+        self.session.capabilities().edit
+    }
+
+    /// 是否有等待节流后再显示的内容
+    fn pending(&self) -> bool {
+        if self.can_edit() {
+            self.buffer != self.shown && self.options.max_edits.is_none_or(|max| self.edits < max)
+        } else {
+            self.buffer.contains("\n\n")
+        }
+    }
+
+    fn until_due(&self) -> Duration {
+        self.last_update.map_or(Duration::ZERO, |at| {
+            self.options.update_interval.saturating_sub(at.elapsed())
+        })
+    }
+
+    /// 中间更新：可编辑的通道以纯文本刷新当前消息，否则发出已完成的段落
+    ///
+    /// 中间更新失败（如触发限流）只记录日志，最终内容由 `finish` 保证。
+    async fn update(&mut self) {
+        self.last_update = Some(Instant::now());
+
+        if !self.can_edit() {
+            if let Some(at) = self.buffer.rfind("\n\n") {
+                let rest = self.buffer.split_off(at).trim_start().to_string();
+                let head = std::mem::replace(&mut self.buffer, rest);
+                if let Err(e) = self.finalize(&head).await {
+                    tracing::warn!("Failed to send reply chunk: {}", e);
+                }
+            }
+            return;
+        }
+
+        if !self.pending() || self.buffer.trim().is_empty() {
+            return;
+        }
+        match self.session.replace(&self.buffer).await {
+            Ok(_) => {
+                self.shown = self.buffer.clone();
+                self.edits += 1;
+            }
+            Err(e) => tracing::warn!("Failed to update streaming reply: {}", e),
+        }
+    }
+
+    /// 当前消息超出长度上限时，把前半部分定稿，剩余内容写入新消息
+    async fn split_overflow(&mut self) -> Result<()> {
+        while self.buffer.chars().count() > self.options.max_message_len {
+            let at = split_point(&self.buffer, self.options.max_message_len);
+            let mut rest = self.buffer.split_off(at).trim_start().to_string();
+            let mut head = std::mem::take(&mut self.buffer);
+
+            // 代码块被拆开时两边各自补全围栏
+            if head.matches("```").count() % 2 == 1 {
+                head.push_str("\n```");
+                rest.insert_str(0, "```\n");
+            }
+
+            self.finalize(&head).await?;
+            self.session.next_message();
+            self.buffer = rest;
+            self.shown.clear();
+            self.edits = 0;
+        }
+        Ok(())
+    }
+
+    /// 以最终格式写出一条消息，格式化失败时退回纯文本
+    async fn finalize(&mut self, content: &str) -> Result<()> {
+        let parse_mode = self.options.parse_mode.clone();
+
+        // 纯文本且内容未变时无需再编辑（部分平台会报“消息未修改”）
+        if parse_mode == ParseMode::Plain
+            && self.can_edit()
+            && self.shown == content
+            && let Some(sent) = self.session.message()
+        {
+            self.messages.push(sent.clone());
+            return Ok(());
+        }
+
+        let mut reply = SendMessage::text(
+            self.session.source().chat_id.clone(),
+            render(content, &parse_mode),
+        );
+        reply.message_type = parse_mode.message_type().to_string();
+
+        let sent = match self.session.replace_with(reply).await {
+            Ok(sent) => sent,
+            Err(e) if parse_mode != ParseMode::Plain => {
+                tracing::warn!(
+                    "Formatted reply rejected, falling back to plain text: {}",
+                    e
+                );
+                self.session.replace(content).await?
+            }
+            Err(e) => return Err(e),
+        };
+        self.shown = content.to_string();
+        self.messages.push(sent);
+        Ok(())
+    }
+}
+
+/// 在不超过 `max_chars` 个字符的前提下寻找拆分位置（字节下标）
+///
+/// 依次尝试段落、换行、空白处拆分，避免拆出过短的片段。
+fn split_point(text: &str, max_chars: usize) -> usize {
+    let limit = text
+        .char_indices()
+        .nth(max_chars)
+        .map_or(text.len(), |(i, _)| i);
+    let window = &text[..limit];
+
+    for separator in ["\n\n", "\n", " "] {
+        if let Some(at) = window.rfind(separator)
+            && at > limit / 2
+        {
+            return at;
+        }
+    }
+    limit
+}
+
+/// 按格式渲染最终内容
+fn render(content: &str, parse_mode: &ParseMode) -> String {
+    match parse_mode {
+        ParseMode::Html => markdown_to_html(content),
+        _ => content.to_string(),
+    }
+}
+
+static BOLD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\*\*(.+?)\*\*|__(.+?)__").expect("Invalid regex: bold"));
+static ITALIC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\*([^*\n]+)\*").expect("Invalid regex: italic"));
+static STRIKE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"~~(.+?)~~").expect("Invalid regex: strike"));
+static LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]\n]+)\]\(([^)\s]+)\)").expect("Invalid regex: link"));
+static HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^#{1,6}\s+(.+)$").expect("Invalid regex: heading"));
+
+/// 把模型输出的 Markdown 转成 Telegram 支持的 HTML 子集
+fn markdown_to_html(text: &str) -> String {
+    let mut out = String::new();
+
+    // 按 ``` 切分，奇数段是代码块（未闭合的代码块延续到结尾）
+    for (i, segment) in text.split("```").enumerate() {
+        if i % 2 == 1 {
+            let (language, code) = match segment.split_once('\n') {
+                Some((first, rest)) if !first.trim().contains(' ') => (first.trim(), rest),
+                _ => ("", segment),
+            };
+            let code = escape_html(code.trim_end_matches('\n'));
+            if language.is_empty() {
+                out.push_str(&format!("<pre>{}</pre>", code));
+            } else {
+                out.push_str(&format!(
+                    "<pre><code class=\"language-{}\">{}</code></pre>",
+                    escape_html(language),
+                    code
+                ));
+            }
+            continue;
+        }
+
+        // 行内代码同理按反引号切分
+        for (j, part) in segment.split('`').enumerate() {
+            if j % 2 == 1 {
+                out.push_str(&format!("<code>{}</code>", escape_html(part)));
+                continue;
+            }
+            let html = escape_html(part);
+            let html = HEADING.replace_all(&html, "<b>$1</b>");
+            let html = LINK.replace_all(&html, r#"<a href="$2">$1</a>"#);
+            let html = BOLD.replace_all(&html, "<b>$1$2</b>");
+            let html = ITALIC.replace_all(&html, "<i>$1</i>");
+            let html = STRIKE.replace_all(&html, "<s>$1</s>");
+            out.push_str(&html);
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::Channel;
+    use crate::types::ChannelCapabilities;
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::RwLock;
+
+    /// 记录发送与编辑的通道
+    struct RecordingChannel {
+        capabilities: ChannelCapabilities,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    fn message(id: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: id.to_string(),
+            channel_type: ChannelType::Telegram,
+            chat_id: "42".to_string(),
+            user_id: "u".to_string(),
+            content: content.to_string(),
+            timestamp: chrono::Utc::now(),
+            metadata: None,
+            thread_id: None,
+            reply_to: None,
+        }
+    }
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn channel_type(&self) -> ChannelType {
+            ChannelType::Telegram
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn start(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, reply: SendMessage) -> Result<ChannelMessage> {
+            let mut log = self.log.lock().unwrap();
+            log.push(format!("send:{}", reply.content));
+            Ok(message(&format!("m{}", log.len()), &reply.content))
+        }
+
+        fn capabilities(&self) -> ChannelCapabilities {
+            self.capabilities
+        }
+
+        async fn edit_message(
+            &self,
+            message_id: &str,
+            reply: SendMessage,
+        ) -> Result<ChannelMessage> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("edit:{}:{}", message_id, reply.content));
+            Ok(message(message_id, &reply.content))
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    async fn session(edit: bool) -> (ReplySession, Arc<Mutex<Vec<String>>>) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let channel: Arc<RwLock<dyn Channel>> = Arc::new(RwLock::new(RecordingChannel {
+            capabilities: ChannelCapabilities {
+                edit,
+                ..Default::default()
+            },
+            log: log.clone(),
+        }));
+        (ReplySession::new(channel, message("in", "hi")).await, log)
+    }
+
+    fn options(max_message_len: usize) -> StreamingOptions {
+        StreamingOptions {
+            update_interval: Duration::ZERO,
+            max_message_len,
+            ..Default::default()
+        }
+    }
+
+    fn deltas(parts: &[&str]) -> TextStream {
+        let items: Vec<Result<String>> = parts.iter().map(|p| Ok(p.to_string())).collect();
+        Box::pin(futures::stream::iter(items))
+    }
+
+    #[tokio::test]
+    async fn test_streaming_edits_placeholder_in_place() {
+        let (mut session, log) = session(true).await;
+        let messages = StreamingReply::new(&mut session, options(100))
+            .run(deltas(&["Hello", ", world"]))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["send:…", "edit:m1:Hello", "edit:m1:Hello, world"]
+        );
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, "Hello, world");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_splits_long_replies() {
+        let (mut session, log) = session(true).await;
+        let messages = StreamingReply::new(&mut session, options(12))
+            .run(deltas(&["first line\n", "second line"]))
+            .await
+            .unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content, "first line");
+        assert_eq!(messages[1].content, "second line");
+        assert!(
+            log.lock()
+                .unwrap()
+                .contains(&"send:second line".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_without_edit_sends_paragraphs() {
+        let (mut session, log) = session(false).await;
+        StreamingReply::new(&mut session, options(100))
+            .run(deltas(&["one\n\n", "two"]))
+            .await
+            .unwrap();
+
+        assert_eq!(*log.lock().unwrap(), vec!["send:one", "send:two"]);
+    }
+
+    #[test]
+    fn test_split_point_prefers_line_breaks() {
+        assert_eq!(split_point("aaaa bbbb\ncccc", 12), 9);
+        assert_eq!(split_point("abcdefgh", 4), 4);
+        assert_eq!(split_point("你好世界", 2), "你好".len());
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        assert_eq!(
+            markdown_to_html("**bold** and *it* <x> [a](https://e.com?a=1&b=2)"),
+            "<b>bold</b> and <i>it</i> &lt;x&gt; <a href=\"https://e.com?a=1&amp;b=2\">a</a>"
+        );
+        assert_eq!(
+            markdown_to_html("run `a<b`\n```rust\nfn main() {}\n```"),
+            "run <code>a&lt;b</code>\n<pre><code class=\"language-rust\">fn main() {}</code></pre>"
+        );
+    }
+}
@@ -232,6 +232,8 @@ impl Channel for TeamsChannel {
             content: message.content.clone(),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use openclaw_core::{OpenClawError, Result};
 
 use crate::base::Channel;
-use crate::types::{ChannelMessage, ChannelType, SendMessage};
+use crate::types::{ChannelCapabilities, ChannelMessage, ChannelType, SendMessage};
 
 /// Telegram 配置
 #[derive(Debug, Clone)]
@@ -69,12 +69,56 @@ impl TelegramBot {
         Ok(result.result.unwrap_or_default())
     }
 
+    /// 调用 Bot API 方法
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        body: &serde_json::Value,
+    ) -> Result<T> {
+        let response = self
+            .client
+            .post(self.get_api_url(method))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| OpenClawError::Http(format!("Telegram API 错误: {}", e)))?;
+
+        let result: TelegramResponse<T> = response
+            .json()
+            .await
+            .map_err(|e| OpenClawError::Http(format!("解析响应失败: {}", e)))?;
+
+        if !result.ok {
+            return Err(OpenClawError::Channel(format!(
+                "Telegram {} 失败: {}",
+                method,
+                result.description.unwrap_or_default()
+            )));
+        }
+
+        result
+            .result
+            .ok_or_else(|| OpenClawError::Channel("无返回消息".into()))
+    }
+
     /// 发送消息
     pub async fn send_text(
         &self,
         chat_id: i64,
         text: &str,
         parse_mode: Option<&str>,
+    ) -> Result<TelegramMessage> {
+        self.send_reply(chat_id, text, parse_mode, None, None).await
+    }
+
+    /// 发送消息，可回复指定消息或发到论坛话题
+    async fn send_reply(
+        &self,
+        chat_id: i64,
+        text: &str,
+        parse_mode: Option<&str>,
+        reply_to: Option<i64>,
+        thread_id: Option<i64>,
     ) -> Result<TelegramMessage> {
         let mut body = serde_json::json!({
             "chat_id": chat_id,
@@ -84,27 +128,39 @@ impl TelegramBot {
         if let Some(mode) = parse_mode {
             body["parse_mode"] = serde_json::json!(mode);
         }
+        if let Some(id) = reply_to {
+            // 被回复的消息已删除时仍然发送
+            body["reply_parameters"] = serde_json::json!({
+                "message_id": id,
+                "allow_sending_without_reply": true,
+            });
+        }
+        if let Some(id) = thread_id {
+            body["message_thread_id"] = serde_json::json!(id);
+        }
 
-        let response = self
-            .client
-            .post(self.get_api_url("sendMessage"))
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| OpenClawError::Http(format!("Telegram API 错误: {}", e)))?;
+        self.call("sendMessage", &body).await
+    }
 
-        let result: TelegramResponse<TelegramMessage> = response
-            .json()
-            .await
-            .map_err(|e| OpenClawError::Http(format!("解析响应失败: {}", e)))?;
+    /// 编辑文本消息
+    async fn edit_text(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        text: &str,
+        parse_mode: Option<&str>,
+    ) -> Result<TelegramMessage> {
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_id": message_id,
+            "text": text,
+        });
 
-        if !result.ok {
-            return Err(OpenClawError::Channel("发送消息失败".into()));
+        if let Some(mode) = parse_mode {
+            body["parse_mode"] = serde_json::json!(mode);
         }
 
-        result
-            .result
-            .ok_or_else(|| OpenClawError::Channel("无返回消息".into()))
+        self.call("editMessageText", &body).await
     }
 
     /// 发送图片
@@ -227,6 +283,8 @@ impl TelegramBot {
             content,
             timestamp: message.date,
             metadata,
+            thread_id: message.message_thread_id.map(|id| id.to_string()),
+            reply_to: message.reply_to.as_ref().map(|m| m.message_id.to_string()),
         }))
     }
 }
@@ -298,18 +356,8 @@ impl Channel for TelegramBot {
             return Err(OpenClawError::Config("Telegram 通道未启用".to_string()));
         }
 
-        let chat_id: i64 = message
-            .chat_id
-            .parse()
-            .map_err(|_| OpenClawError::Config("无效的 chat_id，需要数字格式".into()))?;
-
-        let parse_mode = message.message_type.as_str();
-        let mode = match parse_mode {
-            "markdown" => Some("Markdown"),
-            "markdownv2" | "markdown_v2" => Some("MarkdownV2"),
-            "html" => Some("HTML"),
-            _ => None,
-        };
+        let chat_id = parse_chat_id(&message.chat_id)?;
+        let mode = parse_mode(&message.message_type);
 
         // 根据消息类型发送
         match message.message_type.as_str() {
@@ -326,12 +374,67 @@ impl Channel for TelegramBot {
                 self.create_channel_message(sent, &message.content)
             }
             _ => {
-                let sent = self.send_text(chat_id, &message.content, mode).await?;
+                let reply_to = message.reply_to.as_deref().and_then(|id| id.parse().ok());
+                let thread_id = message.thread_id.as_deref().and_then(|id| id.parse().ok());
+                let sent = self
+                    .send_reply(chat_id, &message.content, mode, reply_to, thread_id)
+                    .await?;
                 self.create_channel_message(sent, &message.content)
             }
         }
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            threads: true,
+            replies: true,
+            edit: true,
+            delete: true,
+            reactions: true,
+            typing: true,
+        }
+    }
+
+    async fn edit_message(&self, message_id: &str, message: SendMessage) -> Result<ChannelMessage> {
+        let sent = self
+            .edit_text(
+                parse_chat_id(&message.chat_id)?,
+                parse_message_id(message_id)?,
+                &message.content,
+                parse_mode(&message.message_type),
+            )
+            .await?;
+        self.create_channel_message(sent, &message.content)
+    }
+
+    async fn delete_message(&self, chat_id: &str, message_id: &str) -> Result<()> {
+        let body = serde_json::json!({
+            "chat_id": parse_chat_id(chat_id)?,
+            "message_id": parse_message_id(message_id)?,
+        });
+        self.call::<bool>("deleteMessage", &body).await?;
+        Ok(())
+    }
+
+    async fn add_reaction(&self, chat_id: &str, message_id: &str, emoji: &str) -> Result<()> {
+        let body = serde_json::json!({
+            "chat_id": parse_chat_id(chat_id)?,
+            "message_id": parse_message_id(message_id)?,
+            "reaction": [{ "type": "emoji", "emoji": emoji }],
+        });
+        self.call::<bool>("setMessageReaction", &body).await?;
+        Ok(())
+    }
+
+    async fn send_typing(&self, chat_id: &str) -> Result<()> {
+        let body = serde_json::json!({
+            "chat_id": parse_chat_id(chat_id)?,
+            "action": "typing",
+        });
+        self.call::<bool>("sendChatAction", &body).await?;
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<bool> {
         match self.get_me().await {
             Ok(_) => Ok(true),
@@ -354,16 +457,41 @@ impl TelegramBot {
             content: content.to_string(),
             timestamp: sent.date,
             metadata: None,
+            thread_id: sent.message_thread_id.map(|id| id.to_string()),
+            reply_to: sent.reply_to.as_ref().map(|m| m.message_id.to_string()),
         })
     }
 }
 
+fn parse_chat_id(chat_id: &str) -> Result<i64> {
+    chat_id
+        .parse()
+        .map_err(|_| OpenClawError::Config("无效的 chat_id，需要数字格式".into()))
+}
+
+fn parse_message_id(message_id: &str) -> Result<i64> {
+    message_id
+        .parse()
+        .map_err(|_| OpenClawError::Config(format!("无效的 Telegram 消息 ID: {}", message_id)))
+}
+
+/// 消息类型对应的 Telegram parse_mode
+fn parse_mode(message_type: &str) -> Option<&'static str> {
+    match message_type {
+        "markdown" => Some("Markdown"),
+        "markdownv2" | "markdown_v2" => Some("MarkdownV2"),
+        "html" => Some("HTML"),
+        _ => None,
+    }
+}
+
 // ============== Telegram API 类型 ==============
 
 #[derive(Debug, Deserialize)]
 struct TelegramResponse<T> {
     ok: bool,
     result: Option<T>,
+    description: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -384,6 +512,8 @@ struct TelegramMessage {
     document: Option<TelegramDocument>,
     #[serde(rename = "reply_to_message")]
     reply_to: Option<Box<TelegramMessage>>,
+    /// 论坛话题 ID
+    message_thread_id: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -29,6 +29,31 @@ pub enum ChannelType {
     SMS,
 }
 
+impl ChannelType {
+    /// 稳定的通道名，与序列化名称一致，用于持久化的键和指标标签
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChannelType::Telegram => "telegram",
+            ChannelType::Discord => "discord",
+            ChannelType::WhatsApp => "whatsapp",
+            ChannelType::Slack => "slack",
+            ChannelType::Signal => "signal",
+            ChannelType::Matrix => "matrix",
+            ChannelType::Teams => "teams",
+            ChannelType::IMessage => "imessage",
+            ChannelType::GoogleChat => "googlechat",
+            ChannelType::DingTalk => "dingtalk",
+            ChannelType::WeCom => "wecom",
+            ChannelType::Feishu => "feishu",
+            ChannelType::Zalo => "zalo",
+            ChannelType::ZaloPersonal => "zalopersonal",
+            ChannelType::WebChat => "webchat",
+            ChannelType::Email => "email",
+            ChannelType::SMS => "sms",
+        }
+    }
+}
+
 /// 通道消息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelMessage {
@@ -46,6 +71,12 @@ pub struct ChannelMessage {
     pub timestamp: DateTime<Utc>,
     /// 元数据
     pub metadata: Option<serde_json::Value>,
+    /// 所属话题/线程 ID（Slack thread_ts、Telegram 话题、Matrix 线程根事件等）
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// 所回复的消息 ID
+    #[serde(default)]
+    pub reply_to: Option<String>,
 }
 
 /// 发送者信息
@@ -135,6 +166,59 @@ pub struct SendMessage {
     pub articles: Option<Vec<crate::wecom::NewsArticle>>,
     /// 媒体文件 ID
     pub media_id: Option<String>,
+    /// 发送到的话题/线程 ID
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// 回复的消息 ID
+    #[serde(default)]
+    pub reply_to: Option<String>,
+}
+
+impl SendMessage {
+    /// 纯文本消息
+    pub fn text(chat_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            chat_id: chat_id.into(),
+            message_type: "text".to_string(),
+            content: content.into(),
+            title: None,
+            url: None,
+            at_mobiles: None,
+            mentioned_list: None,
+            base64: None,
+            md5: None,
+            articles: None,
+            media_id: None,
+            thread_id: None,
+            reply_to: None,
+        }
+    }
+
+    /// 作为对某条消息的回复发送，并沿用其所在话题
+    ///
+    /// 以消息为线程根的平台（如 Slack）在没有话题时用 `reply_to` 开启新线程。
+    pub fn in_reply_to(mut self, message: &ChannelMessage) -> Self {
+        self.reply_to = Some(message.id.clone());
+        self.thread_id = message.thread_id.clone();
+        self
+    }
+}
+
+/// 通道能力，调用方据此决定回复方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelCapabilities {
+    /// 支持话题/线程
+    pub threads: bool,
+    /// 支持引用回复
+    pub replies: bool,
+    /// 支持编辑已发送的消息
+    pub edit: bool,
+    /// 支持删除已发送的消息
+    pub delete: bool,
+    /// 支持表情回应
+    pub reactions: bool,
+    /// 支持“正在输入”提示
+    pub typing: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -145,3 +229,15 @@ pub enum ParseMode {
     Html,
     Plain,
 }
+
+impl ParseMode {
+    /// 对应的 `SendMessage::message_type`
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            ParseMode::Markdown => "markdown",
+            ParseMode::MarkdownV2 => "markdownv2",
+            ParseMode::Html => "html",
+            ParseMode::Plain => "text",
+        }
+    }
+}
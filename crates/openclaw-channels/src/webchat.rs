@@ -69,6 +69,8 @@ impl WebChatClient {
             content: content.to_string(),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         };
 
         Ok(message)
@@ -87,6 +89,8 @@ impl WebChatClient {
             content: msg.content,
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
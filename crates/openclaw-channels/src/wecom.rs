@@ -208,6 +208,8 @@ impl Channel for WeComChannel {
             content: message.content.clone(),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
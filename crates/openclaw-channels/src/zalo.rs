@@ -254,6 +254,8 @@ impl Channel for ZaloClient {
             content: message.content,
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
@@ -258,6 +258,8 @@ impl ZaloPersonalClient {
             content: content.to_string(),
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
@@ -335,6 +337,8 @@ impl Channel for ZaloPersonalClient {
             content: message.content,
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         })
     }
 
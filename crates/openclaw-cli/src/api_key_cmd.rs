@@ -1,9 +1,13 @@
 //! API Key 管理 CLI 工具
 //!
-//! 提供命令行接口来管理用户的 API Key
+//! 提供命令行接口来管理用户的 API Key，以及访问本服务 REST API 的密钥
+
+use std::path::PathBuf;
 
 use clap::Subcommand;
 use openclaw_core::{OpenClawError, UserConfigManager, UserProviderConfig};
+use openclaw_sandbox::Permission;
+use openclaw_server::api_auth::{ApiAuthError, ApiKeyStore, JWT_SECRET_ENV, JwtClaims, issue_jwt};
 
 #[derive(Debug, Subcommand)]
 pub enum ApiKeyCommand {
@@ -52,11 +56,70 @@ pub enum ApiKeyCommand {
         /// API Key
         api_key: String,
     },
+
+    /// 创建访问服务 REST API 的密钥
+    Create {
+        /// 密钥名称
+        name: String,
+        /// 作用域，逗号分隔 (如 agent:chat,browser:control,device:camera)
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        scopes: Vec<String>,
+        /// 每分钟请求上限（0 表示不限）
+        #[arg(short, long)]
+        rate_limit: Option<u32>,
+        /// 有效天数
+        #[arg(long)]
+        expires_in_days: Option<i64>,
+        /// 密钥存储文件（默认 ~/.openclaw-rust/api_keys.json）
+        #[arg(long)]
+        store: Option<PathBuf>,
+    },
+
+    /// 列出服务 REST API 密钥
+    Keys {
+        /// 密钥存储文件
+        #[arg(long)]
+        store: Option<PathBuf>,
+    },
+
+    /// 吊销服务 REST API 密钥
+    Revoke {
+        /// 密钥 ID
+        id: String,
+        /// 密钥存储文件
+        #[arg(long)]
+        store: Option<PathBuf>,
+    },
+
+    /// 签发 JWT 访问令牌
+    Token {
+        /// 令牌主体（用户或服务名）
+        subject: String,
+        /// 作用域，逗号分隔
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        scopes: Vec<String>,
+        /// 有效小时数
+        #[arg(long, default_value = "24")]
+        ttl_hours: i64,
+        /// 每分钟请求上限
+        #[arg(short, long)]
+        rate_limit: Option<u32>,
+        /// 签名密钥，默认读取 OPENCLAW_JWT_SECRET
+        #[arg(long)]
+        secret: Option<String>,
+    },
+
+    /// 列出可用作用域
+    Scopes,
 }
 
 impl ApiKeyCommand {
     /// 执行命令
     pub async fn execute(&self) -> Result<(), OpenClawError> {
+        if self.execute_server_key()? {
+            return Ok(());
+        }
+
         let mut manager = UserConfigManager::new(None)?;
 
         match self {
@@ -176,10 +239,120 @@ impl ApiKeyCommand {
                     Err(e) => println!("❌ 验证失败: {}", e),
                 }
             }
+
+            _ => unreachable!("服务密钥命令已在 execute_server_key 中处理"),
         }
 
         Ok(())
     }
+
+    /// 处理服务 REST API 密钥相关命令，非此类命令返回 false
+    fn execute_server_key(&self) -> Result<bool, OpenClawError> {
+        match self {
+            ApiKeyCommand::Create {
+                name,
+                scopes,
+                rate_limit,
+                expires_in_days,
+                store,
+            } => {
+                let mut store = load_store(store)?;
+                let expires_at =
+                    expires_in_days.map(|d| chrono::Utc::now() + chrono::Duration::days(d));
+                let (record, secret) = store
+                    .create(name.clone(), scopes.clone(), *rate_limit, expires_at)
+                    .map_err(auth_error)?;
+                store.save().map_err(auth_error)?;
+
+                println!("✅ 已创建密钥 {} ({})", record.id, record.name);
+                println!("   作用域: {}", record.scopes.join(", "));
+                if let Some(expires_at) = record.expires_at {
+                    println!("   过期时间: {}", expires_at.format("%Y-%m-%d %H:%M UTC"));
+                }
+                println!();
+                println!("   {}", secret);
+                println!();
+                println!("⚠️  密钥只显示这一次，请妥善保存");
+            }
+
+            ApiKeyCommand::Keys { store } => {
+                let store = load_store(store)?;
+                if store.list().is_empty() {
+                    println!("暂无服务密钥");
+                    println!("\n使用方法:");
+                    println!("  openclaw-rust api-key create my-app --scopes agent:chat");
+                }
+                for record in store.list() {
+                    let status = if record.revoked {
+                        "已吊销"
+                    } else if record.is_expired() {
+                        "已过期"
+                    } else {
+                        "有效"
+                    };
+                    println!(
+                        "  {} {} {}… [{}] {}",
+                        record.id,
+                        record.name,
+                        record.prefix,
+                        record.scopes.join(","),
+                        status
+                    );
+                }
+            }
+
+            ApiKeyCommand::Revoke { id, store } => {
+                let mut store = load_store(store)?;
+                store.revoke(id).map_err(auth_error)?;
+                store.save().map_err(auth_error)?;
+                println!("✅ 已吊销密钥 {}", id);
+            }
+
+            ApiKeyCommand::Token {
+                subject,
+                scopes,
+                ttl_hours,
+                rate_limit,
+                secret,
+            } => {
+                let secret = secret
+                    .clone()
+                    .or_else(|| std::env::var(JWT_SECRET_ENV).ok())
+                    .ok_or_else(|| {
+                        OpenClawError::Config(format!(
+                            "请通过 --secret 或 {} 提供签名密钥",
+                            JWT_SECRET_ENV
+                        ))
+                    })?;
+                openclaw_server::api_auth::parse_scopes(scopes).map_err(auth_error)?;
+
+                let mut claims =
+                    JwtClaims::new(subject.clone(), scopes, chrono::Duration::hours(*ttl_hours));
+                claims.rate_limit = *rate_limit;
+                println!("{}", issue_jwt(&secret, &claims).map_err(auth_error)?);
+            }
+
+            ApiKeyCommand::Scopes => {
+                for scope in Permission::known_scopes() {
+                    println!("  {}", scope);
+                }
+                println!("  custom:<name>");
+                println!("  * (全部权限)");
+            }
+
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+fn load_store(path: &Option<PathBuf>) -> Result<ApiKeyStore, OpenClawError> {
+    ApiKeyStore::load(path.clone().unwrap_or_else(ApiKeyStore::default_path)).map_err(auth_error)
+}
+
+fn auth_error(e: ApiAuthError) -> OpenClawError {
+    OpenClawError::Config(e.to_string())
 }
 
 /// 获取默认模型
@@ -65,7 +65,10 @@ pub async fn run(
         tracing::info!("Loaded {} workspaces from workspaces.yaml", config.workspaces.workspaces.len());
     }
 
-    let gateway: openclaw_server::gateway_service::Gateway = Gateway::new(config).await?;
+    let mut gateway: openclaw_server::gateway_service::Gateway = Gateway::new(config).await?;
+    if let Some(config_dir) = get_config_dir() {
+        gateway = gateway.with_config_dir(config_dir);
+    }
     gateway.start().await?;
 
     Ok(())
@@ -141,32 +141,74 @@ fn check_cargo() -> CheckResult {
     }
 }
 
-/// 检查配置文件
+/// 检查配置文件：展开占位符并按 Schema 校验网关实际加载的文件
 fn check_config_file() -> CheckResult {
-    let config_path = dirs::home_dir().map(|h| h.join(".openclaw-rust").join("openclaw.json"));
+    use openclaw_core::config_interpolation::read_config_json;
+    use openclaw_core::config_schema::validate_config;
 
-    match config_path {
-        Some(path) if path.exists() => CheckResult {
-            name: "配置文件".to_string(),
-            status: CheckStatus::Ok,
-            message: format!("存在于 {}", path.display()),
+    let name = "配置文件".to_string();
+    let Some(config_dir) = dirs::home_dir().map(|h| h.join(".openclaw-rust")) else {
+        return CheckResult {
+            name,
+            status: CheckStatus::Error,
+            message: "无法确定配置路径".to_string(),
             fix_hint: None,
-        },
-        Some(path) => CheckResult {
-            name: "配置文件".to_string(),
+        };
+    };
+
+    let Some(path) = openclaw_core::Config::find_in_dir(&config_dir) else {
+        return CheckResult {
+            name,
             status: CheckStatus::Warning,
             message: "配置文件不存在".to_string(),
             fix_hint: Some(format!(
                 "运行 `openclaw-rust wizard` 创建配置，或创建 {}",
-                path.display()
+                config_dir.join("config.json").display()
             )),
-        },
-        None => CheckResult {
-            name: "配置文件".to_string(),
-            status: CheckStatus::Error,
-            message: "无法确定配置路径".to_string(),
+        };
+    };
+
+    let value = match read_config_json(&path) {
+        Ok(value) => value,
+        Err(e) => {
+            return CheckResult {
+                name,
+                status: CheckStatus::Error,
+                message: format!("{}: {}", path.display(), e),
+                fix_hint: Some("检查 JSON 语法以及 ${ENV} / ${file:...} 引用的变量和文件".to_string()),
+            };
+        }
+    };
+
+    let issues = validate_config(&value);
+    if issues.is_empty() {
+        return CheckResult {
+            name,
+            status: CheckStatus::Ok,
+            message: format!("{} 校验通过", path.display()),
             fix_hint: None,
-        },
+        };
+    }
+
+    let status = if issues.iter().any(|i| i.is_error()) {
+        CheckStatus::Error
+    } else {
+        CheckStatus::Warning
+    };
+    let details: Vec<String> = issues.iter().take(5).map(ToString::to_string).collect();
+    let more = if issues.len() > details.len() {
+        format!(" 等 {} 项", issues.len())
+    } else {
+        String::new()
+    };
+    CheckResult {
+        name,
+        status,
+        message: format!("{}: {}{}", path.display(), details.join("; "), more),
+        fix_hint: Some(format!(
+            "参照 {} 修改配置，编辑器可通过 $schema 字段获得补全",
+            config_dir.join(openclaw_core::config_schema::SCHEMA_FILE_NAME).display()
+        )),
     }
 }
 
@@ -481,23 +523,25 @@ fn run_fixes(results: &[CheckResult]) -> Result<()> {
             // 自动修复逻辑
             match result.name.as_str() {
                 "配置文件" => {
-                    // 创建默认配置
                     if let Some(home) = dirs::home_dir() {
                         let config_dir = home.join(".openclaw-rust");
                         std::fs::create_dir_all(&config_dir)?;
-                        let config_path = config_dir.join("openclaw.json");
-
-                        let default_config = serde_json::json!({
-                            "user_name": "User",
-                            "default_provider": "openai",
-                            "default_model": "gpt-4o",
-                        });
-
-                        std::fs::write(
-                            &config_path,
-                            serde_json::to_string_pretty(&default_config)?,
-                        )?;
-                        println!("✅ 已创建默认配置文件: {}", config_path.display());
+                        let schema_path =
+                            openclaw_core::config_schema::write_schema_file(&config_dir)?;
+                        println!("✅ 已写入配置 Schema: {}", schema_path.display());
+
+                        // 已有配置文件时不覆盖，只提供 Schema 辅助修改
+                        if openclaw_core::Config::find_in_dir(&config_dir).is_none() {
+                            let config_path = config_dir.join("config.json");
+                            let default_config = openclaw_core::config_schema::to_json_with_schema(
+                                &openclaw_core::Config::default(),
+                            )?;
+                            std::fs::write(
+                                &config_path,
+                                serde_json::to_string_pretty(&default_config)?,
+                            )?;
+                            println!("✅ 已创建默认配置文件: {}", config_path.display());
+                        }
                     }
                 }
                 "项目依赖" => {
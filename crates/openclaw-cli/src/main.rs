@@ -15,8 +15,10 @@ mod daemon_cmd;
 mod doctor_cmd;
 mod evo_cmd;
 mod evo_runner;
+mod memory_cmd;
 mod message_cmd;
 mod onboard;
+mod session_cmd;
 mod skill_cmd;
 mod voice_cmd;
 mod wizard_cmd;
@@ -115,6 +117,16 @@ enum Commands {
         #[command(subcommand)]
         command: daemon_cmd::DaemonCommand,
     },
+    /// Memory and knowledge base commands
+    Memory {
+        #[command(subcommand)]
+        command: memory_cmd::MemoryCommand,
+    },
+    /// Fork, compare and merge conversation branches
+    Session {
+        #[command(subcommand)]
+        command: session_cmd::SessionCommand,
+    },
     /// Send a message to a channel
     Message {
         #[command(subcommand)]
@@ -167,13 +179,22 @@ enum AgentCommands {
 #[tokio::main]
 async fn main() -> Result<()> {
     // 初始化日志
-    tracing_subscriber::registry()
+    let subscriber = tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "openclaw=debug,info".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+        .with(tracing_subscriber::fmt::layer());
+
+    #[cfg(feature = "otel")]
+    let subscriber = subscriber.with(openclaw_server::telemetry::otlp_layer().unwrap_or_else(
+        |e| {
+            eprintln!("OTLP 追踪初始化失败: {}", e);
+            None
+        },
+    ));
+
+    subscriber.init();
 
     let cli = Cli::parse();
 
@@ -220,6 +241,12 @@ async fn main() -> Result<()> {
         Commands::Daemon { command } => {
             daemon_cmd::execute(command).await?;
         }
+        Commands::Memory { command } => {
+            command.execute().await?;
+        }
+        Commands::Session { command } => {
+            command.execute().await?;
+        }
         Commands::Message { command } => {
             command.execute().await?;
         }
@@ -248,5 +275,8 @@ async fn main() -> Result<()> {
         }
     }
 
+    #[cfg(feature = "otel")]
+    openclaw_server::telemetry::shutdown_tracing();
+
     Ok(())
 }
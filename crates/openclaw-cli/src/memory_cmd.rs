@@ -0,0 +1,342 @@
+//! 记忆与知识库 CLI 工具
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::Subcommand;
+use openclaw_memory::embedding::EmbeddingProvider;
+use openclaw_memory::ingest::IngestedDocument;
+use openclaw_memory::types::LocalEmbeddingSettings;
+use openclaw_memory::{
+    ExtractorRegistry, MemoryStoreBackend, ReindexJob, create_embedding_provider_from_config,
+    create_local_embedding_provider, create_memory_store,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Subcommand)]
+pub enum MemoryCommand {
+    /// Ingest documents (PDF, DOCX, PPTX, XLSX, HTML, EPUB, Markdown, text) into the knowledge base
+    Ingest {
+        /// File or directory to ingest
+        path: PathBuf,
+        /// Gateway URL
+        #[arg(long, default_value = "http://localhost:18789")]
+        gateway_url: String,
+        /// API key (defaults to OPENCLAW_API_KEY)
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Extract locally and print the document structure without uploading
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List supported document formats
+    Formats,
+    /// Re-embed stored memories after changing the embedding model, or copy them to another backend
+    Reindex {
+        /// Source vector store backend (only sqlite supports reading every stored item)
+        #[arg(long, default_value = "sqlite")]
+        backend: String,
+        /// Source collection
+        #[arg(long, default_value = "memories")]
+        collection: String,
+        /// Target backend (defaults to the source backend)
+        #[arg(long)]
+        target_backend: Option<String>,
+        /// Target collection (defaults to "<collection>_reindexed")
+        #[arg(long)]
+        target_collection: Option<String>,
+        /// Embedding provider for the new vectors (openai, ollama, local, custom, ...)
+        #[arg(long, default_value = "openai")]
+        provider: String,
+        /// Embedding model for the new vectors
+        #[arg(long, default_value = "text-embedding-3-small")]
+        model: String,
+        /// Embedding API key
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Embedding API base URL
+        #[arg(long)]
+        base_url: Option<String>,
+        /// Model cache directory for the local provider
+        #[arg(long)]
+        cache_dir: Option<String>,
+        /// Only use locally cached model files (local provider)
+        #[arg(long)]
+        offline: bool,
+        /// Copy existing vectors without re-embedding (backend migration)
+        #[arg(long)]
+        copy_vectors: bool,
+        /// Items per batch
+        #[arg(long, default_value_t = 64)]
+        batch_size: usize,
+        /// Checkpoint file used to resume an interrupted run
+        #[arg(long, default_value = openclaw_memory::reindex::DEFAULT_CHECKPOINT_PATH)]
+        checkpoint: PathBuf,
+    },
+}
+
+/// 支持按 id 遍历（`VectorStore::scan`）的持久化后端，可作为重建索引的源集合
+const REINDEX_SOURCE_BACKENDS: &[&str] = &["sqlite"];
+
+#[derive(Debug, Deserialize)]
+struct IngestResponse {
+    success: bool,
+    data: Option<IngestedDocument>,
+    error: Option<String>,
+}
+
+impl MemoryCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match self {
+            MemoryCommand::Ingest {
+                path,
+                gateway_url,
+                api_key,
+                dry_run,
+            } => {
+                let registry = ExtractorRegistry::with_defaults();
+                let files = collect_files(path, &registry)?;
+                if files.is_empty() {
+                    anyhow::bail!("No supported documents found under {}", path.display());
+                }
+
+                if *dry_run {
+                    for file in &files {
+                        preview(&registry, file);
+                    }
+                    return Ok(());
+                }
+
+                let api_key = api_key
+                    .clone()
+                    .or_else(|| std::env::var("OPENCLAW_API_KEY").ok());
+                upload(&files, gateway_url, api_key.as_deref()).await
+            }
+            MemoryCommand::Formats => {
+                let registry = ExtractorRegistry::with_defaults();
+                println!("Supported formats: {}", registry.extensions().join(", "));
+                Ok(())
+            }
+            MemoryCommand::Reindex {
+                backend,
+                collection,
+                target_backend,
+                target_collection,
+                provider,
+                model,
+                api_key,
+                base_url,
+                cache_dir,
+                offline,
+                copy_vectors,
+                batch_size,
+                checkpoint,
+            } => {
+                let target_backend = target_backend.as_deref().unwrap_or(backend);
+                let target_collection = target_collection
+                    .clone()
+                    .unwrap_or_else(|| format!("{}_reindexed", collection));
+                if target_backend == backend && &target_collection == collection {
+                    anyhow::bail!("Source and target collections must differ");
+                }
+                if !REINDEX_SOURCE_BACKENDS.contains(&backend.as_str()) {
+                    anyhow::bail!(
+                        "Backend '{}' cannot be used as a reindex source: it does not support \
+                         listing stored items. Supported source backends: {}",
+                        backend,
+                        REINDEX_SOURCE_BACKENDS.join(", ")
+                    );
+                }
+
+                let source =
+                    create_memory_store(MemoryStoreBackend::from(backend.as_str()), collection)
+                        .await?;
+                let target = create_memory_store(
+                    MemoryStoreBackend::from(target_backend),
+                    &target_collection,
+                )
+                .await?;
+
+                let mut job = ReindexJob::new(source, target)
+                    .with_batch_size(*batch_size)
+                    .with_checkpoint(checkpoint);
+                if !copy_vectors {
+                    let api_key = api_key
+                        .clone()
+                        .or_else(|| std::env::var("OPENAI_API_KEY").ok());
+                    let embedder = if provider == "local" {
+                        let settings = LocalEmbeddingSettings {
+                            cache_dir: cache_dir.clone(),
+                            offline: *offline,
+                            ..Default::default()
+                        };
+                        create_local_embedding_provider(model, &settings).await?
+                    } else {
+                        create_embedding_provider_from_config(
+                            provider,
+                            model,
+                            api_key,
+                            base_url.clone(),
+                        )
+                        .await?
+                    };
+                    println!(
+                        "Re-embedding {}/{} with {} ({} dimensions)",
+                        backend,
+                        collection,
+                        embedder.model(),
+                        embedder.dimensions()
+                    );
+                    job = job.with_embedder(embedder);
+                }
+
+                let handle = job.spawn();
+                let mut progress = handle.subscribe();
+                while progress.changed().await.is_ok() {
+                    let current = progress.borrow_and_update().clone();
+                    println!(
+                        "  {:>5.1}%  {}/{} written, {} skipped",
+                        current.percent(),
+                        current.processed,
+                        current.total,
+                        current.skipped
+                    );
+                    if current.done {
+                        break;
+                    }
+                }
+
+                let result = handle.wait().await?;
+                println!(
+                    "\n✅ Reindexed {} items into {}/{}",
+                    result.processed, target_backend, target_collection
+                );
+                println!(
+                    "   Point long_term.backend/collection and the embedding settings at the new \
+                     collection to switch over."
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 收集受支持的文件，目录按路径排序递归遍历
+fn collect_files(path: &Path, registry: &ExtractorRegistry) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        if registry.for_path(path).is_none() {
+            anyhow::bail!("Unsupported document format: {}", path.display());
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(path)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            files.extend(collect_files(&entry, registry)?);
+        } else if registry.for_path(&entry).is_some() {
+            files.push(entry);
+        }
+    }
+    Ok(files)
+}
+
+fn preview(registry: &ExtractorRegistry, file: &Path) {
+    let document = match std::fs::read(file)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| registry.extract(file, &bytes).map_err(anyhow::Error::from))
+    {
+        Ok(document) => document,
+        Err(e) => {
+            println!("❌ {}: {}", file.display(), e);
+            return;
+        }
+    };
+
+    println!(
+        "📄 {} [{}] {}",
+        file.display(),
+        document.format,
+        document.title.as_deref().unwrap_or("")
+    );
+    for section in &document.sections {
+        let mut label = section.headings.join(" › ");
+        if let Some(location) = &section.location {
+            label = format!("{} {}", location, label);
+        }
+        println!(
+            "   {:?} {} ({} chars)",
+            section.kind,
+            label.trim(),
+            section.text.chars().count()
+        );
+    }
+}
+
+async fn upload(files: &[PathBuf], gateway_url: &str, api_key: Option<&str>) -> Result<()> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/memory/ingest", gateway_url.trim_end_matches('/'));
+    let mut failed = 0;
+
+    for file in files {
+        let file_name = file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let document_id = std::fs::canonicalize(file)
+            .unwrap_or_else(|_| file.clone())
+            .display()
+            .to_string();
+        let bytes = std::fs::read(file)?;
+
+        let mut request = client
+            .post(&url)
+            .query(&[("filename", &file_name), ("document_id", &document_id)])
+            .body(bytes);
+        if let Some(key) = api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let result = match request.send().await {
+            Ok(response) if response.status().is_success() => response
+                .json::<IngestResponse>()
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| match (r.success, r.data) {
+                    (true, Some(document)) => Ok(document),
+                    _ => Err(r.error.unwrap_or_else(|| "unknown error".to_string())),
+                }),
+            Ok(response) => Err(format!("gateway returned {}", response.status())),
+            Err(e) => Err(e.to_string()),
+        };
+
+        match result {
+            Ok(document) => println!(
+                "✅ {} ({} sections, {} chunks{})",
+                file.display(),
+                document.sections,
+                document.chunks,
+                if document.embedded { "" } else { ", BM25 only" }
+            ),
+            Err(e) => {
+                failed += 1;
+                println!("❌ {}: {}", file.display(), e);
+            }
+        }
+    }
+
+    println!(
+        "\nIngested {}/{} documents",
+        files.len() - failed,
+        files.len()
+    );
+    if failed > 0 {
+        anyhow::bail!("{} documents failed to ingest", failed);
+    }
+    Ok(())
+}
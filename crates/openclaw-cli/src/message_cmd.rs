@@ -77,6 +77,8 @@ impl MessageCommand {
             md5: None,
             articles: None,
             media_id: None,
+            thread_id: None,
+            reply_to: None,
         };
 
         println!(
@@ -35,12 +35,18 @@ impl StartStep {
 
         println!("\n💾 保存配置...\n");
         
-        if let Err(e) = save_config(state) {
-            println!("   ❌ 保存失败: {}", e);
-            return Ok(StepResult::failure(format!("保存配置失败: {}", e)));
-        }
+        let config_path = match save_config(state) {
+            Ok(path) => path,
+            Err(e) => {
+                println!("   ❌ 保存失败: {}", e);
+                return Ok(StepResult::failure(format!("保存配置失败: {}", e)));
+            }
+        };
         
-        println!("   ✅ 配置已保存到 ~/.openclaw-rust/openclaw.json");
+        println!("   ✅ 配置已保存到 {}", config_path.display());
+        if state.api_key.is_some() {
+            println!("   🔑 API 密钥保存在 ~/.openclaw-rust/secrets/，配置中以 ${{file:...}} 引用");
+        }
 
         println!("\n🎉 初始化完成！\n");
         println!("  下一步:\n");
@@ -52,60 +58,78 @@ impl StartStep {
     }
 }
 
-fn save_config(state: &WizardState) -> anyhow::Result<()> {
+/// 写入网关配置，API 密钥单独存放并通过 `${file:...}` 引用
+fn save_config(state: &WizardState) -> anyhow::Result<std::path::PathBuf> {
+    use openclaw_core::Config;
+    use openclaw_core::config::{ProviderConfig, ProviderType};
+    use openclaw_core::config_schema::{to_json_with_schema, validate_config, write_schema_file};
     use std::fs;
     use std::path::PathBuf;
-    
+
     let config_dir = dirs::home_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(".openclaw-rust");
-    
+
     fs::create_dir_all(&config_dir)?;
-    
-    let config_path = config_dir.join("openclaw.json");
-    
-    let provider_key = match state.provider.as_str() {
-        "OpenAI" => "openai",
-        "Anthropic (Claude)" => "anthropic",
-        "DeepSeek" => "deepseek",
-        "通义千问 (Qwen)" => "qwen",
-        "智谱 GLM" => "zhipu",
-        "Moonshot (Kimi)" => "moonshot",
-        "MiniMax" => "minimax",
-        "豆包 (Doubao)" => "doubao",
-        "Google (Gemini)" => "google",
-        _ => "custom",
+
+    let config_path = config_dir.join("config.json");
+
+    let (provider_key, provider_type) = match state.provider.as_str() {
+        "OpenAI" => ("openai", ProviderType::OpenAI),
+        "Anthropic (Claude)" => ("anthropic", ProviderType::Anthropic),
+        "DeepSeek" => ("deepseek", ProviderType::DeepSeek),
+        "通义千问 (Qwen)" => ("qwen", ProviderType::Qwen),
+        "智谱 GLM" => ("zhipu", ProviderType::Glm),
+        "Moonshot (Kimi)" => ("moonshot", ProviderType::Kimi),
+        "MiniMax" => ("minimax", ProviderType::Minimax),
+        "豆包 (Doubao)" => ("doubao", ProviderType::Doubao),
+        "Google (Gemini)" => ("google", ProviderType::Google),
+        _ => ("custom", ProviderType::Custom),
     };
-    
-    let mut provider_config = serde_json::Map::new();
-    if let Some(ref key) = state.api_key {
-        provider_config.insert("api_key".to_string(), serde_json::Value::String(key.clone()));
-    }
-    if let Some(ref base) = state.api_base {
-        provider_config.insert("api_base".to_string(), serde_json::Value::String(base.clone()));
-    }
-    
-    let mut providers = serde_json::Map::new();
-    providers.insert(provider_key.to_string(), serde_json::Value::Object(provider_config));
-    
-    let config = serde_json::json!({
-        "user_name": state.user_name,
-        "default_provider": provider_key,
-        "default_model": state.model,
-        "providers": providers,
-        "sandbox": {
-            "enabled": state.sandbox_enabled,
-            "default_type": state.sandbox_type,
-            "timeout_secs": 30,
-            "memory_limit_mb": 64
-        },
-        "security": {
-            "dm_policy": state.dm_policy
+
+    let api_key = match state.api_key {
+        Some(ref key) => {
+            let secrets_dir = config_dir.join("secrets");
+            fs::create_dir_all(&secrets_dir)?;
+            let key_file = format!("{}.key", provider_key);
+            write_secret(&secrets_dir.join(&key_file), key)?;
+            Some(format!("${{file:~/.openclaw-rust/secrets/{}}}", key_file))
         }
+        None => None,
+    };
+
+    let mut config = Config::default();
+    config.ai.default_provider = provider_key.to_string();
+    config.ai.providers.push(ProviderConfig {
+        name: provider_key.to_string(),
+        provider_type,
+        api_key,
+        base_url: state.api_base.clone(),
+        default_model: state.model.clone(),
+        models: vec![state.model.clone()],
+        auth: Default::default(),
     });
-    
-    let content = serde_json::to_string_pretty(&config)?;
-    fs::write(&config_path, content)?;
-    
+    config.sandbox.enabled = state.sandbox_enabled;
+    config.sandbox.default_type = state.sandbox_type.clone();
+    config.server.enable_voice = state.voice_enabled;
+
+    let value = to_json_with_schema(&config)?;
+    if let Some(error) = validate_config(&value).into_iter().find(|i| i.is_error()) {
+        anyhow::bail!("生成的配置未通过校验: {}", error);
+    }
+
+    write_schema_file(&config_dir)?;
+    fs::write(&config_path, serde_json::to_string_pretty(&value)?)?;
+
+    Ok(config_path)
+}
+
+fn write_secret(path: &std::path::Path, secret: &str) -> std::io::Result<()> {
+    std::fs::write(path, secret)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
     Ok(())
 }
@@ -143,7 +143,7 @@ impl ProvidersSubCmd {
         }
 
         let config_path = openclaw_core::UnifiedConfig::default_path();
-        let mut config = openclaw_core::UnifiedConfig::load(&config_path).unwrap_or_default();
+        let mut config = openclaw_core::UnifiedConfig::load_raw(&config_path).unwrap_or_default();
 
         config.providers.entries.insert(
             provider_name.clone(),
@@ -167,7 +167,7 @@ impl ProvidersSubCmd {
 
     async fn remove(&self, name: &str) -> Result<()> {
         let config_path = openclaw_core::UnifiedConfig::default_path();
-        let mut config = openclaw_core::UnifiedConfig::load(&config_path).unwrap_or_default();
+        let mut config = openclaw_core::UnifiedConfig::load_raw(&config_path).unwrap_or_default();
 
         if config.providers.entries.remove(name).is_some() {
             config.save(&config_path)?;
@@ -0,0 +1,279 @@
+//! 会话分支 CLI 工具
+//!
+//! 通过网关接口分叉、比较和合并会话分支。
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use openclaw_agent::session_tree::{BranchDiff, BranchInfo, SessionMessage};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, Args)]
+pub struct GatewayArgs {
+    /// Gateway URL
+    #[arg(long, default_value = "http://localhost:18789")]
+    gateway_url: String,
+    /// API key (defaults to OPENCLAW_API_KEY)
+    #[arg(long)]
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SessionCommand {
+    /// List sessions and their branch counts
+    List {
+        #[command(flatten)]
+        gateway: GatewayArgs,
+    },
+    /// Show the branch tree of a session
+    Branches {
+        session_id: String,
+        #[command(flatten)]
+        gateway: GatewayArgs,
+    },
+    /// Print the transcript of a branch, from the root to the branch tip
+    Show {
+        session_id: String,
+        /// Branch ID (defaults to the root)
+        #[arg(long)]
+        branch: Option<String>,
+        #[command(flatten)]
+        gateway: GatewayArgs,
+    },
+    /// Fork the conversation at a message, keeping history up to and including it
+    Fork {
+        session_id: String,
+        message_id: String,
+        /// Branch that holds the message (looked up automatically if omitted)
+        #[arg(long)]
+        branch: Option<String>,
+        /// Name of the new branch
+        #[arg(long)]
+        name: Option<String>,
+        #[command(flatten)]
+        gateway: GatewayArgs,
+    },
+    /// Compare the transcripts of two branches
+    Diff {
+        session_id: String,
+        left: String,
+        right: String,
+        #[command(flatten)]
+        gateway: GatewayArgs,
+    },
+    /// Merge a branch's summary back into its parent
+    Merge {
+        session_id: String,
+        branch: String,
+        /// Summary to append to the parent (defaults to an excerpt of the branch)
+        #[arg(long)]
+        summary: Option<String>,
+        #[command(flatten)]
+        gateway: GatewayArgs,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchResponse<T> {
+    success: bool,
+    data: Option<T>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionSummary {
+    id: String,
+    name: String,
+    state: String,
+    #[serde(default)]
+    branches: Vec<BranchInfo>,
+}
+
+impl SessionCommand {
+    pub async fn execute(&self) -> Result<()> {
+        match self {
+            SessionCommand::List { gateway } => {
+                let sessions: Vec<SessionSummary> = gateway
+                    .request(reqwest::Method::GET, "/api/sessions")
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                if sessions.is_empty() {
+                    println!("No sessions");
+                }
+                for session in sessions {
+                    println!(
+                        "{}  {}  [{}]  {} branches",
+                        session.id,
+                        session.name,
+                        session.state,
+                        session.branches.len().saturating_sub(1)
+                    );
+                }
+                Ok(())
+            }
+            SessionCommand::Branches {
+                session_id,
+                gateway,
+            } => {
+                let branches: Vec<BranchInfo> = gateway
+                    .call(gateway.request(
+                        reqwest::Method::GET,
+                        &format!("/api/sessions/{}/branches", session_id),
+                    ))
+                    .await?;
+                print_tree(&branches);
+                Ok(())
+            }
+            SessionCommand::Show {
+                session_id,
+                branch,
+                gateway,
+            } => {
+                let branch = branch.as_deref().unwrap_or(session_id.as_str());
+                let messages: Vec<SessionMessage> = gateway
+                    .call(gateway.request(
+                        reqwest::Method::GET,
+                        &format!(
+                            "/api/sessions/{}/branches/{}/transcript",
+                            session_id, branch
+                        ),
+                    ))
+                    .await?;
+                for message in &messages {
+                    print_message(' ', message);
+                }
+                Ok(())
+            }
+            SessionCommand::Fork {
+                session_id,
+                message_id,
+                branch,
+                name,
+                gateway,
+            } => {
+                let request = gateway
+                    .request(
+                        reqwest::Method::POST,
+                        &format!("/api/sessions/{}/fork", session_id),
+                    )
+                    .json(&serde_json::json!({
+                        "message_id": message_id,
+                        "branch_id": branch,
+                        "name": name,
+                    }));
+                let node: BranchInfo = gateway.call(request).await?;
+                println!("✅ Forked branch {} ({})", node.name, node.id);
+                Ok(())
+            }
+            SessionCommand::Diff {
+                session_id,
+                left,
+                right,
+                gateway,
+            } => {
+                let request = gateway
+                    .request(
+                        reqwest::Method::GET,
+                        &format!("/api/sessions/{}/diff", session_id),
+                    )
+                    .query(&[("left", left), ("right", right)]);
+                let diff: BranchDiff = gateway.call(request).await?;
+                println!("{} shared messages", diff.shared);
+                for message in &diff.left_only {
+                    print_message('-', message);
+                }
+                for message in &diff.right_only {
+                    print_message('+', message);
+                }
+                Ok(())
+            }
+            SessionCommand::Merge {
+                session_id,
+                branch,
+                summary,
+                gateway,
+            } => {
+                let request = gateway
+                    .request(
+                        reqwest::Method::POST,
+                        &format!("/api/sessions/{}/branches/{}/merge", session_id, branch),
+                    )
+                    .json(&serde_json::json!({ "summary": summary }));
+                let parent: BranchInfo = gateway.call(request).await?;
+                println!("✅ Merged {} into {} ({})", branch, parent.name, parent.id);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl GatewayArgs {
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}{}", self.gateway_url.trim_end_matches('/'), path);
+        let request = reqwest::Client::new().request(method, url);
+        match self
+            .api_key
+            .clone()
+            .or_else(|| std::env::var("OPENCLAW_API_KEY").ok())
+        {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        let response: BranchResponse<T> = request.send().await?.error_for_status()?.json().await?;
+        match (response.success, response.data) {
+            (true, Some(data)) => Ok(data),
+            _ => anyhow::bail!(
+                "{}",
+                response
+                    .error
+                    .unwrap_or_else(|| "unknown error".to_string())
+            ),
+        }
+    }
+}
+
+/// 按父子关系缩进打印分支树
+fn print_tree(branches: &[BranchInfo]) {
+    let mut children: HashMap<_, Vec<&BranchInfo>> = HashMap::new();
+    for branch in branches {
+        children.entry(branch.parent_id).or_default().push(branch);
+    }
+
+    let mut stack: Vec<(&BranchInfo, usize)> = children
+        .get(&None)
+        .map(|roots| roots.iter().rev().map(|b| (*b, 0)).collect())
+        .unwrap_or_default();
+    while let Some((branch, depth)) = stack.pop() {
+        println!(
+            "{}{} {}  {} messages  [{:?}]{}",
+            "  ".repeat(depth),
+            if depth == 0 { "●" } else { "└" },
+            branch.name,
+            branch.message_count,
+            branch.state,
+            branch
+                .fork_message_id
+                .map(|id| format!("  forked at {}", id))
+                .unwrap_or_default()
+        );
+        println!("{}  {}", "  ".repeat(depth), branch.id);
+        if let Some(kids) = children.get(&Some(branch.id)) {
+            stack.extend(kids.iter().rev().map(|b| (*b, depth + 1)));
+        }
+    }
+}
+
+fn print_message(marker: char, message: &SessionMessage) {
+    println!(
+        "{} [{}] {}: {}",
+        marker, message.id, message.role, message.content
+    );
+}
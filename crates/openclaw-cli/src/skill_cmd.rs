@@ -2,6 +2,9 @@ use std::path::PathBuf;
 
 use clap::Subcommand;
 use openclaw_core::OpenClawError;
+use openclaw_tools::{
+    BundleSigner, BundleVerifier, SkillBundle, TrustStore, UnsignedBundlePolicy, VerificationStatus,
+};
 
 #[derive(Debug, Subcommand)]
 pub enum SkillCommand {
@@ -29,6 +32,45 @@ pub enum SkillCommand {
         /// 技能包 ID
         bundle_id: String,
     },
+    /// 生成签名密钥对
+    Keygen {
+        /// 私钥输出文件
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// 签名技能包目录
+    Sign {
+        /// 技能包目录（包含 bundle.json）
+        path: PathBuf,
+        /// 私钥文件
+        #[arg(short, long)]
+        key: PathBuf,
+        /// 签名后打包为 zip
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// 校验技能包签名与完整性
+    Verify {
+        /// 技能包目录或 zip 文件
+        path: PathBuf,
+        /// 未签名策略 (reject, warn, allow)
+        #[arg(long, default_value = "reject")]
+        policy: String,
+    },
+    /// 信任发布者公钥
+    Trust {
+        /// 发布者名称
+        publisher: String,
+        /// 公钥 (hex)
+        public_key: String,
+    },
+    /// 移除受信任的公钥
+    Untrust {
+        /// 公钥 ID
+        key_id: String,
+    },
+    /// 列出受信任的公钥
+    TrustList,
 }
 
 pub async fn execute(command: SkillCommand) -> Result<(), OpenClawError> {
@@ -123,9 +165,106 @@ pub async fn execute(command: SkillCommand) -> Result<(), OpenClawError> {
             println!();
             println!("⚠️  详情功能需要市场 API 支持");
         }
+
+        SkillCommand::Keygen { output } => {
+            let signer = BundleSigner::generate();
+            std::fs::write(&output, signer.secret_hex())?;
+            println!("🔑 已生成签名密钥");
+            println!("   私钥文件: {}", output.display());
+            println!("   公钥 ID: {}", signer.key_id());
+            println!("   公钥: {}", signer.public_key_hex());
+            println!();
+            println!("⚠️  请妥善保管私钥文件，将公钥分发给使用者:");
+            println!(
+                "   openclaw-rust skill trust <发布者> {}",
+                signer.public_key_hex()
+            );
+        }
+
+        SkillCommand::Sign { path, key, output } => {
+            let signer = BundleSigner::from_file(&key).map_err(tool_error)?;
+            let manifest = signer.sign_dir(&path).map_err(tool_error)?;
+            println!("✍️  技能包已签名: {} v{}", manifest.name, manifest.version);
+            println!("   摘要: {}", manifest.digest.unwrap_or_default());
+            println!("   公钥 ID: {}", signer.key_id());
+
+            if let Some(output) = output {
+                let bundle = SkillBundle::from_dir(&path).map_err(tool_error)?;
+                bundle.pack(&output).await.map_err(tool_error)?;
+                println!("   已打包: {}", output.display());
+            }
+        }
+
+        SkillCommand::Verify { path, policy } => {
+            let policy = policy.parse::<UnsignedBundlePolicy>().map_err(tool_error)?;
+            let trust_store = TrustStore::load(&TrustStore::default_path()).map_err(tool_error)?;
+            let verifier = BundleVerifier::new(trust_store, policy);
+
+            let temp_dir = tempfile::tempdir()?;
+            let dir = if path.is_dir() {
+                path.clone()
+            } else {
+                SkillBundle::extract_archive(&path, temp_dir.path()).map_err(tool_error)?;
+                temp_dir.path().to_path_buf()
+            };
+
+            match verifier.verify_dir(&dir) {
+                Ok(VerificationStatus::Verified { key_id, publisher }) => {
+                    println!("✅ 签名有效: {} ({})", publisher, key_id);
+                }
+                Ok(VerificationStatus::Untrusted { key_id }) => {
+                    println!("⚠️  签名有效，但公钥 {} 不在信任库中", key_id);
+                }
+                Ok(VerificationStatus::Unsigned) => {
+                    println!("⚠️  技能包未签名");
+                }
+                Err(e) => {
+                    println!("❌ 校验失败: {}", e);
+                    return Err(tool_error(e));
+                }
+            }
+        }
+
+        SkillCommand::Trust {
+            publisher,
+            public_key,
+        } => {
+            let mut store = TrustStore::load(&TrustStore::default_path()).map_err(tool_error)?;
+            let key_id = store.add_key(&publisher, &public_key).map_err(tool_error)?;
+            store.save().map_err(tool_error)?;
+            println!("🔐 已信任 {} 的公钥: {}", publisher, key_id);
+        }
+
+        SkillCommand::Untrust { key_id } => {
+            let mut store = TrustStore::load(&TrustStore::default_path()).map_err(tool_error)?;
+            if store.remove_key(&key_id) {
+                store.save().map_err(tool_error)?;
+                println!("🗑️  已移除公钥: {}", key_id);
+            } else {
+                println!("   公钥不存在: {}", key_id);
+            }
+        }
+
+        SkillCommand::TrustList => {
+            let store = TrustStore::load(&TrustStore::default_path()).map_err(tool_error)?;
+            println!("🔐 受信任的发布者公钥:");
+            println!();
+            let keys = store.list();
+            if keys.is_empty() {
+                println!("   (暂无受信任的公钥)");
+            }
+            for key in keys {
+                println!("   {} - {}", key.key_id, key.publisher);
+                println!("      {}", key.public_key);
+            }
+        }
     }
 
     Ok(())
 }
 
 use std::sync::Arc;
+
+fn tool_error(e: openclaw_tools::BundleError) -> OpenClawError {
+    OpenClawError::Tool(e.to_string())
+}
@@ -26,9 +26,18 @@ pub struct Config {
     /// 浏览器配置
     #[serde(default)]
     pub browser: Option<serde_json::Value>,
+    /// 画布存储配置
+    #[serde(default)]
+    pub canvas: Option<serde_json::Value>,
+    /// Agentic RAG 配置（数据源、规划、重排等），未设置时使用默认配置
+    #[serde(default)]
+    pub agentic_rag: Option<serde_json::Value>,
     /// 沙箱配置
     #[serde(default)]
     pub sandbox: SandboxSettings,
+    /// REST API 认证配置
+    #[serde(default)]
+    pub api_auth: ApiAuthSettings,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -46,6 +55,9 @@ pub struct ChannelSettings {
     pub config: Option<serde_json::Value>,
     #[serde(default)]
     pub channel_to_agent_map: std::collections::HashMap<String, String>,
+    /// 流式回复：边生成边编辑消息
+    #[serde(default)]
+    pub stream_replies: bool,
 }
 
 /// 服务配置
@@ -68,6 +80,9 @@ pub struct ServerConfig {
     pub enable_evolution: bool,
     #[serde(default)]
     pub evolution_model: Option<String>,
+    /// 会话数据库路径（SQLite），未设置时会话及分支只保存在内存中
+    #[serde(default)]
+    pub session_db: Option<PathBuf>,
 }
 
 impl Default for ServerConfig {
@@ -83,6 +98,7 @@ impl Default for ServerConfig {
             enable_agentic_rag: false,
             enable_evolution: false,
             evolution_model: None,
+            session_db: None,
         }
     }
 }
@@ -232,6 +248,9 @@ pub struct MemoryConfig {
     pub short_term: ShortTermMemoryConfig,
     /// 长期记忆配置
     pub long_term: LongTermMemoryConfig,
+    /// 记忆所属用户，设置后长期记忆的读写和遗忘都限定在该用户的命名空间内
+    #[serde(default)]
+    pub namespace_user: Option<String>,
 }
 
 impl Default for MemoryConfig {
@@ -241,6 +260,7 @@ impl Default for MemoryConfig {
             working: WorkingMemoryConfig::default(),
             short_term: ShortTermMemoryConfig::default(),
             long_term: LongTermMemoryConfig::default(),
+            namespace_user: None,
         }
     }
 }
@@ -311,6 +331,9 @@ pub struct LongTermMemoryConfig {
     pub enable_knowledge_graph: bool,
     /// Custom embedding config
     pub custom_embedding: Option<CustomEmbeddingConfig>,
+    /// 本地嵌入模型配置 (当 embedding_provider 为 local 时使用)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_embedding: Option<LocalEmbeddingConfig>,
 }
 
 impl Default for LongTermMemoryConfig {
@@ -327,6 +350,7 @@ impl Default for LongTermMemoryConfig {
             enable_bm25: false,
             enable_knowledge_graph: false,
             custom_embedding: None,
+            local_embedding: None,
         }
     }
 }
@@ -338,6 +362,19 @@ pub struct CustomEmbeddingConfig {
     pub model: Option<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocalEmbeddingConfig {
+    /// 模型缓存目录
+    pub cache_dir: Option<String>,
+    /// 仅使用本地缓存，不访问网络
+    #[serde(default)]
+    pub offline: bool,
+    /// 权重精度 (f32, f16, bf16)
+    pub precision: Option<String>,
+    /// 批处理大小
+    pub batch_size: Option<usize>,
+}
+
 /// 向量存储配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorConfig {
@@ -447,6 +484,38 @@ impl Default for SecurityConfig {
     }
 }
 
+/// REST API 认证配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiAuthSettings {
+    /// 是否启用认证，关闭后所有接口可匿名访问
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// JWT (HS256) 签名密钥，未设置时读取 OPENCLAW_JWT_SECRET
+    #[serde(default)]
+    pub jwt_secret: Option<String>,
+    /// API Key 存储文件，默认 ~/.openclaw-rust/api_keys.json
+    #[serde(default)]
+    pub keys_path: Option<PathBuf>,
+    /// 凭证未单独设置限流时的每分钟请求上限
+    #[serde(default = "default_api_rate_limit")]
+    pub default_rate_limit_per_minute: u32,
+}
+
+fn default_api_rate_limit() -> u32 {
+    120
+}
+
+impl Default for ApiAuthSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            jwt_secret: None,
+            keys_path: None,
+            default_rate_limit_per_minute: default_api_rate_limit(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SandboxSettings {
     pub enabled: bool,
@@ -756,16 +825,26 @@ pub struct CommandDef {
 
 impl Config {
     /// 从文件加载配置
+    ///
+    /// 展开 `${ENV}` / `${file:...}` 占位符并按 Schema 校验，警告只记录日志
     pub fn from_file(path: &std::path::Path) -> crate::Result<Self> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| crate::OpenClawError::Config(format!("读取配置文件失败: {}", e)))?;
-
-        let config: Config = serde_json::from_str(&content)
-            .map_err(|e| crate::OpenClawError::Config(format!("解析配置文件失败: {}", e)))?;
+        let value = crate::config_interpolation::read_config_json(path)?;
+        let (config, warnings) = crate::config_schema::parse_config(value)?;
+        for warning in &warnings {
+            tracing::warn!("{}: {}", path.display(), warning);
+        }
 
         Ok(config)
     }
 
+    /// 在配置目录中查找网关配置文件，优先 config.json，其次旧的 openclaw.json
+    pub fn find_in_dir(dir: &std::path::Path) -> Option<PathBuf> {
+        ["config.json", "openclaw.json"]
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.exists())
+    }
+
     /// 保存配置到文件
     pub fn save(&self, path: &std::path::Path) -> crate::Result<()> {
         let content = serde_json::to_string_pretty(self)
@@ -878,6 +957,7 @@ mod tests {
                 }
             })),
             channel_to_agent_map: Default::default(),
+            stream_replies: false,
         };
         assert!(settings.enabled);
         assert!(settings.config.is_some());
@@ -897,6 +977,7 @@ mod tests {
                 }
             })),
             channel_to_agent_map: Default::default(),
+            stream_replies: false,
         };
 
         let json = serde_json::to_string(&settings).unwrap();
@@ -0,0 +1,181 @@
+//! 配置值插值
+//!
+//! 字符串值中的占位符在加载时展开，密钥无需明文写入配置文件：
+//! - `${NAME}`：环境变量，未设置时报错
+//! - `${NAME:-default}`：环境变量，未设置或为空时使用默认值
+//! - `${file:/path}`：文件内容（去掉末尾换行），支持 `~/` 开头
+//! - `$${`：输出字面量 `${`
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::{OpenClawError, Result};
+
+/// 读取 JSON 配置文件并展开所有占位符
+pub fn read_config_json(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| OpenClawError::Config(format!("读取配置文件失败: {}", e)))?;
+    let mut value: Value = serde_json::from_str(&content)
+        .map_err(|e| OpenClawError::Config(format!("解析配置文件失败: {}", e)))?;
+    interpolate_value(&mut value)?;
+    Ok(value)
+}
+
+/// 展开 JSON 中所有字符串值（不含键）的占位符，错误信息带字段路径
+pub fn interpolate_value(value: &mut Value) -> Result<()> {
+    interpolate_at(value, &mut String::new())
+}
+
+/// 展开单个字符串中的占位符
+pub fn interpolate_str(input: &str) -> Result<String> {
+    expand(input).map_err(OpenClawError::Config)
+}
+
+fn interpolate_at(value: &mut Value, path: &mut String) -> Result<()> {
+    match value {
+        Value::String(s) if s.contains('$') => {
+            *s = expand(s)
+                .map_err(|e| OpenClawError::Config(format!("{}: {}", display_path(path), e)))?;
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{}]", i));
+                interpolate_at(item, path)?;
+                path.truncate(len);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                let len = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+                interpolate_at(item, path)?;
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "(root)" } else { path }
+}
+
+fn expand(input: &str) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+
+        if let Some(after) = tail.strip_prefix("$${") {
+            out.push_str("${");
+            rest = after;
+        } else if let Some(body) = tail.strip_prefix("${") {
+            let end = body
+                .find('}')
+                .ok_or_else(|| format!("占位符未闭合: {}", tail))?;
+            out.push_str(&resolve(&body[..end])?);
+            rest = &body[end + 1..];
+        } else {
+            out.push('$');
+            rest = &tail[1..];
+        }
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn resolve(expr: &str) -> std::result::Result<String, String> {
+    if let Some(path) = expr.strip_prefix("file:") {
+        let path = expand_home(path.trim());
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("无法读取 {}: {}", path.display(), e))?;
+        return Ok(content.trim_end_matches(['\r', '\n']).to_string());
+    }
+
+    let (name, default) = match expr.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (expr, None),
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("无效的环境变量名: {}", name));
+    }
+
+    match std::env::var(name) {
+        Ok(value) if !value.is_empty() || default.is_none() => Ok(value),
+        _ => default
+            .map(str::to_string)
+            .ok_or_else(|| format!("环境变量 {} 未设置", name)),
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_interpolate_env_and_defaults() {
+        // SAFETY: 变量名仅本测试使用
+        unsafe {
+            std::env::set_var("OPENCLAW_TEST_INTERP_KEY", "sk-123");
+            std::env::remove_var("OPENCLAW_TEST_INTERP_MISSING");
+        }
+
+        assert_eq!(
+            interpolate_str("Bearer ${OPENCLAW_TEST_INTERP_KEY}").unwrap(),
+            "Bearer sk-123"
+        );
+        assert_eq!(
+            interpolate_str("${OPENCLAW_TEST_INTERP_MISSING:-fallback}").unwrap(),
+            "fallback"
+        );
+        assert_eq!(
+            interpolate_str("cost $5, $${literal}").unwrap(),
+            "cost $5, ${literal}"
+        );
+        assert!(interpolate_str("${OPENCLAW_TEST_INTERP_MISSING}").is_err());
+        assert!(interpolate_str("${UNCLOSED").is_err());
+    }
+
+    #[test]
+    fn test_interpolate_file_and_error_path() {
+        let mut secret = NamedTempFile::new().unwrap();
+        writeln!(secret, "token-from-file").unwrap();
+
+        let mut value = serde_json::json!({
+            "channels": {
+                "telegram": { "bot_token": format!("${{file:{}}}", secret.path().display()) }
+            },
+            "providers": [{ "api_key": "${OPENCLAW_TEST_INTERP_ABSENT}" }]
+        });
+        let err = interpolate_value(&mut value).unwrap_err().to_string();
+        assert!(err.contains("providers[0].api_key"));
+        assert!(err.contains("OPENCLAW_TEST_INTERP_ABSENT"));
+
+        value["providers"][0]["api_key"] = Value::String("plain".to_string());
+        interpolate_value(&mut value).unwrap();
+        assert_eq!(
+            value["channels"]["telegram"]["bot_token"],
+            "token-from-file"
+        );
+    }
+}
@@ -161,6 +161,9 @@ pub struct FeaturesSection {
     pub evolution: bool,
     #[serde(default)]
     pub evolution_model: Option<String>,
+    /// 会话及分支树的 SQLite 数据库路径，未设置时只保存在内存中
+    #[serde(default)]
+    pub session_db: Option<PathBuf>,
 }
 
 fn default_true() -> bool {
@@ -204,11 +207,23 @@ pub struct VoiceSection {
 }
 
 impl UnifiedConfig {
+    /// 加载配置并展开 `${ENV}` / `${file:...}` 占位符，用于读取密钥等实际值
     pub fn load(path: &PathBuf) -> crate::Result<Self> {
         if !path.exists() {
             return Ok(Self::default());
         }
 
+        let value = crate::config_interpolation::read_config_json(path)?;
+        serde_json::from_value(value)
+            .map_err(|e| crate::OpenClawError::Config(format!("解析配置失败: {}", e)))
+    }
+
+    /// 加载配置但保留占位符，修改后再 `save` 不会把密钥写成明文
+    pub fn load_raw(path: &PathBuf) -> crate::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
         let content = fs::read_to_string(path)
             .map_err(|e| crate::OpenClawError::Config(format!("读取配置失败: {}", e)))?;
 
@@ -311,6 +326,7 @@ impl UnifiedConfig {
             enable_agentic_rag: self.features.agentic_rag,
             enable_evolution: self.features.evolution,
             evolution_model: self.features.evolution_model.clone(),
+            session_db: self.features.session_db.clone(),
         };
 
         let security_config = crate::config::SecurityConfig {
@@ -332,7 +348,10 @@ impl UnifiedConfig {
             security: security_config,
             voice: None,
             browser: None,
+            canvas: None,
+            agentic_rag: None,
             sandbox: crate::config::SandboxSettings::default(),
+            api_auth: crate::config::ApiAuthSettings::default(),
         }
     }
 }
@@ -393,7 +412,10 @@ mod tests {
                 email: ChannelConfig::default(),
                 custom: HashMap::new(),
             },
-            features: FeaturesSection::default(),
+            features: FeaturesSection {
+                session_db: Some(PathBuf::from("/var/lib/openclaw/sessions.db")),
+                ..Default::default()
+            },
             security: SecuritySection {
                 enable_input_filter: true,
                 enable_audit: true,
@@ -414,6 +436,10 @@ mod tests {
         assert_eq!(config.server.enable_agents, true);
         assert_eq!(config.server.enable_channels, true);
         assert_eq!(config.server.enable_voice, true);
+        assert_eq!(
+            config.server.session_db,
+            Some(PathBuf::from("/var/lib/openclaw/sessions.db"))
+        );
 
         assert_eq!(config.ai.default_provider, "openai");
         assert_eq!(config.ai.providers.len(), 2);
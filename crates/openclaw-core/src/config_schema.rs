@@ -0,0 +1,694 @@
+//! 网关配置文件的 JSON Schema 与校验
+//!
+//! Schema 与 [`Config`] 的结构一一对应，写入配置目录后可供编辑器补全；
+//! `openclaw doctor`、设置向导和网关热加载都通过 [`validate_config`] 校验配置。
+//! 与 serde 解析不同，校验会指出具体字段，并对会被忽略的未知字段给出警告。
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::config::{Config, ProviderType, VectorBackend};
+use crate::{OpenClawError, Result};
+
+/// 与配置文件放在同一目录的 Schema 文件名
+pub const SCHEMA_FILE_NAME: &str = "openclaw.schema.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// 配置无法使用
+    Error,
+    /// 配置可以加载，但可能与预期不符
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigIssue {
+    /// 字段路径，如 `ai.providers[0].api_key`
+    pub path: String,
+    pub message: String,
+    pub severity: IssueSeverity,
+}
+
+impl ConfigIssue {
+    fn error(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+            severity: IssueSeverity::Error,
+        }
+    }
+
+    fn warning(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+            severity: IssueSeverity::Warning,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "(root)"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+/// 将 Schema 写入配置目录，返回文件路径
+pub fn write_schema_file(dir: &Path) -> Result<PathBuf> {
+    let path = dir.join(SCHEMA_FILE_NAME);
+    let content = serde_json::to_string_pretty(&config_schema())
+        .map_err(|e| OpenClawError::Config(format!("序列化 Schema 失败: {}", e)))?;
+    std::fs::write(&path, content)
+        .map_err(|e| OpenClawError::Config(format!("写入 Schema 失败: {}", e)))?;
+    Ok(path)
+}
+
+/// 序列化配置并加上指向同目录 Schema 的 `$schema`，供编辑器补全
+pub fn to_json_with_schema(config: &Config) -> Result<Value> {
+    let mut value = serde_json::to_value(config)
+        .map_err(|e| OpenClawError::Config(format!("序列化配置失败: {}", e)))?;
+    if let Value::Object(map) = &mut value {
+        map.insert(
+            "$schema".to_string(),
+            Value::String(format!("./{}", SCHEMA_FILE_NAME)),
+        );
+    }
+    Ok(value)
+}
+
+/// 校验已插值的配置，返回全部错误和警告
+pub fn validate_config(value: &Value) -> Vec<ConfigIssue> {
+    let schema = config_schema();
+    let mut validator = Validator {
+        root: &schema,
+        issues: Vec::new(),
+    };
+    validator.check(&schema, value, "");
+
+    let mut issues = validator.issues;
+    if !issues.iter().any(ConfigIssue::is_error) {
+        issues.extend(semantic_issues(value));
+    }
+    issues
+}
+
+/// 校验并解析配置，有错误时拒绝，警告随结果返回
+pub fn parse_config(value: Value) -> Result<(Config, Vec<ConfigIssue>)> {
+    let issues = validate_config(&value);
+    let errors: Vec<String> = issues
+        .iter()
+        .filter(|i| i.is_error())
+        .map(ToString::to_string)
+        .collect();
+    if !errors.is_empty() {
+        return Err(OpenClawError::Config(format!(
+            "配置校验失败: {}",
+            errors.join("; ")
+        )));
+    }
+
+    let config = serde_json::from_value(value)
+        .map_err(|e| OpenClawError::Config(format!("解析配置文件失败: {}", e)))?;
+    Ok((config, issues))
+}
+
+/// 不依赖 Schema 表达的跨字段检查，只产生警告
+fn semantic_issues(value: &Value) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let Some(ai) = value.get("ai") else {
+        return issues;
+    };
+
+    let names: Vec<&str> = ai
+        .get("providers")
+        .and_then(Value::as_array)
+        .map(|providers| {
+            providers
+                .iter()
+                .filter_map(|p| p.get("name").and_then(Value::as_str))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for (i, name) in names.iter().enumerate() {
+        if names[..i].contains(name) {
+            issues.push(ConfigIssue::warning(
+                &format!("ai.providers[{}].name", i),
+                format!("提供商 {} 重复定义，仅第一个生效", name),
+            ));
+        }
+    }
+
+    if let Some(default) = ai.get("default_provider").and_then(Value::as_str)
+        && !names.is_empty()
+        && !names.contains(&default)
+    {
+        issues.push(ConfigIssue::warning(
+            "ai.default_provider",
+            format!("{} 不在 ai.providers 中，将使用无密钥的默认配置", default),
+        ));
+    }
+
+    issues
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    issues: Vec<ConfigIssue>,
+}
+
+impl Validator<'_> {
+    fn check(&mut self, schema: &Value, value: &Value, path: &str) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                self.issues
+                    .push(ConfigIssue::error(path, "不允许设置该字段"));
+                return;
+            }
+            _ => schema,
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.trim_start_matches("#/$defs/");
+            match self.root.get("$defs").and_then(|defs| defs.get(name)) {
+                Some(def) => self.check(def, value, path),
+                None => self.issues.push(ConfigIssue::error(
+                    path,
+                    format!("未知的定义 {}", reference),
+                )),
+            }
+            return;
+        }
+
+        if let Some(branches) = schema.get("anyOf").and_then(Value::as_array) {
+            self.check_any_of(branches, value, path);
+            return;
+        }
+
+        if let Some(expected) = schema.get("type")
+            && !type_matches(expected, value)
+        {
+            self.issues.push(ConfigIssue::error(
+                path,
+                format!(
+                    "类型应为 {}，实际为 {}",
+                    describe_type(expected),
+                    json_type(value)
+                ),
+            ));
+            return;
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+            && !allowed.contains(value)
+        {
+            let options: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            self.issues.push(ConfigIssue::error(
+                path,
+                format!("取值应为 {} 之一", options.join(", ")),
+            ));
+        }
+
+        if let Some(n) = value.as_f64() {
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64)
+                && n < min
+            {
+                self.issues
+                    .push(ConfigIssue::error(path, format!("不能小于 {}", min)));
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64)
+                && n > max
+            {
+                self.issues
+                    .push(ConfigIssue::error(path, format!("不能大于 {}", max)));
+            }
+        }
+
+        match value {
+            Value::Object(map) => self.check_object(schema, map, path),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        self.check(item_schema, item, &format!("{}[{}]", path, i));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_object(&mut self, schema: &Value, map: &serde_json::Map<String, Value>, path: &str) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    self.issues
+                        .push(ConfigIssue::error(&join(path, key), "缺少必填字段"));
+                }
+            }
+        }
+
+        for (key, item) in map {
+            let item_path = join(path, key);
+            if let Some(item_schema) = properties.and_then(|p| p.get(key)) {
+                self.check(item_schema, item, &item_path);
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(additional) => self.check(additional, item, &item_path),
+                None if properties.is_some() => self
+                    .issues
+                    .push(ConfigIssue::warning(&item_path, "未知字段，将被忽略")),
+                None => {}
+            }
+        }
+    }
+
+    /// 选择类型匹配的分支报告问题，全部不匹配时报告类型错误
+    fn check_any_of(&mut self, branches: &[Value], value: &Value, path: &str) {
+        let mut fallback: Option<Vec<ConfigIssue>> = None;
+
+        for branch in branches {
+            let mut sub = Validator {
+                root: self.root,
+                issues: Vec::new(),
+            };
+            sub.check(branch, value, path);
+            if !sub.issues.iter().any(ConfigIssue::is_error) {
+                self.issues.extend(sub.issues);
+                return;
+            }
+
+            let resolved = self.resolve(branch);
+            let type_ok = resolved
+                .get("type")
+                .is_none_or(|expected| type_matches(expected, value));
+            if type_ok && fallback.is_none() {
+                fallback = Some(sub.issues);
+            }
+        }
+
+        match fallback {
+            Some(issues) => self.issues.extend(issues),
+            None => {
+                let expected: Vec<String> = branches
+                    .iter()
+                    .filter_map(|b| self.resolve(b).get("type").map(describe_type))
+                    .collect();
+                self.issues.push(ConfigIssue::error(
+                    path,
+                    format!(
+                        "类型应为 {}，实际为 {}",
+                        expected.join(" 或 "),
+                        json_type(value)
+                    ),
+                ));
+            }
+        }
+    }
+
+    fn resolve<'s>(&'s self, schema: &'s Value) -> &'s Value {
+        schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| {
+                self.root
+                    .get("$defs")?
+                    .get(r.trim_start_matches("#/$defs/"))
+            })
+            .unwrap_or(schema)
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn type_matches(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(t) => single_type_matches(t, value),
+        Value::Array(types) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|t| single_type_matches(t, value)),
+        _ => true,
+    }
+}
+
+fn single_type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    match expected {
+        Value::Array(types) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        other => other.as_str().unwrap_or("any").to_string(),
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn variant_names<T: Serialize>(variants: &[T]) -> Vec<Value> {
+    variants
+        .iter()
+        .filter_map(|v| serde_json::to_value(v).ok())
+        .collect()
+}
+
+fn object(description: &str, required: &[&str], properties: Value) -> Value {
+    json!({
+        "type": "object",
+        "description": description,
+        "required": required,
+        "properties": properties,
+    })
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({ "anyOf": [schema, { "type": "null" }] })
+}
+
+fn uint(description: &str) -> Value {
+    json!({ "type": "integer", "minimum": 0, "description": description })
+}
+
+/// 网关配置文件（`config.json` / `openclaw.json`）的 JSON Schema
+pub fn config_schema() -> Value {
+    let provider_types = variant_names(&[
+        ProviderType::OpenAI,
+        ProviderType::Anthropic,
+        ProviderType::Google,
+        ProviderType::Azure,
+        ProviderType::DeepSeek,
+        ProviderType::OpenRouter,
+        ProviderType::Ollama,
+        ProviderType::Qwen,
+        ProviderType::Doubao,
+        ProviderType::Glm,
+        ProviderType::Minimax,
+        ProviderType::Kimi,
+        ProviderType::Custom,
+    ]);
+    let vector_backends = variant_names(&[
+        VectorBackend::Qdrant,
+        VectorBackend::LanceDB,
+        VectorBackend::PgVector,
+        VectorBackend::Milvus,
+        VectorBackend::SQLite,
+    ]);
+    let secret = "支持 ${ENV_VAR}、${ENV_VAR:-默认值} 和 ${file:/path} 插值";
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "OpenClaw 网关配置",
+        "type": "object",
+        "required": ["server", "ai", "memory", "vector", "channels", "security"],
+        "properties": {
+            "$schema": { "type": "string" },
+            "server": object("服务配置", &["host", "port", "log_level"], json!({
+                "host": { "type": "string" },
+                "port": { "type": "integer", "minimum": 0, "maximum": 65535 },
+                "log_level": { "type": "string" },
+                "enable_agents": { "type": "boolean" },
+                "enable_channels": { "type": "boolean" },
+                "enable_voice": { "type": "boolean" },
+                "enable_canvas": { "type": "boolean" },
+                "enable_agentic_rag": { "type": "boolean" },
+                "enable_evolution": { "type": "boolean" },
+                "evolution_model": { "type": ["string", "null"] },
+                "session_db": { "type": ["string", "null"], "description": "会话及分支树的 SQLite 数据库路径" },
+            })),
+            "ai": object("AI 提供商配置", &["default_provider", "providers", "token_budget"], json!({
+                "default_provider": { "type": "string", "description": "ai.providers 中的提供商名称" },
+                "providers": { "type": "array", "items": { "$ref": "#/$defs/provider" } },
+                "token_budget": object("Token 预算", &["max_context", "max_response", "warning_threshold", "auto_compress"], json!({
+                    "max_context": uint("最大上下文 token"),
+                    "max_response": uint("最大响应 token"),
+                    "warning_threshold": { "type": "number", "minimum": 0, "maximum": 1 },
+                    "auto_compress": { "type": "boolean" },
+                })),
+                "auth_profiles": { "type": "array", "items": { "$ref": "#/$defs/auth_profile" } },
+                "use_accurate_token_count": { "type": "boolean" },
+            })),
+            "memory": object("记忆配置", &["working", "short_term", "long_term"], json!({
+                "backend_type": { "type": "string", "description": "hybrid | simple | vector" },
+                "namespace_user": {
+                    "type": ["string", "null"],
+                    "description": "记忆所属用户，设置后长期记忆按该用户隔离"
+                },
+                "working": object("工作记忆", &["max_messages", "max_tokens"], json!({
+                    "max_messages": uint("最大消息数"),
+                    "max_tokens": uint("最大 token 数"),
+                })),
+                "short_term": object("短期记忆", &["compress_after", "max_summaries"], json!({
+                    "compress_after": uint("压缩阈值（消息数）"),
+                    "max_summaries": uint("最大摘要数"),
+                    "compression_mode": { "type": "string", "description": "simple | ai" },
+                    "summary_model": { "type": ["string", "null"] },
+                })),
+                "long_term": object("长期记忆", &[
+                    "enabled", "backend", "collection", "embedding_provider", "embedding_model",
+                    "embedding_dimensions", "chunk_size", "overlap", "enable_bm25", "enable_knowledge_graph",
+                ], json!({
+                    "enabled": { "type": "boolean" },
+                    "backend": { "type": "string" },
+                    "collection": { "type": "string" },
+                    "embedding_provider": { "type": "string" },
+                    "embedding_model": { "type": "string" },
+                    "embedding_dimensions": uint("嵌入向量维度"),
+                    "chunk_size": uint("文本分块大小"),
+                    "overlap": uint("分块重叠大小"),
+                    "enable_bm25": { "type": "boolean" },
+                    "enable_knowledge_graph": { "type": "boolean" },
+                    "custom_embedding": nullable(object("自定义嵌入服务", &["base_url", "api_key"], json!({
+                        "base_url": { "type": "string" },
+                        "api_key": { "type": "string", "description": secret },
+                        "model": { "type": ["string", "null"] },
+                    }))),
+                    "local_embedding": nullable(object("本地嵌入模型", &[], json!({
+                        "cache_dir": { "type": ["string", "null"] },
+                        "offline": { "type": "boolean" },
+                        "precision": { "type": ["string", "null"], "description": "f32 | f16 | bf16" },
+                        "batch_size": { "type": ["integer", "null"], "minimum": 1 },
+                    }))),
+                })),
+            })),
+            "vector": object("向量存储配置", &["backend"], json!({
+                "backend": { "enum": vector_backends },
+                "backends": { "type": ["array", "null"], "items": { "type": "string" } },
+                "qdrant": nullable(object("Qdrant", &["url", "collection"], json!({
+                    "url": { "type": "string" },
+                    "collection": { "type": "string" },
+                    "api_key": { "type": ["string", "null"], "description": secret },
+                }))),
+                "lancedb": nullable(object("LanceDB", &["path"], json!({
+                    "path": { "type": "string" },
+                }))),
+                "milvus": nullable(object("Milvus", &["url", "collection"], json!({
+                    "url": { "type": "string" },
+                    "collection": { "type": "string" },
+                    "dimension": { "type": ["integer", "null"], "minimum": 0 },
+                }))),
+            })),
+            "channels": object("通道设置", &[], json!({
+                "enabled": { "type": "boolean" },
+                "config": {
+                    "type": ["object", "null"],
+                    "description": "通道名 -> 通道配置，修改后网关会热加载",
+                    "additionalProperties": { "type": "object" },
+                },
+                "channel_to_agent_map": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                },
+                "stream_replies": { "type": "boolean" },
+            })),
+            "security": object("安全配置", &[
+                "enable_input_filter", "enable_classifier", "enable_output_validation",
+                "enable_audit", "enable_self_healer", "classifier_strict_mode", "stuck_timeout",
+            ], json!({
+                "enable_input_filter": { "type": "boolean" },
+                "enable_classifier": { "type": "boolean" },
+                "enable_output_validation": { "type": "boolean" },
+                "enable_audit": { "type": "boolean" },
+                "enable_self_healer": { "type": "boolean" },
+                "classifier_strict_mode": { "type": "boolean" },
+                "stuck_timeout": object("卡死判定时长", &["secs", "nanos"], json!({
+                    "secs": uint("秒"),
+                    "nanos": { "type": "integer", "minimum": 0, "maximum": 999_999_999 },
+                })),
+            })),
+            "voice": nullable(object("语音配置", &["stt_provider", "tts_provider"], json!({
+                "stt_provider": { "type": "string" },
+                "tts_provider": { "type": "string" },
+                "api_key": { "type": ["string", "null"], "description": secret },
+            }))),
+            "browser": { "description": "浏览器配置" },
+            "canvas": { "description": "画布存储配置" },
+            "agentic_rag": { "description": "Agentic RAG 配置" },
+            "sandbox": object("沙箱配置", &["enabled", "default_type", "timeout_secs", "memory_limit_mb"], json!({
+                "enabled": { "type": "boolean" },
+                "default_type": { "type": "string" },
+                "timeout_secs": uint("超时秒数"),
+                "memory_limit_mb": uint("内存上限 MB"),
+            })),
+            "api_auth": object("REST API 认证配置", &[], json!({
+                "enabled": { "type": "boolean" },
+                "jwt_secret": { "type": ["string", "null"], "description": secret },
+                "keys_path": { "type": ["string", "null"] },
+                "default_rate_limit_per_minute": uint("每分钟请求上限"),
+            })),
+        },
+        "$defs": {
+            "provider": object("AI 提供商", &["name", "provider_type", "default_model", "models"], json!({
+                "name": { "type": "string" },
+                "provider_type": { "enum": provider_types },
+                "api_key": { "type": ["string", "null"], "description": secret },
+                "base_url": { "type": ["string", "null"] },
+                "default_model": { "type": "string" },
+                "models": { "type": "array", "items": { "type": "string" } },
+                "auth": { "$ref": "#/$defs/auth" },
+            })),
+            "auth": {
+                "anyOf": [
+                    object("API 密钥认证", &["type", "key"], json!({
+                        "type": { "enum": ["api_key"] },
+                        "key": { "type": "string", "description": secret },
+                    })),
+                    object("OAuth 认证", &["type", "client_id", "client_secret", "scopes"], json!({
+                        "type": { "enum": ["o_auth"] },
+                        "client_id": { "type": "string" },
+                        "client_secret": { "type": "string", "description": secret },
+                        "refresh_token": { "type": ["string", "null"] },
+                        "expires_at": { "type": ["string", "null"] },
+                        "scopes": { "type": "array", "items": { "type": "string" } },
+                    })),
+                    object("Azure AD 认证", &["type", "tenant_id", "client_id", "client_secret"], json!({
+                        "type": { "enum": ["azure_ad"] },
+                        "tenant_id": { "type": "string" },
+                        "client_id": { "type": "string" },
+                        "client_secret": { "type": "string", "description": secret },
+                    })),
+                ],
+            },
+            "auth_profile": object("认证轮换配置", &["id", "name", "provider", "auth", "priority", "enabled"], json!({
+                "id": { "type": "string" },
+                "name": { "type": "string" },
+                "provider": { "type": "string" },
+                "auth": { "$ref": "#/$defs/auth" },
+                "priority": { "type": "integer", "minimum": 0, "maximum": 255 },
+                "enabled": { "type": "boolean" },
+            })),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_matches_schema() {
+        // Config 新增字段而 Schema 未同步时，这里会出现"未知字段"警告
+        let value = serde_json::to_value(Config::default()).unwrap();
+        let issues = validate_config(&value);
+        assert!(issues.is_empty(), "{:?}", issues);
+
+        let mut config = Config::default();
+        config.ai.providers.push(crate::config::ProviderConfig {
+            name: "openai".to_string(),
+            provider_type: ProviderType::OpenAI,
+            api_key: Some("sk".to_string()),
+            base_url: None,
+            default_model: "gpt-4o".to_string(),
+            models: vec![],
+            auth: Default::default(),
+        });
+        let issues = validate_config(&serde_json::to_value(config).unwrap());
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_validate_reports_paths() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value["server"]["port"] = json!("8080");
+        value["server"]["enable_agent"] = json!(true);
+        value["vector"]["backend"] = json!("faiss");
+        value["ai"]["providers"] =
+            json!([{ "name": "openai", "provider_type": "open_a_i", "default_model": "gpt-4o" }]);
+        value["voice"] = json!({ "stt_provider": 1, "tts_provider": "openai" });
+        value.as_object_mut().unwrap().remove("security");
+
+        let issues = validate_config(&value);
+        let find = |path: &str| issues.iter().find(|i| i.path == path);
+
+        assert!(find("server.port").unwrap().is_error());
+        assert_eq!(
+            find("server.enable_agent").unwrap().severity,
+            IssueSeverity::Warning
+        );
+        assert!(find("vector.backend").unwrap().is_error());
+        assert!(find("ai.providers[0].models").unwrap().is_error());
+        assert!(find("voice.stt_provider").unwrap().is_error());
+        assert!(find("security").unwrap().is_error());
+        assert!(parse_config(value).is_err());
+    }
+
+    #[test]
+    fn test_parse_config_returns_warnings() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value["ai"]["default_provider"] = json!("anthropic");
+        value["ai"]["providers"] = json!([{
+            "name": "openai", "provider_type": "open_a_i",
+            "default_model": "gpt-4o", "models": []
+        }]);
+        value["$schema"] = json!("./openclaw.schema.json");
+
+        let (config, warnings) = parse_config(value).unwrap();
+        assert_eq!(config.ai.providers.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, "ai.default_provider");
+    }
+}
@@ -3,17 +3,21 @@
 //! 提供项目的基础类型、错误处理、配置等核心功能。
 
 pub mod config;
+pub mod config_interpolation;
 pub mod config_loader;
+pub mod config_schema;
 pub mod error;
 pub mod group_context;
 pub mod i18n;
 pub mod message;
 pub mod session;
+pub mod telemetry;
 pub mod user_config;
 
 pub use error::{OpenClawError, Result};
 pub use message::{Message, Content, Role};
 pub use config::Config;
+pub use config_schema::{ConfigIssue, IssueSeverity};
 pub use session::Session;
 pub use user_config::{UserConfig, UserConfigManager, UserProviderConfig};
 pub use i18n::{Locale, I18n};
@@ -0,0 +1,33 @@
+//! 可观测性指标名称
+//!
+//! 各 crate 通过 `metrics` 门面记录指标，名称集中定义于此，
+//! 由 openclaw-server 安装 Prometheus 导出器并暴露 `/metrics`。
+
+/// HTTP 请求数 (method, route, status)
+pub const HTTP_REQUESTS_TOTAL: &str = "openclaw_http_requests_total";
+/// HTTP 请求耗时 (method, route)
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "openclaw_http_request_duration_seconds";
+
+/// LLM 请求数 (provider, model, operation, status)
+pub const LLM_REQUESTS_TOTAL: &str = "openclaw_llm_requests_total";
+/// LLM 请求耗时 (provider, model, operation)
+pub const LLM_REQUEST_DURATION_SECONDS: &str = "openclaw_llm_request_duration_seconds";
+/// LLM token 用量 (provider, model, kind=prompt|completion)
+pub const LLM_TOKENS_TOTAL: &str = "openclaw_llm_tokens_total";
+
+/// 工具执行次数 (tool, status)
+pub const TOOL_EXECUTIONS_TOTAL: &str = "openclaw_tool_executions_total";
+/// 工具执行耗时 (tool)
+pub const TOOL_EXECUTION_DURATION_SECONDS: &str = "openclaw_tool_execution_duration_seconds";
+
+/// 通道消息数 (channel, direction=inbound|outbound, status)
+pub const CHANNEL_MESSAGES_TOTAL: &str = "openclaw_channel_messages_total";
+/// 正在处理的通道入站消息数 (channel)
+pub const CHANNEL_MESSAGES_IN_FLIGHT: &str = "openclaw_channel_messages_in_flight";
+
+/// Agent 任务数 (agent, status)
+pub const AGENT_TASKS_TOTAL: &str = "openclaw_agent_tasks_total";
+/// Agent 任务耗时 (agent)
+pub const AGENT_TASK_DURATION_SECONDS: &str = "openclaw_agent_task_duration_seconds";
+/// 正在执行的 Agent 任务数 (agent)
+pub const AGENT_TASKS_IN_FLIGHT: &str = "openclaw_agent_tasks_in_flight";
@@ -1,9 +1,16 @@
 use anyhow::Result;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use tantivy::collector::TopDocs;
-use tantivy::query::QueryParser;
+use std::sync::{Arc, Mutex, OnceLock};
+use tantivy::collector::{Count, DocSetCollector, TopDocs};
+use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, TermQuery};
 use tantivy::schema::*;
-use tantivy::{Index, ReloadPolicy, TantivyDocument, doc};
+use tantivy::{Index, ReloadPolicy, TantivyDocument, Term, doc};
+
+use crate::types::MemoryNamespace;
+
+/// 混合记忆检索使用的 BM25 索引目录
+pub const DEFAULT_INDEX_PATH: &str = "data/bm25";
 
 pub struct Bm25Index {
     index: Index,
@@ -11,6 +18,8 @@ pub struct Bm25Index {
     content_field: Field,
     source_field: Field,
     timestamp_field: Field,
+    user_field: Field,
+    persona_field: Field,
 }
 
 #[derive(Debug, Clone)]
@@ -30,15 +39,22 @@ impl Bm25Index {
         let content_field = schema_builder.add_text_field("content", TEXT | STORED);
         let source_field = schema_builder.add_text_field("source", STRING | STORED);
         let timestamp_field = schema_builder.add_i64_field("timestamp", INDEXED | STORED | FAST);
+        let user_field = schema_builder.add_text_field("user_id", STRING | STORED);
+        let persona_field = schema_builder.add_text_field("persona_id", STRING | STORED);
 
         let schema = schema_builder.build();
 
         std::fs::create_dir_all(index_path)?;
 
         let index = if index_path.join("meta.json").exists() {
-            Index::open_in_dir(index_path)?
+            let index = Index::open_in_dir(index_path)?;
+            if index.schema() == schema {
+                index
+            } else {
+                migrate_index(index, index_path, schema)?
+            }
         } else {
-            Index::create_in_dir(index_path, schema.clone())?
+            Index::create_in_dir(index_path, schema)?
         };
 
         Ok(Self {
@@ -47,9 +63,29 @@ impl Bm25Index {
             content_field,
             source_field,
             timestamp_field,
+            user_field,
+            persona_field,
         })
     }
 
+    /// 按目录共享的索引实例
+    ///
+    /// 记忆检索和文档导入写同一个索引，共用实例避免多个 writer 争抢目录锁。
+    pub fn shared(index_path: &Path) -> Result<Arc<Self>> {
+        static SHARED: OnceLock<Mutex<HashMap<PathBuf, Arc<Bm25Index>>>> = OnceLock::new();
+
+        let mut indexes = SHARED
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .map_err(|_| anyhow::anyhow!("BM25 index registry poisoned"))?;
+        if let Some(index) = indexes.get(index_path) {
+            return Ok(index.clone());
+        }
+        let index = Arc::new(Self::new(index_path)?);
+        indexes.insert(index_path.to_path_buf(), index.clone());
+        Ok(index)
+    }
+
     pub async fn add_document(
         &self,
         id: &str,
@@ -91,6 +127,107 @@ impl Bm25Index {
         Ok(())
     }
 
+    /// 批量写入带命名空间的文档
+    pub async fn add_namespaced_documents_batch(
+        &self,
+        docs: Vec<(String, String, String, i64)>,
+        namespace: &MemoryNamespace,
+    ) -> Result<()> {
+        let mut writer = self.index.writer(50_000_000)?;
+
+        for (id, content, source, timestamp) in docs {
+            writer.add_document(doc!(
+                self.id_field => id,
+                self.content_field => content,
+                self.source_field => source,
+                self.timestamp_field => timestamp,
+                self.user_field => namespace.user_id.as_str(),
+                self.persona_field => namespace.persona_id.as_str(),
+            ))?;
+        }
+
+        writer.commit()?;
+
+        Ok(())
+    }
+
+    /// 写入带命名空间的文档
+    pub async fn add_namespaced_document(
+        &self,
+        id: &str,
+        content: &str,
+        source: &str,
+        timestamp: i64,
+        namespace: &MemoryNamespace,
+    ) -> Result<()> {
+        let mut writer = self.index.writer(50_000_000)?;
+
+        writer.add_document(doc!(
+            self.id_field => id,
+            self.content_field => content,
+            self.source_field => source,
+            self.timestamp_field => timestamp,
+            self.user_field => namespace.user_id.as_str(),
+            self.persona_field => namespace.persona_id.as_str(),
+        ))?;
+
+        writer.commit()?;
+
+        Ok(())
+    }
+
+    /// 只在命名空间内检索，迁移前写入的无归属文档不会命中
+    pub fn search_in_namespace(
+        &self,
+        query_str: &str,
+        namespace: &MemoryNamespace,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let reader = self
+            .index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()?;
+        let searcher = reader.searcher();
+
+        let query_parser = QueryParser::for_index(&self.index, vec![self.content_field]);
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![
+            (Occur::Must, query_parser.parse_query(query_str)?),
+            (Occur::Must, term_query(self.user_field, &namespace.user_id)),
+        ];
+        if !namespace.persona_id.is_empty() {
+            clauses.push((
+                Occur::Must,
+                term_query(self.persona_field, &namespace.persona_id),
+            ));
+        }
+
+        let top_docs = searcher.search(&BooleanQuery::new(clauses), &TopDocs::with_limit(limit))?;
+        let mut results = Vec::new();
+        for (score, doc_address) in top_docs {
+            let retrieved_doc: TantivyDocument = searcher.doc(doc_address)?;
+            let text = |field: Field| {
+                retrieved_doc
+                    .get_first(field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            };
+            results.push(SearchResult {
+                id: text(self.id_field),
+                content: text(self.content_field),
+                source: text(self.source_field),
+                score,
+                timestamp: retrieved_doc
+                    .get_first(self.timestamp_field)
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0),
+            });
+        }
+
+        Ok(results)
+    }
+
     pub fn search(&self, query_str: &str, limit: usize) -> Result<Vec<SearchResult>> {
         let reader = self
             .index
@@ -240,6 +377,60 @@ impl Bm25Index {
         Ok(())
     }
 
+    /// 删除某个来源下的全部文档
+    pub async fn delete_by_source(&self, source: &str) -> Result<()> {
+        let mut writer: tantivy::IndexWriter = self.index.writer(50_000_000)?;
+
+        let term = tantivy::Term::from_field_text(self.source_field, source);
+        writer.delete_term(term);
+
+        writer.commit()?;
+
+        Ok(())
+    }
+
+    /// 按 id 删除文档，返回实际删除的数量
+    pub async fn delete_documents(&self, ids: &[String]) -> Result<usize> {
+        let terms: Vec<Term> = ids
+            .iter()
+            .map(|id| Term::from_field_text(self.id_field, id))
+            .collect();
+        self.delete_terms(terms)
+    }
+
+    /// 删除某个用户的全部文档，返回删除数量
+    pub async fn delete_by_user(&self, user_id: &str) -> Result<usize> {
+        self.delete_terms(vec![Term::from_field_text(self.user_field, user_id)])
+    }
+
+    fn delete_terms(&self, terms: Vec<Term>) -> Result<usize> {
+        if terms.is_empty() {
+            return Ok(0);
+        }
+
+        let reader = self
+            .index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let searcher = reader.searcher();
+        let mut count = 0;
+        for term in &terms {
+            count += searcher.search(
+                &TermQuery::new(term.clone(), IndexRecordOption::Basic),
+                &Count,
+            )?;
+        }
+
+        let mut writer: tantivy::IndexWriter = self.index.writer(50_000_000)?;
+        for term in terms {
+            writer.delete_term(term);
+        }
+        writer.commit()?;
+
+        Ok(count)
+    }
+
     pub async fn clear(&self) -> Result<()> {
         let mut writer: tantivy::IndexWriter = self.index.writer(50_000_000)?;
 
@@ -250,6 +441,82 @@ impl Bm25Index {
     }
 }
 
+/// 旧版索引缺少命名空间字段，tantivy 无法原地加字段：
+/// 把全部文档复制到新 schema 的临时目录后替换原目录，旧文档不带归属，只有不限命名空间的检索能命中
+fn migrate_index(old: Index, index_path: &Path, schema: Schema) -> Result<Index> {
+    let old_schema = old.schema();
+    let text_of = |doc: &TantivyDocument, name: &str| {
+        old_schema
+            .get_field(name)
+            .ok()
+            .and_then(|field| doc.get_first(field))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    let searcher = old.reader()?.searcher();
+    let mut docs = Vec::new();
+    for address in searcher.search(&AllQuery, &DocSetCollector)? {
+        let doc: TantivyDocument = searcher.doc(address)?;
+        let timestamp = old_schema
+            .get_field("timestamp")
+            .ok()
+            .and_then(|field| doc.get_first(field))
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0);
+        docs.push((
+            text_of(&doc, "id"),
+            text_of(&doc, "content"),
+            text_of(&doc, "source"),
+            timestamp,
+        ));
+    }
+    drop(searcher);
+    drop(old);
+
+    let staging = index_path.with_extension("migrating");
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+    {
+        let index = Index::create_in_dir(&staging, schema.clone())?;
+        let field = |name: &str| schema.get_field(name);
+        let (id, content, source, ts) = (
+            field("id")?,
+            field("content")?,
+            field("source")?,
+            field("timestamp")?,
+        );
+        let mut writer: tantivy::IndexWriter = index.writer(50_000_000)?;
+        for (doc_id, doc_content, doc_source, timestamp) in &docs {
+            writer.add_document(doc!(
+                id => doc_id.as_str(),
+                content => doc_content.as_str(),
+                source => doc_source.as_str(),
+                ts => *timestamp,
+            ))?;
+        }
+        writer.commit()?;
+    }
+
+    std::fs::remove_dir_all(index_path)?;
+    std::fs::rename(&staging, index_path)?;
+    tracing::info!(
+        "BM25 索引已迁移到带命名空间的 schema: {} 条文档",
+        docs.len()
+    );
+    Ok(Index::open_in_dir(index_path)?)
+}
+
+fn term_query(field: Field, value: &str) -> Box<dyn Query> {
+    Box::new(TermQuery::new(
+        Term::from_field_text(field, value),
+        IndexRecordOption::Basic,
+    ))
+}
+
 pub struct Bm25Config {
     pub index_path: PathBuf,
     pub default_limit: usize,
@@ -307,4 +574,73 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(temp_dir);
     }
+
+    #[tokio::test]
+    async fn test_namespaced_search_and_delete() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let index = Bm25Index::new(temp_dir.path()).unwrap();
+        let alice = MemoryNamespace::new("alice", "assistant");
+        let bob = MemoryNamespace::new("bob", "assistant");
+
+        index
+            .add_namespaced_document("a1", "alice likes rust", "memory", 1, &alice)
+            .await
+            .unwrap();
+        index
+            .add_namespaced_document("b1", "bob likes rust", "memory", 2, &bob)
+            .await
+            .unwrap();
+
+        let results = index.search_in_namespace("rust", &alice, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "a1");
+
+        assert_eq!(index.delete_by_user("alice").await.unwrap(), 1);
+        assert!(
+            index
+                .search_in_namespace("rust", &alice, 10)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(index.search("rust", 10).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_index_is_migrated() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        {
+            let mut builder = Schema::builder();
+            let id = builder.add_text_field("id", STRING | STORED);
+            let content = builder.add_text_field("content", TEXT | STORED);
+            let source = builder.add_text_field("source", STRING | STORED);
+            let timestamp = builder.add_i64_field("timestamp", INDEXED | STORED | FAST);
+            let index = Index::create_in_dir(temp_dir.path(), builder.build()).unwrap();
+            let mut writer: tantivy::IndexWriter = index.writer(50_000_000).unwrap();
+            writer
+                .add_document(doc!(
+                    id => "old1",
+                    content => "legacy rust notes",
+                    source => "memory",
+                    timestamp => 7i64,
+                ))
+                .unwrap();
+            writer.commit().unwrap();
+        }
+
+        let index = Bm25Index::new(temp_dir.path()).unwrap();
+        let alice = MemoryNamespace::new("alice", "assistant");
+        let legacy = index.search("legacy", 10).unwrap();
+        assert_eq!(legacy.len(), 1);
+        assert_eq!(legacy[0].id, "old1");
+        assert_eq!(legacy[0].timestamp, 7);
+
+        // 迁移后的索引支持命名空间读写，旧文档不属于任何命名空间
+        index
+            .add_namespaced_document("a1", "alice rust notes", "memory", 8, &alice)
+            .await
+            .unwrap();
+        let scoped = index.search_in_namespace("rust", &alice, 10).unwrap();
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0].id, "a1");
+    }
 }
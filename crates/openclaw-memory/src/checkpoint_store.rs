@@ -75,6 +75,37 @@ impl CheckpointStore {
         Ok(())
     }
 
+    /// 删除满足条件的检查点，返回删除数量
+    pub async fn delete_where(
+        &self,
+        predicate: impl Fn(&Checkpoint) -> bool,
+    ) -> Result<usize, String> {
+        let mut checkpoints = self.checkpoints.write().await;
+        let before = checkpoints.len();
+        checkpoints.retain(|c| !predicate(c));
+        let removed = before - checkpoints.len();
+        if removed > 0 {
+            self.persist_to_disk(&checkpoints).await?;
+        }
+        Ok(removed)
+    }
+
+    /// 从所有检查点的消息历史中删除命中的消息，返回删除数量
+    pub async fn scrub_messages(&self, matches: impl Fn(&str) -> bool) -> Result<usize, String> {
+        let mut checkpoints = self.checkpoints.write().await;
+        let mut removed = 0;
+        for checkpoint in checkpoints.iter_mut() {
+            let history = &mut checkpoint.state.message_history;
+            let before = history.len();
+            history.retain(|m| !matches(&m.content));
+            removed += before - history.len();
+        }
+        if removed > 0 {
+            self.persist_to_disk(&checkpoints).await?;
+        }
+        Ok(removed)
+    }
+
     async fn persist_to_disk(&self, checkpoints: &[Checkpoint]) -> Result<(), String> {
         let json = serde_json::to_string_pretty(checkpoints)
             .map_err(|e| format!("Failed to serialize checkpoints: {}", e))?;
@@ -20,6 +20,12 @@ pub struct ChunkMetadata {
     pub chunk_index: usize,
     pub total_chunks: usize,
     pub document_id: Option<String>,
+    /// 起始行号（从 1 开始），仅按行切分时填写
+    #[serde(default)]
+    pub start_line: Option<usize>,
+    /// 结束行号（含）
+    #[serde(default)]
+    pub end_line: Option<usize>,
 }
 
 pub struct ChunkManager {
@@ -88,6 +94,8 @@ impl ChunkManager {
                     chunk_index,
                     total_chunks: 0,
                     document_id: None,
+                    start_line: None,
+                    end_line: None,
                 },
             };
 
@@ -134,6 +142,8 @@ impl ChunkManager {
                         chunk_index,
                         total_chunks: 0,
                         document_id: None,
+                        start_line: None,
+                        end_line: None,
                     },
                 });
 
@@ -160,6 +170,8 @@ impl ChunkManager {
                     chunk_index,
                     total_chunks: chunks.len(),
                     document_id: None,
+                    start_line: None,
+                    end_line: None,
                 },
             });
         }
@@ -172,6 +184,74 @@ impl ChunkManager {
         Ok(chunks)
     }
 
+    /// 按整行切分，块之间按 `overlap` token 重叠，并记录每块的行号范围
+    ///
+    /// 单行超过 `chunk_size` 时独占一块，不在行内截断，保证引用的行号准确。
+    pub fn chunk_text_by_lines(&self, text: &str, source: &str) -> Result<Vec<Chunk>> {
+        let bpe = cl100k_base()?;
+
+        // (行内容, token 数, 行首字符偏移)
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for line in text.split_inclusive('\n') {
+            lines.push((line, bpe.encode_with_special_tokens(line).len(), offset));
+            offset += line.chars().count();
+        }
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+
+        while start < lines.len() {
+            let mut end = start;
+            let mut tokens = 0;
+            while end < lines.len() && (end == start || tokens + lines[end].1 <= self.chunk_size) {
+                tokens += lines[end].1;
+                end += 1;
+            }
+
+            let content: String = lines[start..end].iter().map(|(line, _, _)| *line).collect();
+            if !content.trim().is_empty() {
+                let (_, _, start_index) = lines[start];
+                chunks.push(Chunk {
+                    id: format!("{}_{}_{}", source, chunks.len(), uuid::Uuid::new_v4()),
+                    token_count: tokens,
+                    start_index,
+                    end_index: start_index + content.chars().count(),
+                    content,
+                    source: source.to_string(),
+                    metadata: ChunkMetadata {
+                        created_at: chrono::Utc::now().timestamp(),
+                        chunk_index: chunks.len(),
+                        total_chunks: 0,
+                        document_id: None,
+                        start_line: Some(start + 1),
+                        end_line: Some(end),
+                    },
+                });
+            }
+
+            if end >= lines.len() {
+                break;
+            }
+
+            // 向前回退若干行作为重叠，但至少前进一行
+            let mut next = end;
+            let mut overlap_tokens = 0;
+            while next > start + 1 && overlap_tokens + lines[next - 1].1 <= self.overlap {
+                overlap_tokens += lines[next - 1].1;
+                next -= 1;
+            }
+            start = next;
+        }
+
+        let total_chunks = chunks.len();
+        for chunk in &mut chunks {
+            chunk.metadata.total_chunks = total_chunks;
+        }
+
+        Ok(chunks)
+    }
+
     pub fn chunk_file(&self, file_path: &PathBuf, source: &str) -> Result<Vec<Chunk>> {
         let content = std::fs::read_to_string(file_path)?;
         self.chunk_text(&content, source)
@@ -242,4 +322,38 @@ mod tests {
 
         assert!(!chunks.is_empty());
     }
+
+    #[test]
+    fn test_chunk_by_lines_tracks_line_ranges() {
+        let manager = ChunkManager::new(30, 10, "cl100k_base");
+
+        let text: String = (1..=30)
+            .map(|i| format!("line number {} of the file\n", i))
+            .collect();
+        let chunks = manager.chunk_text_by_lines(&text, "test").unwrap();
+
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks[0].metadata.start_line, Some(1));
+        assert_eq!(chunks.last().unwrap().metadata.end_line, Some(30));
+        for chunk in &chunks {
+            let start = chunk.metadata.start_line.unwrap();
+            let end = chunk.metadata.end_line.unwrap();
+            assert!(
+                chunk
+                    .content
+                    .starts_with(&format!("line number {} ", start))
+            );
+            assert!(
+                chunk
+                    .content
+                    .trim_end()
+                    .ends_with(&format!("line number {} of the file", end))
+            );
+        }
+        // 相邻块之间有重叠且不会原地踏步
+        for pair in chunks.windows(2) {
+            assert!(pair[1].metadata.start_line > pair[0].metadata.start_line);
+            assert!(pair[1].metadata.start_line <= pair[0].metadata.end_line);
+        }
+    }
 }
@@ -10,7 +10,7 @@ use openclaw_vector::{StoreBackend, VectorStore, create_store_async};
 
 use crate::ai_adapter::AIProviderEmbeddingAdapter;
 use crate::embedding::EmbeddingProvider;
-use crate::types::MemoryConfig;
+use crate::types::{LocalEmbeddingSettings, MemoryConfig};
 
 #[derive(Debug, Clone)]
 pub enum MemoryBackend {
@@ -116,6 +116,10 @@ pub async fn create_embedding_provider_from_config(
     api_key: Option<String>,
     base_url: Option<String>,
 ) -> Result<Arc<dyn EmbeddingProvider>> {
+    if provider_name == "local" {
+        return create_local_embedding_provider(model, &LocalEmbeddingSettings::default()).await;
+    }
+
     let provider: Arc<dyn AIProvider> = match provider_name {
         "custom" => {
             let base_url = base_url.unwrap_or_else(|| "https://api.openai.com/v1".to_string());
@@ -167,3 +171,35 @@ pub async fn create_embedding_provider_from_config(
 
     Ok(Arc::new(adapter))
 }
+
+/// 本地嵌入模型：`model` 为仓库名、简写或本地目录，`settings` 覆盖缓存目录、精度等默认值
+#[cfg(feature = "local-embedding")]
+pub async fn create_local_embedding_provider(
+    model: &str,
+    settings: &LocalEmbeddingSettings,
+) -> Result<Arc<dyn EmbeddingProvider>> {
+    use crate::local_embedding::{LocalEmbedding, LocalEmbeddingConfig, WeightPrecision};
+
+    let mut config = LocalEmbeddingConfig::new(model).with_offline(settings.offline);
+    if let Some(cache_dir) = &settings.cache_dir {
+        config = config.with_cache_dir(cache_dir);
+    }
+    if let Some(precision) = &settings.precision {
+        config = config.with_precision(WeightPrecision::from(precision.as_str()));
+    }
+    if let Some(batch_size) = settings.batch_size {
+        config = config.with_batch_size(batch_size);
+    }
+    Ok(Arc::new(LocalEmbedding::load(config).await?))
+}
+
+#[cfg(not(feature = "local-embedding"))]
+pub async fn create_local_embedding_provider(
+    _model: &str,
+    _settings: &LocalEmbeddingSettings,
+) -> Result<Arc<dyn EmbeddingProvider>> {
+    Err(openclaw_core::OpenClawError::Config(
+        "Local embedding not enabled. Enable 'local-embedding' feature for openclaw-memory"
+            .to_string(),
+    ))
+}
@@ -3,11 +3,19 @@
 //! 检测并解决记忆中的矛盾信息
 
 use chrono::{DateTime, Utc};
+use openclaw_ai::{AIProvider, ChatRequest};
+use openclaw_core::{Message, OpenClawError, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use crate::fact_extractor::{AtomicFact, FactCategory};
 
+/// 每次请求模型裁决的默认冲突数
+const DEFAULT_LLM_BATCH_SIZE: usize = 10;
+/// 置信度相差超过该值时按置信度裁决，否则按时间
+const CONFIDENCE_MARGIN: f32 = 0.2;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Conflict {
     pub id: String,
@@ -33,6 +41,24 @@ pub struct ConflictResolution {
     pub reason: String,
     pub resolved_at: DateTime<Utc>,
     pub method: ResolutionMethod,
+    #[serde(default)]
+    pub action: ConflictAction,
+    /// 合并或加限定条件后写回的事实，加限定条件时沿用原事实 ID
+    #[serde(default)]
+    pub replacements: Vec<AtomicFact>,
+}
+
+/// 冲突的处理方式
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictAction {
+    /// 保留一条，淘汰另一条
+    #[default]
+    KeepOne,
+    /// 合并为一条新事实
+    Merge,
+    /// 两条都保留，分别加上限定条件
+    KeepBoth,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -44,8 +70,18 @@ pub enum ResolutionMethod {
     LLMDecision,
 }
 
+/// 模型裁决后的事实集合
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedFacts {
+    pub facts: Vec<AtomicFact>,
+    /// 实际生效的冲突，`resolution` 中保留裁决方式和理由，供审计
+    pub conflicts: Vec<Conflict>,
+}
+
 pub struct ConflictResolver {
     category_weights: HashMap<FactCategory, f32>,
+    llm: Option<LlmArbiter>,
+    llm_batch_size: usize,
 }
 
 impl ConflictResolver {
@@ -63,6 +99,8 @@ impl ConflictResolver {
 
         Self {
             category_weights: weights,
+            llm: None,
+            llm_batch_size: DEFAULT_LLM_BATCH_SIZE,
         }
     }
 
@@ -71,6 +109,21 @@ impl ConflictResolver {
         self
     }
 
+    /// 配置裁决冲突的模型，未配置时 `LLMDecision` 按时间和置信度规则处理
+    pub fn with_llm(mut self, provider: Arc<dyn AIProvider>, model: impl Into<String>) -> Self {
+        self.llm = Some(LlmArbiter {
+            provider,
+            model: model.into(),
+        });
+        self
+    }
+
+    /// 每次请求模型裁决的冲突数，与 `with_llm` 的调用顺序无关
+    pub fn with_llm_batch_size(mut self, batch_size: usize) -> Self {
+        self.llm_batch_size = batch_size.max(1);
+        self
+    }
+
     pub fn detect_conflicts(&self, facts: &[AtomicFact]) -> Vec<Conflict> {
         let mut conflicts = Vec::new();
 
@@ -108,6 +161,8 @@ impl ConflictResolver {
                 reason: "等待用户确认".to_string(),
                 resolved_at: Utc::now(),
                 method: ResolutionMethod::UserConfirmed,
+                action: ConflictAction::KeepOne,
+                replacements: Vec::new(),
             },
         }
     }
@@ -131,6 +186,8 @@ impl ConflictResolver {
             reason: "根据时间戳选择最新事实".to_string(),
             resolved_at: Utc::now(),
             method: ResolutionMethod::Latest,
+            action: ConflictAction::KeepOne,
+            replacements: Vec::new(),
         }
     }
 
@@ -153,17 +210,72 @@ impl ConflictResolver {
             reason: "根据置信度选择更高置信度的事实".to_string(),
             resolved_at: Utc::now(),
             method: ResolutionMethod::HigherConfidence,
+            action: ConflictAction::KeepOne,
+            replacements: Vec::new(),
         }
     }
 
-    fn resolve_by_llm(&self, _conflict: &Conflict) -> ConflictResolution {
-        ConflictResolution {
-            winner: String::new(),
-            loser: String::new(),
-            reason: "需要 LLM 决策".to_string(),
-            resolved_at: Utc::now(),
-            method: ResolutionMethod::LLMDecision,
+    /// 同步接口无法调用模型，按规则处理；需要模型裁决时使用 `resolve_conflicts_with_llm`
+    fn resolve_by_llm(&self, conflict: &Conflict) -> ConflictResolution {
+        self.resolve_by_rules(conflict, "未调用 LLM")
+    }
+
+    /// 置信度相差明显时取置信度高的，否则取最新的
+    fn resolve_by_rules(&self, conflict: &Conflict, note: &str) -> ConflictResolution {
+        let diff = conflict.fact_a.confidence - conflict.fact_b.confidence;
+        let mut resolution = if diff.abs() > CONFIDENCE_MARGIN {
+            self.resolve_by_confidence(conflict)
+        } else {
+            self.resolve_by_time(conflict)
+        };
+        resolution.reason = format!("{}，{}", note, resolution.reason);
+        resolution
+    }
+
+    /// 分批请求模型裁决冲突，未配置模型、调用失败或裁决无效时按规则处理
+    pub async fn resolve_conflicts_with_llm(
+        &self,
+        conflicts: &[Conflict],
+    ) -> Vec<ConflictResolution> {
+        let Some(llm) = &self.llm else {
+            return conflicts
+                .iter()
+                .map(|c| self.resolve_by_rules(c, "未配置 LLM"))
+                .collect();
+        };
+
+        let mut resolutions = Vec::with_capacity(conflicts.len());
+        for batch in conflicts.chunks(self.llm_batch_size) {
+            let mut decisions = match llm.arbitrate(batch).await {
+                Ok(decisions) => decisions,
+                Err(e) => {
+                    tracing::warn!("LLM 冲突裁决失败，改用规则处理: {}", e);
+                    HashMap::new()
+                }
+            };
+            for (index, conflict) in batch.iter().enumerate() {
+                let resolution = decisions
+                    .remove(&index)
+                    .and_then(|decision| decision.into_resolution(conflict))
+                    .unwrap_or_else(|| self.resolve_by_rules(conflict, "LLM 未给出有效裁决"));
+                resolutions.push(resolution);
+            }
+        }
+        resolutions
+    }
+
+    /// 解决事实集合中的冲突，由模型决定保留、合并或加限定条件
+    pub async fn resolve_facts_with_llm(&self, facts: &[AtomicFact]) -> ResolvedFacts {
+        let conflicts = self.detect_conflicts(facts);
+        if conflicts.is_empty() {
+            return ResolvedFacts {
+                facts: facts.to_vec(),
+                conflicts,
+            };
         }
+
+        let resolutions = self.resolve_conflicts_with_llm(&conflicts).await;
+        apply_resolutions(facts, conflicts, resolutions)
     }
 
     pub fn resolve_facts(&self, facts: &[AtomicFact], method: ResolutionMethod) -> Vec<AtomicFact> {
@@ -235,9 +347,188 @@ impl Default for ConflictResolver {
     }
 }
 
+/// 按裁决结果更新事实集合，已被淘汰的事实不再参与后续冲突
+fn apply_resolutions(
+    facts: &[AtomicFact],
+    conflicts: Vec<Conflict>,
+    resolutions: Vec<ConflictResolution>,
+) -> ResolvedFacts {
+    let mut removed: HashSet<String> = HashSet::new();
+    let mut rewritten: HashMap<String, AtomicFact> = HashMap::new();
+    let mut merged = Vec::new();
+    let mut applied = Vec::new();
+
+    for (mut conflict, resolution) in conflicts.into_iter().zip(resolutions) {
+        if removed.contains(&conflict.fact_a.id) || removed.contains(&conflict.fact_b.id) {
+            continue;
+        }
+        match resolution.action {
+            ConflictAction::KeepOne => {
+                removed.insert(resolution.loser.clone());
+            }
+            ConflictAction::Merge => {
+                removed.insert(conflict.fact_a.id.clone());
+                removed.insert(conflict.fact_b.id.clone());
+                merged.extend(resolution.replacements.iter().cloned());
+            }
+            ConflictAction::KeepBoth => {
+                for fact in &resolution.replacements {
+                    rewritten.insert(fact.id.clone(), fact.clone());
+                }
+            }
+        }
+        conflict.resolution = Some(resolution);
+        applied.push(conflict);
+    }
+
+    let facts = facts
+        .iter()
+        .filter(|f| !removed.contains(&f.id))
+        .map(|f| rewritten.get(&f.id).cloned().unwrap_or_else(|| f.clone()))
+        .chain(merged)
+        .collect();
+    ResolvedFacts {
+        facts,
+        conflicts: applied,
+    }
+}
+
+struct LlmArbiter {
+    provider: Arc<dyn AIProvider>,
+    model: String,
+}
+
+impl LlmArbiter {
+    /// 一次请求裁决一批冲突，返回按批内序号索引的裁决
+    async fn arbitrate(&self, batch: &[Conflict]) -> Result<HashMap<usize, LlmDecision>> {
+        let items: Vec<serde_json::Value> = batch
+            .iter()
+            .enumerate()
+            .map(|(index, conflict)| {
+                serde_json::json!({
+                    "conflict": index,
+                    "a": fact_summary(&conflict.fact_a),
+                    "b": fact_summary(&conflict.fact_b),
+                })
+            })
+            .collect();
+
+        let prompt = format!(
+            r#"以下是记忆中相互矛盾的事实，请逐条裁决。每条冲突可以：
+1. keep_one：只保留其中一条，keep 填 "a" 或 "b"
+2. merge：合并为一条新事实，content 填合并后的内容
+3. keep_both：两条都保留，content_a 和 content_b 填加上限定条件（如时间、场景）后的内容
+
+参考事实的记录时间、来源和置信度，较新、来源明确、置信度高的信息通常更可信。
+
+返回 JSON 数组，每条冲突一项，reason 说明理由：
+[{{"conflict": 0, "action": "keep_one", "keep": "b", "reason": "较新的陈述取代了旧偏好"}}]
+
+冲突列表：
+{}"#,
+            serde_json::to_string_pretty(&items).unwrap_or_default()
+        );
+
+        let request = ChatRequest::new(self.model.clone(), vec![Message::user(prompt)])
+            .with_temperature(0.2)
+            .with_max_tokens(2000);
+        let response = self.provider.chat(request).await?;
+        let content = response.message.text_content().unwrap_or_default();
+
+        // 模型可能在 JSON 外包裹说明文字或代码块
+        let json = match (content.find('['), content.rfind(']')) {
+            (Some(start), Some(end)) if start < end => &content[start..=end],
+            _ => content,
+        };
+        let decisions: Vec<LlmDecision> = serde_json::from_str(json)
+            .map_err(|e| OpenClawError::AIProvider(format!("解析冲突裁决结果失败: {}", e)))?;
+
+        Ok(decisions
+            .into_iter()
+            .filter(|d| d.conflict < batch.len())
+            .map(|d| (d.conflict, d))
+            .collect())
+    }
+}
+
+fn fact_summary(fact: &AtomicFact) -> serde_json::Value {
+    serde_json::json!({
+        "content": fact.content,
+        "category": fact.category,
+        "created_at": fact.created_at.to_rfc3339(),
+        "source": fact.source_message_id,
+        "confidence": fact.confidence,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmDecision {
+    conflict: usize,
+    action: ConflictAction,
+    #[serde(default)]
+    keep: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    content_a: Option<String>,
+    #[serde(default)]
+    content_b: Option<String>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+impl LlmDecision {
+    fn into_resolution(self, conflict: &Conflict) -> Option<ConflictResolution> {
+        let (a, b) = (&conflict.fact_a, &conflict.fact_b);
+        let non_empty = |s: Option<String>| s.filter(|s| !s.trim().is_empty());
+
+        let (winner, loser, replacements) = match self.action {
+            ConflictAction::KeepOne => match self.keep.as_deref()? {
+                "a" => (a.id.clone(), b.id.clone(), Vec::new()),
+                "b" => (b.id.clone(), a.id.clone(), Vec::new()),
+                id if id == a.id => (a.id.clone(), b.id.clone(), Vec::new()),
+                id if id == b.id => (b.id.clone(), a.id.clone(), Vec::new()),
+                _ => return None,
+            },
+            ConflictAction::Merge => {
+                let newer = if a.created_at >= b.created_at { a } else { b };
+                let mut fact = AtomicFact::new(non_empty(self.content)?, a.category.clone())
+                    .with_confidence(a.confidence.max(b.confidence));
+                fact.source_message_id = newer.source_message_id.clone();
+                (fact.id.clone(), String::new(), vec![fact])
+            }
+            ConflictAction::KeepBoth => {
+                let mut qualified_a = a.clone();
+                qualified_a.content = non_empty(self.content_a)?;
+                let mut qualified_b = b.clone();
+                qualified_b.content = non_empty(self.content_b)?;
+                (String::new(), String::new(), vec![qualified_a, qualified_b])
+            }
+        };
+
+        Some(ConflictResolution {
+            winner,
+            loser,
+            reason: non_empty(self.reason).unwrap_or_else(|| "LLM 裁决".to_string()),
+            resolved_at: Utc::now(),
+            method: ResolutionMethod::LLMDecision,
+            action: self.action,
+            replacements,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use async_trait::async_trait;
+    use futures::Stream;
+    use openclaw_ai::{
+        ChatResponse, EmbeddingRequest, EmbeddingResponse, FinishReason, StreamChunk, TokenUsage,
+    };
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::sync::Mutex;
 
     fn create_test_fact(
         id: &str,
@@ -481,4 +772,179 @@ mod tests {
         assert!(other_weight.is_some());
         assert!(*other_weight.unwrap() < 1.0);
     }
+
+    struct ScriptedProvider {
+        replies: Mutex<VecDeque<Result<String>>>,
+        calls: Mutex<usize>,
+    }
+
+    impl ScriptedProvider {
+        fn new(replies: Vec<Result<String>>) -> Arc<Self> {
+            Arc::new(Self {
+                replies: Mutex::new(replies.into()),
+                calls: Mutex::new(0),
+            })
+        }
+
+        fn calls(&self) -> usize {
+            *self.calls.lock().unwrap()
+        }
+    }
+
+    #[async_trait]
+    impl AIProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse> {
+            *self.calls.lock().unwrap() += 1;
+            let text = self.replies.lock().unwrap().pop_front().unwrap()?;
+            Ok(ChatResponse {
+                id: "scripted".to_string(),
+                model: "scripted".to_string(),
+                message: Message::assistant(text),
+                usage: TokenUsage::new(10, 5),
+                finish_reason: FinishReason::Stop,
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: ChatRequest,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+            Err(OpenClawError::AIProvider("not supported".to_string()))
+        }
+
+        async fn embed(&self, _request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+            Err(OpenClawError::AIProvider("not supported".to_string()))
+        }
+
+        async fn models(&self) -> Result<Vec<String>> {
+            Ok(vec!["scripted".to_string()])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    fn conflicting_pairs() -> Vec<AtomicFact> {
+        let old = Utc::now() - chrono::Duration::days(30);
+        vec![
+            create_test_fact(
+                "1",
+                "用户喜欢Python",
+                FactCategory::UserPreference,
+                old,
+                0.9,
+            ),
+            create_test_fact(
+                "2",
+                "用户不喜欢Python",
+                FactCategory::UserPreference,
+                Utc::now(),
+                0.9,
+            ),
+            create_test_fact("3", "用户在上海", FactCategory::PersonalInfo, old, 0.9),
+            create_test_fact(
+                "4",
+                "用户不在上海",
+                FactCategory::PersonalInfo,
+                Utc::now(),
+                0.9,
+            ),
+            create_test_fact("5", "用户会开车", FactCategory::UserBackground, old, 0.9),
+            create_test_fact(
+                "6",
+                "用户不会开车",
+                FactCategory::UserBackground,
+                Utc::now(),
+                0.9,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_llm_decision_without_provider_uses_rules() {
+        let resolver = ConflictResolver::new();
+        let facts = conflicting_pairs();
+        let conflict = &resolver.detect_conflicts(&facts[..2])[0];
+
+        let resolution = resolver.resolve_conflict(conflict, ResolutionMethod::LLMDecision);
+
+        assert_eq!(resolution.winner, "2");
+        assert_eq!(resolution.loser, "1");
+        assert_eq!(resolution.action, ConflictAction::KeepOne);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_facts_with_llm_in_batches() {
+        let provider = ScriptedProvider::new(vec![
+            Ok(r#"```json
+[
+  {"conflict": 0, "action": "keep_one", "keep": "a", "reason": "旧偏好更可信"},
+  {"conflict": 1, "action": "keep_both", "content_a": "用户去年在上海", "content_b": "用户现在不在上海"}
+]
+```"#
+                .to_string()),
+            Ok(r#"[{"conflict": 0, "action": "merge", "content": "用户会开车但最近不开"}]"#
+                .to_string()),
+        ]);
+        // 批大小先于模型配置同样生效
+        let resolver = ConflictResolver::new()
+            .with_llm_batch_size(2)
+            .with_llm(provider.clone(), "test-model");
+
+        let resolved = resolver.resolve_facts_with_llm(&conflicting_pairs()).await;
+
+        assert_eq!(provider.calls(), 2);
+        let contents: Vec<&str> = resolved.facts.iter().map(|f| f.content.as_str()).collect();
+        assert_eq!(
+            contents,
+            vec![
+                "用户喜欢Python",
+                "用户去年在上海",
+                "用户现在不在上海",
+                "用户会开车但最近不开"
+            ]
+        );
+        assert_eq!(resolved.facts[1].id, "3");
+
+        let resolutions: Vec<&ConflictResolution> = resolved
+            .conflicts
+            .iter()
+            .map(|c| c.resolution.as_ref().unwrap())
+            .collect();
+        assert_eq!(resolutions.len(), 3);
+        assert_eq!(resolutions[0].reason, "旧偏好更可信");
+        assert_eq!(resolutions[0].loser, "2");
+        assert_eq!(resolutions[2].action, ConflictAction::Merge);
+        assert!(
+            resolutions
+                .iter()
+                .all(|r| r.method == ResolutionMethod::LLMDecision)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_llm_failure_falls_back_to_rules() {
+        let provider = ScriptedProvider::new(vec![
+            Err(OpenClawError::AIProvider("unavailable".to_string())),
+            Ok(r#"[{"conflict": 0, "action": "keep_one", "keep": "c"}]"#.to_string()),
+        ]);
+        let resolver = ConflictResolver::new()
+            .with_llm(provider, "test-model")
+            .with_llm_batch_size(2);
+        let conflicts = resolver.detect_conflicts(&conflicting_pairs());
+
+        let resolutions = resolver.resolve_conflicts_with_llm(&conflicts).await;
+
+        assert_eq!(resolutions.len(), 3);
+        for resolution in &resolutions {
+            assert_eq!(resolution.method, ResolutionMethod::Latest);
+            assert_eq!(resolution.action, ConflictAction::KeepOne);
+        }
+        assert_eq!(resolutions[2].winner, "6");
+    }
 }
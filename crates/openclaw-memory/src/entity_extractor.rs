@@ -0,0 +1,887 @@
+//! 实体与关系提取
+//!
+//! 从对话和导入文档中提取实体和关系写入知识图谱：
+//! - `LLMEntityExtractor`：通过工具调用获取结构化输出
+//! - `RuleBasedEntityExtractor`：基于正则的第一人称陈述识别，作为后备
+//! - `GraphExtractor`：按名称和嵌入相似度去重，并记录来源记忆 ID
+
+use async_trait::async_trait;
+use chrono::Utc;
+use openclaw_ai::{AIProvider, ChatRequest, ToolDefinition};
+use openclaw_core::{Content, Message, OpenClawError, Result, Role};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::embedding::EmbeddingProvider;
+use crate::graph_store::enum_from_key;
+use crate::ingest::pipeline::DocumentChunk;
+use crate::knowledge_graph::{Entity, EntityType, KnowledgeGraph, Relation, RelationType};
+
+const RECORD_TOOL: &str = "record_knowledge";
+/// 规则提取结果的置信度
+const RULE_CONFIDENCE: f32 = 0.6;
+/// 第一人称陈述对应的用户实体
+pub const USER_ENTITY_NAME: &str = "用户";
+
+/// 一次提取的结果，关系的两端指向 `entities` 中的实体 ID
+#[derive(Debug, Clone, Default)]
+pub struct ExtractedGraph {
+    pub entities: Vec<Entity>,
+    pub relations: Vec<Relation>,
+}
+
+impl ExtractedGraph {
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// 按名称取实体 ID，不存在时创建
+    fn entity_id(&mut self, name: &str, entity_type: EntityType, confidence: f32) -> String {
+        let key = name.to_lowercase();
+        if let Some(entity) = self.entities.iter().find(|e| e.name.to_lowercase() == key) {
+            return entity.id.clone();
+        }
+        let entity = Entity::new(name.to_string(), entity_type).with_confidence(confidence);
+        let id = entity.id.clone();
+        self.entities.push(entity);
+        id
+    }
+}
+
+#[async_trait]
+pub trait EntityExtractor: Send + Sync {
+    async fn extract(&self, text: &str) -> Result<ExtractedGraph>;
+}
+
+pub struct LLMEntityExtractor {
+    provider: Arc<dyn AIProvider>,
+    model: String,
+}
+
+impl LLMEntityExtractor {
+    pub fn new(provider: Arc<dyn AIProvider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+        }
+    }
+
+    fn record_tool() -> ToolDefinition {
+        ToolDefinition {
+            name: RECORD_TOOL.to_string(),
+            description: "Record the entities and relations found in the text".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "entities": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "name": { "type": "string" },
+                                "type": { "type": "string", "enum": [
+                                    "person", "organization", "location", "project", "concept",
+                                    "preference", "skill", "goal", "other"
+                                ] },
+                                "confidence": { "type": "number" },
+                                "properties": { "type": "object" }
+                            },
+                            "required": ["name", "type"]
+                        }
+                    },
+                    "relations": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "source": { "type": "string" },
+                                "target": { "type": "string" },
+                                "type": { "type": "string", "enum": [
+                                    "knows", "works_for", "participates_in", "has_preference",
+                                    "has_skill", "related_to", "owns", "uses", "goal_of"
+                                ] },
+                                "confidence": { "type": "number" }
+                            },
+                            "required": ["source", "target", "type"]
+                        }
+                    }
+                },
+                "required": ["entities", "relations"]
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl EntityExtractor for LLMEntityExtractor {
+    async fn extract(&self, text: &str) -> Result<ExtractedGraph> {
+        let prompt = format!(
+            r#"从以下文本中提取实体和实体之间的关系，调用 {} 工具记录结果。
+
+要求：
+1. 只提取文本明确提到的实体，不要推测
+2. 文本中的“我”指用户，用实体名“{}”（类型 person）表示
+3. 关系的 source 和 target 使用实体名
+4. confidence 取 0 到 1，表示信息的确定程度
+
+文本：
+{}"#,
+            RECORD_TOOL, USER_ENTITY_NAME, text
+        );
+
+        let mut request = ChatRequest::new(self.model.clone(), vec![Message::user(prompt)])
+            .with_temperature(0.1)
+            .with_max_tokens(2000);
+        request.tools = vec![Self::record_tool()];
+
+        let response = self.provider.chat(request).await?;
+        let arguments = response.message.content.iter().find_map(|c| match c {
+            Content::ToolCall {
+                name, arguments, ..
+            } if name == RECORD_TOOL => Some(arguments.clone()),
+            _ => None,
+        });
+
+        // 不支持工具调用的模型会直接返回 JSON 文本
+        let value = match arguments {
+            Some(serde_json::Value::String(raw)) => parse_json_object(&raw)?,
+            Some(value) => value,
+            None => parse_json_object(response.message.text_content().unwrap_or_default())?,
+        };
+        let output: LlmGraph = serde_json::from_value(value)
+            .map_err(|e| OpenClawError::AIProvider(format!("解析实体提取结果失败: {}", e)))?;
+        Ok(output.into_graph())
+    }
+}
+
+fn parse_json_object(content: &str) -> Result<serde_json::Value> {
+    let json = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => {
+            return Err(OpenClawError::AIProvider(
+                "实体提取结果中没有 JSON 对象".to_string(),
+            ));
+        }
+    };
+    serde_json::from_str(json)
+        .map_err(|e| OpenClawError::AIProvider(format!("解析实体提取结果失败: {}", e)))
+}
+
+#[derive(Deserialize)]
+struct LlmGraph {
+    #[serde(default)]
+    entities: Vec<LlmEntity>,
+    #[serde(default)]
+    relations: Vec<LlmRelation>,
+}
+
+#[derive(Deserialize)]
+struct LlmEntity {
+    name: String,
+    #[serde(rename = "type", default)]
+    entity_type: String,
+    confidence: Option<f32>,
+    #[serde(default)]
+    properties: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct LlmRelation {
+    source: String,
+    target: String,
+    #[serde(rename = "type", default)]
+    relation_type: String,
+    confidence: Option<f32>,
+}
+
+impl LlmGraph {
+    fn into_graph(self) -> ExtractedGraph {
+        let mut graph = ExtractedGraph::default();
+        for entity in self.entities {
+            let name = entity.name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let entity_type = enum_from_key(&entity.entity_type).unwrap_or(EntityType::Other);
+            let confidence = entity.confidence.unwrap_or(1.0).clamp(0.0, 1.0);
+            let id = graph.entity_id(name, entity_type, confidence);
+            if let Some(target) = graph.entities.iter_mut().find(|e| e.id == id) {
+                for (key, value) in entity.properties {
+                    let value = match value {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    target.properties.insert(key, value);
+                }
+            }
+        }
+
+        for relation in self.relations {
+            let source = graph
+                .entities
+                .iter()
+                .find(|e| e.name.eq_ignore_ascii_case(relation.source.trim()));
+            let target = graph
+                .entities
+                .iter()
+                .find(|e| e.name.eq_ignore_ascii_case(relation.target.trim()));
+            // 关系两端必须是已提取的实体
+            let (Some(source), Some(target)) = (source, target) else {
+                continue;
+            };
+            let relation_type =
+                enum_from_key(&relation.relation_type).unwrap_or(RelationType::RelatedTo);
+            let weight = relation.confidence.unwrap_or(1.0).clamp(0.0, 1.0);
+            graph.relations.push(
+                Relation::new(source.id.clone(), target.id.clone(), relation_type)
+                    .with_weight(weight),
+            );
+        }
+        graph
+    }
+}
+
+/// 规则：匹配到的宾语成为实体，并与用户实体建立关系
+struct ExtractionRule {
+    pattern: Regex,
+    entity_type: EntityType,
+    relation_type: RelationType,
+    /// 关系方向为宾语指向用户
+    reversed: bool,
+}
+
+pub struct RuleBasedEntityExtractor {
+    rules: Vec<ExtractionRule>,
+    name_pattern: Regex,
+    separator: Regex,
+}
+
+impl RuleBasedEntityExtractor {
+    pub fn new() -> Self {
+        const OBJECT: &str = r"([^\s，。,.！!？?；;：:]{1,30})";
+        const ZH: &str = "(?:我|用户)";
+        let rule = |pattern: String, entity_type, relation_type, reversed| ExtractionRule {
+            pattern: Regex::new(&pattern).unwrap(),
+            entity_type,
+            relation_type,
+            reversed,
+        };
+        let rules = vec![
+            rule(
+                format!(r"{ZH}(?:在|就职于){OBJECT}(?:工作|上班|任职)"),
+                EntityType::Organization,
+                RelationType::WorksFor,
+                false,
+            ),
+            rule(
+                r"(?i:\bI (?:work|am working) (?:at|for)) ([A-Z][\w&.-]*(?: [A-Z][\w&.-]*)*)"
+                    .to_string(),
+                EntityType::Organization,
+                RelationType::WorksFor,
+                false,
+            ),
+            rule(
+                format!(r"{ZH}(?:住在|来自){OBJECT}"),
+                EntityType::Location,
+                RelationType::RelatedTo,
+                false,
+            ),
+            rule(
+                r"(?i:\bI (?:live in|am from)) ([A-Z][\w.-]*(?: [A-Z][\w.-]*)*)".to_string(),
+                EntityType::Location,
+                RelationType::RelatedTo,
+                false,
+            ),
+            rule(
+                format!(r"{ZH}(?:喜欢|偏好|爱用|爱){OBJECT}"),
+                EntityType::Preference,
+                RelationType::HasPreference,
+                false,
+            ),
+            rule(
+                r"(?i:\bI (?:like|love|prefer)) ([\w+#.-]+(?: [\w+#.-]+){0,2})".to_string(),
+                EntityType::Preference,
+                RelationType::HasPreference,
+                false,
+            ),
+            rule(
+                format!(r"{ZH}(?:会|擅长|熟悉|精通){OBJECT}"),
+                EntityType::Skill,
+                RelationType::HasSkill,
+                false,
+            ),
+            rule(
+                r"(?i:\bI (?:know|am good at|am familiar with)) ([\w+#.-]+(?: [\w+#.-]+){0,2})"
+                    .to_string(),
+                EntityType::Skill,
+                RelationType::HasSkill,
+                false,
+            ),
+            rule(
+                format!(r"{ZH}(?:负责|参与){OBJECT}"),
+                EntityType::Project,
+                RelationType::ParticipatesIn,
+                false,
+            ),
+            rule(
+                format!(r"{ZH}(?:计划|打算|目标是|想要){OBJECT}"),
+                EntityType::Goal,
+                RelationType::GoalOf,
+                true,
+            ),
+        ];
+
+        Self {
+            rules,
+            name_pattern: Regex::new(
+                r"(?:我叫|我的名字是|(?i:my name is) )([^\s，。,.！!？?；;]{1,20})",
+            )
+            .unwrap(),
+            separator: Regex::new(r"和|、|与|及|,| and ").unwrap(),
+        }
+    }
+}
+
+impl Default for RuleBasedEntityExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EntityExtractor for RuleBasedEntityExtractor {
+    async fn extract(&self, text: &str) -> Result<ExtractedGraph> {
+        let mut graph = ExtractedGraph::default();
+        let mut user_id: Option<String> = None;
+
+        for rule in &self.rules {
+            for captures in rule.pattern.captures_iter(text) {
+                let Some(object) = captures.get(1) else {
+                    continue;
+                };
+                for name in self.separator.split(object.as_str()) {
+                    let name = name.trim();
+                    if name.is_empty() {
+                        continue;
+                    }
+                    let user = user_id
+                        .get_or_insert_with(|| {
+                            graph.entity_id(USER_ENTITY_NAME, EntityType::Person, 1.0)
+                        })
+                        .clone();
+                    let object_id =
+                        graph.entity_id(name, rule.entity_type.clone(), RULE_CONFIDENCE);
+                    let (source, target) = if rule.reversed {
+                        (object_id, user)
+                    } else {
+                        (user, object_id)
+                    };
+                    graph.relations.push(
+                        Relation::new(source, target, rule.relation_type.clone())
+                            .with_weight(RULE_CONFIDENCE),
+                    );
+                }
+            }
+        }
+
+        if let Some(captures) = self.name_pattern.captures(text) {
+            let id = graph.entity_id(USER_ENTITY_NAME, EntityType::Person, 1.0);
+            if let Some(user) = graph.entities.iter_mut().find(|e| e.id == id) {
+                user.properties
+                    .insert("name".to_string(), captures[1].to_string());
+            }
+        }
+
+        Ok(graph)
+    }
+}
+
+/// 一次写入图谱的统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractionReport {
+    pub entities_added: usize,
+    pub entities_merged: usize,
+    pub relations_added: usize,
+    pub relations_merged: usize,
+    /// 模型提取失败，改用了规则提取
+    pub used_fallback: bool,
+}
+
+impl ExtractionReport {
+    fn absorb(&mut self, other: ExtractionReport) {
+        self.entities_added += other.entities_added;
+        self.entities_merged += other.entities_merged;
+        self.relations_added += other.relations_added;
+        self.relations_merged += other.relations_merged;
+        self.used_fallback |= other.used_fallback;
+    }
+}
+
+/// 提取实体和关系并合并进知识图谱
+pub struct GraphExtractor {
+    graph: Arc<KnowledgeGraph>,
+    extractor: Option<Arc<dyn EntityExtractor>>,
+    rules: RuleBasedEntityExtractor,
+    embedder: Option<Arc<dyn EmbeddingProvider>>,
+    similarity_threshold: f32,
+    min_confidence: f32,
+    /// 已有实体名称的嵌入缓存，按实体 ID 索引
+    name_embeddings: RwLock<HashMap<String, Vec<f32>>>,
+}
+
+impl GraphExtractor {
+    /// 仅使用规则提取
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self {
+            graph,
+            extractor: None,
+            rules: RuleBasedEntityExtractor::new(),
+            embedder: None,
+            similarity_threshold: 0.92,
+            min_confidence: 0.5,
+            name_embeddings: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 设置主提取器，失败时回退到规则提取
+    pub fn with_extractor(mut self, extractor: Arc<dyn EntityExtractor>) -> Self {
+        self.extractor = Some(extractor);
+        self
+    }
+
+    /// 启用按名称嵌入相似度去重
+    pub fn with_embedder(mut self, embedder: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    pub fn with_similarity_threshold(mut self, threshold: f32) -> Self {
+        self.similarity_threshold = threshold;
+        self
+    }
+
+    /// 低于该置信度的实体和关系会被丢弃
+    pub fn with_min_confidence(mut self, confidence: f32) -> Self {
+        self.min_confidence = confidence;
+        self
+    }
+
+    pub fn graph(&self) -> Arc<KnowledgeGraph> {
+        self.graph.clone()
+    }
+
+    /// 从文本提取并写入图谱，`source_id` 为文本所属的记忆 ID
+    pub async fn extract(&self, text: &str, source_id: Option<&str>) -> Result<ExtractionReport> {
+        let mut report = ExtractionReport::default();
+        if text.trim().is_empty() {
+            return Ok(report);
+        }
+
+        let extracted = match &self.extractor {
+            Some(extractor) => match extractor.extract(text).await {
+                Ok(graph) => graph,
+                Err(e) => {
+                    tracing::warn!("Entity extraction failed, using rules: {}", e);
+                    report.used_fallback = true;
+                    self.rules.extract(text).await?
+                }
+            },
+            None => self.rules.extract(text).await?,
+        };
+
+        report.absorb(self.merge(extracted, source_id).await?);
+        Ok(report)
+    }
+
+    /// 提取对话中用户和助手的消息
+    pub async fn extract_conversation(
+        &self,
+        messages: &[Message],
+        source_id: Option<&str>,
+    ) -> Result<ExtractionReport> {
+        let text = messages
+            .iter()
+            .filter_map(|m| {
+                let speaker = match m.role {
+                    Role::User => "User",
+                    Role::Assistant => "Assistant",
+                    _ => return None,
+                };
+                let text = m.text_content()?.trim();
+                (!text.is_empty()).then(|| format!("{}: {}", speaker, text))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.extract(&text, source_id).await
+    }
+
+    /// 提取导入文档的各个块，来源为块 ID
+    pub async fn extract_chunks(&self, chunks: &[DocumentChunk]) -> Result<ExtractionReport> {
+        let mut report = ExtractionReport::default();
+        for chunk in chunks {
+            report.absorb(self.extract(&chunk.content, Some(&chunk.id)).await?);
+        }
+        Ok(report)
+    }
+
+    async fn merge(
+        &self,
+        extracted: ExtractedGraph,
+        source_id: Option<&str>,
+    ) -> Result<ExtractionReport> {
+        let mut report = ExtractionReport::default();
+        // 提取结果中的临时 ID -> 图谱中的实体 ID
+        let mut id_map: HashMap<String, String> = HashMap::new();
+
+        for mut entity in extracted.entities {
+            if entity.confidence < self.min_confidence {
+                continue;
+            }
+            let temp_id = entity.id.clone();
+            let mut merged = match self.find_existing(&entity).await? {
+                Some(mut existing) => {
+                    for (key, value) in std::mem::take(&mut entity.properties) {
+                        existing.properties.entry(key).or_insert(value);
+                    }
+                    existing.confidence = existing.confidence.max(entity.confidence);
+                    existing.updated_at = Utc::now();
+                    report.entities_merged += 1;
+                    existing
+                }
+                None => {
+                    report.entities_added += 1;
+                    entity
+                }
+            };
+            if let Some(source_id) = source_id {
+                merged.add_source(source_id);
+            }
+            id_map.insert(temp_id, merged.id.clone());
+            self.graph
+                .add_entity(merged)
+                .await
+                .map_err(OpenClawError::Memory)?;
+        }
+
+        for mut relation in extracted.relations {
+            if relation.weight < self.min_confidence {
+                continue;
+            }
+            let (Some(source), Some(target)) = (
+                id_map.get(&relation.source_id),
+                id_map.get(&relation.target_id),
+            ) else {
+                continue;
+            };
+            if source == target {
+                continue;
+            }
+
+            let existing = self
+                .graph
+                .get_relations_from(source)
+                .await
+                .into_iter()
+                .find(|r| &r.target_id == target && r.relation_type == relation.relation_type);
+            let mut relation = match existing {
+                Some(mut existing) => {
+                    existing.weight = existing.weight.max(relation.weight);
+                    report.relations_merged += 1;
+                    existing
+                }
+                None => {
+                    relation.source_id = source.clone();
+                    relation.target_id = target.clone();
+                    report.relations_added += 1;
+                    relation
+                }
+            };
+            if let Some(source_id) = source_id {
+                relation.add_source(source_id);
+            }
+            self.graph
+                .add_relation(relation)
+                .await
+                .map_err(OpenClawError::Memory)?;
+        }
+
+        Ok(report)
+    }
+
+    /// 先按名称匹配同类实体，再按名称嵌入的相似度匹配
+    async fn find_existing(&self, entity: &Entity) -> Result<Option<Entity>> {
+        let same_name = self.graph.find_entities_by_name(&entity.name).await;
+        if let Some(existing) = same_name
+            .iter()
+            .find(|e| e.entity_type == entity.entity_type)
+            .or_else(|| same_name.first())
+        {
+            return Ok(Some(existing.clone()));
+        }
+
+        let Some(embedder) = &self.embedder else {
+            return Ok(None);
+        };
+        let candidates = self.graph.find_entities_by_type(&entity.entity_type).await;
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        let missing: Vec<&Entity> = {
+            let cache = self.name_embeddings.read().await;
+            candidates
+                .iter()
+                .filter(|c| !cache.contains_key(&c.id))
+                .collect()
+        };
+        if !missing.is_empty() {
+            let names: Vec<String> = missing.iter().map(|c| c.name.clone()).collect();
+            let vectors = embedder.embed_batch(&names).await?;
+            let mut cache = self.name_embeddings.write().await;
+            for (candidate, vector) in missing.into_iter().zip(vectors) {
+                cache.insert(candidate.id.clone(), vector);
+            }
+        }
+
+        let query = embedder.embed(&entity.name).await?;
+        let cache = self.name_embeddings.read().await;
+        let best = candidates
+            .into_iter()
+            .filter_map(|c| {
+                let score = embedder.similarity(&query, cache.get(&c.id)?);
+                Some((c, score))
+            })
+            .filter(|(_, score)| *score >= self.similarity_threshold)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        drop(cache);
+
+        if best.is_none() {
+            // 新实体写入后也会成为候选，先缓存其嵌入
+            self.name_embeddings
+                .write()
+                .await
+                .insert(entity.id.clone(), query);
+        }
+        Ok(best.map(|(c, _)| c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::Stream;
+    use openclaw_ai::{
+        ChatResponse, EmbeddingRequest, EmbeddingResponse, FinishReason, StreamChunk, TokenUsage,
+    };
+    use std::pin::Pin;
+
+    struct ToolCallProvider {
+        reply: Message,
+    }
+
+    #[async_trait]
+    impl AIProvider for ToolCallProvider {
+        fn name(&self) -> &str {
+            "tool-call"
+        }
+
+        async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+            assert_eq!(request.tools[0].name, RECORD_TOOL);
+            Ok(ChatResponse {
+                id: "tool-call".to_string(),
+                model: "tool-call".to_string(),
+                message: self.reply.clone(),
+                usage: TokenUsage::new(10, 5),
+                finish_reason: FinishReason::ToolCalls,
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: ChatRequest,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamChunk>> + Send>>> {
+            Err(OpenClawError::AIProvider("not supported".to_string()))
+        }
+
+        async fn embed(&self, _request: EmbeddingRequest) -> Result<EmbeddingResponse> {
+            Err(OpenClawError::AIProvider("not supported".to_string()))
+        }
+
+        async fn models(&self) -> Result<Vec<String>> {
+            Ok(vec!["tool-call".to_string()])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    /// 名称以相同首字母开头的视为相同
+    struct InitialEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for InitialEmbedding {
+        fn name(&self) -> &str {
+            "initial"
+        }
+
+        fn model(&self) -> &str {
+            "initial"
+        }
+
+        fn dimensions(&self) -> usize {
+            26
+        }
+
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            let mut vector = vec![0.0; 26];
+            if let Some(c) = text
+                .to_ascii_lowercase()
+                .bytes()
+                .find(u8::is_ascii_lowercase)
+            {
+                vector[(c - b'a') as usize] = 1.0;
+            }
+            Ok(vector)
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut vectors = Vec::new();
+            for text in texts {
+                vectors.push(self.embed(text).await?);
+            }
+            Ok(vectors)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rule_extraction() {
+        let rules = RuleBasedEntityExtractor::new();
+        let graph = rules
+            .extract("我叫小王，我在字节跳动工作，我喜欢Python和Rust。我计划学习日语")
+            .await
+            .unwrap();
+
+        let user = graph
+            .entities
+            .iter()
+            .find(|e| e.name == USER_ENTITY_NAME)
+            .unwrap();
+        assert_eq!(
+            user.properties.get("name").map(String::as_str),
+            Some("小王")
+        );
+        let names: Vec<&str> = graph.entities.iter().map(|e| e.name.as_str()).collect();
+        assert!(names.contains(&"字节跳动"));
+        assert!(names.contains(&"Python"));
+        assert!(names.contains(&"Rust"));
+        assert_eq!(graph.relations.len(), 4);
+
+        let goal = graph
+            .entities
+            .iter()
+            .find(|e| e.name == "学习日语")
+            .unwrap();
+        let goal_of = graph
+            .relations
+            .iter()
+            .find(|r| r.relation_type == RelationType::GoalOf)
+            .unwrap();
+        assert_eq!(goal_of.source_id, goal.id);
+        assert_eq!(goal_of.target_id, user.id);
+
+        let english = rules
+            .extract("I work at Acme Corp and I like tea")
+            .await
+            .unwrap();
+        assert!(english.entities.iter().any(|e| e.name == "Acme Corp"));
+        assert!(english.entities.iter().any(|e| e.name == "tea"));
+    }
+
+    #[tokio::test]
+    async fn test_llm_tool_call_extraction() {
+        let arguments = serde_json::json!({
+            "entities": [
+                {"name": "Alice", "type": "person", "confidence": 0.9},
+                {"name": "OpenClaw", "type": "project", "properties": {"stars": 10}}
+            ],
+            "relations": [
+                {"source": "alice", "target": "OpenClaw", "type": "participates_in"},
+                {"source": "Alice", "target": "Bob", "type": "knows"}
+            ]
+        });
+        let reply = Message::new(
+            Role::Assistant,
+            vec![Content::ToolCall {
+                id: "call-1".to_string(),
+                name: RECORD_TOOL.to_string(),
+                arguments,
+            }],
+        );
+        let extractor = LLMEntityExtractor::new(Arc::new(ToolCallProvider { reply }), "test");
+        let graph = extractor.extract("Alice works on OpenClaw").await.unwrap();
+
+        assert_eq!(graph.entities.len(), 2);
+        assert_eq!(graph.entities[1].entity_type, EntityType::Project);
+        assert_eq!(
+            graph.entities[1]
+                .properties
+                .get("stars")
+                .map(String::as_str),
+            Some("10")
+        );
+        assert_eq!(graph.relations.len(), 1);
+        assert_eq!(
+            graph.relations[0].relation_type,
+            RelationType::ParticipatesIn
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_dedupes_and_links_sources() {
+        let graph = Arc::new(KnowledgeGraph::new());
+        let extractor = GraphExtractor::new(graph.clone());
+
+        let first = extractor
+            .extract("我喜欢Python", Some("memory-1"))
+            .await
+            .unwrap();
+        assert_eq!(first.entities_added, 2);
+        assert_eq!(first.relations_added, 1);
+
+        let second = extractor
+            .extract("我喜欢python", Some("memory-2"))
+            .await
+            .unwrap();
+        assert_eq!(second.entities_added, 0);
+        assert_eq!(second.entities_merged, 2);
+        assert_eq!(second.relations_merged, 1);
+
+        let python = &graph.find_entities_by_name("Python").await[0];
+        assert_eq!(python.source_ids(), vec!["memory-1", "memory-2"]);
+        assert_eq!(graph.stats().await.relation_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_and_embedding_dedupe() {
+        let reply = Message::assistant("no structured output here");
+        let graph = Arc::new(KnowledgeGraph::new());
+        let extractor = GraphExtractor::new(graph.clone())
+            .with_extractor(Arc::new(LLMEntityExtractor::new(
+                Arc::new(ToolCallProvider { reply }),
+                "test",
+            )))
+            .with_embedder(Arc::new(InitialEmbedding));
+
+        let report = extractor.extract("我擅长Kotlin", None).await.unwrap();
+        assert!(report.used_fallback);
+        assert_eq!(report.entities_added, 2);
+
+        let report = extractor.extract("我擅长Kotlin语言", None).await.unwrap();
+        assert_eq!(report.entities_added, 0);
+        assert_eq!(graph.get_skills().await.len(), 1);
+    }
+}
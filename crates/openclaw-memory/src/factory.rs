@@ -8,11 +8,14 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::ai_adapter::AIProviderEmbeddingAdapter;
-use crate::bm25::Bm25Index;
+use crate::bm25::{Bm25Index, DEFAULT_INDEX_PATH};
 use crate::compress_adapter::AIProviderCompressAdapter;
+use crate::config::create_local_embedding_provider;
+use crate::embedding::EmbeddingProvider;
 use crate::hybrid_search::{HybridSearchConfig, HybridSearchManager};
-use crate::knowledge_graph::KnowledgeGraph;
+use crate::knowledge_graph::{DEFAULT_GRAPH_PATH, KnowledgeGraph};
 use crate::manager::MemoryManager;
+use crate::privacy::{ForgetReceipt, ForgetTarget};
 use crate::recall::RecallResult;
 use crate::types::{MemoryConfig, MemoryItem, MemoryRetrieval};
 
@@ -22,6 +25,13 @@ pub trait MemoryBackend: Send + Sync {
     async fn recall(&self, query: &str) -> Result<RecallResult>;
     async fn add(&self, message: openclaw_core::Message) -> Result<()>;
     async fn retrieve(&self, query: &str, limit: usize) -> Result<MemoryRetrieval>;
+
+    /// 遗忘匹配的记忆，后端不支持时返回错误
+    async fn forget(&self, _target: ForgetTarget) -> Result<ForgetReceipt> {
+        Err(openclaw_core::OpenClawError::Memory(
+            "Forgetting is not supported by this memory backend".to_string(),
+        ))
+    }
 }
 
 pub struct HybridMemoryBackend {
@@ -59,6 +69,11 @@ impl MemoryBackend for HybridMemoryBackend {
         let manager = self.manager.lock().await;
         manager.retrieve(query, limit).await
     }
+
+    async fn forget(&self, target: ForgetTarget) -> Result<ForgetReceipt> {
+        let mut manager = self.manager.lock().await;
+        manager.forget(target).await
+    }
 }
 
 #[async_trait]
@@ -86,11 +101,15 @@ impl HybridMemoryFactory {
         vector_store: Arc<dyn VectorStore>,
     ) -> Result<Arc<dyn MemoryBackend>> {
         let ai_provider_clone = ai_provider.clone();
-        let embedding_provider = AIProviderEmbeddingAdapter::new(
-            ai_provider,
-            config.long_term.embedding_model.clone(),
-            config.long_term.embedding_dimensions,
-        );
+        let embedding_provider = create_embedding_provider(config, ai_provider).await?;
+
+        // 集合中的向量须与当前嵌入模型一致，否则提示重建索引
+        openclaw_vector::ensure_embedding_model(
+            vector_store.as_ref(),
+            embedding_provider.model(),
+            embedding_provider.dimensions(),
+        )
+        .await?;
 
         let hybrid_config = HybridSearchConfig {
             vector_weight: 0.5,
@@ -99,25 +118,37 @@ impl HybridMemoryFactory {
             knowledge_graph_weight: 0.1,
             min_score: Some(0.0),
             limit: 10,
-            embedding_dimension: Some(config.long_term.embedding_dimensions),
+            embedding_dimension: Some(embedding_provider.dimensions()),
             enable_vector: true,
             enable_bm25: config.long_term.enable_bm25,
             enable_knowledge_graph: config.long_term.enable_knowledge_graph,
+            namespace: config.privacy.namespace.clone(),
         };
 
         let mut hybrid_search = HybridSearchManager::new(vector_store.clone(), hybrid_config.clone());
 
         if hybrid_config.enable_bm25 {
-            if let Ok(bm25_index) = Bm25Index::new(std::path::Path::new("data/bm25")) {
-                hybrid_search = hybrid_search.with_bm25(Arc::new(bm25_index));
+            if let Ok(bm25_index) = Bm25Index::shared(std::path::Path::new(DEFAULT_INDEX_PATH)) {
+                hybrid_search = hybrid_search.with_bm25(bm25_index);
             }
         }
 
         if hybrid_config.enable_knowledge_graph {
-            let kg = KnowledgeGraph::new();
+            // 与导入管线共用持久化图谱，打开失败时退回内存图谱
+            let kg = KnowledgeGraph::open(DEFAULT_GRAPH_PATH).unwrap_or_else(|e| {
+                tracing::warn!("Failed to open knowledge graph, using in-memory graph: {}", e);
+                KnowledgeGraph::new()
+            });
             hybrid_search = hybrid_search.with_knowledge_graph(Arc::new(tokio::sync::RwLock::new(kg)));
         }
 
+        if let Some(settings) = &config.long_term.reranker {
+            match settings.build(Some(ai_provider_clone.clone())).await {
+                Ok(stage) => hybrid_search = hybrid_search.with_reranker(Arc::new(stage)),
+                Err(e) => tracing::warn!("Failed to build reranker, skipping rerank stage: {}", e),
+            }
+        }
+
         let manager = if config.short_term.compression_mode == "ai" {
             let summary_model = config
                 .short_term
@@ -127,16 +158,21 @@ impl HybridMemoryFactory {
             let compress_adapter = AIProviderCompressAdapter::new(ai_provider_clone, summary_model);
             MemoryManager::new(config.clone())
                 .with_vector_store(vector_store)
-                .with_embedding_provider(embedding_provider)
+                .with_shared_embedding_provider(embedding_provider)
                 .with_hybrid_search(Arc::new(hybrid_search))
                 .with_ai_compressor(Arc::new(compress_adapter))
         } else {
             MemoryManager::new(config.clone())
                 .with_vector_store(vector_store)
-                .with_embedding_provider(embedding_provider)
+                .with_shared_embedding_provider(embedding_provider)
                 .with_hybrid_search(Arc::new(hybrid_search))
         };
 
+        let manager = match config.privacy.namespace.clone() {
+            Some(namespace) => manager.with_namespace(namespace),
+            None => manager,
+        };
+
         Ok(Arc::new(HybridMemoryBackend::new(manager)) as Arc<dyn MemoryBackend>)
     }
 }
@@ -163,6 +199,25 @@ impl MemoryManagerFactory for HybridMemoryFactory {
     }
 }
 
+/// 按长期记忆配置创建嵌入模型
+///
+/// 本地模型在进程内推理，不依赖对话所用的 AI 提供商；其余复用该提供商的嵌入接口。
+pub async fn create_embedding_provider(
+    config: &MemoryConfig,
+    ai_provider: Arc<dyn AIProvider>,
+) -> Result<Arc<dyn EmbeddingProvider>> {
+    if config.long_term.embedding_provider == "local" {
+        let settings = config.long_term.local_embedding.clone().unwrap_or_default();
+        return create_local_embedding_provider(&config.long_term.embedding_model, &settings)
+            .await;
+    }
+    Ok(Arc::new(AIProviderEmbeddingAdapter::new(
+        ai_provider,
+        config.long_term.embedding_model.clone(),
+        config.long_term.embedding_dimensions,
+    )))
+}
+
 pub async fn create_memory_backend(
     backend_type: &str,
     config: &MemoryConfig,
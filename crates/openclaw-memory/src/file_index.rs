@@ -0,0 +1,718 @@
+//! 文件语料索引
+//!
+//! 递归遍历配置的目录，按 glob 过滤后用 [`ChunkManager`] 按行切块，
+//! 同时写入 BM25 全文索引和（可选的）向量索引。
+//! 索引清单持久化在 `index_dir` 下，按文件修改时间、大小和内容哈希增量更新，
+//! 可配合 [`FileWatcher`] 在文件变化时自动重建对应文件的索引。
+//! 检索结果精确到块，并附带文件路径和行号范围，便于在回答中引用。
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use openclaw_core::{OpenClawError, Result};
+use openclaw_vector::{SearchQuery, VectorItem, VectorStore};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, RwLock};
+
+use crate::bm25::Bm25Index;
+use crate::chunk::{Chunk, ChunkManager};
+use crate::embedding::EmbeddingProvider;
+use crate::file_watcher::{FileChangeType, FileWatcher, FileWatcherConfig};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+/// RRF 融合常数
+const RRF_K: f32 = 60.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileIndexConfig {
+    /// 需要索引的根目录
+    pub roots: Vec<PathBuf>,
+    /// 包含的文件 glob（相对根目录），为空表示全部
+    pub include: Vec<String>,
+    /// 排除的文件 glob（相对根目录），优先于 include
+    pub exclude: Vec<String>,
+    /// 索引清单与 BM25 索引的存放目录
+    pub index_dir: PathBuf,
+    pub chunk_size: usize,
+    pub chunk_overlap: usize,
+    /// 超过该大小的文件不索引
+    pub max_file_bytes: u64,
+    /// 文件监听轮询间隔
+    pub poll_interval_ms: u64,
+}
+
+impl Default for FileIndexConfig {
+    fn default() -> Self {
+        Self {
+            roots: vec![],
+            include: vec![],
+            exclude: vec![
+                ".git/**".to_string(),
+                "**/node_modules/**".to_string(),
+                "**/target/**".to_string(),
+            ],
+            index_dir: PathBuf::from(".openclaw-rust/indexes/files"),
+            chunk_size: 400,
+            chunk_overlap: 50,
+            max_file_bytes: 2 * 1024 * 1024,
+            poll_interval_ms: 5000,
+        }
+    }
+}
+
+/// 已索引的块及其在文件中的行号范围
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedChunk {
+    pub id: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedFile {
+    modified_ms: u64,
+    size: u64,
+    hash: String,
+    chunks: Vec<IndexedChunk>,
+    /// 是否已写入向量索引
+    #[serde(default)]
+    embedded: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    files: HashMap<String, IndexedFile>,
+}
+
+/// 块级检索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHit {
+    pub chunk_id: String,
+    pub path: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+    /// 归一化到 0~1 的融合得分
+    pub score: f32,
+}
+
+impl FileHit {
+    /// 引用格式：`path:start-end`
+    pub fn citation(&self) -> String {
+        format!(
+            "{}:{}-{}",
+            self.path.display(),
+            self.start_line,
+            self.end_line
+        )
+    }
+}
+
+/// 一次同步的统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub skipped: usize,
+}
+
+enum SyncOutcome {
+    Indexed,
+    Unchanged,
+    Removed,
+    Skipped,
+}
+
+pub struct FileCorpusIndex {
+    config: FileIndexConfig,
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+    chunker: ChunkManager,
+    bm25: Bm25Index,
+    vector_store: Option<Arc<dyn VectorStore>>,
+    embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
+    manifest: RwLock<Manifest>,
+    /// 串行化索引写入，tantivy 同一时间只允许一个 writer
+    write_lock: Mutex<()>,
+}
+
+impl FileCorpusIndex {
+    /// 打开（或创建）索引，加载已有清单
+    pub fn open(config: FileIndexConfig) -> Result<Self> {
+        let include = compile_patterns(&config.include)?;
+        let exclude = compile_patterns(&config.exclude)?;
+
+        std::fs::create_dir_all(&config.index_dir)?;
+        let bm25 = Bm25Index::new(&config.index_dir.join("bm25"))
+            .map_err(|e| OpenClawError::Memory(format!("打开 BM25 索引失败: {}", e)))?;
+
+        let manifest_path = config.index_dir.join(MANIFEST_FILE);
+        let manifest = if manifest_path.exists() {
+            let manifest: Manifest =
+                serde_json::from_str(&std::fs::read_to_string(&manifest_path)?)?;
+            if manifest.version == MANIFEST_VERSION {
+                manifest
+            } else {
+                tracing::info!("文件索引清单版本变化，重建索引: {:?}", manifest_path);
+                Manifest::default()
+            }
+        } else {
+            Manifest::default()
+        };
+
+        Ok(Self {
+            chunker: ChunkManager::new(config.chunk_size, config.chunk_overlap, "cl100k_base"),
+            config,
+            include,
+            exclude,
+            bm25,
+            vector_store: None,
+            embedding_provider: None,
+            manifest: RwLock::new(manifest),
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// 启用向量索引
+    pub fn with_vectors(
+        mut self,
+        vector_store: Arc<dyn VectorStore>,
+        embedding_provider: Arc<dyn EmbeddingProvider>,
+    ) -> Self {
+        self.vector_store = Some(vector_store);
+        self.embedding_provider = Some(embedding_provider);
+        self
+    }
+
+    pub fn config(&self) -> &FileIndexConfig {
+        &self.config
+    }
+
+    /// 当前已索引的文件数
+    pub async fn file_count(&self) -> usize {
+        self.manifest.read().await.files.len()
+    }
+
+    /// 全量增量同步：索引新增和变化的文件，清理已删除的文件
+    pub async fn refresh(&self) -> Result<IndexStats> {
+        let _guard = self.write_lock.lock().await;
+        let mut stats = IndexStats::default();
+
+        // 向量存储为空（如内存存储重启后）时需要重新嵌入
+        if let Some(store) = &self.vector_store
+            && store.stats().await?.total_vectors == 0
+        {
+            for file in self.manifest.write().await.files.values_mut() {
+                file.embedded = false;
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for path in self.scan() {
+            seen.insert(path_key(&path));
+            match self.sync_file(&path).await {
+                Ok(SyncOutcome::Indexed) => stats.indexed += 1,
+                Ok(SyncOutcome::Unchanged) => stats.unchanged += 1,
+                Ok(SyncOutcome::Removed) => stats.removed += 1,
+                Ok(SyncOutcome::Skipped) => stats.skipped += 1,
+                Err(e) => {
+                    tracing::warn!("索引文件失败 {:?}: {}", path, e);
+                    stats.skipped += 1;
+                }
+            }
+        }
+
+        let stale: Vec<String> = self
+            .manifest
+            .read()
+            .await
+            .files
+            .keys()
+            .filter(|key| !seen.contains(*key))
+            .cloned()
+            .collect();
+        for key in stale {
+            self.remove_entry(&key).await?;
+            stats.removed += 1;
+        }
+
+        self.save_manifest().await?;
+        Ok(stats)
+    }
+
+    /// 重建单个文件的索引；文件已删除或不再匹配过滤规则时移除其索引
+    pub async fn index_path(&self, path: &Path) -> Result<bool> {
+        let _guard = self.write_lock.lock().await;
+        let changed = matches!(
+            self.sync_file(path).await?,
+            SyncOutcome::Indexed | SyncOutcome::Removed
+        );
+        if changed {
+            self.save_manifest().await?;
+        }
+        Ok(changed)
+    }
+
+    /// 移除单个文件的索引
+    pub async fn remove_path(&self, path: &Path) -> Result<bool> {
+        let _guard = self.write_lock.lock().await;
+        let removed = self.remove_entry(&path_key(path)).await?;
+        if removed {
+            self.save_manifest().await?;
+        }
+        Ok(removed)
+    }
+
+    /// 启动文件监听，变化的文件会被增量重建索引
+    ///
+    /// 返回的 [`FileWatcher`] 需要由调用方持有，调用 `stop` 停止监听。
+    pub async fn watch(self: &Arc<Self>) -> Result<FileWatcher> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let watcher = FileWatcher::new(FileWatcherConfig {
+            watch_paths: self.config.roots.clone(),
+            poll_interval_ms: self.config.poll_interval_ms,
+            ignored_patterns: vec![],
+            auto_reindex: true,
+        })
+        .with_callback(Arc::new(move |change| {
+            let _ = tx.send(change);
+        }));
+
+        let index = Arc::downgrade(self);
+        tokio::spawn(async move {
+            while let Some(change) = rx.recv().await {
+                let Some(index) = index.upgrade() else {
+                    break;
+                };
+                let result = match change.change_type {
+                    FileChangeType::Removed if !change.path.exists() => {
+                        index.remove_path(&change.path).await
+                    }
+                    _ => index.index_path(&change.path).await,
+                };
+                if let Err(e) = result {
+                    tracing::warn!("增量索引失败 {:?}: {}", change.path, e);
+                }
+            }
+        });
+
+        watcher.start().await.map_err(OpenClawError::Memory)?;
+        Ok(watcher)
+    }
+
+    /// 块级混合检索，BM25 与向量结果按 RRF 融合
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<FileHit>> {
+        let candidates = limit.max(1) * 2;
+        // 记录每个块在各路结果中的排名
+        let mut ranked: HashMap<String, (f32, Option<FileHit>)> = HashMap::new();
+        let mut channels = 0;
+
+        let keywords = sanitize_query(query);
+        if !keywords.is_empty() {
+            channels += 1;
+            let results = self
+                .bm25
+                .search(&keywords, candidates)
+                .map_err(|e| OpenClawError::Memory(format!("BM25 检索失败: {}", e)))?;
+            let manifest = self.manifest.read().await;
+            for (rank, result) in results.into_iter().enumerate() {
+                let Some(chunk) = manifest
+                    .files
+                    .get(&result.source)
+                    .and_then(|file| file.chunks.iter().find(|c| c.id == result.id))
+                else {
+                    continue;
+                };
+                let entry = ranked.entry(result.id.clone()).or_insert((0.0, None));
+                entry.0 += 1.0 / (RRF_K + rank as f32 + 1.0);
+                entry.1.get_or_insert_with(|| FileHit {
+                    chunk_id: result.id,
+                    path: PathBuf::from(&result.source),
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    content: result.content,
+                    score: 0.0,
+                });
+            }
+        }
+
+        if let (Some(store), Some(embedder)) = (&self.vector_store, &self.embedding_provider) {
+            channels += 1;
+            let vector = embedder.embed(query).await?;
+            let results = store
+                .search(SearchQuery::new(vector).with_limit(candidates))
+                .await?;
+            for (rank, result) in results.into_iter().enumerate() {
+                let payload = &result.payload;
+                let Some(path) = payload.get("path").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let line =
+                    |key: &str| payload.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+                let entry = ranked.entry(result.id.clone()).or_insert((0.0, None));
+                entry.0 += 1.0 / (RRF_K + rank as f32 + 1.0);
+                entry.1.get_or_insert_with(|| FileHit {
+                    chunk_id: result.id.clone(),
+                    path: PathBuf::from(path),
+                    start_line: line("start_line"),
+                    end_line: line("end_line"),
+                    content: payload
+                        .get("content")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    score: 0.0,
+                });
+            }
+        }
+
+        // 在所有通道都排第一时得分为 1
+        let best = channels as f32 / (RRF_K + 1.0);
+        let mut hits: Vec<FileHit> = ranked
+            .into_values()
+            .filter_map(|(score, hit)| {
+                let mut hit = hit?;
+                hit.score = if best > 0.0 { score / best } else { 0.0 };
+                Some(hit)
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        hits.truncate(limit);
+        Ok(hits)
+    }
+
+    /// 遍历所有根目录，返回符合过滤规则的文件
+    fn scan(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for root in &self.config.roots {
+            if !root.exists() {
+                tracing::warn!("文件索引根目录不存在: {:?}", root);
+                continue;
+            }
+            for entry in walkdir::WalkDir::new(root)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+            {
+                if self.matches(entry.path()) {
+                    files.push(entry.into_path());
+                }
+            }
+        }
+        files
+    }
+
+    /// 文件是否位于某个根目录下且通过 include/exclude 过滤
+    fn matches(&self, path: &Path) -> bool {
+        let Some(relative) = self
+            .config
+            .roots
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())
+        else {
+            return false;
+        };
+
+        if self.exclude.iter().any(|p| p.matches_path(relative)) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(|p| p.matches_path(relative))
+    }
+
+    async fn sync_file(&self, path: &Path) -> Result<SyncOutcome> {
+        let key = path_key(path);
+
+        let metadata = match tokio::fs::metadata(path).await {
+            Ok(metadata) if metadata.is_file() && self.matches(path) => metadata,
+            _ => {
+                return Ok(if self.remove_entry(&key).await? {
+                    SyncOutcome::Removed
+                } else {
+                    SyncOutcome::Skipped
+                });
+            }
+        };
+
+        if metadata.len() > self.config.max_file_bytes {
+            self.remove_entry(&key).await?;
+            return Ok(SyncOutcome::Skipped);
+        }
+
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let needs_vectors = self.vector_store.is_some();
+
+        if let Some(existing) = self.manifest.read().await.files.get(&key)
+            && existing.modified_ms == modified_ms
+            && existing.size == metadata.len()
+            && (existing.embedded || !needs_vectors)
+        {
+            return Ok(SyncOutcome::Unchanged);
+        }
+
+        // 非 UTF-8 文件视为二进制，跳过
+        let Ok(content) = tokio::fs::read_to_string(path).await else {
+            self.remove_entry(&key).await?;
+            return Ok(SyncOutcome::Skipped);
+        };
+        let hash = content_hash(&content);
+
+        {
+            let mut manifest = self.manifest.write().await;
+            if let Some(existing) = manifest.files.get_mut(&key)
+                && existing.hash == hash
+                && (existing.embedded || !needs_vectors)
+            {
+                // 仅修改时间变化（如 touch）
+                existing.modified_ms = modified_ms;
+                existing.size = metadata.len();
+                return Ok(SyncOutcome::Unchanged);
+            }
+        }
+
+        self.remove_entry(&key).await?;
+
+        let chunks = self
+            .chunker
+            .chunk_text_by_lines(&content, &key)
+            .map_err(|e| OpenClawError::Memory(format!("切分文件失败: {}", e)))?;
+        let timestamp = chrono::Utc::now().timestamp();
+
+        let mut indexed = Vec::with_capacity(chunks.len());
+        let mut documents = Vec::with_capacity(chunks.len());
+        for chunk in &chunks {
+            let id = uuid::Uuid::new_v4().to_string();
+            indexed.push(IndexedChunk {
+                id: id.clone(),
+                start_line: chunk.metadata.start_line.unwrap_or(1),
+                end_line: chunk.metadata.end_line.unwrap_or(1),
+            });
+            documents.push((id, chunk.content.clone(), key.clone(), timestamp));
+        }
+
+        self.bm25
+            .add_documents_batch(documents)
+            .await
+            .map_err(|e| OpenClawError::Memory(format!("写入 BM25 索引失败: {}", e)))?;
+
+        // 嵌入失败时仍保留 BM25 索引，下次同步再补写向量
+        let embedded = match self.embed_chunks(&key, &indexed, &chunks).await {
+            Ok(embedded) => embedded,
+            Err(e) => {
+                tracing::warn!("写入文件向量失败 {}: {}", key, e);
+                false
+            }
+        };
+
+        self.manifest.write().await.files.insert(
+            key,
+            IndexedFile {
+                modified_ms,
+                size: metadata.len(),
+                hash,
+                chunks: indexed,
+                embedded,
+            },
+        );
+
+        Ok(SyncOutcome::Indexed)
+    }
+
+    /// 写入向量索引，未启用向量索引时返回 false
+    async fn embed_chunks(
+        &self,
+        key: &str,
+        indexed: &[IndexedChunk],
+        chunks: &[Chunk],
+    ) -> Result<bool> {
+        let (Some(store), Some(embedder)) = (&self.vector_store, &self.embedding_provider) else {
+            return Ok(false);
+        };
+        if chunks.is_empty() {
+            return Ok(true);
+        }
+
+        let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+        let vectors = embedder.embed_batch(&texts).await?;
+        let items = indexed
+            .iter()
+            .zip(chunks.iter())
+            .zip(vectors)
+            .map(|((meta, chunk), vector)| {
+                VectorItem::new(
+                    vector,
+                    serde_json::json!({
+                        "content": chunk.content,
+                        "path": key,
+                        "start_line": meta.start_line,
+                        "end_line": meta.end_line,
+                        "source": "file",
+                    }),
+                )
+                .with_id(meta.id.clone())
+            })
+            .collect();
+        store.upsert_batch(items).await?;
+        Ok(true)
+    }
+
+    async fn remove_entry(&self, key: &str) -> Result<bool> {
+        let Some(file) = self.manifest.write().await.files.remove(key) else {
+            return Ok(false);
+        };
+
+        self.bm25
+            .delete_by_source(key)
+            .await
+            .map_err(|e| OpenClawError::Memory(format!("删除 BM25 文档失败: {}", e)))?;
+        if let Some(store) = &self.vector_store {
+            for chunk in &file.chunks {
+                store.delete(&chunk.id).await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn save_manifest(&self) -> Result<()> {
+        let content = {
+            let mut manifest = self.manifest.write().await;
+            manifest.version = MANIFEST_VERSION;
+            serde_json::to_string(&*manifest)?
+        };
+        tokio::fs::write(self.config.index_dir.join(MANIFEST_FILE), content).await?;
+        Ok(())
+    }
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .map(|p| {
+            glob::Pattern::new(p)
+                .map_err(|e| OpenClawError::Config(format!("无效的 glob 模式 '{}': {}", p, e)))
+        })
+        .collect()
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+fn content_hash(content: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+/// 去掉 tantivy 查询语法中的特殊字符，避免用户输入导致解析失败
+fn sanitize_query(query: &str) -> String {
+    query
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(root: &Path, index_dir: &Path) -> FileIndexConfig {
+        FileIndexConfig {
+            roots: vec![root.to_path_buf()],
+            include: vec!["**/*.md".to_string(), "*.txt".to_string()],
+            exclude: vec!["drafts/**".to_string()],
+            index_dir: index_dir.to_path_buf(),
+            chunk_size: 40,
+            chunk_overlap: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sanitize_query() {
+        assert_eq!(
+            sanitize_query("what's `tokio::spawn`?"),
+            "what s tokio spawn"
+        );
+        assert_eq!(sanitize_query("  ***  "), "");
+    }
+
+    #[tokio::test]
+    async fn test_glob_filters() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        let index = FileCorpusIndex::open(test_config(root.path(), index_dir.path())).unwrap();
+
+        assert!(index.matches(&root.path().join("docs/guide.md")));
+        assert!(index.matches(&root.path().join("notes.txt")));
+        assert!(!index.matches(&root.path().join("drafts/todo.md")));
+        assert!(!index.matches(&root.path().join("main.rs")));
+        assert!(!index.matches(Path::new("/elsewhere/readme.md")));
+    }
+
+    #[tokio::test]
+    async fn test_incremental_index_and_citations() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("docs")).unwrap();
+        let guide = root.path().join("docs/guide.md");
+        std::fs::write(
+            &guide,
+            "# Guide\n\nInstall the toolchain first.\n\nThe gateway listens on port 18789 by default.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("drafts.md"),
+            "unrelated notes about cooking\n",
+        )
+        .unwrap();
+
+        let index = FileCorpusIndex::open(test_config(root.path(), index_dir.path())).unwrap();
+        let stats = index.refresh().await.unwrap();
+        assert_eq!(stats.indexed, 2);
+
+        let hits = index.search("gateway port", 5).await.unwrap();
+        assert!(!hits.is_empty());
+        assert_eq!(hits[0].path, guide);
+        assert!(hits[0].content.contains("18789"));
+        assert!(hits[0].start_line >= 1 && hits[0].end_line >= hits[0].start_line);
+        assert!(
+            hits[0]
+                .citation()
+                .ends_with(&format!("{}-{}", hits[0].start_line, hits[0].end_line))
+        );
+
+        // 未变化的文件不会重复索引
+        let stats = index.refresh().await.unwrap();
+        assert_eq!(stats.unchanged, 2);
+
+        // 重新打开时清单从磁盘恢复
+        drop(index);
+        let index = FileCorpusIndex::open(test_config(root.path(), index_dir.path())).unwrap();
+        assert_eq!(index.file_count().await, 2);
+
+        std::fs::remove_file(&guide).unwrap();
+        assert!(index.index_path(&guide).await.unwrap());
+        assert!(index.search("gateway port", 5).await.unwrap().is_empty());
+        assert_eq!(index.file_count().await, 1);
+    }
+}
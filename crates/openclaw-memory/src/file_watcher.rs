@@ -113,15 +113,15 @@ impl FileWatcher {
         callback: &Option<ChangeCallback>,
     ) -> Result<(), String> {
         let mut dirs_to_scan = vec![dir.to_path_buf()];
+        // 整棵目录树扫描完后再比对，避免把其他子目录（或其他监听根目录）的文件误报为删除
+        let mut current_files: HashMap<PathBuf, u64> = HashMap::new();
+        let mut last = last_known.write().await;
 
         while let Some(current_dir) = dirs_to_scan.pop() {
             let mut entries = tokio::fs::read_dir(&current_dir)
                 .await
                 .map_err(|e| format!("Failed to read directory: {}", e))?;
 
-            let mut current_files: HashMap<PathBuf, u64> = HashMap::new();
-            let mut last = last_known.write().await;
-
             while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
                 let path = entry.path();
 
@@ -164,22 +164,23 @@ impl FileWatcher {
                     }
                 }
             }
+        }
 
-            for (path, _) in last.iter() {
-                if !current_files.contains_key(path) {
-                    if let Some(cb) = callback {
-                        cb(FileChange {
-                            path: path.clone(),
-                            change_type: FileChangeType::Removed,
-                            timestamp: std::time::SystemTime::now(),
-                        });
-                    }
+        for (path, _) in last.iter() {
+            if path.starts_with(dir) && !current_files.contains_key(path) {
+                if let Some(cb) = callback {
+                    cb(FileChange {
+                        path: path.clone(),
+                        change_type: FileChangeType::Removed,
+                        timestamp: std::time::SystemTime::now(),
+                    });
                 }
             }
-
-            *last = current_files;
         }
 
+        last.retain(|path, _| !path.starts_with(dir));
+        last.extend(current_files);
+
         Ok(())
     }
 
@@ -1,5 +1,8 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::knowledge_graph::{Entity, EntityType, GraphQuery, KnowledgeGraph};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkingContext {
@@ -176,6 +179,108 @@ impl ContextProvider for SimpleContextProvider {
     }
 }
 
+/// 基于知识图谱的上下文：关键词命中的实体及其多跳邻居
+pub struct KnowledgeGraphContextProvider {
+    graph: Arc<KnowledgeGraph>,
+    query: GraphQuery,
+    max_seeds: usize,
+}
+
+impl KnowledgeGraphContextProvider {
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self {
+            graph,
+            query: GraphQuery::new().with_limit(20),
+            max_seeds: 5,
+        }
+    }
+
+    pub fn with_query(mut self, query: GraphQuery) -> Self {
+        self.query = query;
+        self
+    }
+
+    pub fn with_max_seeds(mut self, max_seeds: usize) -> Self {
+        self.max_seeds = max_seeds;
+        self
+    }
+}
+
+#[async_trait]
+impl ContextProvider for KnowledgeGraphContextProvider {
+    async fn prepare_context(
+        &self,
+        query: &str,
+    ) -> Result<ContextBundle, Box<dyn std::error::Error + Send + Sync>> {
+        let mut bundle = ContextBundle::new();
+        let query_lower = query.to_lowercase();
+        let terms: Vec<&str> = query_lower.split_whitespace().collect();
+
+        let mut seeds = self.graph.search_entities(&terms).await;
+        seeds.truncate(self.max_seeds);
+        bundle.add_retrieval_step(RetrievalStep {
+            step_type: RetrievalStepType::KeywordMatch,
+            path: "knowledge_graph".to_string(),
+            reasoning: format!("Matched entities for {} query terms", terms.len()),
+            results_count: seeds.len(),
+        });
+
+        let seed_ids: Vec<String> = seeds.iter().map(|e| e.id.clone()).collect();
+        for entity in &seeds {
+            bundle
+                .knowledge
+                .push(entity_item(entity, entity.describe(), entity.confidence));
+        }
+        if seed_ids.is_empty() {
+            return Ok(bundle);
+        }
+
+        let matches = self.graph.query(&seed_ids, &self.query).await?;
+        bundle.add_retrieval_step(RetrievalStep {
+            step_type: RetrievalStepType::PathTraverse,
+            path: "knowledge_graph".to_string(),
+            reasoning: format!(
+                "Traversed up to {} hops from matched entities",
+                self.query.max_hops
+            ),
+            results_count: matches.len(),
+        });
+
+        for found in matches {
+            let mut content = found.entity.describe();
+            if let Some(relation) = found.path.last() {
+                content.push_str(&format!(" (via {})", enum_label(&relation.relation_type)));
+            }
+            bundle
+                .knowledge
+                .push(entity_item(&found.entity, content, found.score));
+        }
+
+        Ok(bundle)
+    }
+}
+
+fn entity_item(entity: &Entity, content: String, similarity: f32) -> KnowledgeItem {
+    let memory_type = match entity.entity_type {
+        EntityType::Skill => KnowledgeType::Skill,
+        EntityType::Person | EntityType::Preference | EntityType::Goal => {
+            KnowledgeType::UserProfile
+        }
+        _ => KnowledgeType::ProjectKnowledge,
+    };
+    KnowledgeItem {
+        id: entity.id.clone(),
+        content,
+        source_path: format!("kg/{}", enum_label(&entity.entity_type)),
+        memory_type,
+        similarity,
+    }
+}
+
+fn enum_label<T: Serialize>(value: &T) -> String {
+    crate::graph_store::enum_key(value)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,6 +373,40 @@ mod tests {
         assert!(bundle.working.current_task.is_some());
     }
 
+    #[tokio::test]
+    async fn test_knowledge_graph_context_provider() {
+        use crate::knowledge_graph::{Relation, RelationType};
+
+        let graph = Arc::new(KnowledgeGraph::new());
+        let user = Entity::new("Alice".to_string(), EntityType::Person);
+        let skill = Entity::new("Rust".to_string(), EntityType::Skill);
+        graph.add_entity(user.clone()).await.unwrap();
+        graph.add_entity(skill.clone()).await.unwrap();
+        graph
+            .add_relation(Relation::new(
+                user.id.clone(),
+                skill.id,
+                RelationType::HasSkill,
+            ))
+            .await
+            .unwrap();
+
+        let provider = KnowledgeGraphContextProvider::new(graph);
+        let bundle = provider
+            .prepare_context("what does alice know")
+            .await
+            .unwrap();
+
+        assert_eq!(bundle.knowledge.len(), 2);
+        assert_eq!(bundle.knowledge[0].id, user.id);
+        assert!(bundle.knowledge[1].content.contains("Rust (via has_skill)"));
+        assert!(matches!(
+            bundle.knowledge[1].memory_type,
+            KnowledgeType::Skill
+        ));
+        assert_eq!(bundle.retrieval_trace.len(), 2);
+    }
+
     #[test]
     fn test_knowledge_type_variants() {
         let types = vec![
@@ -0,0 +1,568 @@
+//! 知识图谱导入导出
+//!
+//! 支持 JSON-LD 与 GraphML 两种格式，便于与外部图工具交换数据。
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use openclaw_core::{OpenClawError, Result};
+use quick_xml::Reader;
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+
+use crate::graph_store::{GraphStore, enum_from_key, enum_key, parse_time};
+use crate::ingest::office::{attr_value, xml_error};
+use crate::knowledge_graph::{Entity, EntityType, Relation, RelationType};
+
+const JSON_LD_VOCAB: &str = "urn:openclaw:kg#";
+const GRAPHML_NS: &str = "http://graphml.graphdrawing.org/xmlns";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    JsonLd,
+    GraphMl,
+}
+
+impl GraphFormat {
+    /// 按文件扩展名推断格式
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "jsonld" | "json" => Some(Self::JsonLd),
+            "graphml" | "xml" => Some(Self::GraphMl),
+            _ => None,
+        }
+    }
+}
+
+/// 图谱的完整快照
+#[derive(Debug, Clone, Default)]
+pub struct GraphSnapshot {
+    pub entities: Vec<Entity>,
+    pub relations: Vec<Relation>,
+}
+
+impl GraphSnapshot {
+    pub async fn from_store(store: &dyn GraphStore) -> Result<Self> {
+        Ok(Self {
+            entities: store.all_entities().await?,
+            relations: store.all_relations().await?,
+        })
+    }
+
+    /// 写入存储，已存在的同 ID 实体和关系会被覆盖
+    pub async fn write_to(&self, store: &dyn GraphStore) -> Result<()> {
+        for entity in &self.entities {
+            store.upsert_entity(entity).await?;
+        }
+        for relation in &self.relations {
+            store.upsert_relation(relation).await?;
+        }
+        Ok(())
+    }
+
+    pub fn encode(&self, format: GraphFormat) -> Result<String> {
+        match format {
+            GraphFormat::JsonLd => self.to_json_ld(),
+            GraphFormat::GraphMl => Ok(self.to_graphml()),
+        }
+    }
+
+    pub fn decode(data: &str, format: GraphFormat) -> Result<Self> {
+        match format {
+            GraphFormat::JsonLd => Self::from_json_ld(data),
+            GraphFormat::GraphMl => Self::from_graphml(data),
+        }
+    }
+
+    pub fn to_json_ld(&self) -> Result<String> {
+        let graph = self
+            .entities
+            .iter()
+            .map(|e| JsonLdNode::Entity(JsonLdEntity::from(e)))
+            .chain(
+                self.relations
+                    .iter()
+                    .map(|r| JsonLdNode::Relation(JsonLdRelation::from(r))),
+            )
+            .collect();
+        let document = JsonLdDocument {
+            context: serde_json::json!({
+                "@vocab": JSON_LD_VOCAB,
+                "source": { "@type": "@id" },
+                "target": { "@type": "@id" },
+            }),
+            graph,
+        };
+        serde_json::to_string_pretty(&document).map_err(OpenClawError::from)
+    }
+
+    pub fn from_json_ld(data: &str) -> Result<Self> {
+        let document: JsonLdDocument = serde_json::from_str(data)
+            .map_err(|e| OpenClawError::Parse(format!("JSON-LD 解析失败: {}", e)))?;
+        let mut snapshot = Self::default();
+        for node in document.graph {
+            match node {
+                JsonLdNode::Entity(e) => snapshot.entities.push(e.into()),
+                JsonLdNode::Relation(r) => snapshot.relations.push(r.into()),
+            }
+        }
+        Ok(snapshot)
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!("<graphml xmlns=\"{}\">\n", GRAPHML_NS));
+        for (id, target, kind) in [
+            ("name", "node", "string"),
+            ("entity_type", "node", "string"),
+            ("confidence", "node", "double"),
+            ("updated_at", "node", "string"),
+            ("relation_type", "edge", "string"),
+            ("weight", "edge", "double"),
+            ("properties", "all", "string"),
+            ("created_at", "all", "string"),
+        ] {
+            out.push_str(&format!(
+                "  <key id=\"{id}\" for=\"{target}\" attr.name=\"{id}\" attr.type=\"{kind}\"/>\n"
+            ));
+        }
+        out.push_str("  <graph id=\"openclaw\" edgedefault=\"directed\">\n");
+
+        for entity in &self.entities {
+            out.push_str(&format!(
+                "    <node id=\"{}\">\n",
+                escape(entity.id.as_str())
+            ));
+            push_data(&mut out, "name", &entity.name);
+            push_data(&mut out, "entity_type", &enum_key(&entity.entity_type));
+            push_data(&mut out, "confidence", &entity.confidence.to_string());
+            push_data(&mut out, "properties", &properties_json(&entity.properties));
+            push_data(&mut out, "created_at", &entity.created_at.to_rfc3339());
+            push_data(&mut out, "updated_at", &entity.updated_at.to_rfc3339());
+            out.push_str("    </node>\n");
+        }
+        for relation in &self.relations {
+            out.push_str(&format!(
+                "    <edge id=\"{}\" source=\"{}\" target=\"{}\">\n",
+                escape(relation.id.as_str()),
+                escape(relation.source_id.as_str()),
+                escape(relation.target_id.as_str())
+            ));
+            push_data(
+                &mut out,
+                "relation_type",
+                &enum_key(&relation.relation_type),
+            );
+            push_data(&mut out, "weight", &relation.weight.to_string());
+            push_data(
+                &mut out,
+                "properties",
+                &properties_json(&relation.properties),
+            );
+            push_data(&mut out, "created_at", &relation.created_at.to_rfc3339());
+            out.push_str("    </edge>\n");
+        }
+
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    /// 解析 GraphML，`<key>` 的 `attr.name` 决定字段含义，因此也能读取其他工具导出的文件
+    pub fn from_graphml(data: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(data);
+        let mut snapshot = Self::default();
+        let mut key_names: HashMap<String, String> = HashMap::new();
+        let mut element: Option<GraphElement> = None;
+        let mut data_key: Option<String> = None;
+        let mut text = String::new();
+
+        loop {
+            match reader.read_event().map_err(xml_error)? {
+                Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"key" => {
+                    if let Some(id) = attr_value(&e, b"id") {
+                        let name = attr_value(&e, b"attr.name").unwrap_or_else(|| id.clone());
+                        key_names.insert(id, name);
+                    }
+                }
+                Event::Start(e) => match e.local_name().as_ref() {
+                    b"node" => element = Some(GraphElement::node(&e)?),
+                    b"edge" => element = Some(GraphElement::edge(&e)?),
+                    b"data" => {
+                        data_key = attr_value(&e, b"key");
+                        text.clear();
+                    }
+                    _ => {}
+                },
+                Event::Empty(e) => match e.local_name().as_ref() {
+                    b"node" => snapshot.push(GraphElement::node(&e)?),
+                    b"edge" => snapshot.push(GraphElement::edge(&e)?),
+                    _ => {}
+                },
+                Event::Text(t) if data_key.is_some() => {
+                    text.push_str(&t.unescape().map_err(xml_error)?);
+                }
+                Event::End(e) => match e.local_name().as_ref() {
+                    b"data" => {
+                        if let (Some(key), Some(current)) = (data_key.take(), element.as_mut()) {
+                            let name = key_names.get(&key).cloned().unwrap_or(key);
+                            current.data.insert(name, text.trim().to_string());
+                        }
+                    }
+                    b"node" | b"edge" => {
+                        if let Some(current) = element.take() {
+                            snapshot.push(current);
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    fn push(&mut self, mut element: GraphElement) {
+        match element.endpoints.take() {
+            None => self.entities.push(element.into_entity()),
+            Some((source, target)) => self.relations.push(element.into_relation(source, target)),
+        }
+    }
+}
+
+/// 导出存储中的整个图谱
+pub async fn export_graph(store: &dyn GraphStore, format: GraphFormat) -> Result<String> {
+    GraphSnapshot::from_store(store).await?.encode(format)
+}
+
+/// 导入图谱数据，返回导入的实体数和关系数
+pub async fn import_graph(
+    store: &dyn GraphStore,
+    data: &str,
+    format: GraphFormat,
+) -> Result<(usize, usize)> {
+    let snapshot = GraphSnapshot::decode(data, format)?;
+    snapshot.write_to(store).await?;
+    Ok((snapshot.entities.len(), snapshot.relations.len()))
+}
+
+fn push_data(out: &mut String, key: &str, value: &str) {
+    out.push_str(&format!(
+        "      <data key=\"{}\">{}</data>\n",
+        key,
+        escape(value)
+    ));
+}
+
+fn properties_json(properties: &HashMap<String, String>) -> String {
+    serde_json::to_string(properties).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// 解析中的 GraphML 节点或边
+struct GraphElement {
+    id: String,
+    endpoints: Option<(String, String)>,
+    data: HashMap<String, String>,
+}
+
+impl GraphElement {
+    fn node(e: &quick_xml::events::BytesStart<'_>) -> Result<Self> {
+        let id = attr_value(e, b"id").ok_or_else(|| xml_error("node 缺少 id"))?;
+        Ok(Self {
+            id,
+            endpoints: None,
+            data: HashMap::new(),
+        })
+    }
+
+    fn edge(e: &quick_xml::events::BytesStart<'_>) -> Result<Self> {
+        let source = attr_value(e, b"source").ok_or_else(|| xml_error("edge 缺少 source"))?;
+        let target = attr_value(e, b"target").ok_or_else(|| xml_error("edge 缺少 target"))?;
+        let id = attr_value(e, b"id").unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        Ok(Self {
+            id,
+            endpoints: Some((source, target)),
+            data: HashMap::new(),
+        })
+    }
+
+    fn time(&self, key: &str) -> DateTime<Utc> {
+        self.data
+            .get(key)
+            .map(|v| parse_time(v))
+            .unwrap_or_else(Utc::now)
+    }
+
+    fn number(&self, key: &str) -> Option<f32> {
+        self.data.get(key).and_then(|v| v.parse().ok())
+    }
+
+    fn properties(&self) -> HashMap<String, String> {
+        self.data
+            .get("properties")
+            .and_then(|v| serde_json::from_str(v).ok())
+            .unwrap_or_default()
+    }
+
+    fn into_entity(self) -> Entity {
+        let created_at = self.time("created_at");
+        Entity {
+            name: self
+                .data
+                .get("name")
+                .cloned()
+                .unwrap_or_else(|| self.id.clone()),
+            entity_type: self
+                .data
+                .get("entity_type")
+                .and_then(|v| enum_from_key(v))
+                .unwrap_or(EntityType::Other),
+            properties: self.properties(),
+            created_at,
+            updated_at: self
+                .data
+                .get("updated_at")
+                .map_or(created_at, |v| parse_time(v)),
+            confidence: self.number("confidence").unwrap_or(1.0),
+            id: self.id,
+        }
+    }
+
+    fn into_relation(self, source_id: String, target_id: String) -> Relation {
+        Relation {
+            source_id,
+            target_id,
+            relation_type: self
+                .data
+                .get("relation_type")
+                .and_then(|v| enum_from_key(v))
+                .unwrap_or(RelationType::RelatedTo),
+            properties: self.properties(),
+            weight: self.number("weight").unwrap_or(1.0),
+            created_at: self.time("created_at"),
+            id: self.id,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonLdDocument {
+    #[serde(rename = "@context", default)]
+    context: serde_json::Value,
+    #[serde(rename = "@graph", default)]
+    graph: Vec<JsonLdNode>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "@type")]
+enum JsonLdNode {
+    Entity(JsonLdEntity),
+    Relation(JsonLdRelation),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonLdEntity {
+    #[serde(rename = "@id")]
+    id: String,
+    name: String,
+    #[serde(default = "default_entity_type")]
+    entity_type: EntityType,
+    #[serde(default)]
+    properties: HashMap<String, String>,
+    #[serde(default = "default_score")]
+    confidence: f32,
+    #[serde(default = "Utc::now")]
+    created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonLdRelation {
+    #[serde(rename = "@id")]
+    id: String,
+    source: String,
+    target: String,
+    #[serde(default = "default_relation_type")]
+    relation_type: RelationType,
+    #[serde(default)]
+    properties: HashMap<String, String>,
+    #[serde(default = "default_score")]
+    weight: f32,
+    #[serde(default = "Utc::now")]
+    created_at: DateTime<Utc>,
+}
+
+fn default_entity_type() -> EntityType {
+    EntityType::Other
+}
+
+fn default_relation_type() -> RelationType {
+    RelationType::RelatedTo
+}
+
+fn default_score() -> f32 {
+    1.0
+}
+
+impl From<&Entity> for JsonLdEntity {
+    fn from(e: &Entity) -> Self {
+        Self {
+            id: e.id.clone(),
+            name: e.name.clone(),
+            entity_type: e.entity_type.clone(),
+            properties: e.properties.clone(),
+            confidence: e.confidence,
+            created_at: e.created_at,
+            updated_at: e.updated_at,
+        }
+    }
+}
+
+impl From<JsonLdEntity> for Entity {
+    fn from(e: JsonLdEntity) -> Self {
+        Self {
+            id: e.id,
+            name: e.name,
+            entity_type: e.entity_type,
+            properties: e.properties,
+            created_at: e.created_at,
+            updated_at: e.updated_at,
+            confidence: e.confidence,
+        }
+    }
+}
+
+impl From<&Relation> for JsonLdRelation {
+    fn from(r: &Relation) -> Self {
+        Self {
+            id: r.id.clone(),
+            source: r.source_id.clone(),
+            target: r.target_id.clone(),
+            relation_type: r.relation_type.clone(),
+            properties: r.properties.clone(),
+            weight: r.weight,
+            created_at: r.created_at,
+        }
+    }
+}
+
+impl From<JsonLdRelation> for Relation {
+    fn from(r: JsonLdRelation) -> Self {
+        Self {
+            id: r.id,
+            source_id: r.source,
+            target_id: r.target,
+            relation_type: r.relation_type,
+            properties: r.properties,
+            weight: r.weight,
+            created_at: r.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph_store::MemoryGraphStore;
+
+    fn sample() -> GraphSnapshot {
+        let alice = Entity::new("Alice & Bob <Co>".to_string(), EntityType::Organization)
+            .with_property("city", "上海")
+            .with_confidence(0.8);
+        let rust = Entity::new("Rust".to_string(), EntityType::Skill);
+        let relation = Relation::new(alice.id.clone(), rust.id.clone(), RelationType::Uses)
+            .with_weight(0.5)
+            .with_property("since", "2020");
+        GraphSnapshot {
+            entities: vec![alice, rust],
+            relations: vec![relation],
+        }
+    }
+
+    fn assert_same(decoded: &GraphSnapshot, original: &GraphSnapshot) {
+        assert_eq!(decoded.entities.len(), original.entities.len());
+        assert_eq!(decoded.relations.len(), original.relations.len());
+        let alice = &original.entities[0];
+        let found = decoded.entities.iter().find(|e| e.id == alice.id).unwrap();
+        assert_eq!(found.name, alice.name);
+        assert_eq!(found.entity_type, EntityType::Organization);
+        assert_eq!(
+            found.properties.get("city").map(String::as_str),
+            Some("上海")
+        );
+        assert!((found.confidence - 0.8).abs() < 1e-6);
+        assert_eq!(found.created_at, alice.created_at);
+
+        let relation = &decoded.relations[0];
+        assert_eq!(relation.source_id, alice.id);
+        assert_eq!(relation.relation_type, RelationType::Uses);
+        assert!((relation.weight - 0.5).abs() < 1e-6);
+        assert_eq!(
+            relation.properties.get("since").map(String::as_str),
+            Some("2020")
+        );
+    }
+
+    #[test]
+    fn test_json_ld_round_trip() {
+        let original = sample();
+        let encoded = original.to_json_ld().unwrap();
+        assert!(encoded.contains("\"@graph\""));
+        assert!(encoded.contains("\"entityType\": \"organization\""));
+        assert_same(&GraphSnapshot::from_json_ld(&encoded).unwrap(), &original);
+    }
+
+    #[test]
+    fn test_graphml_round_trip() {
+        let original = sample();
+        let encoded = original.to_graphml();
+        assert!(encoded.contains("Alice &amp; Bob &lt;Co&gt;"));
+        assert_same(&GraphSnapshot::from_graphml(&encoded).unwrap(), &original);
+    }
+
+    #[test]
+    fn test_graphml_foreign_keys() {
+        let xml = r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+            <key id="d0" for="node" attr.name="name" attr.type="string"/>
+            <key id="d1" for="edge" attr.name="weight" attr.type="double"/>
+            <graph edgedefault="directed">
+                <node id="n0"><data key="d0">Paris</data></node>
+                <node id="n1"/>
+                <edge source="n0" target="n1"><data key="d1">0.25</data></edge>
+            </graph>
+        </graphml>"#;
+        let snapshot = GraphSnapshot::from_graphml(xml).unwrap();
+        assert_eq!(snapshot.entities.len(), 2);
+        assert_eq!(snapshot.entities[0].name, "Paris");
+        assert_eq!(snapshot.entities[1].name, "n1");
+        assert_eq!(snapshot.entities[1].entity_type, EntityType::Other);
+        assert_eq!(snapshot.relations[0].relation_type, RelationType::RelatedTo);
+        assert!((snapshot.relations[0].weight - 0.25).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_import_export_store() {
+        let source = MemoryGraphStore::new();
+        sample().write_to(&source).await.unwrap();
+        let exported = export_graph(&source, GraphFormat::GraphMl).await.unwrap();
+
+        let target = MemoryGraphStore::new();
+        let counts = import_graph(&target, &exported, GraphFormat::GraphMl)
+            .await
+            .unwrap();
+        assert_eq!(counts, (2, 1));
+        assert_eq!(target.all_relations().await.unwrap().len(), 1);
+        assert_eq!(
+            GraphFormat::from_path("kg.jsonld"),
+            Some(GraphFormat::JsonLd)
+        );
+    }
+}
@@ -0,0 +1,724 @@
+//! 知识图谱存储
+//!
+//! - `GraphStore`：实体与关系的存储接口，按名称、类型和边建立索引
+//! - `MemoryGraphStore`：内存实现，进程退出后数据丢失
+//! - `SqliteGraphStore`：嵌入式 SQLite 实现，重启后保留数据
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use openclaw_core::{OpenClawError, Result};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+use tokio::sync::RwLock;
+
+use crate::knowledge_graph::{Entity, EntityType, Relation, RelationType};
+
+/// 沿关系遍历的方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Direction {
+    Outgoing,
+    Incoming,
+    #[default]
+    Both,
+}
+
+/// 关系过滤条件
+#[derive(Debug, Clone, Default)]
+pub struct RelationFilter {
+    /// 为空时不限关系类型
+    pub relation_types: Vec<RelationType>,
+    pub min_weight: Option<f32>,
+}
+
+impl RelationFilter {
+    pub fn matches(&self, relation: &Relation) -> bool {
+        (self.relation_types.is_empty() || self.relation_types.contains(&relation.relation_type))
+            && self.min_weight.is_none_or(|w| relation.weight >= w)
+    }
+}
+
+#[async_trait]
+pub trait GraphStore: Send + Sync {
+    /// 写入实体，ID 相同时覆盖
+    async fn upsert_entity(&self, entity: &Entity) -> Result<()>;
+
+    /// 写入关系，ID 相同时覆盖
+    async fn upsert_relation(&self, relation: &Relation) -> Result<()>;
+
+    async fn get_entity(&self, id: &str) -> Result<Option<Entity>>;
+
+    /// 删除实体及与其相连的关系
+    async fn remove_entity(&self, id: &str) -> Result<bool>;
+
+    async fn remove_relation(&self, id: &str) -> Result<bool>;
+
+    /// 按名称查找，不区分大小写
+    async fn find_by_name(&self, name: &str) -> Result<Vec<Entity>>;
+
+    async fn find_by_type(&self, entity_type: &EntityType) -> Result<Vec<Entity>>;
+
+    /// 名称或属性值包含任一关键词的实体，关键词为小写
+    async fn search(&self, terms: &[String]) -> Result<Vec<Entity>>;
+
+    /// 与实体相连且满足过滤条件的关系
+    async fn relations(
+        &self,
+        entity_id: &str,
+        direction: Direction,
+        filter: &RelationFilter,
+    ) -> Result<Vec<Relation>>;
+
+    async fn all_entities(&self) -> Result<Vec<Entity>>;
+
+    async fn all_relations(&self) -> Result<Vec<Relation>>;
+
+    async fn clear(&self) -> Result<()>;
+}
+
+#[derive(Default)]
+struct MemoryGraph {
+    entities: HashMap<String, Entity>,
+    relations: HashMap<String, Relation>,
+    by_name: HashMap<String, HashSet<String>>,
+    by_type: HashMap<EntityType, HashSet<String>>,
+    outgoing: HashMap<String, HashSet<String>>,
+    incoming: HashMap<String, HashSet<String>>,
+}
+
+impl MemoryGraph {
+    fn unindex_entity(&mut self, entity: &Entity) {
+        if let Some(ids) = self.by_name.get_mut(&entity.name.to_lowercase()) {
+            ids.remove(&entity.id);
+        }
+        if let Some(ids) = self.by_type.get_mut(&entity.entity_type) {
+            ids.remove(&entity.id);
+        }
+    }
+
+    fn unindex_relation(&mut self, relation: &Relation) {
+        if let Some(ids) = self.outgoing.get_mut(&relation.source_id) {
+            ids.remove(&relation.id);
+        }
+        if let Some(ids) = self.incoming.get_mut(&relation.target_id) {
+            ids.remove(&relation.id);
+        }
+    }
+
+    fn collect(&self, ids: Option<&HashSet<String>>) -> Vec<Entity> {
+        ids.map(|ids| {
+            ids.iter()
+                .filter_map(|id| self.entities.get(id).cloned())
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// 内存图存储
+#[derive(Default)]
+pub struct MemoryGraphStore {
+    graph: RwLock<MemoryGraph>,
+}
+
+impl MemoryGraphStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl GraphStore for MemoryGraphStore {
+    async fn upsert_entity(&self, entity: &Entity) -> Result<()> {
+        let mut graph = self.graph.write().await;
+        if let Some(old) = graph.entities.remove(&entity.id) {
+            graph.unindex_entity(&old);
+        }
+        graph
+            .by_name
+            .entry(entity.name.to_lowercase())
+            .or_default()
+            .insert(entity.id.clone());
+        graph
+            .by_type
+            .entry(entity.entity_type.clone())
+            .or_default()
+            .insert(entity.id.clone());
+        graph.entities.insert(entity.id.clone(), entity.clone());
+        Ok(())
+    }
+
+    async fn upsert_relation(&self, relation: &Relation) -> Result<()> {
+        let mut graph = self.graph.write().await;
+        if let Some(old) = graph.relations.remove(&relation.id) {
+            graph.unindex_relation(&old);
+        }
+        graph
+            .outgoing
+            .entry(relation.source_id.clone())
+            .or_default()
+            .insert(relation.id.clone());
+        graph
+            .incoming
+            .entry(relation.target_id.clone())
+            .or_default()
+            .insert(relation.id.clone());
+        graph
+            .relations
+            .insert(relation.id.clone(), relation.clone());
+        Ok(())
+    }
+
+    async fn get_entity(&self, id: &str) -> Result<Option<Entity>> {
+        Ok(self.graph.read().await.entities.get(id).cloned())
+    }
+
+    async fn remove_entity(&self, id: &str) -> Result<bool> {
+        let mut graph = self.graph.write().await;
+        let Some(entity) = graph.entities.remove(id) else {
+            return Ok(false);
+        };
+        graph.unindex_entity(&entity);
+
+        let mut edges: HashSet<String> = graph.outgoing.remove(id).unwrap_or_default();
+        edges.extend(graph.incoming.remove(id).unwrap_or_default());
+        for edge in edges {
+            if let Some(relation) = graph.relations.remove(&edge) {
+                graph.unindex_relation(&relation);
+            }
+        }
+        Ok(true)
+    }
+
+    async fn remove_relation(&self, id: &str) -> Result<bool> {
+        let mut graph = self.graph.write().await;
+        match graph.relations.remove(id) {
+            Some(relation) => {
+                graph.unindex_relation(&relation);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Vec<Entity>> {
+        let graph = self.graph.read().await;
+        Ok(graph.collect(graph.by_name.get(&name.to_lowercase())))
+    }
+
+    async fn find_by_type(&self, entity_type: &EntityType) -> Result<Vec<Entity>> {
+        let graph = self.graph.read().await;
+        Ok(graph.collect(graph.by_type.get(entity_type)))
+    }
+
+    async fn search(&self, terms: &[String]) -> Result<Vec<Entity>> {
+        let graph = self.graph.read().await;
+        Ok(graph
+            .entities
+            .values()
+            .filter(|entity| matches_terms(entity, terms))
+            .cloned()
+            .collect())
+    }
+
+    async fn relations(
+        &self,
+        entity_id: &str,
+        direction: Direction,
+        filter: &RelationFilter,
+    ) -> Result<Vec<Relation>> {
+        let graph = self.graph.read().await;
+        let mut ids: Vec<&String> = Vec::new();
+        if direction != Direction::Incoming {
+            ids.extend(graph.outgoing.get(entity_id).into_iter().flatten());
+        }
+        if direction != Direction::Outgoing {
+            ids.extend(graph.incoming.get(entity_id).into_iter().flatten());
+        }
+        let mut seen = HashSet::new();
+        Ok(ids
+            .into_iter()
+            .filter(|id| seen.insert(*id))
+            .filter_map(|id| graph.relations.get(id))
+            .filter(|r| filter.matches(r))
+            .cloned()
+            .collect())
+    }
+
+    async fn all_entities(&self) -> Result<Vec<Entity>> {
+        Ok(self.graph.read().await.entities.values().cloned().collect())
+    }
+
+    async fn all_relations(&self) -> Result<Vec<Relation>> {
+        Ok(self
+            .graph
+            .read()
+            .await
+            .relations
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        *self.graph.write().await = MemoryGraph::default();
+        Ok(())
+    }
+}
+
+const ENTITY_COLUMNS: &str =
+    "id, name, entity_type, properties, created_at, updated_at, confidence";
+const RELATION_COLUMNS: &str =
+    "id, source_id, target_id, relation_type, properties, weight, created_at";
+
+/// SQLite 图存储，实体按名称和类型建索引，关系按两端和类型建索引
+pub struct SqliteGraphStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteGraphStore {
+    /// 打开或创建数据库文件，父目录不存在时一并创建
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path).map_err(sqlite_error)?;
+        // 导入管线和检索可能各自打开同一文件
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(sqlite_error)?;
+        Self::init(conn)
+    }
+
+    /// 内存数据库，主要用于测试
+    pub fn open_in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory().map_err(sqlite_error)?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS kg_entities (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                name_lower TEXT NOT NULL,
+                entity_type TEXT NOT NULL,
+                properties TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                confidence REAL NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_kg_entities_name ON kg_entities(name_lower);
+            CREATE INDEX IF NOT EXISTS idx_kg_entities_type ON kg_entities(entity_type);
+            CREATE TABLE IF NOT EXISTS kg_relations (
+                id TEXT PRIMARY KEY,
+                source_id TEXT NOT NULL,
+                target_id TEXT NOT NULL,
+                relation_type TEXT NOT NULL,
+                properties TEXT NOT NULL,
+                weight REAL NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_kg_relations_source
+                ON kg_relations(source_id, relation_type);
+            CREATE INDEX IF NOT EXISTS idx_kg_relations_target
+                ON kg_relations(target_id, relation_type);",
+        )
+        .map_err(sqlite_error)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn query_entities(&self, sql: &str, args: &[&dyn rusqlite::ToSql]) -> Result<Vec<Entity>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(sql).map_err(sqlite_error)?;
+        let rows = stmt
+            .query_map(args, entity_from_row)
+            .map_err(sqlite_error)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(sqlite_error)?;
+        Ok(rows)
+    }
+
+    fn query_relations(&self, sql: &str, args: &[&dyn rusqlite::ToSql]) -> Result<Vec<Relation>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(sql).map_err(sqlite_error)?;
+        let rows = stmt
+            .query_map(args, relation_from_row)
+            .map_err(sqlite_error)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(sqlite_error)?;
+        Ok(rows)
+    }
+}
+
+#[async_trait]
+impl GraphStore for SqliteGraphStore {
+    async fn upsert_entity(&self, entity: &Entity) -> Result<()> {
+        self.conn()
+            .execute(
+                "INSERT OR REPLACE INTO kg_entities
+                 (id, name, name_lower, entity_type, properties, created_at, updated_at, confidence)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    entity.id,
+                    entity.name,
+                    entity.name.to_lowercase(),
+                    enum_key(&entity.entity_type),
+                    serde_json::to_string(&entity.properties)?,
+                    entity.created_at.to_rfc3339(),
+                    entity.updated_at.to_rfc3339(),
+                    entity.confidence as f64,
+                ],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    async fn upsert_relation(&self, relation: &Relation) -> Result<()> {
+        self.conn()
+            .execute(
+                "INSERT OR REPLACE INTO kg_relations
+                 (id, source_id, target_id, relation_type, properties, weight, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    relation.id,
+                    relation.source_id,
+                    relation.target_id,
+                    enum_key(&relation.relation_type),
+                    serde_json::to_string(&relation.properties)?,
+                    relation.weight as f64,
+                    relation.created_at.to_rfc3339(),
+                ],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    async fn get_entity(&self, id: &str) -> Result<Option<Entity>> {
+        let sql = format!("SELECT {} FROM kg_entities WHERE id = ?1", ENTITY_COLUMNS);
+        self.conn()
+            .query_row(&sql, [id], entity_from_row)
+            .optional()
+            .map_err(sqlite_error)
+    }
+
+    async fn remove_entity(&self, id: &str) -> Result<bool> {
+        let mut conn = self.conn();
+        let tx = conn.transaction().map_err(sqlite_error)?;
+        tx.execute(
+            "DELETE FROM kg_relations WHERE source_id = ?1 OR target_id = ?1",
+            [id],
+        )
+        .map_err(sqlite_error)?;
+        let removed = tx
+            .execute("DELETE FROM kg_entities WHERE id = ?1", [id])
+            .map_err(sqlite_error)?;
+        tx.commit().map_err(sqlite_error)?;
+        Ok(removed > 0)
+    }
+
+    async fn remove_relation(&self, id: &str) -> Result<bool> {
+        let removed = self
+            .conn()
+            .execute("DELETE FROM kg_relations WHERE id = ?1", [id])
+            .map_err(sqlite_error)?;
+        Ok(removed > 0)
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Vec<Entity>> {
+        let sql = format!(
+            "SELECT {} FROM kg_entities WHERE name_lower = ?1",
+            ENTITY_COLUMNS
+        );
+        self.query_entities(&sql, &[&name.to_lowercase()])
+    }
+
+    async fn find_by_type(&self, entity_type: &EntityType) -> Result<Vec<Entity>> {
+        let sql = format!(
+            "SELECT {} FROM kg_entities WHERE entity_type = ?1",
+            ENTITY_COLUMNS
+        );
+        self.query_entities(&sql, &[&enum_key(entity_type)])
+    }
+
+    async fn search(&self, terms: &[String]) -> Result<Vec<Entity>> {
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let patterns: Vec<String> = terms
+            .iter()
+            .map(|t| {
+                format!(
+                    "%{}%",
+                    t.replace('\\', "\\\\")
+                        .replace('%', "\\%")
+                        .replace('_', "\\_")
+                )
+            })
+            .collect();
+        let conditions: Vec<String> = (1..=patterns.len())
+            .map(|i| {
+                format!(
+                    "name_lower LIKE ?{i} ESCAPE '\\' OR lower(properties) LIKE ?{i} ESCAPE '\\'"
+                )
+            })
+            .collect();
+        let sql = format!(
+            "SELECT {} FROM kg_entities WHERE {}",
+            ENTITY_COLUMNS,
+            conditions.join(" OR ")
+        );
+        let args: Vec<&dyn rusqlite::ToSql> =
+            patterns.iter().map(|p| p as &dyn rusqlite::ToSql).collect();
+        let candidates = self.query_entities(&sql, &args)?;
+
+        // properties 以 JSON 存储，键名也可能命中，这里再按属性值过滤一次
+        Ok(candidates
+            .into_iter()
+            .filter(|entity| matches_terms(entity, terms))
+            .collect())
+    }
+
+    async fn relations(
+        &self,
+        entity_id: &str,
+        direction: Direction,
+        filter: &RelationFilter,
+    ) -> Result<Vec<Relation>> {
+        let condition = match direction {
+            Direction::Outgoing => "source_id = ?1",
+            Direction::Incoming => "target_id = ?1",
+            Direction::Both => "(source_id = ?1 OR target_id = ?1)",
+        };
+        let mut sql = format!(
+            "SELECT {} FROM kg_relations WHERE {} AND weight >= ?2",
+            RELATION_COLUMNS, condition
+        );
+        let types: Vec<String> = filter.relation_types.iter().map(enum_key).collect();
+        if !types.is_empty() {
+            let placeholders: Vec<String> =
+                (3..3 + types.len()).map(|i| format!("?{}", i)).collect();
+            sql.push_str(&format!(
+                " AND relation_type IN ({})",
+                placeholders.join(", ")
+            ));
+        }
+
+        let min_weight = filter.min_weight.unwrap_or(f32::MIN) as f64;
+        let mut args: Vec<&dyn rusqlite::ToSql> = vec![&entity_id, &min_weight];
+        args.extend(types.iter().map(|t| t as &dyn rusqlite::ToSql));
+        self.query_relations(&sql, &args)
+    }
+
+    async fn all_entities(&self) -> Result<Vec<Entity>> {
+        let sql = format!("SELECT {} FROM kg_entities", ENTITY_COLUMNS);
+        self.query_entities(&sql, &[])
+    }
+
+    async fn all_relations(&self) -> Result<Vec<Relation>> {
+        let sql = format!("SELECT {} FROM kg_relations", RELATION_COLUMNS);
+        self.query_relations(&sql, &[])
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.conn()
+            .execute_batch("DELETE FROM kg_relations; DELETE FROM kg_entities;")
+            .map_err(sqlite_error)
+    }
+}
+
+fn matches_terms(entity: &Entity, terms: &[String]) -> bool {
+    let name = entity.name.to_lowercase();
+    terms.iter().any(|term| {
+        name.contains(term.as_str())
+            || entity
+                .properties
+                .values()
+                .any(|v| v.to_lowercase().contains(term.as_str()))
+    })
+}
+
+fn entity_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Entity> {
+    Ok(Entity {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        entity_type: enum_from_key(&row.get::<_, String>(2)?).unwrap_or(EntityType::Other),
+        properties: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+        created_at: parse_time(&row.get::<_, String>(4)?),
+        updated_at: parse_time(&row.get::<_, String>(5)?),
+        confidence: row.get::<_, f64>(6)? as f32,
+    })
+}
+
+fn relation_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Relation> {
+    Ok(Relation {
+        id: row.get(0)?,
+        source_id: row.get(1)?,
+        target_id: row.get(2)?,
+        relation_type: enum_from_key(&row.get::<_, String>(3)?).unwrap_or(RelationType::RelatedTo),
+        properties: serde_json::from_str(&row.get::<_, String>(4)?).unwrap_or_default(),
+        weight: row.get::<_, f64>(5)? as f32,
+        created_at: parse_time(&row.get::<_, String>(6)?),
+    })
+}
+
+/// 枚举按 serde 名称存储，如 `works_for`
+pub(crate) fn enum_key<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+pub(crate) fn enum_from_key<T: DeserializeOwned>(key: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(key.to_string())).ok()
+}
+
+pub(crate) fn parse_time(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+fn sqlite_error(e: rusqlite::Error) -> OpenClawError {
+    OpenClawError::Memory(format!("知识图谱存储错误: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn exercise(store: &dyn GraphStore) {
+        let alice =
+            Entity::new("Alice".to_string(), EntityType::Person).with_property("city", "Berlin");
+        let acme = Entity::new("Acme".to_string(), EntityType::Organization);
+        let rust = Entity::new("Rust".to_string(), EntityType::Skill);
+        for entity in [&alice, &acme, &rust] {
+            store.upsert_entity(entity).await.unwrap();
+        }
+        let works = Relation::new(alice.id.clone(), acme.id.clone(), RelationType::WorksFor);
+        let skill = Relation::new(alice.id.clone(), rust.id.clone(), RelationType::HasSkill)
+            .with_weight(0.3);
+        store.upsert_relation(&works).await.unwrap();
+        store.upsert_relation(&skill).await.unwrap();
+
+        assert_eq!(store.find_by_name("alice").await.unwrap().len(), 1);
+        assert_eq!(
+            store.find_by_type(&EntityType::Skill).await.unwrap()[0].id,
+            rust.id
+        );
+        assert_eq!(
+            store.search(&["berlin".to_string()]).await.unwrap()[0].id,
+            alice.id
+        );
+        assert!(
+            store
+                .search(&["city".to_string()])
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let all = RelationFilter::default();
+        assert_eq!(
+            store
+                .relations(&alice.id, Direction::Outgoing, &all)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(
+            store
+                .relations(&alice.id, Direction::Incoming, &all)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            store
+                .relations(&acme.id, Direction::Both, &all)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let heavy = RelationFilter {
+            min_weight: Some(0.5),
+            ..Default::default()
+        };
+        let found = store
+            .relations(&alice.id, Direction::Outgoing, &heavy)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].relation_type, RelationType::WorksFor);
+
+        let skills = RelationFilter {
+            relation_types: vec![RelationType::HasSkill],
+            ..Default::default()
+        };
+        let found = store
+            .relations(&alice.id, Direction::Both, &skills)
+            .await
+            .unwrap();
+        assert_eq!(found[0].target_id, rust.id);
+
+        // 改名后旧名称不再命中
+        let renamed = Entity {
+            name: "Alicia".to_string(),
+            ..alice.clone()
+        };
+        store.upsert_entity(&renamed).await.unwrap();
+        assert!(store.find_by_name("alice").await.unwrap().is_empty());
+
+        assert!(store.remove_entity(&alice.id).await.unwrap());
+        assert!(store.all_relations().await.unwrap().is_empty());
+        assert_eq!(store.all_entities().await.unwrap().len(), 2);
+
+        store.clear().await.unwrap();
+        assert!(store.all_entities().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        exercise(&MemoryGraphStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store() {
+        exercise(&SqliteGraphStore::open_in_memory().unwrap()).await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.db");
+        let entity = Entity::new("Persisted".to_string(), EntityType::Concept)
+            .with_property("note", "survives restart");
+
+        SqliteGraphStore::open(&path)
+            .unwrap()
+            .upsert_entity(&entity)
+            .await
+            .unwrap();
+
+        let reopened = SqliteGraphStore::open(&path).unwrap();
+        let loaded = reopened.get_entity(&entity.id).await.unwrap().unwrap();
+        assert_eq!(loaded.name, "Persisted");
+        assert_eq!(loaded.entity_type, EntityType::Concept);
+        assert_eq!(loaded.properties.get("note").unwrap(), "survives restart");
+    }
+}
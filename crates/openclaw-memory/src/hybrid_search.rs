@@ -6,16 +6,25 @@
 //! - 知识图谱搜索：实体关系推理
 
 use openclaw_core::Result;
-use openclaw_vector::{SearchQuery, SearchResult, VectorItem, VectorStore};
+use openclaw_vector::{Filter, SearchQuery, SearchResult, VectorItem, VectorStore};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::bm25::Bm25Index;
-use crate::knowledge_graph::KnowledgeGraph;
+use crate::knowledge_graph::{GraphQuery, KnowledgeGraph};
+use crate::rerank::RerankStage;
+use crate::types::MemoryNamespace;
 use crate::unified_search::config::UnifiedSearchConfig;
 use crate::unified_search::fusion::{FusionStrategy, ResultFusion};
 use crate::unified_search::result::{SearchSource, UnifiedSearchResult};
 
+/// 图谱邻居实体相对直接命中实体的得分折扣
+const NEIGHBOR_DISCOUNT: f32 = 0.5;
+
+/// 列出记忆时每次扫描的条数
+const LIST_PAGE: usize = 500;
+
 pub struct HybridSearchManager {
     vector_store: Arc<dyn VectorStore>,
     vector_weight: f32,
@@ -23,6 +32,7 @@ pub struct HybridSearchManager {
     embedding_dimension: usize,
     bm25_index: Option<Arc<Bm25Index>>,
     knowledge_graph: Option<Arc<RwLock<KnowledgeGraph>>>,
+    reranker: Option<Arc<RerankStage>>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +47,8 @@ pub struct HybridSearchConfig {
     pub enable_vector: bool,
     pub enable_bm25: bool,
     pub enable_knowledge_graph: bool,
+    /// 设置后所有存储查询都限定在该命名空间内，共享的知识图谱不参与检索
+    pub namespace: Option<MemoryNamespace>,
 }
 
 impl Default for HybridSearchConfig {
@@ -52,6 +64,7 @@ impl Default for HybridSearchConfig {
             enable_vector: true,
             enable_bm25: true,
             enable_knowledge_graph: true,
+            namespace: None,
         }
     }
 }
@@ -66,6 +79,7 @@ impl HybridSearchManager {
             embedding_dimension,
             bm25_index: None,
             knowledge_graph: None,
+            reranker: None,
         }
     }
 
@@ -79,11 +93,51 @@ impl HybridSearchManager {
         self
     }
 
+    pub fn bm25_index(&self) -> Option<&Arc<Bm25Index>> {
+        self.bm25_index.as_ref()
+    }
+
+    pub fn knowledge_graph(&self) -> Option<&Arc<RwLock<KnowledgeGraph>>> {
+        self.knowledge_graph.as_ref()
+    }
+
+    /// 融合排序后追加重排阶段，召回数放大到 `top_k_in`
+    pub fn with_reranker(mut self, reranker: Arc<RerankStage>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
+    /// 重排阶段需要的候选数不少于 `top_k_in`
+    fn candidate_config(&self, config: &HybridSearchConfig) -> HybridSearchConfig {
+        let mut widened = config.clone();
+        if let Some(stage) = &self.reranker {
+            widened.limit = config.limit.max(stage.config().top_k_in);
+        }
+        widened
+    }
+
     pub async fn search(
         &self,
         query_text: &str,
         query_vector: Option<Vec<f32>>,
         config: &HybridSearchConfig,
+    ) -> Result<Vec<SearchResult>> {
+        let Some(stage) = &self.reranker else {
+            return self.fused_search(query_text, query_vector, config).await;
+        };
+        let candidates = self
+            .fused_search(query_text, query_vector, &self.candidate_config(config))
+            .await?;
+        let mut ranked = stage.rerank(query_text, candidates).await;
+        ranked.truncate(config.limit);
+        Ok(ranked)
+    }
+
+    async fn fused_search(
+        &self,
+        query_text: &str,
+        query_vector: Option<Vec<f32>>,
+        config: &HybridSearchConfig,
     ) -> Result<Vec<SearchResult>> {
         let mut all_results: Vec<SearchResult> = Vec::new();
 
@@ -93,6 +147,7 @@ impl HybridSearchManager {
             let mut query = SearchQuery::new(vector);
             query.limit = config.limit;
             query.min_score = config.min_score;
+            query.filter = config.namespace.as_ref().map(MemoryNamespace::filter);
 
             let vector_results = self.vector_store.search(query).await?;
             all_results.extend(vector_results);
@@ -100,7 +155,9 @@ impl HybridSearchManager {
 
         if config.keyword_weight > 0.0
             && !query_text.is_empty()
-            && let Ok(fts_results) = self.fts_search(query_text, config.limit).await
+            && let Ok(fts_results) = self
+                .fts_search(query_text, config.limit, config.namespace.as_ref())
+                .await
         {
             all_results.extend(fts_results);
         }
@@ -108,8 +165,13 @@ impl HybridSearchManager {
         Ok(self.merge_results(all_results, config))
     }
 
-    async fn fts_search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-        let all_items = self.get_all_items().await?;
+    async fn fts_search(
+        &self,
+        query: &str,
+        limit: usize,
+        namespace: Option<&MemoryNamespace>,
+    ) -> Result<Vec<SearchResult>> {
+        let all_items = self.get_all_items(namespace).await?;
 
         let mut results = Vec::new();
         for item in all_items {
@@ -128,12 +190,13 @@ impl HybridSearchManager {
         Ok(results)
     }
 
-    async fn get_all_items(&self) -> Result<Vec<VectorItem>> {
+    async fn get_all_items(&self, namespace: Option<&MemoryNamespace>) -> Result<Vec<VectorItem>> {
         let stats = self.vector_store.stats().await?;
         let limit = stats.total_vectors.min(1000);
 
         let dummy_vector = vec![0.0; self.embedding_dimension];
-        let query = SearchQuery::new(dummy_vector).with_limit(limit);
+        let mut query = SearchQuery::new(dummy_vector).with_limit(limit);
+        query.filter = namespace.map(MemoryNamespace::filter);
 
         let results = self.vector_store.search(query).await?;
 
@@ -206,6 +269,23 @@ impl HybridSearchManager {
         Ok(())
     }
 
+    /// 列出满足过滤条件的全部记忆，按 id 顺序分页扫描
+    pub async fn list_memories(&self, filter: &Filter) -> Result<Vec<VectorItem>> {
+        let mut items: Vec<VectorItem> = Vec::new();
+        loop {
+            let after = items.last().map(|item| item.id.clone());
+            let page = self
+                .vector_store
+                .scan(Some(filter), after.as_deref(), LIST_PAGE)
+                .await?;
+            let done = page.len() < LIST_PAGE;
+            items.extend(page);
+            if done {
+                return Ok(items);
+            }
+        }
+    }
+
     pub async fn stats(&self) -> Result<openclaw_vector::StoreStats> {
         self.vector_store.stats().await
     }
@@ -215,6 +295,25 @@ impl HybridSearchManager {
         query_text: &str,
         query_vector: Option<Vec<f32>>,
         config: &HybridSearchConfig,
+    ) -> Result<Vec<UnifiedSearchResult>> {
+        let Some(stage) = &self.reranker else {
+            return self
+                .fused_unified_search(query_text, query_vector, config)
+                .await;
+        };
+        let candidates = self
+            .fused_unified_search(query_text, query_vector, &self.candidate_config(config))
+            .await?;
+        let mut ranked = stage.rerank(query_text, candidates).await;
+        ranked.truncate(config.limit);
+        Ok(ranked)
+    }
+
+    async fn fused_unified_search(
+        &self,
+        query_text: &str,
+        query_vector: Option<Vec<f32>>,
+        config: &HybridSearchConfig,
     ) -> Result<Vec<UnifiedSearchResult>> {
         let mut vector_results: Vec<UnifiedSearchResult> = Vec::new();
         let mut bm25_results: Vec<UnifiedSearchResult> = Vec::new();
@@ -225,6 +324,7 @@ impl HybridSearchManager {
                 let mut query = SearchQuery::new(vector);
                 query.limit = config.limit;
                 query.min_score = config.min_score;
+                query.filter = config.namespace.as_ref().map(MemoryNamespace::filter);
 
                 let results = self.vector_store.search(query).await?;
                 vector_results = results
@@ -244,7 +344,12 @@ impl HybridSearchManager {
 
         if config.enable_bm25 && config.bm25_weight > 0.0 {
             if let Some(ref bm25) = self.bm25_index {
-                let results = bm25.search(query_text, config.limit);
+                let results = match &config.namespace {
+                    Some(namespace) => {
+                        bm25.search_in_namespace(query_text, namespace, config.limit)
+                    }
+                    None => bm25.search(query_text, config.limit),
+                };
                 if let Ok(results) = results {
                     bm25_results = results
                         .into_iter()
@@ -256,12 +361,17 @@ impl HybridSearchManager {
             }
         }
 
-        if config.enable_knowledge_graph && config.knowledge_graph_weight > 0.0 {
+        if config.enable_knowledge_graph
+            && config.knowledge_graph_weight > 0.0
+            && config.namespace.is_none()
+        {
             if let Some(ref kg) = self.knowledge_graph {
                 let kg_guard = kg.read().await;
                 let query_lower = query_text.to_lowercase();
                 let query_terms: Vec<&str> = query_lower.split_whitespace().collect();
-                kg_results = self.knowledge_graph_search(&kg_guard, &query_terms);
+                kg_results = self
+                    .knowledge_graph_search(&kg_guard, &query_terms, config.limit)
+                    .await;
             }
         }
 
@@ -282,16 +392,20 @@ impl HybridSearchManager {
         Ok(fused)
     }
 
-    fn knowledge_graph_search(
+    /// 关键词命中的实体按置信度计分，其一跳邻居按关系权重折减后一并返回
+    async fn knowledge_graph_search(
         &self,
         kg: &KnowledgeGraph,
         query_terms: &[&str],
+        limit: usize,
     ) -> Vec<UnifiedSearchResult> {
-        let entities = kg.search_entities(query_terms);
+        let entities = kg.search_entities(query_terms).await;
+        let mut seen: HashSet<String> = entities.iter().map(|e| e.id.clone()).collect();
         let mut results: Vec<UnifiedSearchResult> = Vec::new();
 
+        let start_ids: Vec<String> = entities.iter().map(|e| e.id.clone()).collect();
         for entity in entities {
-            let content = format!("{}: {:?}", entity.name, entity.properties);
+            let content = entity.describe();
             results.push(UnifiedSearchResult::new(
                 entity.id,
                 content,
@@ -300,6 +414,25 @@ impl HybridSearchManager {
             ));
         }
 
+        let neighbors = GraphQuery::new().with_max_hops(1).with_limit(limit);
+        match kg.query(&start_ids, &neighbors).await {
+            Ok(matches) => {
+                for found in matches {
+                    if !seen.insert(found.entity.id.clone()) {
+                        continue;
+                    }
+                    let content = found.entity.describe();
+                    results.push(UnifiedSearchResult::new(
+                        found.entity.id,
+                        content,
+                        found.score * NEIGHBOR_DISCOUNT,
+                        SearchSource::KnowledgeGraph,
+                    ));
+                }
+            }
+            Err(e) => tracing::warn!("Knowledge graph neighbor query failed: {}", e),
+        }
+
         results.sort_by(|a, b| {
             b.score
                 .partial_cmp(&a.score)
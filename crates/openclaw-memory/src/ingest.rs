@@ -0,0 +1,16 @@
+//! 文档导入模块
+//!
+//! 从 PDF、DOCX、PPTX、XLSX、HTML、EPUB、Markdown 和纯文本中提取文本、
+//! 标题层级、表格和元数据，按结构切块后写入 BM25 + 向量混合索引。
+//! 新格式通过实现 [`DocumentExtractor`] 并注册到 [`ExtractorRegistry`] 接入。
+
+pub mod document;
+pub mod epub;
+pub mod html;
+pub mod office;
+pub mod pdf;
+pub mod pipeline;
+pub mod text;
+
+pub use document::*;
+pub use pipeline::*;
@@ -0,0 +1,286 @@
+//! 文档结构与提取器注册表
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use openclaw_core::{OpenClawError, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionKind {
+    Text,
+    Table,
+}
+
+/// 文档中的一段内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSection {
+    /// 所属标题路径，从一级标题开始
+    pub headings: Vec<String>,
+    pub kind: SectionKind,
+    pub text: String,
+    /// 页码、幻灯片、工作表等位置信息
+    pub location: Option<String>,
+}
+
+/// 提取结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractedDocument {
+    pub format: String,
+    pub title: Option<String>,
+    /// 作者、创建时间等元数据
+    pub metadata: HashMap<String, String>,
+    pub sections: Vec<DocumentSection>,
+}
+
+impl ExtractedDocument {
+    pub fn new(format: &str) -> Self {
+        Self {
+            format: format.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.iter().all(|s| s.text.trim().is_empty())
+    }
+}
+
+/// 文档提取器
+///
+/// 提取是 CPU 密集操作，由导入管线放到阻塞线程池中执行。
+pub trait DocumentExtractor: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// 支持的扩展名（小写、不含点）
+    fn extensions(&self) -> &[&'static str];
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument>;
+}
+
+/// 按扩展名查找提取器，后注册的覆盖先注册的
+#[derive(Clone, Default)]
+pub struct ExtractorRegistry {
+    extractors: HashMap<String, Arc<dyn DocumentExtractor>>,
+}
+
+impl ExtractorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册全部内置提取器
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(super::text::PlainTextExtractor));
+        registry.register(Arc::new(super::text::MarkdownExtractor));
+        registry.register(Arc::new(super::html::HtmlExtractor));
+        registry.register(Arc::new(super::pdf::PdfExtractor));
+        registry.register(Arc::new(super::office::DocxExtractor));
+        registry.register(Arc::new(super::office::PptxExtractor));
+        registry.register(Arc::new(super::office::XlsxExtractor));
+        registry.register(Arc::new(super::epub::EpubExtractor));
+        registry
+    }
+
+    pub fn register(&mut self, extractor: Arc<dyn DocumentExtractor>) {
+        for ext in extractor.extensions() {
+            self.extractors.insert(ext.to_string(), extractor.clone());
+        }
+    }
+
+    pub fn get(&self, extension: &str) -> Option<Arc<dyn DocumentExtractor>> {
+        self.extractors.get(&extension.to_lowercase()).cloned()
+    }
+
+    pub fn for_path(&self, path: &Path) -> Option<Arc<dyn DocumentExtractor>> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| self.get(ext))
+    }
+
+    /// 已支持的扩展名，按字母排序
+    pub fn extensions(&self) -> Vec<String> {
+        let mut extensions: Vec<String> = self.extractors.keys().cloned().collect();
+        extensions.sort();
+        extensions
+    }
+
+    pub fn extract(&self, path: &Path, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let extractor = self
+            .for_path(path)
+            .ok_or_else(|| OpenClawError::Parse(format!("不支持的文档格式: {}", path.display())))?;
+        extractor.extract(bytes)
+    }
+}
+
+/// 边解析边维护标题层级，供各提取器复用
+pub(crate) struct SectionBuilder {
+    document: ExtractedDocument,
+    headings: Vec<(usize, String)>,
+    location: Option<String>,
+}
+
+impl SectionBuilder {
+    pub fn new(format: &str) -> Self {
+        Self {
+            document: ExtractedDocument::new(format),
+            headings: Vec::new(),
+            location: None,
+        }
+    }
+
+    /// 切换到新的页面/幻灯片/工作表，清空标题层级
+    pub fn location(&mut self, location: impl Into<String>) {
+        self.location = Some(location.into());
+        self.headings.clear();
+    }
+
+    pub fn heading(&mut self, level: usize, text: &str) {
+        let text = normalize_whitespace(text);
+        if text.is_empty() {
+            return;
+        }
+        let level = level.max(1);
+        self.headings.retain(|(l, _)| *l < level);
+        self.headings.push((level, text.clone()));
+        if self.document.title.is_none() && level == 1 {
+            self.document.title = Some(text);
+        }
+    }
+
+    pub fn paragraph(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        // 同一标题下的连续段落合并为一节，由切块器按段落再切分
+        if let Some(last) = self.document.sections.last_mut()
+            && last.kind == SectionKind::Text
+            && last.location == self.location
+            && last.headings.len() == self.headings.len()
+            && last
+                .headings
+                .iter()
+                .zip(&self.headings)
+                .all(|(a, (_, b))| a == b)
+        {
+            last.text.push_str("\n\n");
+            last.text.push_str(text);
+            return;
+        }
+        self.push(SectionKind::Text, text.to_string());
+    }
+
+    /// 添加表格，第一行视为表头
+    pub fn table(&mut self, rows: Vec<Vec<String>>) {
+        let rows: Vec<Vec<String>> = rows
+            .into_iter()
+            .map(|row| {
+                row.iter()
+                    .map(|c| normalize_whitespace(c))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|row| row.iter().any(|c| !c.is_empty()))
+            .collect();
+        if rows.is_empty() {
+            return;
+        }
+        self.push(SectionKind::Table, render_table(&rows));
+    }
+
+    pub fn title(&mut self, title: &str) {
+        let title = normalize_whitespace(title);
+        if !title.is_empty() {
+            self.document.title = Some(title);
+        }
+    }
+
+    pub fn metadata(&mut self, key: &str, value: &str) {
+        let value = normalize_whitespace(value);
+        if !value.is_empty() {
+            self.document.metadata.insert(key.to_string(), value);
+        }
+    }
+
+    pub fn finish(self) -> ExtractedDocument {
+        self.document
+    }
+
+    fn push(&mut self, kind: SectionKind, text: String) {
+        self.document.sections.push(DocumentSection {
+            headings: self.headings.iter().map(|(_, h)| h.clone()).collect(),
+            kind,
+            text,
+            location: self.location.clone(),
+        });
+    }
+}
+
+/// 渲染为 Markdown 表格，列数按最宽的行补齐
+pub(crate) fn render_table(rows: &[Vec<String>]) -> String {
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let render_row = |row: &Vec<String>| {
+        let cells: Vec<String> = (0..width)
+            .map(|i| {
+                row.get(i)
+                    .map(|c| c.replace('|', "\\|"))
+                    .unwrap_or_default()
+            })
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = vec![render_row(&rows[0])];
+    lines.push(format!("|{}", " --- |".repeat(width)));
+    lines.extend(rows[1..].iter().map(render_row));
+    lines.join("\n")
+}
+
+pub(crate) fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_section_builder_tracks_heading_path() {
+        let mut builder = SectionBuilder::new("test");
+        builder.heading(1, "Handbook");
+        builder.paragraph("Welcome.");
+        builder.heading(2, "Setup");
+        builder.paragraph("Install it.");
+        builder.paragraph("Then run it.");
+        builder.heading(2, "Usage");
+        builder.table(vec![
+            vec!["Command".to_string(), "Effect".to_string()],
+            vec!["run".to_string(), "starts | serves".to_string()],
+        ]);
+
+        let doc = builder.finish();
+        assert_eq!(doc.title.as_deref(), Some("Handbook"));
+        assert_eq!(doc.sections.len(), 3);
+        assert_eq!(doc.sections[1].headings, vec!["Handbook", "Setup"]);
+        assert_eq!(doc.sections[1].text, "Install it.\n\nThen run it.");
+        assert_eq!(doc.sections[2].kind, SectionKind::Table);
+        assert_eq!(doc.sections[2].headings, vec!["Handbook", "Usage"]);
+        assert_eq!(
+            doc.sections[2].text,
+            "| Command | Effect |\n| --- | --- |\n| run | starts \\| serves |"
+        );
+    }
+
+    #[test]
+    fn test_registry_lookup_by_extension() {
+        let registry = ExtractorRegistry::with_defaults();
+        for ext in ["pdf", "docx", "pptx", "xlsx", "html", "epub", "md", "txt"] {
+            assert!(registry.get(ext).is_some(), "missing extractor for {}", ext);
+        }
+        assert!(registry.for_path(Path::new("Report.PDF")).is_some());
+        assert!(registry.for_path(Path::new("image.png")).is_none());
+    }
+}
@@ -0,0 +1,196 @@
+//! EPUB 提取
+//!
+//! 通过 container.xml 定位 OPF，按 spine 顺序解析各章节的 XHTML。
+
+use std::collections::HashMap;
+
+use openclaw_core::{OpenClawError, Result};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use super::document::{DocumentExtractor, ExtractedDocument, SectionBuilder};
+use super::html::parse_html_into;
+use super::office::{attr_value, open_archive, read_entry, xml_error};
+
+pub struct EpubExtractor;
+
+impl DocumentExtractor for EpubExtractor {
+    fn name(&self) -> &str {
+        "epub"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["epub"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let mut archive = open_archive(bytes)?;
+
+        let container = read_entry(&mut archive, "META-INF/container.xml")?
+            .ok_or_else(|| OpenClawError::Parse("EPUB 缺少 META-INF/container.xml".to_string()))?;
+        let opf_path = find_rootfile(&container)?
+            .ok_or_else(|| OpenClawError::Parse("EPUB 未声明 rootfile".to_string()))?;
+        let opf = read_entry(&mut archive, &opf_path)?
+            .ok_or_else(|| OpenClawError::Parse(format!("EPUB 缺少 {}", opf_path)))?;
+        let package = parse_package(&opf)?;
+
+        let base = opf_path
+            .rfind('/')
+            .map(|i| &opf_path[..=i])
+            .unwrap_or_default();
+
+        let mut builder = SectionBuilder::new("epub");
+        for (key, value) in &package.metadata {
+            builder.metadata(key, value);
+        }
+
+        let mut chapter = 0;
+        for idref in &package.spine {
+            let Some(href) = package.manifest.get(idref) else {
+                continue;
+            };
+            let path = format!("{}{}", base, decode_href(href));
+            if let Some(xhtml) = read_entry(&mut archive, &path)? {
+                chapter += 1;
+                builder.location(format!("chapter {}", chapter));
+                parse_html_into(&xhtml, &mut builder);
+            }
+        }
+
+        // 章节中的 <title> 和一级标题不代表整本书
+        if let Some(title) = &package.title {
+            builder.title(title);
+        }
+        Ok(builder.finish())
+    }
+}
+
+#[derive(Default)]
+struct Package {
+    title: Option<String>,
+    metadata: Vec<(String, String)>,
+    /// 清单 id -> 相对 OPF 的路径
+    manifest: HashMap<String, String>,
+    /// 阅读顺序
+    spine: Vec<String>,
+}
+
+fn find_rootfile(container: &str) -> Result<Option<String>> {
+    let mut reader = Reader::from_str(container);
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"rootfile" => {
+                return Ok(attr_value(&e, b"full-path"));
+            }
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+fn parse_package(opf: &str) -> Result<Package> {
+    let mut reader = Reader::from_str(opf);
+    let mut package = Package::default();
+    let mut in_metadata = false;
+    let mut current: Option<String> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"item" => {
+                if let (Some(id), Some(href)) = (attr_value(&e, b"id"), attr_value(&e, b"href")) {
+                    package.manifest.insert(id, href);
+                }
+            }
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"itemref" => {
+                if let Some(idref) = attr_value(&e, b"idref") {
+                    package.spine.push(idref);
+                }
+            }
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                if name == "metadata" {
+                    in_metadata = true;
+                } else if in_metadata {
+                    current = Some(name);
+                    text.clear();
+                }
+            }
+            Event::Text(t) if current.is_some() => text.push_str(&t.unescape().map_err(xml_error)?),
+            Event::End(e) => {
+                if e.local_name().as_ref() == b"metadata" {
+                    in_metadata = false;
+                }
+                match current.take().as_deref() {
+                    Some("title") if package.title.is_none() => {
+                        package.title = Some(text.trim().to_string());
+                    }
+                    Some("creator") => package.metadata.push(("author".to_string(), text.clone())),
+                    Some(key @ ("language" | "publisher" | "date" | "subject" | "description")) => {
+                        package.metadata.push((key.to_string(), text.clone()));
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(package)
+}
+
+/// 去掉锚点并解码常见的百分号转义
+fn decode_href(href: &str) -> String {
+    let href = href.split('#').next().unwrap_or(href);
+    let bytes = href.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = href
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::office::tests::build_zip;
+
+    #[test]
+    fn test_epub_spine_order_and_metadata() {
+        let container = r#"<container><rootfiles><rootfile full-path="OEBPS/content.opf"/></rootfiles></container>"#;
+        let opf = r#"<package xmlns:dc="dc"><metadata><dc:title>Field Guide</dc:title><dc:creator>Ana</dc:creator></metadata>
+<manifest><item id="c2" href="Text/two.xhtml"/><item id="c1" href="Text/chapter%20one.xhtml"/></manifest>
+<spine><itemref idref="c1"/><itemref idref="c2"/></spine></package>"#;
+        let bytes = build_zip(&[
+            ("META-INF/container.xml", container),
+            ("OEBPS/content.opf", opf),
+            (
+                "OEBPS/Text/chapter one.xhtml",
+                "<html><body><h1>Birds</h1><p>Look up.</p></body></html>",
+            ),
+            (
+                "OEBPS/Text/two.xhtml",
+                "<html><body><h1>Trees</h1><p>Look around.</p></body></html>",
+            ),
+        ]);
+
+        let doc = EpubExtractor.extract(&bytes).unwrap();
+        assert_eq!(doc.title.as_deref(), Some("Field Guide"));
+        assert_eq!(doc.metadata.get("author").unwrap(), "Ana");
+        assert_eq!(doc.sections[0].location.as_deref(), Some("chapter 1"));
+        assert_eq!(doc.sections[0].headings, vec!["Birds"]);
+        assert_eq!(doc.sections[1].location.as_deref(), Some("chapter 2"));
+        assert_eq!(doc.sections[1].text, "Look around.");
+    }
+}
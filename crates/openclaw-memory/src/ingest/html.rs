@@ -0,0 +1,364 @@
+//! HTML 提取
+//!
+//! 轻量的容错解析：只关心标题、段落、列表和表格，脚本与样式整体跳过。
+//! EPUB 章节同样经由此处解析。
+
+use openclaw_core::Result;
+
+use super::document::{DocumentExtractor, ExtractedDocument, SectionBuilder, normalize_whitespace};
+
+pub struct HtmlExtractor;
+
+impl DocumentExtractor for HtmlExtractor {
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["html", "htm", "xhtml"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let html = String::from_utf8_lossy(bytes);
+        let mut builder = SectionBuilder::new("html");
+        parse_html_into(&html, &mut builder);
+        Ok(builder.finish())
+    }
+}
+
+enum Token<'a> {
+    Open { name: String, attrs: &'a str },
+    Close(String),
+    Text(&'a str),
+}
+
+/// 块级元素，开闭时结束当前段落
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "main",
+    "aside",
+    "nav",
+    "blockquote",
+    "pre",
+    "ul",
+    "ol",
+    "li",
+    "dl",
+    "dt",
+    "dd",
+    "figure",
+    "figcaption",
+    "br",
+    "hr",
+    "form",
+    "address",
+    "body",
+];
+
+/// 将 HTML 解析进 `builder`，标题、段落和表格按出现顺序追加
+pub(crate) fn parse_html_into(html: &str, builder: &mut SectionBuilder) {
+    let mut paragraph = String::new();
+    let mut heading: Option<(usize, String)> = None;
+    let mut title: Option<String> = None;
+    let mut table_depth = 0usize;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut cell: Option<String> = None;
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(raw) => {
+                let text = decode_entities(raw);
+                if let Some(title) = title.as_mut() {
+                    title.push_str(&text);
+                } else if let Some((_, heading)) = heading.as_mut() {
+                    heading.push_str(&text);
+                } else if let Some(cell) = cell.as_mut() {
+                    cell.push_str(&text);
+                } else {
+                    paragraph.push_str(&text);
+                }
+            }
+            Token::Open { name, attrs } => match name.as_str() {
+                "title" => title = Some(String::new()),
+                "meta" => {
+                    let key = attr(attrs, "name").or_else(|| attr(attrs, "property"));
+                    if let (Some(key), Some(content)) = (key, attr(attrs, "content")) {
+                        match key.to_lowercase().as_str() {
+                            "author" | "dc.creator" => builder.metadata("author", &content),
+                            "description" | "og:description" => {
+                                builder.metadata("description", &content)
+                            }
+                            "keywords" => builder.metadata("keywords", &content),
+                            _ => {}
+                        }
+                    }
+                }
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6" if table_depth == 0 => {
+                    flush(builder, &mut paragraph);
+                    heading = Some((name[1..].parse().unwrap_or(1), String::new()));
+                }
+                "table" => {
+                    if table_depth == 0 {
+                        flush(builder, &mut paragraph);
+                        rows.clear();
+                    }
+                    table_depth += 1;
+                }
+                "tr" if table_depth == 1 => rows.push(Vec::new()),
+                "td" | "th" if table_depth == 1 => cell = Some(String::new()),
+                "li" => {
+                    flush(builder, &mut paragraph);
+                    paragraph.push_str("- ");
+                }
+                _ if BLOCK_TAGS.contains(&name.as_str()) => {
+                    if let Some(cell) = cell.as_mut() {
+                        cell.push(' ');
+                    } else {
+                        flush(builder, &mut paragraph);
+                    }
+                }
+                _ => {}
+            },
+            Token::Close(name) => match name.as_str() {
+                "title" => {
+                    if let Some(title) = title.take() {
+                        builder.title(&title);
+                    }
+                }
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                    if let Some((level, text)) = heading.take() {
+                        builder.heading(level, &text);
+                    }
+                }
+                "td" | "th" if table_depth == 1 => {
+                    if let (Some(text), Some(row)) = (cell.take(), rows.last_mut()) {
+                        row.push(normalize_whitespace(&text));
+                    }
+                }
+                "table" if table_depth > 0 => {
+                    table_depth -= 1;
+                    if table_depth == 0 {
+                        cell = None;
+                        builder.table(std::mem::take(&mut rows));
+                    }
+                }
+                _ if BLOCK_TAGS.contains(&name.as_str()) && cell.is_none() => {
+                    flush(builder, &mut paragraph);
+                }
+                _ => {}
+            },
+        }
+    }
+
+    if let Some((level, text)) = heading {
+        builder.heading(level, &text);
+    }
+    if !rows.is_empty() {
+        builder.table(rows);
+    }
+    flush(builder, &mut paragraph);
+}
+
+fn flush(builder: &mut SectionBuilder, paragraph: &mut String) {
+    let text = normalize_whitespace(paragraph);
+    if text != "-" {
+        builder.paragraph(&text);
+    }
+    paragraph.clear();
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    // ASCII 小写不改变字节偏移，用于大小写无关地查找结束标签
+    let lower = html.to_ascii_lowercase();
+    let bytes = html.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < html.len() {
+        if bytes[i] != b'<' {
+            let end = html[i..].find('<').map(|p| i + p).unwrap_or(html.len());
+            tokens.push(Token::Text(&html[i..end]));
+            i = end;
+            continue;
+        }
+
+        if lower[i..].starts_with("<!--") {
+            i = lower[i..]
+                .find("-->")
+                .map(|p| i + p + 3)
+                .unwrap_or(html.len());
+            continue;
+        }
+
+        let next = bytes.get(i + 1).copied().unwrap_or(b' ');
+        if !(next.is_ascii_alphabetic() || matches!(next, b'/' | b'!' | b'?')) {
+            // 不是标签，例如 "a < b"
+            tokens.push(Token::Text(&html[i..i + 1]));
+            i += 1;
+            continue;
+        }
+
+        let Some(close) = html[i..].find('>').map(|p| i + p) else {
+            break;
+        };
+        let tag = &html[i + 1..close];
+        i = close + 1;
+
+        if tag.starts_with('!') || tag.starts_with('?') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim().to_ascii_lowercase()));
+            continue;
+        }
+
+        let name_end = tag
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(tag.len());
+        let name = tag[..name_end].to_ascii_lowercase();
+        let attrs = &tag[name_end..];
+
+        if name == "script" || name == "style" {
+            let end_tag = format!("</{}", name);
+            i = lower[i..]
+                .find(&end_tag)
+                .and_then(|p| lower[i + p..].find('>').map(|q| i + p + q + 1))
+                .unwrap_or(html.len());
+            continue;
+        }
+
+        let self_closing = attrs.trim_end().ends_with('/');
+        tokens.push(Token::Open {
+            name: name.clone(),
+            attrs,
+        });
+        if self_closing {
+            tokens.push(Token::Close(name));
+        }
+    }
+
+    tokens
+}
+
+/// 读取属性值，属性名大小写无关
+fn attr(attrs: &str, key: &str) -> Option<String> {
+    let lower = attrs.to_ascii_lowercase();
+    let mut search = 0;
+
+    while let Some(pos) = lower[search..].find(key).map(|p| search + p) {
+        search = pos + key.len();
+        let boundary = pos == 0 || lower.as_bytes()[pos - 1].is_ascii_whitespace();
+        let rest = attrs[search..].trim_start();
+        if !boundary || !rest.starts_with('=') {
+            continue;
+        }
+
+        let value = rest[1..].trim_start();
+        let raw = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or(""),
+            _ => value
+                .split(|c: char| c.is_whitespace() || c == '>')
+                .next()
+                .unwrap_or(""),
+        };
+        return Some(decode_entities(raw));
+    }
+    None
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| {
+                let entity = &rest[1..1 + end];
+                let ch = match entity {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    "ndash" => Some('–'),
+                    "mdash" => Some('—'),
+                    "hellip" => Some('…'),
+                    "copy" => Some('©'),
+                    "reg" => Some('®'),
+                    _ => entity
+                        .strip_prefix("#x")
+                        .or_else(|| entity.strip_prefix("#X"))
+                        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                        .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                        .and_then(char::from_u32),
+                };
+                ch.map(|ch| (ch, end + 2))
+            });
+
+        match decoded {
+            Some((ch, len)) => {
+                out.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::document::SectionKind;
+
+    #[test]
+    fn test_html_structure_and_metadata() {
+        let html = r#"<!DOCTYPE html>
+<html><head><title>Ops &amp; Runbook</title>
+<meta name="author" content="Platform Team">
+<style>p { color: red; }</style><script>var x = "<p>nope</p>";</script>
+</head><body>
+<h1>Runbook</h1>
+<p>Restart the <b>gateway</b> if it&#39;s stuck.</p>
+<h2>Ports</h2>
+<table><tr><th>Service</th><th>Port</th></tr><tr><td>gateway</td><td>18789</td></tr></table>
+<ul><li>Check logs</li><li>Page on-call</li></ul>
+</body></html>"#;
+
+        let doc = HtmlExtractor.extract(html.as_bytes()).unwrap();
+        assert_eq!(doc.title.as_deref(), Some("Ops & Runbook"));
+        assert_eq!(doc.metadata.get("author").unwrap(), "Platform Team");
+        assert_eq!(doc.sections[0].text, "Restart the gateway if it's stuck.");
+        assert_eq!(doc.sections[0].headings, vec!["Runbook"]);
+        assert_eq!(doc.sections[1].kind, SectionKind::Table);
+        assert!(doc.sections[1].text.contains("| gateway | 18789 |"));
+        assert_eq!(doc.sections[2].text, "- Check logs\n\n- Page on-call");
+        assert!(!doc.sections.iter().any(|s| s.text.contains("nope")));
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("a &lt; b &#x4E2D;&#25991; &unknown; &"),
+            "a < b 中文 &unknown; &"
+        );
+    }
+}
@@ -0,0 +1,604 @@
+//! Office Open XML 提取（DOCX / PPTX / XLSX）
+//!
+//! 三种格式都是 zip 包内的 XML，按各自的文档模型流式解析。
+
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+use openclaw_core::{OpenClawError, Result};
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use zip::ZipArchive;
+
+use super::document::{DocumentExtractor, ExtractedDocument, SectionBuilder};
+
+/// 单个压缩包条目解压后的上限
+pub(crate) const MAX_ENTRY_BYTES: u64 = 32 * 1024 * 1024;
+/// 同一文档所有条目解压后的总上限，防止压缩炸弹
+pub(crate) const MAX_UNCOMPRESSED_BYTES: u64 = 128 * 1024 * 1024;
+
+/// 文档压缩包，记录剩余可解压的字节数
+pub(crate) struct Archive<'a> {
+    zip: ZipArchive<Cursor<&'a [u8]>>,
+    remaining: u64,
+}
+
+pub struct DocxExtractor;
+
+impl DocumentExtractor for DocxExtractor {
+    fn name(&self) -> &str {
+        "docx"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["docx"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let mut archive = open_archive(bytes)?;
+        let xml = read_entry(&mut archive, "word/document.xml")?
+            .ok_or_else(|| OpenClawError::Parse("DOCX 缺少 word/document.xml".to_string()))?;
+
+        let mut builder = SectionBuilder::new("docx");
+        parse_docx_body(&xml, &mut builder)?;
+        apply_core_properties(&mut archive, &mut builder)?;
+        Ok(builder.finish())
+    }
+}
+
+fn parse_docx_body(xml: &str, builder: &mut SectionBuilder) -> Result<()> {
+    let mut reader = Reader::from_str(xml);
+    let mut paragraph = String::new();
+    let mut level: Option<usize> = None;
+    let mut in_text = false;
+    let mut table_depth = 0usize;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut cell: Option<String> = None;
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"p" => {
+                    paragraph.clear();
+                    level = None;
+                }
+                b"t" => in_text = true,
+                b"tbl" => {
+                    if table_depth == 0 {
+                        rows.clear();
+                    }
+                    table_depth += 1;
+                }
+                b"tr" if table_depth == 1 => rows.push(Vec::new()),
+                b"tc" if table_depth == 1 => cell = Some(String::new()),
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"pStyle" => {
+                    if let Some(style) = attr_value(&e, b"val") {
+                        level = heading_level(&style).or(level);
+                    }
+                }
+                b"outlineLvl" => {
+                    if let Some(lvl) = attr_value(&e, b"val").and_then(|v| v.parse::<usize>().ok())
+                    {
+                        level = Some(lvl + 1);
+                    }
+                }
+                b"tab" => paragraph.push('\t'),
+                b"br" | b"cr" => paragraph.push('\n'),
+                _ => {}
+            },
+            Event::Text(t) if in_text => {
+                paragraph.push_str(&t.unescape().map_err(xml_error)?);
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => {
+                    if let Some(cell) = cell.as_mut() {
+                        cell.push_str(&paragraph);
+                        cell.push(' ');
+                    } else if let Some(level) = level {
+                        builder.heading(level, &paragraph);
+                    } else {
+                        builder.paragraph(&paragraph);
+                    }
+                    paragraph.clear();
+                }
+                b"tc" if table_depth == 1 => {
+                    if let (Some(text), Some(row)) = (cell.take(), rows.last_mut()) {
+                        row.push(text);
+                    }
+                }
+                b"tbl" if table_depth > 0 => {
+                    table_depth -= 1;
+                    if table_depth == 0 {
+                        builder.table(std::mem::take(&mut rows));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 从段落样式推断标题级别，兼容 "Heading1"、"heading 2" 和 "Title"
+fn heading_level(style: &str) -> Option<usize> {
+    let style = style.to_lowercase();
+    if style == "title" {
+        return Some(1);
+    }
+    style
+        .strip_prefix("heading")
+        .and_then(|rest| rest.trim().parse::<usize>().ok())
+        .filter(|level| (1..=9).contains(level))
+}
+
+pub struct PptxExtractor;
+
+impl DocumentExtractor for PptxExtractor {
+    fn name(&self) -> &str {
+        "pptx"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["pptx"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let mut archive = open_archive(bytes)?;
+
+        let mut slides: Vec<(usize, String)> = archive
+            .zip
+            .file_names()
+            .filter_map(|name| {
+                let number = name
+                    .strip_prefix("ppt/slides/slide")?
+                    .strip_suffix(".xml")?
+                    .parse()
+                    .ok()?;
+                Some((number, name.to_string()))
+            })
+            .collect();
+        slides.sort();
+
+        let mut builder = SectionBuilder::new("pptx");
+        for (number, name) in slides {
+            if let Some(xml) = read_entry(&mut archive, &name)? {
+                builder.location(format!("slide {}", number));
+                parse_slide(&xml, &mut builder)?;
+            }
+        }
+        apply_core_properties(&mut archive, &mut builder)?;
+        Ok(builder.finish())
+    }
+}
+
+fn parse_slide(xml: &str, builder: &mut SectionBuilder) -> Result<()> {
+    let mut reader = Reader::from_str(xml);
+    let mut paragraph = String::new();
+    let mut shape_paragraphs: Vec<String> = Vec::new();
+    let mut is_title = false;
+    let mut in_text = false;
+    let mut in_table = false;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut cell: Option<String> = None;
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"ph" => {
+                let kind = attr_value(&e, b"type");
+                is_title = matches!(kind.as_deref(), Some("title" | "ctrTitle"));
+            }
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"sp" => {
+                    is_title = false;
+                    shape_paragraphs.clear();
+                }
+                b"t" => in_text = true,
+                b"tbl" => {
+                    in_table = true;
+                    rows.clear();
+                }
+                b"tr" if in_table => rows.push(Vec::new()),
+                b"tc" if in_table => cell = Some(String::new()),
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"br" => paragraph.push('\n'),
+            Event::Text(t) if in_text => {
+                paragraph.push_str(&t.unescape().map_err(xml_error)?);
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => {
+                    let text = std::mem::take(&mut paragraph);
+                    if let Some(cell) = cell.as_mut() {
+                        cell.push_str(&text);
+                        cell.push(' ');
+                    } else {
+                        shape_paragraphs.push(text);
+                    }
+                }
+                b"sp" => {
+                    if is_title {
+                        builder.heading(1, &shape_paragraphs.join(" "));
+                    } else {
+                        for text in shape_paragraphs.drain(..) {
+                            builder.paragraph(&text);
+                        }
+                    }
+                    shape_paragraphs.clear();
+                    is_title = false;
+                }
+                b"tc" if in_table => {
+                    if let (Some(text), Some(row)) = (cell.take(), rows.last_mut()) {
+                        row.push(text);
+                    }
+                }
+                b"tbl" => {
+                    in_table = false;
+                    builder.table(std::mem::take(&mut rows));
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+pub struct XlsxExtractor;
+
+impl DocumentExtractor for XlsxExtractor {
+    fn name(&self) -> &str {
+        "xlsx"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["xlsx"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let mut archive = open_archive(bytes)?;
+        let workbook = read_entry(&mut archive, "xl/workbook.xml")?
+            .ok_or_else(|| OpenClawError::Parse("XLSX 缺少 xl/workbook.xml".to_string()))?;
+        let relationships = match read_entry(&mut archive, "xl/_rels/workbook.xml.rels")? {
+            Some(xml) => parse_relationships(&xml)?,
+            None => HashMap::new(),
+        };
+        let shared_strings = match read_entry(&mut archive, "xl/sharedStrings.xml")? {
+            Some(xml) => parse_shared_strings(&xml)?,
+            None => Vec::new(),
+        };
+
+        let mut builder = SectionBuilder::new("xlsx");
+        for (name, rel_id) in parse_sheets(&workbook)? {
+            let Some(target) = relationships.get(&rel_id) else {
+                continue;
+            };
+            let path = match target.strip_prefix('/') {
+                Some(absolute) => absolute.to_string(),
+                None => format!("xl/{}", target),
+            };
+            if let Some(xml) = read_entry(&mut archive, &path)? {
+                builder.location(format!("sheet {}", name));
+                builder.table(parse_sheet_rows(&xml, &shared_strings)?);
+            }
+        }
+        apply_core_properties(&mut archive, &mut builder)?;
+        Ok(builder.finish())
+    }
+}
+
+/// 单元格列数上限，避免稀疏引用（如 XFD1）撑出巨大的行
+const MAX_COLUMNS: usize = 256;
+
+/// 工作表名称与关系 ID，按工作簿中的顺序
+fn parse_sheets(xml: &str) -> Result<Vec<(String, String)>> {
+    let mut reader = Reader::from_str(xml);
+    let mut sheets = Vec::new();
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+                if let (Some(name), Some(id)) = (attr_value(&e, b"name"), attr_value(&e, b"id")) {
+                    sheets.push((name, id));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(sheets)
+}
+
+fn parse_relationships(xml: &str) -> Result<HashMap<String, String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut relationships = HashMap::new();
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+                if let (Some(id), Some(target)) = (attr_value(&e, b"Id"), attr_value(&e, b"Target"))
+                {
+                    relationships.insert(id, target);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(relationships)
+}
+
+fn parse_shared_strings(xml: &str) -> Result<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    // 注音（rPh）中的文本不属于单元格内容
+    let mut in_phonetic = false;
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"si" => current.clear(),
+                b"t" => in_text = !in_phonetic,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Event::Text(t) if in_text => current.push_str(&t.unescape().map_err(xml_error)?),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"si" => strings.push(std::mem::take(&mut current)),
+                b"t" => in_text = false,
+                b"rPh" => in_phonetic = false,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+fn parse_sheet_rows(xml: &str, shared_strings: &[String]) -> Result<Vec<Vec<String>>> {
+    let mut reader = Reader::from_str(xml);
+    let mut rows = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut column = 0usize;
+    let mut cell_type = String::new();
+    let mut value = String::new();
+    let mut in_value = false;
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"row" => row.clear(),
+                b"c" => {
+                    column = attr_value(&e, b"r")
+                        .and_then(|r| column_index(&r))
+                        .unwrap_or(row.len());
+                    cell_type = attr_value(&e, b"t").unwrap_or_default();
+                    value.clear();
+                }
+                b"v" | b"t" => in_value = true,
+                _ => {}
+            },
+            Event::Text(t) if in_value => value.push_str(&t.unescape().map_err(xml_error)?),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"v" | b"t" => in_value = false,
+                b"c" => {
+                    let text = match cell_type.as_str() {
+                        "s" => value
+                            .trim()
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|i| shared_strings.get(i).cloned())
+                            .unwrap_or_default(),
+                        "b" => (if value.trim() == "1" { "TRUE" } else { "FALSE" }).to_string(),
+                        _ => value.clone(),
+                    };
+                    if !text.trim().is_empty() && column < MAX_COLUMNS {
+                        if row.len() <= column {
+                            row.resize(column + 1, String::new());
+                        }
+                        row[column] = text;
+                    }
+                }
+                b"row" => {
+                    if row.iter().any(|c| !c.trim().is_empty()) {
+                        rows.push(std::mem::take(&mut row));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(rows)
+}
+
+/// "B12" -> 1
+fn column_index(reference: &str) -> Option<usize> {
+    let letters: String = reference
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    if letters.is_empty() {
+        return None;
+    }
+    let index = letters
+        .to_ascii_uppercase()
+        .bytes()
+        .fold(0usize, |acc, b| acc * 26 + (b - b'A' + 1) as usize);
+    Some(index - 1)
+}
+
+/// 读取 docProps/core.xml 中的标题、作者等元数据
+fn apply_core_properties(archive: &mut Archive<'_>, builder: &mut SectionBuilder) -> Result<()> {
+    let Some(xml) = read_entry(archive, "docProps/core.xml")? else {
+        return Ok(());
+    };
+
+    let mut reader = Reader::from_str(&xml);
+    let mut current: Option<String> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) => {
+                current = Some(String::from_utf8_lossy(e.local_name().as_ref()).into_owned());
+                text.clear();
+            }
+            Event::Text(t) if current.is_some() => text.push_str(&t.unescape().map_err(xml_error)?),
+            Event::End(_) => {
+                match current.take().as_deref() {
+                    Some("title") => builder.title(&text),
+                    Some("creator") => builder.metadata("author", &text),
+                    Some(
+                        key @ ("created" | "modified" | "keywords" | "subject" | "description"),
+                    ) => builder.metadata(key, &text),
+                    _ => {}
+                }
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn open_archive(bytes: &[u8]) -> Result<Archive<'_>> {
+    let zip = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| OpenClawError::Parse(format!("无法打开文档压缩包: {}", e)))?;
+    Ok(Archive {
+        zip,
+        remaining: MAX_UNCOMPRESSED_BYTES,
+    })
+}
+
+/// 读取条目文本；单个条目或累计解压量超过上限时报错
+pub(crate) fn read_entry(archive: &mut Archive<'_>, name: &str) -> Result<Option<String>> {
+    let limit = archive.remaining.min(MAX_ENTRY_BYTES);
+    let file = match archive.zip.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(OpenClawError::Parse(format!("读取 {} 失败: {}", name, e))),
+    };
+
+    // 不信任条目头中声明的大小，多读一个字节判断是否超限
+    let mut bytes = Vec::new();
+    file.take(limit + 1).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > limit {
+        return Err(OpenClawError::Parse(format!(
+            "{} 解压后超过上限（单个条目 {} 字节，整个文档 {} 字节）",
+            name, MAX_ENTRY_BYTES, MAX_UNCOMPRESSED_BYTES
+        )));
+    }
+    archive.remaining -= bytes.len() as u64;
+
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|e| OpenClawError::Parse(format!("{} 不是有效的 UTF-8: {}", name, e)))
+}
+
+pub(crate) fn attr_value(element: &BytesStart<'_>, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == name)
+        .and_then(|attr| attr.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+pub(crate) fn xml_error(e: impl std::fmt::Display) -> OpenClawError {
+    OpenClawError::Parse(format!("XML 解析失败: {}", e))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::ingest::document::SectionKind;
+    use std::io::Write;
+
+    pub(crate) fn build_zip(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buffer);
+            for (name, content) in entries {
+                writer
+                    .start_file(*name, zip::write::FileOptions::default())
+                    .unwrap();
+                writer.write_all(content.as_bytes()).unwrap();
+            }
+            writer.finish().unwrap();
+        }
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn test_docx_headings_and_tables() {
+        let document = r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>
+<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Onboarding</w:t></w:r></w:p>
+<w:p><w:r><w:t xml:space="preserve">Read the </w:t></w:r><w:r><w:t>handbook &amp; wiki.</w:t></w:r></w:p>
+<w:p><w:pPr><w:pStyle w:val="Heading2"/></w:pPr><w:r><w:t>Contacts</w:t></w:r></w:p>
+<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Team</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Owner</w:t></w:r></w:p></w:tc></w:tr>
+<w:tr><w:tc><w:p><w:r><w:t>Infra</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Alice</w:t></w:r></w:p></w:tc></w:tr></w:tbl>
+</w:body></w:document>"#;
+        let core = r#"<cp:coreProperties xmlns:cp="cp" xmlns:dc="dc"><dc:creator>HR</dc:creator></cp:coreProperties>"#;
+        let bytes = build_zip(&[("word/document.xml", document), ("docProps/core.xml", core)]);
+
+        let doc = DocxExtractor.extract(&bytes).unwrap();
+        assert_eq!(doc.title.as_deref(), Some("Onboarding"));
+        assert_eq!(doc.metadata.get("author").unwrap(), "HR");
+        assert_eq!(doc.sections[0].text, "Read the handbook & wiki.");
+        assert_eq!(doc.sections[1].kind, SectionKind::Table);
+        assert_eq!(doc.sections[1].headings, vec!["Onboarding", "Contacts"]);
+        assert!(doc.sections[1].text.contains("| Infra | Alice |"));
+    }
+
+    #[test]
+    fn test_oversized_entry_is_rejected() {
+        let document = "a".repeat(MAX_ENTRY_BYTES as usize + 1);
+        let bytes = build_zip(&[("word/document.xml", &document)]);
+        assert!(bytes.len() < 1024 * 1024);
+
+        let err = DocxExtractor.extract(&bytes).unwrap_err();
+        assert!(err.to_string().contains("上限"));
+
+        // 累计解压量用尽后，小条目同样被拒绝
+        let small = build_zip(&[("docProps/core.xml", "<props/>")]);
+        let mut archive = open_archive(&small).unwrap();
+        archive.remaining = 4;
+        assert!(read_entry(&mut archive, "docProps/core.xml").is_err());
+    }
+
+    #[test]
+    fn test_xlsx_sheets_with_shared_strings() {
+        let workbook = r#"<workbook xmlns:r="rel"><sheets><sheet name="Budget" sheetId="1" r:id="rId1"/></sheets></workbook>"#;
+        let rels = r#"<Relationships><Relationship Id="rId1" Target="worksheets/sheet1.xml"/></Relationships>"#;
+        let shared = r#"<sst><si><t>Item</t></si><si><t>Cost</t></si><si><r><t>GP</t></r><r><t>U</t></r></si></sst>"#;
+        let sheet = r#"<worksheet><sheetData>
+<row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row>
+<row r="2"><c r="A2" t="s"><v>2</v></c><c r="C2"><v>1200</v></c></row>
+<row r="3"></row>
+</sheetData></worksheet>"#;
+        let bytes = build_zip(&[
+            ("xl/workbook.xml", workbook),
+            ("xl/_rels/workbook.xml.rels", rels),
+            ("xl/sharedStrings.xml", shared),
+            ("xl/worksheets/sheet1.xml", sheet),
+        ]);
+
+        let doc = XlsxExtractor.extract(&bytes).unwrap();
+        assert_eq!(doc.sections.len(), 1);
+        assert_eq!(doc.sections[0].location.as_deref(), Some("sheet Budget"));
+        assert_eq!(
+            doc.sections[0].text,
+            "| Item | Cost |  |\n| --- | --- | --- |\n| GPU |  | 1200 |"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! PDF 提取
+//!
+//! PDF 没有可靠的结构信息，按页提取文本并以页码作为引用位置。
+
+use lopdf::{Document, Object};
+use openclaw_core::{OpenClawError, Result};
+
+use super::document::{DocumentExtractor, ExtractedDocument, SectionBuilder};
+
+pub struct PdfExtractor;
+
+impl DocumentExtractor for PdfExtractor {
+    fn name(&self) -> &str {
+        "pdf"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["pdf"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let document = Document::load_mem(bytes)
+            .map_err(|e| OpenClawError::Parse(format!("无法解析 PDF: {}", e)))?;
+        if document.is_encrypted() {
+            return Err(OpenClawError::Parse("不支持加密的 PDF".to_string()));
+        }
+
+        let mut builder = SectionBuilder::new("pdf");
+        for (number, _) in document.get_pages() {
+            // 单页失败（如缺字体映射）不影响其余页面
+            let text = match document.extract_text(&[number]) {
+                Ok(text) => text,
+                Err(e) => {
+                    tracing::debug!("PDF 第 {} 页提取失败: {}", number, e);
+                    continue;
+                }
+            };
+            builder.location(format!("page {}", number));
+            for paragraph in split_paragraphs(&text) {
+                builder.paragraph(&paragraph);
+            }
+        }
+
+        if let Ok(info) = document.trailer.get(b"Info").and_then(|info| match info {
+            Object::Reference(id) => document.get_dictionary(*id),
+            other => other.as_dict(),
+        }) {
+            for (key, name) in [
+                ("Title", "title"),
+                ("Author", "author"),
+                ("Subject", "subject"),
+            ] {
+                let Some(value) = info.get(key.as_bytes()).ok().and_then(decode_pdf_string) else {
+                    continue;
+                };
+                if name == "title" {
+                    builder.title(&value);
+                } else {
+                    builder.metadata(name, &value);
+                }
+            }
+        }
+
+        Ok(builder.finish())
+    }
+}
+
+/// 以空行分段；没有空行时按行尾标点粗略断段
+fn split_paragraphs(text: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(line);
+        if line.ends_with(['.', '。', '!', '！', '?', '？', ':', '：']) {
+            paragraphs.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
+
+/// 信息字典中的字符串为 PDFDocEncoding 或带 BOM 的 UTF-16BE
+fn decode_pdf_string(object: &Object) -> Option<String> {
+    let Object::String(bytes, _) = object else {
+        return None;
+    };
+    let text = if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        bytes.iter().map(|b| *b as char).collect()
+    };
+    let text = text.trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::StringFormat;
+
+    #[test]
+    fn test_split_paragraphs() {
+        let text = "Quarterly results\nRevenue grew by\n12 percent.\nCosts were flat.\n\nOutlook";
+        assert_eq!(
+            split_paragraphs(text),
+            vec![
+                "Quarterly results Revenue grew by 12 percent.",
+                "Costs were flat.",
+                "Outlook"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_pdf_string() {
+        let utf16 = Object::String(
+            vec![0xFE, 0xFF, 0x62, 0x11, 0x00, 0x41],
+            StringFormat::Literal,
+        );
+        assert_eq!(decode_pdf_string(&utf16).as_deref(), Some("我A"));
+        let latin = Object::String(b"Annual Report".to_vec(), StringFormat::Literal);
+        assert_eq!(decode_pdf_string(&latin).as_deref(), Some("Annual Report"));
+    }
+}
@@ -0,0 +1,667 @@
+//! 导入管线
+//!
+//! 提取 -> 按结构切块 -> 写入 BM25 -> 嵌入并写入向量库。
+//! 每个块的正文前附带引用标注（文件名、页码/幻灯片、标题路径），
+//! 检索命中时可直接作为回答的出处。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use openclaw_core::{OpenClawError, Result};
+use openclaw_vector::{VectorItem, VectorStore};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use super::document::{DocumentSection, ExtractedDocument, ExtractorRegistry, SectionKind};
+use crate::bm25::Bm25Index;
+use crate::chunk::ChunkManager;
+use crate::embedding::EmbeddingProvider;
+use crate::entity_extractor::GraphExtractor;
+use crate::types::MemoryNamespace;
+
+/// 单个文件大小上限
+pub const MAX_DOCUMENT_BYTES: usize = 50 * 1024 * 1024;
+const DEFAULT_CHUNK_SIZE: usize = 512;
+const DEFAULT_CHUNK_OVERLAP: usize = 64;
+const EMBED_BATCH_SIZE: usize = 64;
+
+/// 写入索引的文档块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentChunk {
+    pub id: String,
+    /// 带引用标注的正文
+    pub content: String,
+    pub citation: String,
+    pub headings: Vec<String>,
+    pub location: Option<String>,
+    pub kind: SectionKind,
+    pub chunk_index: usize,
+}
+
+/// 单个文档的导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestedDocument {
+    pub document_id: String,
+    pub file_name: String,
+    pub format: String,
+    pub title: Option<String>,
+    pub metadata: HashMap<String, String>,
+    pub sections: usize,
+    pub chunks: usize,
+    /// 是否写入了向量索引
+    pub embedded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// 目录导入汇总
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestReport {
+    pub documents: Vec<IngestedDocument>,
+    pub failed: Vec<IngestFailure>,
+    /// 不支持的格式
+    pub skipped: usize,
+}
+
+pub struct IngestPipeline {
+    registry: ExtractorRegistry,
+    chunker: ChunkManager,
+    chunk_size: usize,
+    bm25: Arc<Bm25Index>,
+    vector_store: Option<Arc<dyn VectorStore>>,
+    embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
+    graph_extractor: Option<Arc<GraphExtractor>>,
+    /// 同一文档的删除与重建需要串行
+    write_lock: Mutex<()>,
+}
+
+impl IngestPipeline {
+    pub fn new(bm25: Arc<Bm25Index>) -> Self {
+        Self {
+            registry: ExtractorRegistry::with_defaults(),
+            chunker: ChunkManager::new(DEFAULT_CHUNK_SIZE, DEFAULT_CHUNK_OVERLAP, "cl100k_base"),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            bm25,
+            vector_store: None,
+            embedding_provider: None,
+            graph_extractor: None,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// 启用向量索引
+    pub fn with_vectors(
+        mut self,
+        vector_store: Arc<dyn VectorStore>,
+        embedding_provider: Arc<dyn EmbeddingProvider>,
+    ) -> Self {
+        self.vector_store = Some(vector_store);
+        self.embedding_provider = Some(embedding_provider);
+        self
+    }
+
+    /// 导入后提取实体和关系写入知识图谱
+    pub fn with_graph_extractor(mut self, extractor: Arc<GraphExtractor>) -> Self {
+        self.graph_extractor = Some(extractor);
+        self
+    }
+
+    pub fn with_registry(mut self, registry: ExtractorRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn with_chunking(mut self, chunk_size: usize, overlap: usize) -> Self {
+        self.chunker = ChunkManager::new(chunk_size, overlap, "cl100k_base");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    pub fn registry(&self) -> &ExtractorRegistry {
+        &self.registry
+    }
+
+    /// 导入单个文件或递归导入目录，单个文件失败不影响其余文件
+    pub async fn ingest_path(&self, path: &Path) -> Result<IngestReport> {
+        let mut report = IngestReport::default();
+
+        let files: Vec<PathBuf> = if path.is_dir() {
+            walkdir::WalkDir::new(path)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+                .map(|entry| entry.into_path())
+                .collect()
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        for file in files {
+            if self.registry.for_path(&file).is_none() {
+                report.skipped += 1;
+                continue;
+            }
+            match self.ingest_file(&file).await {
+                Ok(document) => report.documents.push(document),
+                Err(e) => {
+                    tracing::warn!("导入文档失败 {:?}: {}", file, e);
+                    report.failed.push(IngestFailure {
+                        path: file,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// 导入本地文件，以规范化路径作为文档 ID
+    pub async fn ingest_file(&self, path: &Path) -> Result<IngestedDocument> {
+        let metadata = tokio::fs::metadata(path).await?;
+        if metadata.len() as usize > MAX_DOCUMENT_BYTES {
+            return Err(OpenClawError::Parse(format!(
+                "文件过大 ({} 字节): {}",
+                metadata.len(),
+                path.display()
+            )));
+        }
+
+        let bytes = tokio::fs::read(path).await?;
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        let document_id = tokio::fs::canonicalize(path)
+            .await
+            .unwrap_or_else(|_| path.to_path_buf())
+            .display()
+            .to_string();
+
+        self.ingest_bytes(&file_name, bytes, &document_id).await
+    }
+
+    /// 导入内存中的文档，格式由 `file_name` 的扩展名决定
+    ///
+    /// 相同 `document_id` 的旧块会先被删除，重复导入即为更新。
+    pub async fn ingest_bytes(
+        &self,
+        file_name: &str,
+        bytes: Vec<u8>,
+        document_id: &str,
+    ) -> Result<IngestedDocument> {
+        self.ingest(file_name, bytes, document_id, None).await
+    }
+
+    /// 导入到指定命名空间，`document_id` 只在该命名空间内唯一
+    pub async fn ingest_bytes_in(
+        &self,
+        file_name: &str,
+        bytes: Vec<u8>,
+        document_id: &str,
+        namespace: &MemoryNamespace,
+    ) -> Result<IngestedDocument> {
+        self.ingest(file_name, bytes, document_id, Some(namespace))
+            .await
+    }
+
+    async fn ingest(
+        &self,
+        file_name: &str,
+        bytes: Vec<u8>,
+        document_id: &str,
+        namespace: Option<&MemoryNamespace>,
+    ) -> Result<IngestedDocument> {
+        if bytes.len() > MAX_DOCUMENT_BYTES {
+            return Err(OpenClawError::Parse(format!(
+                "文件过大 ({} 字节): {}",
+                bytes.len(),
+                file_name
+            )));
+        }
+        let extractor = self
+            .registry
+            .for_path(Path::new(file_name))
+            .ok_or_else(|| OpenClawError::Parse(format!("不支持的文档格式: {}", file_name)))?;
+
+        let document = tokio::task::spawn_blocking(move || extractor.extract(&bytes))
+            .await
+            .map_err(|e| OpenClawError::Execution(format!("文档提取任务失败: {}", e)))??;
+        if document.is_empty() {
+            return Err(OpenClawError::Parse(format!(
+                "未能从 {} 中提取到文本",
+                file_name
+            )));
+        }
+
+        let source = storage_source(document_id, namespace);
+        let chunks = self.chunk_document(&document, &source, file_name)?;
+
+        let _guard = self.write_lock.lock().await;
+        self.remove_chunks(&source, namespace).await?;
+
+        let timestamp = chrono::Utc::now().timestamp();
+        let docs = chunks
+            .iter()
+            .map(|c| (c.id.clone(), c.content.clone(), source.clone(), timestamp))
+            .collect();
+        match namespace {
+            Some(namespace) => {
+                self.bm25
+                    .add_namespaced_documents_batch(docs, namespace)
+                    .await
+            }
+            None => self.bm25.add_documents_batch(docs).await,
+        }
+        .map_err(|e| OpenClawError::Memory(format!("写入 BM25 索引失败: {}", e)))?;
+
+        let embedded = self
+            .embed_chunks(
+                &document,
+                document_id,
+                file_name,
+                &chunks,
+                timestamp,
+                namespace,
+            )
+            .await?;
+
+        // 图谱提取失败不影响文档检索
+        if let Some(extractor) = &self.graph_extractor
+            && let Err(e) = extractor.extract_chunks(&chunks).await
+        {
+            tracing::warn!("文档实体提取失败 {}: {}", file_name, e);
+        }
+
+        tracing::info!(
+            "已导入文档 {} ({} 节, {} 块)",
+            file_name,
+            document.sections.len(),
+            chunks.len()
+        );
+
+        Ok(IngestedDocument {
+            document_id: document_id.to_string(),
+            file_name: file_name.to_string(),
+            format: document.format,
+            title: document.title,
+            metadata: document.metadata,
+            sections: document.sections.len(),
+            chunks: chunks.len(),
+            embedded,
+        })
+    }
+
+    /// 从索引中删除文档
+    pub async fn remove_document(&self, document_id: &str) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        self.remove_chunks(document_id, None).await
+    }
+
+    /// 删除指定命名空间内的文档，不会影响其他命名空间的同名文档
+    pub async fn remove_document_in(
+        &self,
+        document_id: &str,
+        namespace: &MemoryNamespace,
+    ) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        self.remove_chunks(
+            &storage_source(document_id, Some(namespace)),
+            Some(namespace),
+        )
+        .await
+    }
+
+    /// 按结构切块：正文按段落切分，表格按行切分并在每块重复表头
+    pub fn chunk_document(
+        &self,
+        document: &ExtractedDocument,
+        document_id: &str,
+        file_name: &str,
+    ) -> Result<Vec<DocumentChunk>> {
+        let bpe =
+            tiktoken_rs::cl100k_base().map_err(|e| OpenClawError::TokenCount(e.to_string()))?;
+        let mut chunks = Vec::new();
+
+        for section in &document.sections {
+            let citation = citation(file_name, section);
+            let pieces = match section.kind {
+                SectionKind::Text => self.split_text(&section.text, document_id)?,
+                SectionKind::Table => split_table(&section.text, self.chunk_size, |text| {
+                    bpe.encode_with_special_tokens(text).len()
+                }),
+            };
+
+            for piece in pieces {
+                let chunk_index = chunks.len();
+                chunks.push(DocumentChunk {
+                    id: chunk_id(document_id, chunk_index),
+                    content: format!("[{}]\n{}", citation, piece),
+                    citation: citation.clone(),
+                    headings: section.headings.clone(),
+                    location: section.location.clone(),
+                    kind: section.kind,
+                    chunk_index,
+                });
+            }
+        }
+
+        Ok(chunks)
+    }
+
+    fn split_text(&self, text: &str, source: &str) -> Result<Vec<String>> {
+        let to_error = |e: anyhow::Error| OpenClawError::Memory(format!("切分文档失败: {}", e));
+
+        let mut pieces = Vec::new();
+        for chunk in self
+            .chunker
+            .chunk_text_by_paragraph(text, source)
+            .map_err(to_error)?
+        {
+            // 超长段落退回按 token 窗口切分
+            if chunk.token_count > self.chunk_size {
+                pieces.extend(
+                    self.chunker
+                        .chunk_text(&chunk.content, source)
+                        .map_err(to_error)?
+                        .into_iter()
+                        .map(|c| c.content),
+                );
+            } else {
+                pieces.push(chunk.content);
+            }
+        }
+        pieces.retain(|p| !p.trim().is_empty());
+        Ok(pieces)
+    }
+
+    async fn embed_chunks(
+        &self,
+        document: &ExtractedDocument,
+        document_id: &str,
+        file_name: &str,
+        chunks: &[DocumentChunk],
+        timestamp: i64,
+        namespace: Option<&MemoryNamespace>,
+    ) -> Result<bool> {
+        let (Some(store), Some(embedder)) = (&self.vector_store, &self.embedding_provider) else {
+            return Ok(false);
+        };
+
+        for batch in chunks.chunks(EMBED_BATCH_SIZE) {
+            let texts: Vec<String> = batch.iter().map(|c| c.content.clone()).collect();
+            let vectors = embedder.embed_batch(&texts).await?;
+            let items = batch
+                .iter()
+                .zip(vectors)
+                .map(|(chunk, vector)| {
+                    let mut payload = serde_json::json!({
+                            "content": chunk.content,
+                            "source": "document",
+                            "document_id": document_id,
+                            "file_name": file_name,
+                            "title": document.title,
+                            "format": document.format,
+                            "citation": chunk.citation,
+                            "headings": chunk.headings,
+                            "location": chunk.location,
+                            "chunk_index": chunk.chunk_index,
+                            "timestamp": timestamp,
+                    });
+                    if let Some(namespace) = namespace {
+                        namespace.stamp(&mut payload);
+                    }
+                    VectorItem::new(vector, payload).with_id(chunk.id.clone())
+                })
+                .collect();
+            store.upsert_batch(items).await?;
+        }
+        Ok(true)
+    }
+
+    async fn remove_chunks(&self, source: &str, namespace: Option<&MemoryNamespace>) -> Result<()> {
+        self.bm25
+            .delete_by_source(source)
+            .await
+            .map_err(|e| OpenClawError::Memory(format!("删除 BM25 文档失败: {}", e)))?;
+
+        // 块 ID 由文档来源和序号确定，逐个探测删除，不依赖后端的过滤删除支持
+        if let Some(store) = &self.vector_store {
+            for index in 0.. {
+                let id = chunk_id(source, index);
+                let Some(item) = store.get(&id).await? else {
+                    break;
+                };
+                if namespace.is_some_and(|ns| !ns.matches(&item.payload)) {
+                    break;
+                }
+                store.delete(&id).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 索引中使用的文档来源，带命名空间时以 JSON 数组编码，避免不同用户的同名文档互相覆盖
+fn storage_source(document_id: &str, namespace: Option<&MemoryNamespace>) -> String {
+    match namespace {
+        Some(namespace) => {
+            serde_json::json!([namespace.user_id, namespace.persona_id, document_id]).to_string()
+        }
+        None => document_id.to_string(),
+    }
+}
+
+/// "handbook.pdf, page 3 › Setup › Install"
+fn citation(file_name: &str, section: &DocumentSection) -> String {
+    let mut citation = file_name.to_string();
+    if let Some(location) = &section.location {
+        citation.push_str(", ");
+        citation.push_str(location);
+    }
+    for heading in &section.headings {
+        citation.push_str(" › ");
+        citation.push_str(heading);
+    }
+    citation
+}
+
+/// 按行切分 Markdown 表格，每块都带上表头和分隔行
+fn split_table(table: &str, budget: usize, count_tokens: impl Fn(&str) -> usize) -> Vec<String> {
+    let lines: Vec<&str> = table.lines().collect();
+    if lines.len() <= 2 || count_tokens(table) <= budget {
+        return vec![table.to_string()];
+    }
+
+    let header = lines[..2].join("\n");
+    let header_tokens = count_tokens(&header);
+    let mut pieces = Vec::new();
+    let mut current = header.clone();
+    let mut current_tokens = header_tokens;
+
+    for row in &lines[2..] {
+        let row_tokens = count_tokens(row);
+        if current_tokens + row_tokens > budget && current_tokens > header_tokens {
+            pieces.push(std::mem::replace(&mut current, header.clone()));
+            current_tokens = header_tokens;
+        }
+        current.push('\n');
+        current.push_str(row);
+        current_tokens += row_tokens;
+    }
+    if current_tokens > header_tokens {
+        pieces.push(current);
+    }
+    pieces
+}
+
+/// 由文档 ID 和序号生成稳定的 UUID（FNV-1a 128 位），兼容只接受 UUID 的向量库
+fn chunk_id(document_id: &str, index: usize) -> String {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    let hash = document_id
+        .bytes()
+        .chain((index as u64).to_le_bytes())
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ byte as u128).wrapping_mul(PRIME)
+        });
+    uuid::Uuid::from_u128(hash).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use openclaw_vector::MemoryStore;
+
+    struct KeywordEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedding {
+        fn name(&self) -> &str {
+            "keyword"
+        }
+
+        fn model(&self) -> &str {
+            "keyword"
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+
+        async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+            let text = text.to_lowercase();
+            Ok(vec![
+                text.matches("gateway").count() as f32 + 0.1,
+                text.matches("budget").count() as f32 + 0.1,
+            ])
+        }
+
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let mut vectors = Vec::new();
+            for text in texts {
+                vectors.push(self.embed(text).await?);
+            }
+            Ok(vectors)
+        }
+    }
+
+    #[test]
+    fn test_split_table_repeats_header() {
+        let table = "| A | B |\n| --- | --- |\n| 1 | 2 |\n| 3 | 4 |\n| 5 | 6 |";
+        let pieces = split_table(table, 12, |text| text.split_whitespace().count());
+
+        assert_eq!(pieces.len(), 3);
+        for piece in &pieces {
+            assert!(piece.starts_with("| A | B |\n| --- | --- |\n"));
+        }
+        assert!(pieces[2].ends_with("| 5 | 6 |"));
+    }
+
+    #[test]
+    fn test_chunk_ids_are_stable() {
+        assert_eq!(chunk_id("doc", 0), chunk_id("doc", 0));
+        assert_ne!(chunk_id("doc", 0), chunk_id("doc", 1));
+        assert_ne!(chunk_id("doc", 0), chunk_id("other", 0));
+        assert!(uuid::Uuid::parse_str(&chunk_id("doc", 3)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ingest_markdown_with_citations() {
+        let dir = tempfile::tempdir().unwrap();
+        let bm25 = Arc::new(Bm25Index::new(&dir.path().join("bm25")).unwrap());
+        let store: Arc<dyn VectorStore> = Arc::new(MemoryStore::new());
+        let pipeline = IngestPipeline::new(bm25.clone())
+            .with_vectors(store.clone(), Arc::new(KeywordEmbedding));
+
+        let markdown = "# Runbook\n\n## Gateway\n\nRestart the gateway with `openclaw gateway restart`.\n\n## Budget\n\n| Item | Cost |\n|---|---|\n| GPU | 1200 |\n";
+        let document = pipeline
+            .ingest_bytes(
+                "runbook.md",
+                markdown.as_bytes().to_vec(),
+                "upload:runbook.md",
+            )
+            .await
+            .unwrap();
+        assert_eq!(document.title.as_deref(), Some("Runbook"));
+        assert_eq!(document.chunks, 2);
+        assert!(document.embedded);
+
+        let hits = bm25.search("restart gateway", 5).unwrap();
+        assert!(
+            hits[0]
+                .content
+                .starts_with("[runbook.md › Runbook › Gateway]\n")
+        );
+        assert_eq!(store.stats().await.unwrap().total_vectors, 2);
+
+        // 重复导入会替换旧块
+        let updated = "# Runbook\n\nOnly one section now.\n";
+        pipeline
+            .ingest_bytes(
+                "runbook.md",
+                updated.as_bytes().to_vec(),
+                "upload:runbook.md",
+            )
+            .await
+            .unwrap();
+        assert_eq!(store.stats().await.unwrap().total_vectors, 1);
+        assert!(bm25.search("gateway", 5).unwrap().is_empty());
+
+        pipeline.remove_document("upload:runbook.md").await.unwrap();
+        assert_eq!(store.stats().await.unwrap().total_vectors, 0);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_is_scoped_to_namespace() {
+        let dir = tempfile::tempdir().unwrap();
+        let bm25 = Arc::new(Bm25Index::new(&dir.path().join("bm25")).unwrap());
+        let store: Arc<dyn VectorStore> = Arc::new(MemoryStore::new());
+        let pipeline = IngestPipeline::new(bm25.clone())
+            .with_vectors(store.clone(), Arc::new(KeywordEmbedding));
+        let alice = MemoryNamespace::new("alice", "assistant");
+        let bob = MemoryNamespace::new("bob", "assistant");
+
+        for (namespace, text) in [(&alice, "alice gateway notes"), (&bob, "bob gateway notes")] {
+            pipeline
+                .ingest_bytes_in(
+                    "notes.md",
+                    text.as_bytes().to_vec(),
+                    "upload:notes.md",
+                    namespace,
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(store.stats().await.unwrap().total_vectors, 2);
+
+        let hits = bm25.search_in_namespace("gateway", &alice, 5).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].content.contains("alice"));
+
+        // 删除只作用于自己的命名空间
+        pipeline
+            .remove_document_in("upload:notes.md", &bob)
+            .await
+            .unwrap();
+        assert_eq!(store.stats().await.unwrap().total_vectors, 1);
+        assert_eq!(
+            bm25.search_in_namespace("gateway", &alice, 5)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            bm25.search_in_namespace("gateway", &bob, 5)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! 纯文本与 Markdown 提取
+
+use openclaw_core::Result;
+
+use super::document::{DocumentExtractor, ExtractedDocument, SectionBuilder};
+
+pub struct PlainTextExtractor;
+
+impl DocumentExtractor for PlainTextExtractor {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["txt", "text", "log", "rst"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let text = String::from_utf8_lossy(bytes);
+        let mut builder = SectionBuilder::new("text");
+        for paragraph in text.split("\n\n") {
+            builder.paragraph(paragraph);
+        }
+        Ok(builder.finish())
+    }
+}
+
+pub struct MarkdownExtractor;
+
+impl DocumentExtractor for MarkdownExtractor {
+    fn name(&self) -> &str {
+        "markdown"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["md", "markdown"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let text = String::from_utf8_lossy(bytes);
+        Ok(parse_markdown(&text))
+    }
+}
+
+fn parse_markdown(text: &str) -> ExtractedDocument {
+    let mut builder = SectionBuilder::new("markdown");
+    let mut paragraph = String::new();
+    let mut table = Vec::new();
+    let mut in_code = false;
+
+    let flush_paragraph = |builder: &mut SectionBuilder, paragraph: &mut String| {
+        builder.paragraph(paragraph);
+        paragraph.clear();
+    };
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            paragraph.push_str(line);
+            paragraph.push('\n');
+            continue;
+        }
+        if in_code {
+            paragraph.push_str(line);
+            paragraph.push('\n');
+            continue;
+        }
+
+        if trimmed.starts_with('|') {
+            flush_paragraph(&mut builder, &mut paragraph);
+            // 跳过表头分隔行
+            if !trimmed.chars().all(|c| matches!(c, '|' | '-' | ':' | ' ')) {
+                table.push(split_table_row(trimmed));
+            }
+            continue;
+        }
+        if !table.is_empty() {
+            builder.table(std::mem::take(&mut table));
+        }
+
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            flush_paragraph(&mut builder, &mut paragraph);
+            builder.heading(level, trimmed[level..].trim().trim_end_matches('#'));
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush_paragraph(&mut builder, &mut paragraph);
+        } else {
+            paragraph.push_str(line);
+            paragraph.push('\n');
+        }
+    }
+
+    if !table.is_empty() {
+        builder.table(table);
+    }
+    flush_paragraph(&mut builder, &mut paragraph);
+
+    builder.finish()
+}
+
+fn split_table_row(line: &str) -> Vec<String> {
+    line.trim()
+        .trim_matches('|')
+        .split('|')
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::document::SectionKind;
+
+    #[test]
+    fn test_markdown_structure() {
+        let doc = parse_markdown(
+            "# Guide\n\nIntro text.\n\n## Ports\n\n| Name | Port |\n|------|------|\n| gateway | 18789 |\n\n```\n# not a heading\n```\n",
+        );
+
+        assert_eq!(doc.title.as_deref(), Some("Guide"));
+        assert_eq!(doc.sections[0].text, "Intro text.");
+        let table = &doc.sections[1];
+        assert_eq!(table.kind, SectionKind::Table);
+        assert_eq!(table.headings, vec!["Guide", "Ports"]);
+        assert!(table.text.contains("| gateway | 18789 |"));
+        assert!(doc.sections[2].text.contains("# not a heading"));
+        assert_eq!(doc.sections[2].headings, vec!["Guide", "Ports"]);
+    }
+}
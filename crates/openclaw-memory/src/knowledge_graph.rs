@@ -3,13 +3,26 @@
 //! 实现记忆的图结构化存储和查询：
 //! - 实体节点
 //! - 关系边
-//! - 图查询和遍历
+//! - 图查询和遍历（多跳、按关系类型和权重过滤）
+//! - 存储由 `GraphStore` 提供，可选内存或 SQLite
 
 use chrono::{DateTime, Utc};
+use openclaw_core::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+
+use crate::graph_store::{
+    Direction, GraphStore, MemoryGraphStore, RelationFilter, SqliteGraphStore,
+};
+
+/// 默认的持久化图谱文件
+pub const DEFAULT_GRAPH_PATH: &str = "data/knowledge_graph.db";
+/// 记录实体或关系来源记忆 ID 的属性，值为 JSON 数组
+pub const SOURCE_IDS_PROPERTY: &str = "source_ids";
+/// 每个实体或关系最多保留的来源数，超出时丢弃最早的
+const MAX_SOURCE_IDS: usize = 32;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entity {
@@ -59,6 +72,32 @@ impl Entity {
         self.confidence = confidence;
         self
     }
+
+    /// 名称加属性的可读描述，不含来源记录
+    pub fn describe(&self) -> String {
+        let mut properties: Vec<String> = self
+            .properties
+            .iter()
+            .filter(|(k, _)| k.as_str() != SOURCE_IDS_PROPERTY)
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        properties.sort();
+        if properties.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} [{}]", self.name, properties.join(", "))
+        }
+    }
+
+    /// 提及该实体的记忆 ID
+    pub fn source_ids(&self) -> Vec<String> {
+        source_ids(&self.properties)
+    }
+
+    /// 记录来源，返回是否新增
+    pub fn add_source(&mut self, source_id: &str) -> bool {
+        add_source(&mut self.properties, source_id)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,202 +147,387 @@ impl Relation {
         self.properties.insert(key.into(), value.into());
         self
     }
+
+    pub fn source_ids(&self) -> Vec<String> {
+        source_ids(&self.properties)
+    }
+
+    pub fn add_source(&mut self, source_id: &str) -> bool {
+        add_source(&mut self.properties, source_id)
+    }
 }
 
-pub struct KnowledgeGraph {
-    entities: Arc<RwLock<HashMap<String, Entity>>>,
-    relations: Arc<RwLock<HashMap<String, Relation>>>,
-    entity_index: Arc<RwLock<HashMap<String, HashSet<String>>>>,
-    relation_index: Arc<RwLock<HashMap<RelationType, HashSet<String>>>>,
+fn source_ids(properties: &HashMap<String, String>) -> Vec<String> {
+    properties
+        .get(SOURCE_IDS_PROPERTY)
+        .and_then(|v| serde_json::from_str(v).ok())
+        .unwrap_or_default()
 }
 
-impl KnowledgeGraph {
-    pub fn new() -> Self {
+/// 移除给定来源，返回 (是否有改动, 是否已无来源)
+fn remove_sources(
+    properties: &mut HashMap<String, String>,
+    forgotten: &HashSet<String>,
+) -> (bool, bool) {
+    let ids = source_ids(properties);
+    if ids.is_empty() {
+        return (false, false);
+    }
+    let kept: Vec<String> = ids
+        .iter()
+        .filter(|id| !forgotten.contains(*id))
+        .cloned()
+        .collect();
+    if kept.len() == ids.len() {
+        return (false, false);
+    }
+    let value = serde_json::to_string(&kept).unwrap_or_default();
+    properties.insert(SOURCE_IDS_PROPERTY.to_string(), value);
+    (true, kept.is_empty())
+}
+
+fn add_source(properties: &mut HashMap<String, String>, source_id: &str) -> bool {
+    let mut ids = source_ids(properties);
+    if ids.iter().any(|id| id == source_id) {
+        return false;
+    }
+    ids.push(source_id.to_string());
+    if ids.len() > MAX_SOURCE_IDS {
+        ids.drain(..ids.len() - MAX_SOURCE_IDS);
+    }
+    let value = serde_json::to_string(&ids).unwrap_or_default();
+    properties.insert(SOURCE_IDS_PROPERTY.to_string(), value);
+    true
+}
+
+/// 图谱查询：从起点实体出发做多跳遍历，可按关系类型、权重和目标实体类型过滤
+#[derive(Debug, Clone)]
+pub struct GraphQuery {
+    pub max_hops: usize,
+    pub direction: Direction,
+    pub relations: RelationFilter,
+    /// 为空时返回所有类型的实体
+    pub entity_types: Vec<EntityType>,
+    pub limit: usize,
+}
+
+impl Default for GraphQuery {
+    fn default() -> Self {
         Self {
-            entities: Arc::new(RwLock::new(HashMap::new())),
-            relations: Arc::new(RwLock::new(HashMap::new())),
-            entity_index: Arc::new(RwLock::new(HashMap::new())),
-            relation_index: Arc::new(RwLock::new(HashMap::new())),
+            max_hops: 2,
+            direction: Direction::Both,
+            relations: RelationFilter::default(),
+            entity_types: Vec::new(),
+            limit: 50,
         }
     }
+}
 
-    pub async fn add_entity(&self, entity: Entity) -> Result<(), String> {
-        let mut entities = self.entities.write().await;
-        let mut index = self.entity_index.write().await;
+impl GraphQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_hops(mut self, hops: usize) -> Self {
+        self.max_hops = hops;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_relation_type(mut self, relation_type: RelationType) -> Self {
+        self.relations.relation_types.push(relation_type);
+        self
+    }
 
-        let name_key = entity.name.to_lowercase();
-        index.entry(name_key).or_default().insert(entity.id.clone());
+    pub fn with_min_weight(mut self, weight: f32) -> Self {
+        self.relations.min_weight = Some(weight);
+        self
+    }
 
-        let type_key = format!("{:?}", entity.entity_type);
-        index.entry(type_key).or_default().insert(entity.id.clone());
+    pub fn with_entity_type(mut self, entity_type: EntityType) -> Self {
+        self.entity_types.push(entity_type);
+        self
+    }
 
-        entities.insert(entity.id.clone(), entity);
-        Ok(())
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
     }
+}
 
-    pub async fn add_relation(&self, relation: Relation) -> Result<(), String> {
-        let mut relations = self.relations.write().await;
-        let mut index = self.relation_index.write().await;
+/// 查询命中的实体及到达它的路径
+#[derive(Debug, Clone)]
+pub struct GraphMatch {
+    pub entity: Entity,
+    pub path: Vec<Relation>,
+    /// 路径上关系权重之积乘以实体置信度
+    pub score: f32,
+}
 
-        index
-            .entry(relation.relation_type.clone())
-            .or_default()
-            .insert(relation.id.clone());
+/// 知识图谱，数据由 `GraphStore` 保存，默认使用内存存储
+pub struct KnowledgeGraph {
+    store: Arc<dyn GraphStore>,
+}
 
-        relations.insert(relation.id.clone(), relation);
-        Ok(())
+impl KnowledgeGraph {
+    pub fn new() -> Self {
+        Self::with_store(Arc::new(MemoryGraphStore::new()))
     }
 
-    pub async fn get_entity(&self, id: &str) -> Option<Entity> {
-        self.entities.read().await.get(id).cloned()
+    pub fn with_store(store: Arc<dyn GraphStore>) -> Self {
+        Self { store }
     }
 
-    pub async fn find_entities_by_name(&self, name: &str) -> Vec<Entity> {
-        let index = self.entity_index.read().await;
-        let entities = self.entities.read().await;
-
-        let name_key = name.to_lowercase();
-        if let Some(ids) = index.get(&name_key) {
-            ids.iter()
-                .filter_map(|id| entities.get(id).cloned())
-                .collect()
-        } else {
-            Vec::new()
-        }
+    /// 打开 SQLite 图谱，文件不存在时创建
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::with_store(Arc::new(SqliteGraphStore::open(path)?)))
     }
 
-    pub async fn find_entities_by_type(&self, entity_type: &EntityType) -> Vec<Entity> {
-        let index = self.entity_index.read().await;
-        let entities = self.entities.read().await;
-
-        let type_key = format!("{:?}", entity_type);
-        if let Some(ids) = index.get(&type_key) {
-            ids.iter()
-                .filter_map(|id| entities.get(id).cloned())
-                .collect()
-        } else {
-            Vec::new()
-        }
+    pub fn store(&self) -> Arc<dyn GraphStore> {
+        self.store.clone()
     }
 
-    pub async fn get_relations_from(&self, source_id: &str) -> Vec<Relation> {
-        self.relations
-            .read()
+    pub async fn add_entity(&self, entity: Entity) -> std::result::Result<(), String> {
+        self.store
+            .upsert_entity(&entity)
             .await
-            .values()
-            .filter(|r| r.source_id == source_id)
-            .cloned()
-            .collect()
+            .map_err(|e| e.to_string())
     }
 
-    pub async fn get_relations_to(&self, target_id: &str) -> Vec<Relation> {
-        self.relations
-            .read()
+    pub async fn add_relation(&self, relation: Relation) -> std::result::Result<(), String> {
+        self.store
+            .upsert_relation(&relation)
             .await
-            .values()
-            .filter(|r| r.target_id == target_id)
-            .cloned()
-            .collect()
+            .map_err(|e| e.to_string())
     }
 
-    pub async fn find_path(&self, from: &str, to: &str) -> Option<Vec<Relation>> {
-        let mut visited: HashSet<String> = HashSet::new();
-        let mut queue: Vec<(String, Vec<Relation>)> = vec![(from.to_string(), Vec::new())];
+    pub async fn remove_entity(&self, id: &str) -> Result<bool> {
+        self.store.remove_entity(id).await
+    }
 
-        while let Some((current_id, path)) = queue.pop() {
-            if current_id == to {
-                return Some(path);
-            }
+    /// 删除来源于给定记忆的实体和关系
+    ///
+    /// 来源全部被遗忘的实体或关系直接删除，仍有其他来源的只移除对应记录。
+    /// 返回删除的 (实体数, 关系数)。
+    pub async fn forget_sources(&self, forgotten: &HashSet<String>) -> Result<(usize, usize)> {
+        if forgotten.is_empty() {
+            return Ok((0, 0));
+        }
 
-            if visited.contains(&current_id) {
-                continue;
+        let mut entities = 0;
+        for mut entity in self.store.all_entities().await? {
+            match remove_sources(&mut entity.properties, forgotten) {
+                (true, true) if self.store.remove_entity(&entity.id).await? => entities += 1,
+                (true, false) => self.store.upsert_entity(&entity).await?,
+                _ => {}
             }
-            visited.insert(current_id.clone());
+        }
 
-            let relations = self.get_relations_from(&current_id).await;
-            for relation in relations {
-                let mut new_path = path.clone();
-                new_path.push(relation.clone());
-                queue.push((relation.target_id, new_path));
+        let mut relations = 0;
+        for mut relation in self.store.all_relations().await? {
+            match remove_sources(&mut relation.properties, forgotten) {
+                (true, true) if self.store.remove_relation(&relation.id).await? => relations += 1,
+                (true, false) => self.store.upsert_relation(&relation).await?,
+                _ => {}
             }
         }
 
-        None
+        Ok((entities, relations))
     }
 
-    pub async fn get_subgraph(&self, entity_id: &str, depth: usize) -> Subgraph {
-        let mut visited: HashSet<String> = HashSet::new();
-        let mut entities = Vec::new();
-        let mut relations = Vec::new();
-
-        self.bfs_collect(
-            entity_id,
-            depth,
-            &mut visited,
-            &mut entities,
-            &mut relations,
+    pub async fn remove_relation(&self, id: &str) -> Result<bool> {
+        self.store.remove_relation(id).await
+    }
+
+    pub async fn get_entity(&self, id: &str) -> Option<Entity> {
+        logged(self.store.get_entity(id).await).flatten()
+    }
+
+    pub async fn find_entities_by_name(&self, name: &str) -> Vec<Entity> {
+        logged(self.store.find_by_name(name).await).unwrap_or_default()
+    }
+
+    pub async fn find_entities_by_type(&self, entity_type: &EntityType) -> Vec<Entity> {
+        logged(self.store.find_by_type(entity_type).await).unwrap_or_default()
+    }
+
+    pub async fn get_relations_from(&self, source_id: &str) -> Vec<Relation> {
+        let filter = RelationFilter::default();
+        logged(
+            self.store
+                .relations(source_id, Direction::Outgoing, &filter)
+                .await,
         )
-        .await;
+        .unwrap_or_default()
+    }
 
-        Subgraph {
-            entities,
-            relations,
+    pub async fn get_relations_to(&self, target_id: &str) -> Vec<Relation> {
+        let filter = RelationFilter::default();
+        logged(
+            self.store
+                .relations(target_id, Direction::Incoming, &filter)
+                .await,
+        )
+        .unwrap_or_default()
+    }
+
+    /// 从起点实体出发按跳数逐层遍历，起点本身不在结果中
+    pub async fn query(&self, start_ids: &[String], query: &GraphQuery) -> Result<Vec<GraphMatch>> {
+        let mut visited: HashSet<String> = start_ids.iter().cloned().collect();
+        let mut frontier: Vec<(String, Vec<Relation>, f32)> = start_ids
+            .iter()
+            .map(|id| (id.clone(), Vec::new(), 1.0))
+            .collect();
+        let mut matches = Vec::new();
+
+        for _ in 0..query.max_hops {
+            let mut next = Vec::new();
+            for (entity_id, path, weight) in frontier {
+                let relations = self
+                    .store
+                    .relations(&entity_id, query.direction, &query.relations)
+                    .await?;
+                for relation in relations {
+                    let neighbor = if relation.source_id == entity_id {
+                        relation.target_id.clone()
+                    } else {
+                        relation.source_id.clone()
+                    };
+                    if !visited.insert(neighbor.clone()) {
+                        continue;
+                    }
+                    let Some(entity) = self.store.get_entity(&neighbor).await? else {
+                        continue;
+                    };
+
+                    let weight = weight * relation.weight;
+                    let mut path = path.clone();
+                    path.push(relation);
+                    if query.entity_types.is_empty()
+                        || query.entity_types.contains(&entity.entity_type)
+                    {
+                        matches.push(GraphMatch {
+                            score: weight * entity.confidence,
+                            entity,
+                            path: path.clone(),
+                        });
+                    }
+                    next.push((neighbor, path, weight));
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
         }
+
+        matches.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.path.len().cmp(&b.path.len()))
+        });
+        matches.truncate(query.limit);
+        Ok(matches)
     }
 
-    async fn bfs_collect(
+    /// 沿出边查找最短路径
+    pub async fn find_path(&self, from: &str, to: &str) -> Option<Vec<Relation>> {
+        let query = GraphQuery::new()
+            .with_direction(Direction::Outgoing)
+            .with_max_hops(usize::MAX);
+        logged(self.find_path_matching(from, to, &query).await).flatten()
+    }
+
+    /// 按查询条件查找最短路径，实体类型过滤不作用于中间节点
+    pub async fn find_path_matching(
         &self,
-        entity_id: &str,
-        depth: usize,
-        visited: &mut HashSet<String>,
-        entities: &mut Vec<Entity>,
-        relations: &mut Vec<Relation>,
-    ) {
-        if depth == 0 {
-            return;
+        from: &str,
+        to: &str,
+        query: &GraphQuery,
+    ) -> Result<Option<Vec<Relation>>> {
+        if from == to {
+            return Ok(Some(Vec::new()));
         }
 
-        if visited.contains(entity_id) {
-            return;
+        let mut visited: HashSet<String> = HashSet::from([from.to_string()]);
+        let mut queue: VecDeque<(String, Vec<Relation>)> =
+            VecDeque::from([(from.to_string(), Vec::new())]);
+
+        while let Some((current, path)) = queue.pop_front() {
+            if path.len() >= query.max_hops {
+                continue;
+            }
+            let relations = self
+                .store
+                .relations(&current, query.direction, &query.relations)
+                .await?;
+            for relation in relations {
+                let neighbor = if relation.source_id == current {
+                    relation.target_id.clone()
+                } else {
+                    relation.source_id.clone()
+                };
+                if !visited.insert(neighbor.clone()) {
+                    continue;
+                }
+                let mut path = path.clone();
+                path.push(relation);
+                if neighbor == to {
+                    return Ok(Some(path));
+                }
+                queue.push_back((neighbor, path));
+            }
         }
 
-        visited.insert(entity_id.to_string());
+        Ok(None)
+    }
 
-        if let Some(entity) = self.get_entity(entity_id).await {
-            entities.push(entity);
+    /// 以实体为中心、`depth` 层以内的子图，`depth` 为 1 时只包含中心实体及其关系
+    pub async fn get_subgraph(&self, entity_id: &str, depth: usize) -> Subgraph {
+        let mut subgraph = Subgraph {
+            entities: Vec::new(),
+            relations: Vec::new(),
+        };
+        if depth == 0 {
+            return subgraph;
         }
-
-        let outgoing = self.get_relations_from(entity_id).await;
-        for relation in outgoing {
-            let already_added = relations.iter().any(|r| r.id == relation.id);
-            if !already_added {
-                relations.push(relation.clone());
-                Box::pin(self.bfs_collect(
-                    &relation.target_id,
-                    depth - 1,
-                    visited,
-                    entities,
-                    relations,
-                ))
-                .await;
-            }
+        let Some(root) = self.get_entity(entity_id).await else {
+            return subgraph;
+        };
+        subgraph.entities.push(root);
+
+        let query = GraphQuery::new()
+            .with_max_hops(depth - 1)
+            .with_limit(usize::MAX);
+        let reached =
+            logged(self.query(&[entity_id.to_string()], &query).await).unwrap_or_default();
+        let mut relation_ids = HashSet::new();
+        let mut centers = vec![entity_id.to_string()];
+        for found in reached {
+            centers.push(found.entity.id.clone());
+            subgraph.entities.push(found.entity);
         }
 
-        let incoming = self.get_relations_to(entity_id).await;
-        for relation in incoming {
-            let already_added = relations.iter().any(|r| r.id == relation.id);
-            if !already_added {
-                relations.push(relation.clone());
-                Box::pin(self.bfs_collect(
-                    &relation.source_id,
-                    depth - 1,
-                    visited,
-                    entities,
-                    relations,
-                ))
-                .await;
+        let filter = RelationFilter::default();
+        for center in centers {
+            let relations = logged(
+                self.store
+                    .relations(&center, Direction::Both, &filter)
+                    .await,
+            )
+            .unwrap_or_default();
+            for relation in relations {
+                if relation_ids.insert(relation.id.clone()) {
+                    subgraph.relations.push(relation);
+                }
             }
         }
+        subgraph
     }
 
     pub async fn get_preferences(&self) -> Vec<Entity> {
@@ -319,65 +543,62 @@ impl KnowledgeGraph {
     }
 
     pub async fn stats(&self) -> KnowledgeGraphStats {
-        let entities = self.entities.read().await;
-        let relations = self.relations.read().await;
-
+        let entities = logged(self.store.all_entities().await).unwrap_or_default();
+        let relations = logged(self.store.all_relations().await).unwrap_or_default();
+
+        let mut entity_types: HashMap<String, usize> = HashMap::new();
+        for entity in &entities {
+            *entity_types
+                .entry(format!("{:?}", entity.entity_type))
+                .or_insert(0) += 1;
+        }
         KnowledgeGraphStats {
             entity_count: entities.len(),
             relation_count: relations.len(),
-            entity_types: {
-                let mut types: HashMap<String, usize> = HashMap::new();
-                for entity in entities.values() {
-                    *types
-                        .entry(format!("{:?}", entity.entity_type))
-                        .or_insert(0) += 1;
-                }
-                types
-            },
+            entity_types,
         }
     }
 
     pub async fn clear(&self) {
-        let mut entities = self.entities.write().await;
-        let mut relations = self.relations.write().await;
-        let mut entity_index = self.entity_index.write().await;
-        let mut relation_index = self.relation_index.write().await;
-
-        entities.clear();
-        relations.clear();
-        entity_index.clear();
-        relation_index.clear();
+        logged(self.store.clear().await);
     }
 
-    pub fn search_entities(&self, query_terms: &[&str]) -> Vec<Entity> {
-        let entities = self.entities.blocking_read();
-        let mut results: Vec<(Entity, usize)> = Vec::new();
-
-        for entity in entities.values() {
-            let name_lower = entity.name.to_lowercase();
-            let mut match_count = 0;
+    /// 按名称和属性值匹配关键词，命中越多越靠前
+    pub async fn search_entities(&self, query_terms: &[&str]) -> Vec<Entity> {
+        let terms: Vec<String> = query_terms.iter().map(|t| t.to_lowercase()).collect();
+        let candidates = logged(self.store.search(&terms).await).unwrap_or_default();
 
-            for term in query_terms {
-                if name_lower.contains(*term) {
-                    match_count += 1;
-                }
-                for (_, value) in &entity.properties {
-                    if value.to_lowercase().contains(*term) {
+        let mut results: Vec<(Entity, usize)> = candidates
+            .into_iter()
+            .map(|entity| {
+                let name = entity.name.to_lowercase();
+                let mut match_count = 0;
+                for term in &terms {
+                    if name.contains(term.as_str()) {
                         match_count += 1;
                     }
+                    match_count += entity
+                        .properties
+                        .values()
+                        .filter(|v| v.to_lowercase().contains(term.as_str()))
+                        .count();
                 }
-            }
-
-            if match_count > 0 {
-                results.push((entity.clone(), match_count));
-            }
-        }
+                (entity, match_count)
+            })
+            .collect();
 
-        results.sort_by(|a, b| b.1.cmp(&a.1));
+        results.sort_by_key(|r| std::cmp::Reverse(r.1));
         results.into_iter().map(|(e, _)| e).collect()
     }
 }
 
+/// 兼容旧接口的查询方法不返回错误，存储出错时记录日志
+fn logged<T>(result: Result<T>) -> Option<T> {
+    result
+        .map_err(|e| tracing::warn!("Knowledge graph store error: {}", e))
+        .ok()
+}
+
 impl Default for KnowledgeGraph {
     fn default() -> Self {
         Self::new()
@@ -609,4 +830,117 @@ mod tests {
         let stats_after = graph.stats().await;
         assert_eq!(stats_after.entity_count, 0);
     }
+
+    async fn chain(graph: &KnowledgeGraph) -> Vec<Entity> {
+        let names = [
+            ("A", EntityType::Person),
+            ("B", EntityType::Project),
+            ("C", EntityType::Skill),
+        ];
+        let mut entities = Vec::new();
+        for (name, entity_type) in names {
+            let entity = Entity::new(name.to_string(), entity_type);
+            graph.add_entity(entity.clone()).await.unwrap();
+            entities.push(entity);
+        }
+        let edges = [
+            (0, 1, RelationType::ParticipatesIn, 0.8),
+            (1, 2, RelationType::Uses, 0.5),
+            (0, 2, RelationType::HasSkill, 0.1),
+        ];
+        for (from, to, relation_type, weight) in edges {
+            let relation = Relation::new(
+                entities[from].id.clone(),
+                entities[to].id.clone(),
+                relation_type,
+            )
+            .with_weight(weight);
+            graph.add_relation(relation).await.unwrap();
+        }
+        entities
+    }
+
+    #[tokio::test]
+    async fn test_query_multi_hop() {
+        let graph = KnowledgeGraph::new();
+        let e = chain(&graph).await;
+        let start = [e[0].id.clone()];
+
+        let all = graph.query(&start, &GraphQuery::new()).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].entity.id, e[1].id);
+        assert_eq!(all[1].entity.id, e[2].id);
+        assert_eq!(all[1].path.len(), 1);
+
+        let strong = GraphQuery::new().with_min_weight(0.3);
+        let found = graph.query(&start, &strong).await.unwrap();
+        let skill = found.iter().find(|m| m.entity.id == e[2].id).unwrap();
+        assert_eq!(skill.path.len(), 2);
+        assert!((skill.score - 0.4).abs() < 1e-6);
+
+        let skills_only = GraphQuery::new()
+            .with_relation_type(RelationType::ParticipatesIn)
+            .with_relation_type(RelationType::Uses)
+            .with_entity_type(EntityType::Skill);
+        let found = graph.query(&start, &skills_only).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_find_path_shortest() {
+        let graph = KnowledgeGraph::new();
+        let e = chain(&graph).await;
+
+        let path = graph.find_path(&e[0].id, &e[2].id).await.unwrap();
+        assert_eq!(path.len(), 1);
+        assert!(graph.find_path(&e[2].id, &e[0].id).await.is_none());
+
+        let strong = GraphQuery::new()
+            .with_direction(Direction::Outgoing)
+            .with_min_weight(0.3);
+        let path = graph
+            .find_path_matching(&e[0].id, &e[2].id, &strong)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(path.len(), 2);
+
+        let subgraph = graph.get_subgraph(&e[2].id, 2).await;
+        assert_eq!(subgraph.entities.len(), 3);
+        assert_eq!(subgraph.relations.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_open_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kg.db");
+
+        let e = chain(&KnowledgeGraph::open(&path).unwrap()).await;
+        let graph = KnowledgeGraph::open(&path).unwrap();
+        assert_eq!(graph.stats().await.relation_count, 3);
+        assert_eq!(graph.search_entities(&["b"]).await[0].id, e[1].id);
+        assert!(graph.remove_entity(&e[1].id).await.unwrap());
+        assert_eq!(graph.stats().await.relation_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_forget_sources() {
+        let graph = KnowledgeGraph::new();
+
+        let mut shared = Entity::new("Rust".to_string(), EntityType::Skill);
+        shared.add_source("m1");
+        shared.add_source("m2");
+        let mut private = Entity::new("Alice".to_string(), EntityType::Person);
+        private.add_source("m1");
+        graph.add_entity(shared.clone()).await.unwrap();
+        graph.add_entity(private.clone()).await.unwrap();
+
+        let forgotten: HashSet<String> = ["m1".to_string()].into_iter().collect();
+        assert_eq!(graph.forget_sources(&forgotten).await.unwrap(), (1, 0));
+
+        assert!(graph.get_entity(&private.id).await.is_none());
+        let shared = graph.get_entity(&shared.id).await.unwrap();
+        assert_eq!(shared.source_ids(), vec!["m2".to_string()]);
+    }
 }
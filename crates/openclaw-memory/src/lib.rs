@@ -16,17 +16,27 @@ pub mod compress_adapter;
 pub mod config;
 pub mod conflict_resolver;
 pub mod embedding;
+pub mod entity_extractor;
 pub mod fact_extractor;
+pub mod file_index;
 pub mod file_tracker;
 pub mod file_watcher;
 pub mod graph_context;
+pub mod graph_interchange;
+pub mod graph_store;
 pub mod hybrid_search;
+pub mod ingest;
 pub mod knowledge_graph;
+#[cfg(feature = "local-embedding")]
+pub mod local_embedding;
 pub mod maintenance_scheduler;
 pub mod manager;
+pub mod privacy;
 pub mod pruning;
 pub mod recall;
 pub mod recall_strategy;
+pub mod reindex;
+pub mod rerank;
 pub mod scorer;
 pub mod store;
 pub mod schema;
@@ -38,10 +48,10 @@ pub mod working;
 pub mod workspace;
 pub mod workspace_config;
 
-pub use config::{create_memory_store, create_memory_store_from_config, create_embedding_provider_from_config, MemoryBackend as MemoryStoreBackend};
+pub use config::{create_memory_store, create_memory_store_from_config, create_embedding_provider_from_config, create_local_embedding_provider, MemoryBackend as MemoryStoreBackend};
 pub use factory::{MemoryBackend, HybridMemoryBackend, MemoryManagerFactory, HybridMemoryFactory};
 pub use manager::MemoryManager;
-pub use types::{MemoryConfig, MemoryContent, MemoryItem, MemoryLevel, MemoryRetrieval};
+pub use types::{MemoryConfig, MemoryContent, MemoryItem, MemoryLevel, MemoryNamespace, MemoryRetrieval, PrivacyConfig};
 pub use recall::{MemoryRecall, RecallResult, RecallConfig, SimpleMemoryRecall};
 pub use scorer::ImportanceScorer;
 pub use compressor::MemoryCompressor;
@@ -50,7 +60,15 @@ pub use workspace_config::{WorkspaceConfig, WorkspacesConfig};
 
 pub use bm25::Bm25Index;
 pub use chunk::ChunkManager;
+pub use entity_extractor::{EntityExtractor, GraphExtractor, LLMEntityExtractor, RuleBasedEntityExtractor};
+pub use file_index::{FileCorpusIndex, FileHit, FileIndexConfig};
 pub use file_tracker::{FileTracker, FileTrackerConfig};
+pub use graph_store::{GraphStore, MemoryGraphStore, SqliteGraphStore};
+pub use knowledge_graph::{GraphMatch, GraphQuery, KnowledgeGraph};
+pub use ingest::{ExtractorRegistry, IngestPipeline};
+pub use privacy::{ForgetReceipt, ForgetTarget, MemoryEraser, NamespacedVectorStore, PiiRedactor};
 pub use recall_strategy::{RecallStrategy, RecallItem};
+pub use reindex::{ReindexHandle, ReindexJob, ReindexProgress};
+pub use rerank::{ApiReranker, LlmReranker, RerankStage, Reranker, RerankerSettings};
 pub use workspace::AgentWorkspace;
-pub use schema::{CONTENT, TEXT_PREVIEW, EMBEDDING, TIMESTAMP, MEMORY_LEVEL, MEMORY_ID, CATEGORY, SOURCE, IMPORTANCE, TAGS, METADATA};
+pub use schema::{CONTENT, TEXT_PREVIEW, EMBEDDING, TIMESTAMP, MEMORY_LEVEL, MEMORY_ID, CATEGORY, SOURCE, IMPORTANCE, TAGS, METADATA, USER_ID, PERSONA_ID};
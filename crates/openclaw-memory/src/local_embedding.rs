@@ -0,0 +1,590 @@
+//! 本地嵌入模型
+//!
+//! 在进程内用 candle 于 CPU 上运行 BERT 类 sentence-transformer 模型。
+//! 模型首次使用时从 Hugging Face 下载到本地缓存，之后可完全离线运行。
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use candle_core::{DType, Device, IndexOp, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config as BertConfig};
+use hf_hub::api::tokio::{ApiBuilder, ApiRepo};
+use hf_hub::{Cache, CacheRepo, Repo, RepoType};
+use openclaw_core::{OpenClawError, Result};
+use serde::Deserialize;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+use crate::embedding::{Embedding, EmbeddingProvider, Embeddings};
+
+/// 默认模型
+pub const DEFAULT_LOCAL_MODEL: &str = "sentence-transformers/all-MiniLM-L6-v2";
+/// 默认模型缓存目录
+pub const DEFAULT_MODEL_CACHE_DIR: &str = "data/models";
+
+/// 已知模型及其输出维度，用于在加载前确定向量维度
+const KNOWN_MODELS: &[(&str, usize)] = &[
+    ("sentence-transformers/all-MiniLM-L6-v2", 384),
+    ("sentence-transformers/all-MiniLM-L12-v2", 384),
+    (
+        "sentence-transformers/paraphrase-multilingual-MiniLM-L12-v2",
+        384,
+    ),
+    ("BAAI/bge-small-en-v1.5", 384),
+    ("BAAI/bge-small-zh-v1.5", 512),
+    ("BAAI/bge-base-en-v1.5", 768),
+];
+
+/// 将简写模型名（如 `all-MiniLM-L6-v2`）展开为完整仓库名
+pub fn resolve_model_id(model: &str) -> String {
+    let model = model.trim();
+    if model.is_empty() {
+        return DEFAULT_LOCAL_MODEL.to_string();
+    }
+    if model.contains('/') || Path::new(model).exists() {
+        return model.to_string();
+    }
+    KNOWN_MODELS
+        .iter()
+        .find(|(id, _)| {
+            id.rsplit('/')
+                .next()
+                .is_some_and(|name| name.eq_ignore_ascii_case(model))
+        })
+        .map(|(id, _)| id.to_string())
+        .unwrap_or_else(|| model.to_string())
+}
+
+/// 已知模型的输出维度，未知模型返回 None
+pub fn known_dimensions(model: &str) -> Option<usize> {
+    let id = resolve_model_id(model);
+    KNOWN_MODELS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(&id))
+        .map(|(_, dims)| *dims)
+}
+
+/// 权重精度，半精度变体占用更少内存
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightPrecision {
+    #[default]
+    F32,
+    F16,
+    BF16,
+}
+
+impl WeightPrecision {
+    fn dtype(self) -> DType {
+        match self {
+            Self::F32 => DType::F32,
+            Self::F16 => DType::F16,
+            Self::BF16 => DType::BF16,
+        }
+    }
+}
+
+impl From<&str> for WeightPrecision {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "f16" | "fp16" | "half" => Self::F16,
+            "bf16" => Self::BF16,
+            _ => Self::F32,
+        }
+    }
+}
+
+/// 池化方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pooling {
+    Mean,
+    Cls,
+}
+
+#[derive(Deserialize)]
+struct PoolingConfig {
+    #[serde(default)]
+    pooling_mode_cls_token: bool,
+}
+
+/// 本地嵌入配置
+#[derive(Debug, Clone)]
+pub struct LocalEmbeddingConfig {
+    /// Hugging Face 仓库名或本地模型目录
+    pub model: String,
+    pub revision: String,
+    pub cache_dir: PathBuf,
+    pub precision: WeightPrecision,
+    pub batch_size: usize,
+    pub max_length: usize,
+    /// 截断后的输出维度，None 表示使用模型原始维度
+    pub dimensions: Option<usize>,
+    /// 仅使用本地缓存，不访问网络
+    pub offline: bool,
+}
+
+impl Default for LocalEmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            model: DEFAULT_LOCAL_MODEL.to_string(),
+            revision: "main".to_string(),
+            cache_dir: PathBuf::from(DEFAULT_MODEL_CACHE_DIR),
+            precision: WeightPrecision::F32,
+            batch_size: 32,
+            max_length: 256,
+            dimensions: None,
+            offline: false,
+        }
+    }
+}
+
+impl LocalEmbeddingConfig {
+    pub fn new(model: impl AsRef<str>) -> Self {
+        Self {
+            model: resolve_model_id(model.as_ref()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_revision(mut self, revision: impl Into<String>) -> Self {
+        self.revision = revision.into();
+        self
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    pub fn with_precision(mut self, precision: WeightPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length.max(1);
+        self
+    }
+
+    pub fn with_dimensions(mut self, dimensions: usize) -> Self {
+        self.dimensions = Some(dimensions);
+        self
+    }
+
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// 加载前即可确定的输出维度
+    pub fn expected_dimensions(&self) -> Option<usize> {
+        self.dimensions.or_else(|| known_dimensions(&self.model))
+    }
+}
+
+/// 模型所需的本地文件
+pub(crate) struct ModelFiles {
+    pub(crate) config: PathBuf,
+    pub(crate) tokenizer: PathBuf,
+    pub(crate) weights: PathBuf,
+    pooling: Option<PathBuf>,
+}
+
+const WEIGHT_FILES: [&str; 2] = ["model.safetensors", "pytorch_model.bin"];
+const POOLING_FILE: &str = "1_Pooling/config.json";
+
+/// 模型文件来源：先查缓存，未命中且允许联网时再下载
+struct ModelSource {
+    cache: CacheRepo,
+    api: Option<ApiRepo>,
+    model: String,
+}
+
+impl ModelSource {
+    fn new(config: &LocalEmbeddingConfig) -> Result<Self> {
+        let repo = Repo::with_revision(
+            config.model.clone(),
+            RepoType::Model,
+            config.revision.clone(),
+        );
+        let cache = Cache::new(config.cache_dir.clone()).repo(repo.clone());
+        let api = if config.offline {
+            None
+        } else {
+            let api = ApiBuilder::new()
+                .with_cache_dir(config.cache_dir.clone())
+                .with_progress(false)
+                .build()
+                .map_err(model_error)?;
+            Some(api.repo(repo))
+        };
+        Ok(Self {
+            cache,
+            api,
+            model: config.model.clone(),
+        })
+    }
+
+    async fn get(&self, filename: &str) -> Result<PathBuf> {
+        if let Some(path) = self.cache.get(filename) {
+            return Ok(path);
+        }
+        match &self.api {
+            Some(api) => api.get(filename).await.map_err(|e| {
+                OpenClawError::AIProvider(format!(
+                    "下载模型文件 {}/{} 失败: {}",
+                    self.model, filename, e
+                ))
+            }),
+            None => Err(OpenClawError::Config(format!(
+                "模型文件 {}/{} 未缓存，且当前为离线模式",
+                self.model, filename
+            ))),
+        }
+    }
+
+    async fn resolve(&self) -> Result<ModelFiles> {
+        let config = self.get("config.json").await?;
+        let tokenizer = self.get("tokenizer.json").await?;
+        let mut weights = None;
+        let mut last_error = None;
+        for name in WEIGHT_FILES {
+            match self.get(name).await {
+                Ok(path) => {
+                    weights = Some(path);
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let weights = match (weights, last_error) {
+            (Some(path), _) => path,
+            (None, Some(e)) => return Err(e),
+            (None, None) => unreachable!("WEIGHT_FILES 非空"),
+        };
+        let pooling = self.get(POOLING_FILE).await.ok();
+        Ok(ModelFiles {
+            config,
+            tokenizer,
+            weights,
+            pooling,
+        })
+    }
+}
+
+/// 从本地目录定位模型文件
+fn files_from_dir(dir: &Path) -> Result<ModelFiles> {
+    let weights = WEIGHT_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            OpenClawError::Config(format!("模型目录 {} 中缺少权重文件", dir.display()))
+        })?;
+    let tokenizer = dir.join("tokenizer.json");
+    if !tokenizer.is_file() {
+        return Err(OpenClawError::Config(format!(
+            "模型目录 {} 中缺少 tokenizer.json",
+            dir.display()
+        )));
+    }
+    let pooling = Some(dir.join(POOLING_FILE)).filter(|path| path.is_file());
+    Ok(ModelFiles {
+        config: dir.join("config.json"),
+        tokenizer,
+        weights,
+        pooling,
+    })
+}
+
+pub(crate) fn model_error(e: impl std::fmt::Display) -> OpenClawError {
+    OpenClawError::AIProvider(format!("本地模型错误: {}", e))
+}
+
+/// 定位模型文件：本地目录直接使用，否则从缓存或 Hugging Face 获取
+pub(crate) async fn resolve_files(config: &LocalEmbeddingConfig) -> Result<ModelFiles> {
+    let dir = Path::new(&config.model);
+    if dir.join("config.json").is_file() {
+        files_from_dir(dir)
+    } else {
+        ModelSource::new(config)?.resolve().await
+    }
+}
+
+/// 加载分词器，按批内最长序列补齐并截断到 `max_length`
+pub(crate) fn load_tokenizer(path: &Path, max_length: usize) -> Result<Tokenizer> {
+    let mut tokenizer = Tokenizer::from_file(path).map_err(model_error)?;
+    tokenizer.with_padding(Some(PaddingParams {
+        strategy: PaddingStrategy::BatchLongest,
+        ..Default::default()
+    }));
+    tokenizer
+        .with_truncation(Some(TruncationParams {
+            max_length,
+            ..Default::default()
+        }))
+        .map_err(model_error)?;
+    Ok(tokenizer)
+}
+
+/// 以指定精度在 CPU 上加载权重
+pub(crate) fn load_weights(
+    files: &ModelFiles,
+    precision: WeightPrecision,
+) -> Result<VarBuilder<'static>> {
+    let device = Device::Cpu;
+    let dtype = precision.dtype();
+    let is_safetensors = files
+        .weights
+        .extension()
+        .is_some_and(|ext| ext == "safetensors");
+    if is_safetensors {
+        VarBuilder::from_buffered_safetensors(std::fs::read(&files.weights)?, dtype, &device)
+    } else {
+        VarBuilder::from_pth(&files.weights, dtype, &device)
+    }
+    .map_err(model_error)
+}
+
+/// 已加载到内存中的模型
+struct LoadedModel {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    pooling: Pooling,
+    hidden_size: usize,
+    device: Device,
+}
+
+impl LoadedModel {
+    fn load(files: &ModelFiles, config: &LocalEmbeddingConfig) -> Result<Self> {
+        let bert_config: BertConfig =
+            serde_json::from_str(&std::fs::read_to_string(&files.config)?)?;
+
+        let tokenizer = load_tokenizer(
+            &files.tokenizer,
+            config.max_length.min(bert_config.max_position_embeddings),
+        )?;
+
+        let pooling = match &files.pooling {
+            Some(path) => {
+                let pooling: PoolingConfig = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+                if pooling.pooling_mode_cls_token {
+                    Pooling::Cls
+                } else {
+                    Pooling::Mean
+                }
+            }
+            None => Pooling::Mean,
+        };
+
+        let vb = load_weights(files, config.precision)?;
+        let device = vb.device().clone();
+        let model = BertModel::load(vb, &bert_config).map_err(model_error)?;
+
+        Ok(Self {
+            model,
+            tokenizer,
+            pooling,
+            hidden_size: bert_config.hidden_size,
+            device,
+        })
+    }
+
+    /// 对一批文本推理，返回池化后的原始向量
+    fn forward(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(model_error)?;
+
+        let ids = encodings
+            .iter()
+            .map(|e| Tensor::new(e.get_ids(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()
+            .map_err(model_error)?;
+        let masks = encodings
+            .iter()
+            .map(|e| Tensor::new(e.get_attention_mask(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()
+            .map_err(model_error)?;
+
+        let run = || -> candle_core::Result<Vec<Vec<f32>>> {
+            let input_ids = Tensor::stack(&ids, 0)?;
+            let mask = Tensor::stack(&masks, 0)?;
+            let token_type_ids = input_ids.zeros_like()?;
+            let hidden = self
+                .model
+                .forward(&input_ids, &token_type_ids, Some(&mask))?;
+            pool(&hidden, &mask, self.pooling)?
+                .to_dtype(DType::F32)?
+                .to_vec2()
+        };
+        run().map_err(model_error)
+    }
+}
+
+/// 将 (batch, seq, hidden) 的隐状态池化为 (batch, hidden)
+fn pool(hidden: &Tensor, mask: &Tensor, pooling: Pooling) -> candle_core::Result<Tensor> {
+    match pooling {
+        Pooling::Cls => hidden.i((.., 0)),
+        Pooling::Mean => {
+            let mask = mask.to_dtype(hidden.dtype())?.unsqueeze(2)?;
+            let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+            let counts = mask.sum(1)?.clamp(1e-9, f64::MAX)?;
+            summed.broadcast_div(&counts)
+        }
+    }
+}
+
+/// 截断到目标维度并做 L2 归一化
+fn finish_embedding(mut vector: Vec<f32>, dimensions: usize) -> Embedding {
+    vector.truncate(dimensions);
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > f32::EPSILON {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
+
+/// 进程内本地嵌入提供者
+pub struct LocalEmbedding {
+    model_name: String,
+    dimensions: usize,
+    batch_size: usize,
+    inner: Arc<LoadedModel>,
+}
+
+impl LocalEmbedding {
+    /// 加载模型，必要时下载到缓存目录
+    pub async fn load(config: LocalEmbeddingConfig) -> Result<Self> {
+        let files = resolve_files(&config).await?;
+
+        let load_config = config.clone();
+        let inner = tokio::task::spawn_blocking(move || LoadedModel::load(&files, &load_config))
+            .await
+            .map_err(model_error)??;
+
+        let dimensions = match config.dimensions {
+            Some(dims) if dims == 0 || dims > inner.hidden_size => {
+                return Err(OpenClawError::Config(format!(
+                    "嵌入维度 {} 无效，模型 {} 的隐藏维度为 {}",
+                    dims, config.model, inner.hidden_size
+                )));
+            }
+            Some(dims) => dims,
+            None => inner.hidden_size,
+        };
+
+        tracing::info!(
+            "本地嵌入模型已加载: {} (维度 {}, 精度 {:?})",
+            config.model,
+            dimensions,
+            config.precision
+        );
+
+        Ok(Self {
+            model_name: config.model,
+            dimensions,
+            batch_size: config.batch_size.max(1),
+            inner: Arc::new(inner),
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for LocalEmbedding {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn model(&self) -> &str {
+        &self.model_name
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    async fn embed(&self, text: &str) -> Result<Embedding> {
+        let mut embeddings = self.embed_batch(&[text.to_string()]).await?;
+        embeddings
+            .pop()
+            .ok_or_else(|| OpenClawError::AIProvider("本地嵌入模型未返回结果".to_string()))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Embeddings> {
+        let mut results = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            let inner = self.inner.clone();
+            let batch = batch.to_vec();
+            let vectors = tokio::task::spawn_blocking(move || inner.forward(&batch))
+                .await
+                .map_err(model_error)??;
+            results.extend(
+                vectors
+                    .into_iter()
+                    .map(|v| finish_embedding(v, self.dimensions)),
+            );
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_model_id_and_dimensions() {
+        assert_eq!(resolve_model_id(""), DEFAULT_LOCAL_MODEL);
+        assert_eq!(resolve_model_id("all-minilm-l6-v2"), DEFAULT_LOCAL_MODEL);
+        assert_eq!(
+            resolve_model_id("bge-small-zh-v1.5"),
+            "BAAI/bge-small-zh-v1.5"
+        );
+        assert_eq!(known_dimensions("bge-small-zh-v1.5"), Some(512));
+        assert_eq!(known_dimensions("someone/unknown-model"), None);
+
+        let config = LocalEmbeddingConfig::new("all-MiniLM-L6-v2");
+        assert_eq!(config.expected_dimensions(), Some(384));
+        assert_eq!(config.with_dimensions(128).expected_dimensions(), Some(128));
+    }
+
+    #[test]
+    fn test_pooling_and_normalize() {
+        let device = Device::Cpu;
+        let hidden = Tensor::new(&[[[1f32, 2.0], [3.0, 4.0], [100.0, 100.0]]], &device).unwrap();
+        let mask = Tensor::new(&[[1u32, 1, 0]], &device).unwrap();
+
+        let mean: Vec<Vec<f32>> = pool(&hidden, &mask, Pooling::Mean)
+            .unwrap()
+            .to_vec2()
+            .unwrap();
+        assert_eq!(mean, vec![vec![2.0, 3.0]]);
+
+        let cls: Vec<Vec<f32>> = pool(&hidden, &mask, Pooling::Cls)
+            .unwrap()
+            .to_vec2()
+            .unwrap();
+        assert_eq!(cls, vec![vec![1.0, 2.0]]);
+
+        let v = finish_embedding(vec![3.0, 4.0, 12.0], 2);
+        assert_eq!(v.len(), 2);
+        assert!((v[0] - 0.6).abs() < 1e-6 && (v[1] - 0.8).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_offline_without_cache_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = LocalEmbeddingConfig::new("all-MiniLM-L6-v2")
+            .with_cache_dir(dir.path())
+            .with_offline(true);
+        let err = LocalEmbedding::load(config).await.err().unwrap();
+        assert!(matches!(err, OpenClawError::Config(_)));
+    }
+}
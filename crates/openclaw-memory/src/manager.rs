@@ -6,12 +6,18 @@ use std::sync::Arc;
 use openclaw_core::{Message, OpenClawError, Result};
 use openclaw_vector::VectorStore;
 
+use crate::checkpoint_store::CheckpointStore;
 use crate::compressor::MemoryCompressor;
 use crate::embedding::EmbeddingProvider;
 use crate::hybrid_search::{HybridSearchConfig, HybridSearchManager};
+use crate::privacy::{
+    ForgetReceipt, ForgetTarget, MemoryEraser, NamespacedVectorStore, PiiRedactor, markdown_tag,
+};
 use crate::recall::{MemoryRecall, RecallResult, SimpleMemoryRecall};
 use crate::scorer::ImportanceScorer;
-use crate::types::{MemoryConfig, MemoryContent, MemoryItem, MemoryLevel, MemoryRetrieval};
+use crate::types::{
+    MemoryConfig, MemoryContent, MemoryItem, MemoryLevel, MemoryNamespace, MemoryRetrieval,
+};
 use crate::working::WorkingMemory;
 use crate::workspace::AgentWorkspace;
 
@@ -28,10 +34,17 @@ pub struct MemoryManager {
     embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
     recall_strategy: Option<Arc<dyn MemoryRecall>>,
     workspace: Option<Arc<AgentWorkspace>>,
+    namespace: Option<MemoryNamespace>,
+    redactor: Option<Arc<PiiRedactor>>,
+    checkpoints: Option<Arc<CheckpointStore>>,
 }
 
 impl MemoryManager {
     pub fn new(config: MemoryConfig) -> Self {
+        let redactor = config
+            .privacy
+            .redact_pii
+            .then(|| Arc::new(PiiRedactor::new()));
         Self {
             working: WorkingMemory::new(config.working.clone()),
             short_term: Vec::new(),
@@ -43,6 +56,47 @@ impl MemoryManager {
             embedding_provider: None,
             recall_strategy: None,
             workspace: None,
+            namespace: None,
+            redactor,
+            checkpoints: None,
+        }
+    }
+
+    /// 限定在某个用户/persona 的命名空间内，所有存储读写都带上命名空间过滤
+    pub fn with_namespace(mut self, namespace: MemoryNamespace) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
+    /// 设置持久化前使用的脱敏器
+    pub fn with_redactor(mut self, redactor: Arc<PiiRedactor>) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// 设置检查点存储，遗忘时一并清理
+    pub fn with_checkpoint_store(mut self, checkpoints: Arc<CheckpointStore>) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    pub fn namespace(&self) -> Option<&MemoryNamespace> {
+        self.namespace.as_ref()
+    }
+
+    /// 长期记忆存储，设置了命名空间时包装为隔离视图
+    fn long_term_store(&self) -> Option<Arc<dyn VectorStore>> {
+        let store = self.long_term.clone()?;
+        Some(match &self.namespace {
+            Some(namespace) => Arc::new(NamespacedVectorStore::new(store, namespace.clone())),
+            None => store,
+        })
+    }
+
+    fn redact(&self, text: &str) -> String {
+        match &self.redactor {
+            Some(redactor) => redactor.redact_text(text),
+            None => text.to_string(),
         }
     }
 
@@ -85,16 +139,23 @@ impl MemoryManager {
         self
     }
 
+    /// 设置共享的嵌入向量提供者
+    pub fn with_shared_embedding_provider(mut self, provider: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedding_provider = Some(provider);
+        self
+    }
+
     /// 自动召回相关记忆
     pub async fn recall(&self, query: &str) -> Result<RecallResult> {
+        let namespace = self.namespace.as_ref();
         if let Some(strategy) = &self.recall_strategy {
-            return strategy.recall(query, None, None).await;
+            return strategy.recall(query, namespace, None).await;
         }
         
         if let Some(provider) = &self.embedding_provider {
-            if let Some(vector_store) = &self.long_term {
-                let recall_tool = SimpleMemoryRecall::new(provider.clone(), vector_store.clone());
-                return recall_tool.recall(query, None, None).await;
+            if let Some(vector_store) = self.long_term_store() {
+                let recall_tool = SimpleMemoryRecall::new(provider.clone(), vector_store);
+                return recall_tool.recall(query, namespace, None).await;
             }
             return Err(OpenClawError::Memory(
                 "Vector store not configured".to_string(),
@@ -108,7 +169,8 @@ impl MemoryManager {
 
     /// 添加消息到记忆
     pub async fn add(&mut self, message: Message) -> Result<()> {
-        self.add_with_namespace(message, None).await
+        let namespace = self.namespace.clone();
+        self.add_with_namespace(message, namespace).await
     }
 
     /// 添加消息到记忆（带命名空间）
@@ -128,7 +190,7 @@ impl MemoryManager {
                 // 将最旧的摘要移到长期记忆
                 if let Some(old_summary) = self.short_term.first().cloned() {
                     if self.config.long_term.enabled
-                        && let Some(store) = &self.long_term
+                        && let Some(store) = self.long_term_store()
                     {
                         self.archive_to_long_term(store.as_ref(), old_summary)
                             .await?;
@@ -169,7 +231,10 @@ impl MemoryManager {
         if self.config.long_term.enabled
             && let Some(search) = &self.hybrid_search
         {
-            let config = HybridSearchConfig::default();
+            let config = HybridSearchConfig {
+                namespace: self.namespace.clone(),
+                ..Default::default()
+            };
             if let Ok(results) = search.search(_query, None, &config).await {
                 for result in results {
                     let content_preview = result
@@ -182,7 +247,7 @@ impl MemoryManager {
                     let token_count = content_preview.len() / 4;
                     let memory_item = MemoryItem {
                         id: uuid::Uuid::new_v4(),
-                        namespace: None,
+                        namespace: self.namespace.clone(),
                         level: MemoryLevel::LongTerm,
                         content: MemoryContent::VectorRef {
                             vector_id: result.id.clone(),
@@ -228,20 +293,71 @@ impl MemoryManager {
         self.working.clear();
         self.short_term.clear();
 
-        if let Some(store) = &self.long_term {
+        if let Some(store) = self.long_term_store() {
             store.clear().await?;
         }
 
         Ok(())
     }
 
+    /// 遗忘某个用户或包含查询文本的全部记忆，返回审计回执
+    ///
+    /// 覆盖会话内记忆、向量存储、BM25 索引、知识图谱、工作区 Markdown 和检查点；
+    /// 设置了命名空间时只删除本命名空间的存储条目。
+    pub async fn forget(&mut self, target: ForgetTarget) -> Result<ForgetReceipt> {
+        if let (ForgetTarget::User { user_id }, Some(namespace)) = (&target, &self.namespace)
+            && &namespace.user_id != user_id
+        {
+            return Err(OpenClawError::Memory(format!(
+                "Cannot forget user {} from namespace of {}",
+                user_id, namespace.user_id
+            )));
+        }
+
+        let mut eraser = MemoryEraser::new()
+            .with_namespace(self.namespace.clone())
+            .with_audit_log(self.config.privacy.audit_log.clone());
+        if let Some(store) = self.long_term_store() {
+            eraser = eraser.with_vector_store(store);
+        }
+        if let Some(search) = &self.hybrid_search {
+            if let Some(bm25) = search.bm25_index() {
+                eraser = eraser.with_bm25(bm25.clone());
+            }
+            if let Some(kg) = search.knowledge_graph() {
+                eraser = eraser.with_knowledge_graph(kg.clone());
+            }
+        }
+        if let Some(workspace) = &self.workspace {
+            eraser = eraser.with_workspace(workspace.clone());
+        }
+        if let Some(checkpoints) = &self.checkpoints {
+            eraser = eraser.with_checkpoints(checkpoints.clone());
+        }
+
+        let mut receipt = ForgetReceipt::new(&target);
+        let mut removed = self.working.remove_where(|item| target.matches_item(item));
+        let (forgotten, kept) = std::mem::take(&mut self.short_term)
+            .into_iter()
+            .partition(|item| target.matches_item(item));
+        self.short_term = kept;
+        removed.extend(forgotten);
+        receipt.session_items = removed.len();
+        // 导出的 Markdown 按记忆 ID 清理
+        receipt
+            .memory_ids
+            .extend(removed.iter().map(|item| item.id.to_string()));
+
+        Ok(eraser.forget_into(&target, receipt).await)
+    }
+
     /// 归档到长期记忆
     async fn archive_to_long_term(
         &self,
         store: &dyn VectorStore,
         mut item: MemoryItem,
     ) -> Result<()> {
-        let text = item.content.to_text();
+        let text = self.redact(&item.content.to_text());
         let vector_id = item.id.to_string();
 
         let embedding = if let Some(provider) = &self.embedding_provider {
@@ -300,9 +416,9 @@ impl MemoryManager {
         if !working_items.is_empty() {
             md.push_str("## 最近对话\n\n");
             for item in working_items.iter().rev().take(50) {
-                let content = item.content.to_text();
+                let content = self.redact(&item.content.to_text());
                 if !content.is_empty() {
-                    md.push_str(&format!("- {}\n", content));
+                    md.push_str(&self.markdown_entry(&item.id.to_string(), &content));
                     count += 1;
                 }
             }
@@ -312,11 +428,12 @@ impl MemoryManager {
         if !self.short_term.is_empty() {
             md.push_str("## 摘要\n\n");
             for item in &self.short_term {
-                let content = item.content.to_text();
+                let content = self.redact(&item.content.to_text());
                 if !content.is_empty() {
                     md.push_str(&format!(
-                        "### {}\n\n{}\n\n---\n\n",
+                        "### {} {}\n\n{}\n\n---\n\n",
                         item.created_at.format("%Y-%m-%d %H:%M"),
+                        markdown_tag(self.namespace.as_ref(), &item.id.to_string()),
                         content
                     ));
                     count += 1;
@@ -324,7 +441,7 @@ impl MemoryManager {
             }
         }
 
-        if let Some(store) = &self.long_term {
+        if let Some(store) = self.long_term_store() {
             let empty_vector = vec![0.0; 384];
             let query = openclaw_vector::SearchQuery::new(empty_vector);
             if let Ok(items) = store.search(query).await {
@@ -336,7 +453,7 @@ impl MemoryManager {
                         .and_then(|v| v.as_str())
                         .unwrap_or("");
                     if !content.is_empty() {
-                        md.push_str(&format!("- {}\n", content));
+                        md.push_str(&self.markdown_entry(&item.id, &self.redact(content)));
                         count += 1;
                     }
                 }
@@ -347,6 +464,16 @@ impl MemoryManager {
         Ok(count)
     }
 
+    /// 单行列表项，末尾附带记忆标记以便按 ID 遗忘
+    fn markdown_entry(&self, id: &str, content: &str) -> String {
+        let content = content.split_whitespace().collect::<Vec<_>>().join(" ");
+        format!(
+            "- {} {}\n",
+            content,
+            markdown_tag(self.namespace.as_ref(), id)
+        )
+    }
+
     /// 导出到默认路径 (需要先设置 workspace)
     pub async fn export_to_markdown_default(&self) -> Result<usize> {
         let path = self.get_default_markdown_path()?;
@@ -393,7 +520,7 @@ impl MemoryManager {
         let mut count = 0;
 
         for item in items {
-            if let Some(store) = &self.long_term {
+            if let Some(store) = self.long_term_store() {
                 store.upsert(item).await?;
                 count += 1;
             }
@@ -622,4 +749,33 @@ mod tests {
         assert!(content.contains("## 最近对话"));
         assert!(content.contains("今天天气真好"));
     }
+
+    #[tokio::test]
+    async fn test_forget_and_redact() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = MemoryConfig::default();
+        config.privacy.audit_log = temp_dir.path().join("audit.jsonl");
+        let workspace = AgentWorkspace::new("assistant".to_string(), temp_dir.path().to_path_buf());
+        let mut manager = MemoryManager::new(config)
+            .with_namespace(MemoryNamespace::new("alice", "assistant"))
+            .with_workspace(Arc::new(workspace.clone()));
+
+        manager.add(Message::user("我的邮箱是 alice@example.com")).await.unwrap();
+        manager.add(Message::user("我住在海港街 42 号")).await.unwrap();
+
+        manager.export_to_markdown_default().await.unwrap();
+        let content = std::fs::read_to_string(workspace.memory_path()).unwrap();
+        assert!(!content.contains("alice@example.com"));
+        assert!(content.contains("<!-- memory:alice/assistant:"));
+
+        assert!(manager.forget(ForgetTarget::user("bob")).await.is_err());
+        let receipt = manager.forget(ForgetTarget::query("海港街")).await.unwrap();
+        assert_eq!(receipt.session_items, 1);
+        assert_eq!(receipt.markdown_lines, 1);
+        assert_eq!(manager.stats().working_count, 1);
+        assert!(temp_dir.path().join("audit.jsonl").exists());
+
+        let content = std::fs::read_to_string(workspace.memory_path()).unwrap();
+        assert!(!content.contains("海港街") && content.contains("邮箱"));
+    }
 }
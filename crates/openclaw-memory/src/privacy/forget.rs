@@ -0,0 +1,574 @@
+//! 遗忘操作
+//!
+//! 按用户或查询在所有记忆存储中删除匹配内容，结果写成回执并追加到审计日志。
+//! 回执不记录被删除的原文，查询只保留摘要。
+//!
+//! 工作区 Markdown 只删除带有记忆标记（见 [`markdown_tag`]）且 ID 或命名空间命中的条目，
+//! 手写内容不会因文本相似被误删。
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use openclaw_core::Result;
+use openclaw_vector::{Filter, VectorStore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+
+use crate::bm25::Bm25Index;
+use crate::checkpoint_store::CheckpointStore;
+use crate::knowledge_graph::KnowledgeGraph;
+use crate::schema;
+use crate::types::MemoryNamespace;
+use crate::workspace::AgentWorkspace;
+
+/// 扫描向量存储时的分页大小
+const SCAN_PAGE: usize = 256;
+/// 查询遗忘时从 BM25 索引取回的候选上限
+const BM25_CANDIDATES: usize = 1000;
+/// 按已删除内容匹配检查点消息时要求的最短长度，避免误删
+const MIN_CONTENT_MATCH: usize = 8;
+/// Markdown 记忆标记前缀
+const MARKDOWN_TAG_PREFIX: &str = "<!-- memory:";
+const MARKDOWN_TAG_SUFFIX: &str = " -->";
+
+/// 导出到 Markdown 的记忆条目末尾附加的标记：`<!-- memory:<用户>/<persona>:<ID> -->`
+pub fn markdown_tag(namespace: Option<&MemoryNamespace>, id: &str) -> String {
+    format!(
+        "{}{}:{}{}",
+        MARKDOWN_TAG_PREFIX,
+        namespace_key(namespace),
+        id,
+        MARKDOWN_TAG_SUFFIX
+    )
+}
+
+fn namespace_key(namespace: Option<&MemoryNamespace>) -> String {
+    namespace
+        .map(|ns| format!("{}/{}", ns.user_id, ns.persona_id))
+        .unwrap_or_default()
+}
+
+/// 解析行内的记忆标记，返回 (命名空间键, 记忆 ID)
+fn parse_markdown_tag(line: &str) -> Option<(&str, &str)> {
+    let start = line.rfind(MARKDOWN_TAG_PREFIX)? + MARKDOWN_TAG_PREFIX.len();
+    let tag = line[start..].strip_suffix(MARKDOWN_TAG_SUFFIX)?;
+    tag.rsplit_once(':')
+}
+
+/// 遗忘对象
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ForgetTarget {
+    /// 某个用户的全部记忆
+    User { user_id: String },
+    /// 内容包含查询文本的记忆，不区分大小写
+    Query { query: String },
+}
+
+impl ForgetTarget {
+    pub fn user(user_id: impl Into<String>) -> Self {
+        Self::User {
+            user_id: user_id.into(),
+        }
+    }
+
+    pub fn query(query: impl Into<String>) -> Self {
+        Self::Query {
+            query: query.into(),
+        }
+    }
+
+    /// 回执中的描述，查询只记录 SHA-256 摘要
+    fn describe(&self) -> String {
+        match self {
+            Self::User { user_id } => format!("user:{}", user_id),
+            Self::Query { query } => {
+                format!("query:sha256:{:x}", Sha256::digest(query.as_bytes()))
+            }
+        }
+    }
+
+    fn matches_payload(&self, payload: &serde_json::Value) -> bool {
+        match self {
+            Self::User { user_id } => {
+                payload.get(schema::USER_ID).and_then(|v| v.as_str()) == Some(user_id.as_str())
+            }
+            Self::Query { query } => payload
+                .get(schema::CONTENT)
+                .and_then(|v| v.as_str())
+                .is_some_and(|content| contains_ignore_case(content, query)),
+        }
+    }
+
+    /// 会话内的记忆项是否命中
+    pub fn matches_item(&self, item: &crate::types::MemoryItem) -> bool {
+        match self {
+            Self::User { user_id } => item
+                .namespace
+                .as_ref()
+                .is_some_and(|ns| &ns.user_id == user_id),
+            Self::Query { query } => contains_ignore_case(&item.content.to_text(), query),
+        }
+    }
+}
+
+fn contains_ignore_case(text: &str, needle: &str) -> bool {
+    text.to_lowercase().contains(&needle.to_lowercase())
+}
+
+/// 遗忘回执
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForgetReceipt {
+    pub id: String,
+    pub target: String,
+    pub requested_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub vector_items: usize,
+    pub bm25_documents: usize,
+    pub graph_entities: usize,
+    pub graph_relations: usize,
+    pub markdown_lines: usize,
+    pub checkpoints: usize,
+    pub checkpoint_messages: usize,
+    /// 工作记忆和短期记忆中删除的条目
+    pub session_items: usize,
+    /// 被删除的记忆 ID
+    pub memory_ids: Vec<String>,
+    /// 各存储删除失败的原因，为空表示全部完成
+    pub errors: Vec<String>,
+}
+
+impl ForgetReceipt {
+    pub(crate) fn new(target: &ForgetTarget) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            target: target.describe(),
+            requested_at: now,
+            completed_at: now,
+            vector_items: 0,
+            bm25_documents: 0,
+            graph_entities: 0,
+            graph_relations: 0,
+            markdown_lines: 0,
+            checkpoints: 0,
+            checkpoint_messages: 0,
+            session_items: 0,
+            memory_ids: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// 删除的条目总数
+    pub fn total(&self) -> usize {
+        self.vector_items
+            + self.bm25_documents
+            + self.graph_entities
+            + self.graph_relations
+            + self.markdown_lines
+            + self.checkpoints
+            + self.checkpoint_messages
+            + self.session_items
+    }
+}
+
+/// 跨存储执行遗忘
+#[derive(Default)]
+pub struct MemoryEraser {
+    vector_store: Option<Arc<dyn VectorStore>>,
+    bm25: Option<Arc<Bm25Index>>,
+    knowledge_graph: Option<Arc<RwLock<KnowledgeGraph>>>,
+    workspace: Option<Arc<AgentWorkspace>>,
+    checkpoints: Option<Arc<CheckpointStore>>,
+    namespace: Option<MemoryNamespace>,
+    audit_log: Option<PathBuf>,
+}
+
+impl MemoryEraser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_vector_store(mut self, store: Arc<dyn VectorStore>) -> Self {
+        self.vector_store = Some(store);
+        self
+    }
+
+    pub fn with_bm25(mut self, bm25: Arc<Bm25Index>) -> Self {
+        self.bm25 = Some(bm25);
+        self
+    }
+
+    pub fn with_knowledge_graph(mut self, kg: Arc<RwLock<KnowledgeGraph>>) -> Self {
+        self.knowledge_graph = Some(kg);
+        self
+    }
+
+    pub fn with_workspace(mut self, workspace: Arc<AgentWorkspace>) -> Self {
+        self.workspace = Some(workspace);
+        self
+    }
+
+    pub fn with_checkpoints(mut self, checkpoints: Arc<CheckpointStore>) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// 限定在命名空间内遗忘；共享的图谱只按来源记忆清理
+    pub fn with_namespace(mut self, namespace: Option<MemoryNamespace>) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    pub fn with_audit_log(mut self, path: impl Into<PathBuf>) -> Self {
+        self.audit_log = Some(path.into());
+        self
+    }
+
+    /// 执行遗忘；单个存储失败不会中断其余步骤，失败原因记录在回执中
+    pub async fn forget(&self, target: &ForgetTarget) -> ForgetReceipt {
+        self.forget_into(target, ForgetReceipt::new(target)).await
+    }
+
+    /// 在已有回执上继续执行，调用方可先填入会话内删除的条目数
+    pub(crate) async fn forget_into(
+        &self,
+        target: &ForgetTarget,
+        mut receipt: ForgetReceipt,
+    ) -> ForgetReceipt {
+        if let ForgetTarget::Query { query } = target
+            && query.trim().is_empty()
+        {
+            receipt.errors.push("empty forget query".to_string());
+            return receipt;
+        }
+
+        let contents = self.forget_vectors(target, &mut receipt).await;
+        self.forget_bm25(target, &mut receipt).await;
+        self.forget_graph(target, &mut receipt).await;
+
+        if let Some(workspace) = &self.workspace {
+            match workspace.forget_lines(|line| self.matches_markdown(target, &receipt, line)) {
+                Ok(count) => receipt.markdown_lines = count,
+                Err(e) => receipt.errors.push(format!("workspace: {}", e)),
+            }
+        }
+
+        // 检查点没有命名空间，按查询文本和已删除记忆的原文逐条匹配
+        let mut needles: Vec<String> = contents
+            .into_iter()
+            .map(|c| c.trim().to_lowercase())
+            .filter(|c| c.chars().count() >= MIN_CONTENT_MATCH)
+            .collect();
+        if let ForgetTarget::Query { query } = target {
+            needles.push(query.trim().to_lowercase());
+        }
+        let matches = |text: &str| {
+            let text = text.to_lowercase();
+            needles.iter().any(|needle| text.contains(needle.as_str()))
+        };
+
+        if let Some(checkpoints) = &self.checkpoints {
+            if let ForgetTarget::User { user_id } = target {
+                match checkpoints
+                    .delete_where(|c| {
+                        c.state.metadata.get(schema::USER_ID).map(String::as_str)
+                            == Some(user_id.as_str())
+                    })
+                    .await
+                {
+                    Ok(count) => receipt.checkpoints = count,
+                    Err(e) => receipt.errors.push(format!("checkpoints: {}", e)),
+                }
+            }
+            if !needles.is_empty() {
+                match checkpoints.scrub_messages(matches).await {
+                    Ok(count) => receipt.checkpoint_messages = count,
+                    Err(e) => receipt.errors.push(format!("checkpoints: {}", e)),
+                }
+            }
+        }
+
+        receipt.completed_at = Utc::now();
+        if let Some(path) = &self.audit_log
+            && let Err(e) = append_receipt(path, &receipt)
+        {
+            tracing::warn!("Failed to write forget receipt {}: {}", receipt.id, e);
+            receipt.errors.push(format!("audit log: {}", e));
+        }
+        tracing::info!(
+            "Forget {} finished: {} entries removed, {} errors",
+            receipt.target,
+            receipt.total(),
+            receipt.errors.len()
+        );
+        receipt
+    }
+
+    /// Markdown 行是否属于被遗忘的记忆：标记中的 ID 已删除，或按用户遗忘时命名空间属于该用户
+    fn matches_markdown(&self, target: &ForgetTarget, receipt: &ForgetReceipt, line: &str) -> bool {
+        let Some((key, id)) = parse_markdown_tag(line) else {
+            return false;
+        };
+        if self.namespace.is_some() && key != namespace_key(self.namespace.as_ref()) {
+            return false;
+        }
+        match target {
+            ForgetTarget::User { user_id } => {
+                key.split_once('/').map(|(user, _)| user) == Some(user_id.as_str())
+                    || receipt.memory_ids.iter().any(|m| m == id)
+            }
+            ForgetTarget::Query { .. } => receipt.memory_ids.iter().any(|m| m == id),
+        }
+    }
+
+    /// 删除匹配的向量项，返回其内容供其他存储匹配
+    async fn forget_vectors(
+        &self,
+        target: &ForgetTarget,
+        receipt: &mut ForgetReceipt,
+    ) -> Vec<String> {
+        let Some(store) = &self.vector_store else {
+            return Vec::new();
+        };
+
+        // 按用户遗忘时由存储过滤，查询遗忘只能逐条比对内容
+        let filter = match target {
+            ForgetTarget::User { user_id } => {
+                Some(Filter::eq(schema::USER_ID, serde_json::json!(user_id)))
+            }
+            ForgetTarget::Query { .. } => None,
+        };
+        let mut matched = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let page = match store
+                .scan(filter.as_ref(), after.as_deref(), SCAN_PAGE)
+                .await
+            {
+                Ok(page) => page,
+                Err(e) => {
+                    // 不支持遍历的后端只能按用户过滤删除
+                    if let Some(filter) = filter
+                        && after.is_none()
+                    {
+                        match store.delete_by_filter(filter).await {
+                            Ok(count) => receipt.vector_items += count,
+                            Err(e) => receipt.errors.push(format!("vector store: {}", e)),
+                        }
+                    } else {
+                        receipt.errors.push(format!("vector store: {}", e));
+                    }
+                    return Vec::new();
+                }
+            };
+            if page.is_empty() {
+                break;
+            }
+            after = page.last().map(|item| item.id.clone());
+            matched.extend(
+                page.into_iter()
+                    .filter(|item| target.matches_payload(&item.payload)),
+            );
+        }
+
+        let mut contents = Vec::new();
+        for item in matched {
+            match store.delete(&item.id).await {
+                Ok(()) => {
+                    receipt.vector_items += 1;
+                    if let Some(content) =
+                        item.payload.get(schema::CONTENT).and_then(|v| v.as_str())
+                    {
+                        contents.push(content.to_string());
+                    }
+                    receipt.memory_ids.push(item.id);
+                }
+                Err(e) => receipt
+                    .errors
+                    .push(format!("vector store {}: {}", item.id, e)),
+            }
+        }
+        contents
+    }
+
+    async fn forget_bm25(&self, target: &ForgetTarget, receipt: &mut ForgetReceipt) {
+        let Some(bm25) = &self.bm25 else {
+            return;
+        };
+
+        let mut ids = receipt.memory_ids.clone();
+        match target {
+            ForgetTarget::User { user_id } => match bm25.delete_by_user(user_id).await {
+                Ok(count) => receipt.bm25_documents += count,
+                Err(e) => receipt.errors.push(format!("bm25: {}", e)),
+            },
+            ForgetTarget::Query { query } => {
+                let hits = match &self.namespace {
+                    Some(namespace) => bm25.search_in_namespace(query, namespace, BM25_CANDIDATES),
+                    None => bm25.search(query, BM25_CANDIDATES),
+                };
+                match hits {
+                    Ok(hits) => ids.extend(
+                        hits.into_iter()
+                            .filter(|hit| contains_ignore_case(&hit.content, query))
+                            .map(|hit| hit.id),
+                    ),
+                    Err(e) => receipt.errors.push(format!("bm25: {}", e)),
+                }
+            }
+        }
+
+        ids.sort();
+        ids.dedup();
+        match bm25.delete_documents(&ids).await {
+            Ok(count) => receipt.bm25_documents += count,
+            Err(e) => receipt.errors.push(format!("bm25: {}", e)),
+        }
+    }
+
+    async fn forget_graph(&self, target: &ForgetTarget, receipt: &mut ForgetReceipt) {
+        let Some(kg) = &self.knowledge_graph else {
+            return;
+        };
+        let kg = kg.read().await;
+
+        let forgotten: HashSet<String> = receipt.memory_ids.iter().cloned().collect();
+        match kg.forget_sources(&forgotten).await {
+            Ok((entities, relations)) => {
+                receipt.graph_entities += entities;
+                receipt.graph_relations += relations;
+            }
+            Err(e) => receipt.errors.push(format!("knowledge graph: {}", e)),
+        }
+
+        // 图谱在用户间共享，只有全局遗忘才按文本删除实体
+        if let (ForgetTarget::Query { query }, None) = (target, &self.namespace) {
+            let result: Result<()> = async {
+                for entity in kg.store().all_entities().await? {
+                    if contains_ignore_case(&entity.describe(), query)
+                        && kg.remove_entity(&entity.id).await?
+                    {
+                        receipt.graph_entities += 1;
+                    }
+                }
+                Ok(())
+            }
+            .await;
+            if let Err(e) = result {
+                receipt.errors.push(format!("knowledge graph: {}", e));
+            }
+        }
+    }
+}
+
+/// 以 JSON Lines 追加回执
+fn append_receipt(path: &std::path::Path, receipt: &ForgetReceipt) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", serde_json::to_string(receipt)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::knowledge_graph::{Entity, EntityType};
+    use openclaw_vector::{MemoryStore, VectorItem};
+
+    async fn seed_store() -> Arc<dyn VectorStore> {
+        let store: Arc<dyn VectorStore> = Arc::new(MemoryStore::new());
+        for (id, user, content) in [
+            ("m1", "alice", "Alice lives at 42 Harbour Street"),
+            ("m2", "alice", "Alice prefers green tea"),
+            ("m3", "bob", "Bob lives at 7 Hill Road"),
+        ] {
+            let payload = serde_json::json!({"user_id": user, "content": content});
+            store
+                .upsert(VectorItem::new(vec![1.0, 0.0], payload).with_id(id))
+                .await
+                .unwrap();
+        }
+        store
+    }
+
+    #[tokio::test]
+    async fn test_forget_user_across_stores() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = seed_store().await;
+
+        let kg = KnowledgeGraph::new();
+        let mut entity = Entity::new("Harbour Street".to_string(), EntityType::Location);
+        entity.add_source("m1");
+        kg.add_entity(entity.clone()).await.unwrap();
+
+        let workspace = AgentWorkspace::new("agent".to_string(), dir.path().to_path_buf());
+        std::fs::write(
+            workspace.memory_path(),
+            format!(
+                "# Memory\n- Alice lives at 42 Harbour Street {}\n- Bob lives at 7 Hill Road {}\n\
+                 - Alice wrote this note by hand\n",
+                markdown_tag(None, "m1"),
+                markdown_tag(None, "m3"),
+            ),
+        )
+        .unwrap();
+
+        let audit_log = dir.path().join("audit.jsonl");
+        let eraser = MemoryEraser::new()
+            .with_vector_store(store.clone())
+            .with_knowledge_graph(Arc::new(RwLock::new(kg)))
+            .with_workspace(Arc::new(workspace.clone()))
+            .with_audit_log(&audit_log);
+
+        let receipt = eraser.forget(&ForgetTarget::user("alice")).await;
+        assert!(receipt.is_complete(), "{:?}", receipt.errors);
+        assert_eq!(receipt.vector_items, 2);
+        assert_eq!(receipt.graph_entities, 1);
+        assert_eq!(receipt.markdown_lines, 1);
+
+        let remaining = store.scan(None, None, 10).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "m3");
+        let memory = std::fs::read_to_string(workspace.memory_path()).unwrap();
+        assert!(!memory.contains("Harbour") && memory.contains("Bob"));
+        // 没有记忆标记的手写内容不按文本删除
+        assert!(memory.contains("by hand"));
+
+        let logged: ForgetReceipt =
+            serde_json::from_str(std::fs::read_to_string(&audit_log).unwrap().trim()).unwrap();
+        assert_eq!(logged.id, receipt.id);
+    }
+
+    #[tokio::test]
+    async fn test_forget_query_hides_text_in_receipt() {
+        let store = seed_store().await;
+        let eraser = MemoryEraser::new().with_vector_store(store.clone());
+
+        let receipt = eraser.forget(&ForgetTarget::query("lives at")).await;
+        assert_eq!(receipt.vector_items, 2);
+        assert!(receipt.target.starts_with("query:sha256:"));
+        assert!(
+            !serde_json::to_string(&receipt)
+                .unwrap()
+                .contains("lives at")
+        );
+
+        let empty = eraser.forget(&ForgetTarget::query("  ")).await;
+        assert!(!empty.is_complete());
+        assert_eq!(store.scan(None, None, 10).await.unwrap().len(), 1);
+    }
+}
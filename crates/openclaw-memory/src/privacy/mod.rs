@@ -0,0 +1,17 @@
+//! 记忆隐私控制
+//!
+//! - `NamespacedVectorStore`：为每次存储查询强制附加命名空间过滤，多用户部署下互不可见
+//! - `PiiRedactor`：持久化前按沙箱泄露检测规则脱敏
+//! - `MemoryEraser`：按用户或查询删除向量、BM25、图谱、工作区 Markdown 和检查点中的记忆，
+//!   并生成审计回执
+
+mod forget;
+mod namespace;
+mod redact;
+
+pub use forget::{ForgetReceipt, ForgetTarget, MemoryEraser, markdown_tag};
+pub use namespace::NamespacedVectorStore;
+pub use redact::PiiRedactor;
+
+/// 遗忘回执的默认审计日志
+pub const DEFAULT_AUDIT_LOG: &str = "data/privacy/forget_audit.jsonl";
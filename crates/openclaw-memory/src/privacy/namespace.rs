@@ -0,0 +1,189 @@
+//! 命名空间隔离的向量存储
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use openclaw_core::Result;
+use openclaw_vector::{
+    CollectionMetadata, Filter, SearchQuery, SearchResult, StoreStats, VectorItem, VectorStore,
+};
+
+use crate::types::MemoryNamespace;
+
+/// 包装向量存储，写入时标记命名空间，读取和删除时强制按命名空间过滤
+pub struct NamespacedVectorStore {
+    inner: Arc<dyn VectorStore>,
+    namespace: MemoryNamespace,
+}
+
+impl NamespacedVectorStore {
+    pub fn new(inner: Arc<dyn VectorStore>, namespace: MemoryNamespace) -> Self {
+        Self { inner, namespace }
+    }
+
+    pub fn namespace(&self) -> &MemoryNamespace {
+        &self.namespace
+    }
+
+    fn scoped(&self, filter: Option<Filter>) -> Filter {
+        match filter {
+            Some(filter) => filter.and(self.namespace.filter()),
+            None => self.namespace.filter(),
+        }
+    }
+
+    fn stamp(&self, mut item: VectorItem) -> VectorItem {
+        self.namespace.stamp(&mut item.payload);
+        item
+    }
+}
+
+#[async_trait]
+impl VectorStore for NamespacedVectorStore {
+    async fn upsert(&self, item: VectorItem) -> Result<()> {
+        // 同 id 的条目属于其他命名空间时不能覆盖
+        if let Some(existing) = self.inner.get(&item.id).await?
+            && !self.namespace.matches(&existing.payload)
+        {
+            return Err(openclaw_core::OpenClawError::Memory(format!(
+                "Memory item {} belongs to another namespace",
+                item.id
+            )));
+        }
+        self.inner.upsert(self.stamp(item)).await
+    }
+
+    async fn upsert_batch(&self, items: Vec<VectorItem>) -> Result<usize> {
+        let mut stamped = Vec::with_capacity(items.len());
+        for item in items {
+            if let Some(existing) = self.inner.get(&item.id).await?
+                && !self.namespace.matches(&existing.payload)
+            {
+                continue;
+            }
+            stamped.push(self.stamp(item));
+        }
+        self.inner.upsert_batch(stamped).await
+    }
+
+    async fn search(&self, mut query: SearchQuery) -> Result<Vec<SearchResult>> {
+        query.filter = Some(self.scoped(query.filter.take()));
+        let mut results = self.inner.search(query).await?;
+        // 后端未实现过滤时兜底
+        results.retain(|r| self.namespace.matches(&r.payload));
+        Ok(results)
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<VectorItem>> {
+        Ok(self
+            .inner
+            .get(id)
+            .await?
+            .filter(|item| self.namespace.matches(&item.payload)))
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        if self.get(id).await?.is_some() {
+            self.inner.delete(id).await?;
+        }
+        Ok(())
+    }
+
+    async fn delete_by_filter(&self, filter: Filter) -> Result<usize> {
+        self.inner.delete_by_filter(self.scoped(Some(filter))).await
+    }
+
+    /// 统计信息来自底层集合
+    async fn stats(&self) -> Result<StoreStats> {
+        self.inner.stats().await
+    }
+
+    /// 只清空本命名空间的记忆
+    async fn clear(&self) -> Result<()> {
+        self.inner.delete_by_filter(self.namespace.filter()).await?;
+        Ok(())
+    }
+
+    async fn metadata(&self) -> Result<Option<CollectionMetadata>> {
+        self.inner.metadata().await
+    }
+
+    async fn set_metadata(&self, metadata: CollectionMetadata) -> Result<()> {
+        self.inner.set_metadata(metadata).await
+    }
+
+    /// 命名空间过滤交给底层存储执行，只返回本命名空间内的条目
+    async fn scan(
+        &self,
+        filter: Option<&Filter>,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<VectorItem>> {
+        let filter = self.scoped(filter.cloned());
+        self.inner.scan(Some(&filter), after, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openclaw_vector::MemoryStore;
+
+    fn item(id: &str, payload: serde_json::Value) -> VectorItem {
+        VectorItem::new(vec![1.0, 0.0], payload).with_id(id)
+    }
+
+    #[tokio::test]
+    async fn test_namespace_isolation() {
+        let inner: Arc<dyn VectorStore> = Arc::new(MemoryStore::new());
+        let alice =
+            NamespacedVectorStore::new(inner.clone(), MemoryNamespace::new("alice", "assistant"));
+        let bob =
+            NamespacedVectorStore::new(inner.clone(), MemoryNamespace::new("bob", "assistant"));
+
+        alice
+            .upsert(item(
+                "a1",
+                serde_json::json!({"content": "alice's address"}),
+            ))
+            .await
+            .unwrap();
+        bob.upsert(item(
+            "b1",
+            serde_json::json!({"content": "bob's address", "user_id": "alice"}),
+        ))
+        .await
+        .unwrap();
+
+        // 写入时覆盖伪造的 user_id
+        assert_eq!(
+            inner.get("b1").await.unwrap().unwrap().payload["user_id"],
+            "bob"
+        );
+
+        let results = alice
+            .search(SearchQuery::new(vec![1.0, 0.0]))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "a1");
+        assert!(alice.get("b1").await.unwrap().is_none());
+        assert!(
+            bob.upsert(item("a1", serde_json::json!({"content": "overwrite"})))
+                .await
+                .is_err()
+        );
+
+        alice.delete("b1").await.unwrap();
+        alice.clear().await.unwrap();
+        let remaining: Vec<String> = inner
+            .scan(None, None, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(remaining, vec!["b1"]);
+        assert_eq!(bob.scan(None, None, 10).await.unwrap().len(), 1);
+    }
+}
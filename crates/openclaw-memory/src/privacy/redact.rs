@@ -0,0 +1,61 @@
+//! 敏感信息脱敏
+
+use std::sync::Arc;
+
+use openclaw_sandbox::leak_detector::{LeakDetector, create_default_detector};
+
+/// 复用沙箱泄露检测规则，在记忆持久化前替换密钥、证件号、联系方式等内容
+pub struct PiiRedactor {
+    detector: Arc<dyn LeakDetector>,
+}
+
+impl PiiRedactor {
+    pub fn new() -> Self {
+        Self::with_detector(Arc::new(create_default_detector()))
+    }
+
+    pub fn with_detector(detector: Arc<dyn LeakDetector>) -> Self {
+        Self { detector }
+    }
+
+    /// 返回脱敏后的文本和命中的规则名
+    pub fn redact(&self, text: &str) -> (String, Vec<String>) {
+        let (redacted, result) = self.detector.detect_and_redact(text);
+        let mut patterns: Vec<String> = result
+            .detections
+            .into_iter()
+            .map(|d| d.pattern_name)
+            .collect();
+        patterns.sort();
+        patterns.dedup();
+        (redacted, patterns)
+    }
+
+    /// 只返回脱敏后的文本
+    pub fn redact_text(&self, text: &str) -> String {
+        self.redact(text).0
+    }
+}
+
+impl Default for PiiRedactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let redactor = PiiRedactor::new();
+        let (text, patterns) =
+            redactor.redact("mail me at alice@example.com, password: hunter2hunter2");
+
+        assert!(!text.contains("alice@example.com"));
+        assert!(!text.contains("hunter2hunter2"));
+        assert_eq!(patterns, vec!["email", "password"]);
+        assert_eq!(redactor.redact_text("nothing to hide"), "nothing to hide");
+    }
+}
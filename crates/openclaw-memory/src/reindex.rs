@@ -0,0 +1,433 @@
+//! 重建向量索引与后端迁移
+//!
+//! 更换嵌入模型后，已存储的向量与新模型不兼容。`ReindexJob` 在后台
+//! 分批遍历源集合，用新模型重新生成向量写入目标集合；不指定嵌入模型时
+//! 原样复制向量，用于在后端之间迁移数据。进度写入检查点文件，中断后可续跑。
+//! 源集合须支持按 id 遍历（`VectorStore::scan`）。
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use openclaw_core::{OpenClawError, Result};
+use openclaw_vector::{CollectionMetadata, VectorItem, VectorStore};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::embedding::EmbeddingProvider;
+use crate::schema::CONTENT;
+
+/// 默认检查点路径
+pub const DEFAULT_CHECKPOINT_PATH: &str = "data/reindex_checkpoint.json";
+
+/// 重建进度
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReindexProgress {
+    /// 开始时源集合中的条目数
+    pub total: usize,
+    /// 已写入目标集合的条目数
+    pub processed: usize,
+    /// 因缺少文本内容而跳过的条目数
+    pub skipped: usize,
+    pub done: bool,
+}
+
+impl ReindexProgress {
+    pub fn percent(&self) -> f32 {
+        if self.total == 0 {
+            return if self.done { 100.0 } else { 0.0 };
+        }
+        ((self.processed + self.skipped) as f32 / self.total as f32 * 100.0).min(100.0)
+    }
+}
+
+/// 检查点，记录最后处理的条目 id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ReindexCheckpoint {
+    last_id: String,
+    progress: ReindexProgress,
+    /// 目标嵌入模型，模型不同时不复用检查点
+    embedding_model: Option<String>,
+}
+
+impl ReindexCheckpoint {
+    fn load(path: &Path, embedding_model: Option<&str>) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let checkpoint: Self = serde_json::from_str(&content)
+            .inspect_err(|e| tracing::warn!("Ignoring unreadable reindex checkpoint: {}", e))
+            .ok()?;
+        if checkpoint.embedding_model.as_deref() != embedding_model {
+            tracing::warn!("Reindex checkpoint was written for another model, starting over");
+            return None;
+        }
+        Some(checkpoint)
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // 先写临时文件再改名，避免中断时留下半个检查点
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// 重建索引任务
+pub struct ReindexJob {
+    source: Arc<dyn VectorStore>,
+    target: Arc<dyn VectorStore>,
+    embedder: Option<Arc<dyn EmbeddingProvider>>,
+    batch_size: usize,
+    checkpoint: Option<PathBuf>,
+}
+
+impl ReindexJob {
+    pub fn new(source: Arc<dyn VectorStore>, target: Arc<dyn VectorStore>) -> Self {
+        Self {
+            source,
+            target,
+            embedder: None,
+            batch_size: 64,
+            checkpoint: None,
+        }
+    }
+
+    /// 用新的嵌入模型重新生成向量，不设置时原样复制
+    pub fn with_embedder(mut self, embedder: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn with_checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    /// 在后台运行，通过返回的句柄观察进度
+    pub fn spawn(self) -> ReindexHandle {
+        let (tx, rx) = watch::channel(ReindexProgress::default());
+        let task = tokio::spawn(async move { self.run(tx).await });
+        ReindexHandle { progress: rx, task }
+    }
+
+    async fn run(self, tx: watch::Sender<ReindexProgress>) -> Result<ReindexProgress> {
+        let model = self.embedder.as_ref().map(|e| e.model().to_string());
+        self.bind_target().await?;
+
+        let resumed = self
+            .checkpoint
+            .as_deref()
+            .and_then(|path| ReindexCheckpoint::load(path, model.as_deref()));
+        let (mut last_id, mut progress) = match resumed {
+            Some(checkpoint) => {
+                tracing::info!("Resuming reindex after id {}", checkpoint.last_id);
+                (Some(checkpoint.last_id), checkpoint.progress)
+            }
+            None => (None, ReindexProgress::default()),
+        };
+        progress.total = self.source.stats().await?.total_vectors;
+        tx.send_replace(progress.clone());
+
+        loop {
+            let batch = self
+                .source
+                .scan(None, last_id.as_deref(), self.batch_size)
+                .await
+                .map_err(unsupported_source)?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            let next_id = last.id.clone();
+            self.process(batch, &mut progress).await?;
+            last_id = Some(next_id);
+
+            if let (Some(path), Some(last_id)) = (&self.checkpoint, &last_id) {
+                ReindexCheckpoint {
+                    last_id: last_id.clone(),
+                    progress: progress.clone(),
+                    embedding_model: model.clone(),
+                }
+                .save(path)?;
+            }
+            tx.send_replace(progress.clone());
+        }
+
+        // 迁移期间源集合可能有新写入，补齐目标中缺失的条目
+        self.catch_up(&mut progress).await?;
+
+        progress.total = progress.total.max(progress.processed + progress.skipped);
+        progress.done = true;
+        if let Some(path) = &self.checkpoint {
+            let _ = std::fs::remove_file(path);
+        }
+        tx.send_replace(progress.clone());
+        tracing::info!(
+            "Reindex finished: {} items written, {} skipped",
+            progress.processed,
+            progress.skipped
+        );
+        Ok(progress)
+    }
+
+    /// 目标集合绑定新模型；已绑定其他模型时拒绝写入
+    async fn bind_target(&self) -> Result<()> {
+        let metadata = match &self.embedder {
+            Some(embedder) => Some(CollectionMetadata::new(
+                embedder.model(),
+                embedder.dimensions(),
+            )),
+            None => self.source.metadata().await?,
+        };
+        let Some(metadata) = metadata else {
+            return Ok(());
+        };
+        if let Some(existing) = self.target.metadata().await?
+            && !existing.matches(&metadata.embedding_model, metadata.dimensions)
+        {
+            return Err(OpenClawError::VectorStore(format!(
+                "Target collection is already bound to {} ({} dimensions)",
+                existing.embedding_model, existing.dimensions
+            )));
+        }
+        self.target.set_metadata(metadata).await
+    }
+
+    async fn process(&self, batch: Vec<VectorItem>, progress: &mut ReindexProgress) -> Result<()> {
+        let Some(embedder) = &self.embedder else {
+            progress.processed += self.target.upsert_batch(batch).await?;
+            return Ok(());
+        };
+
+        let batch_len = batch.len();
+        let (items, texts): (Vec<VectorItem>, Vec<String>) = batch
+            .into_iter()
+            .filter_map(|item| {
+                let text = item.payload.get(CONTENT)?.as_str()?.trim().to_string();
+                (!text.is_empty()).then_some((item, text))
+            })
+            .unzip();
+        progress.skipped += batch_len - items.len();
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let vectors = embedder.embed_batch(&texts).await?;
+        if vectors.len() != items.len() {
+            return Err(OpenClawError::AIProvider(format!(
+                "Embedding provider returned {} vectors for {} texts",
+                vectors.len(),
+                items.len()
+            )));
+        }
+        let items = items
+            .into_iter()
+            .zip(vectors)
+            .map(|(item, vector)| VectorItem { vector, ..item })
+            .collect();
+        progress.processed += self.target.upsert_batch(items).await?;
+        Ok(())
+    }
+
+    async fn catch_up(&self, progress: &mut ReindexProgress) -> Result<()> {
+        let source_total = self.source.stats().await?.total_vectors;
+        if source_total <= progress.processed + progress.skipped {
+            return Ok(());
+        }
+
+        let mut last_id: Option<String> = None;
+        loop {
+            let batch = self
+                .source
+                .scan(None, last_id.as_deref(), self.batch_size)
+                .await?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            last_id = Some(last.id.clone());
+
+            let mut missing = Vec::new();
+            for item in batch {
+                if self.target.get(&item.id).await?.is_none() {
+                    missing.push(item);
+                }
+            }
+            if !missing.is_empty() {
+                progress.total += missing.len();
+                self.process(missing, progress).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 后台重建任务的句柄
+pub struct ReindexHandle {
+    progress: watch::Receiver<ReindexProgress>,
+    task: JoinHandle<Result<ReindexProgress>>,
+}
+
+impl ReindexHandle {
+    /// 当前进度快照
+    pub fn progress(&self) -> ReindexProgress {
+        self.progress.borrow().clone()
+    }
+
+    /// 订阅进度变化
+    pub fn subscribe(&self) -> watch::Receiver<ReindexProgress> {
+        self.progress.clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// 等待任务结束
+    pub async fn wait(self) -> Result<ReindexProgress> {
+        self.task
+            .await
+            .map_err(|e| OpenClawError::Memory(format!("Reindex task failed: {}", e)))?
+    }
+}
+
+/// 源集合不支持遍历时给出可操作的提示
+fn unsupported_source(e: OpenClawError) -> OpenClawError {
+    OpenClawError::VectorStore(format!(
+        "Cannot read the source collection for reindexing ({}). Reindexing currently \
+         requires a sqlite or memory source backend.",
+        e
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::{Embedding, Embeddings};
+    use async_trait::async_trait;
+    use openclaw_vector::MemoryStore;
+    use serde_json::json;
+
+    /// 以文本长度和首字节生成二维向量
+    struct LengthEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for LengthEmbedding {
+        fn name(&self) -> &str {
+            "test"
+        }
+        fn model(&self) -> &str {
+            "length-v2"
+        }
+        fn dimensions(&self) -> usize {
+            2
+        }
+        async fn embed(&self, text: &str) -> Result<Embedding> {
+            Ok(vec![
+                text.len() as f32,
+                text.bytes().next().unwrap_or(0) as f32,
+            ])
+        }
+        async fn embed_batch(&self, texts: &[String]) -> Result<Embeddings> {
+            let mut out = Vec::new();
+            for text in texts {
+                out.push(self.embed(text).await?);
+            }
+            Ok(out)
+        }
+    }
+
+    async fn source_store(count: usize) -> Arc<dyn VectorStore> {
+        let store = MemoryStore::new();
+        store
+            .set_metadata(CollectionMetadata::new("old-model", 3))
+            .await
+            .unwrap();
+        for i in 0..count {
+            let item = VectorItem::new(
+                vec![1.0, 0.0, 0.0],
+                json!({ CONTENT: format!("memory {i}") }),
+            )
+            .with_id(format!("m{i:02}"));
+            store.upsert(item).await.unwrap();
+        }
+        store
+            .upsert(VectorItem::new(vec![0.0, 1.0, 0.0], json!({})).with_id("empty"))
+            .await
+            .unwrap();
+        Arc::new(store)
+    }
+
+    #[tokio::test]
+    async fn test_reindex_with_new_model() {
+        let source = source_store(5).await;
+        let target: Arc<dyn VectorStore> = Arc::new(MemoryStore::new());
+
+        let handle = ReindexJob::new(source, target.clone())
+            .with_embedder(Arc::new(LengthEmbedding))
+            .with_batch_size(2)
+            .spawn();
+        let progress = handle.wait().await.unwrap();
+
+        assert!(progress.done);
+        assert_eq!(progress.processed, 5);
+        assert_eq!(progress.skipped, 1);
+        let metadata = target.metadata().await.unwrap().unwrap();
+        assert!(metadata.matches("length-v2", 2));
+        let item = target.get("m03").await.unwrap().unwrap();
+        assert_eq!(item.vector, vec![8.0, b'm' as f32]);
+        assert_eq!(item.payload[CONTENT], "memory 3");
+    }
+
+    #[tokio::test]
+    async fn test_copy_between_backends_resumes_from_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let checkpoint = dir.path().join("reindex.json");
+        let source = source_store(4).await;
+        let target: Arc<dyn VectorStore> = Arc::new(MemoryStore::new());
+
+        // 模拟上次中断在 m01 之后，前三条已写入
+        for item in source.scan(None, None, 3).await.unwrap() {
+            target.upsert(item).await.unwrap();
+        }
+        // 中断期间删除一条已处理的记忆，按 id 续跑不会跳过后续条目
+        source.delete("m00").await.unwrap();
+        ReindexCheckpoint {
+            last_id: "m01".to_string(),
+            progress: ReindexProgress {
+                total: 5,
+                processed: 3,
+                skipped: 0,
+                done: false,
+            },
+            embedding_model: None,
+        }
+        .save(&checkpoint)
+        .unwrap();
+
+        let progress = ReindexJob::new(source.clone(), target.clone())
+            .with_checkpoint(&checkpoint)
+            .spawn()
+            .wait()
+            .await
+            .unwrap();
+
+        assert_eq!(progress.processed, 5);
+        assert_eq!(target.stats().await.unwrap().total_vectors, 5);
+        assert!(
+            target
+                .metadata()
+                .await
+                .unwrap()
+                .unwrap()
+                .matches("old-model", 3)
+        );
+        assert!(!checkpoint.exists());
+    }
+}
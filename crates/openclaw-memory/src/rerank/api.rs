@@ -0,0 +1,129 @@
+//! Cohere / Jina 兼容的远程重排接口
+
+use async_trait::async_trait;
+use openclaw_core::{OpenClawError, Result};
+use serde::Deserialize;
+
+use super::Reranker;
+
+#[derive(Deserialize)]
+struct RerankResponse {
+    results: Vec<RerankHit>,
+}
+
+#[derive(Deserialize)]
+struct RerankHit {
+    index: usize,
+    relevance_score: f32,
+}
+
+/// 调用 `POST {base_url}/rerank` 的重排器
+pub struct ApiReranker {
+    client: reqwest::Client,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl ApiReranker {
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        let base_url = base_url.into();
+        let base_url = base_url.trim_end_matches('/');
+        let endpoint = if base_url.ends_with("/rerank") {
+            base_url.to_string()
+        } else {
+            format!("{}/rerank", base_url)
+        };
+        Self {
+            client: reqwest::Client::new(),
+            endpoint,
+            model: model.into(),
+            api_key: None,
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+}
+
+/// 按 index 还原分数，未返回的文档记 0 分
+fn scores_from_response(response: RerankResponse, count: usize) -> Vec<f32> {
+    let mut scores = vec![0.0; count];
+    for hit in response.results {
+        if let Some(score) = scores.get_mut(hit.index) {
+            *score = hit.relevance_score;
+        }
+    }
+    scores
+}
+
+#[async_trait]
+impl Reranker for ApiReranker {
+    fn name(&self) -> &str {
+        "api"
+    }
+
+    async fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut body = serde_json::json!({
+            "query": query,
+            "documents": documents,
+            "top_n": documents.len(),
+            "return_documents": false,
+        });
+        if !self.model.is_empty() {
+            body["model"] = serde_json::json!(self.model);
+        }
+
+        let mut request = self.client.post(&self.endpoint).json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| OpenClawError::AIProvider(e.to_string()))?;
+        if !response.status().is_success() {
+            let error = response.text().await.unwrap_or_default();
+            return Err(OpenClawError::AIProvider(error));
+        }
+
+        let parsed: RerankResponse = response
+            .json()
+            .await
+            .map_err(|e| OpenClawError::AIProvider(e.to_string()))?;
+        Ok(scores_from_response(parsed, documents.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_and_response_parsing() {
+        assert_eq!(
+            ApiReranker::new("https://api.jina.ai/v1/", "jina-reranker-v2").endpoint,
+            "https://api.jina.ai/v1/rerank"
+        );
+        assert_eq!(
+            ApiReranker::new("http://localhost:8080/rerank", "").endpoint,
+            "http://localhost:8080/rerank"
+        );
+
+        let response: RerankResponse = serde_json::from_str(
+            r#"{"results": [
+                {"index": 2, "relevance_score": 0.9},
+                {"index": 0, "relevance_score": 0.3},
+                {"index": 7, "relevance_score": 0.5}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(scores_from_response(response, 3), vec![0.3, 0.0, 0.9]);
+    }
+}
@@ -0,0 +1,125 @@
+//! 本地 cross-encoder 重排器
+//!
+//! 将 (查询, 文档) 成对输入 BERT 类 cross-encoder，取分类头输出经 sigmoid 作为相关性。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use candle_core::{IndexOp, Module, Tensor};
+use candle_nn::{Linear, linear};
+use candle_transformers::models::bert::{BertModel, Config as BertConfig};
+use openclaw_core::Result;
+use tokenizers::Tokenizer;
+
+use super::Reranker;
+use crate::local_embedding::{
+    LocalEmbeddingConfig, load_tokenizer, load_weights, model_error, resolve_files,
+};
+
+/// 默认 cross-encoder 模型
+pub const DEFAULT_CROSS_ENCODER: &str = "cross-encoder/ms-marco-MiniLM-L-6-v2";
+
+struct LoadedCrossEncoder {
+    bert: BertModel,
+    pooler: Linear,
+    classifier: Linear,
+    tokenizer: Tokenizer,
+}
+
+impl LoadedCrossEncoder {
+    fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+        let pairs: Vec<(String, String)> = documents
+            .iter()
+            .map(|doc| (query.to_string(), doc.clone()))
+            .collect();
+        let encodings = self
+            .tokenizer
+            .encode_batch(pairs, true)
+            .map_err(model_error)?;
+
+        let run = || -> candle_core::Result<Vec<f32>> {
+            let device = &self.bert.device;
+            let stack = |field: fn(&tokenizers::Encoding) -> &[u32]| {
+                let rows = encodings
+                    .iter()
+                    .map(|e| Tensor::new(field(e), device))
+                    .collect::<candle_core::Result<Vec<_>>>()?;
+                Tensor::stack(&rows, 0)
+            };
+            let input_ids = stack(|e| e.get_ids())?;
+            let type_ids = stack(|e| e.get_type_ids())?;
+            let mask = stack(|e| e.get_attention_mask())?;
+
+            let hidden = self.bert.forward(&input_ids, &type_ids, Some(&mask))?;
+            let pooled = self.pooler.forward(&hidden.i((.., 0))?)?.tanh()?;
+            let logits = self.classifier.forward(&pooled)?.i((.., 0))?;
+            candle_nn::ops::sigmoid(&logits)?
+                .to_dtype(candle_core::DType::F32)?
+                .to_vec1()
+        };
+        run().map_err(model_error)
+    }
+}
+
+/// 进程内 cross-encoder 重排器
+pub struct CrossEncoderReranker {
+    inner: Arc<LoadedCrossEncoder>,
+    batch_size: usize,
+}
+
+impl CrossEncoderReranker {
+    /// 加载模型，必要时下载到缓存目录
+    pub async fn load(config: LocalEmbeddingConfig) -> Result<Self> {
+        let files = resolve_files(&config).await?;
+        let batch_size = config.batch_size.max(1);
+
+        let inner = tokio::task::spawn_blocking(move || -> Result<LoadedCrossEncoder> {
+            let bert_config: BertConfig =
+                serde_json::from_str(&std::fs::read_to_string(&files.config)?)?;
+            let tokenizer = load_tokenizer(
+                &files.tokenizer,
+                config.max_length.min(bert_config.max_position_embeddings),
+            )?;
+            let vb = load_weights(&files, config.precision)?;
+            let hidden = bert_config.hidden_size;
+            let bert = BertModel::load(vb.pp("bert"), &bert_config).map_err(model_error)?;
+            let pooler = linear(hidden, hidden, vb.pp("bert.pooler.dense")).map_err(model_error)?;
+            let classifier = linear(hidden, 1, vb.pp("classifier")).map_err(model_error)?;
+            tracing::info!("本地 cross-encoder 已加载: {}", config.model);
+            Ok(LoadedCrossEncoder {
+                bert,
+                pooler,
+                classifier,
+                tokenizer,
+            })
+        })
+        .await
+        .map_err(model_error)??;
+
+        Ok(Self {
+            inner: Arc::new(inner),
+            batch_size,
+        })
+    }
+}
+
+#[async_trait]
+impl Reranker for CrossEncoderReranker {
+    fn name(&self) -> &str {
+        "cross-encoder"
+    }
+
+    async fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+        let mut scores = Vec::with_capacity(documents.len());
+        for batch in documents.chunks(self.batch_size) {
+            let inner = self.inner.clone();
+            let query = query.to_string();
+            let batch = batch.to_vec();
+            let batch_scores = tokio::task::spawn_blocking(move || inner.score(&query, &batch))
+                .await
+                .map_err(model_error)??;
+            scores.extend(batch_scores);
+        }
+        Ok(scores)
+    }
+}
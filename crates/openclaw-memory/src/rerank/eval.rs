@@ -0,0 +1,185 @@
+//! 重排离线评估
+//!
+//! 夹具中每个查询给出一阶段检索的候选顺序和分级相关性标注，
+//! 分别计算原顺序与重排后的 nDCG@k，用于比较不同重排器。
+
+use std::path::Path;
+use std::time::Instant;
+
+use openclaw_core::Result;
+use serde::{Deserialize, Serialize};
+
+use super::Reranker;
+
+/// 评估夹具
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalFixture {
+    pub queries: Vec<EvalQuery>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalQuery {
+    pub query: String,
+    /// 按一阶段检索顺序排列的候选
+    pub candidates: Vec<EvalCandidate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCandidate {
+    pub id: String,
+    pub text: String,
+    /// 分级相关性，0 表示不相关
+    #[serde(default)]
+    pub relevance: f32,
+}
+
+impl EvalFixture {
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// 评估结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalReport {
+    pub reranker: String,
+    pub k: usize,
+    /// 参与计算的查询数（至少有一个相关候选）
+    pub queries: usize,
+    pub baseline_ndcg: f32,
+    pub reranked_ndcg: f32,
+    pub mean_latency_ms: f64,
+    pub max_latency_ms: f64,
+    /// 重排失败、按原顺序计分的查询数
+    pub failures: usize,
+}
+
+fn dcg(relevances: &[f32], k: usize) -> f32 {
+    relevances
+        .iter()
+        .take(k)
+        .enumerate()
+        .map(|(i, rel)| (2f32.powf(*rel) - 1.0) / (i as f32 + 2.0).log2())
+        .sum()
+}
+
+/// 按排名顺序给出的相关性计算 nDCG@k，没有相关项时返回 None
+pub fn ndcg_at_k(relevances: &[f32], k: usize) -> Option<f32> {
+    let mut ideal = relevances.to_vec();
+    ideal.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    let ideal_dcg = dcg(&ideal, k);
+    (ideal_dcg > 0.0).then(|| dcg(relevances, k) / ideal_dcg)
+}
+
+/// 在夹具上评估重排器
+pub async fn evaluate(reranker: &dyn Reranker, fixture: &EvalFixture, k: usize) -> EvalReport {
+    let mut baseline_total = 0.0;
+    let mut reranked_total = 0.0;
+    let mut latencies = Vec::new();
+    let mut queries = 0;
+    let mut failures = 0;
+
+    for query in &fixture.queries {
+        let baseline: Vec<f32> = query.candidates.iter().map(|c| c.relevance).collect();
+        let Some(baseline_ndcg) = ndcg_at_k(&baseline, k) else {
+            continue;
+        };
+
+        let documents: Vec<String> = query.candidates.iter().map(|c| c.text.clone()).collect();
+        let started = Instant::now();
+        let scored = reranker.score(&query.query, &documents).await;
+        latencies.push(started.elapsed().as_secs_f64() * 1000.0);
+
+        let reranked_ndcg = match scored {
+            Ok(scores) if scores.len() == baseline.len() => {
+                let mut order: Vec<(f32, f32)> = scores.into_iter().zip(baseline).collect();
+                order.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+                let relevances: Vec<f32> = order.into_iter().map(|(_, rel)| rel).collect();
+                ndcg_at_k(&relevances, k).unwrap_or_default()
+            }
+            Ok(_) | Err(_) => {
+                failures += 1;
+                baseline_ndcg
+            }
+        };
+
+        baseline_total += baseline_ndcg;
+        reranked_total += reranked_ndcg;
+        queries += 1;
+    }
+
+    let mean = |total: f32| {
+        if queries > 0 {
+            total / queries as f32
+        } else {
+            0.0
+        }
+    };
+    EvalReport {
+        reranker: reranker.name().to_string(),
+        k,
+        queries,
+        baseline_ndcg: mean(baseline_total),
+        reranked_ndcg: mean(reranked_total),
+        mean_latency_ms: if latencies.is_empty() {
+            0.0
+        } else {
+            latencies.iter().sum::<f64>() / latencies.len() as f64
+        },
+        max_latency_ms: latencies.iter().copied().fold(0.0, f64::max),
+        failures,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::collections::HashSet;
+
+    const FIXTURE: &str = include_str!("../../fixtures/rerank_eval.json");
+
+    /// 按查询词覆盖率打分的词法重排器
+    struct OverlapReranker;
+
+    #[async_trait]
+    impl Reranker for OverlapReranker {
+        fn name(&self) -> &str {
+            "overlap"
+        }
+
+        async fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+            let terms: HashSet<String> =
+                query.split_whitespace().map(|t| t.to_lowercase()).collect();
+            Ok(documents
+                .iter()
+                .map(|doc| {
+                    let doc = doc.to_lowercase();
+                    terms.iter().filter(|t| doc.contains(t.as_str())).count() as f32
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_ndcg() {
+        assert_eq!(ndcg_at_k(&[3.0, 2.0, 0.0], 3), Some(1.0));
+        assert_eq!(ndcg_at_k(&[0.0, 0.0], 2), None);
+        let swapped = ndcg_at_k(&[0.0, 1.0], 2).unwrap();
+        assert!((swapped - 1.0 / 3f32.log2()).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_fixture() {
+        let fixture = EvalFixture::from_json(FIXTURE).unwrap();
+        let report = evaluate(&OverlapReranker, &fixture, 3).await;
+
+        assert_eq!(report.queries, fixture.queries.len());
+        assert_eq!(report.failures, 0);
+        assert!(report.reranked_ndcg > report.baseline_ndcg);
+    }
+}
@@ -0,0 +1,98 @@
+//! 大模型排序重排器
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use openclaw_ai::{AIProvider, ChatRequest};
+use openclaw_core::{Message, Result};
+
+use super::Reranker;
+
+/// 每个候选送入提示词的最大字符数
+const SNIPPET_CHARS: usize = 200;
+
+/// 让大模型按相关性给出候选顺序，再折算为分数
+pub struct LlmReranker {
+    llm: Arc<dyn AIProvider>,
+    model: String,
+}
+
+impl LlmReranker {
+    pub fn new(llm: Arc<dyn AIProvider>) -> Self {
+        Self {
+            llm,
+            model: "default".to_string(),
+        }
+    }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+}
+
+/// 解析回复中的编号数组（从 1 开始），排名越靠前分数越高，未提及的记 0 分
+fn scores_from_ranking(text: &str, count: usize) -> Vec<f32> {
+    let mut scores = vec![0.0; count];
+    let ranking = text
+        .find('[')
+        .zip(text.rfind(']'))
+        .and_then(|(start, end)| serde_json::from_str::<Vec<usize>>(&text[start..=end]).ok())
+        .unwrap_or_default();
+
+    let mut rank = 0;
+    for number in ranking {
+        if let Some(score) = number.checked_sub(1).and_then(|i| scores.get_mut(i))
+            && *score == 0.0
+        {
+            *score = (count - rank) as f32 / count as f32;
+            rank += 1;
+        }
+    }
+    scores
+}
+
+#[async_trait]
+impl Reranker for LlmReranker {
+    fn name(&self) -> &str {
+        "llm"
+    }
+
+    async fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let prompt = Message::system(format!(
+            "Given the query: \"{}\"\n\nRank the following results by relevance (1 = most relevant):\n\n{}\n\nProvide rankings as a JSON array of result numbers in order of relevance.",
+            query,
+            documents
+                .iter()
+                .enumerate()
+                .map(|(i, d)| format!(
+                    "{}. {}",
+                    i + 1,
+                    d.chars().take(SNIPPET_CHARS).collect::<String>()
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+
+        let request = ChatRequest::new(self.model.clone(), vec![prompt]);
+        let response = self.llm.chat(request).await?;
+        let text = response.message.text_content().unwrap_or("[]");
+        Ok(scores_from_ranking(text, documents.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scores_from_ranking() {
+        let scores = scores_from_ranking("Ranking: [3, 1, 3, 9]", 4);
+        assert_eq!(scores, vec![0.75, 0.0, 1.0, 0.0]);
+        assert_eq!(scores_from_ranking("no idea", 2), vec![0.0, 0.0]);
+    }
+}
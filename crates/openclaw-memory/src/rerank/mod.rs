@@ -0,0 +1,369 @@
+//! 检索结果重排
+//!
+//! 混合检索融合 BM25 与向量分数后，由 `Reranker` 对候选重新打分。
+//! 记忆召回与 Agentic RAG 共用同一套接口，可选实现：
+//! - `ApiReranker`：Cohere / Jina 兼容的远程 rerank 接口
+//! - `LlmReranker`：让大模型给出排序，成本最高
+//! - `CrossEncoderReranker`：本地 cross-encoder 模型（需 `local-embedding` 特性）
+
+mod api;
+#[cfg(feature = "local-embedding")]
+mod cross_encoder;
+pub mod eval;
+mod llm;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use openclaw_ai::AIProvider;
+use openclaw_core::{OpenClawError, Result};
+use openclaw_vector::SearchResult;
+use serde::{Deserialize, Serialize};
+
+use crate::unified_search::result::UnifiedSearchResult;
+
+pub use api::ApiReranker;
+#[cfg(feature = "local-embedding")]
+pub use cross_encoder::{CrossEncoderReranker, DEFAULT_CROSS_ENCODER};
+pub use llm::LlmReranker;
+
+/// 重排器
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    fn name(&self) -> &str;
+    /// 为每个文档打相关性分数，返回值与 `documents` 一一对应
+    async fn score(&self, query: &str, documents: &[String]) -> Result<Vec<f32>>;
+}
+
+/// 可参与重排的检索结果
+pub trait Rerankable {
+    fn rerank_text(&self) -> &str;
+    fn set_rerank_score(&mut self, score: f32);
+}
+
+impl Rerankable for SearchResult {
+    fn rerank_text(&self) -> &str {
+        self.payload
+            .get(crate::schema::CONTENT)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+    }
+
+    fn set_rerank_score(&mut self, score: f32) {
+        self.score = score;
+    }
+}
+
+impl Rerankable for UnifiedSearchResult {
+    fn rerank_text(&self) -> &str {
+        &self.content
+    }
+
+    fn set_rerank_score(&mut self, score: f32) {
+        self.score = score;
+    }
+}
+
+/// 重排参数
+#[derive(Debug, Clone)]
+pub struct RerankConfig {
+    /// 送入重排器的候选数
+    pub top_k_in: usize,
+    /// 重排后保留的结果数
+    pub top_k_out: usize,
+    /// 延迟预算，超时则保留融合顺序
+    pub budget: Option<Duration>,
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            top_k_in: 50,
+            top_k_out: 10,
+            budget: Some(Duration::from_millis(500)),
+        }
+    }
+}
+
+/// 重排阶段：截取候选、调用重排器并在超时或失败时降级
+pub struct RerankStage {
+    reranker: Arc<dyn Reranker>,
+    config: RerankConfig,
+}
+
+impl RerankStage {
+    pub fn new(reranker: Arc<dyn Reranker>) -> Self {
+        Self {
+            reranker,
+            config: RerankConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: RerankConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_top_k_in(mut self, top_k_in: usize) -> Self {
+        self.config.top_k_in = top_k_in.max(1);
+        self
+    }
+
+    pub fn with_top_k_out(mut self, top_k_out: usize) -> Self {
+        self.config.top_k_out = top_k_out.max(1);
+        self
+    }
+
+    pub fn with_budget(mut self, budget: Option<Duration>) -> Self {
+        self.config.budget = budget;
+        self
+    }
+
+    pub fn config(&self) -> &RerankConfig {
+        &self.config
+    }
+
+    pub fn reranker(&self) -> &Arc<dyn Reranker> {
+        &self.reranker
+    }
+
+    /// 对已按融合分数排好序的候选重排，返回至多 `top_k_out` 条
+    pub async fn rerank<T: Rerankable>(&self, query: &str, mut candidates: Vec<T>) -> Vec<T> {
+        let top_k_out = self.config.top_k_out;
+        if candidates.len() <= 1 {
+            return candidates;
+        }
+
+        let head_len = candidates.len().min(self.config.top_k_in);
+        let tail = candidates.split_off(head_len);
+        let documents: Vec<String> = candidates
+            .iter()
+            .map(|c| c.rerank_text().to_string())
+            .collect();
+
+        let started = Instant::now();
+        let scored = match self.config.budget {
+            Some(budget) => tokio::time::timeout(budget, self.reranker.score(query, &documents))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(OpenClawError::AIProvider(format!(
+                        "rerank exceeded {}ms budget",
+                        budget.as_millis()
+                    )))
+                }),
+            None => self.reranker.score(query, &documents).await,
+        };
+
+        let mut ranked = match scored {
+            Ok(scores) if scores.len() == candidates.len() => {
+                let mut pairs: Vec<(f32, T)> = scores.into_iter().zip(candidates).collect();
+                pairs.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+                pairs
+                    .into_iter()
+                    .map(|(score, mut candidate)| {
+                        candidate.set_rerank_score(score);
+                        candidate
+                    })
+                    .collect()
+            }
+            Ok(scores) => {
+                tracing::warn!(
+                    "Reranker {} returned {} scores for {} candidates, keeping fused order",
+                    self.reranker.name(),
+                    scores.len(),
+                    candidates.len()
+                );
+                candidates
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Reranker {} failed, keeping fused order: {}",
+                    self.reranker.name(),
+                    e
+                );
+                candidates
+            }
+        };
+        tracing::debug!(
+            "Reranked {} candidates with {} in {:?}",
+            head_len,
+            self.reranker.name(),
+            started.elapsed()
+        );
+
+        ranked.extend(tail);
+        ranked.truncate(top_k_out);
+        ranked
+    }
+}
+
+/// 重排器类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RerankerKind {
+    /// 本地 cross-encoder 模型
+    Local,
+    /// Cohere / Jina 兼容接口
+    Api,
+    /// 大模型排序
+    Llm,
+}
+
+/// 可序列化的重排配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankerSettings {
+    pub kind: RerankerKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default = "default_top_k_in")]
+    pub top_k_in: usize,
+    #[serde(default = "default_top_k_out")]
+    pub top_k_out: usize,
+    /// 延迟预算（毫秒），0 表示不限
+    #[serde(default = "default_budget_ms")]
+    pub budget_ms: u64,
+}
+
+fn default_top_k_in() -> usize {
+    RerankConfig::default().top_k_in
+}
+
+fn default_top_k_out() -> usize {
+    RerankConfig::default().top_k_out
+}
+
+fn default_budget_ms() -> u64 {
+    500
+}
+
+impl RerankerSettings {
+    pub fn config(&self) -> RerankConfig {
+        RerankConfig {
+            top_k_in: self.top_k_in.max(1),
+            top_k_out: self.top_k_out.max(1),
+            budget: (self.budget_ms > 0).then(|| Duration::from_millis(self.budget_ms)),
+        }
+    }
+
+    /// 构建重排阶段；LLM 重排需要传入对话模型
+    pub async fn build(&self, llm: Option<Arc<dyn AIProvider>>) -> Result<RerankStage> {
+        let reranker: Arc<dyn Reranker> = match self.kind {
+            RerankerKind::Api => {
+                let base_url = self.base_url.clone().ok_or_else(|| {
+                    OpenClawError::Config("API reranker requires base_url".to_string())
+                })?;
+                let model = self.model.clone().unwrap_or_default();
+                Arc::new(ApiReranker::new(base_url, model).with_api_key(self.api_key.clone()))
+            }
+            RerankerKind::Llm => {
+                let llm = llm.ok_or_else(|| {
+                    OpenClawError::Config("LLM reranker requires an AI provider".to_string())
+                })?;
+                let mut reranker = LlmReranker::new(llm);
+                if let Some(model) = &self.model {
+                    reranker = reranker.with_model(model.clone());
+                }
+                Arc::new(reranker)
+            }
+            RerankerKind::Local => build_local(self.model.as_deref()).await?,
+        };
+        Ok(RerankStage::new(reranker).with_config(self.config()))
+    }
+}
+
+#[cfg(feature = "local-embedding")]
+async fn build_local(model: Option<&str>) -> Result<Arc<dyn Reranker>> {
+    let config =
+        crate::local_embedding::LocalEmbeddingConfig::new(model.unwrap_or(DEFAULT_CROSS_ENCODER));
+    Ok(Arc::new(CrossEncoderReranker::load(config).await?))
+}
+
+#[cfg(not(feature = "local-embedding"))]
+async fn build_local(_model: Option<&str>) -> Result<Arc<dyn Reranker>> {
+    Err(OpenClawError::Config(
+        "Local reranker not enabled. Enable 'local-embedding' feature for openclaw-memory"
+            .to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedReranker {
+        scores: Vec<f32>,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Reranker for FixedReranker {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn score(&self, _query: &str, documents: &[String]) -> Result<Vec<f32>> {
+            tokio::time::sleep(self.delay).await;
+            Ok(self.scores.iter().copied().take(documents.len()).collect())
+        }
+    }
+
+    fn candidates(n: usize) -> Vec<UnifiedSearchResult> {
+        (0..n)
+            .map(|i| {
+                UnifiedSearchResult::new(
+                    format!("d{i}"),
+                    format!("doc {i}"),
+                    1.0 - i as f32 * 0.1,
+                    crate::unified_search::result::SearchSource::Fusion,
+                )
+            })
+            .collect()
+    }
+
+    fn ids(results: &[UnifiedSearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_rerank_top_k_in_and_out() {
+        let stage = RerankStage::new(Arc::new(FixedReranker {
+            scores: vec![0.1, 0.9, 0.5],
+            delay: Duration::ZERO,
+        }))
+        .with_top_k_in(3)
+        .with_top_k_out(4);
+
+        let ranked = stage.rerank("q", candidates(5)).await;
+        // 前三条按重排分数排序，其余保持融合顺序追加
+        assert_eq!(ids(&ranked), vec!["d1", "d2", "d0", "d3"]);
+        assert_eq!(ranked[0].score, 0.9);
+    }
+
+    #[tokio::test]
+    async fn test_rerank_falls_back_on_budget() {
+        let stage = RerankStage::new(Arc::new(FixedReranker {
+            scores: vec![0.1, 0.9, 0.5],
+            delay: Duration::from_millis(200),
+        }))
+        .with_top_k_out(2)
+        .with_budget(Some(Duration::from_millis(10)));
+
+        let ranked = stage.rerank("q", candidates(3)).await;
+        assert_eq!(ids(&ranked), vec!["d0", "d1"]);
+    }
+
+    #[test]
+    fn test_settings_defaults() {
+        let settings: RerankerSettings =
+            serde_json::from_str(r#"{"kind": "api", "base_url": "http://localhost:8080"}"#)
+                .unwrap();
+        let config = settings.config();
+        assert_eq!(config.top_k_in, 50);
+        assert_eq!(config.budget, Some(Duration::from_millis(500)));
+    }
+}
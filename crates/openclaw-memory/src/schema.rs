@@ -13,3 +13,5 @@ pub const SOURCE: &str = "source";
 pub const IMPORTANCE: &str = "importance";
 pub const TAGS: &str = "tags";
 pub const METADATA: &str = "metadata";
+pub const USER_ID: &str = "user_id";
+pub const PERSONA_ID: &str = "persona_id";
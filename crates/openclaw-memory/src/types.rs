@@ -2,9 +2,13 @@
 
 use chrono::{DateTime, Utc};
 use openclaw_core::Message;
+use openclaw_vector::Filter;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use uuid::Uuid;
 
+use crate::schema;
+
 /// 记忆层级
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "snake_case")]
@@ -33,6 +37,35 @@ impl MemoryNamespace {
             persona_id: persona_id.into(),
         }
     }
+
+    /// 存储查询的强制过滤条件，persona 为空时只按用户隔离
+    pub fn filter(&self) -> Filter {
+        let filter = Filter::eq(schema::USER_ID, serde_json::json!(self.user_id));
+        if self.persona_id.is_empty() {
+            filter
+        } else {
+            filter.and(Filter::eq(
+                schema::PERSONA_ID,
+                serde_json::json!(self.persona_id),
+            ))
+        }
+    }
+
+    /// payload 是否属于该命名空间
+    pub fn matches(&self, payload: &serde_json::Value) -> bool {
+        self.filter().matches(payload)
+    }
+
+    /// 在 payload 中写入命名空间字段
+    pub fn stamp(&self, payload: &mut serde_json::Value) {
+        if !payload.is_object() {
+            *payload = serde_json::json!({});
+        }
+        payload[schema::USER_ID] = serde_json::json!(self.user_id);
+        if !self.persona_id.is_empty() {
+            payload[schema::PERSONA_ID] = serde_json::json!(self.persona_id);
+        }
+    }
 }
 
 /// 记忆项
@@ -152,6 +185,41 @@ pub struct MemoryConfig {
     /// 嵌入向量维度 (可选，未设置时从 embedding provider 获取或使用默认值)
     #[serde(default)]
     pub embedding_dimensions: Option<usize>,
+    /// 隐私配置
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+}
+
+/// 隐私配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    /// 持久化前脱敏敏感信息
+    #[serde(default = "default_true")]
+    pub redact_pii: bool,
+    /// 遗忘操作回执的审计日志
+    #[serde(default = "default_audit_log")]
+    pub audit_log: PathBuf,
+    /// 记忆命名空间，设置后所有存储读写都限定在该用户/persona 内
+    #[serde(default)]
+    pub namespace: Option<MemoryNamespace>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_audit_log() -> PathBuf {
+    PathBuf::from(crate::privacy::DEFAULT_AUDIT_LOG)
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            redact_pii: true,
+            audit_log: default_audit_log(),
+            namespace: None,
+        }
+    }
 }
 
 fn default_backend_type() -> String {
@@ -212,7 +280,7 @@ pub struct LongTermMemoryConfig {
     pub backend: String,
     /// 向量存储集合名
     pub collection: String,
-    /// 嵌入向量提供商 (openai, ollama, anthropic, deepseek, glm, qwen, minimax, kimi, custom)
+    /// 嵌入向量提供商 (openai, ollama, anthropic, deepseek, glm, qwen, minimax, kimi, custom, local)
     pub embedding_provider: String,
     /// 嵌入模型
     pub embedding_model: String,
@@ -229,6 +297,12 @@ pub struct LongTermMemoryConfig {
     /// 自定义提供商配置 (当 embedding_provider 为 custom 时使用)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_embedding: Option<CustomEmbeddingConfig>,
+    /// 检索结果重排配置，未配置时只做分数融合
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reranker: Option<crate::rerank::RerankerSettings>,
+    /// 本地嵌入模型配置 (当 embedding_provider 为 local 时使用)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_embedding: Option<LocalEmbeddingSettings>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -255,10 +329,29 @@ impl Default for LongTermMemoryConfig {
             enable_bm25: false,
             enable_knowledge_graph: false,
             custom_embedding: None,
+            reranker: None,
+            local_embedding: None,
         }
     }
 }
 
+/// 本地嵌入模型的可序列化配置，未设置的字段使用模型默认值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocalEmbeddingSettings {
+    /// 模型缓存目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<String>,
+    /// 仅使用本地缓存，不访问网络
+    #[serde(default)]
+    pub offline: bool,
+    /// 权重精度 (f32, f16, bf16)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<String>,
+    /// 批处理大小
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<usize>,
+}
+
 /// 记忆检索结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryRetrieval {
@@ -88,6 +88,14 @@ impl WorkingMemory {
         items.len()
     }
 
+    /// 删除满足条件的消息，返回被删除的消息
+    pub fn remove_where(&self, predicate: impl Fn(&MemoryItem) -> bool) -> Vec<MemoryItem> {
+        let mut items = self.items.write().unwrap();
+        let (removed, kept): (Vec<_>, Vec<_>) = items.drain(..).partition(|item| predicate(item));
+        items.extend(kept);
+        removed
+    }
+
     /// 是否为空
     pub fn is_empty(&self) -> bool {
         self.len() == 0
@@ -445,6 +445,59 @@ impl AgentWorkspace {
         Ok(fs::read_to_string(path)?)
     }
 
+    /// 从 MEMORY.md、USER.md、每日记忆和会话转录中删除命中的行，返回删除行数
+    ///
+    /// 命中的行是标题时连同其下内容一起删除，直到下一个标题或分隔线 `---`（含）。
+    pub fn forget_lines(&self, matches: impl Fn(&str) -> bool) -> Result<usize> {
+        let mut files = vec![self.memory_path(), self.user_path()];
+        for dir in [self.memory_dir(), self.transcripts_dir()] {
+            if let Ok(entries) = fs::read_dir(dir) {
+                files.extend(
+                    entries
+                        .flatten()
+                        .map(|e| e.path())
+                        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("md")),
+                );
+            }
+        }
+
+        let mut removed = 0;
+        for path in files {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let mut kept: Vec<&str> = Vec::new();
+            let mut in_block = false;
+            for line in content.lines() {
+                if in_block {
+                    if line.trim() == "---" {
+                        in_block = false;
+                        continue;
+                    }
+                    if !line.starts_with('#') {
+                        continue;
+                    }
+                    in_block = false;
+                }
+                if matches(line) {
+                    in_block = line.starts_with('#');
+                } else {
+                    kept.push(line);
+                }
+            }
+            let dropped = content.lines().count() - kept.len();
+            if dropped > 0 {
+                let mut rewritten = kept.join("\n");
+                if content.ends_with('\n') {
+                    rewritten.push('\n');
+                }
+                fs::write(&path, rewritten)?;
+                removed += dropped;
+            }
+        }
+        Ok(removed)
+    }
+
     pub fn consolidate_to_memory(&self, days: Option<u32>) -> Result<String> {
         let days = days.unwrap_or(7);
         let cutoff = Local::now().date_naive() - chrono::Duration::days(days as i64);
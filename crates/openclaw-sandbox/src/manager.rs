@@ -4,6 +4,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use openclaw_core::telemetry::{TOOL_EXECUTION_DURATION_SECONDS, TOOL_EXECUTIONS_TOTAL};
 use openclaw_security::{GrantResult, NetworkDecision, SecurityMiddleware};
 
 use crate::docker::DockerClient;
@@ -301,11 +302,38 @@ impl SandboxManager {
         }
     }
 
+    #[tracing::instrument(name = "tool.execute", skip(self, input, target), fields(exit_code = tracing::field::Empty))]
     pub async fn execute(
         &self,
         tool_id: &str,
         input: &str,
         target: Option<&str>,
+    ) -> Result<ExecutionResult, SandboxError> {
+        let started = std::time::Instant::now();
+        let result = self.execute_inner(tool_id, input, target).await;
+
+        let status = match &result {
+            Ok(r) if r.timed_out => "timeout",
+            Ok(r) if r.exit_code == 0 => "ok",
+            Ok(_) => "failed",
+            Err(_) => "error",
+        };
+        if let Ok(r) = &result {
+            tracing::Span::current().record("exit_code", r.exit_code);
+        }
+        metrics::counter!(TOOL_EXECUTIONS_TOTAL, "tool" => tool_id.to_string(), "status" => status)
+            .increment(1);
+        metrics::histogram!(TOOL_EXECUTION_DURATION_SECONDS, "tool" => tool_id.to_string())
+            .record(started.elapsed().as_secs_f64());
+
+        result
+    }
+
+    async fn execute_inner(
+        &self,
+        tool_id: &str,
+        input: &str,
+        target: Option<&str>,
     ) -> Result<ExecutionResult, SandboxError> {
         let config = self
             .get_tool_config(tool_id)
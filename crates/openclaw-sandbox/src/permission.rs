@@ -71,6 +71,22 @@ pub enum Permission {
     /// 浏览器导航
     BrowserNavigate,
 
+    /// 查看设备
+    DeviceView,
+    /// 摄像头采集
+    DeviceCamera,
+    /// 屏幕采集
+    DeviceScreen,
+
+    /// 与 Agent 对话
+    AgentChat,
+    /// Agent 管理
+    AgentManage,
+    /// 通道管理
+    ChannelManage,
+    /// 语音合成与识别
+    VoiceUse,
+
     /// 工具调用
     ToolCall,
     /// 工具管理
@@ -82,6 +98,14 @@ pub enum Permission {
     /// Webhook 管理
     WebhookManage,
 
+    /// 查看运行指标
+    MetricsView,
+
+    /// 检索记忆与知识库
+    MemoryRead,
+    /// 写入记忆、导入文档
+    MemoryWrite,
+
     /// 系统管理
     SystemAdmin,
     /// 用户管理
@@ -93,6 +117,69 @@ pub enum Permission {
     Custom(String),
 }
 
+/// 作用域字符串与权限的对应关系，如 `browser:control`、`device:camera`
+const PERMISSION_SCOPES: &[(&str, Permission)] = &[
+    ("sandbox:create", Permission::SandboxCreate),
+    ("sandbox:execute", Permission::SandboxExecute),
+    ("sandbox:delete", Permission::SandboxDelete),
+    ("sandbox:view", Permission::SandboxView),
+    ("sandbox:manage", Permission::SandboxManage),
+    ("canvas:create", Permission::CanvasCreate),
+    ("canvas:edit", Permission::CanvasEdit),
+    ("canvas:delete", Permission::CanvasDelete),
+    ("canvas:view", Permission::CanvasView),
+    ("browser:control", Permission::BrowserControl),
+    ("browser:screenshot", Permission::BrowserScreenshot),
+    ("browser:navigate", Permission::BrowserNavigate),
+    ("device:view", Permission::DeviceView),
+    ("device:camera", Permission::DeviceCamera),
+    ("device:screen", Permission::DeviceScreen),
+    ("agent:chat", Permission::AgentChat),
+    ("agent:manage", Permission::AgentManage),
+    ("channel:manage", Permission::ChannelManage),
+    ("voice:use", Permission::VoiceUse),
+    ("tool:call", Permission::ToolCall),
+    ("tool:manage", Permission::ToolManage),
+    ("schedule:manage", Permission::ScheduleManage),
+    ("webhook:manage", Permission::WebhookManage),
+    ("metrics:read", Permission::MetricsView),
+    ("memory:read", Permission::MemoryRead),
+    ("memory:write", Permission::MemoryWrite),
+    ("system:admin", Permission::SystemAdmin),
+    ("user:admin", Permission::UserAdmin),
+    ("role:admin", Permission::RoleAdmin),
+];
+
+impl Permission {
+    /// 从作用域字符串解析权限，`custom:` 前缀映射为自定义权限
+    pub fn from_scope(scope: &str) -> Option<Self> {
+        if let Some(name) = scope.strip_prefix("custom:") {
+            return (!name.is_empty()).then(|| Permission::Custom(name.to_string()));
+        }
+        PERMISSION_SCOPES
+            .iter()
+            .find(|(s, _)| *s == scope)
+            .map(|(_, p)| p.clone())
+    }
+
+    /// 权限对应的作用域字符串
+    pub fn scope(&self) -> String {
+        match self {
+            Permission::Custom(s) => format!("custom:{}", s),
+            _ => PERMISSION_SCOPES
+                .iter()
+                .find(|(_, p)| p == self)
+                .map(|(s, _)| s.to_string())
+                .unwrap_or_else(|| format!("{:?}", self)),
+        }
+    }
+
+    /// 全部内置作用域
+    pub fn known_scopes() -> impl Iterator<Item = &'static str> {
+        PERMISSION_SCOPES.iter().map(|(s, _)| *s)
+    }
+}
+
 impl std::fmt::Display for Permission {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -154,10 +241,20 @@ impl Role {
             Permission::BrowserControl,
             Permission::BrowserScreenshot,
             Permission::BrowserNavigate,
+            Permission::DeviceView,
+            Permission::DeviceCamera,
+            Permission::DeviceScreen,
+            Permission::AgentChat,
+            Permission::AgentManage,
+            Permission::ChannelManage,
+            Permission::VoiceUse,
             Permission::ToolCall,
             Permission::ToolManage,
             Permission::ScheduleManage,
             Permission::WebhookManage,
+            Permission::MetricsView,
+            Permission::MemoryRead,
+            Permission::MemoryWrite,
             Permission::SystemAdmin,
             Permission::UserAdmin,
             Permission::RoleAdmin,
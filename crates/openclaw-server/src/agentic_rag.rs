@@ -2,6 +2,7 @@
 //!
 //! 实现自主信息检索和问答的 Agentic RAG 引擎
 
+pub mod citation;
 pub mod config;
 pub mod engine;
 pub mod executor;
@@ -9,6 +10,7 @@ pub mod loop_control;
 pub mod planner;
 pub mod reflector;
 
+pub use citation::*;
 pub use config::*;
 pub use engine::*;
 pub use executor::*;
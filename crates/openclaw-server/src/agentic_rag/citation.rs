@@ -0,0 +1,620 @@
+//! 答案引用与溯源
+//!
+//! 检索结果按顺序编号后交给模型，答案中的 `[n]` 标记被映射回结果 ID 和原文片段。
+//! 逐句检查答案是否有所引来源支撑，并按通道格式渲染引用列表。
+
+use std::collections::{HashMap, HashSet};
+
+use once_cell::sync::Lazy;
+use openclaw_channels::ChannelType;
+use serde::{Deserialize, Serialize};
+
+use super::config::SourceType;
+use super::executor::RetrievalResult;
+
+static MARKER_PATTERN: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r"\[(\d+(?:\s*,\s*\d+)*)\]").expect("Invalid regex: citation marker")
+});
+
+/// 句子实词在所引来源中的覆盖率达到该值视为有支撑
+pub const LEXICAL_SUPPORT_THRESHOLD: f32 = 0.5;
+/// 提供给模型的来源数上限
+pub const MAX_ANSWER_SOURCES: usize = 12;
+/// 检索不到足够依据时的回答
+pub const ABSTAIN_ANSWER: &str =
+    "I couldn't find enough support in the retrieved sources to answer this confidently.";
+/// 实词少于该数量的句子（过渡语、标题）不参与溯源
+const MIN_CLAIM_TOKENS: usize = 3;
+const QUOTE_MAX_CHARS: usize = 200;
+
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "are", "was", "were", "that", "this", "these", "those", "with", "from",
+    "have", "has", "had", "not", "but", "you", "your", "can", "will", "its", "into", "than",
+    "then", "there", "their", "they", "which", "what", "when", "where", "who", "how", "also",
+    "been", "being", "such", "via", "per", "our", "use", "used", "using",
+];
+const CJK_STOPWORDS: &[char] = &[
+    '的', '了', '是', '在', '和', '与', '或', '等', '也', '就', '都', '而', '及', '这', '那', '有',
+    '为', '中', '对', '可', '以',
+];
+
+/// 引用的渲染格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationStyle {
+    #[default]
+    Markdown,
+    /// Telegram HTML、邮件
+    Html,
+    /// Slack mrkdwn
+    Slack,
+    /// 不支持富文本的通道
+    Plain,
+}
+
+impl CitationStyle {
+    pub fn for_channel(channel: &ChannelType) -> Self {
+        match channel {
+            ChannelType::Telegram | ChannelType::Email => CitationStyle::Html,
+            ChannelType::Slack => CitationStyle::Slack,
+            ChannelType::WhatsApp
+            | ChannelType::Signal
+            | ChannelType::IMessage
+            | ChannelType::SMS
+            | ChannelType::Zalo
+            | ChannelType::ZaloPersonal => CitationStyle::Plain,
+            _ => CitationStyle::Markdown,
+        }
+    }
+}
+
+/// 支撑片段在结果正文中的字节范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CitationSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Citation {
+    /// 答案中的编号，从 1 开始按首次出现排序
+    pub number: usize,
+    pub result_id: String,
+    pub source: SourceType,
+    pub label: String,
+    pub url: Option<String>,
+    pub span: Option<CitationSpan>,
+    pub quote: Option<String>,
+}
+
+/// 单句溯源结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentenceGrounding {
+    /// 去掉引用标记后的句子
+    pub text: String,
+    /// 句中引用的来源编号
+    pub citations: Vec<usize>,
+    /// 是否为需要来源支撑的陈述
+    pub claim: bool,
+    pub supported: bool,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerVerification {
+    pub sentences: Vec<SentenceGrounding>,
+    /// 有支撑的陈述句占比，没有陈述句时为 1.0
+    pub grounding_score: f32,
+}
+
+impl AnswerVerification {
+    pub fn new(sentences: Vec<SentenceGrounding>) -> Self {
+        let mut verification = Self {
+            sentences,
+            grounding_score: 1.0,
+        };
+        verification.recompute();
+        verification
+    }
+
+    /// 修改 `supported` 后重新计算得分
+    pub fn recompute(&mut self) {
+        let claims = self.sentences.iter().filter(|s| s.claim).count();
+        let supported = self
+            .sentences
+            .iter()
+            .filter(|s| s.claim && s.supported)
+            .count();
+        self.grounding_score = if claims == 0 {
+            1.0
+        } else {
+            supported as f32 / claims as f32
+        };
+    }
+
+    pub fn unsupported_sentences(&self) -> Vec<String> {
+        self.sentences
+            .iter()
+            .filter(|s| s.claim && !s.supported)
+            .map(|s| s.text.clone())
+            .collect()
+    }
+}
+
+/// 带编号引用的答案
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CitedAnswer {
+    /// 引用标记已按 `citations` 重新编号的答案
+    pub text: String,
+    pub citations: Vec<Citation>,
+}
+
+impl CitedAnswer {
+    /// 将答案中指向 `results` 下标（从 1 开始）的标记映射为引用
+    ///
+    /// 只保留实际被引用的来源，并按首次出现重新编号；越界标记原样保留。
+    pub fn build(answer: &str, results: &[RetrievalResult]) -> Self {
+        let mut numbering: HashMap<usize, usize> = HashMap::new();
+        let mut citations = Vec::new();
+
+        for sentence in split_sentences(answer) {
+            let tokens = content_tokens(&strip_markers(&sentence));
+            for n in extract_markers(&sentence) {
+                let Some(result) = n.checked_sub(1).and_then(|i| results.get(i)) else {
+                    continue;
+                };
+                if numbering.contains_key(&n) {
+                    continue;
+                }
+                let number = citations.len() + 1;
+                numbering.insert(n, number);
+
+                let located = best_span(&result.content, &tokens);
+                citations.push(Citation {
+                    number,
+                    result_id: result.id.clone(),
+                    source: result.source.clone(),
+                    label: source_label(result),
+                    url: result.metadata.get("url").cloned(),
+                    span: located.as_ref().map(|(span, _)| *span),
+                    quote: located.map(|(_, quote)| quote),
+                });
+            }
+        }
+
+        let text = MARKER_PATTERN
+            .replace_all(answer, |caps: &regex::Captures| {
+                let renumbered: Vec<String> = parse_marker_group(&caps[1])
+                    .filter_map(|n| numbering.get(&n))
+                    .map(|n| format!("[{}]", n))
+                    .collect();
+                if renumbered.is_empty() {
+                    caps[0].to_string()
+                } else {
+                    renumbered.join("")
+                }
+            })
+            .to_string();
+
+        Self { text, citations }
+    }
+
+    pub fn abstain() -> Self {
+        Self {
+            text: ABSTAIN_ANSWER.to_string(),
+            citations: Vec::new(),
+        }
+    }
+
+    /// 渲染为正文加来源列表
+    pub fn render(&self, style: CitationStyle) -> String {
+        let mut out = match style {
+            CitationStyle::Html => escape_html(&self.text),
+            _ => self.text.clone(),
+        };
+        if self.citations.is_empty() {
+            return out;
+        }
+
+        out.push_str(match style {
+            CitationStyle::Markdown => "\n\n**Sources**",
+            CitationStyle::Html => "\n\n<b>Sources</b>",
+            CitationStyle::Slack => "\n\n*Sources*",
+            CitationStyle::Plain => "\n\nSources:",
+        });
+        for citation in &self.citations {
+            let label = match (style, &citation.url) {
+                (CitationStyle::Markdown, Some(url)) => format!("[{}]({})", citation.label, url),
+                (CitationStyle::Html, Some(url)) => format!(
+                    "<a href=\"{}\">{}</a>",
+                    escape_html(url),
+                    escape_html(&citation.label)
+                ),
+                (CitationStyle::Html, None) => escape_html(&citation.label),
+                (CitationStyle::Slack, Some(url)) => format!("<{}|{}>", url, citation.label),
+                (CitationStyle::Plain, Some(url)) if *url != citation.label => {
+                    format!("{} ({})", citation.label, url)
+                }
+                _ => citation.label.clone(),
+            };
+            out.push_str(&format!("\n[{}] {}", citation.number, label));
+        }
+        out
+    }
+
+    pub fn render_for_channel(&self, channel: &ChannelType) -> String {
+        self.render(CitationStyle::for_channel(channel))
+    }
+}
+
+/// 去重（保留最高分）后按相关度排序并截断，作为答案的编号来源
+pub fn rank_sources(results: Vec<RetrievalResult>) -> Vec<RetrievalResult> {
+    let mut best: HashMap<String, RetrievalResult> = HashMap::new();
+    for result in results {
+        match best.get(&result.id) {
+            Some(existing) if existing.relevance_score >= result.relevance_score => {}
+            _ => {
+                best.insert(result.id.clone(), result);
+            }
+        }
+    }
+
+    let mut ranked: Vec<RetrievalResult> = best.into_values().collect();
+    ranked.sort_by(|a, b| {
+        b.relevance_score
+            .partial_cmp(&a.relevance_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    ranked.truncate(MAX_ANSWER_SOURCES);
+    ranked
+}
+
+/// 按 `[n] 标签` 编号格式化来源，供生成答案的提示词使用
+pub fn format_sources(results: &[RetrievalResult]) -> String {
+    results
+        .iter()
+        .enumerate()
+        .map(|(i, r)| format!("[{}] {}\n{}", i + 1, source_label(r), r.content))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// 来源的可读标签：优先使用检索器提供的引用标注
+pub fn source_label(result: &RetrievalResult) -> String {
+    ["citation", "title", "path", "url"]
+        .iter()
+        .find_map(|key| result.metadata.get(*key).filter(|v| !v.is_empty()))
+        .cloned()
+        .unwrap_or_else(|| format!("{:?} {}", result.source, result.id))
+}
+
+/// 仅基于词汇重合度的逐句溯源
+pub fn verify_lexically(answer: &str, results: &[RetrievalResult]) -> AnswerVerification {
+    let source_tokens: Vec<HashSet<String>> =
+        results.iter().map(|r| content_tokens(&r.content)).collect();
+
+    let sentences = split_sentences(answer)
+        .into_iter()
+        .map(|sentence| {
+            let citations = extract_markers(&sentence);
+            let text = strip_markers(&sentence);
+            let tokens = content_tokens(&text);
+            let claim = tokens.len() >= MIN_CLAIM_TOKENS;
+            let score = citations
+                .iter()
+                .filter_map(|n| n.checked_sub(1).and_then(|i| source_tokens.get(i)))
+                .map(|source| lexical_support(&tokens, source))
+                .fold(0.0, f32::max);
+
+            SentenceGrounding {
+                supported: !claim || score >= LEXICAL_SUPPORT_THRESHOLD,
+                text,
+                citations,
+                claim,
+                score,
+            }
+        })
+        .collect();
+
+    AnswerVerification::new(sentences)
+}
+
+/// 按句切分，句末标点后紧跟的引用标记归入当前句
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+
+    for line in text.lines() {
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if !matches!(c, '.' | '!' | '?' | '。' | '！' | '？') {
+                continue;
+            }
+
+            let mut end = i + c.len_utf8();
+            loop {
+                let rest = &line[end..];
+                let trimmed = rest.trim_start();
+                match MARKER_PATTERN.find(trimmed) {
+                    Some(m) if m.start() == 0 => end += rest.len() - trimmed.len() + m.end(),
+                    _ => break,
+                }
+            }
+
+            // 英文标点后需跟空白才断句，避免切开小数和版本号
+            let ascii = c.is_ascii();
+            if ascii
+                && line[end..]
+                    .chars()
+                    .next()
+                    .is_some_and(|n| !n.is_whitespace())
+            {
+                continue;
+            }
+
+            let sentence = line[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+            start = end;
+            while chars.peek().is_some_and(|(j, _)| *j < end) {
+                chars.next();
+            }
+        }
+
+        let rest = line[start..].trim();
+        if !rest.is_empty() {
+            sentences.push(rest.to_string());
+        }
+    }
+
+    sentences
+}
+
+/// 句中引用的编号，按出现顺序去重
+pub fn extract_markers(text: &str) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    MARKER_PATTERN
+        .captures_iter(text)
+        .flat_map(|caps| parse_marker_group(&caps[1]).collect::<Vec<_>>())
+        .filter(|n| seen.insert(*n))
+        .collect()
+}
+
+pub fn strip_markers(text: &str) -> String {
+    let stripped = MARKER_PATTERN.replace_all(text, "");
+    stripped
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" .", ".")
+        .replace(" ,", ",")
+}
+
+fn parse_marker_group(group: &str) -> impl Iterator<Item = usize> + '_ {
+    group.split(',').filter_map(|n| n.trim().parse().ok())
+}
+
+fn lexical_support(claim: &HashSet<String>, source: &HashSet<String>) -> f32 {
+    if claim.is_empty() {
+        return 0.0;
+    }
+    claim.intersection(source).count() as f32 / claim.len() as f32
+}
+
+/// 在来源正文中找与句子重合最多的句子
+fn best_span(content: &str, tokens: &HashSet<String>) -> Option<(CitationSpan, String)> {
+    let (start, end) = content_sentence_spans(content)
+        .into_iter()
+        .map(|(start, end)| {
+            let overlap = content_tokens(&content[start..end])
+                .intersection(tokens)
+                .count();
+            (overlap, start, end)
+        })
+        .filter(|(overlap, _, _)| *overlap > 0)
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
+        .map(|(_, start, end)| (start, end))?;
+
+    let quote: String = content[start..end].chars().take(QUOTE_MAX_CHARS).collect();
+    Some((CitationSpan { start, end }, quote))
+}
+
+fn content_sentence_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if matches!(c, '.' | '!' | '?' | '。' | '！' | '？' | '\n') {
+            let end = i + c.len_utf8();
+            spans.push((start, end));
+            start = end;
+        }
+    }
+    if start < text.len() {
+        spans.push((start, text.len()));
+    }
+
+    spans
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let segment = &text[start..end];
+            let lead = segment.len() - segment.trim_start().len();
+            let trail = segment.len() - segment.trim_end().len();
+            (start + lead < end - trail).then_some((start + lead, end - trail))
+        })
+        .collect()
+}
+
+/// 小写实词集合：拉丁词至少 3 个字符（数字至少 2 位），中日韩文字逐字计入
+fn content_tokens(text: &str) -> HashSet<String> {
+    let mut tokens = HashSet::new();
+    let mut word = String::new();
+
+    let flush = |word: &mut String, tokens: &mut HashSet<String>| {
+        let numeric = word.chars().all(|c| c.is_ascii_digit());
+        let long_enough = if numeric {
+            word.len() >= 2
+        } else {
+            word.chars().count() >= 3
+        };
+        if long_enough && !STOPWORDS.contains(&word.as_str()) {
+            tokens.insert(std::mem::take(word));
+        }
+        word.clear();
+    };
+
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if is_cjk(c) {
+            flush(&mut word, &mut tokens);
+            if !CJK_STOPWORDS.contains(&c) {
+                tokens.insert(c.to_string());
+            }
+        } else if c.is_alphanumeric() {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut tokens);
+        }
+    }
+    flush(&mut word, &mut tokens);
+
+    tokens
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{4e00}'..='\u{9fff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{3040}'..='\u{30ff}'
+        | '\u{ac00}'..='\u{d7af}')
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, content: &str, metadata: &[(&str, &str)]) -> RetrievalResult {
+        RetrievalResult {
+            id: id.to_string(),
+            content: content.to_string(),
+            source: SourceType::File,
+            relevance_score: 0.8,
+            metadata: metadata
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    fn sources() -> Vec<RetrievalResult> {
+        vec![
+            result(
+                "chunk-a",
+                "Install Rust first. The gateway listens on port 18789 by default.",
+                &[("citation", "docs/guide.md:3-4")],
+            ),
+            result(
+                "chunk-b",
+                "Telegram bots need a token from BotFather.",
+                &[("url", "https://core.telegram.org/bots")],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_split_sentences_keeps_trailing_markers() {
+        let sentences = split_sentences(
+            "The gateway uses port 18789 [1]. Version 1.2 is current. [2][1]\n- Tokens come from BotFather.",
+        );
+        assert_eq!(
+            sentences,
+            vec![
+                "The gateway uses port 18789 [1].",
+                "Version 1.2 is current. [2][1]",
+                "- Tokens come from BotFather.",
+            ]
+        );
+        assert_eq!(extract_markers("a [2, 1] b [1]"), vec![2, 1]);
+        assert_eq!(strip_markers("port 18789 [1]."), "port 18789.");
+    }
+
+    #[test]
+    fn test_build_renumbers_and_locates_spans() {
+        let results = sources();
+        let cited = CitedAnswer::build(
+            "Bots need a BotFather token [2]. The gateway listens on port 18789 [1]. See array[7].",
+            &results,
+        );
+
+        assert_eq!(
+            cited.text,
+            "Bots need a BotFather token [1]. The gateway listens on port 18789 [2]. See array[7]."
+        );
+        assert_eq!(cited.citations.len(), 2);
+        assert_eq!(cited.citations[0].result_id, "chunk-b");
+        assert_eq!(cited.citations[1].label, "docs/guide.md:3-4");
+
+        let span = cited.citations[1].span.unwrap();
+        assert_eq!(
+            &results[0].content[span.start..span.end],
+            "The gateway listens on port 18789 by default."
+        );
+    }
+
+    #[test]
+    fn test_lexical_verification_flags_unsupported_sentences() {
+        let results = sources();
+        let verification = verify_lexically(
+            "Here is what I found. The gateway listens on port 18789 [1]. Discord webhooks require OAuth scopes [1]. Bots need BotFather tokens.",
+            &results,
+        );
+
+        assert!(!verification.sentences[0].claim);
+        assert!(verification.sentences[1].supported);
+        assert!(!verification.sentences[2].supported);
+        // 未引用来源的陈述视为无支撑
+        assert!(!verification.sentences[3].supported);
+        assert!((verification.grounding_score - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(verification.unsupported_sentences().len(), 2);
+    }
+
+    #[test]
+    fn test_render_per_channel() {
+        let cited = CitedAnswer::build("Bots need a BotFather token <b> [2].", &sources());
+
+        let markdown = cited.render(CitationStyle::Markdown);
+        assert!(markdown.ends_with(
+            "**Sources**\n[1] [https://core.telegram.org/bots](https://core.telegram.org/bots)"
+        ));
+
+        let html = cited.render_for_channel(&ChannelType::Telegram);
+        assert!(html.starts_with("Bots need a BotFather token &lt;b&gt; [1]."));
+        assert!(html.contains("<a href=\"https://core.telegram.org/bots\">"));
+
+        let slack = cited.render_for_channel(&ChannelType::Slack);
+        assert!(slack.contains("*Sources*\n[1] <https://core.telegram.org/bots|"));
+
+        let sms = cited.render_for_channel(&ChannelType::SMS);
+        assert!(sms.ends_with("Sources:\n[1] https://core.telegram.org/bots"));
+    }
+
+    #[test]
+    fn test_rank_sources_dedupes_by_id() {
+        let mut low = result("chunk-a", "x", &[]);
+        low.relevance_score = 0.1;
+        let mut high = result("chunk-c", "y", &[]);
+        high.relevance_score = 0.95;
+
+        let ranked = rank_sources(vec![low, high].into_iter().chain(sources()).collect());
+        let ids: Vec<&str> = ranked.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["chunk-c", "chunk-a", "chunk-b"]);
+        assert_eq!(ranked[1].relevance_score, 0.8);
+    }
+}
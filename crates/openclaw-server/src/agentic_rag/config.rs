@@ -1,9 +1,11 @@
 //! Agentic RAG 配置
 
+use openclaw_memory::rerank::RerankerSettings;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AgenticRAGConfig {
     pub enabled: bool,
     pub planner: PlannerConfig,
@@ -59,6 +61,9 @@ pub struct ExecutorConfig {
     pub timeout_ms: u64,
     pub max_results_per_source: usize,
     pub enable_parallel: bool,
+    /// 多源结果合并后的重排配置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reranker: Option<RerankerSettings>,
 }
 
 impl Default for ExecutorConfig {
@@ -67,6 +72,7 @@ impl Default for ExecutorConfig {
             timeout_ms: 30000,
             max_results_per_source: 10,
             enable_parallel: true,
+            reranker: None,
         }
     }
 }
@@ -76,6 +82,20 @@ pub struct ReflectorConfig {
     pub min_confidence: f32,
     pub max_iterations: usize,
     pub enable_verification: bool,
+    /// 答案中有来源支撑的陈述句占比下限，低于该值时补充检索或拒答
+    #[serde(default = "default_min_grounding")]
+    pub min_grounding: f32,
+    /// 溯源不足时补充检索的次数
+    #[serde(default = "default_max_grounding_retries")]
+    pub max_grounding_retries: usize,
+}
+
+fn default_min_grounding() -> f32 {
+    0.6
+}
+
+fn default_max_grounding_retries() -> usize {
+    1
 }
 
 impl Default for ReflectorConfig {
@@ -84,6 +104,8 @@ impl Default for ReflectorConfig {
             min_confidence: 0.7,
             max_iterations: 3,
             enable_verification: true,
+            min_grounding: default_min_grounding(),
+            max_grounding_retries: default_max_grounding_retries(),
         }
     }
 }
@@ -93,6 +115,7 @@ pub struct SourceConfig {
     pub source_type: SourceType,
     pub enabled: bool,
     pub priority: usize,
+    #[serde(default)]
     pub config: HashMap<String, String>,
 }
 
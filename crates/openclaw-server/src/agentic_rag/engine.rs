@@ -1,16 +1,22 @@
 //! Agentic RAG 核心引擎
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use openclaw_channels::ChannelType;
 use openclaw_core::{Message, Result};
 
+use super::citation::{self, Citation, CitationStyle, CitedAnswer};
 use super::config::{AgenticRAGConfig, SourceType};
 use super::executor::{MultiSourceRetrievalExecutor, RetrievalResult};
-use super::loop_control::{AgentLoopState, DefaultLoopController, LoopController};
+use super::loop_control::{AgentAction, AgentLoopState, DefaultLoopController, LoopController};
 use super::planner::QueryPlanner;
 use super::reflector::{Reflection, ResultReflector};
 
+/// 每轮补充检索最多使用的无支撑句子数
+const MAX_GROUNDING_QUERIES: usize = 3;
+
 pub struct AgenticRAGEngine {
     config: AgenticRAGConfig,
     llm: Arc<dyn openclaw_ai::AIProvider>,
@@ -24,7 +30,7 @@ impl AgenticRAGEngine {
     pub async fn new(
         config: AgenticRAGConfig,
         llm: Arc<dyn openclaw_ai::AIProvider>,
-        memory_manager: Option<Arc<openclaw_memory::MemoryManager>>,
+        memory: Option<Arc<dyn openclaw_memory::factory::MemoryBackend>>,
         vector_store: Option<Arc<dyn openclaw_vector::VectorStore>>,
         embedding_provider: Option<Arc<dyn openclaw_memory::embedding::EmbeddingProvider>>,
     ) -> Result<Self> {
@@ -38,18 +44,42 @@ impl AgenticRAGEngine {
 
         let mut executor = MultiSourceRetrievalExecutor::new();
 
-        if let Some(memory) = memory_manager {
+        if let Some(memory) = memory {
             let memory_executor = super::executor::MemoryRetrievalExecutor::new(memory);
             executor = executor.add_executor(Box::new(memory_executor));
         }
 
-        if let Some(vs) = vector_store {
-            if let Some(ep) = embedding_provider {
+        if let Some(vs) = vector_store.clone() {
+            if let Some(ep) = embedding_provider.clone() {
                 let vector_executor = super::executor::VectorDBRetrievalExecutor::new(vs, ep);
                 executor = executor.add_executor(Box::new(vector_executor));
             }
         }
 
+        for source in config
+            .sources
+            .iter()
+            .filter(|s| s.enabled && s.source_type == SourceType::File)
+        {
+            match super::executor::FileRetrievalExecutor::from_source_config(
+                &source.config,
+                vector_store.clone(),
+                embedding_provider.clone(),
+            )
+            .await
+            {
+                Ok(file_executor) => executor = executor.add_executor(Box::new(file_executor)),
+                Err(e) => tracing::warn!("文件数据源初始化失败: {}", e),
+            }
+        }
+
+        if let Some(settings) = &config.executor.reranker {
+            match settings.build(Some(llm.clone())).await {
+                Ok(stage) => executor = executor.with_reranker(Arc::new(stage)),
+                Err(e) => tracing::warn!("重排器初始化失败，跳过重排: {}", e),
+            }
+        }
+
         Ok(Self {
             config,
             llm,
@@ -101,25 +131,96 @@ impl AgenticRAGEngine {
             state.add_results(refined_results);
         }
 
-        let answer = self
+        let mut context = citation::rank_sources(std::mem::take(&mut state.retrieved_context));
+        let mut answer = self
+            .reflector
+            .generate_answer(&request.query, &context, &request.history)
+            .await?;
+        let mut verification = self
             .reflector
-            .generate_answer(&request.query, &state.retrieved_context, &request.history)
+            .verify_answer(&request.query, &answer, &context, &self.config.reflector)
             .await?;
 
-        let confidence = if state.retrieved_context.is_empty() {
+        // 溯源不足时以无支撑的句子为查询补充检索后重新作答
+        let mut retries = 0;
+        while !context.is_empty()
+            && verification.grounding_score < self.config.reflector.min_grounding
+            && retries < self.config.reflector.max_grounding_retries
+        {
+            retries += 1;
+            let unsupported = verification.unsupported_sentences();
+            state.add_thought(
+                AgentAction::Reflect,
+                format!(
+                    "Grounding {:.2} below {:.2}, re-retrieving for {} unsupported sentences",
+                    verification.grounding_score,
+                    self.config.reflector.min_grounding,
+                    unsupported.len()
+                ),
+            );
+
+            let known: HashSet<String> = context.iter().map(|r| r.id.clone()).collect();
+            let additional: Vec<RetrievalResult> = self
+                .retrieve_for_claims(&unsupported)
+                .await?
+                .into_iter()
+                .filter(|r| !known.contains(&r.id))
+                .collect();
+            if additional.is_empty() {
+                break;
+            }
+
+            context = citation::rank_sources(context.into_iter().chain(additional).collect());
+            answer = self
+                .reflector
+                .generate_answer(&request.query, &context, &request.history)
+                .await?;
+            verification = self
+                .reflector
+                .verify_answer(&request.query, &answer, &context, &self.config.reflector)
+                .await?;
+        }
+
+        let abstained = context.is_empty()
+            || verification.grounding_score < self.config.reflector.min_grounding;
+        let cited = if abstained {
+            state.add_thought(
+                AgentAction::Answer,
+                format!(
+                    "Abstained: grounding {:.2} below {:.2}",
+                    verification.grounding_score, self.config.reflector.min_grounding
+                ),
+            );
+            CitedAnswer::abstain()
+        } else {
+            CitedAnswer::build(&answer, &context)
+        };
+
+        let style = request
+            .options
+            .citation_style
+            .or_else(|| {
+                request
+                    .options
+                    .channel
+                    .as_ref()
+                    .map(CitationStyle::for_channel)
+            })
+            .unwrap_or_default();
+
+        let confidence = if context.is_empty() {
             0.0
         } else {
-            state
-                .retrieved_context
-                .iter()
-                .map(|r| r.relevance_score)
-                .sum::<f32>()
-                / state.retrieved_context.len() as f32
+            context.iter().map(|r| r.relevance_score).sum::<f32>() / context.len() as f32
         };
 
         Ok(RAGResponse {
-            answer,
-            sources: state.retrieved_context,
+            answer: cited.render(style),
+            sources: context,
+            citations: cited.citations,
+            grounding_score: verification.grounding_score,
+            unsupported_sentences: verification.unsupported_sentences(),
+            abstained,
             iterations: state.iteration,
             confidence,
             trace: state
@@ -144,18 +245,11 @@ impl AgenticRAGEngine {
         reflection: &Reflection,
     ) -> Result<Vec<RetrievalResult>> {
         let mut refined_results = Vec::new();
+        let sources = self.enabled_sources();
 
         for suggestion in &reflection.suggestions {
             let refined_query = format!("{} {}", query, suggestion);
 
-            let sources: Vec<SourceType> = self
-                .config
-                .sources
-                .iter()
-                .filter(|s| s.enabled)
-                .map(|s| s.source_type.clone())
-                .collect();
-
             let results = self
                 .executor
                 .execute_all(
@@ -172,6 +266,35 @@ impl AgenticRAGEngine {
         Ok(refined_results)
     }
 
+    async fn retrieve_for_claims(&self, claims: &[String]) -> Result<Vec<RetrievalResult>> {
+        let mut results = Vec::new();
+        let sources = self.enabled_sources();
+
+        for claim in claims.iter().take(MAX_GROUNDING_QUERIES) {
+            results.extend(
+                self.executor
+                    .execute_all(
+                        claim,
+                        &sources,
+                        &self.config.executor,
+                        self.config.executor.enable_parallel,
+                    )
+                    .await?,
+            );
+        }
+
+        Ok(results)
+    }
+
+    fn enabled_sources(&self) -> Vec<SourceType> {
+        self.config
+            .sources
+            .iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.source_type.clone())
+            .collect()
+    }
+
     pub async fn retrieve(
         &self,
         query: &str,
@@ -234,6 +357,12 @@ pub struct RAGOptions {
     pub max_iterations: Option<usize>,
     pub min_confidence: Option<f32>,
     pub sources: Option<Vec<SourceType>>,
+    /// 引用渲染格式，优先于 `channel`
+    #[serde(default)]
+    pub citation_style: Option<CitationStyle>,
+    /// 回答将发送到的通道，用于选择引用格式
+    #[serde(default)]
+    pub channel: Option<ChannelType>,
 }
 
 impl Default for RAGOptions {
@@ -242,14 +371,27 @@ impl Default for RAGOptions {
             max_iterations: None,
             min_confidence: None,
             sources: None,
+            citation_style: None,
+            channel: None,
         }
     }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RAGResponse {
+    /// 已按通道格式渲染引用的答案
     pub answer: String,
     pub sources: Vec<RetrievalResult>,
+    #[serde(default)]
+    pub citations: Vec<Citation>,
+    /// 有来源支撑的陈述句占比
+    #[serde(default)]
+    pub grounding_score: f32,
+    #[serde(default)]
+    pub unsupported_sentences: Vec<String>,
+    /// 依据不足而拒绝作答
+    #[serde(default)]
+    pub abstained: bool,
     pub iterations: usize,
     pub confidence: f32,
     pub trace: Vec<ActionTrace>,
@@ -314,6 +456,10 @@ mod tests {
         let response = RAGResponse {
             answer: "Test answer".to_string(),
             sources: vec![],
+            citations: vec![],
+            grounding_score: 1.0,
+            unsupported_sentences: vec![],
+            abstained: false,
             iterations: 1,
             confidence: 0.8,
             trace: vec![],
@@ -9,6 +9,11 @@ use lru::LruCache;
 use serde::{Deserialize, Serialize};
 
 use openclaw_core::{Message, Result};
+use openclaw_memory::file_watcher::FileWatcher;
+use openclaw_memory::rerank::{RerankStage, Rerankable};
+use openclaw_memory::{FileCorpusIndex, FileIndexConfig};
+
+pub use openclaw_memory::rerank::{LlmReranker, Reranker};
 
 use super::config::{ExecutorConfig, SourceType};
 
@@ -77,14 +82,14 @@ pub trait RetrievalExecutor: Send + Sync {
 }
 
 pub struct MemoryRetrievalExecutor {
-    memory_manager: Arc<openclaw_memory::MemoryManager>,
+    memory: Arc<dyn openclaw_memory::factory::MemoryBackend>,
     source_type: SourceType,
 }
 
 impl MemoryRetrievalExecutor {
-    pub fn new(memory_manager: Arc<openclaw_memory::MemoryManager>) -> Self {
+    pub fn new(memory: Arc<dyn openclaw_memory::factory::MemoryBackend>) -> Self {
         Self {
-            memory_manager,
+            memory,
             source_type: SourceType::Memory,
         }
     }
@@ -93,7 +98,7 @@ impl MemoryRetrievalExecutor {
 #[async_trait]
 impl RetrievalExecutor for MemoryRetrievalExecutor {
     async fn execute(&self, query: &str, _config: &ExecutorConfig) -> Result<Vec<RetrievalResult>> {
-        let result = self.memory_manager.recall(query).await?;
+        let result = self.memory.recall(query).await?;
 
         Ok(result
             .items
@@ -238,66 +243,144 @@ impl WebRetrievalExecutor {
     }
 }
 
+/// 文件语料检索执行器
+///
+/// 基于持久化的 [`FileCorpusIndex`]，返回块级结果，
+/// `metadata` 中带有 `path`、`start_line`、`end_line` 和 `citation`。
 pub struct FileRetrievalExecutor {
-    base_path: Option<String>,
+    index: Arc<FileCorpusIndex>,
     source_type: SourceType,
+    watcher: Option<FileWatcher>,
 }
 
 impl FileRetrievalExecutor {
-    pub fn new() -> Self {
+    pub fn new(index: Arc<FileCorpusIndex>) -> Self {
         Self {
-            base_path: None,
+            index,
             source_type: SourceType::File,
+            watcher: None,
         }
     }
 
-    pub fn with_base_path(mut self, path: String) -> Self {
-        self.base_path = Some(path);
-        self
+    /// 按数据源配置打开索引并完成首次同步
+    ///
+    /// 识别的配置项：`paths`、`include`、`exclude`（均为逗号分隔）、`index_dir`、
+    /// `chunk_size`、`chunk_overlap`，以及 `watch`（默认开启文件监听增量索引）。
+    /// 同时提供向量库和嵌入模型时启用向量检索，否则只走 BM25。
+    pub async fn from_source_config(
+        config: &HashMap<String, String>,
+        vector_store: Option<Arc<dyn openclaw_vector::VectorStore>>,
+        embedding_provider: Option<Arc<dyn openclaw_memory::embedding::EmbeddingProvider>>,
+    ) -> Result<Self> {
+        let index_config = file_index_config(config)?;
+        let mut index = FileCorpusIndex::open(index_config)?;
+        if let (Some(store), Some(provider)) = (vector_store, embedding_provider) {
+            index = index.with_vectors(store, provider);
+        }
+        let index = Arc::new(index);
+
+        let stats = index.refresh().await?;
+        tracing::info!(
+            "文件语料索引完成: 新增/更新 {}, 未变化 {}, 移除 {}, 跳过 {}",
+            stats.indexed,
+            stats.unchanged,
+            stats.removed,
+            stats.skipped
+        );
+
+        let mut executor = Self::new(index);
+        if config.get("watch").map(|v| v != "false").unwrap_or(true) {
+            executor.watcher = Some(executor.index.watch().await?);
+        }
+        Ok(executor)
+    }
+
+    pub fn index(&self) -> &Arc<FileCorpusIndex> {
+        &self.index
+    }
+
+    pub async fn stop_watching(&self) {
+        if let Some(watcher) = &self.watcher {
+            let _ = watcher.stop().await;
+        }
     }
 }
 
-impl Default for FileRetrievalExecutor {
-    fn default() -> Self {
-        Self::new()
+fn file_index_config(config: &HashMap<String, String>) -> Result<FileIndexConfig> {
+    let list = |key: &str| -> Option<Vec<String>> {
+        config.get(key).map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+    };
+    let number = |key: &str| -> Result<Option<usize>> {
+        config
+            .get(key)
+            .map(|v| {
+                v.parse().map_err(|_| {
+                    openclaw_core::OpenClawError::Config(format!("文件数据源 {} 不是有效数字: {}", key, v))
+                })
+            })
+            .transpose()
+    };
+
+    let roots: Vec<std::path::PathBuf> = list("paths")
+        .unwrap_or_default()
+        .into_iter()
+        .map(std::path::PathBuf::from)
+        .collect();
+    if roots.is_empty() {
+        return Err(openclaw_core::OpenClawError::Config(
+            "文件数据源缺少 paths 配置".to_string(),
+        ));
+    }
+
+    let mut index_config = FileIndexConfig {
+        roots,
+        ..Default::default()
+    };
+    if let Some(include) = list("include") {
+        index_config.include = include;
     }
+    if let Some(exclude) = list("exclude") {
+        index_config.exclude = exclude;
+    }
+    if let Some(dir) = config.get("index_dir") {
+        index_config.index_dir = dir.into();
+    }
+    if let Some(size) = number("chunk_size")? {
+        index_config.chunk_size = size;
+    }
+    if let Some(overlap) = number("chunk_overlap")? {
+        index_config.chunk_overlap = overlap;
+    }
+    Ok(index_config)
 }
 
 #[async_trait]
 impl RetrievalExecutor for FileRetrievalExecutor {
     async fn execute(&self, query: &str, config: &ExecutorConfig) -> Result<Vec<RetrievalResult>> {
-        let base = self.base_path.as_ref().ok_or_else(|| {
-            openclaw_core::OpenClawError::Config("FileRetrievalExecutor: base_path not set".to_string())
-        })?;
-
-        let search_query = query.to_lowercase();
-        let mut results = Vec::new();
-
-        let mut entries = tokio::fs::read_dir(base).await?;
-        while let Some(entry) = entries.next_entry().await? {
-            if let Ok(file_type) = entry.file_type().await {
-                if file_type.is_file() {
-                    if let Ok(content) = tokio::fs::read_to_string(entry.path()).await {
-                        if content.to_lowercase().contains(&search_query) {
-                            results.push(RetrievalResult {
-                                id: entry.path().to_string_lossy().to_string(),
-                                content: content.chars().take(1000).collect(),
-                                source: SourceType::File,
-                                relevance_score: 0.8,
-                                metadata: {
-                                    let mut m = HashMap::new();
-                                    m.insert("filename".to_string(), entry.file_name().to_string_lossy().to_string());
-                                    m
-                                },
-                            });
-                        }
-                    }
-                }
-            }
-        }
+        let hits = self.index.search(query, config.max_results_per_source).await?;
 
-        results.truncate(config.max_results_per_source);
-        Ok(results)
+        Ok(hits
+            .into_iter()
+            .map(|hit| RetrievalResult {
+                id: hit.chunk_id.clone(),
+                source: SourceType::File,
+                relevance_score: hit.score,
+                metadata: {
+                    let mut m = HashMap::new();
+                    m.insert("citation".to_string(), hit.citation());
+                    m.insert("path".to_string(), hit.path.to_string_lossy().to_string());
+                    m.insert("start_line".to_string(), hit.start_line.to_string());
+                    m.insert("end_line".to_string(), hit.end_line.to_string());
+                    m
+                },
+                content: hit.content,
+            })
+            .collect())
     }
 
     async fn execute_with_context(
@@ -493,68 +576,35 @@ impl HybridFusionExecutor {
     }
 }
 
-pub trait Reranker: Send + Sync {
-    async fn rerank(&self, query: &str, results: &mut Vec<RetrievalResult>) -> Result<()>;
-}
-
-pub struct LlmReranker {
-    llm: Arc<dyn openclaw_ai::AIProvider>,
-}
-
-impl LlmReranker {
-    pub fn new(llm: Arc<dyn openclaw_ai::AIProvider>) -> Self {
-        Self { llm }
+impl Rerankable for RetrievalResult {
+    fn rerank_text(&self) -> &str {
+        &self.content
     }
-}
-
-impl Reranker for LlmReranker {
-    async fn rerank(&self, query: &str, results: &mut Vec<RetrievalResult>) -> Result<()> {
-        if results.is_empty() {
-            return Ok(());
-        }
-
-        let prompt = Message::system(format!(
-            "Given the query: \"{}\"\n\nRank the following results by relevance (1 = most relevant):\n\n{}\n\nProvide rankings as a JSON array of indices in order of relevance.",
-            query,
-            results
-                .iter()
-                .enumerate()
-                .map(|(i, r)| format!("{}. {}", i + 1, r.content.chars().take(200).collect::<String>()))
-                .collect::<Vec<_>>()
-                .join("\n")
-        ));
-
-        let request = openclaw_ai::ChatRequest::new("default", vec![prompt]);
-        let response = self.llm.chat(request).await?;
-
-        let rankings_text = response.message.text_content().unwrap_or("[]");
-
-        if let Ok(indices) = serde_json::from_str::<Vec<usize>>(rankings_text) {
-            let mut reranked = Vec::with_capacity(results.len());
-            for idx in indices {
-                if idx < results.len() {
-                    reranked.push(results.remove(idx));
-                }
-            }
-            reranked.append(results);
-            *results = reranked;
-        }
 
-        Ok(())
+    fn set_rerank_score(&mut self, score: f32) {
+        self.relevance_score = score;
     }
 }
 
 pub struct MultiSourceRetrievalExecutor {
     executors: HashMap<SourceType, Box<dyn RetrievalExecutor>>,
+    reranker: Option<Arc<RerankStage>>,
 }
 
 impl MultiSourceRetrievalExecutor {
     pub fn new() -> Self {
         Self {
             executors: HashMap::new(),
+            reranker: None,
         }
     }
 
+    /// 多源结果合并后统一重排
+    pub fn with_reranker(mut self, reranker: Arc<RerankStage>) -> Self {
+        self.reranker = Some(reranker);
+        self
+    }
+
     pub fn add_executor(mut self, executor: Box<dyn RetrievalExecutor>) -> Self {
         let source_type = executor.source_type();
         self.executors.insert(source_type, executor);
@@ -585,7 +635,7 @@ impl MultiSourceRetrievalExecutor {
                 .flatten()
                 .collect();
 
-            Ok(results)
+            Ok(self.rerank(query, results).await)
         } else {
             let mut all_results = Vec::new();
             for source in sources {
@@ -596,10 +646,22 @@ impl MultiSourceRetrievalExecutor {
                     }
                 }
             }
-            Ok(all_results)
+            Ok(self.rerank(query, all_results).await)
         }
     }
 
+    async fn rerank(&self, query: &str, mut results: Vec<RetrievalResult>) -> Vec<RetrievalResult> {
+        let Some(stage) = &self.reranker else {
+            return results;
+        };
+        results.sort_by(|a, b| {
+            b.relevance_score
+                .partial_cmp(&a.relevance_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        stage.rerank(query, results).await
+    }
+
     pub fn get_executor(&self, source: &SourceType) -> Option<&Box<dyn RetrievalExecutor>> {
         self.executors.get(source)
     }
@@ -708,18 +770,49 @@ mod tests {
     }
 
     #[test]
-    fn test_file_retrieval_executor_new() {
-        let executor = FileRetrievalExecutor::new();
-        assert_eq!(executor.source_type(), SourceType::File);
+    fn test_file_index_config_from_source() {
+        let mut source = HashMap::new();
+        source.insert("paths".to_string(), "docs, notes".to_string());
+        source.insert("include".to_string(), "**/*.md".to_string());
+        source.insert("chunk_size".to_string(), "200".to_string());
+
+        let config = file_index_config(&source).unwrap();
+        assert_eq!(config.roots.len(), 2);
+        assert_eq!(config.include, vec!["**/*.md".to_string()]);
+        assert_eq!(config.chunk_size, 200);
+
+        source.insert("chunk_size".to_string(), "many".to_string());
+        assert!(file_index_config(&source).is_err());
+        assert!(file_index_config(&HashMap::new()).is_err());
     }
 
-    #[test]
-    fn test_file_retrieval_executor_with_base_path() {
-        let executor = FileRetrievalExecutor::new()
-            .with_base_path("/tmp".to_string());
-        
-        let base = executor.base_path.as_ref();
-        assert!(base.is_some());
-        assert_eq!(base.unwrap(), "/tmp");
+    #[tokio::test]
+    async fn test_file_retrieval_executor_returns_citations() {
+        let root = tempfile::tempdir().unwrap();
+        let index_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("deploy.md"),
+            "# Deploy\n\nRun the gateway behind a reverse proxy.\n",
+        )
+        .unwrap();
+
+        let mut source = HashMap::new();
+        source.insert("paths".to_string(), root.path().to_string_lossy().to_string());
+        source.insert("index_dir".to_string(), index_dir.path().to_string_lossy().to_string());
+        source.insert("watch".to_string(), "false".to_string());
+
+        let executor = FileRetrievalExecutor::from_source_config(&source, None, None)
+            .await
+            .unwrap();
+        assert_eq!(executor.source_type(), SourceType::File);
+
+        let results = executor
+            .execute("reverse proxy", &ExecutorConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content.contains("reverse proxy"));
+        assert_eq!(results[0].metadata.get("start_line").unwrap(), "1");
+        assert!(results[0].metadata.get("citation").unwrap().ends_with("deploy.md:1-3"));
     }
 }
@@ -6,6 +6,7 @@ use async_trait::async_trait;
 
 use openclaw_core::{Message, Result};
 
+use super::citation::{self, AnswerVerification};
 use super::config::ReflectorConfig;
 use super::executor::RetrievalResult;
 
@@ -32,8 +33,23 @@ pub trait ResultReflector: Send + Sync {
         results: &[RetrievalResult],
         context: &[Message],
     ) -> Result<String>;
+
+    /// 逐句检查答案是否有所引来源支撑，`[n]` 对应 `results` 的第 n 项
+    async fn verify_answer(
+        &self,
+        _query: &str,
+        answer: &str,
+        results: &[RetrievalResult],
+        _config: &ReflectorConfig,
+    ) -> Result<AnswerVerification> {
+        Ok(citation::verify_lexically(answer, results))
+    }
 }
 
+const CITATION_INSTRUCTIONS: &str = "Provide a clear, accurate answer using only the numbered sources above. \
+Cite the supporting source after every factual sentence with its number in square brackets, e.g. [1] or [1][3]. \
+Do not cite sources that do not contain the statement. If the sources are insufficient, state that clearly.";
+
 pub struct DefaultResultReflector {
     llm: Arc<dyn openclaw_ai::AIProvider>,
 }
@@ -78,11 +94,7 @@ impl ResultReflector for DefaultResultReflector {
             return Ok("I couldn't find relevant information to answer your question.".to_string());
         }
 
-        let context_str = results
-            .iter()
-            .map(|r| format!("[{:?}] {}", r.source, r.content))
-            .collect::<Vec<_>>()
-            .join("\n\n");
+        let context_str = citation::format_sources(results);
 
         let history_str = if context.is_empty() {
             String::new()
@@ -104,8 +116,8 @@ Retrieved information:
 
 Question: {}
 
-Provide a clear, accurate answer based on the retrieved information. If the information is insufficient, state that clearly."#,
-                context_str, query
+{}"#,
+                context_str, query, CITATION_INSTRUCTIONS
             )
         } else {
             format!(
@@ -119,8 +131,8 @@ Retrieved information:
 
 Question: {}
 
-Provide a clear, accurate answer based on the retrieved information. If the information is insufficient, state that clearly."#,
-                history_str, context_str, query
+{}"#,
+                history_str, context_str, query, CITATION_INSTRUCTIONS
             )
         };
 
@@ -130,6 +142,37 @@ Provide a clear, accurate answer based on the retrieved information. If the info
         let content = response.message.text_content().unwrap_or("");
         Ok(content.to_string())
     }
+
+    async fn verify_answer(
+        &self,
+        query: &str,
+        answer: &str,
+        results: &[RetrievalResult],
+        config: &ReflectorConfig,
+    ) -> Result<AnswerVerification> {
+        let mut verification = citation::verify_lexically(answer, results);
+        if !config.enable_verification || verification.sentences.iter().all(|s| !s.claim) {
+            return Ok(verification);
+        }
+
+        // 模型判断失败时退回词汇重合度结果，不影响答案输出
+        match self
+            .verify_with_llm_grounding(query, &verification, results)
+            .await
+        {
+            Ok(Some(unsupported)) => {
+                let claims = verification.sentences.iter_mut().filter(|s| s.claim);
+                for (i, sentence) in claims.enumerate() {
+                    sentence.supported =
+                        !sentence.citations.is_empty() && !unsupported.contains(&(i + 1));
+                }
+                verification.recompute();
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Answer verification failed, using lexical grounding: {}", e),
+        }
+        Ok(verification)
+    }
 }
 
 impl DefaultResultReflector {
@@ -178,6 +221,60 @@ Response:"#,
         self.parse_reflection(content, config)
     }
 
+    /// 返回模型判定为无支撑的陈述句编号（从 1 开始），无法解析时返回 None
+    async fn verify_with_llm_grounding(
+        &self,
+        query: &str,
+        verification: &AnswerVerification,
+        results: &[RetrievalResult],
+    ) -> Result<Option<Vec<usize>>> {
+        let sentences_str = verification
+            .sentences
+            .iter()
+            .filter(|s| s.claim)
+            .enumerate()
+            .map(|(i, s)| {
+                let cites = s
+                    .citations
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("S{}: {} (cites: {})", i + 1, s.text, cites)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let prompt_text = format!(
+            r#"Check whether each sentence of an answer is supported by the numbered sources it cites.
+
+Question: {}
+
+Sources:
+{}
+
+Answer sentences:
+{}
+
+A sentence is unsupported if the cited sources do not state it, or if it cites nothing.
+Respond in JSON format:
+{{
+  "unsupported": [sentence numbers]
+}}
+
+Response:"#,
+            query,
+            citation::format_sources(results),
+            sentences_str
+        );
+
+        let prompt = Message::system(prompt_text);
+        let request = openclaw_ai::ChatRequest::new("default", vec![prompt]);
+        let response = self.llm.chat(request).await?;
+        let content = response.message.text_content().unwrap_or("");
+        Ok(parse_unsupported(content))
+    }
+
     fn verify_simple(
         &self,
         results: &[RetrievalResult],
@@ -261,6 +358,27 @@ Response:"#,
     }
 }
 
+fn parse_unsupported(response: &str) -> Option<Vec<usize>> {
+    let json_str = response
+        .trim()
+        .trim_start_matches("```json")
+        .trim_end_matches("```")
+        .trim();
+
+    let value = serde_json::from_str::<serde_json::Value>(json_str).ok()?;
+    let unsupported = value.get("unsupported")?.as_array()?;
+    Some(
+        unsupported
+            .iter()
+            .filter_map(|v| {
+                v.as_u64()
+                    .map(|n| n as usize)
+                    .or_else(|| v.as_str()?.trim_start_matches('S').parse().ok())
+            })
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,6 +417,17 @@ mod tests {
         assert_eq!(config.min_confidence, 0.7);
         assert_eq!(config.max_iterations, 3);
         assert!(config.enable_verification);
+        assert_eq!(config.min_grounding, 0.6);
+    }
+
+    #[test]
+    fn test_parse_unsupported() {
+        assert_eq!(
+            parse_unsupported("```json\n{\"unsupported\": [2, \"S3\"]}\n```"),
+            Some(vec![2, 3])
+        );
+        assert_eq!(parse_unsupported("{\"unsupported\": []}"), Some(vec![]));
+        assert_eq!(parse_unsupported("all good"), None);
     }
 
     #[test]
@@ -1,24 +1,37 @@
 //! HTTP API 路由
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{Path, Query, State},
+    middleware,
     routing::{delete, get, post},
     response::{IntoResponse, Response},
+    response::sse::{Event, Sse},
     http::StatusCode,
 };
-use openclaw_agent::{Agent, AgentType, BaseAgent};
+use futures::stream::{Stream, StreamExt};
+use std::convert::Infallible;
+use openclaw_agent::session_tree::BranchInfo;
+use openclaw_agent::sessions::Session;
+use openclaw_agent::{Agent, AgentStep, AgentType, BaseAgent};
 use openclaw_browser::BrowserConfig;
-use openclaw_canvas::CanvasManager;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::agentic_rag_api::create_agentic_rag_router;
+use crate::api_auth::{
+    ApiAuthenticator, Permission, Principal, anonymous_middleware, auth_middleware,
+};
 use crate::app_context::AppContext;
 use crate::browser_api::{BrowserApiState, create_browser_router};
 use crate::canvas_api::{CanvasApiState, create_canvas_router};
 use crate::device_api::create_device_router;
+use crate::identity_api::create_identity_router;
+use crate::memory_api::create_memory_router;
+use crate::openai_api::create_openai_router;
+use crate::websocket::websocket_router;
+use crate::telemetry::{create_metrics_router, http_metrics_middleware};
 use crate::orchestrator::ServiceOrchestrator;
 use crate::sse::error_string_stream_to_sse;
 use crate::voice_service::VoiceService;
@@ -38,7 +51,7 @@ impl IntoResponse for ApiError {
 
 pub fn create_router(
     context: Arc<AppContext>,
-    canvas_manager: Option<Arc<CanvasManager>>,
+    canvas_state: Option<CanvasApiState>,
     browser_config: Option<BrowserConfig>,
 ) -> Router {
     let state = Arc::new(RwLock::new(ApiState::new(context.clone())));
@@ -52,20 +65,36 @@ pub fn create_router(
         .route("/voice/tts", post(tts_handler))
         .route("/voice/stt", post(stt_handler))
         .route("/api/channels", get(list_channels).post(create_channel))
-        .route("/api/channels/:id", delete(delete_channel))
+        .route("/api/channels/{id}", delete(delete_channel))
         .route("/api/agents", get(list_agents).post(create_agent))
-        .route("/api/agents/:id", get(get_agent))
+        .route("/api/agents/{id}", get(get_agent))
         .route("/api/sessions", get(list_sessions).post(create_session))
-        .route("/api/sessions/:id", get(get_session))
-        .route("/api/sessions/:id/close", post(close_session))
+        .route("/api/sessions/{id}", get(get_session))
+        .route("/api/sessions/{id}/close", post(close_session))
+        .route("/api/sessions/{id}/branches", get(list_session_branches))
+        .route("/api/sessions/{id}/fork", post(fork_session))
+        .route("/api/sessions/{id}/diff", get(diff_session_branches))
+        .route(
+            "/api/sessions/{id}/branches/{branch_id}/transcript",
+            get(session_transcript),
+        )
+        .route(
+            "/api/sessions/{id}/branches/{branch_id}/merge",
+            post(merge_session_branch),
+        )
         .route("/api/agent/message", post(send_agent_message))
+        .route("/api/agent/message/stream", post(stream_agent_message))
         .route("/api/presence", get(get_presence).post(set_presence))
         .with_state(state)
         .merge(create_device_router(context.unified_device_manager.clone()))
-        .merge(create_agentic_rag_router());
-
-    if let Some(canvas_mgr) = canvas_manager {
-        let canvas_state = CanvasApiState::with_manager(canvas_mgr);
+        .merge(create_agentic_rag_router())
+        .merge(create_memory_router())
+        .merge(create_identity_router())
+        .merge(create_openai_router(context.clone()))
+        .merge(create_metrics_router())
+        .merge(websocket_router());
+
+    if let Some(canvas_state) = canvas_state {
         router = router.merge(create_canvas_router(canvas_state));
     }
 
@@ -73,7 +102,18 @@ pub fn create_router(
         router = router.merge(create_browser_router(BrowserApiState::new(browser_config)));
     }
 
-    router
+    // 认证层最后添加，覆盖以上全部路由
+    let auth_settings = &context.config.api_auth;
+    if auth_settings.enabled {
+        let auth = Arc::new(ApiAuthenticator::from_settings(auth_settings));
+        router = router.layer(middleware::from_fn_with_state(auth, auth_middleware));
+    } else {
+        tracing::warn!("REST API 认证已关闭，所有接口可匿名访问");
+        router = router.layer(middleware::from_fn(anonymous_middleware));
+    }
+
+    // 指标层在最外层，被认证拒绝的请求同样计入
+    router.layer(middleware::from_fn(http_metrics_middleware))
 }
 
 #[derive(Clone)]
@@ -122,6 +162,9 @@ pub struct SessionInfo {
     pub agent_id: Option<String>,
     pub channel_id: Option<String>,
     pub state: String,
+    /// 分支树（含根节点），前端据此提供“从这里编辑重试”
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<BranchInfo>,
 }
 
 async fn health_check() -> Json<HealthResponse> {
@@ -233,7 +276,7 @@ async fn chat_handler(
         };
 
         let result = orchestrator
-            .process_agent_message(&agent_id, &request.message, &session_id)
+            .process_agent_message(&agent_id, &request.message, &session_id, None)
             .await;
 
         match result {
@@ -486,22 +529,48 @@ async fn create_agent(
     Json(agent_info)
 }
 
-async fn list_sessions(State(state): State<Arc<RwLock<ApiState>>>) -> Json<Vec<SessionInfo>> {
+/// 管理员可以访问所有会话，其他调用方只能访问自己创建的会话
+fn can_access_session(principal: &Principal, session: &Session) -> bool {
+    principal.has_permission(&Permission::SystemAdmin)
+        || session.owner() == Some(principal.id.as_str())
+}
+
+/// 校验调用方能否访问会话，不可见的会话与不存在的会话返回相同错误
+async fn authorize_session(
+    orchestrator: &ServiceOrchestrator,
+    principal: &Principal,
+    id: &str,
+) -> openclaw_agent::Result<()> {
+    match orchestrator.get_session(id).await? {
+        Some(session) if can_access_session(principal, &session) => Ok(()),
+        _ => Err(openclaw_agent::OpenClawError::Session(format!(
+            "Session {} not found",
+            id
+        ))),
+    }
+}
+
+async fn list_sessions(
+    State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
+) -> Json<Vec<SessionInfo>> {
     let state = state.read().await;
 
     if let Some(ref orchestrator) = *state.orchestrator.read().await {
         let sessions = orchestrator
-            .list_sessions(None, None)
+            .list_sessions_with_branches(None, None)
             .await
             .unwrap_or_default();
-        let session_infos: Vec<SessionInfo> = sessions
+        let session_infos = sessions
             .into_iter()
-            .map(|s| SessionInfo {
+            .filter(|(s, _)| can_access_session(&principal, s))
+            .map(|(s, branches)| SessionInfo {
                 id: s.id.to_string(),
                 name: s.name,
                 agent_id: Some(s.agent_id.to_string()),
                 channel_id: s.channel_type,
                 state: format!("{:?}", s.state),
+                branches,
             })
             .collect();
         return Json(session_infos);
@@ -512,20 +581,26 @@ async fn list_sessions(State(state): State<Arc<RwLock<ApiState>>>) -> Json<Vec<S
 
 async fn get_session(
     State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
     Path(id): Path<String>,
 ) -> Json<Option<SessionInfo>> {
     let state = state.read().await;
 
     if let Some(ref orchestrator) = *state.orchestrator.read().await
-        && let Ok(sessions) = orchestrator.list_sessions(None, None).await
-        && let Some(s) = sessions.into_iter().find(|s| s.id.to_string() == id)
+        && let Ok(Some(s)) = orchestrator.get_session(&id).await
+        && can_access_session(&principal, &s)
     {
+        let branches = orchestrator
+            .list_session_branches(&id)
+            .await
+            .unwrap_or_default();
         return Json(Some(SessionInfo {
             id: s.id.to_string(),
             name: s.name,
             agent_id: Some(s.agent_id.to_string()),
             channel_id: s.channel_type,
             state: format!("{:?}", s.state),
+            branches,
         }));
     }
 
@@ -541,6 +616,7 @@ pub struct CreateSessionRequest {
 
 async fn create_session(
     State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
     Json(input): Json<CreateSessionRequest>,
 ) -> Json<SessionInfo> {
     let state = state.read().await;
@@ -555,6 +631,7 @@ async fn create_session(
                 agent_id.clone(),
                 openclaw_core::session::SessionScope::Main,
                 input.channel_id.clone(),
+                Some(&principal.id),
             )
             .await
         {
@@ -565,6 +642,7 @@ async fn create_session(
                     agent_id: Some(session.agent_id.to_string()),
                     channel_id: session.channel_type,
                     state: format!("{:?}", session.state),
+                    branches: Vec::new(),
                 });
             }
             Err(e) => {
@@ -574,6 +652,7 @@ async fn create_session(
                     agent_id: Some(agent_id),
                     channel_id: input.channel_id,
                     state: format!("Error: {}", e),
+                    branches: Vec::new(),
                 });
             }
         }
@@ -585,17 +664,23 @@ async fn create_session(
         agent_id: input.agent_id,
         channel_id: input.channel_id,
         state: "active".to_string(),
+        branches: Vec::new(),
     })
 }
 
 async fn close_session(
     State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
     Path(id): Path<String>,
 ) -> Json<serde_json::Value> {
     let state = state.read().await;
 
     if let Some(ref orchestrator) = *state.orchestrator.read().await {
-        match orchestrator.close_session(&id).await {
+        let result = match authorize_session(orchestrator, &principal, &id).await {
+            Ok(()) => orchestrator.close_session(&id).await,
+            Err(e) => Err(e),
+        };
+        match result {
             Ok(_) => Json(serde_json::json!({ "success": true, "session_id": id })),
             Err(e) => Json(serde_json::json!({ "success": false, "error": format!("{}", e) })),
         }
@@ -604,11 +689,139 @@ async fn close_session(
     }
 }
 
+/// 会话分支接口的统一返回
+fn branch_response<T: Serialize>(result: openclaw_agent::Result<T>) -> Json<serde_json::Value> {
+    match result {
+        Ok(data) => Json(serde_json::json!({ "success": true, "data": data })),
+        Err(e) => Json(serde_json::json!({ "success": false, "error": e.to_string() })),
+    }
+}
+
+fn no_orchestrator() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "success": false, "error": "No orchestrator available" }))
+}
+
+async fn list_session_branches(
+    State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+) -> Json<serde_json::Value> {
+    let state = state.read().await;
+    let Some(ref orchestrator) = *state.orchestrator.read().await else {
+        return no_orchestrator();
+    };
+    if let Err(e) = authorize_session(orchestrator, &principal, &id).await {
+        return branch_response::<()>(Err(e));
+    }
+    branch_response(orchestrator.list_session_branches(&id).await)
+}
+
+async fn session_transcript(
+    State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
+    Path((id, branch_id)): Path<(String, String)>,
+) -> Json<serde_json::Value> {
+    let state = state.read().await;
+    let Some(ref orchestrator) = *state.orchestrator.read().await else {
+        return no_orchestrator();
+    };
+    if let Err(e) = authorize_session(orchestrator, &principal, &id).await {
+        return branch_response::<()>(Err(e));
+    }
+    branch_response(orchestrator.session_transcript(&id, &branch_id).await)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForkSessionRequest {
+    /// 从该消息（含）之后分叉
+    pub message_id: String,
+    /// 消息所在分支，省略时自动查找
+    pub branch_id: Option<String>,
+    pub name: Option<String>,
+}
+
+async fn fork_session(
+    State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+    Json(input): Json<ForkSessionRequest>,
+) -> Json<serde_json::Value> {
+    let state = state.read().await;
+    let Some(ref orchestrator) = *state.orchestrator.read().await else {
+        return no_orchestrator();
+    };
+    if let Err(e) = authorize_session(orchestrator, &principal, &id).await {
+        return branch_response::<()>(Err(e));
+    }
+    branch_response(
+        orchestrator
+            .fork_session(&id, input.branch_id.as_deref(), &input.message_id, input.name)
+            .await
+            .map(|node| BranchInfo::from(&node)),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiffBranchesQuery {
+    pub left: String,
+    pub right: String,
+}
+
+async fn diff_session_branches(
+    State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
+    Path(id): Path<String>,
+    Query(query): Query<DiffBranchesQuery>,
+) -> Json<serde_json::Value> {
+    let state = state.read().await;
+    let Some(ref orchestrator) = *state.orchestrator.read().await else {
+        return no_orchestrator();
+    };
+    if let Err(e) = authorize_session(orchestrator, &principal, &id).await {
+        return branch_response::<()>(Err(e));
+    }
+    branch_response(
+        orchestrator
+            .diff_session_branches(&id, &query.left, &query.right)
+            .await,
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeBranchRequest {
+    /// 合并到父节点的摘要，省略时使用分支消息节选
+    pub summary: Option<String>,
+}
+
+async fn merge_session_branch(
+    State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
+    Path((id, branch_id)): Path<(String, String)>,
+    input: Option<Json<MergeBranchRequest>>,
+) -> Json<serde_json::Value> {
+    let summary = input.and_then(|Json(input)| input.summary);
+    let state = state.read().await;
+    let Some(ref orchestrator) = *state.orchestrator.read().await else {
+        return no_orchestrator();
+    };
+    if let Err(e) = authorize_session(orchestrator, &principal, &id).await {
+        return branch_response::<()>(Err(e));
+    }
+    branch_response(
+        orchestrator
+            .merge_session_branch(&id, &branch_id, summary)
+            .await
+            .map(|node| BranchInfo::from(&node)),
+    )
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AgentMessageRequest {
     pub agent_id: String,
     pub message: String,
     pub session_id: Option<String>,
+    /// 继续对话的分支，省略时写入会话根节点
+    pub branch_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -617,21 +830,51 @@ pub struct AgentMessageResponse {
     pub session_id: String,
 }
 
+/// 指定了会话时校验调用方能否访问；未指定时使用一次性的新会话 ID，无需校验
+async fn authorize_message_session(
+    orchestrator: &ServiceOrchestrator,
+    principal: &Principal,
+    session_id: Option<&str>,
+) -> openclaw_agent::Result<()> {
+    match session_id {
+        Some(id) => authorize_session(orchestrator, principal, id).await,
+        None => Ok(()),
+    }
+}
+
 async fn send_agent_message(
     State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
     Json(input): Json<AgentMessageRequest>,
 ) -> Json<AgentMessageResponse> {
     let session_id = input
         .session_id
+        .clone()
         .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
     let state = state.read().await;
 
     if let Some(ref orchestrator) = *state.orchestrator.read().await {
-        match orchestrator
-            .process_agent_message(&input.agent_id, &input.message, &session_id)
-            .await
+        let result = match authorize_message_session(
+            orchestrator,
+            &principal,
+            input.session_id.as_deref(),
+        )
+        .await
         {
+            Ok(()) => {
+                orchestrator
+                    .process_agent_message(
+                        &input.agent_id,
+                        &input.message,
+                        &session_id,
+                        input.branch_id.as_deref(),
+                    )
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
             Ok(response) => Json(AgentMessageResponse {
                 message: response,
                 session_id,
@@ -649,6 +892,60 @@ async fn send_agent_message(
     }
 }
 
+/// 流式发送消息：工具循环的每一步以 `step` 事件推送，结束时以 `done` 事件返回回复
+async fn stream_agent_message(
+    State(state): State<Arc<RwLock<ApiState>>>,
+    Extension(principal): Extension<Principal>,
+    Json(input): Json<AgentMessageRequest>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let session_id = input
+        .session_id
+        .clone()
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let orchestrator = state.read().await.orchestrator.read().await.clone();
+
+    let (steps_tx, steps_rx) = tokio::sync::mpsc::unbounded_channel::<AgentStep>();
+    let done_session = session_id.clone();
+    let handle = tokio::spawn(async move {
+        let Some(orchestrator) = orchestrator else {
+            return "Orchestrator not available".to_string();
+        };
+        if let Err(e) =
+            authorize_message_session(&orchestrator, &principal, input.session_id.as_deref()).await
+        {
+            return format!("Error: {}", e);
+        }
+        orchestrator
+            .process_session_message(
+                &input.agent_id,
+                input.message,
+                &done_session,
+                input.branch_id.as_deref(),
+                Some(steps_tx),
+            )
+            .await
+            .unwrap_or_else(|e| format!("Error: {}", e))
+    });
+
+    // 发送端随处理任务结束而释放，步骤流随之结束
+    let steps = futures::stream::unfold(steps_rx, |mut rx| async move {
+        let step = rx.recv().await?;
+        let event = Event::default().event("step").json_data(&step).unwrap_or_default();
+        Some((Ok(event), rx))
+    });
+    let done = futures::stream::once(async move {
+        let message = handle
+            .await
+            .unwrap_or_else(|e| format!("Error: {}", e));
+        let response = AgentMessageResponse {
+            message,
+            session_id,
+        };
+        Ok(Event::default().event("done").json_data(&response).unwrap_or_default())
+    });
+    Sse::new(steps.chain(done))
+}
+
 async fn get_presence(State(state): State<Arc<RwLock<ApiState>>>) -> Json<serde_json::Value> {
     let state = state.read().await;
     Json(serde_json::json!({ "status": state.presence }))
@@ -0,0 +1,832 @@
+//! REST API 认证与授权
+//!
+//! 支持两种凭证：
+//! - `openclaw-rust api-key create` 签发的 API Key（`ock_` 前缀）
+//! - HS256 签名的 JWT
+//!
+//! 凭证通过 `Authorization: Bearer`、`X-API-Key` 或 `access_token` 查询参数传递
+//! （后者供无法设置请求头的 WebSocket / EventSource 使用）。凭证携带的作用域映射为
+//! 沙箱 [`Permission`]，每个凭证独立限流。
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use openclaw_core::config::ApiAuthSettings;
+use openclaw_sandbox::{MemoryRateLimiter, Permission, RateLimitConfig, RateLimiter};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+use crate::api::ApiError;
+use crate::config_adapter::DEFAULT_MEMORY_USER;
+
+/// API Key 前缀
+pub const API_KEY_PREFIX: &str = "ock_";
+
+/// JWT 密钥环境变量
+pub const JWT_SECRET_ENV: &str = "OPENCLAW_JWT_SECRET";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 认证错误
+#[derive(Debug, Error)]
+pub enum ApiAuthError {
+    #[error("缺少认证凭证")]
+    MissingCredentials,
+
+    #[error("无效的 API Key")]
+    InvalidApiKey,
+
+    #[error("凭证已过期")]
+    Expired,
+
+    #[error("无效的令牌: {0}")]
+    InvalidToken(String),
+
+    #[error("服务端未配置 JWT 密钥")]
+    JwtNotConfigured,
+
+    #[error("权限不足，需要作用域: {0}")]
+    Forbidden(String),
+
+    #[error("请求过于频繁")]
+    RateLimited,
+
+    #[error("未知作用域: {0}")]
+    UnknownScope(String),
+
+    #[error("API Key 不存在: {0}")]
+    KeyNotFound(String),
+
+    #[error("IO 错误: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("序列化错误: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+impl ApiAuthError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiAuthError::MissingCredentials
+            | ApiAuthError::InvalidApiKey
+            | ApiAuthError::Expired
+            | ApiAuthError::InvalidToken(_)
+            | ApiAuthError::JwtNotConfigured => StatusCode::UNAUTHORIZED,
+            ApiAuthError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiAuthError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for ApiAuthError {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        let mut response = (
+            status,
+            Json(ApiError {
+                error: self.to_string(),
+                code: status.as_u16(),
+            }),
+        )
+            .into_response();
+
+        if status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        } else if status == StatusCode::TOO_MANY_REQUESTS {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from_static("60"));
+        }
+        response
+    }
+}
+
+/// 解析并校验作用域列表
+pub fn parse_scopes<S: AsRef<str>>(scopes: &[S]) -> Result<HashSet<Permission>, ApiAuthError> {
+    scopes
+        .iter()
+        .map(|s| {
+            let s = s.as_ref().trim();
+            match s {
+                // `*` 等价于系统管理员
+                "*" => Ok(Permission::SystemAdmin),
+                _ => Permission::from_scope(s)
+                    .ok_or_else(|| ApiAuthError::UnknownScope(s.to_string())),
+            }
+        })
+        .collect()
+}
+
+/// API Key 记录
+///
+/// 只保存密钥的 SHA-256 摘要，明文仅在创建时返回一次。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyRecord {
+    pub id: String,
+    pub name: String,
+    pub key_hash: String,
+    /// 密钥开头部分，便于辨认
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_per_minute: Option<u32>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub revoked: bool,
+}
+
+impl ApiKeyRecord {
+    /// 是否已过期
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|t| t <= Utc::now())
+    }
+}
+
+fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// API Key 存储
+#[derive(Debug, Clone)]
+pub struct ApiKeyStore {
+    path: PathBuf,
+    keys: Vec<ApiKeyRecord>,
+}
+
+impl ApiKeyStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// 默认存储路径 (~/.openclaw-rust/api_keys.json)
+    pub fn default_path() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".openclaw-rust")
+            .join("api_keys.json")
+    }
+
+    /// 加载存储，文件不存在时返回空存储
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, ApiAuthError> {
+        let path = path.into();
+        if !path.exists() {
+            return Ok(Self::new(path));
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let keys = serde_json::from_str(&content)?;
+        Ok(Self { path, keys })
+    }
+
+    /// 保存存储，Unix 下文件权限为 0600
+    pub fn save(&self) -> Result<(), ApiAuthError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.keys)?)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 创建 API Key，返回记录与明文密钥
+    pub fn create(
+        &mut self,
+        name: impl Into<String>,
+        scopes: Vec<String>,
+        rate_limit_per_minute: Option<u32>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(ApiKeyRecord, String), ApiAuthError> {
+        parse_scopes(&scopes)?;
+
+        let mut bytes = [0u8; 32];
+        rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut bytes);
+        let secret = format!("{}{}", API_KEY_PREFIX, hex::encode(bytes));
+
+        let record = ApiKeyRecord {
+            id: format!("key_{}", &uuid::Uuid::new_v4().simple().to_string()[..12]),
+            name: name.into(),
+            key_hash: hash_secret(&secret),
+            prefix: secret[..API_KEY_PREFIX.len() + 6].to_string(),
+            scopes,
+            rate_limit_per_minute,
+            created_at: Utc::now(),
+            expires_at,
+            revoked: false,
+        };
+        self.keys.push(record.clone());
+        Ok((record, secret))
+    }
+
+    /// 吊销 API Key
+    pub fn revoke(&mut self, id: &str) -> Result<(), ApiAuthError> {
+        let record = self
+            .keys
+            .iter_mut()
+            .find(|k| k.id == id)
+            .ok_or_else(|| ApiAuthError::KeyNotFound(id.to_string()))?;
+        record.revoked = true;
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ApiKeyRecord> {
+        self.keys.iter().find(|k| k.id == id)
+    }
+
+    pub fn list(&self) -> &[ApiKeyRecord] {
+        &self.keys
+    }
+
+    /// 按明文密钥查找
+    pub fn find_by_secret(&self, secret: &str) -> Option<&ApiKeyRecord> {
+        let hash = hash_secret(secret);
+        self.keys.iter().find(|k| k.key_hash == hash)
+    }
+}
+
+/// JWT 声明
+///
+/// `scope` 为空格分隔的作用域列表，与 OAuth 2.0 约定一致。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtClaims {
+    pub sub: String,
+    #[serde(default)]
+    pub scope: String,
+    pub exp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    /// 每分钟请求上限
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+}
+
+impl JwtClaims {
+    pub fn new(subject: impl Into<String>, scopes: &[String], ttl: chrono::Duration) -> Self {
+        let now = Utc::now();
+        Self {
+            sub: subject.into(),
+            scope: scopes.join(" "),
+            exp: (now + ttl).timestamp(),
+            iat: Some(now.timestamp()),
+            rate_limit: None,
+        }
+    }
+}
+
+fn jwt_mac(secret: &str, signing_input: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC 接受任意长度密钥");
+    mac.update(signing_input.as_bytes());
+    mac
+}
+
+/// 签发 HS256 JWT
+pub fn issue_jwt(secret: &str, claims: &JwtClaims) -> Result<String, ApiAuthError> {
+    let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims)?);
+    let signing_input = format!("{}.{}", header, payload);
+    let signature = URL_SAFE_NO_PAD.encode(jwt_mac(secret, &signing_input).finalize().into_bytes());
+    Ok(format!("{}.{}", signing_input, signature))
+}
+
+/// 校验 HS256 JWT 并返回声明
+pub fn verify_jwt(secret: &str, token: &str) -> Result<JwtClaims, ApiAuthError> {
+    let invalid = |msg: &str| ApiAuthError::InvalidToken(msg.to_string());
+
+    let mut parts = token.split('.');
+    let (Some(header), Some(payload), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid("格式错误"));
+    };
+
+    let header: serde_json::Value = URL_SAFE_NO_PAD
+        .decode(header)
+        .ok()
+        .and_then(|h| serde_json::from_slice(&h).ok())
+        .ok_or_else(|| invalid("头部无法解析"))?;
+    // 只接受 HS256，防止 alg=none 等降级
+    if header["alg"] != "HS256" {
+        return Err(invalid("不支持的签名算法"));
+    }
+
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| invalid("签名编码错误"))?;
+    let signing_input = &token[..token.rfind('.').unwrap_or(0)];
+    jwt_mac(secret, signing_input)
+        .verify_slice(&signature)
+        .map_err(|_| invalid("签名不匹配"))?;
+
+    let claims: JwtClaims = URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|p| serde_json::from_slice(&p).ok())
+        .ok_or_else(|| invalid("声明无法解析"))?;
+    if claims.exp <= Utc::now().timestamp() {
+        return Err(ApiAuthError::Expired);
+    }
+    Ok(claims)
+}
+
+/// 已认证的调用方，认证通过后写入请求扩展
+#[derive(Debug, Clone)]
+pub struct Principal {
+    /// 限流键：`key:<id>` 或 `jwt:<sub>`
+    pub id: String,
+    pub name: String,
+    pub permissions: HashSet<Permission>,
+    pub rate_limit_per_minute: Option<u32>,
+}
+
+impl Principal {
+    /// 认证关闭时使用的匿名调用方，拥有全部权限
+    pub fn anonymous() -> Self {
+        Self {
+            id: DEFAULT_MEMORY_USER.to_string(),
+            name: "anonymous".to_string(),
+            permissions: HashSet::from([Permission::SystemAdmin]),
+            rate_limit_per_minute: None,
+        }
+    }
+
+    /// 是否为认证关闭时的匿名调用方
+    pub fn is_anonymous(&self) -> bool {
+        self.id == DEFAULT_MEMORY_USER
+    }
+
+    /// `SystemAdmin` 拥有全部权限
+    pub fn has_permission(&self, permission: &Permission) -> bool {
+        self.permissions.contains(&Permission::SystemAdmin) || self.permissions.contains(permission)
+    }
+}
+
+/// 路由所需权限，返回 None 表示公开接口
+///
+/// 未登记的路由要求 `system:admin`，新增接口默认不对外开放。
+pub fn required_permission(method: &Method, path: &str) -> Option<Permission> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let read = *method == Method::GET || *method == Method::HEAD;
+
+    let permission = match segments.as_slice() {
+        ["health"] => return None,
+        ["metrics"] => Permission::MetricsView,
+        ["chat"]
+        | ["chat", "stream"]
+        | ["models"]
+        | ["api", "agent", "message"]
+        | ["api", "agent", "message", "stream"]
+        | ["api", "sessions", ..]
+        | ["api", "presence"]
+        | ["api", "agentic-rag", ..]
+        | ["ws"]
+        | ["v1", ..] => Permission::AgentChat,
+        ["api", "agents", ..] if read => Permission::AgentChat,
+        ["api", "agents", ..] | ["stats"] => Permission::AgentManage,
+        ["api", "channels", ..] => Permission::ChannelManage,
+        ["api", "memory", ..] if read => Permission::MemoryRead,
+        ["api", "memory", ..] => Permission::MemoryWrite,
+        ["voice", ..] => Permission::VoiceUse,
+        ["device", "camera", ..] => Permission::DeviceCamera,
+        ["device", "screen", ..] => Permission::DeviceScreen,
+        ["device", ..] => Permission::DeviceView,
+        ["page", _, _, "screenshot" | "pdf"] => Permission::BrowserScreenshot,
+        ["page", _, _, "goto" | "reload" | "back" | "forward"] => Permission::BrowserNavigate,
+        ["browser", ..] | ["page", ..] => Permission::BrowserControl,
+        ["canvas"] if !read => Permission::CanvasCreate,
+        ["canvas", _] if *method == Method::DELETE => Permission::CanvasDelete,
+        ["canvas", _, "ws"] => Permission::CanvasEdit,
+        ["canvas", ..] if read => Permission::CanvasView,
+        ["canvas", ..] => Permission::CanvasEdit,
+        _ => Permission::SystemAdmin,
+    };
+    Some(permission)
+}
+
+/// 从请求中提取凭证
+fn extract_credential(headers: &HeaderMap, query: Option<&str>) -> Option<String> {
+    if let Some(value) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        && let Some(token) = value
+            .strip_prefix("Bearer ")
+            .or_else(|| value.strip_prefix("bearer "))
+    {
+        return Some(token.trim().to_string());
+    }
+
+    if let Some(key) = headers.get("x-api-key").and_then(|v| v.to_str().ok()) {
+        return Some(key.trim().to_string());
+    }
+
+    query?.split('&').find_map(|pair| {
+        pair.strip_prefix("access_token=")
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+    })
+}
+
+/// 认证器
+///
+/// API Key 存储文件变化后自动重新加载，CLI 新建或吊销的密钥无需重启服务即可生效。
+pub struct ApiAuthenticator {
+    store: RwLock<ApiKeyStore>,
+    store_mtime: RwLock<Option<SystemTime>>,
+    jwt_secret: Option<String>,
+    default_rate_limit: u32,
+    rate_limiter: MemoryRateLimiter,
+    /// 各调用方令牌桶对应的每分钟请求数，密钥限额变化后重建令牌桶
+    limited: RwLock<HashMap<String, u32>>,
+}
+
+impl ApiAuthenticator {
+    pub fn new(store: ApiKeyStore, jwt_secret: Option<String>, default_rate_limit: u32) -> Self {
+        let mtime = file_mtime(store.path());
+        Self {
+            store: RwLock::new(store),
+            store_mtime: RwLock::new(mtime),
+            jwt_secret: jwt_secret.filter(|s| !s.is_empty()),
+            default_rate_limit,
+            rate_limiter: MemoryRateLimiter::new(),
+            limited: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 按配置创建，存储加载失败时以空存储启动（拒绝所有 API Key）
+    pub fn from_settings(settings: &ApiAuthSettings) -> Self {
+        let path = settings
+            .keys_path
+            .clone()
+            .unwrap_or_else(ApiKeyStore::default_path);
+        let store = ApiKeyStore::load(&path).unwrap_or_else(|e| {
+            tracing::error!("加载 API Key 存储 {:?} 失败: {}", path, e);
+            ApiKeyStore::new(&path)
+        });
+        let jwt_secret = settings
+            .jwt_secret
+            .clone()
+            .or_else(|| std::env::var(JWT_SECRET_ENV).ok());
+
+        if store.list().is_empty() && jwt_secret.is_none() {
+            tracing::warn!(
+                "REST API 认证已启用但没有可用凭证，请运行 `openclaw-rust api-key create` 创建 API Key"
+            );
+        }
+        Self::new(store, jwt_secret, settings.default_rate_limit_per_minute)
+    }
+
+    async fn reload_if_changed(&self) {
+        let path = self.store.read().await.path().to_path_buf();
+        let mtime = file_mtime(&path);
+        if *self.store_mtime.read().await == mtime {
+            return;
+        }
+        match ApiKeyStore::load(&path) {
+            Ok(store) => {
+                *self.store.write().await = store;
+                *self.store_mtime.write().await = mtime;
+                tracing::info!("API Key 存储已重新加载");
+            }
+            Err(e) => tracing::warn!("重新加载 API Key 存储失败: {}", e),
+        }
+    }
+
+    /// 校验凭证并返回调用方
+    pub async fn authenticate(&self, credential: &str) -> Result<Principal, ApiAuthError> {
+        if credential.starts_with(API_KEY_PREFIX) {
+            self.reload_if_changed().await;
+            let store = self.store.read().await;
+            let record = store
+                .find_by_secret(credential)
+                .filter(|k| !k.revoked)
+                .ok_or(ApiAuthError::InvalidApiKey)?;
+            if record.is_expired() {
+                return Err(ApiAuthError::Expired);
+            }
+            return Ok(Principal {
+                id: format!("key:{}", record.id),
+                name: record.name.clone(),
+                permissions: parse_scopes(&record.scopes)?,
+                rate_limit_per_minute: record.rate_limit_per_minute,
+            });
+        }
+
+        let secret = self
+            .jwt_secret
+            .as_deref()
+            .ok_or(ApiAuthError::JwtNotConfigured)?;
+        let claims = verify_jwt(secret, credential)?;
+        let scopes: Vec<&str> = claims.scope.split_whitespace().collect();
+        Ok(Principal {
+            id: format!("jwt:{}", claims.sub),
+            name: claims.sub,
+            permissions: parse_scopes(&scopes)?,
+            rate_limit_per_minute: claims.rate_limit,
+        })
+    }
+
+    /// 按调用方限流
+    pub async fn check_rate_limit(&self, principal: &Principal) -> Result<(), ApiAuthError> {
+        let rpm = principal
+            .rate_limit_per_minute
+            .unwrap_or(self.default_rate_limit);
+        // 0 表示不限流
+        if rpm == 0 {
+            return Ok(());
+        }
+
+        if self.limited.read().await.get(&principal.id) != Some(&rpm) {
+            self.rate_limiter
+                .configure_tool_limits(&principal.id, RateLimitConfig::per_minute(rpm))
+                .await
+                .map_err(|_| ApiAuthError::RateLimited)?;
+            self.limited.write().await.insert(principal.id.clone(), rpm);
+        }
+
+        self.rate_limiter
+            .check_limit(&principal.id)
+            .await
+            .map_err(|_| ApiAuthError::RateLimited)
+    }
+
+    /// 认证、授权并限流一个请求
+    pub async fn authorize_request(
+        &self,
+        method: &Method,
+        path: &str,
+        headers: &HeaderMap,
+        query: Option<&str>,
+    ) -> Result<Option<Principal>, ApiAuthError> {
+        let Some(required) = required_permission(method, path) else {
+            return Ok(None);
+        };
+
+        let credential =
+            extract_credential(headers, query).ok_or(ApiAuthError::MissingCredentials)?;
+        let principal = self.authenticate(&credential).await?;
+        if !principal.has_permission(&required) {
+            return Err(ApiAuthError::Forbidden(required.scope()));
+        }
+        self.check_rate_limit(&principal).await?;
+        Ok(Some(principal))
+    }
+}
+
+fn file_mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// 认证中间件
+///
+/// 认证通过后将 [`Principal`] 写入请求扩展，处理函数可通过 `Extension<Principal>` 获取。
+pub async fn auth_middleware(
+    State(auth): State<Arc<ApiAuthenticator>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    // CORS 预检请求不携带凭证
+    if request.method() == Method::OPTIONS {
+        return next.run(request).await;
+    }
+
+    let result = auth
+        .authorize_request(
+            request.method(),
+            request.uri().path(),
+            request.headers(),
+            request.uri().query(),
+        )
+        .await;
+
+    match result {
+        Ok(principal) => {
+            if let Some(principal) = principal {
+                request.extensions_mut().insert(principal);
+            }
+            next.run(request).await
+        }
+        Err(e) => {
+            tracing::debug!(
+                "拒绝请求 {} {}: {}",
+                request.method(),
+                request.uri().path(),
+                e
+            );
+            e.into_response()
+        }
+    }
+}
+
+/// 认证关闭时注入匿名调用方
+pub async fn anonymous_middleware(mut request: Request, next: Next) -> Response {
+    request.extensions_mut().insert(Principal::anonymous());
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authenticator(dir: &tempfile::TempDir) -> (ApiAuthenticator, String) {
+        let mut store = ApiKeyStore::new(dir.path().join("api_keys.json"));
+        let (_, secret) = store
+            .create("ci", vec!["agent:chat".to_string()], Some(2), None)
+            .unwrap();
+        store.save().unwrap();
+        (
+            ApiAuthenticator::new(store, Some("test-secret".to_string()), 60),
+            secret,
+        )
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_required_permission() {
+        assert_eq!(required_permission(&Method::GET, "/health"), None);
+        assert_eq!(
+            required_permission(&Method::POST, "/device/camera/cam0/capture"),
+            Some(Permission::DeviceCamera)
+        );
+        assert_eq!(
+            required_permission(&Method::POST, "/page/b1/p1/goto"),
+            Some(Permission::BrowserNavigate)
+        );
+        assert_eq!(
+            required_permission(&Method::DELETE, "/canvas/c1"),
+            Some(Permission::CanvasDelete)
+        );
+        assert_eq!(
+            required_permission(&Method::POST, "/v1/chat/completions"),
+            Some(Permission::AgentChat)
+        );
+        assert_eq!(
+            required_permission(&Method::POST, "/api/agent/message/stream"),
+            Some(Permission::AgentChat)
+        );
+        assert_eq!(
+            required_permission(&Method::GET, "/ws"),
+            Some(Permission::AgentChat)
+        );
+        assert_eq!(
+            required_permission(&Method::POST, "/api/memory/ingest"),
+            Some(Permission::MemoryWrite)
+        );
+        assert_eq!(
+            required_permission(&Method::GET, "/unknown"),
+            Some(Permission::SystemAdmin)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_key_scopes_and_rate_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let (auth, secret) = authenticator(&dir);
+
+        let principal = auth
+            .authorize_request(&Method::POST, "/chat", &bearer(&secret), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(principal.has_permission(&Permission::AgentChat));
+
+        let err = auth
+            .authorize_request(&Method::POST, "/browser", &bearer(&secret), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiAuthError::Forbidden(ref s) if s == "browser:control"));
+
+        auth.authorize_request(&Method::POST, "/chat", &bearer(&secret), None)
+            .await
+            .unwrap();
+        let err = auth
+            .authorize_request(&Method::POST, "/chat", &bearer(&secret), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiAuthError::RateLimited));
+
+        let err = auth
+            .authorize_request(&Method::POST, "/chat", &HeaderMap::new(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiAuthError::MissingCredentials));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_follows_updated_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let (auth, _) = authenticator(&dir);
+        let mut principal = Principal {
+            id: "key:k1".to_string(),
+            name: "k1".to_string(),
+            permissions: HashSet::new(),
+            rate_limit_per_minute: Some(1),
+        };
+
+        auth.check_rate_limit(&principal).await.unwrap();
+        assert!(matches!(
+            auth.check_rate_limit(&principal).await,
+            Err(ApiAuthError::RateLimited)
+        ));
+
+        // 密钥限额调高后按新限额重建令牌桶
+        principal.rate_limit_per_minute = Some(3);
+        for _ in 0..3 {
+            auth.check_rate_limit(&principal).await.unwrap();
+        }
+        assert!(auth.check_rate_limit(&principal).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_revoked_key_reloaded_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let (auth, secret) = authenticator(&dir);
+        assert!(auth.authenticate(&secret).await.is_ok());
+
+        // 模拟 CLI 在服务运行期间吊销密钥
+        let path = dir.path().join("api_keys.json");
+        let mut store = ApiKeyStore::load(&path).unwrap();
+        let id = store.list()[0].id.clone();
+        store.revoke(&id).unwrap();
+        store.save().unwrap();
+        *auth.store_mtime.write().await = None;
+
+        assert!(matches!(
+            auth.authenticate(&secret).await,
+            Err(ApiAuthError::InvalidApiKey)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_jwt() {
+        let dir = tempfile::tempdir().unwrap();
+        let (auth, _) = authenticator(&dir);
+
+        let claims = JwtClaims::new(
+            "alice",
+            &["device:camera".to_string()],
+            chrono::Duration::hours(1),
+        );
+        let token = issue_jwt("test-secret", &claims).unwrap();
+        let principal = auth
+            .authorize_request(
+                &Method::POST,
+                "/device/camera/cam0/capture",
+                &HeaderMap::new(),
+                Some(&format!("access_token={}", token)),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(principal.id, "jwt:alice");
+
+        let forged = issue_jwt("other-secret", &claims).unwrap();
+        assert!(matches!(
+            auth.authenticate(&forged).await,
+            Err(ApiAuthError::InvalidToken(_))
+        ));
+
+        let expired = JwtClaims::new("alice", &[], chrono::Duration::seconds(-10));
+        let token = issue_jwt("test-secret", &expired).unwrap();
+        assert!(matches!(
+            verify_jwt("test-secret", &token),
+            Err(ApiAuthError::Expired)
+        ));
+    }
+}
@@ -4,6 +4,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use openclaw_ai::AIProvider;
+use openclaw_ai::providers::ReloadableProvider;
 use openclaw_core::Config;
 use openclaw_device::UnifiedDeviceManager;
 use openclaw_memory::factory::MemoryBackend;
@@ -31,6 +32,8 @@ pub struct AppContext {
     pub voice_service: Arc<VoiceService>,
     pub vector_store_registry: Arc<VectorStoreRegistry>,
     pub acp_service: Option<Arc<AcpService>>,
+    /// 与 `ai_provider` 指向同一实例，配置热加载时用于替换提供商
+    pub provider_handle: Option<Arc<ReloadableProvider>>,
 }
 
 impl AppContext {
@@ -61,9 +64,17 @@ impl AppContext {
             voice_service,
             vector_store_registry,
             acp_service,
+            provider_handle: None,
         }
     }
 
+    /// 使用可热替换的提供商作为 `ai_provider`
+    pub fn with_reloadable_provider(mut self, provider: Arc<ReloadableProvider>) -> Self {
+        self.ai_provider = provider.clone();
+        self.provider_handle = Some(provider);
+        self
+    }
+
     pub async fn get_agent(&self, name: &str) -> Option<Arc<dyn openclaw_agent::Agent>> {
         let orchestrator = self.orchestrator.read().await;
         orchestrator.as_ref()?.get_agent(name).await
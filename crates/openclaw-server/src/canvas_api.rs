@@ -1,23 +1,27 @@
 //! 画布 API 路由
 
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{
-        Path, State,
+        Path, Query, State,
         ws::{Message, WebSocket, WebSocketUpgrade},
     },
-    response::IntoResponse,
+    http::header,
+    response::{IntoResponse, Response},
     routing::{delete, get, post, put},
 };
 use futures::{SinkExt, StreamExt};
 use openclaw_canvas::{
-    CanvasId, CanvasManager, CanvasOps, CanvasState, CollabEvent, CollabManager, DrawAction,
-    Element, ElementUpdate, UserColorGenerator, UserCursor, UserInfo, WsMessage,
+    CanvasId, CanvasManager, CanvasOps, CanvasState, CanvasVersion, CollabEvent, CollabManager,
+    CollabSession, DrawAction, Element, ElementUpdate, ExportFormat, ExportOptions, OpKind,
+    UserColorGenerator, UserCursor, UserInfo, WsMessage,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{debug, info};
 
+use crate::api_auth::Principal;
+
 /// 画布 API 状态
 #[derive(Clone)]
 pub struct CanvasApiState {
@@ -36,9 +40,17 @@ impl CanvasApiState {
     }
 
     pub fn with_manager(manager: Arc<CanvasManager>) -> Self {
+        Self::with_services(manager, Arc::new(CollabManager::new()))
+    }
+
+    /// 与 Agent 工具共享协作会话，使工具的编辑经由 CRDT 文档同步给在线用户
+    pub fn with_services(
+        canvas_manager: Arc<CanvasManager>,
+        collab_manager: Arc<CollabManager>,
+    ) -> Self {
         Self {
-            canvas_manager: manager,
-            collab_manager: Arc::new(CollabManager::new()),
+            canvas_manager,
+            collab_manager,
             color_generator: Arc::new(UserColorGenerator::new()),
         }
     }
@@ -61,6 +73,13 @@ pub fn create_canvas_router(state: CanvasApiState) -> Router {
         .route("/canvas/{id}/elements/{element_id}", put(update_element))
         .route("/canvas/{id}/elements/{element_id}", delete(delete_element))
         .route("/canvas/{id}/clear", post(clear_canvas))
+        .route("/canvas/{id}/versions", get(list_versions))
+        .route("/canvas/{id}/versions", post(create_version))
+        .route(
+            "/canvas/{id}/versions/{version}/restore",
+            post(restore_version),
+        )
+        .route("/canvas/{id}/export", get(export_canvas))
         .route("/canvas/{id}/ws", get(canvas_websocket))
         .with_state(state)
 }
@@ -148,7 +167,27 @@ async fn add_element(
         .await
         .ok_or("画布不存在")?;
 
-    let element_id = CanvasOps::add_element(&canvas, req.element)
+    let element_id = if let Some(session) = state.collab_manager.get_session(&canvas_id).await {
+        let element_id = req.element.id.clone();
+        session
+            .edit(
+                OpKind::Insert {
+                    element: req.element,
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        write_back_session(&state, &session, &canvas_id).await?;
+        element_id
+    } else {
+        CanvasOps::add_element(&canvas, req.element)
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    state
+        .canvas_manager
+        .commit(&canvas_id)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -167,7 +206,26 @@ async fn update_element(
         .await
         .ok_or("画布不存在")?;
 
-    CanvasOps::update_element(&canvas, &element_id, updates)
+    if let Some(session) = state.collab_manager.get_session(&canvas_id).await {
+        session
+            .edit(
+                OpKind::Update {
+                    element_id,
+                    updates,
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        write_back_session(&state, &session, &canvas_id).await?;
+    } else {
+        CanvasOps::update_element(&canvas, &element_id, updates)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    state
+        .canvas_manager
+        .commit(&canvas_id)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -185,7 +243,20 @@ async fn delete_element(
         .await
         .ok_or("画布不存在")?;
 
-    CanvasOps::delete_element(&canvas, &element_id)
+    if let Some(session) = state.collab_manager.get_session(&canvas_id).await {
+        session
+            .edit(OpKind::Delete { element_id }, None)
+            .await
+            .map_err(|e| e.to_string())?;
+        write_back_session(&state, &session, &canvas_id).await?;
+    } else {
+        CanvasOps::delete_element(&canvas, &element_id)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    state
+        .canvas_manager
+        .commit(&canvas_id)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -203,24 +274,147 @@ async fn clear_canvas(
         .await
         .ok_or("画布不存在")?;
 
-    CanvasOps::clear_canvas(&canvas)
+    if let Some(session) = state.collab_manager.get_session(&canvas_id).await {
+        session.clear(None).await;
+        write_back_session(&state, &session, &canvas_id).await?;
+    } else {
+        CanvasOps::clear_canvas(&canvas)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    state
+        .canvas_manager
+        .commit(&canvas_id)
         .await
         .map_err(|e| e.to_string())?;
 
     Ok(Json(serde_json::json!({"success": true})))
 }
 
+/// 列出画布版本
+async fn list_versions(
+    State(state): State<CanvasApiState>,
+    Path(canvas_id): Path<CanvasId>,
+) -> Result<Json<Vec<CanvasVersion>>, String> {
+    state
+        .canvas_manager
+        .list_versions(&canvas_id)
+        .await
+        .map(Json)
+        .map_err(|e| e.to_string())
+}
+
+/// 创建版本请求
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateVersionRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// 创建命名快照
+async fn create_version(
+    State(state): State<CanvasApiState>,
+    Path(canvas_id): Path<CanvasId>,
+    Json(req): Json<CreateVersionRequest>,
+) -> Result<Json<CanvasVersion>, String> {
+    state
+        .canvas_manager
+        .create_snapshot(&canvas_id, req.name)
+        .await
+        .map(Json)
+        .map_err(|e| e.to_string())
+}
+
+/// 回滚到指定版本
+async fn restore_version(
+    State(state): State<CanvasApiState>,
+    Path((canvas_id, version)): Path<(CanvasId, u64)>,
+) -> Result<Json<CanvasState>, String> {
+    let restored = state
+        .canvas_manager
+        .restore_snapshot(&canvas_id, version)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 协作中的客户端通过 CRDT 操作收到回滚结果
+    if let Some(session) = state.collab_manager.get_session(&canvas_id).await {
+        session.reset_to(&restored, None).await;
+    }
+
+    Ok(Json(restored))
+}
+
+/// 导出查询参数
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+    pub scale: Option<f64>,
+    pub background: Option<bool>,
+}
+
+/// 导出画布为 SVG / PNG / PDF
+async fn export_canvas(
+    State(state): State<CanvasApiState>,
+    Path(canvas_id): Path<CanvasId>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, String> {
+    let defaults = ExportOptions::default();
+    let options = ExportOptions {
+        scale: query.scale.unwrap_or(defaults.scale),
+        background: query.background.unwrap_or(defaults.background),
+    };
+    let output = state
+        .canvas_manager
+        .export(&canvas_id, query.format, &options)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let disposition = format!(
+        "inline; filename=\"{}.{}\"",
+        canvas_id,
+        output.format.extension()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, output.mime_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        output.data,
+    )
+        .into_response())
+}
+
+/// WebSocket 连接参数
+#[derive(Debug, Default, Deserialize)]
+pub struct CanvasWsQuery {
+    /// 客户端副本 ID，重连时沿用以便同步离线操作
+    pub client_id: Option<String>,
+}
+
 /// WebSocket 连接
 async fn canvas_websocket(
     State(state): State<CanvasApiState>,
+    Extension(principal): Extension<Principal>,
     Path(canvas_id): Path<CanvasId>,
+    Query(query): Query<CanvasWsQuery>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_canvas_ws(socket, state, canvas_id))
+    ws.on_upgrade(move |socket| {
+        handle_canvas_ws(socket, state, canvas_id, principal, query.client_id)
+    })
 }
 
 /// 处理 WebSocket 连接
-async fn handle_canvas_ws(socket: WebSocket, state: CanvasApiState, canvas_id: CanvasId) {
+///
+/// 用户身份取自认证主体，`client_id` 只用作副本 ID；认证关闭时每个副本视为独立用户。
+async fn handle_canvas_ws(
+    socket: WebSocket,
+    state: CanvasApiState,
+    canvas_id: CanvasId,
+    principal: Principal,
+    client_id: Option<String>,
+) {
     let (mut tx, mut rx) = socket.split();
 
     // 获取或创建协作会话
@@ -228,12 +422,27 @@ async fn handle_canvas_ws(socket: WebSocket, state: CanvasApiState, canvas_id: C
         .collab_manager
         .get_or_create_session(canvas_id.clone())
         .await;
+    if let Ok(canvas_state) = state.canvas_manager.get_canvas_state(&canvas_id).await {
+        session.seed(&canvas_state).await;
+    }
 
     // 生成用户信息
-    let user_id = uuid::Uuid::new_v4().to_string();
+    let client_id = client_id
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let anonymous = principal.is_anonymous();
+    let user_id = if anonymous {
+        client_id.clone()
+    } else {
+        principal.id.clone()
+    };
     let user = UserInfo {
         id: user_id.clone(),
-        name: format!("User-{}", &user_id[..4]),
+        name: if anonymous {
+            format!("User-{}", user_id.chars().take(4).collect::<String>())
+        } else {
+            principal.name.clone()
+        },
         color: state.color_generator.next(),
         avatar_url: None,
     };
@@ -268,8 +477,16 @@ async fn handle_canvas_ws(socket: WebSocket, state: CanvasApiState, canvas_id: C
             msg = rx.next() => {
                 match msg {
                     Some(Ok(Message::Text(text))) => {
-                        if let Ok(ws_msg) = serde_json::from_str::<WsMessage>(&text) {
-                            handle_ws_message(&state, &session, &canvas_id, &user_id, ws_msg).await;
+                        if let Ok(ws_msg) = serde_json::from_str::<WsMessage>(&text)
+                            && let Some(reply) =
+                                handle_ws_message(
+                                    &state, &session, &canvas_id, &client_id, &user_id, ws_msg,
+                                )
+                                .await
+                            && let Ok(msg) = serde_json::to_string(&reply)
+                            && let Err(e) = tx.send(Message::Text(msg.into())).await
+                        {
+                            tracing::warn!("Failed to send reply to WebSocket: {}", e);
                         }
                     }
                     Some(Ok(Message::Close(_))) | None => {
@@ -296,14 +513,15 @@ async fn handle_canvas_ws(socket: WebSocket, state: CanvasApiState, canvas_id: C
     info!("用户 {} 断开画布 {}", user_id, canvas_id);
 }
 
-/// 处理 WebSocket 消息
+/// 处理 WebSocket 消息，返回需要单独回复给该客户端的消息
 async fn handle_ws_message(
     state: &CanvasApiState,
-    session: &Arc<openclaw_canvas::CollabSession>,
+    session: &Arc<CollabSession>,
     canvas_id: &CanvasId,
+    client_id: &str,
     user_id: &str,
     msg: WsMessage,
-) {
+) -> Option<WsMessage> {
     match msg {
         WsMessage::CursorMove { position, tool } => {
             let cursor = UserCursor {
@@ -316,22 +534,49 @@ async fn handle_ws_message(
             session.update_cursor(cursor).await;
         }
         WsMessage::DrawAction { action } => {
-            if let Some(canvas) = state.canvas_manager.get_canvas(canvas_id).await {
-                // 应用绘图操作
-                match &action {
-                    DrawAction::AddElement { element } => {
-                        if let Err(e) = CanvasOps::add_element(&canvas, element.clone()).await {
-                            tracing::warn!("Failed to add canvas element: {}", e);
-                        }
-                    }
-                    DrawAction::DeleteElement { element } => {
-                        if let Err(e) = CanvasOps::delete_element(&canvas, &element.id).await {
-                            tracing::warn!("Failed to delete canvas element: {}", e);
-                        }
-                    }
-                    _ => {}
+            // 旧协议的绘图操作转换为 CRDT 操作，撤销记录归属于该用户
+            let mut kinds = Vec::new();
+            draw_action_to_ops(action, &mut kinds);
+            for kind in kinds {
+                if let Err(e) = session.edit(kind, Some(user_id)).await {
+                    tracing::warn!("Failed to apply draw action: {}", e);
                 }
             }
+            write_back_logged(state, session, canvas_id).await;
+        }
+        WsMessage::SyncStep1 { state_vector } => {
+            if let Some(ops) = session.diff(&state_vector).await {
+                return Some(WsMessage::SyncStep2 {
+                    ops,
+                    state_vector: session.state_vector().await,
+                });
+            }
+            // 缺少的操作已被压缩，改为全量同步
+            let canvas_state = state
+                .canvas_manager
+                .get_canvas_state(canvas_id)
+                .await
+                .ok()?;
+            return Some(WsMessage::SyncResponse {
+                canvas_state,
+                users: session.get_users().await,
+                cursors: session.get_cursors().await,
+            });
+        }
+        WsMessage::Update { ops } => {
+            if !session.apply_ops(client_id, user_id, ops).await.is_empty() {
+                write_back_logged(state, session, canvas_id).await;
+            }
+        }
+        WsMessage::Undo => {
+            if !session.undo(user_id).await.is_empty() {
+                write_back_logged(state, session, canvas_id).await;
+            }
+        }
+        WsMessage::Redo => {
+            if !session.redo(user_id).await.is_empty() {
+                write_back_logged(state, session, canvas_id).await;
+            }
         }
         WsMessage::ViewportChange { viewport } => {
             // 可以广播视口变化给其他用户
@@ -339,6 +584,68 @@ async fn handle_ws_message(
         }
         _ => {}
     }
+    None
+}
+
+/// 将旧协议绘图操作展开为 CRDT 操作
+fn draw_action_to_ops(action: DrawAction, ops: &mut Vec<OpKind>) {
+    match action {
+        DrawAction::AddElement { element } => ops.push(OpKind::Insert { element }),
+        DrawAction::UpdateElement {
+            element_id,
+            new_state,
+            ..
+        } => ops.push(OpKind::Update {
+            element_id,
+            updates: ElementUpdate {
+                shape: Some(new_state.shape),
+                layer: Some(new_state.layer),
+                locked: Some(new_state.locked),
+                visible: Some(new_state.visible),
+                opacity: Some(new_state.opacity),
+                transform: Some(new_state.transform),
+            },
+        }),
+        DrawAction::DeleteElement { element } => ops.push(OpKind::Delete {
+            element_id: element.id,
+        }),
+        DrawAction::Batch { actions } => {
+            for action in actions {
+                draw_action_to_ops(action, ops);
+            }
+        }
+    }
+}
+
+/// 将协作文档的合并结果写回画布状态
+async fn write_back_session(
+    state: &CanvasApiState,
+    session: &CollabSession,
+    canvas_id: &CanvasId,
+) -> Result<(), String> {
+    let canvas = state
+        .canvas_manager
+        .get_canvas(canvas_id)
+        .await
+        .ok_or("画布不存在")?;
+    let mut canvas_state = canvas.write().await;
+    session.apply_to(&mut canvas_state).await;
+    Ok(())
+}
+
+/// 写回画布状态并持久化，失败时仅记录日志
+async fn write_back_logged(state: &CanvasApiState, session: &CollabSession, canvas_id: &CanvasId) {
+    let result = match write_back_session(state, session, canvas_id).await {
+        Ok(()) => state
+            .canvas_manager
+            .commit(canvas_id)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to persist canvas {}: {}", canvas_id, e);
+    }
 }
 
 /// 协作事件包装
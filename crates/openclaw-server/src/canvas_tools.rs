@@ -0,0 +1,200 @@
+//! 画布 Agent 工具
+
+use async_trait::async_trait;
+use base64::Engine;
+use openclaw_canvas::{
+    CanvasError, CanvasId, CanvasManager, CanvasOps, CollabManager, DiagramFormat,
+    DiagramGenerator, DiagramOptions, Element, ExportFormat, ExportOptions, OpKind,
+};
+use openclaw_core::{OpenClawError, Result as OpenClawResult};
+use std::sync::Arc;
+
+/// 画布导出工具
+///
+/// 将画布渲染为图片或文档并以 base64 返回，结果可直接作为图片消息发送到聊天通道。
+pub struct CanvasExportTool {
+    canvas_manager: Arc<CanvasManager>,
+}
+
+impl CanvasExportTool {
+    pub fn new(canvas_manager: Arc<CanvasManager>) -> Self {
+        Self { canvas_manager }
+    }
+}
+
+#[async_trait]
+impl openclaw_tools::Tool for CanvasExportTool {
+    fn name(&self) -> &str {
+        "canvas_export"
+    }
+
+    fn description(&self) -> &str {
+        "Export a canvas as an image or document. Args: canvas_id (required), format (png|svg|pdf, default png), scale (PNG only, default 1.0)"
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> OpenClawResult<serde_json::Value> {
+        let canvas_id = args
+            .get("canvas_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| OpenClawError::Tool("canvas_id is required".to_string()))?
+            .to_string();
+
+        let format = match args.get("format").and_then(|v| v.as_str()) {
+            Some(f) => f
+                .parse::<ExportFormat>()
+                .map_err(|e| OpenClawError::Tool(e.to_string()))?,
+            None => ExportFormat::Png,
+        };
+
+        let options = ExportOptions {
+            scale: args.get("scale").and_then(|v| v.as_f64()).unwrap_or(1.0),
+            ..Default::default()
+        };
+
+        match self
+            .canvas_manager
+            .export(&canvas_id, format, &options)
+            .await
+        {
+            Ok(output) => Ok(serde_json::json!({
+                "success": true,
+                "message_type": if format == ExportFormat::Png { "image" } else { "file" },
+                "data": base64::engine::general_purpose::STANDARD.encode(&output.data),
+                "mime_type": output.mime_type(),
+                "filename": format!("{}.{}", canvas_id, format.extension()),
+                "width": output.width,
+                "height": output.height,
+            })),
+            Err(e) => Ok(serde_json::json!({
+                "success": false,
+                "error": e.to_string()
+            })),
+        }
+    }
+}
+
+/// 画布图表工具
+///
+/// 将 Mermaid / Graphviz DOT 源码自动布局为画布元素，生成的节点和连线均可单独编辑。
+pub struct CanvasDiagramTool {
+    canvas_manager: Arc<CanvasManager>,
+    collab_manager: Arc<CollabManager>,
+}
+
+impl CanvasDiagramTool {
+    pub fn new(canvas_manager: Arc<CanvasManager>, collab_manager: Arc<CollabManager>) -> Self {
+        Self {
+            canvas_manager,
+            collab_manager,
+        }
+    }
+
+    async fn insert(
+        &self,
+        canvas_id: &CanvasId,
+        elements: Vec<Element>,
+    ) -> Result<Vec<String>, CanvasError> {
+        let canvas = self
+            .canvas_manager
+            .get_canvas(canvas_id)
+            .await
+            .ok_or_else(|| CanvasError::NotFound(canvas_id.clone()))?;
+        let ids = elements.iter().map(|e| e.id.clone()).collect();
+
+        // 有在线协作会话时经由 CRDT 文档写入，保证在线用户同步收到
+        if let Some(session) = self.collab_manager.get_session(canvas_id).await {
+            for element in elements {
+                session.edit(OpKind::Insert { element }, None).await?;
+            }
+            let mut state = canvas.write().await;
+            session.apply_to(&mut state).await;
+        } else {
+            for element in elements {
+                CanvasOps::add_element(&canvas, element).await?;
+            }
+        }
+
+        self.canvas_manager.commit(canvas_id).await?;
+        Ok(ids)
+    }
+}
+
+#[async_trait]
+impl openclaw_tools::Tool for CanvasDiagramTool {
+    fn name(&self) -> &str {
+        "canvas_diagram"
+    }
+
+    fn description(&self) -> &str {
+        "Draw a diagram on a canvas from Mermaid (flowchart, sequenceDiagram) or Graphviz DOT source. Nodes and edges are laid out automatically and stay individually editable. Args: canvas_id (required), source (required), format (mermaid|dot, auto-detected if omitted), x, y (top-left position, default 40,40), layer (default 0)"
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> OpenClawResult<serde_json::Value> {
+        let canvas_id = args
+            .get("canvas_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| OpenClawError::Tool("canvas_id is required".to_string()))?
+            .to_string();
+        let source = args
+            .get("source")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| OpenClawError::Tool("source is required".to_string()))?;
+        let format = args
+            .get("format")
+            .and_then(|v| v.as_str())
+            .map(|f| f.parse::<DiagramFormat>())
+            .transpose()
+            .map_err(|e| OpenClawError::Tool(e.to_string()))?;
+
+        let mut options = DiagramOptions::default();
+        if let Some(x) = args.get("x").and_then(|v| v.as_f64()) {
+            options.origin.x = x;
+        }
+        if let Some(y) = args.get("y").and_then(|v| v.as_f64()) {
+            options.origin.y = y;
+        }
+        if let Some(layer) = args.get("layer").and_then(|v| v.as_u64()) {
+            options.layer = layer as usize;
+        }
+
+        let layout = match DiagramGenerator::generate(source, format, &options, None) {
+            Ok(layout) => layout,
+            Err(e) => {
+                return Ok(serde_json::json!({
+                    "success": false,
+                    "error": e.to_string()
+                }));
+            }
+        };
+        let (width, height) = (layout.width, layout.height);
+
+        match self.insert(&canvas_id, layout.elements).await {
+            Ok(element_ids) => Ok(serde_json::json!({
+                "success": true,
+                "element_ids": element_ids,
+                "width": width,
+                "height": height,
+            })),
+            Err(e) => Ok(serde_json::json!({
+                "success": false,
+                "error": e.to_string()
+            })),
+        }
+    }
+}
+
+/// 注册画布工具
+pub fn register_canvas_tools(
+    registry: &mut openclaw_tools::ToolRegistry,
+    canvas_manager: Arc<CanvasManager>,
+    collab_manager: Arc<CollabManager>,
+) {
+    registry.register(
+        "canvas_export".to_string(),
+        Arc::new(CanvasExportTool::new(canvas_manager.clone())),
+    );
+    registry.register(
+        "canvas_diagram".to_string(),
+        Arc::new(CanvasDiagramTool::new(canvas_manager, collab_manager)),
+    );
+}
@@ -1,6 +1,11 @@
 use std::sync::Arc;
 use async_trait::async_trait;
-use openclaw_channels::{ChannelEvent, ChannelHandler, SendMessage};
+use openclaw_channels::identity::ResolvedIdentity;
+use openclaw_channels::dm_policy::DmAccessResult;
+use openclaw_channels::{
+    ChannelEvent, ChannelHandler, ChannelIdentity, ChannelMessage, IdentityCommand,
+    IdentityManager, SendMessage, TextStream,
+};
 use openclaw_core::{Result, OpenClawError};
 use once_cell::sync::Lazy;
 
@@ -25,6 +30,7 @@ static DISCORD_CLEAN_PATTERN: Lazy<regex::Regex> = Lazy::new(|| {
 pub struct ChannelMessageHandler {
     processor: Arc<dyn ChannelMessageProcessor>,
     acp_service: Option<Arc<AcpService>>,
+    identities: Option<Arc<IdentityManager>>,
 }
 
 impl ChannelMessageHandler {
@@ -32,6 +38,7 @@ impl ChannelMessageHandler {
         Self {
             processor,
             acp_service: None,
+            identities: None,
         }
     }
 
@@ -40,6 +47,31 @@ impl ChannelMessageHandler {
         self
     }
 
+    /// 启用跨通道身份：处理 `/link`、`/unlink` 命令，已关联用户按统一 ID 延续会话
+    pub fn with_identity_manager(mut self, identities: Arc<IdentityManager>) -> Self {
+        self.identities = Some(identities);
+        self
+    }
+
+    /// 消息发送者的用户 ID，已关联时为统一用户 ID
+    async fn resolve_user(&self, message: &ChannelMessage) -> ResolvedIdentity {
+        match &self.identities {
+            Some(identities) => identities.resolve(&ChannelIdentity::from_message(message)).await,
+            None => ResolvedIdentity {
+                user_id: message.user_id.clone(),
+                linked: false,
+            },
+        }
+    }
+
+    /// 按统一用户检查 DM 权限，未启用身份关联时不做限制
+    async fn check_access(&self, message: &ChannelMessage) -> DmAccessResult {
+        match &self.identities {
+            Some(identities) => identities.check_message_access(message).await,
+            None => DmAccessResult::Allowed,
+        }
+    }
+
     fn parse_mentions(&self, content: &str) -> Vec<String> {
         let mut mentions = Vec::new();
         
@@ -69,13 +101,35 @@ impl ChannelMessageHandler {
 
 #[async_trait]
 impl ChannelHandler for ChannelMessageHandler {
-    async fn handle(
-        &self,
-        message: openclaw_channels::ChannelMessage,
-    ) -> Result<Option<SendMessage>> {
+    async fn handle(&self, message: ChannelMessage) -> Result<Option<SendMessage>> {
         let channel_name = message.chat_id.clone();
         let content = message.content.clone();
-        let user_id = message.user_id.clone();
+
+        match self.check_access(&message).await {
+            DmAccessResult::Allowed => {}
+            DmAccessResult::NeedsPairing(reason) => {
+                return Ok(Some(SendMessage::text(channel_name, reason).in_reply_to(&message)));
+            }
+            DmAccessResult::Blocked(reason) => {
+                tracing::info!(
+                    "Dropped message from {}: {}",
+                    ChannelIdentity::from_message(&message).key(),
+                    reason
+                );
+                return Ok(None);
+            }
+        }
+
+        if let Some(identities) = &self.identities
+            && let Some(command) = IdentityCommand::parse(&content)
+        {
+            let identity = ChannelIdentity::from_message(&message);
+            let reply = identities.handle_command(&identity, command).await;
+            return Ok(Some(SendMessage::text(channel_name, reply).in_reply_to(&message)));
+        }
+
+        let user = self.resolve_user(&message).await;
+        let user_id = user.user_id.clone();
 
         if let Some(acp) = &self.acp_service {
             let mentions = self.parse_mentions(&content);
@@ -88,19 +142,9 @@ impl ChannelHandler for ChannelMessageHandler {
                     
                     match acp.handle_message(&cleaned, &channel_name, Some(&user_id)).await {
                         Ok(response) => {
-                            return Ok(Some(SendMessage {
-                                chat_id: channel_name,
-                                message_type: "text".to_string(),
-                                content: response,
-                                title: None,
-                                url: None,
-                                at_mobiles: None,
-                                mentioned_list: None,
-                                base64: None,
-                                md5: None,
-                                articles: None,
-                                media_id: None,
-                            }));
+                            return Ok(Some(
+                                SendMessage::text(channel_name, response).in_reply_to(&message),
+                            ));
                         }
                         Err(e) => {
                             tracing::warn!("ACP handle message failed: {:?}", e);
@@ -110,30 +154,81 @@ impl ChannelHandler for ChannelMessageHandler {
             }
         }
 
-        let response = self
-            .processor
-            .process_message(&channel_name, content)
-            .await?;
+        let response = if user.linked {
+            self.processor
+                .process_user_message(&channel_name, &user.user_id, content)
+                .await?
+        } else {
+            self.processor
+                .process_message(&channel_name, content)
+                .await?
+        };
+
+        // 回复挂在来源消息下，支持线程的平台会在线程内回复
+        Ok(Some(SendMessage::text(channel_name, response).in_reply_to(&message)))
+    }
+
+    async fn handle_stream(&self, message: ChannelMessage) -> Result<Option<TextStream>> {
+        // @ 提及走 ACP 路由，不做流式输出
+        if self.acp_service.is_some() && !self.parse_mentions(&message.content).is_empty() {
+            return Ok(None);
+        }
+        // 未通过权限检查的消息和身份命令由 `handle` 处理
+        if self.identities.is_some()
+            && (!self.check_access(&message).await.is_allowed()
+                || IdentityCommand::parse(&message.content).is_some())
+        {
+            return Ok(None);
+        }
 
-        Ok(Some(SendMessage {
-            chat_id: channel_name,
-            message_type: "text".to_string(),
-            content: response,
-            title: None,
-            url: None,
-            at_mobiles: None,
-            mentioned_list: None,
-            base64: None,
-            md5: None,
-            articles: None,
-            media_id: None,
-        }))
+        let user = self.resolve_user(&message).await;
+        if user.linked {
+            self.processor
+                .process_user_message_stream(&message.chat_id, &user.user_id, message.content)
+                .await
+        } else {
+            self.processor
+                .process_message_stream(&message.chat_id, message.content)
+                .await
+        }
     }
 }
 
 #[async_trait]
 pub trait ChannelMessageProcessor: Send + Sync {
     async fn process_message(&self, channel_name: &str, message: String) -> Result<String>;
+
+    /// 处理已关联的统一用户的消息，默认同 `process_message`
+    async fn process_user_message(
+        &self,
+        channel_name: &str,
+        user_id: &str,
+        message: String,
+    ) -> Result<String> {
+        let _ = user_id;
+        self.process_message(channel_name, message).await
+    }
+
+    /// 流式处理消息，返回 None 时改用 `process_message`
+    async fn process_message_stream(
+        &self,
+        channel_name: &str,
+        message: String,
+    ) -> Result<Option<TextStream>> {
+        let _ = (channel_name, message);
+        Ok(None)
+    }
+
+    /// 流式处理已关联的统一用户的消息，默认同 `process_message_stream`
+    async fn process_user_message_stream(
+        &self,
+        channel_name: &str,
+        user_id: &str,
+        message: String,
+    ) -> Result<Option<TextStream>> {
+        let _ = user_id;
+        self.process_message_stream(channel_name, message).await
+    }
 }
 
 pub struct OrchestratorMessageProcessor {
@@ -149,6 +244,29 @@ impl ChannelMessageProcessor for OrchestratorMessageProcessor {
             .await?;
         Ok(response.content)
     }
+
+    async fn process_user_message(
+        &self,
+        channel_name: &str,
+        user_id: &str,
+        message: String,
+    ) -> Result<String> {
+        let response = self
+            .orchestrator
+            .process_user_channel_message(channel_name, user_id, message)
+            .await?;
+        Ok(response.content)
+    }
+
+    async fn process_message_stream(
+        &self,
+        channel_name: &str,
+        message: String,
+    ) -> Result<Option<TextStream>> {
+        self.orchestrator
+            .stream_channel_message(channel_name, message)
+            .await
+    }
 }
 
 pub fn create_channel_handler<P: ChannelMessageProcessor + 'static>(
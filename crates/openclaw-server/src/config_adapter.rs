@@ -8,6 +8,12 @@ use openclaw_memory::types::MemoryConfig;
 use openclaw_security::pipeline::PipelineConfig;
 use std::sync::Arc;
 
+/// 记忆命名空间的默认 persona，与默认 agent 一致
+pub const DEFAULT_PERSONA: &str = "orchestrator";
+
+/// 未配置记忆用户时的默认用户，与认证关闭时的匿名调用方一致
+pub const DEFAULT_MEMORY_USER: &str = "anonymous";
+
 pub struct ConfigAdapter {
     core: Arc<CoreConfig>,
     devices: Arc<DevicesConfig>,
@@ -62,12 +68,30 @@ impl ConfigAdapter {
                 enable_bm25: core.long_term.enable_bm25,
                 enable_knowledge_graph: core.long_term.enable_knowledge_graph,
                 custom_embedding: custom_emb,
+                local_embedding: core.long_term.local_embedding.as_ref().map(|l| {
+                    LocalEmbeddingSettings {
+                        cache_dir: l.cache_dir.clone(),
+                        offline: l.offline,
+                        precision: l.precision.clone(),
+                        batch_size: l.batch_size,
+                    }
+                }),
                 ..Default::default()
             },
             working: WorkingMemoryConfig {
                 max_messages: core.working.max_messages,
                 max_tokens: core.working.max_tokens,
             },
+            // 服务端的记忆读写总是限定在命名空间内，不会落到无归属的共享存储
+            privacy: PrivacyConfig {
+                namespace: Some(MemoryNamespace::new(
+                    core.namespace_user
+                        .as_deref()
+                        .unwrap_or(DEFAULT_MEMORY_USER),
+                    DEFAULT_PERSONA,
+                )),
+                ..Default::default()
+            },
             ..Default::default()
         }
     }
@@ -132,4 +156,14 @@ impl ConfigAdapter {
     pub fn sandbox(&self) -> &openclaw_core::config::SandboxSettings {
         &self.core.sandbox
     }
+
+    /// 获取 Agentic RAG 配置，未设置时使用默认配置
+    pub fn agentic_rag(&self) -> openclaw_core::Result<crate::agentic_rag::AgenticRAGConfig> {
+        match &self.core.agentic_rag {
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
+                openclaw_core::OpenClawError::Config(format!("Invalid agentic_rag config: {}", e))
+            }),
+            None => Ok(Default::default()),
+        }
+    }
 }
@@ -0,0 +1,386 @@
+//! 配置热加载
+//!
+//! 定时检查配置目录中的文件和智能体引用的 AIEOS 文件，内容变化且稳定后重新加载。
+//! 新配置需通过插值和 Schema 校验，失败时保留当前配置继续运行；
+//! 通过后将差异应用到运行中的服务：替换默认提供商、增删通道、更新智能体提示词。
+//! 其余配置段的变更只记录日志，需要重启网关生效。
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use openclaw_agent::AgentInstanceConfig;
+use openclaw_channels::config::ChannelConfigs;
+use openclaw_core::config::ProviderConfig;
+use openclaw_core::{OpenClawError, Result};
+use serde::Serialize;
+
+use crate::app_context::AppContext;
+use crate::config_adapter::ConfigAdapter;
+use crate::server_config::ServerConfig;
+use crate::service_factory::{build_ai_provider, channel_configs_from_settings};
+
+/// 默认检查间隔
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// `ServerConfig::load_strict` 读取的文件
+const WATCHED_FILES: &[&str] = &[
+    "config.json",
+    "openclaw.json",
+    "agents.yaml",
+    "devices.yaml",
+    "workspaces.yaml",
+    "acp.yaml",
+    "channels.yaml",
+];
+
+/// 两份配置之间可热加载的差异
+#[derive(Debug, Default)]
+pub struct ConfigDiff {
+    /// 默认提供商的新配置
+    pub provider: Option<ProviderConfig>,
+    pub channels_removed: Vec<String>,
+    /// 新增或修改的通道
+    pub channels_changed: ChannelConfigs,
+    pub agents_removed: Vec<String>,
+    /// 新增或修改的智能体
+    pub agents_changed: Vec<AgentInstanceConfig>,
+    /// 有变更但需要重启才能生效的配置段
+    pub restart_required: Vec<&'static str>,
+}
+
+impl ConfigDiff {
+    pub fn between(old: &ServerConfig, new: &ServerConfig) -> Self {
+        let mut diff = Self::default();
+
+        let old_provider = ConfigAdapter::from_ref(&old.core).ai_provider();
+        let new_provider = ConfigAdapter::from_ref(&new.core).ai_provider();
+        if !same(&old_provider, &new_provider) {
+            diff.provider = Some(new_provider);
+        }
+
+        let old_channels = channel_configs_from_settings(&old.core.channels).unwrap_or_default();
+        let new_channels = channel_configs_from_settings(&new.core.channels).unwrap_or_default();
+        for name in old_channels.0.keys() {
+            if !new_channels.0.contains_key(name) {
+                diff.channels_removed.push(name.clone());
+            }
+        }
+        for (name, entry) in new_channels.0 {
+            if old_channels
+                .0
+                .get(&name)
+                .is_none_or(|old| old.config != entry.config)
+            {
+                diff.channels_changed.0.insert(name, entry);
+            }
+        }
+
+        let old_agents: HashMap<&str, &AgentInstanceConfig> =
+            old.agents.list.iter().map(|a| (a.id.as_str(), a)).collect();
+        let new_ids: HashSet<&str> = new.agents.list.iter().map(|a| a.id.as_str()).collect();
+        diff.agents_removed = old_agents
+            .keys()
+            .filter(|id| !new_ids.contains(*id))
+            .map(|id| id.to_string())
+            .collect();
+        diff.agents_changed = new
+            .agents
+            .list
+            .iter()
+            .filter(|a| {
+                old_agents
+                    .get(a.id.as_str())
+                    .is_none_or(|old| !same(*old, a))
+            })
+            .cloned()
+            .collect();
+
+        let sections: [(&'static str, bool); 15] = [
+            ("server", same(&old.core.server, &new.core.server)),
+            (
+                "ai",
+                same(&old.core.ai.token_budget, &new.core.ai.token_budget)
+                    && same(&old.core.ai.auth_profiles, &new.core.ai.auth_profiles)
+                    && old.core.ai.use_accurate_token_count == new.core.ai.use_accurate_token_count,
+            ),
+            ("memory", same(&old.core.memory, &new.core.memory)),
+            ("vector", same(&old.core.vector, &new.core.vector)),
+            (
+                "channels",
+                old.core.channels.enabled == new.core.channels.enabled
+                    && old.core.channels.channel_to_agent_map
+                        == new.core.channels.channel_to_agent_map
+                    && old.core.channels.stream_replies == new.core.channels.stream_replies,
+            ),
+            ("security", same(&old.core.security, &new.core.security)),
+            ("voice", same(&old.core.voice, &new.core.voice)),
+            ("browser", old.core.browser == new.core.browser),
+            ("canvas", old.core.canvas == new.core.canvas),
+            ("agentic_rag", old.core.agentic_rag == new.core.agentic_rag),
+            ("sandbox", same(&old.core.sandbox, &new.core.sandbox)),
+            ("api_auth", same(&old.core.api_auth, &new.core.api_auth)),
+            ("devices", same(&old.devices, &new.devices)),
+            ("workspaces", same(&old.workspaces, &new.workspaces)),
+            ("acp", same(&old.acp, &new.acp)),
+        ];
+        diff.restart_required = sections
+            .into_iter()
+            .filter(|(_, unchanged)| !unchanged)
+            .map(|(name, _)| name)
+            .collect();
+
+        diff
+    }
+
+    /// 是否有可热加载的变更
+    pub fn has_live_changes(&self) -> bool {
+        self.provider.is_some()
+            || !self.channels_removed.is_empty()
+            || !self.channels_changed.0.is_empty()
+            || !self.agents_removed.is_empty()
+            || !self.agents_changed.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.has_live_changes() && self.restart_required.is_empty()
+    }
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// 配置文件监视器，由网关启动时创建
+pub struct ConfigWatcher {
+    config_dir: PathBuf,
+    context: Arc<AppContext>,
+    current: ServerConfig,
+    interval: Duration,
+}
+
+impl ConfigWatcher {
+    /// `current` 应为从配置目录加载的原始配置，不含命令行覆盖
+    pub fn new(config_dir: PathBuf, current: ServerConfig, context: Arc<AppContext>) -> Self {
+        Self {
+            config_dir,
+            context,
+            current,
+            interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+
+    async fn run(mut self) {
+        tracing::info!("Watching {} for config changes", self.config_dir.display());
+
+        let mut applied = self.fingerprint();
+        let mut last_seen = applied.clone();
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            let seen = self.fingerprint();
+
+            // 编辑器保存可能分多次写入，连续两次检查一致后再加载
+            if seen == applied || seen != last_seen {
+                last_seen = seen;
+                continue;
+            }
+
+            let touched = changed_files(&applied, &seen);
+            match self.reload(&touched).await {
+                Ok(diff) if diff.is_empty() => {
+                    tracing::debug!("Config files changed without effective changes")
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Rejected config change, keeping current config: {}", e),
+            }
+            // 智能体列表可能变化，按新配置重新计算需要监视的文件
+            applied = self.fingerprint();
+            last_seen = applied.clone();
+        }
+    }
+
+    /// 重新加载并应用配置，`touched` 为内容有变化的文件
+    pub async fn reload(&mut self, touched: &HashSet<PathBuf>) -> Result<ConfigDiff> {
+        let new = ServerConfig::load_strict(&self.config_dir)
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+        let mut diff = ConfigDiff::between(&self.current, &new);
+
+        // AIEOS 文件内容变化时，引用它的智能体也需要重建
+        for agent in &new.agents.list {
+            if agent
+                .aieos_path
+                .as_ref()
+                .is_some_and(|p| touched.contains(p))
+                && !diff.agents_changed.iter().any(|a| a.id == agent.id)
+            {
+                diff.agents_changed.push(agent.clone());
+            }
+        }
+
+        // 先构建新提供商，失败时整次变更都不生效
+        let provider = match &diff.provider {
+            Some(config) => Some(build_ai_provider(config)?),
+            None => None,
+        };
+
+        let orchestrator = self.context.orchestrator.read().await;
+
+        // 通道变更会先校验全部新配置，失败时尚未改动任何运行状态；
+        // 提供商和智能体的替换不会失败，放在通道之后
+        if let Some(orchestrator) = orchestrator.as_ref()
+            && (!diff.channels_removed.is_empty() || !diff.channels_changed.0.is_empty())
+        {
+            orchestrator
+                .apply_channel_changes(&diff.channels_removed, &diff.channels_changed)
+                .await?;
+        }
+
+        if let Some(provider) = provider {
+            match &self.context.provider_handle {
+                Some(handle) => {
+                    handle.swap(provider);
+                    tracing::info!("AI provider switched to {}", new.core.ai.default_provider);
+                }
+                None => tracing::warn!("AI provider is not reloadable, restart to apply"),
+            }
+        }
+
+        if let Some(orchestrator) = orchestrator.as_ref() {
+            for id in &diff.agents_removed {
+                orchestrator.unregister_agent(id).await;
+                tracing::info!("Agent {} removed by config reload", id);
+            }
+            if !diff.agents_changed.is_empty() {
+                orchestrator.reload_agents(&diff.agents_changed).await;
+                tracing::info!("Reloaded {} agents", diff.agents_changed.len());
+            }
+        }
+
+        if !diff.restart_required.is_empty() {
+            tracing::warn!(
+                "Config sections changed but require a restart: {}",
+                diff.restart_required.join(", ")
+            );
+        }
+
+        self.current = new;
+        Ok(diff)
+    }
+
+    fn fingerprint(&self) -> HashMap<PathBuf, u64> {
+        let mut paths: Vec<PathBuf> = WATCHED_FILES
+            .iter()
+            .map(|name| self.config_dir.join(name))
+            .collect();
+        paths.extend(
+            self.current
+                .agents
+                .list
+                .iter()
+                .filter_map(|a| a.aieos_path.clone()),
+        );
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let hash = file_hash(&path);
+                (path, hash)
+            })
+            .collect()
+    }
+}
+
+fn file_hash(path: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    // 文件不存在时不写入任何内容，与空文件的哈希不同
+    if let Ok(bytes) = std::fs::read(path) {
+        bytes.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn changed_files(old: &HashMap<PathBuf, u64>, new: &HashMap<PathBuf, u64>) -> HashSet<PathBuf> {
+    new.iter()
+        .filter(|(path, hash)| old.get(*path) != Some(hash))
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn base() -> ServerConfig {
+        let mut config = ServerConfig::default();
+        config.core.channels.config = Some(json!({
+            "telegram": { "bot_token": "a" },
+            "slack": { "bot_token": "b" }
+        }));
+        config.agents.list = vec![
+            AgentInstanceConfig::new("support", "/tmp/support"),
+            AgentInstanceConfig::new("sales", "/tmp/sales"),
+        ];
+        config
+    }
+
+    #[test]
+    fn test_diff_live_changes() {
+        let old = base();
+        let mut new = base();
+        new.core.channels.config = Some(json!({
+            "telegram": { "bot_token": "changed" },
+            "discord": { "bot_token": "c" }
+        }));
+        new.agents.list[0].system_prompt = Some("Be brief.".to_string());
+        new.agents.list.remove(1);
+        new.core.ai.providers.push(ProviderConfig {
+            name: "openai".to_string(),
+            provider_type: openclaw_core::config::ProviderType::OpenAI,
+            api_key: Some("sk-new".to_string()),
+            base_url: None,
+            default_model: "gpt-4o".to_string(),
+            models: vec![],
+            auth: Default::default(),
+        });
+
+        let diff = ConfigDiff::between(&old, &new);
+        assert_eq!(diff.provider.unwrap().api_key.as_deref(), Some("sk-new"));
+        assert_eq!(diff.channels_removed, vec!["slack"]);
+        let mut changed: Vec<_> = diff.channels_changed.0.keys().cloned().collect();
+        changed.sort();
+        assert_eq!(changed, vec!["discord", "telegram"]);
+        assert_eq!(diff.agents_removed, vec!["sales"]);
+        assert_eq!(diff.agents_changed.len(), 1);
+        assert_eq!(diff.agents_changed[0].id, "support");
+        assert!(diff.restart_required.is_empty());
+    }
+
+    #[test]
+    fn test_diff_restart_required() {
+        let old = base();
+        let mut new = base();
+        assert!(ConfigDiff::between(&old, &new).is_empty());
+
+        new.core.server.port = 9000;
+        new.core.memory.working.max_messages = 50;
+        let diff = ConfigDiff::between(&old, &new);
+        assert!(!diff.has_live_changes());
+        assert_eq!(diff.restart_required, vec!["server", "memory"]);
+    }
+}
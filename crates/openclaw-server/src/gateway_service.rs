@@ -2,6 +2,7 @@
 
 use axum::Router;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
@@ -12,18 +13,25 @@ use crate::server_config::{ServerConfig, AcpConfig};
 use crate::adapters::{AIProviderAdapter, SecurityPipelineAdapter, ToolRegistryAdapter};
 use crate::ports::DevicePortAdapter;
 use crate::api::create_router;
+use crate::canvas_api::CanvasApiState;
 use crate::agentic_rag_api::init_agentic_rag_engine;
+use crate::identity_api::{default_identity_path, init_identity_manager};
+use crate::memory_api::{init_ingest_pipeline, init_memory_backend};
+use crate::orchestrator::ServiceOrchestrator;
 use crate::app_context::AppContext;
 use crate::config_adapter::ConfigAdapter;
+use crate::config_reload::ConfigWatcher;
 use crate::service_factory::{DefaultServiceFactory, ServiceFactory};
-use crate::websocket::websocket_router;
 
+use openclaw_channels::IdentityManager;
+use openclaw_channels::dm_policy::{DmAccessPolicy, DmPolicyConfig, DmPolicyManager};
 use openclaw_vector::init_all_factories;
 
 pub struct Gateway {
     config: ServerConfig,
     context: Arc<AppContext>,
     factory: Arc<DefaultServiceFactory>,
+    config_dir: Option<PathBuf>,
 }
 
 impl Gateway {
@@ -68,15 +76,29 @@ impl Gateway {
             config,
             context,
             factory: Arc::new(factory),
+            config_dir: None,
         })
     }
 
+    /// 设置配置目录，启动后监视其中的配置文件并热加载
+    pub fn with_config_dir(mut self, config_dir: impl Into<PathBuf>) -> Self {
+        self.config_dir = Some(config_dir.into());
+        self
+    }
+
     pub async fn start(&self) -> openclaw_core::Result<()> {
+        crate::telemetry::install_metrics_recorder();
+
         if let Some(ref device_manager) = self.context.device_manager {
             device_manager.init().await?;
         }
 
         if let Some(ref orchestrator) = *self.context.orchestrator.read().await {
+            // 身份管理器要在通道处理器创建（orchestrator.start）之前就绪
+            if self.config.core.channels.enabled {
+                self.init_identity_manager(orchestrator).await;
+            }
+
             if self.config.acp.enabled {
                 let acp_service = self.factory.create_acp_service(&self.config.acp).await?;
                 if let Some(acp_service) = acp_service {
@@ -138,9 +160,38 @@ impl Gateway {
             self.init_voice_service().await?;
         }
 
-        let canvas_manager = (*self.context.orchestrator.read().await)
+        if let Some(ref backend) = self.context.memory_backend {
+            init_memory_backend(backend.clone());
+            match self
+                .factory
+                .create_ingest_pipeline(self.context.ai_provider.clone())
+                .await
+            {
+                Ok(pipeline) => {
+                    init_ingest_pipeline(pipeline);
+                    tracing::info!("Document ingestion pipeline initialized");
+                }
+                Err(e) => tracing::warn!("Failed to initialize document ingestion: {}", e),
+            }
+        }
+
+        if let Some(ref config_dir) = self.config_dir {
+            // 以文件中的原始配置为基准，避免命令行覆盖的字段被误判为变更
+            let baseline = ServerConfig::load_strict(config_dir).unwrap_or_else(|e| {
+                tracing::warn!("Config reload baseline falls back to startup config: {}", e);
+                self.config.clone()
+            });
+            ConfigWatcher::new(config_dir.clone(), baseline, self.context.clone()).spawn();
+        }
+
+        let canvas_state = (*self.context.orchestrator.read().await)
             .as_ref()
-            .map(|orchestrator| orchestrator.canvas_manager());
+            .map(|orchestrator| {
+                CanvasApiState::with_services(
+                    orchestrator.canvas_manager(),
+                    orchestrator.collab_manager(),
+                )
+            });
 
         let browser_config = self
             .config
@@ -152,10 +203,9 @@ impl Gateway {
         let app = Router::new()
             .merge(create_router(
                 self.context.clone(),
-                canvas_manager,
+                canvas_state,
                 browser_config,
             ))
-            .merge(websocket_router())
             .layer(CorsLayer::new().allow_origin(Any).allow_methods(Any))
             .layer(TraceLayer::new_for_http());
 
@@ -191,6 +241,25 @@ impl Gateway {
         Ok(())
     }
 
+    /// 加载跨通道身份关联，供通道处理器和管理 API 使用
+    ///
+    /// 网关默认允许所有 DM，黑名单、白名单和按统一用户设置的策略仍然生效。
+    async fn init_identity_manager(&self, orchestrator: &ServiceOrchestrator) {
+        let dm_policy = Arc::new(DmPolicyManager::new(DmPolicyConfig {
+            default_policy: DmAccessPolicy::AllowAll,
+            ..Default::default()
+        }));
+        match IdentityManager::with_storage(dm_policy, default_identity_path()) {
+            Ok(manager) => {
+                let manager = Arc::new(manager);
+                orchestrator.set_identity_manager(manager.clone()).await;
+                init_identity_manager(manager);
+                tracing::info!("Cross-channel identity linking initialized");
+            }
+            Err(e) => tracing::warn!("Failed to load linked identities: {}", e),
+        }
+    }
+
     async fn init_voice_service(&self) -> openclaw_core::Result<()> {
         let (stt, tts) = self.factory.create_voice_providers().await?;
 
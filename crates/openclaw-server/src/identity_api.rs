@@ -0,0 +1,139 @@
+//! 跨通道身份管理 HTTP API
+//!
+//! 供管理员查看统一用户、手动关联、合并和解除关联账号。
+
+use axum::{
+    Json, Router,
+    extract::Path,
+    routing::{get, post},
+};
+use openclaw_channels::{ChannelIdentity, IdentityManager, UnifiedUser};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+
+static IDENTITY_MANAGER: OnceLock<Arc<IdentityManager>> = OnceLock::new();
+
+pub fn init_identity_manager(manager: Arc<IdentityManager>) {
+    let _ = IDENTITY_MANAGER.set(manager);
+}
+
+pub fn get_identity_manager() -> Option<Arc<IdentityManager>> {
+    IDENTITY_MANAGER.get().cloned()
+}
+
+/// 默认存储路径 (~/.openclaw-rust/identities.json)
+pub fn default_identity_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".openclaw-rust")
+        .join("identities.json")
+}
+
+pub fn create_identity_router() -> Router {
+    Router::new()
+        .route("/api/identities", get(list_handler))
+        .route("/api/identities/{id}", get(get_handler))
+        .route("/api/identities/link", post(link_handler))
+        .route("/api/identities/unlink", post(unlink_handler))
+        .route("/api/identities/merge", post(merge_handler))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinkRequest {
+    pub user_id: String,
+    pub channel: String,
+    pub channel_user_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnlinkRequest {
+    pub channel: String,
+    pub channel_user_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeRequest {
+    /// 保留的用户
+    pub target: String,
+    /// 并入后删除的用户
+    pub source: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdentityApiResponse<T> {
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<String>,
+}
+
+impl<T> IdentityApiResponse<T> {
+    fn ok(data: T) -> Json<Self> {
+        Json(Self {
+            success: true,
+            data: Some(data),
+            error: None,
+        })
+    }
+
+    fn err(error: impl ToString) -> Json<Self> {
+        Json(Self {
+            success: false,
+            data: None,
+            error: Some(error.to_string()),
+        })
+    }
+}
+
+const NOT_INITIALIZED: &str = "Identity linking not initialized";
+
+async fn list_handler() -> Json<IdentityApiResponse<Vec<UnifiedUser>>> {
+    match get_identity_manager() {
+        Some(manager) => IdentityApiResponse::ok(manager.list_users().await),
+        None => IdentityApiResponse::err(NOT_INITIALIZED),
+    }
+}
+
+async fn get_handler(Path(id): Path<String>) -> Json<IdentityApiResponse<UnifiedUser>> {
+    let Some(manager) = get_identity_manager() else {
+        return IdentityApiResponse::err(NOT_INITIALIZED);
+    };
+    match manager.user(&id).await {
+        Some(user) => IdentityApiResponse::ok(user),
+        None => IdentityApiResponse::err(format!("User not found: {}", id)),
+    }
+}
+
+async fn link_handler(Json(request): Json<LinkRequest>) -> Json<IdentityApiResponse<UnifiedUser>> {
+    let Some(manager) = get_identity_manager() else {
+        return IdentityApiResponse::err(NOT_INITIALIZED);
+    };
+    let identity = ChannelIdentity::new(request.channel, request.channel_user_id);
+    match manager.link(&request.user_id, &identity).await {
+        Ok(user) => IdentityApiResponse::ok(user),
+        Err(e) => IdentityApiResponse::err(e),
+    }
+}
+
+async fn unlink_handler(Json(request): Json<UnlinkRequest>) -> Json<IdentityApiResponse<String>> {
+    let Some(manager) = get_identity_manager() else {
+        return IdentityApiResponse::err(NOT_INITIALIZED);
+    };
+    let identity = ChannelIdentity::new(request.channel, request.channel_user_id);
+    match manager.unlink(&identity).await {
+        Ok(()) => IdentityApiResponse::ok(identity.key()),
+        Err(e) => IdentityApiResponse::err(e),
+    }
+}
+
+async fn merge_handler(
+    Json(request): Json<MergeRequest>,
+) -> Json<IdentityApiResponse<UnifiedUser>> {
+    let Some(manager) = get_identity_manager() else {
+        return IdentityApiResponse::err(NOT_INITIALIZED);
+    };
+    match manager.merge(&request.target, &request.source).await {
+        Ok(user) => IdentityApiResponse::ok(user),
+        Err(e) => IdentityApiResponse::err(e),
+    }
+}
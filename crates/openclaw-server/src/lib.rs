@@ -6,24 +6,31 @@ pub mod agent_service;
 pub mod agentic_rag;
 pub mod agentic_rag_api;
 pub mod api;
+pub mod api_auth;
 pub mod app_context;
 pub mod browser_api;
 pub mod canvas_api;
+pub mod canvas_tools;
 pub mod channel_message_handler;
 pub mod channel_service;
 pub mod config_adapter;
+pub mod config_reload;
 pub mod server_config;
 pub mod device_api;
 pub mod device_manager;
 pub mod gateway;
 pub mod gateway_service;
 pub mod hardware_tools;
+pub mod identity_api;
+pub mod memory_api;
+pub mod openai_api;
 pub mod orchestrator;
 pub mod ports;
 pub mod service_factory;
 pub mod skill_service;
 pub mod squad_service;
 pub mod sse;
+pub mod telemetry;
 pub mod vector_store_registry;
 pub mod voice_service;
 pub mod websocket;
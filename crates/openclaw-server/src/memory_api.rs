@@ -0,0 +1,198 @@
+//! 记忆与知识库 HTTP API
+//!
+//! 文档上传使用原始请求体，文件名通过查询参数传入以确定格式。
+//! 上传和删除的文档归属调用方的命名空间，不同调用方的同名文档互不影响。
+//! 遗忘接口只允许调用方遗忘自己的记忆，按查询或替他人遗忘需要管理员权限。
+
+use axum::{
+    Extension, Json, Router,
+    body::Bytes,
+    extract::{DefaultBodyLimit, Query},
+    routing::{get, post},
+};
+use openclaw_memory::factory::MemoryBackend;
+use openclaw_memory::ingest::{IngestedDocument, MAX_DOCUMENT_BYTES};
+use openclaw_memory::privacy::{ForgetReceipt, ForgetTarget};
+use openclaw_memory::{IngestPipeline, MemoryNamespace};
+use openclaw_sandbox::Permission;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+
+use crate::api_auth::Principal;
+use crate::config_adapter::DEFAULT_PERSONA;
+
+static INGEST_PIPELINE: OnceLock<Arc<IngestPipeline>> = OnceLock::new();
+static MEMORY_BACKEND: OnceLock<Arc<dyn MemoryBackend>> = OnceLock::new();
+
+pub fn init_ingest_pipeline(pipeline: Arc<IngestPipeline>) {
+    let _ = INGEST_PIPELINE.set(pipeline);
+}
+
+pub fn get_ingest_pipeline() -> Option<Arc<IngestPipeline>> {
+    INGEST_PIPELINE.get().cloned()
+}
+
+pub fn init_memory_backend(backend: Arc<dyn MemoryBackend>) {
+    let _ = MEMORY_BACKEND.set(backend);
+}
+
+pub fn create_memory_router() -> Router {
+    Router::new()
+        .route(
+            "/api/memory/ingest",
+            post(ingest_handler).delete(remove_handler),
+        )
+        .route("/api/memory/ingest/formats", get(formats_handler))
+        .route("/api/memory/forget", post(forget_handler))
+        .layer(DefaultBodyLimit::max(MAX_DOCUMENT_BYTES))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IngestParams {
+    pub filename: String,
+    /// 默认为 `upload:<filename>`，相同 ID 重复上传会替换旧内容
+    pub document_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoveParams {
+    pub document_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemoryApiResponse<T> {
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<String>,
+}
+
+impl<T> MemoryApiResponse<T> {
+    fn ok(data: T) -> Json<Self> {
+        Json(Self {
+            success: true,
+            data: Some(data),
+            error: None,
+        })
+    }
+
+    fn err(error: impl ToString) -> Json<Self> {
+        Json(Self {
+            success: false,
+            data: None,
+            error: Some(error.to_string()),
+        })
+    }
+}
+
+const NOT_INITIALIZED: &str = "Document ingestion not initialized";
+
+/// 调用方的文档命名空间，与默认 agent 的记忆命名空间一致
+fn principal_namespace(principal: &Principal) -> MemoryNamespace {
+    MemoryNamespace::new(&principal.id, DEFAULT_PERSONA)
+}
+
+async fn ingest_handler(
+    Extension(principal): Extension<Principal>,
+    Query(params): Query<IngestParams>,
+    body: Bytes,
+) -> Json<MemoryApiResponse<IngestedDocument>> {
+    let Some(pipeline) = get_ingest_pipeline() else {
+        return MemoryApiResponse::err(NOT_INITIALIZED);
+    };
+
+    // 只保留文件名部分，避免客户端传入路径
+    let Some(file_name) = std::path::Path::new(&params.filename)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+    else {
+        return MemoryApiResponse::err("Invalid filename");
+    };
+    let document_id = params
+        .document_id
+        .unwrap_or_else(|| format!("upload:{}", file_name));
+
+    match pipeline
+        .ingest_bytes_in(
+            &file_name,
+            body.to_vec(),
+            &document_id,
+            &principal_namespace(&principal),
+        )
+        .await
+    {
+        Ok(document) => MemoryApiResponse::ok(document),
+        Err(e) => MemoryApiResponse::err(e),
+    }
+}
+
+async fn remove_handler(
+    Extension(principal): Extension<Principal>,
+    Query(params): Query<RemoveParams>,
+) -> Json<MemoryApiResponse<String>> {
+    let Some(pipeline) = get_ingest_pipeline() else {
+        return MemoryApiResponse::err(NOT_INITIALIZED);
+    };
+
+    match pipeline
+        .remove_document_in(&params.document_id, &principal_namespace(&principal))
+        .await
+    {
+        Ok(()) => MemoryApiResponse::ok(params.document_id),
+        Err(e) => MemoryApiResponse::err(e),
+    }
+}
+
+/// 管理员可以遗忘任意记忆，其他调用方只能遗忘自己
+fn can_forget(principal: &Principal, target: &ForgetTarget) -> bool {
+    principal.has_permission(&Permission::SystemAdmin)
+        || matches!(target, ForgetTarget::User { user_id } if *user_id == principal.id)
+}
+
+async fn forget_handler(
+    Extension(principal): Extension<Principal>,
+    Json(target): Json<ForgetTarget>,
+) -> Json<MemoryApiResponse<ForgetReceipt>> {
+    let Some(backend) = MEMORY_BACKEND.get() else {
+        return MemoryApiResponse::err("Memory backend not initialized");
+    };
+    if !can_forget(&principal, &target) {
+        return MemoryApiResponse::err(
+            "Forbidden: only administrators can forget other users' memories",
+        );
+    }
+
+    match backend.forget(target).await {
+        Ok(receipt) => MemoryApiResponse::ok(receipt),
+        Err(e) => MemoryApiResponse::err(e),
+    }
+}
+
+async fn formats_handler() -> Json<MemoryApiResponse<Vec<String>>> {
+    match get_ingest_pipeline() {
+        Some(pipeline) => MemoryApiResponse::ok(pipeline.registry().extensions()),
+        None => MemoryApiResponse::err(NOT_INITIALIZED),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_can_forget_only_own_memories() {
+        let principal = Principal {
+            id: "key:alice".to_string(),
+            name: "alice".to_string(),
+            permissions: HashSet::from([Permission::MemoryWrite]),
+            rate_limit_per_minute: None,
+        };
+        assert!(can_forget(&principal, &ForgetTarget::user("key:alice")));
+        assert!(!can_forget(&principal, &ForgetTarget::user("key:bob")));
+        assert!(!can_forget(&principal, &ForgetTarget::query("address")));
+        assert!(can_forget(
+            &Principal::anonymous(),
+            &ForgetTarget::query("address")
+        ));
+    }
+}
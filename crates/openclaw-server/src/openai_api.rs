@@ -0,0 +1,860 @@
+//! OpenAI 兼容 API
+//!
+//! 在网关上提供 `/v1/chat/completions`、`/v1/models` 和 `/v1/embeddings`，
+//! IDE 插件、LangChain、评测工具等 OpenAI SDK 客户端可以直接接入。
+//!
+//! `model` 字段映射到已配置的 Agent（按 ID 或名称匹配），请求经由 Agent 处理，
+//! 记忆、工具与安全检查照常生效。调用方传入的 `tools` 会交给模型，
+//! 模型发起的工具调用以 `tool_calls` 返回，由调用方执行后再回传结果。
+//!
+//! 流式请求同样经由 Agent 处理，完成后按 `chat.completion.chunk` 格式以 SSE 推送。
+//! 会话按认证身份隔离，不同调用方即使传入相同的会话 ID 也互不可见。
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use base64::Engine;
+use openclaw_agent::TaskResult;
+use openclaw_agent::task::{TaskOutput, TaskStatus};
+use openclaw_ai::types::{EmbeddingRequest, FinishReason, StreamChunk, ToolDefinition};
+use openclaw_core::{Content, Message, Role};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use crate::api_auth::Principal;
+use crate::app_context::AppContext;
+use crate::orchestrator::ServiceOrchestrator;
+use crate::sse::result_string_stream_to_sse;
+
+/// 指定会话的请求头，未提供时按 `user` 字段或随机生成；实际会话 ID 按调用方身份加前缀
+const SESSION_HEADER: &str = "x-session-id";
+
+#[derive(Clone)]
+pub struct OpenAiApiState {
+    pub context: Arc<AppContext>,
+}
+
+pub fn create_openai_router(context: Arc<AppContext>) -> Router {
+    Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .route("/v1/models", get(list_models))
+        .route("/v1/models/{model}", get(get_model))
+        .route("/v1/embeddings", post(create_embeddings))
+        .with_state(OpenAiApiState { context })
+}
+
+/// OpenAI 格式的错误响应
+#[derive(Debug)]
+pub struct OpenAiError {
+    pub status: StatusCode,
+    pub message: String,
+    pub error_type: &'static str,
+    pub code: Option<&'static str>,
+}
+
+impl OpenAiError {
+    fn invalid_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+            error_type: "invalid_request_error",
+            code: None,
+        }
+    }
+
+    fn model_not_found(model: &str) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            message: format!("The model `{}` does not exist", model),
+            error_type: "invalid_request_error",
+            code: Some("model_not_found"),
+        }
+    }
+
+    fn server_error(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: message.into(),
+            error_type: "server_error",
+            code: None,
+        }
+    }
+
+    /// Agent 返回的失败原因，输入被安全检查拦截时按 `content_filter` 返回
+    fn agent_failed(error: String) -> Self {
+        if error.contains("Input blocked") {
+            return Self {
+                status: StatusCode::BAD_REQUEST,
+                message: error,
+                error_type: "invalid_request_error",
+                code: Some("content_filter"),
+            };
+        }
+        Self::server_error(error)
+    }
+
+    fn unavailable() -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            message: "No orchestrator available".to_string(),
+            error_type: "server_error",
+            code: None,
+        }
+    }
+}
+
+impl IntoResponse for OpenAiError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "error": {
+                "message": self.message,
+                "type": self.error_type,
+                "param": null,
+                "code": self.code,
+            }
+        });
+        (self.status, Json(body)).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatCompletionMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default)]
+    pub tools: Vec<ChatCompletionTool>,
+    #[serde(default)]
+    pub tool_choice: Option<serde_json::Value>,
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<MessageContent>,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallPayload>,
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+}
+
+/// 消息内容：纯文本或多模态内容块
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContentPart {
+    #[serde(rename = "type")]
+    pub part_type: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub image_url: Option<ImageUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageUrl {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallPayload {
+    pub id: String,
+    #[serde(rename = "type", default = "default_tool_type")]
+    pub call_type: String,
+    pub function: FunctionCallPayload,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCallPayload {
+    pub name: String,
+    /// JSON 编码的参数字符串
+    pub arguments: String,
+}
+
+fn default_tool_type() -> String {
+    "function".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionTool {
+    #[serde(rename = "type", default = "default_tool_type")]
+    pub tool_type: String,
+    pub function: FunctionSpec,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FunctionSpec {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChatCompletionResponse {
+    pub id: String,
+    pub object: &'static str,
+    pub created: i64,
+    pub model: String,
+    pub choices: Vec<ChatCompletionChoice>,
+    pub usage: CompletionUsage,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChatCompletionChoice {
+    pub index: usize,
+    pub message: AssistantMessage,
+    pub finish_reason: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssistantMessage {
+    pub role: &'static str,
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCallPayload>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CompletionUsage {
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub total_tokens: usize,
+}
+
+async fn chat_completions(
+    State(state): State<OpenAiApiState>,
+    Extension(principal): Extension<Principal>,
+    headers: HeaderMap,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Result<Response, OpenAiError> {
+    if request.messages.is_empty() {
+        return Err(OpenAiError::invalid_request("`messages` must not be empty"));
+    }
+
+    let guard = state.context.orchestrator.read().await;
+    let orchestrator = guard.as_ref().ok_or_else(OpenAiError::unavailable)?;
+    let agent_id = resolve_agent(orchestrator, &request.model)
+        .await
+        .ok_or_else(|| OpenAiError::model_not_found(&request.model))?;
+
+    let messages = request
+        .messages
+        .into_iter()
+        .map(to_core_message)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let tools_disabled = request
+        .tool_choice
+        .as_ref()
+        .and_then(|c| c.as_str())
+        .is_some_and(|c| c == "none");
+    let tools: Vec<ToolDefinition> = if tools_disabled {
+        Vec::new()
+    } else {
+        request
+            .tools
+            .into_iter()
+            .filter(|t| t.tool_type == "function")
+            .map(|t| ToolDefinition {
+                name: t.function.name,
+                description: t.function.description.unwrap_or_default(),
+                parameters: t
+                    .function
+                    .parameters
+                    .unwrap_or_else(|| serde_json::json!({ "type": "object", "properties": {} })),
+            })
+            .collect()
+    };
+
+    let session_id = scoped_session_id(
+        &principal,
+        headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok()),
+        request.user.as_deref(),
+    );
+    let id = format!("chatcmpl-{}", uuid::Uuid::new_v4().simple());
+    let created = chrono::Utc::now().timestamp();
+    // 对话记忆按认证身份隔离，未启用认证时按会话所属用户
+    let user_id = (!principal.is_anonymous()).then(|| principal.id.clone());
+
+    if request.stream {
+        let upstream = orchestrator
+            .stream_conversation(&agent_id, messages, tools, session_id, user_id)
+            .await
+            .map_err(|e| OpenAiError::agent_failed(e.to_string()))?;
+        let chunks = completion_chunks(id, created, request.model, upstream);
+        return Ok(result_string_stream_to_sse(chunks).into_response());
+    }
+
+    let result = orchestrator
+        .process_conversation(&agent_id, messages, tools, session_id, user_id)
+        .await
+        .map_err(|e| OpenAiError::server_error(e.to_string()))?;
+
+    if result.status == TaskStatus::Failed {
+        let error = result.error.unwrap_or_else(|| "Agent failed".to_string());
+        return Err(OpenAiError::agent_failed(error));
+    }
+
+    let usage = result
+        .tokens_used
+        .as_ref()
+        .map(|u| CompletionUsage {
+            prompt_tokens: u.prompt_tokens,
+            completion_tokens: u.completion_tokens,
+            total_tokens: u.total_tokens,
+        })
+        .unwrap_or_default();
+    let (content, tool_calls) = completion_output(result);
+    let finish_reason = if tool_calls.is_empty() {
+        "stop"
+    } else {
+        "tool_calls"
+    };
+
+    Ok(Json(ChatCompletionResponse {
+        id,
+        object: "chat.completion",
+        created,
+        model: request.model,
+        choices: vec![ChatCompletionChoice {
+            index: 0,
+            message: AssistantMessage {
+                role: "assistant",
+                content,
+                tool_calls,
+            },
+            finish_reason,
+        }],
+        usage,
+    })
+    .into_response())
+}
+
+/// 会话 ID 以调用方身份为前缀，调用方只能延续自己的会话
+fn scoped_session_id(principal: &Principal, requested: Option<&str>, user: Option<&str>) -> String {
+    let key = requested
+        .map(str::to_string)
+        .or_else(|| user.map(|u| format!("user-{}", u)))
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    format!("openai-{}-{}", principal.id, key)
+}
+
+/// 按 Agent ID 或名称匹配模型，允许 `openclaw/<agent>` 前缀
+async fn resolve_agent(orchestrator: &ServiceOrchestrator, model: &str) -> Option<String> {
+    let model = model.strip_prefix("openclaw/").unwrap_or(model);
+    let agents = orchestrator.list_agents().await;
+
+    agents
+        .iter()
+        .find(|a| a.config.id == model)
+        .or_else(|| {
+            agents
+                .iter()
+                .find(|a| a.config.name.eq_ignore_ascii_case(model))
+        })
+        .map(|a| a.config.id.clone())
+}
+
+fn to_core_message(message: ChatCompletionMessage) -> Result<Message, OpenAiError> {
+    let role = match message.role.as_str() {
+        "system" | "developer" => Role::System,
+        "user" => Role::User,
+        "assistant" => Role::Assistant,
+        "tool" => Role::Tool,
+        other => {
+            return Err(OpenAiError::invalid_request(format!(
+                "Unsupported message role: {}",
+                other
+            )));
+        }
+    };
+
+    let mut texts = Vec::new();
+    let mut content = Vec::new();
+    match message.content {
+        Some(MessageContent::Text(text)) => texts.push(text),
+        Some(MessageContent::Parts(parts)) => {
+            for part in parts {
+                match (part.part_type.as_str(), part.text, part.image_url) {
+                    ("text", Some(text), _) => texts.push(text),
+                    ("image_url", _, Some(image)) => {
+                        content.push(Content::Image { url: image.url })
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None => {}
+    }
+    let text = texts.join("\n");
+
+    if role == Role::Tool {
+        let id = message.tool_call_id.ok_or_else(|| {
+            OpenAiError::invalid_request("`tool_call_id` is required for tool messages")
+        })?;
+        return Ok(Message::new(
+            Role::Tool,
+            vec![Content::ToolResult { id, content: text }],
+        ));
+    }
+
+    if !text.is_empty() || message.tool_calls.is_empty() {
+        content.insert(0, Content::Text { text });
+    }
+    for call in message.tool_calls {
+        content.push(Content::ToolCall {
+            id: call.id,
+            name: call.function.name,
+            arguments: serde_json::from_str(&call.function.arguments)
+                .unwrap_or(serde_json::Value::String(call.function.arguments)),
+        });
+    }
+
+    Ok(Message::new(role, content))
+}
+
+/// 从 Agent 结果中取出回复文本与工具调用
+fn completion_output(result: TaskResult) -> (Option<String>, Vec<ToolCallPayload>) {
+    let message = match result.output {
+        Some(TaskOutput::Message { message }) => message,
+        Some(TaskOutput::Text { content }) => return (Some(content), Vec::new()),
+        Some(other) => return (serde_json::to_string(&other).ok(), Vec::new()),
+        None => return (None, Vec::new()),
+    };
+
+    let mut texts = Vec::new();
+    let mut tool_calls = Vec::new();
+    for c in message.content {
+        match c {
+            Content::Text { text } if !text.is_empty() => texts.push(text),
+            Content::ToolCall {
+                id,
+                name,
+                arguments,
+            } => tool_calls.push(ToolCallPayload {
+                id,
+                call_type: default_tool_type(),
+                function: FunctionCallPayload {
+                    name,
+                    arguments: match arguments {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    },
+                },
+            }),
+            _ => {}
+        }
+    }
+
+    let content = if texts.is_empty() {
+        None
+    } else {
+        Some(texts.join("\n"))
+    };
+    (content, tool_calls)
+}
+
+/// 单个 `chat.completion.chunk`
+fn completion_chunk(
+    id: &str,
+    created: i64,
+    model: &str,
+    delta: serde_json::Value,
+    finish_reason: Option<&str>,
+) -> String {
+    serde_json::json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "delta": delta,
+            "finish_reason": finish_reason,
+        }]
+    })
+    .to_string()
+}
+
+/// 提供商增量转换为 `chat.completion.chunk`，既无内容也未结束的增量返回 None
+fn delta_chunk(id: &str, created: i64, model: &str, chunk: &StreamChunk) -> Option<String> {
+    let mut delta = serde_json::Map::new();
+    if let Some(content) = chunk.delta.content.as_ref().filter(|c| !c.is_empty()) {
+        delta.insert("content".to_string(), serde_json::json!(content));
+    }
+    if !chunk.delta.tool_calls.is_empty() {
+        delta.insert(
+            "tool_calls".to_string(),
+            serde_json::json!(chunk.delta.tool_calls),
+        );
+    }
+    let finish_reason = chunk.finish_reason.as_ref().map(|reason| match reason {
+        FinishReason::Length => "length",
+        FinishReason::ToolCalls => "tool_calls",
+        FinishReason::ContentFilter => "content_filter",
+        FinishReason::Stop | FinishReason::Error => "stop",
+    });
+    if delta.is_empty() && finish_reason.is_none() {
+        return None;
+    }
+    Some(completion_chunk(
+        id,
+        created,
+        model,
+        serde_json::Value::Object(delta),
+        finish_reason,
+    ))
+}
+
+/// 生成 `chat.completion.chunk` 流：角色块、逐个增量，以 `[DONE]` 结尾
+fn completion_chunks(
+    id: String,
+    created: i64,
+    model: String,
+    upstream: impl futures::Stream<Item = openclaw_core::Result<StreamChunk>> + Send + 'static,
+) -> impl futures::Stream<Item = Result<String, Infallible>> + Send + 'static {
+    use futures::StreamExt;
+
+    let head = completion_chunk(
+        &id,
+        created,
+        &model,
+        serde_json::json!({ "role": "assistant", "content": "" }),
+        None,
+    );
+    let deltas = upstream.filter_map(move |chunk| {
+        let data = match chunk {
+            Ok(chunk) => delta_chunk(&id, created, &model, &chunk),
+            Err(e) => Some(
+                serde_json::json!({
+                    "error": { "message": e.to_string(), "type": "server_error" }
+                })
+                .to_string(),
+            ),
+        };
+        futures::future::ready(data.map(Ok))
+    });
+    futures::stream::once(futures::future::ready(Ok(head)))
+        .chain(deltas)
+        .chain(futures::stream::once(futures::future::ready(Ok(
+            "[DONE]".to_string()
+        ))))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelObject {
+    pub id: String,
+    pub object: &'static str,
+    pub created: i64,
+    pub owned_by: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelList {
+    pub object: &'static str,
+    pub data: Vec<ModelObject>,
+}
+
+async fn agent_models(state: &OpenAiApiState) -> Result<Vec<ModelObject>, OpenAiError> {
+    let guard = state.context.orchestrator.read().await;
+    let orchestrator = guard.as_ref().ok_or_else(OpenAiError::unavailable)?;
+    let created = chrono::Utc::now().timestamp();
+
+    Ok(orchestrator
+        .list_agents()
+        .await
+        .into_iter()
+        .map(|a| ModelObject {
+            id: a.config.id,
+            object: "model",
+            created,
+            owned_by: "openclaw",
+        })
+        .collect())
+}
+
+async fn list_models(State(state): State<OpenAiApiState>) -> Result<Json<ModelList>, OpenAiError> {
+    Ok(Json(ModelList {
+        object: "list",
+        data: agent_models(&state).await?,
+    }))
+}
+
+async fn get_model(
+    State(state): State<OpenAiApiState>,
+    Path(model): Path<String>,
+) -> Result<Json<ModelObject>, OpenAiError> {
+    agent_models(&state)
+        .await?
+        .into_iter()
+        .find(|m| m.id == model)
+        .map(Json)
+        .ok_or_else(|| OpenAiError::model_not_found(&model))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmbeddingsRequest {
+    pub model: String,
+    pub input: EmbeddingInput,
+    #[serde(default)]
+    pub encoding_format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    Single(String),
+    Batch(Vec<String>),
+}
+
+async fn create_embeddings(
+    State(state): State<OpenAiApiState>,
+    Json(request): Json<EmbeddingsRequest>,
+) -> Result<Json<serde_json::Value>, OpenAiError> {
+    let input = match request.input {
+        EmbeddingInput::Single(text) => vec![text],
+        EmbeddingInput::Batch(texts) => texts,
+    };
+    if input.is_empty() {
+        return Err(OpenAiError::invalid_request("`input` must not be empty"));
+    }
+    let base64 = match request.encoding_format.as_deref() {
+        None | Some("float") => false,
+        Some("base64") => true,
+        Some(other) => {
+            return Err(OpenAiError::invalid_request(format!(
+                "Unsupported encoding_format: {}",
+                other
+            )));
+        }
+    };
+
+    let response = state
+        .context
+        .ai_provider
+        .embed(EmbeddingRequest {
+            input,
+            model: request.model.clone(),
+        })
+        .await
+        .map_err(|e| OpenAiError::server_error(e.to_string()))?;
+
+    let data: Vec<_> = response
+        .embeddings
+        .iter()
+        .enumerate()
+        .map(|(index, embedding)| {
+            let embedding = if base64 {
+                serde_json::Value::String(encode_embedding(embedding))
+            } else {
+                serde_json::json!(embedding)
+            };
+            serde_json::json!({
+                "object": "embedding",
+                "index": index,
+                "embedding": embedding,
+            })
+        })
+        .collect();
+
+    let model = if response.model.is_empty() {
+        request.model
+    } else {
+        response.model
+    };
+
+    Ok(Json(serde_json::json!({
+        "object": "list",
+        "data": data,
+        "model": model,
+        "usage": {
+            "prompt_tokens": response.usage.prompt_tokens,
+            "total_tokens": response.usage.total_tokens,
+        }
+    })))
+}
+
+/// base64 编码的小端 f32 数组，与 OpenAI `encoding_format=base64` 一致
+fn encode_embedding(embedding: &[f32]) -> String {
+    let bytes: Vec<u8> = embedding.iter().flat_map(|v| v.to_le_bytes()).collect();
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_message(value: serde_json::Value) -> Result<Message, OpenAiError> {
+        to_core_message(serde_json::from_value(value).unwrap())
+    }
+
+    #[test]
+    fn test_to_core_message() {
+        let message = parse_message(serde_json::json!({
+            "role": "user",
+            "content": [
+                { "type": "text", "text": "what is this?" },
+                { "type": "image_url", "image_url": { "url": "https://example.com/a.png" } }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(message.text_content(), Some("what is this?"));
+        assert!(matches!(message.content[1], Content::Image { .. }));
+
+        let message = parse_message(serde_json::json!({
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" }
+            }]
+        }))
+        .unwrap();
+        assert!(matches!(
+            &message.content[0],
+            Content::ToolCall { name, arguments, .. }
+                if name == "get_weather" && arguments["city"] == "Paris"
+        ));
+
+        let message = parse_message(serde_json::json!({
+            "role": "tool",
+            "tool_call_id": "call_1",
+            "content": "sunny"
+        }))
+        .unwrap();
+        assert!(matches!(
+            &message.content[0],
+            Content::ToolResult { id, content } if id == "call_1" && content == "sunny"
+        ));
+
+        assert!(parse_message(serde_json::json!({ "role": "tool", "content": "x" })).is_err());
+        assert!(parse_message(serde_json::json!({ "role": "robot", "content": "x" })).is_err());
+    }
+
+    #[test]
+    fn test_completion_output_with_tool_calls() {
+        let mut message = Message::assistant("");
+        message.content.push(Content::ToolCall {
+            id: "call_1".to_string(),
+            name: "search".to_string(),
+            arguments: serde_json::json!({ "q": "rust" }),
+        });
+        let result = TaskResult::success(
+            uuid::Uuid::new_v4(),
+            "assistant".to_string(),
+            TaskOutput::Message { message },
+        );
+
+        let (content, tool_calls) = completion_output(result);
+        assert_eq!(content, None);
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls[0].function.arguments, r#"{"q":"rust"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_completion_chunks() {
+        use futures::StreamExt;
+        use openclaw_ai::types::{FunctionDelta, StreamDelta, ToolCallDelta};
+
+        let delta = |content: Option<&str>,
+                     tool_calls: Vec<ToolCallDelta>,
+                     finish_reason: Option<FinishReason>|
+         -> openclaw_core::Result<StreamChunk> {
+            Ok(StreamChunk {
+                id: String::new(),
+                model: String::new(),
+                delta: StreamDelta {
+                    role: None,
+                    content: content.map(str::to_string),
+                    tool_calls,
+                },
+                finished: finish_reason.is_some(),
+                finish_reason,
+            })
+        };
+        let call = ToolCallDelta {
+            index: 0,
+            id: Some("call_1".to_string()),
+            call_type: "function".to_string(),
+            function: Some(FunctionDelta {
+                name: Some("search".to_string()),
+                arguments: Some("{}".to_string()),
+            }),
+        };
+        let upstream = futures::stream::iter(vec![
+            delta(Some("hel"), Vec::new(), None),
+            delta(Some(""), Vec::new(), None),
+            delta(Some("lo"), Vec::new(), None),
+            delta(None, vec![call], None),
+            delta(None, Vec::new(), Some(FinishReason::ToolCalls)),
+        ]);
+
+        let chunks: Vec<String> =
+            completion_chunks("chatcmpl-1".into(), 0, "assistant".into(), upstream)
+                .map(|c| c.unwrap())
+                .collect()
+                .await;
+        assert_eq!(chunks.len(), 6);
+        assert_eq!(chunks.last().map(String::as_str), Some("[DONE]"));
+
+        let parsed: Vec<serde_json::Value> = chunks[..5]
+            .iter()
+            .map(|c| serde_json::from_str(c).unwrap())
+            .collect();
+        assert_eq!(parsed[0]["choices"][0]["delta"]["role"], "assistant");
+        assert_eq!(parsed[1]["choices"][0]["delta"]["content"], "hel");
+        assert_eq!(parsed[2]["choices"][0]["delta"]["content"], "lo");
+        assert_eq!(
+            parsed[3]["choices"][0]["delta"]["tool_calls"][0]["function"]["name"],
+            "search"
+        );
+        assert_eq!(parsed[4]["object"], "chat.completion.chunk");
+        assert_eq!(parsed[4]["choices"][0]["finish_reason"], "tool_calls");
+    }
+
+    #[test]
+    fn test_session_id_is_scoped_to_principal() {
+        let principal = |id: &str| Principal {
+            id: id.to_string(),
+            name: id.to_string(),
+            permissions: Default::default(),
+            rate_limit_per_minute: None,
+        };
+        let (alice, bob) = (principal("key:alice"), principal("key:bob"));
+
+        let a = scoped_session_id(&alice, Some("shared"), None);
+        assert_ne!(a, scoped_session_id(&bob, Some("shared"), None));
+        assert_eq!(a, scoped_session_id(&alice, Some("shared"), Some("u1")));
+        assert_eq!(
+            scoped_session_id(&alice, None, Some("u1")),
+            "openai-key:alice-user-u1"
+        );
+    }
+
+    #[test]
+    fn test_encode_embedding() {
+        let encoded = encode_embedding(&[1.0, -0.5]);
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap();
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(f32::from_le_bytes(bytes[4..8].try_into().unwrap()), -0.5);
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use uuid::Uuid;
@@ -8,19 +9,29 @@ use async_trait::async_trait;
 use std::collections::VecDeque;
 
 use openclaw_agent::aieos::{AIEOSParser, AIEOSPromptGenerator};
-use openclaw_agent::sessions::{MemorySessionStorage, SessionManager};
+use openclaw_agent::memory::ConversationMemory;
+use openclaw_agent::session_tree::{BranchDiff, BranchInfo, SessionMessage, SessionNode};
+use openclaw_agent::sessions::{
+    MemorySessionStorage, SessionManager, SessionStorage, SqliteSessionStorage,
+};
 use openclaw_agent::task::TaskOutput;
 use openclaw_agent::task::{TaskInput, TaskRequest, TaskType};
-use openclaw_agent::{Agent, AgentConfig as OpenclawAgentConfig, AgentInfo, AgentType, BaseAgent};
+use openclaw_agent::{
+    Agent, AgentConfig as OpenclawAgentConfig, AgentInfo, AgentStepSender, AgentType, BaseAgent,
+};
 use openclaw_ai::AIProvider;
-use openclaw_canvas::CanvasManager;
-use openclaw_channels::{ChannelManager, ChannelMessage, SendMessage, register_channels_from_config};
+use openclaw_canvas::{CanvasManager, CanvasStorageConfig, CollabManager};
+use openclaw_channels::{
+    ChannelManager, ChannelMessage, IdentityManager, SendMessage, register_channels_from_config,
+};
 use openclaw_core::{Config, Content, Message, OpenClawError, Result, Role};
 
 use openclaw_memory::factory::{MemoryBackend, HybridMemoryBackend};
 use openclaw_memory::MemoryConfig;
 use openclaw_memory::MemoryManager;
 use openclaw_security::SecurityPipeline;
+use openclaw_ai::types::{FinishReason, FunctionDelta, StreamChunk, StreamDelta, ToolCallDelta};
+use tokio_stream::wrappers::ReceiverStream;
 
 use crate::acp_service::AcpService;
 
@@ -59,10 +70,18 @@ impl MemoryBackend for MemoryManagerAdapter {
         let manager = self.manager.lock().await;
         manager.retrieve(query, limit).await
     }
+
+    async fn forget(
+        &self,
+        target: openclaw_memory::privacy::ForgetTarget,
+    ) -> openclaw_core::Result<openclaw_memory::privacy::ForgetReceipt> {
+        let mut manager = self.manager.lock().await;
+        manager.forget(target).await
+    }
 }
 
 use crate::agentic_rag::AgenticRAGEngine;
-use crate::channel_message_handler::{self, create_channel_handler, create_channel_handler_with_acp, OrchestratorMessageProcessor};
+use crate::channel_message_handler::{self, ChannelMessageHandler, OrchestratorMessageProcessor};
 use crate::adapters::ToolRegistryAdapter;
 use crate::ports::{AiPortAdapter, DevicePortAdapter, MemoryPortAdapter, SecurityPortAdapter};
 
@@ -136,10 +155,12 @@ pub struct ServiceOrchestrator {
     running: Arc<RwLock<bool>>,
     ai_provider: Arc<RwLock<Option<Arc<dyn AIProvider>>>>,
     memory_manager: Arc<RwLock<Option<Arc<MemoryManager>>>>,
+    conversation_memory: Arc<RwLock<Option<Arc<ConversationMemory>>>>,
     security_pipeline: Arc<RwLock<Option<Arc<SecurityPipeline>>>>,
     tool_executor: Arc<RwLock<Option<Arc<openclaw_tools::ToolRegistry>>>>,
     channel_factory: Arc<openclaw_channels::ChannelFactoryRegistry>,
     agentic_rag_engine: Arc<RwLock<Option<Arc<AgenticRAGEngine>>>>,
+    identity_manager: Arc<RwLock<Option<Arc<IdentityManager>>>>,
     device_manager: Arc<RwLock<Option<Arc<openclaw_device::UnifiedDeviceManager>>>>,
     evolution_engine: Arc<RwLock<Option<Arc<openclaw_agent::EvolutionEngine>>>>,
     shared_skill_registry: Arc<RwLock<Option<Arc<openclaw_agent::evo::registry::SharedSkillRegistry>>>>,
@@ -170,12 +191,46 @@ impl Default for ChannelServiceState {
 #[derive(Clone)]
 pub struct CanvasServiceState {
     pub manager: Arc<CanvasManager>,
+    pub collab: Arc<CollabManager>,
+    /// 定时批量保存任务，停止时中止
+    autosave: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+}
+
+impl CanvasServiceState {
+    pub fn from_config(config: &CanvasStorageConfig) -> Self {
+        let manager = CanvasManager::from_config(config).unwrap_or_else(|e| {
+            tracing::warn!("Failed to create canvas storage, falling back to memory: {}", e);
+            CanvasManager::new()
+        });
+        Self {
+            manager: Arc::new(manager),
+            collab: Arc::new(CollabManager::new()),
+            autosave: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 启动定时保存，已在运行的任务先中止，重复启动不会产生多个任务
+    pub async fn start_autosave(&self) {
+        let mut autosave = self.autosave.lock().await;
+        if let Some(handle) = autosave.take() {
+            handle.abort();
+        }
+        *autosave = self.manager.start_autosave();
+    }
+
+    pub async fn stop_autosave(&self) {
+        if let Some(handle) = self.autosave.lock().await.take() {
+            handle.abort();
+        }
+    }
 }
 
 impl Default for CanvasServiceState {
     fn default() -> Self {
         Self {
             manager: Arc::new(CanvasManager::new()),
+            collab: Arc::new(CollabManager::new()),
+            autosave: Arc::new(Mutex::new(None)),
         }
     }
 }
@@ -207,10 +262,15 @@ pub struct OrchestratorConfig {
     pub enable_canvas: bool,
     pub default_agent: Option<String>,
     pub channel_to_agent_map: HashMap<String, String>,
+    /// 通道回复是否流式输出
+    pub stream_channel_replies: bool,
     pub agent_to_canvas_map: HashMap<String, String>,
+    pub canvas_storage: CanvasStorageConfig,
     pub channel_configs: Option<openclaw_channels::ChannelConfigs>,
     pub enable_evolution: bool,
     pub evolution_model: Option<String>,
+    /// 会话数据库路径，设置后会话及其分支树持久化到 SQLite，否则只保存在内存中
+    pub session_db: Option<PathBuf>,
     #[cfg(feature = "per_session_memory")]
     pub enable_per_session_memory: bool,
     #[cfg(feature = "per_session_memory")]
@@ -228,10 +288,13 @@ impl Default for OrchestratorConfig {
             enable_canvas: false,
             default_agent: Some("orchestrator".to_string()),
             channel_to_agent_map: HashMap::new(),
+            stream_channel_replies: false,
             agent_to_canvas_map: HashMap::new(),
+            canvas_storage: CanvasStorageConfig::default(),
             channel_configs: None,
             enable_evolution: false,
             evolution_model: None,
+            session_db: None,
             #[cfg(feature = "per_session_memory")]
             enable_per_session_memory: false,
             #[cfg(feature = "per_session_memory")]
@@ -244,7 +307,17 @@ impl Default for OrchestratorConfig {
 
 impl ServiceOrchestrator {
     pub fn new(config: OrchestratorConfig) -> Self {
-        let storage = Arc::new(MemorySessionStorage::new());
+        let storage: Arc<dyn SessionStorage> = match &config.session_db {
+            Some(path) => {
+                if let Some(parent) = path.parent()
+                    && let Err(e) = std::fs::create_dir_all(parent)
+                {
+                    tracing::warn!("Failed to create session db directory {:?}: {}", parent, e);
+                }
+                Arc::new(SqliteSessionStorage::new(path.clone()))
+            }
+            None => Arc::new(MemorySessionStorage::new()),
+        };
         let session_manager = SessionManager::new(storage);
 
         let channel_factory = Arc::new(openclaw_channels::ChannelFactoryRegistry::new());
@@ -279,16 +352,18 @@ impl ServiceOrchestrator {
                 manager: Arc::new(RwLock::new(channel_manager)),
                 factory: channel_factory_for_service,
             },
-            canvas_service: CanvasServiceState::default(),
+            canvas_service: CanvasServiceState::from_config(&config.canvas_storage),
             session_service: SessionServiceState::new(session_manager),
             config: config.clone(),
             running: Arc::new(RwLock::new(false)),
             ai_provider: Arc::new(RwLock::new(None)),
             memory_manager: Arc::new(RwLock::new(None)),
+            conversation_memory: Arc::new(RwLock::new(None)),
             security_pipeline: Arc::new(RwLock::new(None)),
             tool_executor: Arc::new(RwLock::new(None)),
             channel_factory,
             agentic_rag_engine: Arc::new(RwLock::new(None)),
+            identity_manager: Arc::new(RwLock::new(None)),
             device_manager: Arc::new(RwLock::new(None)),
             evolution_engine,
             shared_skill_registry,
@@ -311,9 +386,7 @@ impl ServiceOrchestrator {
                 tracing::warn!("No channel configs provided, channels will not be registered");
             }
 
-            let handler = create_channel_handler(Arc::new(OrchestratorMessageProcessor {
-                orchestrator: Arc::new(self.clone()),
-            }));
+            let handler = self.channel_handler(None).await;
             self.channel_service
                 .manager
                 .read()
@@ -329,11 +402,31 @@ impl ServiceOrchestrator {
                 .await?;
         }
 
+        if self.config.enable_canvas {
+            self.canvas_service.start_autosave().await;
+        }
+
         *self.running.write().await = true;
         tracing::info!("ServiceOrchestrator started");
         Ok(())
     }
 
+    async fn channel_handler(
+        &self,
+        acp_service: Option<Arc<AcpService>>,
+    ) -> Arc<dyn openclaw_channels::ChannelHandler> {
+        let mut handler = ChannelMessageHandler::new(Arc::new(OrchestratorMessageProcessor {
+            orchestrator: Arc::new(self.clone()),
+        }));
+        if let Some(acp_service) = acp_service {
+            handler = handler.with_acp_service(acp_service);
+        }
+        if let Some(identities) = self.get_identity_manager().await {
+            handler = handler.with_identity_manager(identities);
+        }
+        Arc::new(handler)
+    }
+
     pub async fn start_with_acp(&self, acp_service: Arc<AcpService>) -> Result<()> {
         if self.config.enable_agents {
             self.init_default_agents().await?;
@@ -346,12 +439,7 @@ impl ServiceOrchestrator {
                 tracing::warn!("No channel configs provided, channels will not be registered");
             }
 
-            let handler = create_channel_handler_with_acp(
-                Arc::new(OrchestratorMessageProcessor {
-                    orchestrator: Arc::new(self.clone()),
-                }),
-                acp_service,
-            );
+            let handler = self.channel_handler(Some(acp_service)).await;
             self.channel_service
                 .manager
                 .read()
@@ -367,6 +455,10 @@ impl ServiceOrchestrator {
                 .await?;
         }
 
+        if self.config.enable_canvas {
+            self.canvas_service.start_autosave().await;
+        }
+
         *self.running.write().await = true;
         tracing::info!("ServiceOrchestrator started with ACP enabled");
         Ok(())
@@ -379,6 +471,14 @@ impl ServiceOrchestrator {
             self.channel_service.manager.read().await.stop_all().await?;
         }
 
+        if self.config.enable_canvas {
+            // 先停定时保存，避免与最后一次落盘并发
+            self.canvas_service.stop_autosave().await;
+            if let Err(e) = self.canvas_service.manager.flush().await {
+                tracing::warn!("Failed to flush canvases on stop: {}", e);
+            }
+        }
+
         tracing::info!("ServiceOrchestrator stopped");
         Ok(())
     }
@@ -387,7 +487,18 @@ impl ServiceOrchestrator {
         *self.running.read().await
     }
 
+    /// 设置所有智能体共享的对话记忆，之后注册的智能体同样生效
+    pub async fn set_conversation_memory(&self, memory: Arc<ConversationMemory>) {
+        *self.conversation_memory.write().await = Some(memory.clone());
+        let agents: Vec<Arc<dyn Agent>> =
+            self.agent_service.agents.read().await.values().cloned().collect();
+        for agent in agents {
+            agent.set_conversation_memory(memory.clone()).await;
+        }
+    }
+
     async fn init_default_agents(&self) -> Result<()> {
+        let conversation_memory = self.conversation_memory.read().await.clone();
         let mut agents = self.agent_service.agents.write().await;
 
         let orchestrator = Arc::new(BaseAgent::orchestrator()) as Arc<dyn Agent>;
@@ -402,11 +513,21 @@ impl ServiceOrchestrator {
         let writer = Arc::new(BaseAgent::writer()) as Arc<dyn Agent>;
         agents.insert("writer".to_string(), writer);
 
+        if let Some(memory) = conversation_memory {
+            for agent in agents.values() {
+                agent.set_conversation_memory(memory.clone()).await;
+            }
+        }
+
         tracing::info!("Default agents initialized");
         Ok(())
     }
 
     pub async fn register_agent(&self, id: String, agent: Arc<dyn Agent>) {
+        if let Some(memory) = self.conversation_memory.read().await.clone() {
+            agent.set_conversation_memory(memory).await;
+        }
+
         let (should_inject, agent_to_inject) = {
             let mut agents = self.agent_service.agents.write().await;
 
@@ -613,6 +734,15 @@ impl ServiceOrchestrator {
         *e = Some(engine);
     }
 
+    pub async fn get_identity_manager(&self) -> Option<Arc<IdentityManager>> {
+        self.identity_manager.read().await.clone()
+    }
+
+    /// 设置跨通道身份管理器，需在 `start` 之前调用才会作用于通道消息
+    pub async fn set_identity_manager(&self, manager: Arc<IdentityManager>) {
+        *self.identity_manager.write().await = Some(manager);
+    }
+
     pub async fn list_sessions(
         &self,
         agent_id: Option<&str>,
@@ -625,13 +755,35 @@ impl ServiceOrchestrator {
             .await
     }
 
+    /// 列出会话及其分支树
+    pub async fn list_sessions_with_branches(
+        &self,
+        agent_id: Option<&str>,
+        state: Option<openclaw_agent::sessions::SessionState>,
+    ) -> openclaw_agent::Result<Vec<(openclaw_agent::sessions::Session, Vec<BranchInfo>)>> {
+        self.session_service
+            .manager
+            .list_sessions_with_branches(agent_id.map(|s| s.to_string()), state)
+            .await
+    }
+
+    /// 创建会话；指定 `owner` 时仅其本人和管理员可访问
     pub async fn create_session(
         &self,
         name: String,
         agent_id: String,
         scope: openclaw_core::session::SessionScope,
         channel_type: Option<String>,
+        owner: Option<&str>,
     ) -> openclaw_agent::Result<openclaw_agent::sessions::Session> {
+        if let Some(owner) = owner {
+            return self
+                .session_service
+                .manager
+                .create_owned_session(owner, name, agent_id, scope, channel_type)
+                .await;
+        }
+
         let peer_id = match &scope {
             openclaw_core::session::SessionScope::Main => None,
             openclaw_core::session::SessionScope::PerPeer => None,
@@ -659,6 +811,74 @@ impl ServiceOrchestrator {
         self.session_service.manager.close_session(&uuid).await
     }
 
+    pub async fn list_session_branches(
+        &self,
+        session_id: &str,
+    ) -> openclaw_agent::Result<Vec<BranchInfo>> {
+        let session_id = parse_id("session", session_id)?;
+        self.session_service.manager.list_branches(&session_id).await
+    }
+
+    pub async fn session_transcript(
+        &self,
+        session_id: &str,
+        branch_id: &str,
+    ) -> openclaw_agent::Result<Vec<SessionMessage>> {
+        let session_id = parse_id("session", session_id)?;
+        let branch_id = parse_id("branch", branch_id)?;
+        self.session_service
+            .manager
+            .branch_transcript(&session_id, &branch_id)
+            .await
+    }
+
+    /// 在某条消息处分叉会话，用于“从这里编辑重试”
+    pub async fn fork_session(
+        &self,
+        session_id: &str,
+        branch_id: Option<&str>,
+        message_id: &str,
+        name: Option<String>,
+    ) -> openclaw_agent::Result<SessionNode> {
+        let session_id = parse_id("session", session_id)?;
+        let branch_id = branch_id.map(|id| parse_id("branch", id)).transpose()?;
+        let message_id = parse_id("message", message_id)?;
+        let name = name.unwrap_or_else(|| format!("fork-{}", &message_id.to_string()[..8]));
+        self.session_service
+            .manager
+            .fork_session(&session_id, branch_id, message_id, name)
+            .await
+    }
+
+    pub async fn diff_session_branches(
+        &self,
+        session_id: &str,
+        left: &str,
+        right: &str,
+    ) -> openclaw_agent::Result<BranchDiff> {
+        let session_id = parse_id("session", session_id)?;
+        let left = parse_id("branch", left)?;
+        let right = parse_id("branch", right)?;
+        self.session_service
+            .manager
+            .diff_branches(&session_id, &left, &right)
+            .await
+    }
+
+    pub async fn merge_session_branch(
+        &self,
+        session_id: &str,
+        branch_id: &str,
+        summary: Option<String>,
+    ) -> openclaw_agent::Result<SessionNode> {
+        let session_id = parse_id("session", session_id)?;
+        let branch_id = parse_id("branch", branch_id)?;
+        self.session_service
+            .manager
+            .merge_branch(&session_id, &branch_id, summary)
+            .await
+    }
+
     #[cfg(feature = "per_session_memory")]
     pub async fn get_session_memory(&self, session_id: &Uuid) -> Option<Arc<MemoryManager>> {
         if !self.config.enable_per_session_memory {
@@ -709,30 +929,113 @@ impl ServiceOrchestrator {
         agent_id: &str,
         message: &str,
         session_id: &str,
+        branch_id: Option<&str>,
     ) -> Result<String> {
-        self.process_message(agent_id, message.to_string(), Some(session_id.to_string()))
+        self.process_session_message(agent_id, message.to_string(), session_id, branch_id, None)
             .await
     }
 
+    /// 处理会话消息，问答写回会话的分支树
+    ///
+    /// 指定 `branch_id` 时以该分支的对话作为上下文，问答追加到该分支；
+    /// 未持久化的会话 ID 按普通消息处理，不能指定分支。
+    pub async fn process_session_message(
+        &self,
+        agent_id: &str,
+        message: String,
+        session_id: &str,
+        branch_id: Option<&str>,
+        steps: Option<AgentStepSender>,
+    ) -> Result<String> {
+        let session = match Uuid::parse_str(session_id) {
+            Ok(id) => self.session_service.manager.get_session(&id).await?,
+            Err(_) => None,
+        };
+        let Some(session) = session else {
+            if branch_id.is_some() {
+                return Err(OpenClawError::Session(format!(
+                    "Session {} not found",
+                    session_id
+                )));
+            }
+            return self
+                .process_message_with_steps(agent_id, message, Some(session_id.to_string()), steps)
+                .await;
+        };
+
+        let manager = &self.session_service.manager;
+        let branch_id = branch_id.map(|id| parse_id("branch", id)).transpose()?;
+        let context = match branch_id.filter(|id| *id != session.id) {
+            Some(branch_id) => manager
+                .branch_transcript(&session.id, &branch_id)
+                .await?
+                .iter()
+                .filter(|m| m.role != "tool")
+                .map(transcript_message)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let message = Message::user(message);
+        let reply = self
+            .run_message(agent_id, message.clone(), context, Some(session_id.to_string()), steps)
+            .await?;
+        manager
+            .append_messages(
+                &session.id,
+                branch_id,
+                &[message, Message::assistant(reply.clone())],
+            )
+            .await?;
+        Ok(reply)
+    }
+
     pub async fn process_message(
         &self,
         agent_id: &str,
         message: String,
         session_id: Option<String>,
+    ) -> Result<String> {
+        self.process_message_with_steps(agent_id, message, session_id, None)
+            .await
+    }
+
+    /// 处理消息，工具循环的中间步骤推送到 `steps`
+    pub async fn process_message_with_steps(
+        &self,
+        agent_id: &str,
+        message: String,
+        session_id: Option<String>,
+        steps: Option<AgentStepSender>,
+    ) -> Result<String> {
+        let msg = Message::new(Role::User, vec![Content::Text { text: message }]);
+        self.run_message(agent_id, msg, Vec::new(), session_id, steps)
+            .await
+    }
+
+    async fn run_message(
+        &self,
+        agent_id: &str,
+        msg: Message,
+        context: Vec<Message>,
+        session_id: Option<String>,
+        steps: Option<AgentStepSender>,
     ) -> Result<String> {
         let agent = self
             .get_agent(agent_id)
             .await
             .ok_or_else(|| OpenClawError::Config(format!("Agent not found: {}", agent_id)))?;
 
-        let msg = Message::new(Role::User, vec![Content::Text { text: message }]);
-
-        let task = TaskRequest::new(TaskType::Conversation, TaskInput::Message { message: msg })
+        let mut task = TaskRequest::new(TaskType::Conversation, TaskInput::Message { message: msg })
+            .with_context(context)
             .with_session_id(
                 session_id
                     .clone()
                     .unwrap_or_else(|| format!("agent-{}", agent_id)),
             );
+        if let Some(owner) = self.session_owner(session_id.as_deref()).await {
+            task = task.with_user_id(owner);
+        }
 
         #[cfg(feature = "per_session_memory")]
         {
@@ -772,7 +1075,7 @@ impl ServiceOrchestrator {
             }
         }
 
-        let result = agent.process(task.clone()).await?;
+        let result = run_task(agent.as_ref(), task.clone(), steps.clone()).await?;
 
         if result.status == openclaw_agent::task::TaskStatus::Failed {
             if let Some(ref error) = result.error {
@@ -780,7 +1083,7 @@ impl ServiceOrchestrator {
                     if let Some(evo_result) = self.try_evolution(agent_id, &task).await {
                         if evo_result {
                             tracing::info!("Evolution successful, retrying task");
-                            let retry_result = agent.process(task).await?;
+                            let retry_result = run_task(agent.as_ref(), task, steps).await?;
                             return self.extract_output(retry_result).await;
                         }
                     }
@@ -791,6 +1094,85 @@ impl ServiceOrchestrator {
         self.extract_output(result).await
     }
 
+    /// 以完整对话历史驱动 Agent，最后一条消息作为输入，其余作为上下文
+    ///
+    /// 返回原始任务结果，调用方可从中取出工具调用与 token 用量。
+    /// `user_id` 为空时按会话所属用户隔离对话记忆。
+    pub async fn process_conversation(
+        &self,
+        agent_id: &str,
+        messages: Vec<Message>,
+        tools: Vec<openclaw_ai::types::ToolDefinition>,
+        session_id: String,
+        user_id: Option<String>,
+    ) -> Result<openclaw_agent::TaskResult> {
+        let (agent, task) = self
+            .conversation_task(agent_id, messages, tools, session_id, user_id)
+            .await?;
+        agent.process(task).await
+    }
+
+    /// 与 `process_conversation` 相同地经由 Agent 处理，结果以增量返回
+    ///
+    /// 记忆、服务端工具和安全检查照常生效；Agent 完成后，回答文本和交回调用方的工具调用
+    /// 作为一个增量推送，任务失败时推送错误。
+    pub async fn stream_conversation(
+        &self,
+        agent_id: &str,
+        messages: Vec<Message>,
+        tools: Vec<openclaw_ai::types::ToolDefinition>,
+        session_id: String,
+        user_id: Option<String>,
+    ) -> Result<ReceiverStream<Result<StreamChunk>>> {
+        let (agent, task) = self
+            .conversation_task(agent_id, messages, tools, session_id, user_id)
+            .await?;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            let chunk = agent.process(task).await.and_then(result_chunk);
+            let _ = tx.send(chunk).await;
+        });
+        Ok(ReceiverStream::new(rx))
+    }
+
+    async fn conversation_task(
+        &self,
+        agent_id: &str,
+        mut messages: Vec<Message>,
+        tools: Vec<openclaw_ai::types::ToolDefinition>,
+        session_id: String,
+        user_id: Option<String>,
+    ) -> Result<(Arc<dyn Agent>, TaskRequest)> {
+        let agent = self
+            .get_agent(agent_id)
+            .await
+            .ok_or_else(|| OpenClawError::Config(format!("Agent not found: {}", agent_id)))?;
+
+        let message = messages
+            .pop()
+            .ok_or_else(|| OpenClawError::Config("messages must not be empty".to_string()))?;
+
+        let mut task = TaskRequest::new(TaskType::Conversation, TaskInput::Message { message })
+            .with_context(messages)
+            .with_tools(tools);
+        let user_id = match user_id {
+            Some(user_id) => Some(user_id),
+            None => self.session_owner(Some(&session_id)).await,
+        };
+        if let Some(user_id) = user_id {
+            task = task.with_user_id(user_id);
+        }
+
+        Ok((agent, task.with_session_id(session_id)))
+    }
+
+    /// 会话所属用户，对话记忆按该用户隔离
+    async fn session_owner(&self, session_id: Option<&str>) -> Option<String> {
+        let session = self.get_session(session_id?).await.ok().flatten()?;
+        session.owner().map(str::to_string)
+    }
+
     async fn extract_output(&self, result: openclaw_agent::TaskResult) -> Result<String> {
         let output = match result.output {
             Some(TaskOutput::Message { message }) => message
@@ -857,16 +1239,31 @@ impl ServiceOrchestrator {
         channel_name: &str,
         message: String,
     ) -> Result<ChannelMessage> {
-        let agent_id = self
-            .config
-            .channel_to_agent_map
-            .get(channel_name)
-            .cloned()
-            .or_else(|| self.config.default_agent.clone())
-            .ok_or_else(|| OpenClawError::Config("No agent configured".to_string()))?;
+        self.process_channel_message_in_session(channel_name, channel_name, message)
+            .await
+    }
+
+    /// 处理已识别用户的通道消息，会话与记忆按统一用户 ID 区分，跨通道延续
+    pub async fn process_user_channel_message(
+        &self,
+        channel_name: &str,
+        user_id: &str,
+        message: String,
+    ) -> Result<ChannelMessage> {
+        self.process_channel_message_in_session(channel_name, user_id, message)
+            .await
+    }
+
+    async fn process_channel_message_in_session(
+        &self,
+        channel_name: &str,
+        session_id: &str,
+        message: String,
+    ) -> Result<ChannelMessage> {
+        let agent_id = self.channel_agent_id(channel_name)?;
 
         let response = self
-            .process_message(&agent_id, message, Some(channel_name.to_string()))
+            .process_message(&agent_id, message, Some(session_id.to_string()))
             .await?;
 
         let channel_msg = ChannelMessage {
@@ -877,11 +1274,67 @@ impl ServiceOrchestrator {
             content: response,
             timestamp: chrono::Utc::now(),
             metadata: None,
+            thread_id: None,
+            reply_to: None,
         };
 
         Ok(channel_msg)
     }
 
+    /// 通道映射的 Agent，未映射时使用默认 Agent
+    fn channel_agent_id(&self, channel_name: &str) -> Result<String> {
+        self.config
+            .channel_to_agent_map
+            .get(channel_name)
+            .cloned()
+            .or_else(|| self.config.default_agent.clone())
+            .ok_or_else(|| OpenClawError::Config("No agent configured".to_string()))
+    }
+
+    /// 流式处理通道消息，未开启 `stream_channel_replies` 时返回 None
+    ///
+    /// 与 `process_chat_stream` 一样直接从 AI 提供商取增量，使用 Agent 的系统提示词与模型。
+    pub async fn stream_channel_message(
+        &self,
+        channel_name: &str,
+        message: String,
+    ) -> Result<Option<openclaw_channels::TextStream>> {
+        use futures::StreamExt;
+        use openclaw_ai::types::ChatRequest;
+
+        if !self.config.stream_channel_replies {
+            return Ok(None);
+        }
+
+        let agent_id = self.channel_agent_id(channel_name)?;
+        let agent = self
+            .get_agent(&agent_id)
+            .await
+            .ok_or_else(|| OpenClawError::Config(format!("Agent not found: {}", agent_id)))?;
+        let Some(ai_port) = self.ai_provider.read().await.clone() else {
+            return Ok(None);
+        };
+
+        let agent_config = agent.info().config;
+        let mut messages = Vec::new();
+        if let Some(prompt) = agent_config.system_prompt {
+            messages.push(Message::new(Role::System, vec![Content::Text { text: prompt }]));
+        }
+        messages.push(Message::new(Role::User, vec![Content::Text { text: message }]));
+
+        let model = agent_config.model.unwrap_or_else(|| "default".to_string());
+        let request = ChatRequest::new(model, messages).with_stream(true);
+        let stream = ai_port.chat_stream(request).await?;
+
+        let deltas = stream.filter_map(|chunk| async move {
+            match chunk {
+                Ok(chunk) => chunk.delta.content.filter(|c| !c.is_empty()).map(Ok),
+                Err(e) => Some(Err(e)),
+            }
+        });
+        Ok(Some(Box::pin(deltas)))
+    }
+
     pub async fn process_chat_stream(
         &self,
         agent_id: &str,
@@ -996,6 +1449,71 @@ impl ServiceOrchestrator {
         Ok(())
     }
 
+    /// 热加载通道配置
+    ///
+    /// 先按新配置创建所有需要重建的实例，任一失败时返回错误且不改动运行中的通道。
+    /// 之后移除的通道先停止再注销；新增或修改的通道重新注册创建器，已有实例替换为新实例。
+    /// 新实例启动失败时恢复旧实例，不影响正在运行的通道。
+    pub async fn apply_channel_changes(
+        &self,
+        removed: &[String],
+        changed: &openclaw_channels::ChannelConfigs,
+    ) -> Result<()> {
+        if !self.config.enable_channels {
+            tracing::warn!("Channels are disabled, skipping channel reload");
+            return Ok(());
+        }
+
+        let manager = self.channel_service.manager.read().await;
+
+        // 在独立的注册表中创建新实例，校验通过前不替换正在使用的创建器
+        let staging = openclaw_channels::ChannelFactoryRegistry::new();
+        register_channels_from_config(&staging, changed).await;
+        let mut replacements = Vec::new();
+        for (name, entry) in &changed.0 {
+            let Some(old) = manager.get_channel(name).await else {
+                continue;
+            };
+            let new = staging
+                .create(&entry.channel_type, entry.config.clone())
+                .await
+                .map_err(|e| {
+                    OpenClawError::Config(format!("Invalid config for channel {}: {}", name, e))
+                })?;
+            replacements.push((name, old, new));
+        }
+
+        for name in removed {
+            if let Some(channel) = manager.get_channel(name).await
+                && let Err(e) = channel.write().await.stop().await
+            {
+                tracing::warn!("Failed to stop channel {}: {}", name, e);
+            }
+            manager.unregister_channel(name).await;
+            self.channel_service.factory.unregister(name).await;
+            tracing::info!("Channel {} removed by config reload", name);
+        }
+
+        register_channels_from_config(&self.channel_service.factory, changed).await;
+
+        for (name, old, new) in replacements {
+            if let Err(e) = old.write().await.stop().await {
+                tracing::warn!("Failed to stop channel {}: {}", name, e);
+            }
+            if let Err(e) = new.write().await.start().await {
+                tracing::warn!("Failed to start reloaded channel {}, restoring: {}", name, e);
+                if let Err(e) = old.write().await.start().await {
+                    tracing::error!("Failed to restore channel {}: {}", name, e);
+                }
+                continue;
+            }
+            manager.register_channel(name.clone(), new).await;
+            tracing::info!("Channel {} reloaded", name);
+        }
+
+        Ok(())
+    }
+
     pub async fn health_check(&self) -> HashMap<String, bool> {
         let mut health = HashMap::new();
 
@@ -1029,6 +1547,10 @@ impl ServiceOrchestrator {
         self.canvas_service.manager.clone()
     }
 
+    pub fn collab_manager(&self) -> Arc<CollabManager> {
+        self.canvas_service.collab.clone()
+    }
+
     pub fn canvas_service(&self) -> &CanvasServiceState {
         &self.canvas_service
     }
@@ -1075,46 +1597,148 @@ impl ServiceOrchestrator {
     }
 
     pub async fn init_agents_from_config(&self, config: &crate::server_config::ServerConfig) -> Result<()> {
-        let agents_config = &config.agents;
+        self.reload_agents(&config.agents.list).await;
 
-        for agent_cfg in &agents_config.list {
-            let mut openclaw_cfg = OpenclawAgentConfig::new(
-                agent_cfg.id.clone(),
-                agent_cfg.id.clone(),
-                AgentType::Custom(agent_cfg.id.clone()),
-            );
+        tracing::info!(
+            "Initialized {} agents from config",
+            config.agents.list.len()
+        );
+        Ok(())
+    }
 
-            if let Some(aieos_path) = &agent_cfg.aieos_path
-                && aieos_path.exists()
-            {
-                match AIEOSParser::from_file(aieos_path) {
-                    Ok(aieos) => {
-                        let system_prompt = AIEOSPromptGenerator::generate_system_prompt(&aieos);
-                        openclaw_cfg = openclaw_cfg.with_system_prompt(system_prompt);
-                        tracing::info!(
-                            "Loaded AIEOS for agent {} from {:?}",
-                            agent_cfg.id,
-                            aieos_path
-                        );
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to load AIEOS for agent {}: {}", agent_cfg.id, e);
-                    }
+    /// 按配置重建智能体并替换同名实例，新实例会重新注入依赖
+    pub async fn reload_agents(&self, agents: &[openclaw_agent::AgentInstanceConfig]) {
+        for agent_cfg in agents {
+            let agent = Arc::new(BaseAgent::new(Self::agent_config_from(agent_cfg))) as Arc<dyn Agent>;
+            self.register_agent(agent_cfg.id.clone(), agent).await;
+        }
+    }
+
+    pub async fn unregister_agent(&self, id: &str) -> bool {
+        self.agent_service.agents.write().await.remove(id).is_some()
+    }
+
+    /// 系统提示词优先取 AIEOS 文件，其次取配置中的 system_prompt
+    fn agent_config_from(agent_cfg: &openclaw_agent::AgentInstanceConfig) -> OpenclawAgentConfig {
+        let mut openclaw_cfg = OpenclawAgentConfig::new(
+            agent_cfg.id.clone(),
+            agent_cfg.id.clone(),
+            AgentType::Custom(agent_cfg.id.clone()),
+        )
+        .with_memory(agent_cfg.memory.clone());
+
+        if let Some(aieos_path) = &agent_cfg.aieos_path
+            && aieos_path.exists()
+        {
+            match AIEOSParser::from_file(aieos_path) {
+                Ok(aieos) => {
+                    let system_prompt = AIEOSPromptGenerator::generate_system_prompt(&aieos);
+                    tracing::info!(
+                        "Loaded AIEOS for agent {} from {:?}",
+                        agent_cfg.id,
+                        aieos_path
+                    );
+                    return openclaw_cfg.with_system_prompt(system_prompt);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to load AIEOS for agent {}: {}", agent_cfg.id, e);
                 }
             }
+        }
 
-            let agent = Arc::new(BaseAgent::new(openclaw_cfg)) as Arc<dyn Agent>;
-            self.register_agent(agent_cfg.id.clone(), agent).await;
+        if let Some(system_prompt) = &agent_cfg.system_prompt {
+            openclaw_cfg = openclaw_cfg.with_system_prompt(system_prompt.clone());
         }
+        openclaw_cfg
+    }
+}
 
-        tracing::info!(
-            "Initialized {} agents from config",
-            agents_config.list.len()
-        );
-        Ok(())
+async fn run_task(
+    agent: &dyn Agent,
+    task: TaskRequest,
+    steps: Option<AgentStepSender>,
+) -> openclaw_agent::Result<openclaw_agent::TaskResult> {
+    match steps {
+        Some(steps) => agent.process_with_steps(task, steps).await,
+        None => agent.process(task).await,
     }
 }
 
+/// 把分支对话中的消息还原为模型消息
+fn transcript_message(message: &SessionMessage) -> Message {
+    let mut restored = match message.role.as_str() {
+        "system" => Message::system(message.content.clone()),
+        "assistant" => Message::assistant(message.content.clone()),
+        _ => Message::user(message.content.clone()),
+    };
+    restored.id = message.id;
+    restored.created_at = message.timestamp;
+    restored
+}
+
+/// 把 Agent 的任务结果转换为单个结束增量，失败的任务转换为错误
+fn result_chunk(result: openclaw_agent::TaskResult) -> Result<StreamChunk> {
+    if result.status == openclaw_agent::task::TaskStatus::Failed {
+        return Err(OpenClawError::Execution(
+            result.error.unwrap_or_else(|| "Agent failed".to_string()),
+        ));
+    }
+
+    let mut texts = Vec::new();
+    let mut tool_calls = Vec::new();
+    match result.output {
+        Some(TaskOutput::Message { message }) => {
+            for c in message.content {
+                match c {
+                    Content::Text { text } if !text.is_empty() => texts.push(text),
+                    Content::ToolCall {
+                        id,
+                        name,
+                        arguments,
+                    } => tool_calls.push(ToolCallDelta {
+                        index: tool_calls.len(),
+                        id: Some(id),
+                        call_type: "function".to_string(),
+                        function: Some(FunctionDelta {
+                            name: Some(name),
+                            arguments: Some(match arguments {
+                                serde_json::Value::String(s) => s,
+                                other => other.to_string(),
+                            }),
+                        }),
+                    }),
+                    _ => {}
+                }
+            }
+        }
+        Some(TaskOutput::Text { content }) => texts.push(content),
+        Some(other) => texts.extend(serde_json::to_string(&other).ok()),
+        None => {}
+    }
+
+    let finish_reason = if tool_calls.is_empty() {
+        FinishReason::Stop
+    } else {
+        FinishReason::ToolCalls
+    };
+    Ok(StreamChunk {
+        id: result.task_id.to_string(),
+        model: result.agent_id,
+        delta: StreamDelta {
+            role: None,
+            content: (!texts.is_empty()).then(|| texts.join("\n")),
+            tool_calls,
+        },
+        finished: true,
+        finish_reason: Some(finish_reason),
+    })
+}
+
+fn parse_id(kind: &str, id: &str) -> openclaw_agent::Result<Uuid> {
+    Uuid::parse_str(id)
+        .map_err(|_| openclaw_agent::OpenClawError::Config(format!("Invalid {} ID: {}", kind, id)))
+}
+
 impl Default for ServiceOrchestrator {
     fn default() -> Self {
         Self::new(OrchestratorConfig::default())
@@ -1231,6 +1855,163 @@ mod tests {
         assert!(!*running);
     }
 
+    #[tokio::test]
+    async fn test_canvas_autosave_restarts_without_duplicates() {
+        let canvas = CanvasServiceState::from_config(&CanvasStorageConfig {
+            persist_mode: openclaw_canvas::PersistMode::Batched { interval_ms: 10 },
+            ..Default::default()
+        });
+
+        canvas.start_autosave().await;
+        let first = canvas.autosave.lock().await.as_ref().unwrap().id();
+        canvas.start_autosave().await;
+        let second = canvas.autosave.lock().await.as_ref().unwrap().id();
+        assert_ne!(first, second);
+
+        canvas.stop_autosave().await;
+        assert!(canvas.autosave.lock().await.is_none());
+    }
+
+    /// 第一轮请求服务端工具 `lookup`，之后给出回答
+    struct LookupThenAnswer {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl AIProvider for LookupThenAnswer {
+        fn name(&self) -> &str {
+            "lookup-then-answer"
+        }
+
+        async fn chat(
+            &self,
+            _request: openclaw_ai::ChatRequest,
+        ) -> Result<openclaw_ai::ChatResponse> {
+            use std::sync::atomic::Ordering;
+
+            let message = if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                Message::new(
+                    Role::Assistant,
+                    vec![Content::ToolCall {
+                        id: "call_1".to_string(),
+                        name: "lookup".to_string(),
+                        arguments: serde_json::json!({}),
+                    }],
+                )
+            } else {
+                Message::assistant("the answer is 42")
+            };
+            Ok(openclaw_ai::ChatResponse {
+                id: "test".to_string(),
+                model: "test".to_string(),
+                message,
+                usage: openclaw_ai::TokenUsage::new(10, 5),
+                finish_reason: FinishReason::Stop,
+            })
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: openclaw_ai::ChatRequest,
+        ) -> Result<
+            std::pin::Pin<Box<dyn futures::Stream<Item = Result<StreamChunk>> + Send>>,
+        > {
+            Err(OpenClawError::AIProvider("streaming bypasses the agent".to_string()))
+        }
+
+        async fn embed(
+            &self,
+            _request: openclaw_ai::EmbeddingRequest,
+        ) -> Result<openclaw_ai::EmbeddingResponse> {
+            Err(OpenClawError::AIProvider("not supported".to_string()))
+        }
+
+        async fn models(&self) -> Result<Vec<String>> {
+            Ok(vec!["test".to_string()])
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    struct LookupTool {
+        used: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait]
+    impl openclaw_tools::Tool for LookupTool {
+        fn name(&self) -> &str {
+            "lookup"
+        }
+
+        fn description(&self) -> &str {
+            "Look up the answer"
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<serde_json::Value> {
+            self.used.store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(serde_json::json!({ "answer": 42 }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_conversation_runs_through_agent() {
+        use futures::StreamExt;
+
+        let orchestrator = ServiceOrchestrator::new(OrchestratorConfig::default());
+        let agent = Arc::new(BaseAgent::new(OpenclawAgentConfig::new(
+            "streamer",
+            "Streamer",
+            AgentType::Conversationalist,
+        )));
+        agent
+            .set_ai_provider(Arc::new(LookupThenAnswer {
+                calls: Default::default(),
+            }))
+            .await;
+        let used = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut registry = openclaw_tools::ToolRegistry::new();
+        registry.register("lookup".to_string(), Arc::new(LookupTool { used: used.clone() }));
+        agent.set_tool_registry(Arc::new(registry)).await;
+        let memory = Arc::new(Mutex::new(MemoryManager::new(MemoryConfig::default())));
+        agent.set_memory(memory.clone()).await;
+        orchestrator
+            .register_agent("streamer".to_string(), agent)
+            .await;
+
+        let chunks: Vec<_> = orchestrator
+            .stream_conversation(
+                "streamer",
+                vec![Message::user("what is the answer?")],
+                Vec::new(),
+                "stream-session".to_string(),
+                None,
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(chunks.len(), 1);
+        let chunk = chunks[0].as_ref().unwrap();
+        assert_eq!(chunk.delta.content.as_deref(), Some("the answer is 42"));
+        assert_eq!(chunk.finish_reason, Some(FinishReason::Stop));
+        assert!(used.load(std::sync::atomic::Ordering::SeqCst));
+
+        let context = memory.lock().await.get_context();
+        assert!(
+            context
+                .iter()
+                .any(|m| m.text_content() == Some("what is the answer?"))
+        );
+        assert!(
+            context
+                .iter()
+                .any(|m| m.text_content() == Some("the answer is 42"))
+        );
+    }
+
     #[cfg(feature = "per_session_memory")]
     mod per_session_memory_tests {
         use super::*;
@@ -115,22 +115,24 @@ impl ServerConfig {
     }
 
     fn load_core_config(config_dir: &Path) -> std::io::Result<CoreConfig> {
-        // 优先加载 config.json
-        let json_path = config_dir.join("config.json");
-        if json_path.exists() {
-            return CoreConfig::from_file(&json_path)
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e));
-        }
-
-        // 回退到旧的 openclaw.json（向后兼容）
-        let legacy_path = config_dir.join("openclaw.json");
-        if legacy_path.exists() {
-            return CoreConfig::from_file(&legacy_path)
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e));
+        // 优先加载 config.json，回退到旧的 openclaw.json（向后兼容）
+        match CoreConfig::find_in_dir(config_dir) {
+            Some(path) => CoreConfig::from_file(&path)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            None => Ok(CoreConfig::default()),
         }
+    }
 
-        // 返回默认配置
-        Ok(CoreConfig::default())
+    /// 严格加载：YAML 解析失败也返回错误，用于热加载时拒绝无效修改
+    pub fn load_strict(config_dir: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            core: Self::load_core_config(config_dir)?,
+            agents: load_yaml_strict(config_dir.join("agents.yaml"))?,
+            devices: load_yaml_strict(config_dir.join("devices.yaml"))?,
+            workspaces: load_yaml_strict(config_dir.join("workspaces.yaml"))?,
+            acp: load_yaml_strict(config_dir.join("acp.yaml"))?,
+            channels: load_yaml_strict(config_dir.join("channels.yaml"))?,
+        })
     }
 
     pub fn load_or_default(config_dir: &Path) -> Self {
@@ -147,6 +149,19 @@ fn load_yaml_config<T: for<'de> Deserialize<'de>>(path: PathBuf) -> Option<T> {
         .and_then(|content| serde_yaml::from_str(&content).ok())
 }
 
+fn load_yaml_strict<T: for<'de> Deserialize<'de> + Default>(path: PathBuf) -> std::io::Result<T> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    serde_yaml::from_str(&content).map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), e),
+        )
+    })
+}
+
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
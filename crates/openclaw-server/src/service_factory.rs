@@ -8,17 +8,25 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use openclaw_ai::AIProvider;
+use openclaw_ai::providers::ReloadableProvider;
 use openclaw_channels::config::{ChannelConfigEntry, ChannelConfigs};
 use openclaw_core::{Config, Result};
 use openclaw_device::factory::DeviceManagerFactory;
 use openclaw_device::UnifiedDeviceManager;
-use openclaw_memory::factory::{create_memory_backend, MemoryBackend};
-use openclaw_memory::MemoryManager;
+use openclaw_agent::ConversationMemory;
+use openclaw_memory::factory::{create_embedding_provider, create_memory_backend, MemoryBackend};
+use openclaw_memory::bm25::{Bm25Index, DEFAULT_INDEX_PATH};
+use openclaw_memory::knowledge_graph::{DEFAULT_GRAPH_PATH, KnowledgeGraph};
+use openclaw_memory::fact_extractor::LLMFactExtractor;
+use openclaw_memory::hybrid_search::{HybridSearchConfig, HybridSearchManager};
+use openclaw_memory::{GraphExtractor, LLMEntityExtractor};
+use openclaw_memory::{IngestPipeline, MemoryManager};
 use openclaw_security::pipeline::SecurityPipeline;
 use openclaw_sandbox::SandboxManager;
 use openclaw_tools::{ToolRegistry, register_builtin_tools};
 
 use crate::app_context::AppContext;
+use crate::config_adapter::DEFAULT_PERSONA;
 use crate::orchestrator::OrchestratorConfig;
 use crate::orchestrator::ServiceOrchestrator;
 use crate::voice_service::VoiceService;
@@ -45,6 +53,61 @@ pub trait ServiceFactory: Send + Sync {
         memory_backend: Option<Arc<dyn MemoryBackend>>,
     ) -> Result<Arc<crate::agentic_rag::AgenticRAGEngine>>;
     async fn create_acp_service(&self, acp_config: &AcpConfig) -> Result<Option<Arc<AcpService>>>;
+    async fn create_ingest_pipeline(
+        &self,
+        ai_provider: Arc<dyn AIProvider>,
+    ) -> Result<Arc<IngestPipeline>>;
+    async fn create_conversation_memory(
+        &self,
+        ai_provider: Arc<dyn AIProvider>,
+    ) -> Result<Arc<ConversationMemory>>;
+}
+
+/// 按核心配置创建 AI 提供商，热加载替换提供商时也使用此函数
+pub fn build_ai_provider(
+    core_config: &openclaw_core::config::ProviderConfig,
+) -> Result<Arc<dyn AIProvider>> {
+    use openclaw_ai::providers::{ProviderConfig, ProviderFactory, ProviderType};
+
+    let ai_config = ProviderConfig {
+        name: core_config.name.clone(),
+        api_key: core_config.api_key.clone(),
+        base_url: core_config.base_url.clone(),
+        default_model: core_config.default_model.clone(),
+        timeout: None,
+        headers: std::collections::HashMap::new(),
+        organization: None,
+    };
+
+    let provider_type = ProviderType::from_str(&core_config.name).ok_or_else(|| {
+        openclaw_core::OpenClawError::AIProvider(format!(
+            "Unknown AI provider: {}",
+            core_config.name
+        ))
+    })?;
+
+    let provider = ProviderFactory::create(provider_type, ai_config)
+        .map_err(openclaw_core::OpenClawError::AIProvider)?;
+    Ok(provider)
+}
+
+/// 将 `channels.config` 中的每一项转换为通道配置，键名即通道类型
+pub fn channel_configs_from_settings(
+    settings: &openclaw_core::config::ChannelSettings,
+) -> Option<ChannelConfigs> {
+    let channel_config_json = settings.config.as_ref()?;
+    let mut configs: ChannelConfigs = ChannelConfigs::default();
+    if let Some(obj) = channel_config_json.as_object() {
+        for (name, value) in obj.iter() {
+            let entry = ChannelConfigEntry {
+                channel_type: name.clone(),
+                config: value.clone(),
+                enabled: true,
+            };
+            configs.0.insert(name.clone(), entry);
+        }
+    }
+    Some(configs)
 }
 
 /// 默认服务工厂实现
@@ -71,30 +134,7 @@ impl DefaultServiceFactory {
 #[async_trait]
 impl ServiceFactory for DefaultServiceFactory {
     async fn create_ai_provider(&self) -> Result<Arc<dyn AIProvider>> {
-        use openclaw_ai::providers::{ProviderConfig, ProviderFactory, ProviderType};
-
-        let core_config = self.config.ai_provider();
-
-        let ai_config = ProviderConfig {
-            name: core_config.name.clone(),
-            api_key: core_config.api_key.clone(),
-            base_url: core_config.base_url.clone(),
-            default_model: core_config.default_model.clone(),
-            timeout: None,
-            headers: std::collections::HashMap::new(),
-            organization: None,
-        };
-
-        let provider_type = ProviderType::from_str(&core_config.name).ok_or_else(|| {
-            openclaw_core::OpenClawError::AIProvider(format!(
-                "Unknown AI provider: {}",
-                core_config.name
-            ))
-        })?;
-
-        let provider = ProviderFactory::create(provider_type, ai_config)
-            .map_err(openclaw_core::OpenClawError::AIProvider)?;
-        Ok(provider)
+        build_ai_provider(&self.config.ai_provider())
     }
 
     async fn create_memory_backend(&self) -> Result<Arc<dyn MemoryBackend>> {
@@ -117,6 +157,14 @@ impl ServiceFactory for DefaultServiceFactory {
                 }
             };
 
+        if let Some(ref namespace) = memory_config.privacy.namespace {
+            tracing::info!(
+                "Memory scoped to namespace {}/{}",
+                namespace.user_id,
+                namespace.persona_id
+            );
+        }
+
         let backend_type = &memory_config.backend_type;
         if backend_type != "hybrid" {
             tracing::info!("Creating memory backend with type: {}", backend_type);
@@ -230,34 +278,26 @@ impl ServiceFactory for DefaultServiceFactory {
         let memory_config = self.config.memory();
         let channel_to_agent_map = config.channels.channel_to_agent_map.clone();
 
-        let channel_configs = if let Some(ref channel_config_json) = config.channels.config {
-            let mut configs: ChannelConfigs = ChannelConfigs::default();
-            if let Some(obj) = channel_config_json.as_object() {
-                for (name, value) in obj.iter() {
-                    let entry = ChannelConfigEntry {
-                        channel_type: name.clone(),
-                        config: value.clone(),
-                        enabled: true,
-                    };
-                    configs.0.insert(name.clone(), entry);
-                }
-            }
-            Some(configs)
-        } else {
-            None
-        };
+        let channel_configs = channel_configs_from_settings(&config.channels);
 
         let orchestrator_config = OrchestratorConfig {
             enable_agents: config.server.enable_agents,
             enable_channels: config.channels.enabled,
             enable_voice: config.server.enable_voice,
             enable_canvas: config.server.enable_canvas,
-            default_agent: Some("orchestrator".to_string()),
+            default_agent: Some(DEFAULT_PERSONA.to_string()),
             channel_to_agent_map,
+            stream_channel_replies: config.channels.stream_replies,
             agent_to_canvas_map: std::collections::HashMap::new(),
+            canvas_storage: config
+                .canvas
+                .as_ref()
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
             channel_configs,
             enable_evolution: config.server.enable_evolution,
             evolution_model: config.server.evolution_model.clone(),
+            session_db: config.server.session_db.clone(),
             #[cfg(feature = "per_session_memory")]
             enable_per_session_memory: false,
             #[cfg(feature = "per_session_memory")]
@@ -275,10 +315,27 @@ impl ServiceFactory for DefaultServiceFactory {
             },
         ));
 
-        let ai_provider = self.create_ai_provider().await?;
+        let provider_handle = Arc::new(ReloadableProvider::new(self.create_ai_provider().await?));
+        if let Some(ref orchestrator) = *orchestrator.read().await {
+            match self.create_conversation_memory(provider_handle.clone()).await {
+                Ok(memory) => orchestrator.set_conversation_memory(memory).await,
+                Err(e) => tracing::warn!("Failed to initialize conversation memory: {}", e),
+            }
+        }
         let memory_backend = Some(self.create_memory_backend().await?);
         let security_pipeline = self.create_security_pipeline();
-        let tool_registry = self.create_tool_registry();
+        let mut tool_registry = self.create_tool_registry();
+        if config.server.enable_canvas
+            && let Some(ref orchestrator) = *orchestrator.read().await
+        {
+            let mut registry = (*tool_registry).clone();
+            crate::canvas_tools::register_canvas_tools(
+                &mut registry,
+                orchestrator.canvas_manager(),
+                orchestrator.collab_manager(),
+            );
+            tool_registry = Arc::new(registry);
+        }
         let voice_service = Arc::new(VoiceService::new());
 
         let unified_device_manager = match self.create_unified_device_manager().await {
@@ -298,7 +355,7 @@ impl ServiceFactory for DefaultServiceFactory {
 
         let context = AppContext::new(
             config,
-            ai_provider,
+            provider_handle.clone(),
             memory_backend,
             security_pipeline,
             tool_registry,
@@ -309,7 +366,8 @@ impl ServiceFactory for DefaultServiceFactory {
             voice_service,
             self.vector_store_registry.clone(),
             None,
-        );
+        )
+        .with_reloadable_provider(provider_handle);
 
         Ok(Arc::new(context))
     }
@@ -317,13 +375,35 @@ impl ServiceFactory for DefaultServiceFactory {
     async fn create_agentic_rag_engine(
         &self,
         ai_provider: Arc<dyn openclaw_ai::AIProvider>,
-        _memory_backend: Option<Arc<dyn MemoryBackend>>,
+        memory_backend: Option<Arc<dyn MemoryBackend>>,
     ) -> Result<Arc<crate::agentic_rag::AgenticRAGEngine>> {
-        use crate::agentic_rag::{AgenticRAGConfig, AgenticRAGEngine};
+        use crate::agentic_rag::AgenticRAGEngine;
 
-        let config = AgenticRAGConfig::default();
+        let config = self.config.agentic_rag()?;
+        let memory_config = self.config.memory();
 
-        let engine = AgenticRAGEngine::new(config, ai_provider, None, None, None).await?;
+        // 与记忆检索共用向量库和嵌入模型，文件语料的向量随后端持久化
+        let embedding_provider =
+            create_embedding_provider(&memory_config, ai_provider.clone()).await?;
+        let vector_store = self
+            .vector_store_registry
+            .create(&memory_config.long_term.backend)
+            .await;
+        if vector_store.is_none() {
+            tracing::warn!(
+                "Vector store backend '{}' unavailable, agentic RAG will skip vector retrieval",
+                memory_config.long_term.backend
+            );
+        }
+
+        let engine = AgenticRAGEngine::new(
+            config,
+            ai_provider,
+            memory_backend,
+            vector_store,
+            Some(embedding_provider),
+        )
+        .await?;
 
         Ok(Arc::new(engine))
     }
@@ -358,4 +438,101 @@ impl ServiceFactory for DefaultServiceFactory {
 
         Ok(Some(Arc::new(acp)))
     }
+
+    async fn create_ingest_pipeline(
+        &self,
+        ai_provider: Arc<dyn AIProvider>,
+    ) -> Result<Arc<IngestPipeline>> {
+        let memory_config = self.config.memory();
+
+        // 与混合记忆检索共用 BM25 索引和向量库，导入的文档可直接被召回
+        let bm25 = Bm25Index::shared(std::path::Path::new(DEFAULT_INDEX_PATH))
+            .map_err(|e| openclaw_core::OpenClawError::Memory(e.to_string()))?;
+        let mut pipeline = IngestPipeline::new(bm25);
+        // 与对话记忆使用同一嵌入模型，导入向量和查询向量处于同一空间
+        let embedding_provider =
+            create_embedding_provider(&memory_config, ai_provider.clone()).await?;
+
+        // 导入的文档同时提取实体和关系，写入混合检索使用的图谱
+        if memory_config.long_term.enable_knowledge_graph {
+            match KnowledgeGraph::open(DEFAULT_GRAPH_PATH) {
+                Ok(graph) => {
+                    let model = memory_config
+                        .short_term
+                        .summary_model
+                        .clone()
+                        .unwrap_or_else(|| "gpt-4o-mini".to_string());
+                    let extractor = GraphExtractor::new(Arc::new(graph))
+                        .with_extractor(Arc::new(LLMEntityExtractor::new(ai_provider, model)))
+                        .with_embedder(embedding_provider.clone());
+                    pipeline = pipeline.with_graph_extractor(Arc::new(extractor));
+                }
+                Err(e) => tracing::warn!("Failed to open knowledge graph for ingestion: {}", e),
+            }
+        }
+
+        if let Some(vector_store) = self
+            .vector_store_registry
+            .create(&memory_config.long_term.backend)
+            .await
+        {
+            pipeline = pipeline.with_vectors(vector_store, embedding_provider);
+        } else {
+            tracing::warn!(
+                "Vector store backend '{}' unavailable, documents will only be indexed for BM25",
+                memory_config.long_term.backend
+            );
+        }
+
+        Ok(Arc::new(pipeline))
+    }
+
+    async fn create_conversation_memory(
+        &self,
+        ai_provider: Arc<dyn AIProvider>,
+    ) -> Result<Arc<ConversationMemory>> {
+        let memory_config = self.config.memory();
+        let vector_store = self
+            .vector_store_registry
+            .create(&memory_config.long_term.backend)
+            .await
+            .ok_or_else(|| {
+                openclaw_core::OpenClawError::Memory(format!(
+                    "Vector store backend '{}' unavailable",
+                    memory_config.long_term.backend
+                ))
+            })?;
+        let embedder = create_embedding_provider(&memory_config, ai_provider.clone()).await?;
+
+        let search_config = HybridSearchConfig {
+            embedding_dimension: Some(embedder.dimensions()),
+            enable_bm25: memory_config.long_term.enable_bm25,
+            enable_knowledge_graph: false,
+            namespace: memory_config.privacy.namespace.clone(),
+            ..Default::default()
+        };
+        let mut search = HybridSearchManager::new(vector_store, search_config);
+        if memory_config.long_term.enable_bm25
+            && let Ok(bm25) = Bm25Index::shared(std::path::Path::new(DEFAULT_INDEX_PATH))
+        {
+            search = search.with_bm25(bm25);
+        }
+
+        let model = memory_config
+            .short_term
+            .summary_model
+            .clone()
+            .unwrap_or_else(|| "gpt-4o-mini".to_string());
+        let mut memory = ConversationMemory::new(Arc::new(search), embedder)
+            .with_fact_extractor(Arc::new(LLMFactExtractor::new(ai_provider, model)));
+        // 未携带用户的任务落在配置的命名空间内
+        if let Some(namespace) = memory_config.privacy.namespace {
+            memory = memory.with_namespace(namespace);
+        }
+        if !memory_config.privacy.redact_pii {
+            memory = memory.without_redaction();
+        }
+
+        Ok(Arc::new(memory))
+    }
 }
@@ -12,12 +12,14 @@ use openclaw_agent::evo::skill_config_applier::{ConfigSkillApplier, ServerConfig
 use openclaw_agent::evo::skill_hot_reloader::SkillHotReloader;
 use openclaw_agent::evo::skill_loader::SkillLoader;
 use openclaw_agent::evo::workflow_registry::WorkflowRegistry;
+use openclaw_tools::bundle_signing::{BundleVerifier, TrustStore, UnsignedBundlePolicy};
 
 pub struct SkillService {
     skill_loader: Arc<SkillLoader>,
     hot_reloader: Arc<RwLock<Option<SkillHotReloader>>>,
     registry: Arc<SharedSkillRegistry>,
     workflow_registry: Arc<WorkflowRegistry>,
+    verifier: BundleVerifier,
 }
 
 impl SkillService {
@@ -27,9 +29,30 @@ impl SkillService {
             hot_reloader: Arc::new(RwLock::new(None)),
             registry: Arc::new(SharedSkillRegistry::new()),
             workflow_registry: Arc::new(WorkflowRegistry::new()),
+            verifier: Self::default_verifier(),
         }
     }
 
+    /// 设置 Skill 签名校验器
+    pub fn with_verifier(mut self, verifier: BundleVerifier) -> Self {
+        self.verifier = verifier;
+        self
+    }
+
+    /// 默认校验器：加载 `~/.openclaw-rust/trusted_keys.json`，
+    /// 未签名策略可通过 `OPENCLAW_UNSIGNED_SKILLS=reject|warn|allow` 调整
+    fn default_verifier() -> BundleVerifier {
+        let trust_store = TrustStore::load(&TrustStore::default_path()).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load skill trust store: {}", e);
+            TrustStore::new()
+        });
+        let policy = std::env::var("OPENCLAW_UNSIGNED_SKILLS")
+            .ok()
+            .and_then(|p| p.parse::<UnsignedBundlePolicy>().ok())
+            .unwrap_or_default();
+        BundleVerifier::new(trust_store, policy)
+    }
+
     pub fn get_skill_paths() -> Vec<PathBuf> {
         let mut paths = Vec::new();
 
@@ -83,7 +106,7 @@ impl SkillService {
         }
 
         let loader_for_reloader = SkillLoader::new(self.registry.clone_inner());
-        let reloader = SkillHotReloader::new(loader_for_reloader);
+        let reloader = SkillHotReloader::new(loader_for_reloader).with_verifier(self.verifier.clone());
         reloader.start(paths).await?;
 
         *self.hot_reloader.write().await = Some(reloader);
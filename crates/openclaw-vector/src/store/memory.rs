@@ -6,18 +6,31 @@ use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
 use crate::VectorStore;
-use crate::types::{Filter, FilterOperator, SearchQuery, SearchResult, StoreStats, VectorItem};
+use crate::types::{CollectionMetadata, Filter, SearchQuery, SearchResult, StoreStats, VectorItem};
 use openclaw_core::Result;
 
 /// 内存向量存储
 pub struct MemoryStore {
     data: RwLock<HashMap<String, VectorItem>>,
+    metadata: RwLock<Option<CollectionMetadata>>,
 }
 
 impl MemoryStore {
     pub fn new() -> Self {
         Self {
             data: RwLock::new(HashMap::new()),
+            metadata: RwLock::new(None),
+        }
+    }
+
+    fn check_dimensions(&self, dimensions: usize) -> Result<()> {
+        let metadata = self
+            .metadata
+            .read()
+            .map_err(|_| openclaw_core::OpenClawError::VectorStore("Lock poisoned".to_string()))?;
+        match metadata.as_ref() {
+            Some(metadata) => metadata.check_dimensions(dimensions),
+            None => Ok(()),
         }
     }
 
@@ -31,51 +44,6 @@ impl MemoryStore {
             dot / (norm_a * norm_b)
         }
     }
-
-    fn matches_filter(item: &VectorItem, filter: &Filter) -> bool {
-        if filter.conditions.is_empty() {
-            return true;
-        }
-
-        for condition in &filter.conditions {
-            let value = item.payload.get(&condition.field);
-            let matches = match (&condition.operator, value) {
-                (FilterOperator::Eq, Some(v)) => v == &condition.value,
-                (FilterOperator::Ne, Some(v)) => v != &condition.value,
-                (FilterOperator::Gt, Some(v)) => {
-                    v.as_f64().unwrap_or(0.0) > condition.value.as_f64().unwrap_or(0.0)
-                }
-                (FilterOperator::Gte, Some(v)) => {
-                    v.as_f64().unwrap_or(0.0) >= condition.value.as_f64().unwrap_or(0.0)
-                }
-                (FilterOperator::Lt, Some(v)) => {
-                    v.as_f64().unwrap_or(0.0) < condition.value.as_f64().unwrap_or(0.0)
-                }
-                (FilterOperator::Lte, Some(v)) => {
-                    v.as_f64().unwrap_or(0.0) <= condition.value.as_f64().unwrap_or(0.0)
-                }
-                (FilterOperator::In, Some(v)) => {
-                    if let Some(arr) = condition.value.as_array() {
-                        arr.contains(v)
-                    } else {
-                        false
-                    }
-                }
-                (FilterOperator::Contains, Some(v)) => {
-                    if let Some(arr) = v.as_array() {
-                        arr.contains(&condition.value)
-                    } else {
-                        false
-                    }
-                }
-                _ => false,
-            };
-            if !matches {
-                return false;
-            }
-        }
-        true
-    }
 }
 
 impl Default for MemoryStore {
@@ -87,6 +55,7 @@ impl Default for MemoryStore {
 #[async_trait]
 impl VectorStore for MemoryStore {
     async fn upsert(&self, item: VectorItem) -> Result<()> {
+        self.check_dimensions(item.vector.len())?;
         let mut data = self
             .data
             .write()
@@ -96,6 +65,9 @@ impl VectorStore for MemoryStore {
     }
 
     async fn upsert_batch(&self, items: Vec<VectorItem>) -> Result<usize> {
+        for item in &items {
+            self.check_dimensions(item.vector.len())?;
+        }
         let mut data = self
             .data
             .write()
@@ -108,6 +80,7 @@ impl VectorStore for MemoryStore {
     }
 
     async fn search(&self, query: SearchQuery) -> Result<Vec<SearchResult>> {
+        self.check_dimensions(query.vector.len())?;
         let data = self
             .data
             .read()
@@ -117,7 +90,7 @@ impl VectorStore for MemoryStore {
             .values()
             .filter(|item| {
                 if let Some(filter) = &query.filter {
-                    Self::matches_filter(item, filter)
+                    filter.matches(&item.payload)
                 } else {
                     true
                 }
@@ -171,7 +144,7 @@ impl VectorStore for MemoryStore {
             .map_err(|_| openclaw_core::OpenClawError::VectorStore("Lock poisoned".to_string()))?;
         let ids_to_remove: Vec<String> = data
             .values()
-            .filter(|item| Self::matches_filter(item, &filter))
+            .filter(|item| filter.matches(&item.payload))
             .map(|item| item.id.clone())
             .collect();
 
@@ -202,6 +175,42 @@ impl VectorStore for MemoryStore {
         data.clear();
         Ok(())
     }
+
+    async fn metadata(&self) -> Result<Option<CollectionMetadata>> {
+        let metadata = self
+            .metadata
+            .read()
+            .map_err(|_| openclaw_core::OpenClawError::VectorStore("Lock poisoned".to_string()))?;
+        Ok(metadata.clone())
+    }
+
+    async fn set_metadata(&self, metadata: CollectionMetadata) -> Result<()> {
+        let mut current = self
+            .metadata
+            .write()
+            .map_err(|_| openclaw_core::OpenClawError::VectorStore("Lock poisoned".to_string()))?;
+        *current = Some(metadata);
+        Ok(())
+    }
+
+    async fn scan(
+        &self,
+        filter: Option<&Filter>,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<VectorItem>> {
+        let data = self
+            .data
+            .read()
+            .map_err(|_| openclaw_core::OpenClawError::VectorStore("Lock poisoned".to_string()))?;
+        let mut items: Vec<&VectorItem> = data
+            .values()
+            .filter(|item| after.is_none_or(|after| item.id.as_str() > after))
+            .filter(|item| filter.is_none_or(|f| f.matches(&item.payload)))
+            .collect();
+        items.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(items.into_iter().take(limit).cloned().collect())
+    }
 }
 
 #[cfg(test)]
@@ -297,6 +306,62 @@ mod tests {
         assert_eq!(stats.total_vectors, 0);
     }
 
+    #[tokio::test]
+    async fn test_metadata_and_scan() {
+        let store = MemoryStore::new();
+        store
+            .set_metadata(CollectionMetadata::new("model-a", 3))
+            .await
+            .unwrap();
+
+        for id in ["c", "a", "b"] {
+            let item = VectorItem::new(vec![1.0, 0.0, 0.0], json!({})).with_id(id);
+            store.upsert(item).await.unwrap();
+        }
+        let page: Vec<String> = store
+            .scan(None, Some("a"), 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        assert_eq!(page, vec!["b", "c"]);
+
+        store
+            .upsert(VectorItem::new(vec![0.0, 1.0, 0.0], json!({"user_id": "alice"})).with_id("d"))
+            .await
+            .unwrap();
+        let alice = Filter::eq("user_id", json!("alice"));
+        let page = store.scan(Some(&alice), None, 10).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, "d");
+
+        // 维度不一致的查询和写入被拒绝
+        assert!(
+            store
+                .search(SearchQuery::new(vec![1.0, 0.0]))
+                .await
+                .is_err()
+        );
+        assert!(
+            store
+                .upsert(VectorItem::new(vec![1.0], json!({})))
+                .await
+                .is_err()
+        );
+
+        assert!(
+            crate::ensure_embedding_model(&store, "model-a", 3)
+                .await
+                .is_ok()
+        );
+        assert!(
+            crate::ensure_embedding_model(&store, "model-b", 3)
+                .await
+                .is_err()
+        );
+    }
+
     #[tokio::test]
     async fn test_concurrent_access() {
         use tokio::task;
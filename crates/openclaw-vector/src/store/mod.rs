@@ -31,7 +31,9 @@ pub use milvus::MilvusStore;
 #[cfg(feature = "sqlite")]
 pub use sqlite::SqliteStore;
 
-use super::types::{Filter, SearchQuery, SearchResult, StoreStats, VectorItem};
+use super::types::{
+    CollectionMetadata, Filter, SearchQuery, SearchResult, StoreStats, VectorItem,
+};
 
 /// 向量存储 Trait
 #[async_trait]
@@ -44,6 +46,52 @@ pub trait VectorStore: Send + Sync {
     async fn delete_by_filter(&self, filter: Filter) -> Result<usize>;
     async fn stats(&self) -> Result<StoreStats>;
     async fn clear(&self) -> Result<()>;
+
+    /// 读取集合元数据，后端不支持时返回 None
+    async fn metadata(&self) -> Result<Option<CollectionMetadata>> {
+        Ok(None)
+    }
+
+    /// 写入集合元数据，后端不支持时忽略
+    async fn set_metadata(&self, _metadata: CollectionMetadata) -> Result<()> {
+        Ok(())
+    }
+
+    /// 按 id 顺序分页遍历向量项，用于重建索引、迁移和遗忘
+    ///
+    /// 返回 id 大于 `after` 的条目，调用方以上一页最后一条的 id 作为游标继续遍历，
+    /// 遍历期间的写入和删除不会导致条目被跳过。`filter` 在分页之前应用。
+    async fn scan(
+        &self,
+        _filter: Option<&Filter>,
+        _after: Option<&str>,
+        _limit: usize,
+    ) -> Result<Vec<VectorItem>> {
+        Err(OpenClawError::VectorStore(
+            "Scanning is not supported by this backend".to_string(),
+        ))
+    }
+}
+
+/// 校验集合绑定的嵌入模型，尚未绑定时记录当前模型
+pub async fn ensure_embedding_model(
+    store: &dyn VectorStore,
+    embedding_model: &str,
+    dimensions: usize,
+) -> Result<()> {
+    match store.metadata().await? {
+        Some(metadata) if metadata.matches(embedding_model, dimensions) => Ok(()),
+        Some(metadata) => Err(OpenClawError::VectorStore(format!(
+            "Collection was embedded with {} ({} dimensions) but the current embedding model is \
+             {} ({} dimensions). Run `openclaw memory reindex` to re-embed stored memories.",
+            metadata.embedding_model, metadata.dimensions, embedding_model, dimensions
+        ))),
+        None => {
+            store
+                .set_metadata(CollectionMetadata::new(embedding_model, dimensions))
+                .await
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
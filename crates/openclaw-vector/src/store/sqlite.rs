@@ -1,12 +1,14 @@
 //! SQLite 向量存储实现 - 支持 FTS5 全文搜索和向量相似度搜索
 
 use async_trait::async_trait;
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
 use std::path::PathBuf;
 use std::sync::Mutex;
 
 use crate::VectorStore;
-use crate::types::{Filter, SearchQuery, SearchResult, StoreStats, VectorItem};
+use crate::types::{
+    CollectionMetadata, Filter, FilterOperator, SearchQuery, SearchResult, StoreStats, VectorItem,
+};
 use openclaw_core::{OpenClawError, Result};
 
 /// SQLite 向量存储
@@ -47,6 +49,18 @@ impl SqliteStore {
         )
         .map_err(|e| OpenClawError::Config(e.to_string()))?;
 
+        conn.execute(
+            &format!(
+                "CREATE TABLE IF NOT EXISTS {}_meta (
+                    key TEXT PRIMARY KEY,
+                    value TEXT NOT NULL
+                )",
+                table_name
+            ),
+            [],
+        )
+        .map_err(|e| OpenClawError::Config(e.to_string()))?;
+
         Ok(Self {
             conn: Mutex::new(conn),
             table_name: table_name.to_string(),
@@ -54,6 +68,7 @@ impl SqliteStore {
     }
 
     pub fn upsert(&self, item: VectorItem) -> Result<()> {
+        self.check_dimensions(item.vector.len())?;
         let conn = self
             .conn
             .lock()
@@ -97,6 +112,9 @@ impl SqliteStore {
     }
 
     pub fn upsert_batch(&self, items: Vec<VectorItem>) -> Result<usize> {
+        for item in &items {
+            self.check_dimensions(item.vector.len())?;
+        }
         let conn = self
             .conn
             .lock()
@@ -143,6 +161,7 @@ impl SqliteStore {
     }
 
     pub fn vector_search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        self.check_dimensions(query.vector.len())?;
         let conn = self
             .conn
             .lock()
@@ -170,6 +189,14 @@ impl SqliteStore {
         for row in rows {
             let (id, vector_blob, payload_str) =
                 row.map_err(|e| OpenClawError::Config(e.to_string()))?;
+            let payload: serde_json::Value =
+                serde_json::from_str(&payload_str).unwrap_or(serde_json::Value::Null);
+            if let Some(filter) = &query.filter
+                && !filter.matches(&payload)
+            {
+                continue;
+            }
+
             let stored_vector = deserialize_vector(&vector_blob)
                 .map_err(|e| OpenClawError::VectorStore(e.to_string()))?;
             let score = cosine_similarity(query_vector, &stored_vector);
@@ -180,8 +207,6 @@ impl SqliteStore {
                 continue;
             }
 
-            let payload: serde_json::Value =
-                serde_json::from_str(&payload_str).unwrap_or(serde_json::Value::Null);
             results.push(SearchResult { id, score, payload });
         }
 
@@ -380,6 +405,49 @@ impl SqliteStore {
         Ok(())
     }
 
+    /// payload 满足过滤条件的向量项全部删除，返回删除数量
+    pub fn delete_by_filter(&self, filter: &Filter) -> Result<usize> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+
+        let ids: Vec<String> = {
+            let mut stmt = conn
+                .prepare(&format!("SELECT id, payload FROM {}", self.table_name))
+                .map_err(|e| OpenClawError::Config(e.to_string()))?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                })
+                .map_err(|e| OpenClawError::Config(e.to_string()))?;
+            let mut ids = Vec::new();
+            for row in rows {
+                let (id, payload_str) = row.map_err(|e| OpenClawError::Config(e.to_string()))?;
+                let payload: serde_json::Value =
+                    serde_json::from_str(&payload_str).unwrap_or(serde_json::Value::Null);
+                if filter.matches(&payload) {
+                    ids.push(id);
+                }
+            }
+            ids
+        };
+
+        for id in &ids {
+            conn.execute(
+                &format!("DELETE FROM {} WHERE id = ?1", self.table_name),
+                params![id],
+            )
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+            conn.execute(
+                &format!("DELETE FROM {}_fts WHERE id = ?1", self.table_name),
+                params![id],
+            )
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+        }
+        Ok(ids.len())
+    }
+
     pub fn stats(&self) -> Result<StoreStats> {
         let conn = self
             .conn
@@ -410,6 +478,127 @@ impl SqliteStore {
             .map_err(|e| OpenClawError::Config(e.to_string()))?;
         Ok(())
     }
+
+    pub fn metadata(&self) -> Result<Option<CollectionMetadata>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+        let value: Option<String> = conn
+            .query_row(
+                &format!(
+                    "SELECT value FROM {}_meta WHERE key = 'collection'",
+                    self.table_name
+                ),
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+        match value {
+            Some(value) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_metadata(&self, metadata: &CollectionMetadata) -> Result<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+        conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO {}_meta (key, value) VALUES ('collection', ?1)",
+                self.table_name
+            ),
+            params![serde_json::to_string(metadata)?],
+        )
+        .map_err(|e| OpenClawError::Config(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 过滤条件转换为 SQL 在数据库中执行，仅支持等值条件
+    pub fn scan(
+        &self,
+        filter: Option<&Filter>,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<VectorItem>> {
+        let mut clauses = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        if let Some(after) = after {
+            values.push(rusqlite::types::Value::Text(after.to_string()));
+            clauses.push("id > ?1".to_string());
+        }
+        for condition in filter.map(|f| f.conditions.as_slice()).unwrap_or_default() {
+            if condition.operator != FilterOperator::Eq {
+                return Err(OpenClawError::VectorStore(format!(
+                    "Scanning only supports equality filters, got {:?} on {}",
+                    condition.operator, condition.field
+                )));
+            }
+            clauses.push(format!(
+                "json_extract(payload, ?{}) = ?{}",
+                values.len() + 1,
+                values.len() + 2
+            ));
+            values.push(rusqlite::types::Value::Text(format!(
+                "$.\"{}\"",
+                condition.field.replace('"', "\\\"")
+            )));
+            values.push(json_to_sql(&condition.value)?);
+        }
+        let where_clause = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+        values.push(rusqlite::types::Value::Integer(limit as i64));
+
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT id, vector, payload, created_at FROM {} {} ORDER BY id LIMIT ?{}",
+                self.table_name,
+                where_clause,
+                values.len()
+            ))
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                let id: String = row.get(0)?;
+                let vector_blob: Vec<u8> = row.get(1)?;
+                let payload_str: String = row.get(2)?;
+                let created_at_str: String = row.get(3)?;
+                Ok((id, vector_blob, payload_str, created_at_str))
+            })
+            .map_err(|e| OpenClawError::Config(e.to_string()))?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            let (id, vector_blob, payload_str, created_at_str) =
+                row.map_err(|e| OpenClawError::Config(e.to_string()))?;
+            items.push(VectorItem {
+                id,
+                vector: deserialize_vector(&vector_blob)?,
+                payload: serde_json::from_str(&payload_str).unwrap_or(serde_json::Value::Null),
+                created_at: chrono::DateTime::parse_from_rfc3339(&created_at_str)
+                    .map(|dt| dt.with_timezone(&chrono::Utc))
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+            });
+        }
+        Ok(items)
+    }
+
+    fn check_dimensions(&self, dimensions: usize) -> Result<()> {
+        match self.metadata()? {
+            Some(metadata) => metadata.check_dimensions(dimensions),
+            None => Ok(()),
+        }
+    }
 }
 
 #[async_trait]
@@ -429,8 +618,8 @@ impl VectorStore for SqliteStore {
     async fn delete(&self, id: &str) -> Result<()> {
         self.delete(id)
     }
-    async fn delete_by_filter(&self, _filter: Filter) -> Result<usize> {
-        Err(OpenClawError::Config("not implemented".to_string()))
+    async fn delete_by_filter(&self, filter: Filter) -> Result<usize> {
+        self.delete_by_filter(&filter)
     }
     async fn stats(&self) -> Result<StoreStats> {
         self.stats()
@@ -438,6 +627,37 @@ impl VectorStore for SqliteStore {
     async fn clear(&self) -> Result<()> {
         self.clear()
     }
+    async fn metadata(&self) -> Result<Option<CollectionMetadata>> {
+        self.metadata()
+    }
+    async fn set_metadata(&self, metadata: CollectionMetadata) -> Result<()> {
+        self.set_metadata(&metadata)
+    }
+    async fn scan(
+        &self,
+        filter: Option<&Filter>,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<VectorItem>> {
+        self.scan(filter, after, limit)
+    }
+}
+
+/// 过滤值转换为 SQL 值，与 `json_extract` 的返回类型一致
+fn json_to_sql(value: &serde_json::Value) -> Result<rusqlite::types::Value> {
+    use rusqlite::types::Value;
+    match value {
+        serde_json::Value::String(s) => Ok(Value::Text(s.clone())),
+        serde_json::Value::Bool(b) => Ok(Value::Integer(*b as i64)),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Ok(Value::Integer(i)),
+            None => Ok(Value::Real(n.as_f64().unwrap_or_default())),
+        },
+        other => Err(OpenClawError::VectorStore(format!(
+            "Unsupported filter value for scanning: {}",
+            other
+        ))),
+    }
 }
 
 fn serialize_vector(vector: &[f32]) -> Vec<u8> {
@@ -494,6 +714,72 @@ mod tests {
         assert!((cosine_similarity(&a, &b) - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_metadata_persists_and_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectors.db");
+        {
+            let store = SqliteStore::new(path.clone(), "memories").unwrap();
+            store
+                .set_metadata(&CollectionMetadata::new("model-a", 2))
+                .unwrap();
+            for id in ["b", "a"] {
+                let item =
+                    VectorItem::new(vec![1.0, 0.0], serde_json::json!({"content": id})).with_id(id);
+                store.upsert(item).unwrap();
+            }
+        }
+
+        let store = SqliteStore::new(path, "memories").unwrap();
+        assert_eq!(
+            store.metadata().unwrap().unwrap().embedding_model,
+            "model-a"
+        );
+        let ids: Vec<String> = store
+            .scan(None, None, 10)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert!(
+            store
+                .vector_search(&SearchQuery::new(vec![1.0, 0.0, 0.0]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_filtered_search_and_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteStore::new(dir.path().join("vectors.db"), "memories").unwrap();
+        for (id, user) in [("a", "alice"), ("b", "bob"), ("c", "alice")] {
+            let item =
+                VectorItem::new(vec![1.0, 0.0], serde_json::json!({"user_id": user})).with_id(id);
+            store.upsert(item).unwrap();
+        }
+
+        let alice = Filter::eq("user_id", serde_json::json!("alice"));
+        let results = store
+            .vector_search(&SearchQuery::new(vec![1.0, 0.0]).with_filter(alice.clone()))
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.payload["user_id"] == "alice"));
+
+        let page = store.scan(Some(&alice), Some("a"), 10).unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, "c");
+
+        assert_eq!(store.delete_by_filter(&alice).unwrap(), 2);
+        let ids: Vec<String> = store
+            .scan(None, None, 10)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(ids, vec!["b"]);
+    }
+
     #[test]
     fn test_serialize_deserialize() {
         let vector = vec![1.0, 2.0, 3.0, 4.0, 5.0];
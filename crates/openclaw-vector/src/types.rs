@@ -122,6 +122,38 @@ impl Filter {
         self
     }
 
+    /// 判断 payload 是否满足全部条件
+    pub fn matches(&self, payload: &serde_json::Value) -> bool {
+        self.conditions.iter().all(|condition| {
+            let Some(v) = payload.get(&condition.field) else {
+                return false;
+            };
+            match condition.operator {
+                FilterOperator::Eq => v == &condition.value,
+                FilterOperator::Ne => v != &condition.value,
+                FilterOperator::Gt => {
+                    v.as_f64().unwrap_or(0.0) > condition.value.as_f64().unwrap_or(0.0)
+                }
+                FilterOperator::Gte => {
+                    v.as_f64().unwrap_or(0.0) >= condition.value.as_f64().unwrap_or(0.0)
+                }
+                FilterOperator::Lt => {
+                    v.as_f64().unwrap_or(0.0) < condition.value.as_f64().unwrap_or(0.0)
+                }
+                FilterOperator::Lte => {
+                    v.as_f64().unwrap_or(0.0) <= condition.value.as_f64().unwrap_or(0.0)
+                }
+                FilterOperator::In => condition
+                    .value
+                    .as_array()
+                    .is_some_and(|arr| arr.contains(v)),
+                FilterOperator::Contains => v
+                    .as_array()
+                    .is_some_and(|arr| arr.contains(&condition.value)),
+            }
+        })
+    }
+
     pub fn to_sql_condition(&self) -> String {
         if self.conditions.is_empty() {
             return "TRUE".to_string();
@@ -172,3 +204,37 @@ pub struct StoreStats {
     pub total_size_bytes: usize,
     pub last_updated: DateTime<Utc>,
 }
+
+/// 集合元数据，记录写入向量所用的嵌入模型与维度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectionMetadata {
+    pub embedding_model: String,
+    pub dimensions: usize,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CollectionMetadata {
+    pub fn new(embedding_model: impl Into<String>, dimensions: usize) -> Self {
+        Self {
+            embedding_model: embedding_model.into(),
+            dimensions,
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn matches(&self, embedding_model: &str, dimensions: usize) -> bool {
+        self.embedding_model == embedding_model && self.dimensions == dimensions
+    }
+
+    /// 校验向量维度与集合一致
+    pub fn check_dimensions(&self, dimensions: usize) -> openclaw_core::Result<()> {
+        if dimensions == self.dimensions {
+            return Ok(());
+        }
+        Err(openclaw_core::OpenClawError::VectorStore(format!(
+            "Vector has {} dimensions but the collection was embedded with {} ({} dimensions). \
+             Run `openclaw memory reindex` after changing the embedding model.",
+            dimensions, self.embedding_model, self.dimensions
+        )))
+    }
+}